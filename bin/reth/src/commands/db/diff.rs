@@ -6,12 +6,13 @@ use crate::{
 use clap::Parser;
 use reth_db::{
     cursor::DbCursorRO, database::Database, open_db_read_only, table::Table, transaction::DbTx,
-    AccountChangeSets, AccountsHistory, AccountsTrie, BlockBodyIndices, BlockOmmers,
-    BlockWithdrawals, Bytecodes, CanonicalHeaders, DatabaseEnv, HashedAccounts, HashedStorages,
+    AccountChangeSets, AccountsHistory, AccountsTrie, AccountsTrieChangeSets, AccountsTrieHistory,
+    BlobSidecars, BlockBodyIndices, BlockOmmers, BlockWithdrawals, Bytecodes, CanonicalHeaders,
+    DatabaseEnv, HashedAccountPreimages, HashedAccounts, HashedStoragePreimages, HashedStorages,
     HeaderNumbers, HeaderTerminalDifficulties, Headers, PlainAccountState, PlainStorageState,
     PruneCheckpoints, Receipts, StageCheckpointProgresses, StageCheckpoints, StorageChangeSets,
-    StoragesHistory, StoragesTrie, Tables, TransactionBlocks, TransactionHashNumbers,
-    TransactionSenders, Transactions, VersionHistory,
+    StoragesHistory, StoragesTrie, StoragesTrieChangeSets, Tables, TransactionBlocks,
+    TransactionHashNumbers, TransactionSenders, Transactions, VersionHistory,
 };
 use std::{
     collections::HashMap,
@@ -139,6 +140,24 @@ impl Command {
                 Tables::StoragesTrie => {
                     find_diffs::<StoragesTrie>(primary_tx, secondary_tx, output_dir)?
                 }
+                Tables::AccountsTrieChangeSets => {
+                    find_diffs::<AccountsTrieChangeSets>(primary_tx, secondary_tx, output_dir)?
+                }
+                Tables::StoragesTrieChangeSets => {
+                    find_diffs::<StoragesTrieChangeSets>(primary_tx, secondary_tx, output_dir)?
+                }
+                Tables::AccountsTrieHistory => {
+                    find_diffs::<AccountsTrieHistory>(primary_tx, secondary_tx, output_dir)?
+                }
+                Tables::StoragesTrieHistory => {
+                    find_diffs::<StoragesTrieHistory>(primary_tx, secondary_tx, output_dir)?
+                }
+                Tables::HashedAccountPreimages => {
+                    find_diffs::<HashedAccountPreimages>(primary_tx, secondary_tx, output_dir)?
+                }
+                Tables::HashedStoragePreimages => {
+                    find_diffs::<HashedStoragePreimages>(primary_tx, secondary_tx, output_dir)?
+                }
                 Tables::TransactionSenders => {
                     find_diffs::<TransactionSenders>(primary_tx, secondary_tx, output_dir)?
                 }
@@ -154,6 +173,9 @@ impl Command {
                 Tables::VersionHistory => {
                     find_diffs::<VersionHistory>(primary_tx, secondary_tx, output_dir)?
                 }
+                Tables::BlobSidecars => {
+                    find_diffs::<BlobSidecars>(primary_tx, secondary_tx, output_dir)?
+                }
             };
         }
 
@@ -29,6 +29,7 @@ mod static_files;
 mod stats;
 /// DB List TUI
 mod tui;
+mod verify_trie;
 
 /// `reth db` command
 #[derive(Debug, Parser)]
@@ -86,6 +87,13 @@ pub enum Subcommands {
     Clear(clear::Command),
     /// Creates static files from database tables
     CreateStaticFiles(static_files::Command),
+    /// Verifies the stored trie nodes against the hashed state and optionally repairs them
+    ///
+    /// The tries are recomputed from every entry of the hashed account and storage tables, the
+    /// same work as rebuilding the Merkle stage from scratch. On mainnet this takes hours and holds
+    /// a single read transaction (or, with `--repair`, a write transaction) open for the whole
+    /// run. Memory use is bounded, as the recomputed nodes are compared in batches.
+    VerifyTrie(verify_trie::Command),
     /// Lists current and local database versions
     Version,
     /// Returns the full database path
@@ -172,6 +180,17 @@ impl Command {
             Subcommands::CreateStaticFiles(command) => {
                 command.execute(data_dir, self.db.database_args(), self.chain.clone())?;
             }
+            Subcommands::VerifyTrie(command) => {
+                let db = if command.repair {
+                    open_db(&db_path, db_args)?
+                } else {
+                    open_db_read_only(&db_path, db_args)?
+                };
+                let provider_factory =
+                    ProviderFactory::new(db, self.chain.clone(), static_files_path)?;
+
+                command.execute(provider_factory)?;
+            }
             Subcommands::Version => {
                 let local_db_version = match get_db_version(&db_path) {
                     Ok(version) => Some(version),
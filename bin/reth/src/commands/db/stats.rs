@@ -8,12 +8,13 @@ use human_bytes::human_bytes;
 use itertools::Itertools;
 use reth_db::{
     database::Database, mdbx, static_file::iter_static_files, AccountChangeSets, AccountsHistory,
-    AccountsTrie, BlockBodyIndices, BlockOmmers, BlockWithdrawals, Bytecodes, CanonicalHeaders,
-    DatabaseEnv, HashedAccounts, HashedStorages, HeaderNumbers, HeaderTerminalDifficulties,
-    Headers, PlainAccountState, PlainStorageState, PruneCheckpoints, Receipts,
-    StageCheckpointProgresses, StageCheckpoints, StorageChangeSets, StoragesHistory, StoragesTrie,
-    Tables, TransactionBlocks, TransactionHashNumbers, TransactionSenders, Transactions,
-    VersionHistory,
+    AccountsTrie, AccountsTrieChangeSets, AccountsTrieHistory, BlobSidecars, BlockBodyIndices,
+    BlockOmmers, BlockWithdrawals, Bytecodes, CanonicalHeaders, DatabaseEnv,
+    HashedAccountPreimages, HashedAccounts, HashedStoragePreimages, HashedStorages, HeaderNumbers,
+    HeaderTerminalDifficulties, Headers, PlainAccountState, PlainStorageState, PruneCheckpoints,
+    Receipts, StageCheckpointProgresses, StageCheckpoints, StorageChangeSets, StoragesHistory,
+    StoragesTrie, StoragesTrieChangeSets, StoragesTrieHistory, Tables, TransactionBlocks,
+    TransactionHashNumbers, TransactionSenders, Transactions, VersionHistory,
 };
 use reth_node_core::dirs::{ChainPath, DataDirPath};
 use reth_primitives::static_file::{find_fixed_range, SegmentRangeInclusive};
@@ -352,6 +353,24 @@ impl Command {
                 Tables::StorageChangeSets => viewer.get_checksum::<StorageChangeSets>().unwrap(),
                 Tables::StoragesHistory => viewer.get_checksum::<StoragesHistory>().unwrap(),
                 Tables::StoragesTrie => viewer.get_checksum::<StoragesTrie>().unwrap(),
+                Tables::AccountsTrieChangeSets => {
+                    viewer.get_checksum::<AccountsTrieChangeSets>().unwrap()
+                }
+                Tables::StoragesTrieChangeSets => {
+                    viewer.get_checksum::<StoragesTrieChangeSets>().unwrap()
+                }
+                Tables::AccountsTrieHistory => {
+                    viewer.get_checksum::<AccountsTrieHistory>().unwrap()
+                }
+                Tables::StoragesTrieHistory => {
+                    viewer.get_checksum::<StoragesTrieHistory>().unwrap()
+                }
+                Tables::HashedAccountPreimages => {
+                    viewer.get_checksum::<HashedAccountPreimages>().unwrap()
+                }
+                Tables::HashedStoragePreimages => {
+                    viewer.get_checksum::<HashedStoragePreimages>().unwrap()
+                }
                 Tables::TransactionBlocks => viewer.get_checksum::<TransactionBlocks>().unwrap(),
                 Tables::TransactionHashNumbers => {
                     viewer.get_checksum::<TransactionHashNumbers>().unwrap()
@@ -359,6 +378,7 @@ impl Command {
                 Tables::TransactionSenders => viewer.get_checksum::<TransactionSenders>().unwrap(),
                 Tables::Transactions => viewer.get_checksum::<Transactions>().unwrap(),
                 Tables::VersionHistory => viewer.get_checksum::<VersionHistory>().unwrap(),
+                Tables::BlobSidecars => viewer.get_checksum::<BlobSidecars>().unwrap(),
             };
 
             // increment duration for final report
@@ -0,0 +1,137 @@
+use clap::Parser;
+use reth_db::{database::Database, transaction::DbTx};
+use reth_primitives::B256;
+use reth_provider::{BlockNumReader, HeaderProvider, ProviderError, ProviderFactory};
+use reth_trie::{
+    history::TrieHistory,
+    verify::{InconsistencyKind, TrieVerification, TrieVerifier},
+    StateRoot,
+};
+use tracing::{info, warn};
+
+/// The arguments for the `reth db verify-trie` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// Replace the inconsistent trie nodes with the ones recomputed from the hashed state.
+    ///
+    /// Only the affected nodes are rewritten, the rest of the tries are left untouched.
+    #[arg(long)]
+    pub repair: bool,
+}
+
+impl Command {
+    /// Execute `db verify-trie` command
+    pub fn execute<DB: Database>(self, provider_factory: ProviderFactory<DB>) -> eyre::Result<()> {
+        warn!("This command should be run without the node running!");
+
+        if !self.repair {
+            let provider = provider_factory.provider()?.disable_long_read_transaction_safety();
+            let state_root = best_state_root(&provider)?;
+            verify(provider.tx_ref(), state_root)?;
+            return Ok(())
+        }
+
+        let provider = provider_factory.provider_rw()?;
+        let state_root = best_state_root(&*provider)?;
+        let verification = verify(provider.tx_ref(), state_root)?;
+        if verification.is_consistent() {
+            return Ok(())
+        }
+
+        // Repairing the nodes against a hashed state that doesn't match the best block would only
+        // make the trie consistent with the wrong state.
+        if verification.state_root != state_root {
+            eyre::bail!(
+                "Can't repair the trie nodes, the hashed state root {:?} does not match the state \
+                 root {:?} of the best block",
+                verification.state_root,
+                state_root
+            );
+        }
+
+        // The repaired nodes are written without trie changesets, which would break the history.
+        if TrieHistory::new(provider.tx_ref()).first_block()?.is_some() {
+            eyre::bail!(
+                "Can't repair a trie with recorded trie changesets, drop the merkle stage to remove \
+                 the trie history first"
+            );
+        }
+
+        info!(nodes = verification.inconsistencies.len(), "Repairing trie nodes");
+        verification.repair_updates().flush(provider.tx_ref())?;
+
+        let repaired_root = StateRoot::from_tx(provider.tx_ref()).root()?;
+        if repaired_root != verification.state_root {
+            eyre::bail!(
+                "Repair failed. Incorrect state root. Expected: {:?}. Received: {:?}",
+                verification.state_root,
+                repaired_root
+            );
+        }
+
+        provider.commit()?;
+        info!(state_root = ?repaired_root, "Repaired trie nodes");
+
+        Ok(())
+    }
+}
+
+/// Returns the state root of the best block.
+fn best_state_root(provider: &(impl BlockNumReader + HeaderProvider)) -> eyre::Result<B256> {
+    let best_block = provider.best_block_number()?;
+    let best_header = provider
+        .header_by_number(best_block)?
+        .ok_or(ProviderError::HeaderNotFound(best_block.into()))?;
+    Ok(best_header.state_root)
+}
+
+/// Verifies the stored trie nodes and reports the inconsistent ones, grouped by the subtree they
+/// affect.
+fn verify(tx: &impl DbTx, expected_state_root: B256) -> eyre::Result<TrieVerification> {
+    info!("Verifying trie nodes");
+    let verification = TrieVerifier::new(tx).verify()?;
+
+    for (hashed_address, subtree) in verification.affected_subtrees() {
+        let trie = hashed_address
+            .map_or_else(|| "account".to_string(), |address| format!("storage {address}"));
+        warn!(%trie, ?subtree, "Inconsistent subtree");
+
+        for inconsistency in verification.inconsistencies.iter().filter(|inconsistency| {
+            inconsistency.hashed_address == hashed_address &&
+                inconsistency.path.starts_with(&subtree)
+        }) {
+            match &inconsistency.kind {
+                InconsistencyKind::Missing(expected) => {
+                    warn!(path = ?inconsistency.path, ?expected, "Missing trie node")
+                }
+                InconsistencyKind::Extra(stored) => {
+                    warn!(path = ?inconsistency.path, ?stored, "Extra trie node")
+                }
+                InconsistencyKind::Mismatch { stored, expected } => {
+                    warn!(path = ?inconsistency.path, ?stored, ?expected, "Mismatched trie node")
+                }
+            }
+        }
+    }
+
+    if verification.state_root != expected_state_root {
+        warn!(
+            expected = ?expected_state_root,
+            computed = ?verification.state_root,
+            "The hashed state does not match the state root of the best block, repairing the trie \
+             nodes does not fix this"
+        );
+    }
+
+    if verification.is_consistent() {
+        info!(state_root = ?verification.state_root, "Trie nodes are consistent");
+    } else {
+        warn!(
+            nodes = verification.inconsistencies.len(),
+            subtrees = verification.affected_subtrees().len(),
+            "Found inconsistent trie nodes"
+        );
+    }
+
+    Ok(verification)
+}
@@ -7,6 +7,7 @@ mod execution;
 mod in_memory_merkle;
 mod merkle;
 mod replay_engine;
+mod witness;
 
 /// `reth debug` command
 #[derive(Debug, Parser)]
@@ -28,6 +29,8 @@ pub enum Subcommands {
     BuildBlock(build_block::Command),
     /// Debug engine API by replaying stored messages.
     ReplayEngine(replay_engine::Command),
+    /// Generate the execution witness of a block.
+    Witness(witness::Command),
 }
 
 impl Command {
@@ -39,6 +42,7 @@ impl Command {
             Subcommands::InMemoryMerkle(command) => command.execute(ctx).await,
             Subcommands::BuildBlock(command) => command.execute(ctx).await,
             Subcommands::ReplayEngine(command) => command.execute(ctx).await,
+            Subcommands::Witness(command) => command.execute().await,
         }
     }
 }
@@ -0,0 +1,115 @@
+//! Command for generating the execution witness of a block.
+
+use crate::{
+    args::{
+        utils::{chain_help, genesis_value_parser, SUPPORTED_CHAINS},
+        DatabaseArgs,
+    },
+    dirs::{DataDirPath, MaybePlatformPath},
+};
+use clap::Parser;
+use reth_db::open_db_read_only;
+use reth_node_ethereum::EthEvmConfig;
+use reth_primitives::{fs, ChainSpec};
+use reth_provider::{BlockReader, HeaderProvider, ProviderFactory, TransactionVariant};
+use reth_revm::witness::{execute_with_witness, generate_execution_witness};
+use std::{path::PathBuf, sync::Arc};
+use tracing::info;
+
+/// `reth debug witness` command
+///
+/// Executes a canonical block on top of its parent state and writes the execution witness of the
+/// block, the pre-state trie nodes, bytecodes and ancestor headers it reads, as JSON to a file.
+#[derive(Debug, Parser)]
+pub struct Command {
+    /// The path to the data dir for all reth files and subdirectories.
+    ///
+    /// Defaults to the OS-specific data directory:
+    ///
+    /// - Linux: `$XDG_DATA_HOME/reth/` or `$HOME/.local/share/reth/`
+    /// - Windows: `{FOLDERID_RoamingAppData}/reth/`
+    /// - macOS: `$HOME/Library/Application Support/reth/`
+    #[arg(long, value_name = "DATA_DIR", verbatim_doc_comment, default_value_t)]
+    datadir: MaybePlatformPath<DataDirPath>,
+
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(
+        long,
+        value_name = "CHAIN_OR_PATH",
+        long_help = chain_help(),
+        default_value = SUPPORTED_CHAINS[0],
+        value_parser = genesis_value_parser
+    )]
+    chain: Arc<ChainSpec>,
+
+    #[command(flatten)]
+    db: DatabaseArgs,
+
+    /// The number of the block to generate the witness for.
+    #[arg(long)]
+    block: u64,
+
+    /// The file to write the witness to.
+    #[arg(long, value_name = "FILE")]
+    out: PathBuf,
+
+    /// Re-execute the block from the generated witness alone and check its receipts and state
+    /// root.
+    #[arg(long)]
+    verify: bool,
+}
+
+impl Command {
+    /// Execute `debug witness` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
+        let db = open_db_read_only(&data_dir.db_path(), self.db.database_args())?;
+        let factory = ProviderFactory::new(db, self.chain.clone(), data_dir.static_files_path())?;
+        let provider = factory.provider()?;
+
+        let parent_number = self
+            .block
+            .checked_sub(1)
+            .ok_or_else(|| eyre::eyre!("the genesis block has no witness"))?;
+        let block = provider
+            .block_with_senders(self.block.into(), TransactionVariant::WithHash)?
+            .ok_or_else(|| eyre::eyre!("block not found"))?;
+        let total_difficulty = provider
+            .header_td_by_number(self.block)?
+            .ok_or_else(|| eyre::eyre!("total difficulty not found"))?;
+
+        info!(target: "reth::cli", block = self.block, "Generating execution witness");
+        let witness = generate_execution_witness(
+            self.chain.clone(),
+            EthEvmConfig::default(),
+            factory.history_by_block_number(parent_number)?,
+            &provider,
+            &block,
+            total_difficulty,
+        )?;
+        fs::write(&self.out, serde_json::to_string_pretty(&witness)?)?;
+        info!(
+            target: "reth::cli",
+            nodes = witness.state.len(),
+            codes = witness.codes.len(),
+            headers = witness.headers.len(),
+            path = %self.out.display(),
+            "Wrote execution witness"
+        );
+
+        if self.verify {
+            execute_with_witness(
+                self.chain.clone(),
+                EthEvmConfig::default(),
+                &block,
+                total_difficulty,
+                &witness,
+            )?;
+            info!(target: "reth::cli", block = self.block, "Verified block from witness");
+        }
+
+        Ok(())
+    }
+}
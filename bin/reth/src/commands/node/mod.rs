@@ -3,8 +3,8 @@
 use crate::{
     args::{
         utils::{chain_help, genesis_value_parser, parse_socket_address, SUPPORTED_CHAINS},
-        DatabaseArgs, DebugArgs, DevArgs, NetworkArgs, PayloadBuilderArgs, PruningArgs,
-        RpcServerArgs, TxPoolArgs,
+        DatabaseArgs, DebugArgs, DevArgs, EngineArgs, ExExArgs, NetworkArgs,
+        PayloadBuilderArgs, PruningArgs, RpcServerArgs, TxPoolArgs,
     },
     core::cli::runner::CliContext,
     dirs::{DataDirPath, MaybePlatformPath},
@@ -108,6 +108,14 @@ pub struct NodeCommand<Ext: clap::Args + fmt::Debug = NoArgs> {
     #[command(flatten)]
     pub pruning: PruningArgs,
 
+    /// All engine related arguments with --engine prefix
+    #[command(flatten)]
+    pub engine: EngineArgs,
+
+    /// All ExEx related arguments with --exex prefix
+    #[command(flatten)]
+    pub exex: ExExArgs,
+
     /// Additional cli arguments
     #[command(flatten, next_help_heading = "Extension")]
     pub ext: Ext,
@@ -156,6 +164,8 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            engine,
+            exex,
             ext,
         } = self;
 
@@ -173,6 +183,8 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             db,
             dev,
             pruning,
+            engine,
+            exex,
         };
 
         // Register the prometheus recorder before creating the database,
@@ -0,0 +1,743 @@
+//! Command that crawls the discovery network.
+
+use crate::args::{utils::parse_duration_from_secs, DiscoveryArgs};
+use alloy_rlp::Decodable;
+use clap::{Parser, ValueEnum};
+use enr::Enr;
+use eyre::OptionExt;
+use futures::{
+    stream::{self, BoxStream, FuturesUnordered},
+    SinkExt, StreamExt,
+};
+use reth_discv4::{DiscoveryUpdate, Discv4, Discv4Config, EnrForkIdEntry, NatResolver};
+use reth_discv5::{discv5, enr::EnrCombinedKeyWrapper, Discv5};
+use reth_dns_discovery::tree::DnsTree;
+use reth_ecies::stream::ECIESStream;
+use reth_eth_wire::{
+    BlockRangeUpdate, DisconnectReason, EthMessage, HelloMessage, HelloMessageWithProtocols,
+    ProtocolMessage, Status, StatusMessage, UnauthedP2PStream,
+};
+use reth_primitives::{
+    bytes::Bytes, hex, pk2id, ChainSpec, ForkFilter, ForkId, Head, NodeRecord, PeerId, B256,
+};
+use secp256k1::{SecretKey, SECP256K1};
+use serde::Serialize;
+use std::{
+    collections::{HashMap, VecDeque},
+    fs::File,
+    io::{BufWriter, Write},
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::net::TcpStream;
+use tracing::{debug, info};
+
+/// How long a handshake with a discovered node may take.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How often a lookup for a random target is started, to walk the DHT.
+const LOOKUP_INTERVAL: Duration = Duration::from_secs(1);
+
+/// `reth p2p crawl` command
+#[derive(Debug, Parser)]
+pub struct CrawlArgs {
+    /// Comma separated enode URLs to start crawling from.
+    ///
+    /// Required for chains without built-in bootnodes.
+    #[arg(long, value_delimiter = ',')]
+    pub bootnodes: Option<Vec<NodeRecord>>,
+
+    /// How long to crawl, in seconds.
+    #[arg(long, value_parser = parse_duration_from_secs, default_value = "300", value_name = "SECONDS")]
+    duration: Duration,
+
+    /// Connect to every discovered node to record its client version, capabilities and status.
+    #[arg(long)]
+    handshake: bool,
+
+    /// The maximum number of concurrent handshakes.
+    #[arg(long, default_value_t = 32, value_name = "COUNT")]
+    max_concurrent_handshakes: usize,
+
+    /// The file to write the database of discovered nodes to.
+    #[arg(long, short, value_name = "FILE")]
+    output: PathBuf,
+
+    /// The format of the database.
+    ///
+    /// Inferred from the extension of the output file if not set.
+    #[arg(long, value_enum)]
+    format: Option<CrawlFormat>,
+
+    /// Write a signed EIP-1459 DNS tree of the healthy discovered nodes to this file.
+    ///
+    /// The tree is written as JSON object from record name to TXT record content.
+    #[arg(long, value_name = "FILE", requires = "dns_domain")]
+    dns_tree: Option<PathBuf>,
+
+    /// The domain the DNS tree is published at.
+    #[arg(long, value_name = "DOMAIN")]
+    dns_domain: Option<String>,
+
+    /// The secret key to sign the DNS tree with.
+    ///
+    /// Unlike the p2p secret key, this file must exist. Defaults to the p2p secret key.
+    #[arg(long, value_name = "PATH")]
+    dns_key: Option<PathBuf>,
+
+    /// The sequence number of the DNS tree.
+    ///
+    /// Defaults to the current unix timestamp.
+    #[arg(long, value_name = "SEQ")]
+    dns_seq: Option<u64>,
+}
+
+/// The format of the crawled node database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum CrawlFormat {
+    /// A JSON array of nodes.
+    Json,
+    /// A CSV table with one node per row.
+    Csv,
+}
+
+impl CrawlArgs {
+    /// Crawls the discovery network and writes the results.
+    pub async fn execute(
+        &self,
+        chain: Arc<ChainSpec>,
+        secret_key: SecretKey,
+        discovery: &DiscoveryArgs,
+        nat: NatResolver,
+        boot_nodes: Vec<NodeRecord>,
+    ) -> eyre::Result<()> {
+        let format = self.format();
+        let head = genesis_head(&chain);
+        let fork_filter = chain.fork_filter(head);
+        let status = Status::spec_builder(&chain, &head).build();
+        let hello = HelloMessage::builder(pk2id(&secret_key.public_key(SECP256K1))).build();
+
+        // discovered nodes, from all enabled discovery services
+        let mut discovered: Vec<BoxStream<'static, Discovered>> = Vec::new();
+
+        let mut discv4 = None;
+        if !discovery.disable_discovery && !discovery.disable_discv4_discovery {
+            let local_addr = SocketAddr::new(discovery.addr, discovery.port);
+            let config = Discv4Config::builder()
+                .add_boot_nodes(boot_nodes.clone())
+                .external_ip_resolver(Some(nat))
+                .build();
+            let (handle, mut service) = Discv4::bind(
+                local_addr,
+                NodeRecord::from_secret_key(local_addr, &secret_key),
+                secret_key,
+                config,
+            )
+            .await?;
+            let updates = service.update_stream();
+            service.spawn();
+            discv4 = Some(handle);
+
+            discovered.push(
+                updates
+                    .flat_map(|update| {
+                        let mut nodes = Vec::new();
+                        Discovered::from_discv4_update(update, &mut nodes);
+                        stream::iter(nodes)
+                    })
+                    .boxed(),
+            );
+        }
+
+        let mut _discv5 = None;
+        if !discovery.disable_discovery && discovery.enable_discv5_discovery {
+            let local_addr = SocketAddr::new(discovery.discv5_addr, discovery.discv5_port);
+            let config = reth_discv5::Config::builder(discovery.port)
+                .discv5_config(
+                    discv5::ConfigBuilder::new(discv5::ListenConfig::from(local_addr)).build(),
+                )
+                .add_unsigned_boot_nodes(boot_nodes.into_iter())
+                .fork(reth_discv5::config::ETH, chain.latest_fork_id())
+                .build();
+            let (handle, mut events, _) = Discv5::start(&secret_key, config).await?;
+            _discv5 = Some(handle);
+
+            discovered.push(
+                stream::poll_fn(move |cx| events.poll_recv(cx))
+                    .filter_map(|event| async move {
+                        let discv5::Event::SessionEstablished(enr, _) = event else { return None };
+                        let enr: Enr<SecretKey> = EnrCombinedKeyWrapper(enr).into();
+                        let record = NodeRecord::try_from(&enr).ok()?;
+                        Some(Discovered::Enr(record, enr))
+                    })
+                    .boxed(),
+            );
+        }
+
+        if discovered.is_empty() {
+            eyre::bail!("All discovery services are disabled")
+        }
+        let mut discovered = stream::select_all(discovered);
+
+        info!(target: "reth::cli", duration=?self.duration, handshake=self.handshake, "Crawling discovery network");
+
+        let mut nodes = HashMap::<PeerId, CrawledNode>::new();
+        let mut queued_handshakes = VecDeque::new();
+        let mut handshakes = FuturesUnordered::new();
+
+        let deadline = tokio::time::sleep(self.duration);
+        tokio::pin!(deadline);
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+        let mut lookup_interval = tokio::time::interval(LOOKUP_INTERVAL);
+
+        loop {
+            tokio::select! {
+                _ = &mut deadline => break,
+                _ = &mut ctrl_c => {
+                    info!(target: "reth::cli", "Crawl interrupted");
+                    break
+                }
+                _ = lookup_interval.tick() => {
+                    if let Some(discv4) = &discv4 {
+                        discv4.send_lookup(PeerId::random());
+                    }
+                }
+                Some(found) = discovered.next() => {
+                    let record = found.record();
+                    let node = nodes.entry(record.id).or_insert_with(|| {
+                        if self.handshake {
+                            queued_handshakes.push_back(record);
+                        }
+                        CrawledNode::new(record)
+                    });
+                    node.on_discovered(found);
+                }
+                Some((peer_id, result)) = handshakes.next(), if !handshakes.is_empty() => {
+                    if let Some(node) = nodes.get_mut(&peer_id) {
+                        node.on_handshake(result);
+                    }
+                }
+            }
+
+            while handshakes.len() < self.max_concurrent_handshakes {
+                let Some(record) = queued_handshakes.pop_front() else { break };
+                let hello = hello.clone();
+                handshakes.push(async move {
+                    let result = tokio::time::timeout(
+                        HANDSHAKE_TIMEOUT,
+                        handshake(record, secret_key, hello, status),
+                    )
+                    .await
+                    .unwrap_or_else(|_| NodeHandshake::failed("handshake timed out"));
+                    (record.id, result)
+                });
+            }
+        }
+
+        let mut nodes = nodes.into_values().collect::<Vec<_>>();
+        nodes.sort_unstable_by_key(|node| node.id);
+        info!(target: "reth::cli", discovered=nodes.len(), path=?self.output, "Writing crawled nodes");
+        write_nodes(&self.output, format, &nodes)?;
+
+        if let (Some(path), Some(domain)) = (&self.dns_tree, &self.dns_domain) {
+            let key = match &self.dns_key {
+                Some(path) => read_secret_key(path)?,
+                None => secret_key,
+            };
+            let seq = self.dns_seq.unwrap_or_else(|| {
+                SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+            });
+            let records = nodes
+                .iter()
+                .filter(|node| node.is_healthy(&chain, &fork_filter, self.handshake))
+                .filter_map(|node| node.enr.clone())
+                .collect::<Vec<_>>();
+            let num_records = records.len();
+            let tree = DnsTree::new(records, Vec::new(), seq, &key);
+
+            let mut writer = BufWriter::new(File::create(path)?);
+            serde_json::to_writer_pretty(&mut writer, &tree.to_txt_records(domain))?;
+            writer.flush()?;
+            info!(target: "reth::cli", records=num_records, link=%tree.link(domain), ?path, "Wrote DNS tree");
+        }
+
+        Ok(())
+    }
+
+    /// Returns the format of the database, inferred from the output file if not set.
+    fn format(&self) -> CrawlFormat {
+        self.format.unwrap_or_else(|| match self.output.extension().and_then(|ext| ext.to_str()) {
+            Some("csv") => CrawlFormat::Csv,
+            _ => CrawlFormat::Json,
+        })
+    }
+}
+
+/// Returns the [`Head`] of the genesis block of the chain, which is what the crawler announces.
+fn genesis_head(chain: &ChainSpec) -> Head {
+    Head {
+        hash: chain.genesis_hash(),
+        number: 0,
+        timestamp: chain.genesis.timestamp,
+        difficulty: chain.genesis.difficulty,
+        total_difficulty: chain.genesis.difficulty,
+    }
+}
+
+/// A node reported by a discovery service.
+#[derive(Debug)]
+enum Discovered {
+    /// A node without further information.
+    Node(NodeRecord),
+    /// A node that announced the given [`ForkId`] in its ENR.
+    ForkId(NodeRecord, ForkId),
+    /// A node and its signed ENR.
+    Enr(NodeRecord, Enr<SecretKey>),
+}
+
+impl Discovered {
+    /// Collects the nodes of the [`DiscoveryUpdate`].
+    fn from_discv4_update(update: DiscoveryUpdate, nodes: &mut Vec<Self>) {
+        match update {
+            DiscoveryUpdate::Added(record) | DiscoveryUpdate::DiscoveredAtCapacity(record) => {
+                nodes.push(Self::Node(record))
+            }
+            DiscoveryUpdate::EnrForkId(record, fork_id) => {
+                nodes.push(Self::ForkId(record, fork_id))
+            }
+            DiscoveryUpdate::Enr(record, enr) => nodes.push(Self::Enr(record, enr)),
+            DiscoveryUpdate::Removed(_) => {}
+            DiscoveryUpdate::Batch(updates) => {
+                for update in updates {
+                    Self::from_discv4_update(update, nodes)
+                }
+            }
+        }
+    }
+
+    /// Returns the record of the node.
+    const fn record(&self) -> NodeRecord {
+        match self {
+            Self::Node(record) | Self::ForkId(record, _) | Self::Enr(record, _) => *record,
+        }
+    }
+}
+
+/// The result of a handshake with a discovered node.
+#[derive(Debug, Default)]
+struct NodeHandshake {
+    /// The client version from the `Hello` message.
+    client_version: Option<String>,
+    /// The capabilities from the `Hello` message.
+    capabilities: Vec<String>,
+    /// The `Status` message.
+    status: Option<StatusMessage>,
+    /// Why the handshake failed, if it did.
+    error: Option<String>,
+}
+
+impl NodeHandshake {
+    fn failed(error: impl ToString) -> Self {
+        Self { error: Some(error.to_string()), ..Default::default() }
+    }
+}
+
+/// Connects to the node and exchanges `Hello` and `Status` messages.
+///
+/// Unlike a regular session the remote `Status` is not validated, so that nodes of other chains
+/// are recorded as well.
+async fn handshake(
+    record: NodeRecord,
+    secret_key: SecretKey,
+    hello: HelloMessageWithProtocols,
+    mut status: Status,
+) -> NodeHandshake {
+    let stream = async {
+        let stream = TcpStream::connect(record.tcp_addr()).await?;
+        let stream = ECIESStream::connect(stream, secret_key, record.id).await?;
+        Ok::<_, eyre::Report>(UnauthedP2PStream::new(stream).handshake(hello).await?)
+    };
+    let (mut p2p_stream, their_hello) = match stream.await {
+        Ok(stream) => stream,
+        Err(err) => return NodeHandshake::failed(err),
+    };
+    let mut result = NodeHandshake {
+        client_version: Some(their_hello.client_version),
+        capabilities: their_hello.capabilities.iter().map(ToString::to_string).collect(),
+        ..Default::default()
+    };
+
+    let their_status = async {
+        let version = p2p_stream.shared_capabilities().eth_version()?;
+        status.set_eth_version(version);
+        let status = if version.is_eth69() {
+            // we don't serve any blocks
+            let block_range =
+                BlockRangeUpdate { earliest: 0, latest: 0, latest_hash: status.genesis };
+            StatusMessage::Eth69(status.into_eth69(block_range))
+        } else {
+            StatusMessage::Legacy(status)
+        };
+        p2p_stream
+            .send(Bytes::from(alloy_rlp::encode(ProtocolMessage::from(EthMessage::Status(status)))))
+            .await?;
+
+        let msg = p2p_stream.next().await.ok_or_eyre("connection closed")??;
+        match ProtocolMessage::decode_message(version, &mut msg.as_ref())?.message {
+            EthMessage::Status(status) => Ok(status),
+            msg => Err(eyre::eyre!("expected status, got {:?}", msg.message_id())),
+        }
+    };
+    match their_status.await {
+        Ok(status) => result.status = Some(status),
+        Err(err) => result.error = Some(err.to_string()),
+    }
+
+    if let Err(err) = p2p_stream.disconnect(DisconnectReason::ClientQuitting).await {
+        debug!(target: "reth::cli", %err, peer_id=%record.id, "Failed to disconnect");
+    }
+    result
+}
+
+/// A node found while crawling, as written to the database.
+#[derive(Debug, Serialize)]
+struct CrawledNode {
+    /// The id of the node.
+    id: PeerId,
+    /// The `enode` URL of the node.
+    enode: String,
+    /// The signed ENR of the node, if received.
+    #[serde(skip)]
+    enr: Option<Enr<SecretKey>>,
+    /// The text representation of the ENR.
+    #[serde(rename = "enr")]
+    enr_text: Option<String>,
+    /// The [`ForkId`] of the node, from its status if it completed the handshake or its ENR
+    /// otherwise.
+    #[serde(skip)]
+    fork_id: Option<ForkId>,
+    /// The fork hash of the [`ForkId`].
+    fork_hash: Option<String>,
+    /// The next fork of the [`ForkId`].
+    fork_next: Option<u64>,
+    /// The client version of the node.
+    client_version: Option<String>,
+    /// The capabilities of the node.
+    capabilities: Vec<String>,
+    /// The negotiated `eth` version.
+    eth_version: Option<u8>,
+    /// The network id of the node.
+    network_id: Option<u64>,
+    /// The genesis hash of the node.
+    genesis: Option<B256>,
+    /// The best block hash of the node.
+    best_hash: Option<B256>,
+    /// Why the handshake failed, if it did.
+    error: Option<String>,
+    /// Unix timestamp in seconds of when the node was first discovered.
+    first_seen: u64,
+}
+
+impl CrawledNode {
+    fn new(record: NodeRecord) -> Self {
+        Self {
+            id: record.id,
+            enode: record.to_string(),
+            enr: None,
+            enr_text: None,
+            fork_id: None,
+            fork_hash: None,
+            fork_next: None,
+            client_version: None,
+            capabilities: Vec::new(),
+            eth_version: None,
+            network_id: None,
+            genesis: None,
+            best_hash: None,
+            error: None,
+            first_seen: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+        }
+    }
+
+    fn on_discovered(&mut self, found: Discovered) {
+        match found {
+            Discovered::Node(_) => {}
+            Discovered::ForkId(_, fork_id) => {
+                if self.genesis.is_none() {
+                    self.set_fork_id(fork_id)
+                }
+            }
+            Discovered::Enr(_, enr) => {
+                if self.genesis.is_none() {
+                    if let Some(fork_id) = enr_fork_id(&enr) {
+                        self.set_fork_id(fork_id)
+                    }
+                }
+                self.enr_text = Some(enr.to_base64());
+                self.enr = Some(enr);
+            }
+        }
+    }
+
+    fn on_handshake(&mut self, handshake: NodeHandshake) {
+        let NodeHandshake { client_version, capabilities, status, error } = handshake;
+        self.client_version = client_version;
+        self.capabilities = capabilities;
+        self.error = error;
+        if let Some(status) = status {
+            self.eth_version = Some(status.version());
+            self.network_id = Some(status.chain().id());
+            self.genesis = Some(status.genesis());
+            self.best_hash = Some(status.blockhash());
+            self.set_fork_id(status.forkid());
+        }
+    }
+
+    fn set_fork_id(&mut self, fork_id: ForkId) {
+        self.fork_id = Some(fork_id);
+        self.fork_hash = Some(hex::encode_prefixed(fork_id.hash.0));
+        self.fork_next = Some(fork_id.next);
+    }
+
+    /// Returns `true` if the node belongs to the given chain and is compatible with our forks.
+    ///
+    /// If handshakes were enabled, the node must also have completed the handshake.
+    fn is_healthy(&self, chain: &ChainSpec, fork_filter: &ForkFilter, handshake: bool) -> bool {
+        if handshake && (self.error.is_some() || self.genesis != Some(chain.genesis_hash())) {
+            return false
+        }
+        self.fork_id.is_some_and(|fork_id| fork_filter.validate(fork_id).is_ok())
+    }
+
+    /// Returns the fields of the node in the order of [`CSV_HEADER`].
+    fn csv_fields(&self) -> [String; 13] {
+        fn opt<T: ToString>(value: &Option<T>) -> String {
+            value.as_ref().map(ToString::to_string).unwrap_or_default()
+        }
+        [
+            self.id.to_string(),
+            self.enode.clone(),
+            opt(&self.enr_text),
+            opt(&self.fork_hash),
+            opt(&self.fork_next),
+            opt(&self.client_version),
+            self.capabilities.join(" "),
+            opt(&self.eth_version),
+            opt(&self.network_id),
+            opt(&self.genesis),
+            opt(&self.best_hash),
+            opt(&self.error),
+            self.first_seen.to_string(),
+        ]
+    }
+}
+
+/// The columns of the CSV database.
+const CSV_HEADER: [&str; 13] = [
+    "id",
+    "enode",
+    "enr",
+    "fork_hash",
+    "fork_next",
+    "client_version",
+    "capabilities",
+    "eth_version",
+    "network_id",
+    "genesis",
+    "best_hash",
+    "error",
+    "first_seen",
+];
+
+/// Returns the [`ForkId`] from the `eth` entry of the ENR, if any.
+fn enr_fork_id(enr: &Enr<SecretKey>) -> Option<ForkId> {
+    let mut rlp = enr.get_raw_rlp(b"eth")?;
+    EnrForkIdEntry::decode(&mut rlp).ok().map(|entry| entry.fork_id)
+}
+
+/// Reads the secret key stored at the given path.
+///
+/// The key is never generated, signing a DNS tree with a fresh key would publish it under a new
+/// link.
+fn read_secret_key(path: &Path) -> eyre::Result<SecretKey> {
+    let contents = reth_primitives::fs::read_to_string(path)?;
+    Ok(contents.trim().parse::<SecretKey>()?)
+}
+
+/// Writes the nodes to the given file.
+fn write_nodes(path: &Path, format: CrawlFormat, nodes: &[CrawledNode]) -> eyre::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    match format {
+        CrawlFormat::Json => serde_json::to_writer_pretty(&mut writer, nodes)?,
+        CrawlFormat::Csv => {
+            writeln!(writer, "{}", CSV_HEADER.join(","))?;
+            for node in nodes {
+                let row =
+                    node.csv_fields().iter().map(|field| csv_escape(field)).collect::<Vec<_>>();
+                writeln!(writer, "{}", row.join(","))?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Quotes the CSV field if necessary.
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{ForkHash, MAINNET};
+    use std::net::{IpAddr, Ipv4Addr};
+
+    fn record(port: u16) -> NodeRecord {
+        NodeRecord::new(SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port), PeerId::random())
+    }
+
+    fn mainnet_status() -> StatusMessage {
+        StatusMessage::Legacy(Status::spec_builder(&MAINNET, &genesis_head(&MAINNET)).build())
+    }
+
+    #[test]
+    fn infer_format_from_output() {
+        let args = CrawlArgs::parse_from(["reth", "--output", "nodes.csv"]);
+        assert_eq!(args.format(), CrawlFormat::Csv);
+
+        let args = CrawlArgs::parse_from(["reth", "--output", "nodes.json"]);
+        assert_eq!(args.format(), CrawlFormat::Json);
+
+        let args = CrawlArgs::parse_from(["reth", "--output", "nodes"]);
+        assert_eq!(args.format(), CrawlFormat::Json);
+
+        let args = CrawlArgs::parse_from(["reth", "--output", "nodes.json", "--format", "csv"]);
+        assert_eq!(args.format(), CrawlFormat::Csv);
+    }
+
+    #[test]
+    fn dns_tree_requires_domain() {
+        assert!(CrawlArgs::try_parse_from(["reth", "-o", "nodes.json", "--dns-tree", "tree.json"])
+            .is_err());
+        assert!(CrawlArgs::try_parse_from([
+            "reth",
+            "-o",
+            "nodes.json",
+            "--dns-tree",
+            "tree.json",
+            "--dns-domain",
+            "nodes.example.org"
+        ])
+        .is_ok());
+    }
+
+    #[test]
+    fn flatten_discv4_updates() {
+        let (a, b, c) = (record(30303), record(30304), record(30305));
+        let fork_id = MAINNET.latest_fork_id();
+        let update = DiscoveryUpdate::Batch(vec![
+            DiscoveryUpdate::Added(a),
+            DiscoveryUpdate::Removed(b.id),
+            DiscoveryUpdate::Batch(vec![DiscoveryUpdate::EnrForkId(c, fork_id)]),
+            DiscoveryUpdate::DiscoveredAtCapacity(b),
+        ]);
+
+        let mut nodes = Vec::new();
+        Discovered::from_discv4_update(update, &mut nodes);
+        assert_eq!(nodes.iter().map(Discovered::record).collect::<Vec<_>>(), vec![a, c, b]);
+        assert!(matches!(nodes[1], Discovered::ForkId(_, id) if id == fork_id));
+    }
+
+    #[test]
+    fn handshake_status_overrides_discovered_fork_id() {
+        let record = record(30303);
+        let mut node = CrawledNode::new(record);
+        let discovered = ForkId { hash: ForkHash([0xde, 0xad, 0xbe, 0xef]), next: 0 };
+        node.on_discovered(Discovered::ForkId(record, discovered));
+        assert_eq!(node.fork_id, Some(discovered));
+        assert_eq!(node.fork_hash.as_deref(), Some("0xdeadbeef"));
+
+        let status = mainnet_status();
+        node.on_handshake(NodeHandshake {
+            client_version: Some("reth/v0.0.1".to_string()),
+            capabilities: vec!["eth/68".to_string()],
+            status: Some(status),
+            error: None,
+        });
+        assert_eq!(node.fork_id, Some(status.forkid()));
+        assert_eq!(node.genesis, Some(MAINNET.genesis_hash()));
+        assert_eq!(node.network_id, Some(1));
+
+        // the fork id of the status is not replaced by later discoveries
+        node.on_discovered(Discovered::ForkId(record, discovered));
+        assert_eq!(node.fork_id, Some(status.forkid()));
+    }
+
+    #[test]
+    fn healthy_nodes() {
+        let fork_filter = MAINNET.fork_filter(genesis_head(&MAINNET));
+        let record = record(30303);
+
+        // no fork id
+        let mut node = CrawledNode::new(record);
+        assert!(!node.is_healthy(&MAINNET, &fork_filter, false));
+
+        // fork id of another chain
+        node.set_fork_id(ForkId { hash: ForkHash([0xde, 0xad, 0xbe, 0xef]), next: 0 });
+        assert!(!node.is_healthy(&MAINNET, &fork_filter, false));
+
+        // compatible fork id, but no handshake
+        node.set_fork_id(MAINNET.latest_fork_id());
+        assert!(node.is_healthy(&MAINNET, &fork_filter, false));
+        assert!(!node.is_healthy(&MAINNET, &fork_filter, true));
+
+        node.on_handshake(NodeHandshake { status: Some(mainnet_status()), ..Default::default() });
+        assert!(node.is_healthy(&MAINNET, &fork_filter, true));
+
+        node.error = Some("connection closed".to_string());
+        assert!(!node.is_healthy(&MAINNET, &fork_filter, true));
+    }
+
+    #[test]
+    fn escape_csv_fields() {
+        assert_eq!(csv_escape("reth/v0.0.1"), "reth/v0.0.1");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn write_node_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut node = CrawledNode::new(record(30303));
+        node.on_handshake(NodeHandshake::failed("Hello, \"world\""));
+        let nodes = vec![node, CrawledNode::new(record(30304))];
+
+        let csv = dir.path().join("nodes.csv");
+        write_nodes(&csv, CrawlFormat::Csv, &nodes).unwrap();
+        let content = std::fs::read_to_string(&csv).unwrap();
+        let lines = content.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], CSV_HEADER.join(","));
+        assert!(lines[1].starts_with(&format!("{},{}", nodes[0].id, nodes[0].enode)));
+        assert!(lines[1].contains(",\"Hello, \"\"world\"\"\","));
+
+        let json = dir.path().join("nodes.json");
+        write_nodes(&json, CrawlFormat::Json, &nodes).unwrap();
+        let value: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&json).unwrap()).unwrap();
+        let entries = value.as_array().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0]["enode"], nodes[0].enode);
+        assert_eq!(entries[0]["error"], "Hello, \"world\"");
+        assert!(entries[1]["error"].is_null());
+    }
+}
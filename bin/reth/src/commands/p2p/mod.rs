@@ -6,19 +6,24 @@ use crate::{
         utils::{chain_help, chain_spec_value_parser, hash_or_num_value_parser, SUPPORTED_CHAINS},
         DatabaseArgs, DiscoveryArgs,
     },
-    dirs::{DataDirPath, MaybePlatformPath},
+    dirs::{ChainPath, DataDirPath, MaybePlatformPath},
     utils::get_single_header,
 };
 use backon::{ConstantBuilder, Retryable};
 use clap::{Parser, Subcommand};
+use eyre::OptionExt;
 use reth_config::Config;
 use reth_db::create_db;
 use reth_discv4::NatResolver;
 use reth_interfaces::p2p::bodies::client::BodiesClient;
+use reth_network::FetchClient;
 use reth_primitives::{BlockHashOrNumber, ChainSpec, NodeRecord};
 use reth_provider::ProviderFactory;
+use secp256k1::SecretKey;
 use std::{path::PathBuf, sync::Arc};
 
+mod crawl;
+
 /// `reth p2p` command
 #[derive(Debug, Parser)]
 pub struct Command {
@@ -95,13 +100,12 @@ pub enum Subcommands {
         #[arg(value_parser = hash_or_num_value_parser)]
         id: BlockHashOrNumber,
     },
+    /// Crawl the discovery network and export the discovered nodes
+    Crawl(crawl::CrawlArgs),
 }
 impl Command {
     /// Execute `p2p` command
     pub async fn execute(&self) -> eyre::Result<()> {
-        let tempdir = tempfile::TempDir::new()?;
-        let noop_db = Arc::new(create_db(tempdir.into_path(), self.db.database_args())?);
-
         // add network name to data dir
         let data_dir = self.datadir.unwrap_or_chain_default(self.chain.chain);
         let config_path = self.config.clone().unwrap_or_else(|| data_dir.config_path());
@@ -122,26 +126,12 @@ impl Command {
         let secret_key_path = self.p2p_secret_key.clone().unwrap_or(default_secret_key_path);
         let p2p_secret_key = get_secret_key(&secret_key_path)?;
 
-        let mut network_config_builder =
-            config.network_config(self.nat, None, p2p_secret_key).chain_spec(self.chain.clone());
-
-        network_config_builder = self.discovery.apply_to_builder(network_config_builder);
+        let backoff = ConstantBuilder::default().with_max_times(self.retries.max(1));
 
-        let network = network_config_builder
-            .build(Arc::new(ProviderFactory::new(
-                noop_db,
-                self.chain.clone(),
-                data_dir.static_files_path(),
-            )?))
-            .start_network()
-            .await?;
-
-        let fetch_client = network.fetch_client().await?;
-        let retries = self.retries.max(1);
-        let backoff = ConstantBuilder::default().with_max_times(retries);
-
-        match self.command {
+        match &self.command {
             Subcommands::Header { id } => {
+                let id = *id;
+                let fetch_client = self.fetch_client(config, p2p_secret_key, &data_dir).await?;
                 let header = (move || get_single_header(fetch_client.clone(), id))
                     .retry(&backoff)
                     .notify(|err, _| println!("Error requesting header: {err}. Retrying..."))
@@ -149,7 +139,8 @@ impl Command {
                 println!("Successfully downloaded header: {header:?}");
             }
             Subcommands::Body { id } => {
-                let hash = match id {
+                let fetch_client = self.fetch_client(config, p2p_secret_key, &data_dir).await?;
+                let hash = match *id {
                     BlockHashOrNumber::Hash(hash) => hash,
                     BlockHashOrNumber::Number(number) => {
                         println!("Block number provided. Downloading header first...");
@@ -180,8 +171,52 @@ impl Command {
                 let body = result.into_iter().next().unwrap();
                 println!("Successfully downloaded body: {body:?}")
             }
+            Subcommands::Crawl(crawl) => {
+                // the crawler only runs the discovery service, without the network
+                let mut boot_nodes =
+                    crawl.bootnodes.clone().or_else(|| self.chain.bootnodes()).ok_or_eyre(
+                        "The chain has no built-in bootnodes, set them with `--bootnodes`",
+                    )?;
+                boot_nodes.extend(config.peers.trusted_nodes.iter().copied());
+                crawl
+                    .execute(
+                        self.chain.clone(),
+                        p2p_secret_key,
+                        &self.discovery,
+                        self.nat,
+                        boot_nodes,
+                    )
+                    .await?;
+            }
         }
 
         Ok(())
     }
+
+    /// Starts the network on top of an empty database and returns a client to fetch from it.
+    async fn fetch_client(
+        &self,
+        config: Config,
+        secret_key: SecretKey,
+        data_dir: &ChainPath<DataDirPath>,
+    ) -> eyre::Result<FetchClient> {
+        let tempdir = tempfile::TempDir::new()?;
+        let noop_db = Arc::new(create_db(tempdir.into_path(), self.db.database_args())?);
+
+        let mut network_config_builder =
+            config.network_config(self.nat, None, secret_key).chain_spec(self.chain.clone());
+
+        network_config_builder = self.discovery.apply_to_builder(network_config_builder);
+
+        let network = network_config_builder
+            .build(Arc::new(ProviderFactory::new(
+                noop_db,
+                self.chain.clone(),
+                data_dir.static_files_path(),
+            )?))
+            .start_network()
+            .await?;
+
+        Ok(network.fetch_client().await?)
+    }
 }
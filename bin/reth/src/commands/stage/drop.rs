@@ -16,6 +16,7 @@ use reth_primitives::{
     fs, stage::StageId, static_file::find_fixed_range, ChainSpec, StaticFileSegment,
 };
 use reth_provider::{providers::StaticFileWriter, ProviderFactory};
+use reth_trie::history::TrieHistory;
 use std::sync::Arc;
 
 /// `reth drop-stage` command
@@ -167,6 +168,7 @@ impl Command {
             StageEnum::Merkle => {
                 tx.clear::<tables::AccountsTrie>()?;
                 tx.clear::<tables::StoragesTrie>()?;
+                TrieHistory::new(tx).clear()?;
                 tx.put::<tables::StageCheckpoints>(
                     StageId::MerkleExecute.to_string(),
                     Default::default(),
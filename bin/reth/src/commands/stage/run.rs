@@ -24,7 +24,7 @@ use reth_stages::{
     stages::{
         AccountHashingStage, BodyStage, ExecutionStage, ExecutionStageThresholds,
         IndexAccountHistoryStage, IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage,
-        StorageHashingStage, TransactionLookupStage,
+        StorageHashingStage, TransactionLookupStage, MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
     },
     ExecInput, ExecOutput, Stage, StageExt, UnwindInput, UnwindOutput,
 };
@@ -253,7 +253,10 @@ impl Command {
                     (Box::new(StorageHashingStage::new(1, batch_size, etl_config)), None)
                 }
                 StageEnum::Merkle => (
-                    Box::new(MerkleStage::default_execution()),
+                    Box::new(MerkleStage::new_execution(
+                        MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
+                        etl_config,
+                    )),
                     Some(Box::new(MerkleStage::default_unwind())),
                 ),
                 StageEnum::AccountHistory => (Box::<IndexAccountHistoryStage>::default(), None),
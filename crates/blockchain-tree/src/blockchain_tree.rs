@@ -2,6 +2,7 @@
 
 use crate::{
     metrics::{MakeCanonicalAction, MakeCanonicalDurationsRecorder, TreeMetrics},
+    sparse_trie::SparseTrieCache,
     state::{BlockChainId, TreeState},
     AppendableChain, BlockIndices, BlockchainTreeConfig, BundleStateData, TreeExternals,
 };
@@ -101,7 +102,7 @@ where
     ///   storage space efficiently. It's important to validate this configuration to ensure it does
     ///   not lead to unintended data loss.
     pub fn new(
-        externals: TreeExternals<DB, EVM>,
+        mut externals: TreeExternals<DB, EVM>,
         config: BlockchainTreeConfig,
         prune_modes: Option<PruneModes>,
     ) -> RethResult<Self> {
@@ -127,6 +128,10 @@ where
         .copied()
         .unwrap_or_default();
 
+        if config.sparse_trie() {
+            externals.sparse_trie = Some(SparseTrieCache::default());
+        }
+
         Ok(Self {
             externals,
             state: TreeState::new(
@@ -1081,6 +1086,11 @@ where
             }
         };
 
+        // the database is now at the new canonical tip
+        if let Some(sparse_trie) = &self.externals.sparse_trie {
+            sparse_trie.prune(block_hash);
+        }
+
         debug!(
             target: "blockchain_tree",
             actions = ?durations_recorder.actions,
@@ -1194,6 +1204,11 @@ where
         &mut self,
         revert_until: BlockNumber,
     ) -> Result<Option<Chain>, CanonicalError> {
+        // the cached sparse trie may have been built on top of the reverted blocks
+        if let Some(sparse_trie) = &self.externals.sparse_trie {
+            sparse_trie.clear();
+        }
+
         // read data that is needed for new sidechain
         let provider_rw = self.externals.provider_factory.provider_rw()?;
 
@@ -1274,7 +1289,7 @@ mod tests {
         ProviderFactory,
     };
     use reth_revm::EvmProcessorFactory;
-    use reth_trie::StateRoot;
+    use reth_trie::{verify::TrieVerifier, StateRoot};
     use std::collections::HashMap;
 
     fn setup_externals(
@@ -1678,6 +1693,63 @@ mod tests {
         assert_eq!(state_root, block5.state_root);
     }
 
+    #[test]
+    fn sparse_trie_state_root() {
+        let data = BlockChainTestData::default_from_number(11);
+        let (block1, exec1) = data.blocks[0].clone();
+        let (block2, exec2) = data.blocks[1].clone();
+        let (block3, exec3) = data.blocks[2].clone();
+        let genesis = data.genesis;
+
+        // test pops execution results from vector, so order is from last to first.
+        let externals = setup_externals(vec![exec3.clone(), exec2, exec1]);
+
+        // last finalized block would be number 9.
+        setup_genesis(&externals.provider_factory, genesis);
+
+        // make tree
+        let config = BlockchainTreeConfig::new(1, 2, 3, 2).with_sparse_trie(true);
+        let mut tree = BlockchainTree::new(externals, config, None).expect("failed to create tree");
+        // genesis block 10 is already canonical
+        tree.make_canonical(B256::ZERO).unwrap();
+
+        // make genesis block 10 as finalized
+        tree.finalize_block(10);
+
+        // the state root of block 2 is computed from the cached trie of block 1, the trie updates
+        // of both blocks are written to the database when they are committed
+        for block in [&block1, &block2] {
+            assert_eq!(
+                tree.insert_block(block.clone(), BlockValidationKind::Exhaustive).unwrap(),
+                InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+            );
+            let chain_id = tree.state.block_indices.get_blocks_chain_id(&block.hash()).unwrap();
+            assert!(tree.state.chains.get(&chain_id).unwrap().trie_updates().is_some());
+        }
+
+        assert_eq!(
+            tree.make_canonical(block2.hash()).unwrap(),
+            CanonicalOutcome::Committed { head: block2.header.clone() }
+        );
+
+        // the pruned trie of block 2 is used for block 3
+        assert_eq!(
+            tree.insert_block(block3.clone(), BlockValidationKind::Exhaustive).unwrap(),
+            InsertPayloadOk::Inserted(BlockStatus::Valid(BlockAttachment::Canonical))
+        );
+        assert_eq!(
+            tree.make_canonical(block3.hash()).unwrap(),
+            CanonicalOutcome::Committed { head: block3.header.clone() }
+        );
+
+        let provider = tree.externals.provider_factory.provider().unwrap();
+        let prefix_sets = exec3.hash_state_slow().construct_prefix_sets();
+        let state_root =
+            StateRoot::from_tx(provider.tx_ref()).with_prefix_sets(prefix_sets).root().unwrap();
+        assert_eq!(state_root, block3.state_root);
+        assert!(TrieVerifier::new(provider.tx_ref()).verify().unwrap().is_consistent());
+    }
+
     #[test]
     fn test_side_chain_fork() {
         let data = BlockChainTestData::default_from_number(11);
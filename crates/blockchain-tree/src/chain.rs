@@ -4,8 +4,8 @@
 //! blocks, as well as a list of the blocks the chain is composed of.
 
 use super::externals::TreeExternals;
-use crate::BundleStateDataRef;
-use reth_db::database::Database;
+use crate::{sparse_trie::SparseTrieCache, BundleStateDataRef};
+use reth_db::{database::Database, transaction::DbTx};
 use reth_interfaces::{
     blockchain_tree::{
         error::{BlockchainTreeError, InsertBlockErrorKind},
@@ -15,14 +15,18 @@ use reth_interfaces::{
     RethResult,
 };
 use reth_primitives::{
-    BlockHash, BlockNumber, ForkBlock, GotExpected, SealedBlockWithSenders, SealedHeader, U256,
+    trie::SparseTrieError, Block, BlockHash, BlockNumber, ForkBlock, GotExpected,
+    SealedBlockWithSenders, SealedHeader, B256, U256,
 };
 use reth_provider::{
     providers::{BundleStateProvider, ConsistentDbView},
-    BundleStateDataProvider, BundleStateWithReceipts, Chain, ExecutorFactory, ProviderError,
-    StateRootProvider,
+    BundleStateDataProvider, BundleStateWithReceipts, Chain, ExecutorFactory, HeaderProvider,
+    ProviderError, StateRootProvider,
+};
+use reth_trie::{
+    sparse::{DatabaseTrieNodes, SparseStateTrie},
+    updates::TrieUpdates,
 };
-use reth_trie::updates::TrieUpdates;
 use reth_trie_parallel::parallel_root::ParallelStateRoot;
 use std::{
     collections::BTreeMap,
@@ -215,13 +219,63 @@ impl AppendableChain {
             // calculate and check state root
             let start = Instant::now();
             let (state_root, trie_updates) = if block_attachment.is_canonical() {
-                let mut state = provider.bundle_state_data_provider.state().clone();
-                state.extend(bundle_state.clone());
-                let hashed_state = state.hash_state_slow();
-                ParallelStateRoot::new(consistent_view, hashed_state)
-                    .incremental_root_with_updates()
-                    .map(|(root, updates)| (root, Some(updates)))
-                    .map_err(ProviderError::from)?
+                let chain_state = provider.bundle_state_data_provider.state();
+                let sparse_state_root = match &externals.sparse_trie {
+                    Some(sparse_trie) => {
+                        let provider_ro =
+                            consistent_view.provider_ro()?.disable_long_read_transaction_safety();
+                        let canonical_state_root = provider_ro
+                            .header_by_number(canonical_fork.number)?
+                            .ok_or(ProviderError::HeaderNotFound(canonical_fork.number.into()))?
+                            .state_root;
+                        Self::sparse_state_root(
+                            sparse_trie,
+                            provider_ro.tx_ref(),
+                            &block,
+                            block_hash,
+                            canonical_state_root,
+                            chain_state,
+                            &bundle_state,
+                        )
+                        .inspect_err(|err| {
+                            tracing::debug!(
+                                target: "blockchain_tree::chain",
+                                number = block.number,
+                                hash = %block_hash,
+                                %err,
+                                "Falling back to parallel state root"
+                            );
+                        })
+                        .ok()
+                    }
+                    None => None,
+                };
+
+                match sparse_state_root {
+                    Some((state_root, trie_updates)) if state_root == block.state_root => {
+                        (state_root, Some(trie_updates))
+                    }
+                    sparse_state_root => {
+                        // the block is only rejected if the parallel state root agrees
+                        if let Some((state_root, _)) = sparse_state_root {
+                            tracing::debug!(
+                                target: "blockchain_tree::chain",
+                                number = block.number,
+                                hash = %block_hash,
+                                got = %state_root,
+                                expected = %block.state_root,
+                                "Sparse state root mismatch, confirming with parallel state root"
+                            );
+                        }
+                        let mut state = chain_state.clone();
+                        state.extend(bundle_state.clone());
+                        let hashed_state = state.hash_state_slow();
+                        ParallelStateRoot::new(consistent_view, hashed_state)
+                            .incremental_root_with_updates()
+                            .map(|(root, updates)| (root, Some(updates)))
+                            .map_err(ProviderError::from)?
+                    }
+                }
             } else {
                 (provider.state_root(bundle_state.state())?, None)
             };
@@ -246,6 +300,52 @@ impl AppendableChain {
         }
     }
 
+    /// Computes the state root of the given block that extends the canonical chain by applying its
+    /// state changes to the cached sparse trie of its parent.
+    ///
+    /// If the trie of the parent is not cached, the state changes of the whole chain are applied to
+    /// the trie at the canonical tip instead. Nodes that are not revealed in the trie are loaded
+    /// from the database. The trie of the block is cached if the state root matches the block.
+    ///
+    /// Returns the state root and the updates of the trie tables, which are relative to the state
+    /// of the parent if its trie was cached, and relative to the canonical tip otherwise.
+    fn sparse_state_root<TX: DbTx>(
+        sparse_trie: &SparseTrieCache,
+        tx: &TX,
+        block: &Block,
+        block_hash: BlockHash,
+        canonical_state_root: B256,
+        chain_state: &BundleStateWithReceipts,
+        block_state: &BundleStateWithReceipts,
+    ) -> Result<(B256, TrieUpdates), SparseTrieError> {
+        let (mut trie, hashed_state) = match sparse_trie.take(block.parent_hash) {
+            Some(trie) => (trie, block_state.hash_state_slow()),
+            None => {
+                let mut state = chain_state.clone();
+                state.extend(block_state.clone());
+                let trie = SparseStateTrie::new(canonical_state_root).with_updates(true);
+                (trie, state.hash_state_slow())
+            }
+        };
+
+        let nodes = DatabaseTrieNodes::new(tx);
+        let state_root = trie.update(&hashed_state, &nodes)?;
+        tracing::debug!(
+            target: "blockchain_tree::chain",
+            number = block.number,
+            hash = %block_hash,
+            proofs = nodes.proofs(),
+            proof_duration = ?nodes.proof_duration(),
+            "Loaded sparse trie nodes from the database"
+        );
+
+        let trie_updates = trie.take_updates();
+        if state_root == block.state_root {
+            sparse_trie.insert(block_hash, trie);
+        }
+        Ok((state_root, trie_updates))
+    }
+
     /// Validate and execute the given block, and append it to this chain.
     ///
     /// This expects that the block's ancestors can be traced back to the `canonical_fork` (the
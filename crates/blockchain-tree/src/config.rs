@@ -19,6 +19,12 @@ pub struct BlockchainTreeConfig {
     /// be 256. It covers both number of blocks required for reorg, and number of blocks
     /// required for `BLOCKHASH` EVM opcode.
     num_of_additional_canonical_block_hashes: u64,
+    /// Whether the state root of blocks that extend the canonical chain is computed from a sparse
+    /// trie that is kept in memory across blocks.
+    ///
+    /// The trie updates of these blocks are returned by the sparse trie and cached with the chain,
+    /// like the ones of the parallel state root, so they are not recomputed on commit.
+    sparse_trie: bool,
 }
 
 impl Default for BlockchainTreeConfig {
@@ -33,6 +39,7 @@ impl Default for BlockchainTreeConfig {
             num_of_additional_canonical_block_hashes: 256,
             // max unconnected blocks.
             max_unconnected_blocks: 200,
+            sparse_trie: false,
         }
     }
 }
@@ -53,9 +60,16 @@ impl BlockchainTreeConfig {
             max_reorg_depth,
             num_of_additional_canonical_block_hashes,
             max_unconnected_blocks,
+            sparse_trie: false,
         }
     }
 
+    /// Configures whether the state root is computed from an in-memory sparse trie.
+    pub fn with_sparse_trie(mut self, sparse_trie: bool) -> Self {
+        self.sparse_trie = sparse_trie;
+        self
+    }
+
     /// Return the maximum reorg depth.
     pub fn max_reorg_depth(&self) -> u64 {
         self.max_reorg_depth
@@ -87,4 +101,9 @@ impl BlockchainTreeConfig {
     pub fn max_unconnected_blocks(&self) -> usize {
         self.max_unconnected_blocks
     }
+
+    /// Return whether the state root is computed from an in-memory sparse trie.
+    pub fn sparse_trie(&self) -> bool {
+        self.sparse_trie
+    }
 }
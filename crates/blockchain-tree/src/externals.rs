@@ -1,5 +1,6 @@
 //! Blockchain tree externals.
 
+use crate::sparse_trie::SparseTrieCache;
 use reth_db::{
     cursor::DbCursorRO, database::Database, static_file::HeaderMask, tables, transaction::DbTx,
 };
@@ -25,6 +26,9 @@ pub struct TreeExternals<DB, EVM> {
     pub(crate) consensus: Arc<dyn Consensus>,
     /// The executor factory to execute blocks with.
     pub(crate) executor_factory: EVM,
+    /// The sparse state trie of the latest validated block that extends the canonical chain, if
+    /// enabled by [BlockchainTreeConfig::sparse_trie](crate::BlockchainTreeConfig::sparse_trie).
+    pub(crate) sparse_trie: Option<SparseTrieCache>,
 }
 
 impl<DB, EVM> TreeExternals<DB, EVM> {
@@ -34,7 +38,7 @@ impl<DB, EVM> TreeExternals<DB, EVM> {
         consensus: Arc<dyn Consensus>,
         executor_factory: EVM,
     ) -> Self {
-        Self { provider_factory, consensus, executor_factory }
+        Self { provider_factory, consensus, executor_factory, sparse_trie: None }
     }
 }
 
@@ -53,4 +53,6 @@ pub mod noop;
 
 mod state;
 
+mod sparse_trie;
+
 use aquamarine as _;
@@ -0,0 +1,59 @@
+//! Cache of the sparse state trie of the latest block that extends the canonical chain.
+
+use parking_lot::Mutex;
+use reth_primitives::BlockHash;
+use reth_trie::sparse::SparseStateTrie;
+
+/// The depth at which the revealed nodes of the cached trie are pruned once its block is committed
+/// to the database.
+///
+/// The nodes above are touched by almost every block, the nodes below are loaded from the database
+/// again when needed.
+const PRUNE_DEPTH: usize = 4;
+
+/// The sparse state trie of the latest validated block that extends the canonical chain.
+///
+/// The state changes of the next block are applied to the trie of its parent, so that only the
+/// nodes that have not been revealed by previous blocks are loaded from the database.
+///
+/// The unrevealed nodes of the trie must be unchanged in the database, which holds as long as the
+/// database is only advanced along the chain of the cached block. The cache is cleared when the
+/// canonical chain is reverted.
+#[derive(Debug, Default)]
+pub(crate) struct SparseTrieCache {
+    trie: Mutex<Option<(BlockHash, SparseStateTrie)>>,
+}
+
+impl SparseTrieCache {
+    /// Takes the cached trie if it is the trie of the given block.
+    ///
+    /// The cache is left untouched otherwise, so that the trie is still available to the children
+    /// of its block that are inserted later, for example after a sibling of one of them.
+    pub(crate) fn take(&self, block_hash: BlockHash) -> Option<SparseStateTrie> {
+        let mut trie = self.trie.lock();
+        if trie.as_ref().is_some_and(|(hash, _)| *hash == block_hash) {
+            return trie.take().map(|(_, trie)| trie)
+        }
+        None
+    }
+
+    /// Caches the trie of the given block.
+    pub(crate) fn insert(&self, block_hash: BlockHash, trie: SparseStateTrie) {
+        *self.trie.lock() = Some((block_hash, trie));
+    }
+
+    /// Prunes the cached trie if it is the trie of the given block, which has just been committed
+    /// to the database.
+    pub(crate) fn prune(&self, block_hash: BlockHash) {
+        if let Some((hash, trie)) = self.trie.lock().as_mut() {
+            if *hash == block_hash {
+                trie.prune(PRUNE_DEPTH);
+            }
+        }
+    }
+
+    /// Clears the cache.
+    pub(crate) fn clear(&self) {
+        self.trie.lock().take();
+    }
+}
@@ -1,7 +1,9 @@
 //! Configuration files.
 
 use reth_discv4::Discv4Config;
-use reth_network::{NetworkConfigBuilder, PeersConfig, SessionsConfig};
+use reth_network::{
+    transactions::TransactionsManagerConfig, NetworkConfigBuilder, PeersConfig, SessionsConfig,
+};
 use reth_primitives::PruneModes;
 use secp256k1::SecretKey;
 use serde::{Deserialize, Deserializer, Serialize};
@@ -24,6 +26,8 @@ pub struct Config {
     pub peers: PeersConfig,
     /// Configuration for peer sessions.
     pub sessions: SessionsConfig,
+    /// Configuration for transaction gossip.
+    pub transactions: TransactionsManagerConfig,
 }
 
 impl Config {
@@ -218,11 +222,19 @@ pub struct MerkleConfig {
     /// The threshold (in number of blocks) for switching from incremental trie building of changes
     /// to whole rebuild.
     pub clean_threshold: u64,
+    /// Whether to record the trie node changesets of every block, so that proofs for historical
+    /// blocks can be served without recomputing the trie.
+    ///
+    /// If enabled, the trie is always built incrementally one block at a time, ignoring
+    /// `clean_threshold`, and the history starts at the first block built this way. Once started,
+    /// the history keeps being recorded even if this is disabled, until the merkle stage is
+    /// dropped.
+    pub trie_changesets: bool,
 }
 
 impl Default for MerkleConfig {
     fn default() -> Self {
-        Self { clean_threshold: 5_000 }
+        Self { clean_threshold: 5_000, trie_changesets: false }
     }
 }
 
@@ -326,6 +338,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::Config;
+    use reth_network::transactions::{TransactionIngressPolicy, TransactionPropagationMode};
     use std::time::Duration;
 
     const EXTENSION: &str = "toml";
@@ -688,4 +701,20 @@ connect_trusted_nodes_only = true
         let conf: Config = toml::from_str(trusted_nodes_only).unwrap();
         assert!(conf.peers.trusted_nodes_only);
     }
+
+    #[test]
+    fn test_conf_transactions() {
+        let transactions = r"#
+[transactions]
+propagation_mode = 'trusted'
+announce_only = true
+propagation_delay = '500ms'
+ingress_policy = 'trusted'
+#";
+        let conf: Config = toml::from_str(transactions).unwrap();
+        assert_eq!(conf.transactions.propagation_mode, TransactionPropagationMode::Trusted);
+        assert!(conf.transactions.announce_only);
+        assert_eq!(conf.transactions.propagation_delay, Duration::from_millis(500));
+        assert_eq!(conf.transactions.ingress_policy, TransactionIngressPolicy::Trusted);
+    }
 }
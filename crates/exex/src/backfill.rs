@@ -0,0 +1,187 @@
+use std::{fmt::Debug, ops::RangeInclusive, sync::Arc};
+
+use reth_db::database::Database;
+use reth_evm::ConfigureEvm;
+use reth_interfaces::provider::ProviderError;
+use reth_primitives::{BlockNumber, TransactionVariant};
+use reth_provider::{
+    BlockNumReader, BlockReader, CanonStateNotification, Chain, ExecutorFactory, HeaderProvider,
+    ProviderFactory,
+};
+use reth_revm::EvmProcessorFactory;
+use reth_tasks::TaskExecutor;
+use reth_tracing::tracing::debug;
+use tokio::sync::mpsc::{self, Receiver};
+
+/// A job that re-executes a range of historical blocks to produce the [`Chain`]s an ExEx would
+/// have received for them.
+///
+/// The blocks are executed in batches of at most `batch_size` blocks on top of the historical
+/// state before each batch, and every batch is yielded as one [`Chain`].
+#[derive(Debug)]
+pub struct BackfillJob<DB, EvmConfig> {
+    /// The provider factory to read the blocks and the historical state.
+    provider_factory: ProviderFactory<DB>,
+    /// The factory of the block executors.
+    executor_factory: EvmProcessorFactory<EvmConfig>,
+    /// The blocks that are left to execute.
+    range: RangeInclusive<BlockNumber>,
+    /// The maximum number of blocks in a [`Chain`].
+    batch_size: u64,
+}
+
+impl<DB, EvmConfig> BackfillJob<DB, EvmConfig> {
+    /// Create a new job that executes the given range of blocks.
+    pub fn new(
+        provider_factory: ProviderFactory<DB>,
+        executor_factory: EvmProcessorFactory<EvmConfig>,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Self {
+        Self { provider_factory, executor_factory, range, batch_size: 100 }
+    }
+
+    /// Set the maximum number of blocks in a [`Chain`].
+    pub fn with_batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+}
+
+impl<DB, EvmConfig> BackfillJob<DB, EvmConfig>
+where
+    DB: Database,
+    EvmConfig: ConfigureEvm + Clone + Send + Sync + 'static,
+{
+    /// Executes the blocks of the range on top of the state after the block before the range.
+    fn execute_range(&self, range: RangeInclusive<BlockNumber>) -> eyre::Result<Chain> {
+        debug!(?range, "executing backfill range");
+
+        let provider = self.provider_factory.provider()?;
+        let state_provider =
+            self.provider_factory.history_by_block_number(range.start().saturating_sub(1))?;
+        let mut executor = self.executor_factory.with_state(state_provider);
+
+        let mut blocks = Vec::with_capacity(range.clone().count());
+        for block_number in range {
+            let td = provider
+                .header_td_by_number(block_number)?
+                .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+            let block = provider
+                .block_with_senders(block_number.into(), TransactionVariant::WithHash)?
+                .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+
+            executor.execute_and_verify_receipt(&block, td)?;
+
+            let hash = block.header.hash_slow();
+            blocks.push(block.seal(hash));
+        }
+
+        Ok(Chain::new(blocks, executor.take_output_state(), None))
+    }
+}
+
+impl<DB, EvmConfig> Iterator for BackfillJob<DB, EvmConfig>
+where
+    DB: Database,
+    EvmConfig: ConfigureEvm + Clone + Send + Sync + 'static,
+{
+    type Item = eyre::Result<Chain>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.range.is_empty() {
+            return None
+        }
+
+        let start = *self.range.start();
+        let end = (*self.range.end()).min(start.saturating_add(self.batch_size - 1));
+        self.range = end + 1..=*self.range.end();
+
+        Some(self.execute_range(start..=end))
+    }
+}
+
+/// A running [`BackfillJob`] spawned by [`SpawnBackfillJob::spawn_backfill_job`].
+#[derive(Debug)]
+pub struct BackfillJobHandle {
+    /// The last block of the job.
+    pub target: BlockNumber,
+    /// The notifications of the job, one [`CanonStateNotification::Commit`] per executed batch.
+    ///
+    /// The channel is closed once the job has finished, or after the first error.
+    pub notifications: Receiver<eyre::Result<CanonStateNotification>>,
+}
+
+/// Spawns [`BackfillJob`]s for the ExEx's that are behind the node.
+pub trait SpawnBackfillJob: Debug + Send + Sync + 'static {
+    /// Spawns a job that executes the blocks from `start` up to the current tip of the node.
+    ///
+    /// Returns `None` if there are no blocks to execute.
+    fn spawn_backfill_job(&self, start: BlockNumber) -> eyre::Result<Option<BackfillJobHandle>>;
+}
+
+/// Spawns [`BackfillJob`]s over the database of the node as blocking tasks.
+#[derive(Debug, Clone)]
+pub struct BackfillJobFactory<DB, EvmConfig> {
+    /// The provider factory to read the blocks and the historical state.
+    provider_factory: ProviderFactory<DB>,
+    /// The factory of the block executors.
+    executor_factory: EvmProcessorFactory<EvmConfig>,
+    /// The task executor to spawn the jobs on.
+    task_executor: TaskExecutor,
+    /// The maximum number of blocks in a [`Chain`].
+    batch_size: u64,
+}
+
+impl<DB, EvmConfig> BackfillJobFactory<DB, EvmConfig> {
+    /// Create a new factory.
+    pub fn new(
+        provider_factory: ProviderFactory<DB>,
+        executor_factory: EvmProcessorFactory<EvmConfig>,
+        task_executor: TaskExecutor,
+    ) -> Self {
+        Self { provider_factory, executor_factory, task_executor, batch_size: 100 }
+    }
+
+    /// Set the maximum number of blocks in a [`Chain`].
+    pub fn with_batch_size(mut self, batch_size: u64) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+}
+
+impl<DB: Clone, EvmConfig: Clone> BackfillJobFactory<DB, EvmConfig> {
+    /// Creates a job that executes the given range of blocks.
+    pub fn backfill(&self, range: RangeInclusive<BlockNumber>) -> BackfillJob<DB, EvmConfig> {
+        BackfillJob::new(self.provider_factory.clone(), self.executor_factory.clone(), range)
+            .with_batch_size(self.batch_size)
+    }
+}
+
+impl<DB, EvmConfig> SpawnBackfillJob for BackfillJobFactory<DB, EvmConfig>
+where
+    DB: Database + Clone + 'static,
+    EvmConfig: ConfigureEvm + Clone + Send + Sync + 'static,
+{
+    fn spawn_backfill_job(&self, start: BlockNumber) -> eyre::Result<Option<BackfillJobHandle>> {
+        let target = self.provider_factory.best_block_number()?;
+        if start > target {
+            return Ok(None)
+        }
+
+        let job = self.backfill(start..=target);
+        let (tx, rx) = mpsc::channel(1);
+        self.task_executor.spawn_blocking(async move {
+            for chain in job {
+                let failed = chain.is_err();
+                let notification =
+                    chain.map(|chain| CanonStateNotification::Commit { new: Arc::new(chain) });
+                // stop if the receiver was dropped
+                if tx.send(notification).await.is_err() || failed {
+                    break
+                }
+            }
+        });
+
+        Ok(Some(BackfillJobHandle { target, notifications: rx }))
+    }
+}
@@ -1,14 +1,17 @@
+use jsonrpsee::Methods;
+use reth_network::NetworkHandle;
 use reth_node_api::FullNodeComponents;
 use reth_node_core::{
     dirs::{ChainPath, DataDirPath},
     node_config::NodeConfig,
 };
+use reth_payload_builder::PayloadBuilderHandle;
 use reth_primitives::Head;
 use reth_provider::CanonStateNotification;
 use reth_tasks::TaskExecutor;
 use tokio::sync::mpsc::{Receiver, UnboundedSender};
 
-use crate::ExExEvent;
+use crate::{ExExEvent, ExExRpcModules};
 
 /// Captures the context that an ExEx has access to.
 #[derive(Debug)]
@@ -40,7 +43,41 @@ pub struct ExExContext<Node: FullNodeComponents> {
     /// # Important
     ///
     /// Once a `CanonStateNotification` is sent over the channel, it is considered delivered by the
-    /// node.
+    /// node. Notifications above the last `FinishedHeight` of the ExEx are sent again after a
+    /// restart of the node, so the ExEx must be able to handle the same notification twice.
     pub notifications: Receiver<CanonStateNotification>,
-    // TODO(alexey): add pool, payload builder, anything else?
+    /// The components of the node, as built by the node's components builder.
+    ///
+    /// Besides the provider, the transaction pool and the task executor, which are also available
+    /// as fields of the context, this gives access to the network and the payload builder of the
+    /// node.
+    pub components: Node,
+    /// The RPC modules registered by the ExEx's.
+    ///
+    /// See [`ExExContext::register_rpc_module`].
+    pub rpc_modules: ExExRpcModules,
+}
+
+impl<Node: FullNodeComponents> ExExContext<Node> {
+    /// Returns the handle to the network of the node.
+    ///
+    /// This can be used to interact with peers, e.g. to send messages of custom sub-protocols.
+    pub fn network(&self) -> &NetworkHandle {
+        self.components.network()
+    }
+
+    /// Returns the handle to the payload builder service of the node.
+    pub fn payload_builder(&self) -> &PayloadBuilderHandle<Node::Engine> {
+        self.components.payload_builder()
+    }
+
+    /// Registers an RPC module that is merged into the configured transports (http, ws, ipc) of
+    /// the RPC server.
+    ///
+    /// The ExEx's are launched before the RPC server is started, so the module must be
+    /// registered while the ExEx is being launched. Returns an error afterwards, or if the module
+    /// can't be merged.
+    pub fn register_rpc_module(&self, module: impl Into<Methods>) -> eyre::Result<()> {
+        self.rpc_modules.register(module)
+    }
 }
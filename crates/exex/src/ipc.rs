@@ -0,0 +1,686 @@
+use std::{
+    collections::VecDeque,
+    ffi::OsString,
+    io,
+    os::unix::fs::{DirBuilderExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+use alloy_rlp::{BufMut, Decodable, Encodable, Header, RlpDecodable, RlpEncodable};
+use bytes::BytesMut;
+use futures::{SinkExt, StreamExt};
+use reth_node_api::FullNodeComponents;
+use reth_primitives::{fs, Address, BlockNumber, Bytes, B256, U256};
+use reth_provider::{CanonStateNotification, Chain};
+use reth_tracing::tracing::{debug, info, warn};
+use tokio::{
+    net::{UnixListener, UnixStream},
+    sync::mpsc::{Receiver, UnboundedSender},
+};
+use tokio_util::codec::{FramedRead, FramedWrite, LengthDelimitedCodec};
+
+use crate::{ExExContext, ExExEvent};
+
+/// The version of the protocol spoken by the [`IpcExEx`], sent in the [`IpcFrame::Hello`] frame.
+///
+/// The version is incremented on every incompatible change of the frames or of the wire types of
+/// the notifications.
+pub const IPC_EXEX_PROTOCOL_VERSION: u8 = 3;
+
+/// The default maximum number of notifications that are sent to the client without being
+/// acknowledged.
+pub const DEFAULT_MAX_UNACKED_NOTIFICATIONS: usize = 64;
+
+/// The maximum length of a frame sent by the client.
+const MAX_CLIENT_FRAME_LENGTH: usize = 1024;
+
+const HELLO: u8 = 0x00;
+const NOTIFICATION: u8 = 0x01;
+const FINISHED_HEIGHT: u8 = 0x02;
+
+const COMMIT: u8 = 0x00;
+const REORG: u8 = 0x01;
+
+/// A frame of the [`IpcExEx`] protocol.
+///
+/// # Format
+///
+/// Every frame is prefixed with its length as a big-endian `u32`, followed by a one byte kind and
+/// the body of the frame:
+///
+/// | Kind   | Frame                        | Direction      | Body                             |
+/// |--------|------------------------------|----------------|----------------------------------|
+/// | `0x00` | [`IpcFrame::Hello`]          | node -> client | protocol version as `u8`         |
+/// | `0x01` | [`IpcFrame::Notification`]   | node -> client | RLP encoded [`IpcNotification`]  |
+/// | `0x02` | [`IpcFrame::FinishedHeight`] | client -> node | block number as big-endian `u64` |
+///
+/// The RLP encoding of the notifications is documented on [`IpcNotification`] and the types it
+/// contains.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcFrame {
+    /// The first frame sent to a client after it has connected.
+    Hello {
+        /// The version of the protocol, see [`IPC_EXEX_PROTOCOL_VERSION`].
+        version: u8,
+    },
+    /// A notification of a change of the canonical chain.
+    Notification(IpcNotification),
+    /// The client has processed all blocks up to and including the given block number, see
+    /// [`ExExEvent::FinishedHeight`].
+    FinishedHeight(BlockNumber),
+}
+
+impl IpcFrame {
+    /// Encodes the frame, without the length prefix.
+    pub fn encode(&self) -> bytes::Bytes {
+        let mut buf = BytesMut::new();
+        match self {
+            Self::Hello { version } => {
+                buf.put_u8(HELLO);
+                buf.put_u8(*version);
+            }
+            Self::Notification(notification) => {
+                buf.put_u8(NOTIFICATION);
+                notification.encode(&mut buf);
+            }
+            Self::FinishedHeight(height) => {
+                buf.put_u8(FINISHED_HEIGHT);
+                buf.put_u64(*height);
+            }
+        }
+        buf.freeze()
+    }
+
+    /// Decodes a frame, without the length prefix.
+    pub fn decode(frame: &[u8]) -> eyre::Result<Self> {
+        let (&kind, mut body) = frame.split_first().ok_or_else(|| eyre::eyre!("empty frame"))?;
+        match kind {
+            HELLO => {
+                let &[version] = body else { eyre::bail!("invalid hello frame") };
+                Ok(Self::Hello { version })
+            }
+            NOTIFICATION => {
+                let notification = IpcNotification::decode(&mut body)?;
+                if !body.is_empty() {
+                    eyre::bail!("trailing bytes after notification")
+                }
+                Ok(Self::Notification(notification))
+            }
+            FINISHED_HEIGHT => Ok(Self::FinishedHeight(BlockNumber::from_be_bytes(
+                body.try_into().map_err(|_| eyre::eyre!("invalid finished height frame"))?,
+            ))),
+            kind => eyre::bail!("unknown frame kind {kind}"),
+        }
+    }
+}
+
+/// A notification of a change of the canonical chain, as sent in an [`IpcFrame::Notification`].
+///
+/// Encoded as the RLP list `[0x00, new]` for a commit or `[0x01, old, new]` for a reorg, where
+/// `old` and `new` are [`IpcChain`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IpcNotification {
+    /// Blocks were appended to the canonical chain.
+    Commit {
+        /// The appended blocks.
+        new: IpcChain,
+    },
+    /// Blocks were reverted from the canonical chain and replaced by other blocks.
+    Reorg {
+        /// The reverted blocks.
+        old: IpcChain,
+        /// The blocks that replaced them.
+        new: IpcChain,
+    },
+}
+
+impl IpcNotification {
+    fn payload_length(&self) -> usize {
+        match self {
+            Self::Commit { new } => COMMIT.length() + new.length(),
+            Self::Reorg { old, new } => REORG.length() + old.length() + new.length(),
+        }
+    }
+}
+
+impl Encodable for IpcNotification {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        match self {
+            Self::Commit { new } => {
+                COMMIT.encode(out);
+                new.encode(out);
+            }
+            Self::Reorg { old, new } => {
+                REORG.encode(out);
+                old.encode(out);
+                new.encode(out);
+            }
+        }
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for IpcNotification {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let notification = match u8::decode(buf)? {
+            COMMIT => Self::Commit { new: Decodable::decode(buf)? },
+            REORG => Self::Reorg { old: Decodable::decode(buf)?, new: Decodable::decode(buf)? },
+            _ => return Err(alloy_rlp::Error::Custom("unknown notification kind")),
+        };
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+        Ok(notification)
+    }
+}
+
+impl From<&CanonStateNotification> for IpcNotification {
+    fn from(notification: &CanonStateNotification) -> Self {
+        match notification {
+            CanonStateNotification::Commit { new } => Self::Commit { new: new.as_ref().into() },
+            CanonStateNotification::Reorg { old, new } => {
+                Self::Reorg { old: old.as_ref().into(), new: new.as_ref().into() }
+            }
+        }
+    }
+}
+
+/// A range of consecutive blocks of a chain, with the state changes they made.
+///
+/// Encoded as the RLP list `[[block, ...], [account change, ...]]`.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct IpcChain {
+    /// The blocks, in ascending order.
+    pub blocks: Vec<IpcBlock>,
+    /// The accounts changed by the blocks, ordered by address.
+    pub accounts: Vec<IpcAccountChange>,
+}
+
+impl From<&Chain> for IpcChain {
+    fn from(chain: &Chain) -> Self {
+        let blocks = chain
+            .blocks_and_receipts()
+            .map(|(block, receipts)| IpcBlock {
+                hash: block.hash(),
+                number: block.number,
+                block: alloy_rlp::encode(block.block.clone().unseal()).into(),
+                senders: block.senders.clone(),
+                receipts: receipts
+                    .iter()
+                    .flatten()
+                    .map(|receipt| receipt.clone().with_bloom().envelope_encoded())
+                    .collect(),
+            })
+            .collect();
+
+        let mut accounts = chain
+            .state()
+            .bundle_accounts_iter()
+            .map(|(address, account)| {
+                let mut storage = account
+                    .storage
+                    .iter()
+                    .map(|(slot, value)| IpcStorageSlot {
+                        slot: B256::new(slot.to_be_bytes()),
+                        value: value.present_value,
+                    })
+                    .collect::<Vec<_>>();
+                storage.sort_unstable_by_key(|slot| slot.slot);
+
+                IpcAccountChange {
+                    address,
+                    info: account.info.as_ref().map(|info| IpcAccount {
+                        nonce: info.nonce,
+                        balance: info.balance,
+                        code_hash: info.code_hash,
+                    }),
+                    storage_wiped: account.status.was_destroyed(),
+                    storage,
+                }
+            })
+            .collect::<Vec<_>>();
+        accounts.sort_unstable_by_key(|account| account.address);
+
+        Self { blocks, accounts }
+    }
+}
+
+/// A block of an [`IpcChain`].
+///
+/// Encoded as the RLP list `[hash, number, block, [sender, ...], [receipt, ...]]`.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct IpcBlock {
+    /// The hash of the block.
+    pub hash: B256,
+    /// The number of the block.
+    pub number: BlockNumber,
+    /// The block, RLP encoded as in the `eth` wire protocol, as an RLP string.
+    pub block: Bytes,
+    /// The senders of the transactions of the block, in order.
+    pub senders: Vec<Address>,
+    /// The receipts of the transactions of the block, in order, each encoded as specified by
+    /// EIP-2718, as returned by `debug_getRawReceipts`, as RLP strings.
+    pub receipts: Vec<Bytes>,
+}
+
+/// The change of an account in an [`IpcChain`].
+///
+/// Encoded as the RLP list `[address, [account], storage wiped, [storage slot, ...]]`, where the
+/// account list is empty if the account does not exist and the storage wiped flag is `0x80` for
+/// `false` and `0x01` for `true`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IpcAccountChange {
+    /// The address of the account.
+    pub address: Address,
+    /// The account after the last block of the chain, `None` if it does not exist.
+    pub info: Option<IpcAccount>,
+    /// Whether the storage of the account was wiped by the blocks, i.e. the account was destroyed.
+    ///
+    /// Storage slots that are not in [`IpcAccountChange::storage`] are then empty.
+    pub storage_wiped: bool,
+    /// The changed storage slots, with their values after the last block of the chain, ordered by
+    /// slot.
+    pub storage: Vec<IpcStorageSlot>,
+}
+
+impl IpcAccountChange {
+    fn payload_length(&self) -> usize {
+        self.address.length() +
+            alloy_rlp::list_length(self.info.as_slice()) +
+            self.storage_wiped.length() +
+            self.storage.length()
+    }
+}
+
+impl Encodable for IpcAccountChange {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.address.encode(out);
+        alloy_rlp::encode_list(self.info.as_slice(), out);
+        self.storage_wiped.encode(out);
+        self.storage.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for IpcAccountChange {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let address = Decodable::decode(buf)?;
+        let mut info = Vec::<IpcAccount>::decode(buf)?;
+        if info.len() > 1 {
+            return Err(alloy_rlp::Error::Custom("more than one account"))
+        }
+        let change = Self {
+            address,
+            info: info.pop(),
+            storage_wiped: Decodable::decode(buf)?,
+            storage: Decodable::decode(buf)?,
+        };
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+        Ok(change)
+    }
+}
+
+/// An account in an [`IpcAccountChange`].
+///
+/// Encoded as the RLP list `[nonce, balance, code hash]`.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct IpcAccount {
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The hash of the bytecode of the account.
+    pub code_hash: B256,
+}
+
+/// A changed storage slot in an [`IpcAccountChange`].
+///
+/// Encoded as the RLP list `[slot, value]`.
+#[derive(Debug, Clone, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct IpcStorageSlot {
+    /// The storage slot.
+    pub slot: B256,
+    /// The value of the slot after the last block of the chain.
+    pub value: U256,
+}
+
+/// An ExEx that streams the notifications to another process over a Unix socket.
+///
+/// One client is served at a time, further connections are accepted once the current client has
+/// disconnected. After the [`IpcFrame::Hello`] frame, the client receives an
+/// [`IpcFrame::Notification`] for every notification of the ExEx and is expected to answer with
+/// [`IpcFrame::FinishedHeight`] frames, which are forwarded to the node as
+/// [`ExExEvent::FinishedHeight`] events.
+///
+/// Notifications are kept until the client has acknowledged them with a finished height at or
+/// above their tip, and are sent again to the next client if it disconnects before. Once there are
+/// `max_unacked` unacknowledged notifications, no more notifications are received from the node
+/// until the client catches up, so a slow client applies backpressure to the node like any other
+/// ExEx.
+///
+/// The socket is only accessible by the user running the node.
+#[derive(Debug)]
+pub struct IpcExEx {
+    /// The channel to receive the notifications of the ExEx from the node.
+    notifications: Receiver<CanonStateNotification>,
+    /// The channel to send the events of the ExEx to the node.
+    events: UnboundedSender<ExExEvent>,
+    /// The listener of the socket.
+    listener: UnixListener,
+    /// The notifications that have not been acknowledged by the client, in order.
+    unacked: VecDeque<CanonStateNotification>,
+    /// The maximum number of unacknowledged notifications.
+    max_unacked: usize,
+}
+
+impl IpcExEx {
+    /// Create a new ExEx listening on the socket at the given path.
+    ///
+    /// A socket file left behind by a previous run is removed.
+    pub fn new<Node: FullNodeComponents>(
+        ctx: ExExContext<Node>,
+        path: impl Into<PathBuf>,
+    ) -> eyre::Result<Self> {
+        Self::with_channels(ctx.notifications, ctx.events, path)
+    }
+
+    /// Create a new ExEx listening on the socket at the given path, that receives the
+    /// notifications and sends the events over the given channels of an ExEx.
+    ///
+    /// A socket file left behind by a previous run is removed.
+    pub fn with_channels(
+        notifications: Receiver<CanonStateNotification>,
+        events: UnboundedSender<ExExEvent>,
+        path: impl Into<PathBuf>,
+    ) -> eyre::Result<Self> {
+        let path = path.into();
+        match std::fs::remove_file(&path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+            Err(err) => return Err(err.into()),
+        }
+        let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty());
+        let parent = parent.unwrap_or_else(|| Path::new("."));
+        fs::create_dir_all(parent)?;
+        let file_name = path
+            .file_name()
+            .ok_or_else(|| eyre::eyre!("invalid socket path {}", path.display()))?;
+
+        // only the user running the node may connect, so the socket is bound in a directory only
+        // that user can access and moved into place once its own permissions are restricted
+        let mut bind_dir = OsString::from(".");
+        bind_dir.push(file_name);
+        bind_dir.push(".bind");
+        let bind_dir = parent.join(bind_dir);
+        let listener = bind_private(&bind_dir, &path);
+        let _ = std::fs::remove_dir_all(&bind_dir);
+        let listener = listener?;
+        info!(target: "exex::ipc", path = %path.display(), "IPC ExEx listening");
+
+        Ok(Self {
+            notifications,
+            events,
+            listener,
+            unacked: VecDeque::new(),
+            max_unacked: DEFAULT_MAX_UNACKED_NOTIFICATIONS,
+        })
+    }
+
+    /// Set the maximum number of notifications that are sent to the client without being
+    /// acknowledged.
+    pub fn with_max_unacked(mut self, max_unacked: usize) -> Self {
+        self.max_unacked = max_unacked.max(1);
+        self
+    }
+
+    /// Runs the ExEx until the node stops sending notifications.
+    pub async fn run(mut self) -> eyre::Result<()> {
+        loop {
+            // buffer the notifications until a client connects
+            let stream = tokio::select! {
+                res = self.listener.accept() => res?.0,
+                notification = self.notifications.recv(), if self.has_capacity() => {
+                    let Some(notification) = notification else { return Ok(()) };
+                    self.unacked.push_back(notification);
+                    continue
+                }
+            };
+
+            debug!(target: "exex::ipc", unacked = self.unacked.len(), "IPC ExEx client connected");
+            match self.serve(stream).await {
+                Ok(true) => debug!(target: "exex::ipc", "IPC ExEx client disconnected"),
+                Ok(false) => return Ok(()),
+                Err(err) => warn!(target: "exex::ipc", %err, "IPC ExEx client failed"),
+            }
+        }
+    }
+
+    /// Serves a connected client.
+    ///
+    /// Returns `true` if the client has disconnected, and `false` if the node stopped sending
+    /// notifications.
+    async fn serve(&mut self, stream: UnixStream) -> eyre::Result<bool> {
+        let (reader, writer) = stream.into_split();
+        let mut reader = FramedRead::new(
+            reader,
+            LengthDelimitedCodec::builder().max_frame_length(MAX_CLIENT_FRAME_LENGTH).new_codec(),
+        );
+        let mut writer = FramedWrite::new(
+            writer,
+            LengthDelimitedCodec::builder().max_frame_length(u32::MAX as usize).new_codec(),
+        );
+
+        writer.send(IpcFrame::Hello { version: IPC_EXEX_PROTOCOL_VERSION }.encode()).await?;
+        // send again the notifications the previous client has not acknowledged
+        for notification in &self.unacked {
+            writer.send(IpcFrame::Notification(notification.into()).encode()).await?;
+        }
+
+        loop {
+            tokio::select! {
+                frame = reader.next() => {
+                    let Some(frame) = frame else { return Ok(true) };
+                    match IpcFrame::decode(&frame?)? {
+                        IpcFrame::FinishedHeight(height) => self.on_finished_height(height)?,
+                        frame => eyre::bail!("unexpected frame from client: {frame:?}"),
+                    }
+                }
+                notification = self.notifications.recv(), if self.has_capacity() => {
+                    let Some(notification) = notification else { return Ok(false) };
+                    let frame = IpcFrame::Notification((&notification).into()).encode();
+                    // keep the notification in case the client disconnects before receiving it
+                    self.unacked.push_back(notification);
+                    writer.send(frame).await?;
+                }
+            }
+        }
+    }
+
+    /// Returns `true` if more notifications can be received from the node.
+    fn has_capacity(&self) -> bool {
+        self.unacked.len() < self.max_unacked
+    }
+
+    /// Removes the acknowledged notifications and forwards the finished height to the node.
+    fn on_finished_height(&mut self, height: BlockNumber) -> eyre::Result<()> {
+        while self.unacked.front().is_some_and(|notification| notification.tip().number <= height) {
+            self.unacked.pop_front();
+        }
+        self.events.send(ExExEvent::FinishedHeight(height))?;
+        Ok(())
+    }
+}
+
+/// Binds a socket in a new directory at `bind_dir` that only the current user can access, restricts
+/// its permissions to that user and moves it to `path`.
+fn bind_private(bind_dir: &Path, path: &Path) -> io::Result<UnixListener> {
+    // a directory left behind by a previous run
+    match std::fs::remove_dir_all(bind_dir) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+    std::fs::DirBuilder::new().mode(0o700).create(bind_dir)?;
+
+    let bind_path = bind_dir.join("socket");
+    let listener = UnixListener::bind(&bind_path)?;
+    std::fs::set_permissions(&bind_path, std::fs::Permissions::from_mode(0o600))?;
+    std::fs::rename(&bind_path, path)?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::SealedBlockWithSenders;
+    use std::{sync::Arc, time::Duration};
+    use tokio::sync::mpsc;
+    use tokio_util::codec::Framed;
+
+    fn commit(number: BlockNumber) -> CanonStateNotification {
+        let mut block = SealedBlockWithSenders::default();
+        block.block.header.set_block_number(number);
+        CanonStateNotification::Commit {
+            new: Arc::new(Chain::new([block], Default::default(), None)),
+        }
+    }
+
+    /// Reads the next frame, or returns `None` if no frame is received in time.
+    async fn next_frame(client: &mut Framed<UnixStream, LengthDelimitedCodec>) -> Option<IpcFrame> {
+        let frame = tokio::time::timeout(Duration::from_millis(500), client.next()).await.ok()?;
+        Some(IpcFrame::decode(&frame.unwrap().unwrap()).unwrap())
+    }
+
+    #[test]
+    fn encode_decode_frames() {
+        for frame in [
+            IpcFrame::Hello { version: IPC_EXEX_PROTOCOL_VERSION },
+            IpcFrame::Notification((&commit(1)).into()),
+            IpcFrame::FinishedHeight(1),
+        ] {
+            let encoded = frame.encode();
+            assert_eq!(IpcFrame::decode(&encoded).unwrap(), frame);
+        }
+
+        assert_eq!(
+            IpcFrame::FinishedHeight(0x0102).encode().as_ref(),
+            [FINISHED_HEIGHT, 0, 0, 0, 0, 0, 0, 0x01, 0x02]
+        );
+        assert!(IpcFrame::decode(&[]).is_err());
+        assert!(IpcFrame::decode(&[0xff]).is_err());
+    }
+
+    #[test]
+    fn notification_wire_format() {
+        let IpcNotification::Commit { new } = (&commit(1)).into() else {
+            panic!("expected a commit")
+        };
+        let [block] = new.blocks.as_slice() else { panic!("expected one block") };
+        assert_eq!(block.number, 1);
+
+        // a list of the notification kind and the chain
+        let notification = IpcNotification::Commit { new: new.clone() };
+        let mut encoded = Vec::new();
+        notification.encode(&mut encoded);
+        let mut buf = encoded.as_slice();
+        let header = Header::decode(&mut buf).unwrap();
+        assert!(header.list);
+        assert_eq!(header.payload_length, buf.len());
+        assert_eq!(u8::decode(&mut buf).unwrap(), COMMIT);
+        assert_eq!(IpcChain::decode(&mut buf).unwrap(), new);
+        assert!(buf.is_empty());
+
+        let mut account = IpcAccountChange {
+            address: Address::with_last_byte(1),
+            info: Some(IpcAccount { nonce: 1, balance: U256::from(2), code_hash: B256::ZERO }),
+            storage_wiped: true,
+            storage: vec![IpcStorageSlot { slot: B256::with_last_byte(3), value: U256::from(4) }],
+        };
+        let mut old = new.clone();
+        old.accounts.push(account.clone());
+        account.info = None;
+        let mut new = new;
+        new.accounts.push(account);
+        let notification = IpcNotification::Reorg { old, new };
+        let frame = IpcFrame::Notification(notification);
+        assert_eq!(IpcFrame::decode(&frame.encode()).unwrap(), frame);
+    }
+
+    #[tokio::test]
+    async fn streams_notifications_over_socket() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join("exex.ipc");
+
+        let (notifications_tx, notifications_rx) = mpsc::channel(1);
+        let (events_tx, mut events_rx) = mpsc::unbounded_channel();
+        let exex =
+            IpcExEx::with_channels(notifications_rx, events_tx, &path).unwrap().with_max_unacked(2);
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        // only the socket is left in the directory
+        assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+        tokio::spawn(exex.run());
+
+        let mut client =
+            Framed::new(UnixStream::connect(&path).await.unwrap(), LengthDelimitedCodec::new());
+        assert_eq!(
+            next_frame(&mut client).await,
+            Some(IpcFrame::Hello { version: IPC_EXEX_PROTOCOL_VERSION })
+        );
+
+        // the notifications are streamed to the client
+        for number in 1..=2 {
+            notifications_tx.send(commit(number)).await.unwrap();
+            assert_eq!(
+                next_frame(&mut client).await,
+                Some(IpcFrame::Notification((&commit(number)).into()))
+            );
+        }
+
+        // two notifications are unacknowledged, so the next one is not received from the node
+        notifications_tx.send(commit(3)).await.unwrap();
+        assert_eq!(next_frame(&mut client).await, None);
+        assert!(notifications_tx.try_send(commit(4)).is_err());
+
+        // the acknowledgement is forwarded to the node and frees capacity
+        client.send(IpcFrame::FinishedHeight(1).encode()).await.unwrap();
+        assert_eq!(events_rx.recv().await, Some(ExExEvent::FinishedHeight(1)));
+        assert_eq!(
+            next_frame(&mut client).await,
+            Some(IpcFrame::Notification((&commit(3)).into()))
+        );
+    }
+}
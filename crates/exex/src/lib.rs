@@ -23,6 +23,36 @@
 //! To clarify: if the ExEx emits `ExExEvent::FinishedHeight(0)` it will receive notifications for
 //! any `block_number > 0`.
 //!
+//! # Delivery
+//!
+//! The node keeps a write-ahead log ([`Wal`]) of the notifications sent to each ExEx, along with
+//! its last finished height. Notifications that the ExEx has not finished processing when the node
+//! stops are sent again on the next start, so a notification is delivered **at least once**.
+//!
+//! # Backfill
+//!
+//! An ExEx that is behind the node, either because it was added to an already synced node or
+//! because the node ran without it, is caught up by re-executing the historical blocks after its
+//! finished height (see [`BackfillJob`]). The finished height is taken from the WAL or, for a new
+//! ExEx, from its first `FinishedHeight` event, which must be emitted before it receives any
+//! notification. The resulting notifications are sent before any new notification.
+//!
+//! If the backfill job of an ExEx fails, or its WAL can not be read or written, only that ExEx
+//! fails: its notification channel is closed, while the other ExEx's keep receiving notifications.
+//!
+//! # Node access
+//!
+//! Besides the notifications, the [`ExExContext`] gives access to the components of the node
+//! (provider, transaction pool, network, payload builder, task executor). An ExEx can also add
+//! its own RPC methods to the node's RPC server with [`ExExContext::register_rpc_module`] while it
+//! is being launched.
+//!
+//! # Out-of-process ExEx's
+//!
+//! The built-in [`IpcExEx`] streams the notifications over a Unix socket to an ExEx running in
+//! another process, and forwards its `FinishedHeight` acknowledgements to the node. The format of
+//! the stream is documented on [`IpcFrame`].
+//!
 //! [`Future`]: std::future::Future
 //! [`ExExContext`]: crate::ExExContext
 //! [`CanonStateNotification`]: reth_provider::CanonStateNotification
@@ -34,11 +64,25 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+mod backfill;
+pub use backfill::*;
+
 mod context;
 pub use context::*;
 
 mod event;
 pub use event::*;
 
+#[cfg(unix)]
+mod ipc;
+#[cfg(unix)]
+pub use ipc::*;
+
 mod manager;
 pub use manager::*;
+
+mod rpc;
+pub use rpc::*;
+
+mod wal;
+pub use wal::*;
@@ -9,19 +9,23 @@ use std::{
     task::{Context, Poll},
 };
 
-use crate::ExExEvent;
-use futures::StreamExt;
+use crate::{BackfillJobHandle, ExExEvent, SpawnBackfillJob, Wal, WalNotifications, WalWrite};
+use eyre::WrapErr;
+use futures::{ready, FutureExt, StreamExt};
 use metrics::Gauge;
 use reth_metrics::{metrics::Counter, Metrics};
 use reth_primitives::BlockNumber;
 use reth_provider::CanonStateNotification;
-use reth_tracing::tracing::debug;
-use tokio::sync::{
-    mpsc::{self, error::SendError, Receiver, UnboundedReceiver, UnboundedSender},
-    watch,
+use reth_tracing::tracing::{debug, error, info, warn};
+use tokio::{
+    sync::{
+        mpsc::{self, error::SendError, Receiver, UnboundedReceiver, UnboundedSender},
+        watch,
+    },
+    task::JoinHandle,
 };
 use tokio_stream::wrappers::WatchStream;
-use tokio_util::sync::{PollSendError, PollSender};
+use tokio_util::sync::PollSender;
 
 /// Metrics for an ExEx.
 #[derive(Metrics)]
@@ -56,6 +60,39 @@ pub struct ExExHandle {
     ///
     /// If this is `None`, the ExEx has not emitted a `FinishedHeight` event.
     finished_height: Option<BlockNumber>,
+
+    /// The write-ahead log of the notifications sent to the ExEx, if any.
+    wal: Option<Wal>,
+    /// The write of the notification that is being delivered to the WAL, if any.
+    ///
+    /// This is `Some(None)` once the write has finished, until the notification is sent.
+    wal_commit: Option<Option<JoinHandle<eyre::Result<()>>>>,
+    /// The running write of the finished height of the ExEx to the WAL, if any.
+    wal_finalize: Option<JoinHandle<eyre::Result<()>>>,
+    /// The notifications in the WAL that are left to replay, until they are read.
+    replay: Option<WalNotifications>,
+    /// The notifications replayed from the WAL, read by a blocking task.
+    replayed: Option<Receiver<eyre::Result<CanonStateNotification>>>,
+    /// Notifications to send to the ExEx before the ones in the manager's buffer.
+    ///
+    /// These are the notifications replayed from the WAL, which are already persisted, and the
+    /// ones produced by a backfill job, which still need to be persisted.
+    pending: VecDeque<(CanonStateNotification, bool)>,
+    /// The running backfill job of the ExEx, if any.
+    backfill: Option<BackfillJobHandle>,
+    /// The last block of the backfill job of the ExEx, if one was started.
+    ///
+    /// Notifications in the manager's buffer up to this block are not sent to the ExEx.
+    backfilled_height: Option<BlockNumber>,
+    /// Whether a backfill job should be started once the finished height of the ExEx is known.
+    ///
+    /// This is unset once the ExEx receives its first notification from the manager's buffer,
+    /// since it has to receive the blocks in order.
+    should_backfill: bool,
+    /// Whether the ExEx has failed.
+    ///
+    /// A failed ExEx does not receive notifications anymore, but its finished height is kept.
+    failed: bool,
 }
 
 impl ExExHandle {
@@ -75,46 +112,238 @@ impl ExExHandle {
                 receiver: event_rx,
                 next_notification_id: 0,
                 finished_height: None,
+                wal: None,
+                wal_commit: None,
+                wal_finalize: None,
+                replay: None,
+                replayed: None,
+                pending: VecDeque::new(),
+                backfill: None,
+                backfilled_height: None,
+                should_backfill: true,
+                failed: false,
             },
             event_tx,
             canon_rx,
         )
     }
 
-    /// Reserves a slot in the `PollSender` channel and sends the notification if the slot was
-    /// successfully reserved.
+    /// Persists the notifications sent to the ExEx in the given [`Wal`].
+    ///
+    /// The notifications that are still in the log are sent to the ExEx again before any other
+    /// notification, and the finished height persisted in the log is restored. The notifications
+    /// are read one at a time by a blocking task once the manager runs.
+    pub fn with_wal(mut self, wal: Wal) -> Self {
+        if !wal.is_empty() {
+            info!(
+                exex.id = self.id,
+                notifications = wal.len(),
+                "replaying notifications from exex wal"
+            );
+            self.replay = Some(wal.notifications());
+        }
+        if let Some(dropped_tip) = wal.dropped_tip() {
+            warn!(
+                exex.id = self.id,
+                dropped_tip,
+                "exex wal dropped unfinished notifications, they are only sent again by a backfill job"
+            );
+        }
+        self.finished_height = wal.finished_height();
+        self.wal = Some(wal);
+        self
+    }
+
+    /// Marks the ExEx as failed and closes its notification channel.
+    ///
+    /// The other ExEx's are not affected.
+    fn fail(&mut self, err: eyre::Report) {
+        error!(exex.id = self.id, %err, "exex failed, no more notifications are sent to it");
+        self.failed = true;
+        self.sender.close();
+        self.replay = None;
+        self.replayed = None;
+        self.pending.clear();
+        self.backfill = None;
+    }
+
+    /// Records the finished height of the ExEx in the WAL, if it has changed.
+    ///
+    /// Only one write of the finished height runs at a time, so that the writes are not
+    /// reordered.
+    fn poll_finalize(&mut self, cx: &mut Context<'_>) -> Poll<eyre::Result<()>> {
+        loop {
+            if let Some(write) = &mut self.wal_finalize {
+                ready!(write.poll_unpin(cx)).wrap_err("exex wal write panicked")??;
+                self.wal_finalize = None;
+            }
+
+            let (Some(wal), Some(finished_height)) = (&mut self.wal, self.finished_height) else {
+                return Poll::Ready(Ok(()))
+            };
+            if wal.finished_height() == Some(finished_height) {
+                return Poll::Ready(Ok(()))
+            }
+            self.wal_finalize = Some(spawn_wal_write(wal.finalize(finished_height)));
+        }
+    }
+
+    /// Reserves a slot in the `PollSender` channel and sends the notification from the manager's
+    /// buffer if the slot was successfully reserved.
     ///
     /// When the notification is sent, it is considered delivered.
     fn send(
         &mut self,
         cx: &mut Context<'_>,
         (event_id, notification): &(usize, CanonStateNotification),
-    ) -> Poll<Result<(), PollSendError<CanonStateNotification>>> {
+    ) -> Poll<eyre::Result<()>> {
         // check that this notification is above the finished height of the exex if the exex has set
-        // one
-        if let Some(finished_height) = self.finished_height {
-            if finished_height >= notification.tip().number {
+        // one, and above the blocks the exex received from a backfill job
+        if let Some(height) = self.finished_height.max(self.backfilled_height) {
+            if height >= notification.tip().number {
                 self.next_notification_id = event_id + 1;
                 return Poll::Ready(Ok(()))
             }
         }
 
-        match self.sender.poll_reserve(cx) {
-            Poll::Ready(Ok(())) => (),
-            other => return other,
+        ready!(self.deliver(cx, notification, true))?;
+        self.next_notification_id = event_id + 1;
+        self.should_backfill = false;
+        Poll::Ready(Ok(()))
+    }
+
+    /// Sends the notifications replayed from the WAL and the ones produced by the backfill job of
+    /// the ExEx.
+    ///
+    /// Returns [`Poll::Ready`] once there are no such notifications left.
+    fn send_pending(&mut self, cx: &mut Context<'_>) -> Poll<eyre::Result<()>> {
+        loop {
+            if let Some((notification, persist)) = self.pending.front().cloned() {
+                ready!(self.deliver(cx, &notification, persist))?;
+                self.pending.pop_front();
+                continue
+            }
+
+            if let Some(replay) = self.replay.take() {
+                self.replayed = Some(spawn_reader(replay));
+            }
+            if let Some(replayed) = &mut self.replayed {
+                match ready!(replayed.poll_recv(cx)) {
+                    Some(notification) => {
+                        let notification = notification
+                            .wrap_err_with(|| format!("replay of exex {} failed", self.id))?;
+                        self.pending.push_back((notification, false))
+                    }
+                    None => self.replayed = None,
+                }
+                continue
+            }
+
+            let Some(backfill) = &mut self.backfill else { return Poll::Ready(Ok(())) };
+            match ready!(backfill.notifications.poll_recv(cx)) {
+                Some(notification) => {
+                    let notification = notification
+                        .wrap_err_with(|| format!("backfill of exex {} failed", self.id))?;
+                    self.pending.push_back((notification, true))
+                }
+                None => {
+                    info!(exex.id = self.id, target = backfill.target, "exex backfill finished");
+                    self.backfill = None
+                }
+            }
+        }
+    }
+
+    /// Starts a backfill job if the ExEx is behind the node.
+    ///
+    /// The job starts after the finished height of the ExEx, or after the last notification in
+    /// the WAL if that is higher, and ends at the current tip of the node. If notifications were
+    /// removed from the full WAL before the ExEx finished them, the WAL is not replayed and the job
+    /// starts after the finished height, so that the removed blocks are executed again.
+    fn start_backfill(&mut self, spawner: &dyn SpawnBackfillJob) -> eyre::Result<()> {
+        if !self.should_backfill {
+            return Ok(())
         }
+        let Some(finished_height) = self.finished_height else { return Ok(()) };
+        self.should_backfill = false;
 
-        match self.sender.send_item(notification.clone()) {
-            Ok(()) => {
-                self.next_notification_id = event_id + 1;
-                self.metrics.notifications_sent_total.increment(1);
-                Poll::Ready(Ok(()))
+        let dropped_tip = self.wal.as_ref().and_then(Wal::dropped_tip);
+        let start = if dropped_tip.is_some() {
+            finished_height + 1
+        } else {
+            finished_height.max(self.wal.as_ref().and_then(Wal::tip).unwrap_or_default()) + 1
+        };
+        let Some(backfill) = spawner.spawn_backfill_job(start)? else { return Ok(()) };
+        info!(exex.id = self.id, start, target = backfill.target, "started exex backfill");
+
+        if let Some(dropped_tip) = dropped_tip {
+            let replay = self.replay.take();
+            let replayed = self.replayed.take();
+            if replay.is_some() || replayed.is_some() {
+                warn!(
+                    exex.id = self.id,
+                    dropped_tip,
+                    "exex wal dropped unfinished notifications, backfilling instead of replaying it"
+                );
+            }
+            self.pending.retain(|(_, persist)| *persist);
+        }
+
+        self.backfilled_height = Some(backfill.target);
+        self.backfill = Some(backfill);
+        Ok(())
+    }
+
+    /// Reserves a slot in the `PollSender` channel and sends the notification if the slot was
+    /// successfully reserved, persisting it in the WAL first if requested.
+    ///
+    /// The notification is persisted by a blocking task, and only sent once it is written.
+    fn deliver(
+        &mut self,
+        cx: &mut Context<'_>,
+        notification: &CanonStateNotification,
+        persist: bool,
+    ) -> Poll<eyre::Result<()>> {
+        if persist {
+            if let Some(wal) = &mut self.wal {
+                let write = self
+                    .wal_commit
+                    .get_or_insert_with(|| Some(spawn_wal_write(wal.commit(notification))));
+                if let Some(handle) = write {
+                    ready!(handle.poll_unpin(cx)).wrap_err("exex wal write panicked")??;
+                    *write = None;
+                }
             }
-            Err(err) => Poll::Ready(Err(err)),
         }
+
+        ready!(self.sender.poll_reserve(cx))?;
+        self.sender.send_item(notification.clone())?;
+        self.wal_commit = None;
+        self.metrics.notifications_sent_total.increment(1);
+        Poll::Ready(Ok(()))
     }
 }
 
+/// Runs the write of a [`Wal`] as a blocking task.
+fn spawn_wal_write(write: WalWrite) -> JoinHandle<eyre::Result<()>> {
+    tokio::task::spawn_blocking(move || write.run())
+}
+
+/// Reads the notifications of a [`Wal`] one at a time in a blocking task.
+fn spawn_reader(notifications: WalNotifications) -> Receiver<eyre::Result<CanonStateNotification>> {
+    let (tx, rx) = mpsc::channel(1);
+    tokio::task::spawn_blocking(move || {
+        for notification in notifications {
+            let failed = notification.is_err();
+            // stop if the receiver was dropped
+            if tx.blocking_send(notification).is_err() || failed {
+                break
+            }
+        }
+    });
+    rx
+}
+
 /// Metrics for the ExEx manager.
 #[derive(Metrics)]
 #[metrics(scope = "exex_manager")]
@@ -177,6 +406,9 @@ pub struct ExExManager {
     /// The number is inclusive, i.e. all blocks `<= finished_height` are safe to prune.
     finished_height: watch::Sender<Option<BlockNumber>>,
 
+    /// Spawns backfill jobs for the ExEx's that are behind the node, if set.
+    backfill: Option<Box<dyn SpawnBackfillJob>>,
+
     /// A handle to the ExEx manager.
     handle: ExExManagerHandle,
     /// Metrics for the ExEx manager.
@@ -218,6 +450,8 @@ impl ExExManager {
             is_ready: is_ready_tx,
             finished_height: finished_height_tx,
 
+            backfill: None,
+
             handle: ExExManagerHandle {
                 exex_tx: handle_tx,
                 num_exexs,
@@ -230,6 +464,17 @@ impl ExExManager {
         }
     }
 
+    /// Sets the spawner of the backfill jobs.
+    ///
+    /// Once the finished height of an ExEx is known, either from its first `FinishedHeight` event
+    /// or from its WAL, and it has not received any notification from the manager yet, the blocks
+    /// after the finished height are re-executed up to the tip of the node and sent to the ExEx
+    /// before any new notification.
+    pub fn with_backfill(mut self, backfill: impl SpawnBackfillJob) -> Self {
+        self.backfill = Some(Box::new(backfill));
+        self
+    }
+
     /// Returns the handle to the manager.
     pub fn handle(&self) -> ExExManagerHandle {
         self.handle.clone()
@@ -274,10 +519,25 @@ impl Future for ExExManager {
         // update capacity
         self.update_capacity();
 
+        // start backfill jobs for the exex's that are behind the node, before they receive any
+        // notification from the buffer
+        if let Some(backfill) = self.backfill.take() {
+            for exex in self.exex_handles.iter_mut() {
+                if let Err(err) = exex.start_backfill(backfill.as_ref()) {
+                    warn!(exex.id, %err, "failed to start exex backfill");
+                }
+            }
+            self.backfill = Some(backfill);
+        }
+
         // advance all poll senders
         let mut min_id = usize::MAX;
         for idx in (0..self.exex_handles.len()).rev() {
             let mut exex = self.exex_handles.swap_remove(idx);
+            if exex.failed {
+                self.exex_handles.push(exex);
+                continue
+            }
 
             // it is a logic error for this to ever underflow since the manager manages the
             // notification IDs
@@ -285,14 +545,26 @@ impl Future for ExExManager {
                 .next_notification_id
                 .checked_sub(self.min_id)
                 .expect("exex expected notification ID outside the manager's range");
-            if let Some(notification) = self.buffer.get(notification_id) {
-                debug!(exex.id, notification_id, "sent notification to exex");
-                if let Poll::Ready(Err(err)) = exex.send(cx, notification) {
-                    // the channel was closed, which is irrecoverable for the manager
-                    return Poll::Ready(Err(err.into()))
-                }
+
+            // notifications replayed from the wal or produced by a backfill job come first
+            let result = match exex.send_pending(cx) {
+                Poll::Ready(Ok(())) => match self.buffer.get(notification_id) {
+                    Some(notification) => {
+                        debug!(exex.id, notification_id, "sent notification to exex");
+                        exex.send(cx, notification)
+                    }
+                    None => Poll::Ready(Ok(())),
+                },
+                result => result,
+            };
+
+            // the channel was closed, the wal could not be read or written, or the backfill job
+            // failed, which is irrecoverable for the exex but not for the others
+            if let Poll::Ready(Err(err)) = result {
+                exex.fail(err);
+            } else {
+                min_id = min_id.min(exex.next_notification_id);
             }
-            min_id = min_id.min(exex.next_notification_id);
             self.exex_handles.push(exex);
         }
 
@@ -305,16 +577,31 @@ impl Future for ExExManager {
         self.update_capacity();
 
         // handle incoming exex events
+        let has_backfill = self.backfill.is_some();
         for exex in self.exex_handles.iter_mut() {
             while let Poll::Ready(Some(event)) = exex.receiver.poll_recv(cx) {
                 debug!(?event, id = exex.id, "received event from exex");
                 exex.metrics.events_sent_total.increment(1);
                 match event {
-                    ExExEvent::FinishedHeight(height) => exex.finished_height = Some(height),
+                    ExExEvent::FinishedHeight(height) => {
+                        exex.finished_height = Some(height);
+
+                        // poll again to start a backfill job for the exex if it is behind
+                        if has_backfill && exex.should_backfill {
+                            cx.waker().wake_by_ref()
+                        }
+                    }
                 }
             }
         }
 
+        // persist the finished heights
+        for exex in self.exex_handles.iter_mut().filter(|exex| !exex.failed) {
+            if let Poll::Ready(Err(err)) = exex.poll_finalize(cx) {
+                exex.fail(err);
+            }
+        }
+
         // update watch channel block number
         let finished_height = self.exex_handles.iter_mut().try_fold(u64::MAX, |curr, exex| {
             let height = match exex.finished_height {
@@ -452,18 +739,290 @@ impl Clone for ExExManagerHandle {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use reth_primitives::SealedBlockWithSenders;
+    use reth_provider::Chain;
+    use std::time::Duration;
+
+    fn commit(number: BlockNumber) -> CanonStateNotification {
+        let mut block = SealedBlockWithSenders::default();
+        block.block.header.set_block_number(number);
+        CanonStateNotification::Commit {
+            new: Arc::new(Chain::new([block], Default::default(), None)),
+        }
+    }
+
+    /// Polls the manager once.
+    fn poll(manager: &mut ExExManager) {
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+        assert!(manager.poll_unpin(&mut cx).is_pending());
+    }
+
+    /// A backfill job spawner that yields the given notifications.
+    #[derive(Debug)]
+    struct TestBackfill {
+        target: BlockNumber,
+        notifications: Mutex<Option<Vec<eyre::Result<CanonStateNotification>>>>,
+        start: Mutex<Option<BlockNumber>>,
+    }
+
+    impl TestBackfill {
+        fn new(
+            target: BlockNumber,
+            notifications: Vec<eyre::Result<CanonStateNotification>>,
+        ) -> Arc<Self> {
+            Arc::new(Self {
+                target,
+                notifications: Mutex::new(Some(notifications)),
+                start: Mutex::new(None),
+            })
+        }
+    }
+
+    impl SpawnBackfillJob for Arc<TestBackfill> {
+        fn spawn_backfill_job(
+            &self,
+            start: BlockNumber,
+        ) -> eyre::Result<Option<BackfillJobHandle>> {
+            *self.start.lock() = Some(start);
+
+            let notifications = self.notifications.lock().take().unwrap_or_default();
+            let (tx, rx) = mpsc::channel(notifications.len().max(1));
+            for notification in notifications {
+                tx.try_send(notification).unwrap();
+            }
+            Ok(Some(BackfillJobHandle { target: self.target, notifications: rx }))
+        }
+    }
+
+    #[tokio::test]
+    async fn replays_wal() {
+        let directory = tempfile::tempdir().unwrap();
+
+        // notifications 1 and 2 were sent before the restart, but not finished
+        let mut wal = Wal::new(directory.path()).unwrap();
+        wal.commit(&commit(1)).run().unwrap();
+        wal.commit(&commit(2)).run().unwrap();
+
+        let (handle, _events, mut notifications) = ExExHandle::new("test".to_string());
+        let manager = ExExManager::new(vec![handle.with_wal(wal)], 8);
+        let manager_handle = manager.handle();
+        tokio::spawn(manager);
+
+        manager_handle.send(commit(3)).unwrap();
+
+        // the replayed notifications come before the new one
+        for number in 1..=3 {
+            let notification = tokio::time::timeout(Duration::from_secs(5), notifications.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(notification, commit(number));
+        }
+
+        // the new notification is persisted before it is sent, the replayed ones are not
+        // persisted again
+        let wal = Wal::new(directory.path()).unwrap();
+        assert_eq!(
+            wal.notifications().collect::<eyre::Result<Vec<_>>>().unwrap(),
+            vec![commit(1), commit(2), commit(3)]
+        );
+    }
+
+    #[tokio::test]
+    async fn backfills_before_new_notifications() {
+        let backfill = TestBackfill::new(2, vec![Ok(commit(1)), Ok(commit(2))]);
+
+        let (handle, events, mut notifications) = ExExHandle::new("test".to_string());
+        let mut manager = ExExManager::new(vec![handle], 8).with_backfill(backfill.clone());
+        let manager_handle = manager.handle();
+
+        // the exex has finished the genesis block
+        events.send(ExExEvent::FinishedHeight(0)).unwrap();
+        poll(&mut manager);
+
+        // block 2 is also produced by the backfill job, so only block 3 is sent from the buffer
+        manager_handle.send(commit(2)).unwrap();
+        manager_handle.send(commit(3)).unwrap();
+
+        let mut received = Vec::new();
+        for _ in 0..8 {
+            poll(&mut manager);
+            while let Ok(notification) = notifications.try_recv() {
+                received.push(notification);
+            }
+        }
+
+        assert_eq!(*backfill.start.lock(), Some(1));
+        assert_eq!(received, vec![commit(1), commit(2), commit(3)]);
+    }
+
     #[tokio::test]
-    async fn delivers_events() {}
+    async fn backfill_failure_fails_only_its_exex() {
+        let backfill = TestBackfill::new(2, vec![Ok(commit(1)), Err(eyre::eyre!("failed"))]);
+
+        let (failing, failing_events, mut failing_notifications) =
+            ExExHandle::new("failing".to_string());
+        let (healthy, _healthy_events, mut healthy_notifications) =
+            ExExHandle::new("healthy".to_string());
+        let mut manager =
+            ExExManager::new(vec![failing, healthy], 8).with_backfill(backfill.clone());
+        let manager_handle = manager.handle();
+
+        failing_events.send(ExExEvent::FinishedHeight(0)).unwrap();
+        poll(&mut manager);
+
+        manager_handle.send(commit(3)).unwrap();
+        for _ in 0..8 {
+            poll(&mut manager);
+        }
+
+        // the failing exex received the first backfilled notification before its channel was
+        // closed
+        assert_eq!(failing_notifications.try_recv().unwrap(), commit(1));
+        assert_eq!(failing_notifications.try_recv(), Err(mpsc::error::TryRecvError::Disconnected));
+
+        // the other exex still receives notifications
+        assert_eq!(healthy_notifications.try_recv().unwrap(), commit(3));
+    }
+
+    #[tokio::test]
+    async fn backfills_after_dropped_wal_notifications() {
+        let directory = tempfile::tempdir().unwrap();
+
+        // the exex finished the genesis block, but block 1 was removed from the full wal before
+        // the exex finished it
+        let mut wal = Wal::new(directory.path()).unwrap().with_max_len(1);
+        wal.finalize(0).run().unwrap();
+        wal.commit(&commit(1)).run().unwrap();
+        wal.commit(&commit(2)).run().unwrap();
+        assert_eq!(wal.dropped_tip(), Some(1));
+
+        let backfill = TestBackfill::new(2, vec![Ok(commit(1)), Ok(commit(2))]);
+        let (handle, _events, mut notifications) = ExExHandle::new("test".to_string());
+        let manager =
+            ExExManager::new(vec![handle.with_wal(wal)], 8).with_backfill(backfill.clone());
+        tokio::spawn(manager);
+
+        // the blocks after the finished height are backfilled instead of replaying the wal
+        for number in 1..=2 {
+            let notification = tokio::time::timeout(Duration::from_secs(5), notifications.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(notification, commit(number));
+        }
+        assert_eq!(*backfill.start.lock(), Some(1));
+    }
+
+    #[tokio::test]
+    async fn delivers_events() {
+        let (handle, _events, mut notifications) = ExExHandle::new("test".to_string());
+        let mut manager = ExExManager::new(vec![handle], 8);
+        let manager_handle = manager.handle();
+
+        manager_handle.send(commit(1)).unwrap();
+        manager_handle.send(commit(2)).unwrap();
+
+        poll(&mut manager);
+        assert_eq!(notifications.try_recv().unwrap(), commit(1));
+        poll(&mut manager);
+        assert_eq!(notifications.try_recv().unwrap(), commit(2));
+        assert!(notifications.try_recv().is_err());
+    }
 
     #[tokio::test]
-    async fn capacity() {}
+    async fn capacity() {
+        let (handle, _events, mut notifications) = ExExHandle::new("test".to_string());
+        let mut manager = ExExManager::new(vec![handle], 2);
+        let manager_handle = manager.handle();
+        assert_eq!(manager_handle.capacity(), 2);
+
+        for number in 1..=3 {
+            manager_handle.send(commit(number)).unwrap();
+        }
+
+        // notification 1 is sent, notification 2 is buffered
+        poll(&mut manager);
+        assert_eq!(manager_handle.capacity(), 1);
+
+        // the exex does not receive, so notifications 2 and 3 are buffered
+        poll(&mut manager);
+        assert_eq!(manager_handle.capacity(), 0);
+        assert!(!manager_handle.has_capacity());
+
+        assert_eq!(notifications.try_recv().unwrap(), commit(1));
+        poll(&mut manager);
+        assert_eq!(manager_handle.capacity(), 1);
+    }
 
     #[tokio::test]
-    async fn updates_block_height() {}
+    async fn updates_block_height() {
+        let (first, first_events, _first_notifications) = ExExHandle::new("first".to_string());
+        let (second, second_events, _second_notifications) = ExExHandle::new("second".to_string());
+        let mut manager = ExExManager::new(vec![first, second], 8);
+        let mut manager_handle = manager.handle();
+
+        // the finished height is only known once all exex's have emitted one
+        first_events.send(ExExEvent::FinishedHeight(5)).unwrap();
+        poll(&mut manager);
+        assert_eq!(manager_handle.finished_height(), None);
+
+        second_events.send(ExExEvent::FinishedHeight(3)).unwrap();
+        poll(&mut manager);
+        assert_eq!(manager_handle.finished_height(), Some(3));
+
+        second_events.send(ExExEvent::FinishedHeight(7)).unwrap();
+        poll(&mut manager);
+        assert_eq!(manager_handle.finished_height(), Some(5));
+    }
 
     #[tokio::test]
-    async fn slow_exex() {}
+    async fn slow_exex() {
+        let (fast, _fast_events, mut fast_notifications) = ExExHandle::new("fast".to_string());
+        let (slow, _slow_events, mut slow_notifications) = ExExHandle::new("slow".to_string());
+        let mut manager = ExExManager::new(vec![fast, slow], 4);
+        let manager_handle = manager.handle();
+
+        for number in 1..=3 {
+            manager_handle.send(commit(number)).unwrap();
+        }
+
+        let mut received = Vec::new();
+        for _ in 0..4 {
+            poll(&mut manager);
+            while let Ok(notification) = fast_notifications.try_recv() {
+                received.push(notification);
+            }
+        }
+
+        // the fast exex is not held back by the slow one, which only received the first
+        // notification, while the others are kept in the buffer for it
+        assert_eq!(received, vec![commit(1), commit(2), commit(3)]);
+        assert_eq!(manager_handle.capacity(), 2);
+
+        assert_eq!(slow_notifications.try_recv().unwrap(), commit(1));
+        assert!(slow_notifications.try_recv().is_err());
+    }
 
     #[tokio::test]
-    async fn is_ready() {}
+    async fn is_ready() {
+        let (handle, _events, mut notifications) = ExExHandle::new("test".to_string());
+        let mut manager = ExExManager::new(vec![handle], 1);
+        let mut manager_handle = manager.handle();
+        let mut cx = Context::from_waker(futures::task::noop_waker_ref());
+
+        // notification 1 is sent, notification 2 fills the buffer
+        manager_handle.send(commit(1)).unwrap();
+        manager_handle.send(commit(2)).unwrap();
+        poll(&mut manager);
+        poll(&mut manager);
+        assert!(manager_handle.poll_ready(&mut cx).is_pending());
+
+        // the buffer is drained once the exex receives
+        assert_eq!(notifications.try_recv().unwrap(), commit(1));
+        poll(&mut manager);
+        assert!(manager_handle.poll_ready(&mut cx).is_ready());
+    }
 }
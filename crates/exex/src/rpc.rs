@@ -0,0 +1,46 @@
+use std::sync::Arc;
+
+use jsonrpsee::Methods;
+use parking_lot::Mutex;
+
+/// The RPC modules registered by the ExEx's of the node.
+///
+/// The ExEx's are launched before the RPC server, so the modules are collected here and merged
+/// into the configured transports once the server is launched. Registering a module after that
+/// point is an error.
+#[derive(Debug, Clone)]
+pub struct ExExRpcModules {
+    /// The registered methods, or `None` once they have been taken by the RPC server.
+    methods: Arc<Mutex<Option<Methods>>>,
+}
+
+impl ExExRpcModules {
+    /// Create a new, empty set of modules.
+    pub fn new() -> Self {
+        Self { methods: Arc::new(Mutex::new(Some(Methods::new()))) }
+    }
+
+    /// Registers the methods of the given module.
+    ///
+    /// Returns an error if the RPC server has already been launched, or if a method with the same
+    /// name has already been registered.
+    pub fn register(&self, module: impl Into<Methods>) -> eyre::Result<()> {
+        let mut methods = self.methods.lock();
+        let methods =
+            methods.as_mut().ok_or_else(|| eyre::eyre!("rpc server has already been launched"))?;
+        methods.merge(module).map_err(|err| eyre::eyre!("failed to register rpc module: {err}"))
+    }
+
+    /// Takes the registered methods, after which no more modules can be registered.
+    ///
+    /// Returns `None` if the methods have already been taken.
+    pub fn take(&self) -> Option<Methods> {
+        self.methods.lock().take()
+    }
+}
+
+impl Default for ExExRpcModules {
+    fn default() -> Self {
+        Self::new()
+    }
+}
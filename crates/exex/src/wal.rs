@@ -0,0 +1,531 @@
+use std::{
+    borrow::Cow,
+    collections::VecDeque,
+    fs::File,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use eyre::WrapErr;
+use reth_primitives::{
+    fs::{self, FsPathError},
+    BlockNumber,
+};
+use reth_provider::{CanonStateNotification, Chain};
+use reth_tracing::tracing::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+/// The file in the WAL directory that stores the finished height of the ExEx.
+const FINISHED_HEIGHT_FILE: &str = "finished_height";
+
+/// The file in the WAL directory that stores the highest tip of the notifications that were
+/// removed from the full log before the ExEx finished them.
+const DROPPED_TIP_FILE: &str = "dropped_tip";
+
+/// The extension of the files in the WAL directory that store the notifications.
+const NOTIFICATION_EXTENSION: &str = "wal";
+
+/// The version of the encoding of the notification files.
+const NOTIFICATION_VERSION: u8 = 1;
+
+/// The length of the header of a notification file: the version and the tip block number.
+const NOTIFICATION_HEADER_LEN: usize = 1 + 8;
+
+/// The default maximum number of notifications in the log.
+const DEFAULT_MAX_LEN: usize = 1024;
+
+/// A write-ahead log (WAL) of the [`CanonStateNotification`]s sent to an ExEx.
+///
+/// Every notification is persisted before it is sent to the ExEx, and removed once the ExEx has
+/// emitted a `FinishedHeight` event at or above its tip. The notifications that are still in the
+/// log when the node restarts are sent to the ExEx again, which makes the delivery at-least-once.
+///
+/// The log holds at most [`Wal::with_max_len`] notifications. If an ExEx does not emit
+/// `FinishedHeight` events, or falls too far behind, the oldest notifications are removed from the
+/// log before the ExEx finished them, and the log records the highest tip of the removed ones
+/// ([`Wal::dropped_tip`]). Replaying the log would skip the removed blocks, so after a restart the
+/// manager backfills the blocks after the finished height instead, see
+/// [`ExExManager::with_backfill`](crate::ExExManager::with_backfill).
+///
+/// The log also persists the last finished height of the ExEx, so that the node knows which
+/// blocks the ExEx has processed before it emits its first event after a restart.
+///
+/// The log only keeps its index in memory. All file system operations are returned as a
+/// [`WalWrite`] by [`Wal::commit`] and [`Wal::finalize`], and the notifications are read lazily
+/// by [`Wal::notifications`], so that they can be run off the async runtime.
+///
+/// # Layout
+///
+/// Each notification is stored in a file named after its sequential ID, e.g. `0.wal`, in the
+/// directory of the log. A file starts with a header of the version of the encoding (`u8`) and
+/// the tip block number of the notification (big-endian `u64`), followed by the notification
+/// encoded with `bincode`. The finished height and the dropped tip are stored in the
+/// `finished_height` and `dropped_tip` files as big-endian `u64`s.
+///
+/// Files are written to a temporary file first, synced to disk and then renamed, after which the
+/// directory is synced as well, so that a crash never leaves a partially written file behind.
+#[derive(Debug)]
+pub struct Wal {
+    /// The directory of the log.
+    directory: PathBuf,
+    /// The ID and the tip block number of every notification in the log, in order.
+    entries: VecDeque<(u64, BlockNumber)>,
+    /// The ID of the next notification.
+    next_id: u64,
+    /// The last finished height of the ExEx.
+    finished_height: Option<BlockNumber>,
+    /// The highest tip of the notifications removed from the full log before the ExEx finished
+    /// them, until the ExEx finishes it.
+    dropped_tip: Option<BlockNumber>,
+    /// The maximum number of notifications in the log.
+    max_len: usize,
+}
+
+impl Wal {
+    /// Opens the log in the given directory, creating the directory if it does not exist.
+    ///
+    /// Only the headers of the notification files are read.
+    pub fn new(directory: impl Into<PathBuf>) -> eyre::Result<Self> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory)?;
+
+        let finished_height = read_height(&directory.join(FINISHED_HEIGHT_FILE))?;
+        let dropped_tip = read_height(&directory.join(DROPPED_TIP_FILE))?;
+
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(&directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(NOTIFICATION_EXTENSION) {
+                continue
+            }
+            let id = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .and_then(|stem| stem.parse::<u64>().ok())
+                .ok_or_else(|| eyre::eyre!("invalid WAL file name {}", path.display()))?;
+            ids.push(id);
+        }
+        ids.sort_unstable();
+
+        let mut entries = VecDeque::with_capacity(ids.len());
+        for id in ids {
+            let tip = read_tip(&notification_path(&directory, id))?;
+            entries.push_back((id, tip));
+        }
+        let next_id = entries.back().map_or(0, |(id, _)| id + 1);
+
+        let mut wal = Self {
+            directory,
+            entries,
+            next_id,
+            finished_height,
+            dropped_tip,
+            max_len: DEFAULT_MAX_LEN,
+        };
+        if let Some(finished_height) = finished_height {
+            // the node might have crashed before removing the finished notifications
+            wal.finalize(finished_height).run()?;
+        }
+        debug!(directory = %wal.directory.display(), entries = wal.entries.len(), ?finished_height, ?dropped_tip, "opened exex wal");
+
+        Ok(wal)
+    }
+
+    /// Sets the maximum number of notifications in the log, `1024` by default.
+    ///
+    /// Once the log is full, committing a notification removes the oldest one.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len.max(1);
+        self
+    }
+
+    /// Returns the last finished height of the ExEx, if it has emitted one.
+    pub fn finished_height(&self) -> Option<BlockNumber> {
+        self.finished_height
+    }
+
+    /// Returns the highest tip of the notifications that were removed from the full log before the
+    /// ExEx finished them, if the ExEx has not finished it yet.
+    ///
+    /// If this is set, the log does not contain all notifications after the finished height.
+    pub fn dropped_tip(&self) -> Option<BlockNumber> {
+        self.dropped_tip
+    }
+
+    /// Returns the tip block number of the last notification in the log.
+    pub fn tip(&self) -> Option<BlockNumber> {
+        self.entries.back().map(|(_, tip)| *tip)
+    }
+
+    /// Returns the number of notifications in the log.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if there are no notifications in the log.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Appends a notification to the log.
+    ///
+    /// Returns the write of the notification, which also removes the oldest notifications if the
+    /// log is full.
+    pub fn commit(&mut self, notification: &CanonStateNotification) -> WalWrite {
+        let id = self.next_id;
+        self.entries.push_back((id, notification.tip().number));
+        self.next_id += 1;
+
+        let mut removed = Vec::new();
+        let mut dropped_tip = None;
+        while self.entries.len() > self.max_len {
+            let Some((id, tip)) = self.entries.pop_front() else { break };
+            warn!(directory = %self.directory.display(), tip, "exex wal is full, removing the oldest notification");
+            removed.push(id);
+
+            if self.finished_height.map_or(true, |finished_height| tip > finished_height) {
+                let tip = self.dropped_tip.map_or(tip, |dropped_tip| dropped_tip.max(tip));
+                self.dropped_tip = Some(tip);
+                dropped_tip = Some(Some(tip));
+            }
+        }
+
+        WalWrite {
+            directory: self.directory.clone(),
+            notification: Some((id, notification.clone())),
+            finished_height: None,
+            dropped_tip,
+            removed,
+        }
+    }
+
+    /// Records the finished height of the ExEx and removes the notifications it has finished
+    /// processing from the front of the log.
+    ///
+    /// Returns the write of the finished height and the removal of the notifications.
+    pub fn finalize(&mut self, finished_height: BlockNumber) -> WalWrite {
+        let mut write = WalWrite {
+            directory: self.directory.clone(),
+            notification: None,
+            finished_height: None,
+            dropped_tip: None,
+            removed: Vec::new(),
+        };
+
+        if self.finished_height != Some(finished_height) {
+            write.finished_height = Some(finished_height);
+            self.finished_height = Some(finished_height);
+        }
+
+        if self.dropped_tip.is_some_and(|dropped_tip| dropped_tip <= finished_height) {
+            write.dropped_tip = Some(None);
+            self.dropped_tip = None;
+        }
+
+        while let Some(&(id, tip)) = self.entries.front() {
+            if tip > finished_height {
+                break
+            }
+            write.removed.push(id);
+            self.entries.pop_front();
+        }
+        write
+    }
+
+    /// Returns an iterator that reads the notifications in the log, in order.
+    ///
+    /// The iterator owns the paths of the notifications, so that it can be moved to a blocking
+    /// task.
+    pub fn notifications(&self) -> WalNotifications {
+        WalNotifications {
+            paths: self
+                .entries
+                .iter()
+                .map(|(id, _)| notification_path(&self.directory, *id))
+                .collect(),
+        }
+    }
+}
+
+/// A pending write of a [`Wal`], returned by [`Wal::commit`] and [`Wal::finalize`].
+///
+/// The write does blocking file system operations and syncs them to disk, so it should be run off
+/// the async runtime, e.g. with [`tokio::task::spawn_blocking`].
+#[derive(Debug)]
+#[must_use = "the WAL is not written unless the write is run"]
+pub struct WalWrite {
+    /// The directory of the log.
+    directory: PathBuf,
+    /// The notification to write, along with its ID.
+    notification: Option<(u64, CanonStateNotification)>,
+    /// The finished height to write.
+    finished_height: Option<BlockNumber>,
+    /// The dropped tip to write, or `Some(None)` to remove it.
+    dropped_tip: Option<Option<BlockNumber>>,
+    /// The IDs of the notifications to remove.
+    removed: Vec<u64>,
+}
+
+impl WalWrite {
+    /// Runs the write.
+    pub fn run(self) -> eyre::Result<()> {
+        if let Some((id, notification)) = &self.notification {
+            write_atomic(
+                &self.directory,
+                &notification_path(&self.directory, *id),
+                &encode_notification(notification)?,
+            )?;
+        }
+
+        if let Some(finished_height) = self.finished_height {
+            write_atomic(
+                &self.directory,
+                &self.directory.join(FINISHED_HEIGHT_FILE),
+                &finished_height.to_be_bytes(),
+            )?;
+        }
+
+        // the dropped tip is written before the notifications are removed, so that a crash never
+        // leaves a log with removed notifications that are not accounted for
+        match self.dropped_tip {
+            Some(Some(dropped_tip)) => write_atomic(
+                &self.directory,
+                &self.directory.join(DROPPED_TIP_FILE),
+                &dropped_tip.to_be_bytes(),
+            )?,
+            Some(None) => remove_file(&self.directory.join(DROPPED_TIP_FILE))?,
+            None => {}
+        }
+
+        // the notification might not have been written yet when it was removed from a full log
+        for id in self.removed {
+            remove_file(&notification_path(&self.directory, id))?;
+        }
+        Ok(())
+    }
+}
+
+/// An iterator over the notifications of a [`Wal`] that reads them lazily.
+///
+/// Returned by [`Wal::notifications`].
+#[derive(Debug)]
+pub struct WalNotifications {
+    /// The paths of the notifications that are left to read.
+    paths: VecDeque<PathBuf>,
+}
+
+impl Iterator for WalNotifications {
+    type Item = eyre::Result<CanonStateNotification>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.paths.pop_front().map(|path| read_notification(&path))
+    }
+}
+
+/// The serialized form of a [`CanonStateNotification`].
+#[derive(Serialize, Deserialize)]
+enum StoredNotification<'a> {
+    Commit { new: Cow<'a, Chain> },
+    Reorg { old: Cow<'a, Chain>, new: Cow<'a, Chain> },
+}
+
+impl<'a> From<&'a CanonStateNotification> for StoredNotification<'a> {
+    fn from(notification: &'a CanonStateNotification) -> Self {
+        match notification {
+            CanonStateNotification::Commit { new } => {
+                Self::Commit { new: Cow::Borrowed(new.as_ref()) }
+            }
+            CanonStateNotification::Reorg { old, new } => {
+                Self::Reorg { old: Cow::Borrowed(old.as_ref()), new: Cow::Borrowed(new.as_ref()) }
+            }
+        }
+    }
+}
+
+impl From<StoredNotification<'_>> for CanonStateNotification {
+    fn from(notification: StoredNotification<'_>) -> Self {
+        match notification {
+            StoredNotification::Commit { new } => Self::Commit { new: Arc::new(new.into_owned()) },
+            StoredNotification::Reorg { old, new } => {
+                Self::Reorg { old: Arc::new(old.into_owned()), new: Arc::new(new.into_owned()) }
+            }
+        }
+    }
+}
+
+fn notification_path(directory: &Path, id: u64) -> PathBuf {
+    directory.join(format!("{id}.{NOTIFICATION_EXTENSION}"))
+}
+
+/// Reads a big-endian block number from the file, `None` if the file does not exist.
+fn read_height(path: &Path) -> eyre::Result<Option<BlockNumber>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(BlockNumber::from_be_bytes(
+            bytes.try_into().map_err(|_| eyre::eyre!("invalid WAL file {}", path.display()))?,
+        ))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(FsPathError::read(err, path).into()),
+    }
+}
+
+/// Removes the file, ignoring files that do not exist.
+fn remove_file(path: &Path) -> eyre::Result<()> {
+    match std::fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(FsPathError::remove_file(err, path).into()),
+    }
+}
+
+/// Encodes the notification with the header of a notification file.
+fn encode_notification(notification: &CanonStateNotification) -> eyre::Result<Vec<u8>> {
+    let mut buf = Vec::with_capacity(NOTIFICATION_HEADER_LEN);
+    buf.push(NOTIFICATION_VERSION);
+    buf.extend_from_slice(&notification.tip().number.to_be_bytes());
+    bincode::serialize_into(&mut buf, &StoredNotification::from(notification))?;
+    Ok(buf)
+}
+
+/// Decodes the header of a notification file, returning the tip block number and the encoded
+/// notification.
+fn decode_header<'a>(bytes: &'a [u8], path: &Path) -> eyre::Result<(BlockNumber, &'a [u8])> {
+    let (&version, rest) =
+        bytes.split_first().ok_or_else(|| eyre::eyre!("empty WAL file {}", path.display()))?;
+    if version != NOTIFICATION_VERSION {
+        eyre::bail!("unsupported version {version} of WAL file {}", path.display())
+    }
+
+    let tip = rest
+        .get(..8)
+        .and_then(|tip| tip.try_into().ok())
+        .map(BlockNumber::from_be_bytes)
+        .ok_or_else(|| eyre::eyre!("truncated WAL file {}", path.display()))?;
+    Ok((tip, rest.get(8..).unwrap_or_default()))
+}
+
+/// Reads the tip block number from the header of a notification file.
+fn read_tip(path: &Path) -> eyre::Result<BlockNumber> {
+    let mut header = [0; NOTIFICATION_HEADER_LEN];
+    File::open(path)
+        .map_err(|err| FsPathError::open(err, path))?
+        .read_exact(&mut header)
+        .map_err(|err| FsPathError::read(err, path))?;
+    Ok(decode_header(&header, path)?.0)
+}
+
+fn read_notification(path: &Path) -> eyre::Result<CanonStateNotification> {
+    let bytes = fs::read(path)?;
+    let (_, encoded) = decode_header(&bytes, path)?;
+    let notification = bincode::deserialize::<StoredNotification<'_>>(encoded)
+        .wrap_err_with(|| format!("failed to decode WAL file {}", path.display()))?;
+    Ok(notification.into())
+}
+
+/// Writes the file by writing to a temporary file first, syncing it and renaming it, and then
+/// syncing the directory so that the rename is persisted.
+fn write_atomic(directory: &Path, path: &Path, contents: &[u8]) -> eyre::Result<()> {
+    let tmp_path = path.with_extension("tmp");
+    let mut file =
+        File::create(&tmp_path).map_err(|err| FsPathError::create_file(err, &tmp_path))?;
+    file.write_all(contents).map_err(|err| FsPathError::write(err, &tmp_path))?;
+    file.sync_all().map_err(|err| FsPathError::write(err, &tmp_path))?;
+    fs::rename(&tmp_path, path)?;
+
+    File::open(directory)
+        .and_then(|directory| directory.sync_all())
+        .map_err(|err| FsPathError::write(err, directory))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::SealedBlockWithSenders;
+
+    fn commit(number: BlockNumber) -> CanonStateNotification {
+        let mut block = SealedBlockWithSenders::default();
+        block.block.header.set_block_number(number);
+        CanonStateNotification::Commit {
+            new: Arc::new(Chain::new([block], Default::default(), None)),
+        }
+    }
+
+    #[test]
+    fn commit_finalize_and_reopen() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let mut wal = Wal::new(directory.path()).unwrap();
+        assert!(wal.is_empty());
+        assert_eq!(wal.finished_height(), None);
+
+        let notifications = (1..=3).map(commit).collect::<Vec<_>>();
+        for notification in &notifications {
+            wal.commit(notification).run().unwrap();
+        }
+        assert_eq!(wal.len(), 3);
+        assert_eq!(wal.tip(), Some(3));
+
+        // the notifications survive a restart
+        let mut wal = Wal::new(directory.path()).unwrap();
+        assert_eq!(wal.tip(), Some(3));
+        assert_eq!(wal.notifications().collect::<eyre::Result<Vec<_>>>().unwrap(), notifications);
+
+        wal.finalize(2).run().unwrap();
+        assert_eq!(wal.finished_height(), Some(2));
+        assert_eq!(wal.len(), 1);
+
+        let wal = Wal::new(directory.path()).unwrap();
+        assert_eq!(wal.finished_height(), Some(2));
+        assert_eq!(
+            wal.notifications().collect::<eyre::Result<Vec<_>>>().unwrap(),
+            notifications[2..].to_vec()
+        );
+
+        // the next notification gets a new ID
+        let mut wal = wal;
+        wal.commit(&commit(4)).run().unwrap();
+        assert_eq!(wal.len(), 2);
+        assert_eq!(wal.tip(), Some(4));
+    }
+
+    #[test]
+    fn full_wal_removes_oldest() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let mut wal = Wal::new(directory.path()).unwrap().with_max_len(2);
+        for number in 1..=3 {
+            wal.commit(&commit(number)).run().unwrap();
+        }
+        assert_eq!(wal.len(), 2);
+        assert_eq!(wal.dropped_tip(), Some(1));
+
+        // the removed notification is recorded across restarts
+        let mut wal = Wal::new(directory.path()).unwrap().with_max_len(2);
+        assert_eq!(wal.dropped_tip(), Some(1));
+        assert_eq!(
+            wal.notifications().collect::<eyre::Result<Vec<_>>>().unwrap(),
+            vec![commit(2), commit(3)]
+        );
+
+        // until the exex finishes it
+        wal.finalize(1).run().unwrap();
+        assert_eq!(wal.dropped_tip(), None);
+        assert_eq!(Wal::new(directory.path()).unwrap().dropped_tip(), None);
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let directory = tempfile::tempdir().unwrap();
+
+        let mut wal = Wal::new(directory.path()).unwrap();
+        wal.commit(&commit(1)).run().unwrap();
+
+        let path = notification_path(directory.path(), 0);
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes[0] = NOTIFICATION_VERSION + 1;
+        std::fs::write(&path, bytes).unwrap();
+
+        assert!(Wal::new(directory.path()).is_err());
+    }
+}
@@ -1,6 +1,6 @@
 use reth_primitives::{
-    Address, BlockHash, BlockHashOrNumber, BlockNumber, GotExpected, StaticFileSegment,
-    TxHashOrNumber, TxNumber, B256, U256,
+    trie::WitnessError, Address, BlockHash, BlockHashOrNumber, BlockNumber, GotExpected,
+    StaticFileSegment, TxHashOrNumber, TxNumber, B256, U256,
 };
 use std::path::PathBuf;
 use thiserror::Error;
@@ -128,6 +128,9 @@ pub enum ProviderError {
     /// Consistent view error.
     #[error("failed to initialize consistent view: {0}")]
     ConsistentView(Box<ConsistentViewError>),
+    /// Execution witness error.
+    #[error("invalid execution witness: {0}")]
+    InvalidWitness(Box<WitnessError>),
 }
 
 impl From<reth_primitives::fs::FsPathError> for ProviderError {
@@ -170,3 +173,9 @@ impl From<ConsistentViewError> for ProviderError {
         Self::ConsistentView(Box::new(value))
     }
 }
+
+impl From<WitnessError> for ProviderError {
+    fn from(value: WitnessError) -> Self {
+        Self::InvalidWitness(Box::new(value))
+    }
+}
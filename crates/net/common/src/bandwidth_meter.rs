@@ -34,10 +34,7 @@ use std::{
     },
     task::{ready, Context, Poll},
 };
-use tokio::{
-    io::{AsyncRead, AsyncWrite, ReadBuf},
-    net::TcpStream,
-};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
 
 /// Meters bandwidth usage of streams
 #[derive(Debug)]
@@ -46,6 +43,8 @@ struct BandwidthMeterInner {
     inbound: AtomicU64,
     /// Measures the number of outbound packets
     outbound: AtomicU64,
+    /// The meter that additionally records all bandwidth of this meter, if any
+    parent: Option<BandwidthMeter>,
 }
 
 /// Public shareable struct used for getting bandwidth metering info
@@ -70,17 +69,47 @@ impl BandwidthMeter {
     pub fn total_outbound(&self) -> u64 {
         self.inner.outbound.load(Ordering::Relaxed)
     }
-}
 
-impl Default for BandwidthMeter {
-    fn default() -> Self {
+    /// Returns a new [`BandwidthMeter`] that meters its own bandwidth and additionally records it
+    /// in this meter.
+    ///
+    /// This can be used to meter the bandwidth of individual streams while still keeping track of
+    /// the total bandwidth.
+    pub fn child(&self) -> Self {
+        Self::new(Some(self.clone()))
+    }
+
+    fn new(parent: Option<BandwidthMeter>) -> Self {
         Self {
             inner: Arc::new(BandwidthMeterInner {
                 inbound: AtomicU64::new(0),
                 outbound: AtomicU64::new(0),
+                parent,
             }),
         }
     }
+
+    /// Records the given number of downloaded bytes.
+    fn record_inbound(&self, num_bytes: u64) {
+        self.inner.inbound.fetch_add(num_bytes, Ordering::Relaxed);
+        if let Some(parent) = &self.inner.parent {
+            parent.record_inbound(num_bytes);
+        }
+    }
+
+    /// Records the given number of uploaded bytes.
+    fn record_outbound(&self, num_bytes: u64) {
+        self.inner.outbound.fetch_add(num_bytes, Ordering::Relaxed);
+        if let Some(parent) = &self.inner.parent {
+            parent.record_outbound(num_bytes);
+        }
+    }
+}
+
+impl Default for BandwidthMeter {
+    fn default() -> Self {
+        Self::new(None)
+    }
 }
 
 /// Wraps around a single stream that implements [`AsyncRead`] + [`AsyncWrite`] and meters the
@@ -131,10 +160,7 @@ impl<Stream: AsyncRead> AsyncRead for MeteredStream<Stream> {
             ready!(this.inner.poll_read(cx, buf))?;
             buf.filled().len() - init_num_bytes
         };
-        this.meter
-            .inner
-            .inbound
-            .fetch_add(u64::try_from(num_bytes).unwrap_or(u64::MAX), Ordering::Relaxed);
+        this.meter.record_inbound(u64::try_from(num_bytes).unwrap_or(u64::MAX));
         Poll::Ready(Ok(()))
     }
 }
@@ -147,10 +173,7 @@ impl<Stream: AsyncWrite> AsyncWrite for MeteredStream<Stream> {
     ) -> Poll<io::Result<usize>> {
         let this = self.project();
         let num_bytes = ready!(this.inner.poll_write(cx, buf))?;
-        this.meter
-            .inner
-            .outbound
-            .fetch_add(u64::try_from(num_bytes).unwrap_or(u64::MAX), Ordering::Relaxed);
+        this.meter.record_outbound(u64::try_from(num_bytes).unwrap_or(u64::MAX));
         Poll::Ready(Ok(num_bytes))
     }
 
@@ -165,7 +188,7 @@ impl<Stream: AsyncWrite> AsyncWrite for MeteredStream<Stream> {
     }
 }
 
-impl HasRemoteAddr for MeteredStream<TcpStream> {
+impl<S: HasRemoteAddr> HasRemoteAddr for MeteredStream<S> {
     fn remote_addr(&self) -> Option<SocketAddr> {
         self.inner.remote_addr()
     }
@@ -176,7 +199,7 @@ mod tests {
     use super::*;
     use tokio::{
         io::{duplex, AsyncReadExt, AsyncWriteExt, DuplexStream},
-        net::TcpListener,
+        net::{TcpListener, TcpStream},
     };
 
     async fn duplex_stream_ping_pong(
@@ -274,4 +297,28 @@ mod tests {
         assert_bandwidth_counts(&shared_client_bandwidth_meter, 8, 8);
         assert_bandwidth_counts(&shared_server_bandwidth_meter, 8, 8);
     }
+
+    #[tokio::test]
+    async fn test_child_meters() {
+        let (client_1, server_1) = duplex(64);
+        let (client_2, server_2) = duplex(64);
+
+        let total_client_bandwidth_meter = BandwidthMeter::default();
+
+        let mut metered_client_1 =
+            MeteredStream::new_with_meter(client_1, total_client_bandwidth_meter.child());
+        let mut metered_server_1 = MeteredStream::new(server_1);
+
+        let mut metered_client_2 =
+            MeteredStream::new_with_meter(client_2, total_client_bandwidth_meter.child());
+        let mut metered_server_2 = MeteredStream::new(server_2);
+
+        duplex_stream_ping_pong(&mut metered_client_1, &mut metered_server_1).await;
+        duplex_stream_ping_pong(&mut metered_client_2, &mut metered_server_2).await;
+        duplex_stream_ping_pong(&mut metered_client_2, &mut metered_server_2).await;
+
+        assert_bandwidth_counts(metered_client_1.get_bandwidth_meter(), 4, 4);
+        assert_bandwidth_counts(metered_client_2.get_bandwidth_meter(), 8, 8);
+        assert_bandwidth_counts(&total_client_bandwidth_meter, 12, 12);
+    }
 }
@@ -40,7 +40,7 @@ use discv5::{
 use enr::Enr;
 use parking_lot::Mutex;
 use proto::{EnrRequest, EnrResponse, EnrWrapper};
-use reth_primitives::{bytes::Bytes, hex, ForkId, PeerId, B256};
+use reth_primitives::{bytes::Bytes, hex, pk2id, ForkId, PeerId, B256};
 use secp256k1::SecretKey;
 use std::{
     cell::RefCell,
@@ -1259,6 +1259,10 @@ impl Discv4Service {
                     (Some(new), None) => self.notify(DiscoveryUpdate::EnrForkId(record, new)),
                     _ => {}
                 }
+                // only report records that are signed by the node itself
+                if pk2id(&msg.enr.0.public_key()) == id {
+                    self.notify(DiscoveryUpdate::Enr(record, msg.enr.0))
+                }
             }
         }
     }
@@ -2168,6 +2172,8 @@ pub enum DiscoveryUpdate {
     DiscoveredAtCapacity(NodeRecord),
     /// Received a [`ForkId`] via EIP-868 for the given [`NodeRecord`].
     EnrForkId(NodeRecord, ForkId),
+    /// Received the signed [`Enr`] of the given [`NodeRecord`] via EIP-868.
+    Enr(NodeRecord, Enr<SecretKey>),
     /// Node that was removed from the table
     Removed(PeerId),
     /// A series of updates
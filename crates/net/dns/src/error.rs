@@ -59,3 +59,16 @@ pub(crate) enum LookupError {
     /// Indicates the requested entry was not found.
     EntryNotFound,
 }
+
+/// Errors that can happen while publishing a [DnsTree](crate::tree::DnsTree)
+#[derive(thiserror::Error, Debug)]
+pub enum PublishError {
+    /// I/O error.
+    #[error(transparent)]
+    /// Indicates a failure while writing the records.
+    Io(#[from] std::io::Error),
+    /// Other error.
+    #[error("{0}")]
+    /// Indicates other unspecified errors, e.g. of a remote DNS provider.
+    Other(String),
+}
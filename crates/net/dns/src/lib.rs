@@ -13,7 +13,7 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
-pub use crate::resolver::{DnsResolver, MapResolver, Resolver};
+pub use crate::resolver::{BoxedResolver, DnsResolver, MapResolver, Resolver};
 use crate::{
     query::{QueryOutcome, QueryPool, ResolveEntryResult, ResolveRootResult},
     sync::{ResolveKind, SyncAction},
@@ -22,6 +22,8 @@ use crate::{
 pub use config::DnsDiscoveryConfig;
 use enr::Enr;
 use error::ParseDnsEntryError;
+pub use error::PublishError;
+pub use publish::{DnsTreePublisher, DnsTreePublisherConfig, NodeSetPublisher, ZoneFilePublisher};
 use reth_primitives::{pk2id, ForkId, NodeRecord};
 use schnellru::{ByLength, LruMap};
 use secp256k1::SecretKey;
@@ -50,6 +52,7 @@ use tracing::{debug, trace};
 
 mod config;
 mod error;
+pub mod publish;
 mod query;
 pub mod resolver;
 mod sync;
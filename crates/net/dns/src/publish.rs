@@ -0,0 +1,642 @@
+//! Publishing of [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) trees.
+//!
+//! The [`NodeSetPublisher`] keeps track of a set of nodes and re-signs and republishes the
+//! [`DnsTree`] of that set whenever it changes, via a [`DnsTreePublisher`].
+
+use crate::{
+    error::PublishError,
+    resolver::MapResolver,
+    tree::{DnsTree, LinkEntry},
+};
+use enr::Enr;
+use reth_primitives::{pk2id, PeerId};
+use secp256k1::{SecretKey, SECP256K1};
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{self, File},
+    future::Future,
+    io::Write,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{ready, Context, Poll},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use tokio::{
+    task::JoinHandle,
+    time::{Instant, Sleep},
+};
+
+/// The default minimum time between two publications of a tree.
+pub const DEFAULT_REPUBLISH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The default time the node set must be unchanged before it is published.
+pub const DEFAULT_PUBLISH_DEBOUNCE: Duration = Duration::from_secs(5);
+
+/// The maximum time to wait before retrying a failed publication.
+pub const MAX_PUBLISH_BACKOFF: Duration = Duration::from_secs(30 * 60);
+
+/// The default TTL of records written by the [`ZoneFilePublisher`].
+pub const DEFAULT_RECORD_TTL: u32 = 300;
+
+/// The maximum length of a single string in a TXT record.
+const MAX_TXT_STRING_LEN: usize = 255;
+
+/// The future returned by [`DnsTreePublisher::publish`].
+pub type PublishFuture<'a> = Pin<Box<dyn Future<Output = Result<(), PublishError>> + Send + 'a>>;
+
+/// Publishes the TXT records of a [`DnsTree`], e.g. to a zone file or a DNS provider.
+pub trait DnsTreePublisher: fmt::Debug + Send + Sync + 'static {
+    /// Publishes the records of the tree at the given domain, replacing the previously published
+    /// tree.
+    ///
+    /// The [`NodeSetPublisher`] runs the returned future on its own task, so it may perform
+    /// network requests or blocking file I/O via [`tokio::task::spawn_blocking`].
+    fn publish<'a>(&'a self, domain: &'a str, tree: &'a DnsTree) -> PublishFuture<'a>;
+}
+
+impl<T: DnsTreePublisher + ?Sized> DnsTreePublisher for Arc<T> {
+    fn publish<'a>(&'a self, domain: &'a str, tree: &'a DnsTree) -> PublishFuture<'a> {
+        (**self).publish(domain, tree)
+    }
+}
+
+/// Publishes the records into the map, so a [`DnsDiscoveryService`](crate::DnsDiscoveryService)
+/// using the same [`MapResolver`] can sync the tree.
+///
+/// Records of previous trees are kept, they are no longer reachable from the new root.
+impl DnsTreePublisher for MapResolver {
+    fn publish<'a>(&'a self, domain: &'a str, tree: &'a DnsTree) -> PublishFuture<'a> {
+        Box::pin(async move {
+            for (name, txt) in tree.to_txt_records(domain) {
+                self.insert(name, txt);
+            }
+            Ok(())
+        })
+    }
+}
+
+/// A [`DnsTreePublisher`] that writes the records as zone file, which can be loaded by any
+/// authoritative DNS server.
+///
+/// The file is replaced atomically on every publish.
+#[derive(Debug, Clone)]
+pub struct ZoneFilePublisher {
+    /// The path of the zone file.
+    path: PathBuf,
+    /// The TTL of the records.
+    ttl: u32,
+}
+
+// === impl ZoneFilePublisher ===
+
+impl ZoneFilePublisher {
+    /// Creates a new publisher that writes to the given file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into(), ttl: DEFAULT_RECORD_TTL }
+    }
+
+    /// Sets the TTL of the records.
+    pub const fn with_ttl(mut self, ttl: u32) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Returns the path of the zone file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Renders the records of the tree in zone file format.
+    fn zone(&self, domain: &str, tree: &DnsTree) -> Result<Vec<u8>, PublishError> {
+        let mut zone = Vec::new();
+        for (name, txt) in tree.to_txt_records(domain) {
+            write!(zone, "{name}. {} IN TXT", self.ttl)?;
+            // entries are ASCII, so they can be split at any byte
+            for chunk in txt.as_bytes().chunks(MAX_TXT_STRING_LEN) {
+                write!(zone, " \"{}\"", String::from_utf8_lossy(chunk))?;
+            }
+            writeln!(zone)?;
+        }
+        Ok(zone)
+    }
+}
+
+impl DnsTreePublisher for ZoneFilePublisher {
+    fn publish<'a>(&'a self, domain: &'a str, tree: &'a DnsTree) -> PublishFuture<'a> {
+        Box::pin(async move {
+            let zone = self.zone(domain, tree)?;
+            let path = self.path.clone();
+            tokio::task::spawn_blocking(move || write_atomic(&path, &zone))
+                .await
+                .map_err(|err| PublishError::Other(err.to_string()))??;
+            Ok(())
+        })
+    }
+}
+
+/// Writes the file via a temporary file that is synced to disk and then renamed, so that readers
+/// never see a partially written file, even after a crash.
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension("tmp");
+    let mut file = File::create(&tmp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+    // persist the rename
+    if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+        File::open(parent)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Settings for publishing a [`DnsTree`] with a [`NodeSetPublisher`].
+#[derive(Debug, Clone)]
+pub struct DnsTreePublisherConfig {
+    /// The domain the tree is published at.
+    pub domain: String,
+    /// The key the root of the tree is signed with.
+    pub signing_key: SecretKey,
+    /// Links to other trees that are included in the tree.
+    pub links: Vec<LinkEntry>,
+    /// Where the records of the tree are published.
+    pub publisher: Arc<dyn DnsTreePublisher>,
+    /// The minimum time between two publications.
+    ///
+    /// All changes of the node set within the interval are published at once. Failed publications
+    /// are retried after an exponential backoff starting at this interval.
+    ///
+    /// Default: 60s
+    pub republish_interval: Duration,
+    /// How long the node set must be unchanged before a new tree is published, so that a burst
+    /// of session changes results in a single publication.
+    ///
+    /// A change is published at the latest one `republish_interval` after it happened.
+    ///
+    /// Default: 5s
+    pub debounce: Duration,
+}
+
+// === impl DnsTreePublisherConfig ===
+
+impl DnsTreePublisherConfig {
+    /// Creates a new config that publishes the tree at the given domain.
+    pub fn new(
+        domain: impl Into<String>,
+        signing_key: SecretKey,
+        publisher: impl DnsTreePublisher,
+    ) -> Self {
+        Self {
+            domain: domain.into(),
+            signing_key,
+            links: Vec::new(),
+            publisher: Arc::new(publisher),
+            republish_interval: DEFAULT_REPUBLISH_INTERVAL,
+            debounce: DEFAULT_PUBLISH_DEBOUNCE,
+        }
+    }
+
+    /// Adds a link to another tree.
+    pub fn with_link(mut self, link: LinkEntry) -> Self {
+        self.links.push(link);
+        self
+    }
+
+    /// Sets the minimum time between two publications.
+    pub const fn with_republish_interval(mut self, interval: Duration) -> Self {
+        self.republish_interval = interval;
+        self
+    }
+
+    /// Sets how long the node set must be unchanged before it is published.
+    pub const fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Returns the `enrtree://` link under which the tree can be synced.
+    pub fn link(&self) -> LinkEntry {
+        LinkEntry { domain: self.domain.clone(), pubkey: self.signing_key.public_key(SECP256K1) }
+    }
+}
+
+/// Keeps track of a set of nodes and publishes a signed [`DnsTree`] of the set.
+///
+/// Changes are published at most once per
+/// [`republish_interval`](DnsTreePublisherConfig::republish_interval), after the set has been
+/// unchanged for the [`debounce`](DnsTreePublisherConfig::debounce) time, each time with a new
+/// sequence number. The tree is signed and published on a separate task, so a slow
+/// [`DnsTreePublisher`] never blocks the owner of the set. Failed publications are retried with
+/// an exponential backoff.
+///
+/// This type must be polled to publish changes.
+#[derive(Debug)]
+pub struct NodeSetPublisher {
+    /// The settings of the published tree.
+    config: DnsTreePublisherConfig,
+    /// The records of all nodes in the set.
+    nodes: HashMap<PeerId, Enr<SecretKey>>,
+    /// The most recently published tree.
+    tree: Option<DnsTree>,
+    /// The sequence number of the most recently signed tree, whether publishing it succeeded or
+    /// not.
+    sequence_number: Option<u64>,
+    /// When the set first changed since the last publication started, if it did.
+    changed_since: Option<Instant>,
+    /// When the set changed most recently.
+    last_change: Instant,
+    /// Delays the next publication, to limit the publication rate and to back off after failures.
+    delay: Pin<Box<Sleep>>,
+    /// The number of publications that failed in a row.
+    failures: u32,
+    /// The publication in progress, resolves to the published tree.
+    in_progress: Option<JoinHandle<Result<DnsTree, PublishError>>>,
+}
+
+// === impl NodeSetPublisher ===
+
+impl NodeSetPublisher {
+    /// Creates a new publisher with an empty node set.
+    ///
+    /// The first tree is published on the first poll, without waiting for the debounce time.
+    ///
+    /// Note: requires a running runtime
+    pub fn new(config: DnsTreePublisherConfig) -> Self {
+        let now = Instant::now();
+        Self {
+            config,
+            nodes: Default::default(),
+            tree: None,
+            sequence_number: None,
+            changed_since: Some(now),
+            last_change: now,
+            delay: Box::pin(tokio::time::sleep_until(now)),
+            failures: 0,
+            in_progress: None,
+        }
+    }
+
+    /// Returns the `enrtree://` link under which the tree can be synced.
+    pub fn link(&self) -> LinkEntry {
+        self.config.link()
+    }
+
+    /// Returns the most recently published tree.
+    pub fn tree(&self) -> Option<&DnsTree> {
+        self.tree.as_ref()
+    }
+
+    /// Returns the number of nodes in the set.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns `true` if the node is in the set.
+    pub fn contains(&self, peer_id: &PeerId) -> bool {
+        self.nodes.contains_key(peer_id)
+    }
+
+    /// Returns `true` if a publication is in progress.
+    pub fn is_publishing(&self) -> bool {
+        self.in_progress.is_some()
+    }
+
+    /// Adds the node to the set, or updates its record.
+    pub fn insert(&mut self, enr: Enr<SecretKey>) {
+        let peer_id = pk2id(&enr.public_key());
+        if self.nodes.get(&peer_id) != Some(&enr) {
+            self.nodes.insert(peer_id, enr);
+            self.on_change();
+        }
+    }
+
+    /// Removes the node from the set.
+    pub fn remove(&mut self, peer_id: &PeerId) {
+        if self.nodes.remove(peer_id).is_some() {
+            self.on_change();
+        }
+    }
+
+    fn on_change(&mut self) {
+        self.last_change = Instant::now();
+        self.changed_since.get_or_insert(self.last_change);
+    }
+
+    /// Returns the sequence number of the next tree.
+    ///
+    /// Sequence numbers must increase, also across restarts, so they are at least the current
+    /// unix timestamp.
+    fn next_sequence_number(&mut self) -> u64 {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let sequence_number = self.sequence_number.map_or(now, |seq| now.max(seq + 1));
+        self.sequence_number = Some(sequence_number);
+        sequence_number
+    }
+
+    /// Signs and publishes the tree of the current node set right away, on the current task.
+    pub async fn publish(&mut self) -> Result<&DnsTree, PublishError> {
+        let tree = DnsTree::new(
+            self.nodes.values().cloned().collect(),
+            self.config.links.clone(),
+            self.next_sequence_number(),
+            &self.config.signing_key,
+        );
+        self.config.publisher.publish(&self.config.domain, &tree).await?;
+        self.changed_since = None;
+
+        Ok(self.tree.insert(tree))
+    }
+
+    /// Spawns a task that signs and publishes the tree of the current node set.
+    fn spawn_publish(&mut self) {
+        let nodes = self.nodes.values().cloned().collect::<Vec<_>>();
+        let links = self.config.links.clone();
+        let sequence_number = self.next_sequence_number();
+        let signing_key = self.config.signing_key;
+        let domain = self.config.domain.clone();
+        let publisher = Arc::clone(&self.config.publisher);
+
+        self.changed_since = None;
+        self.in_progress = Some(tokio::task::spawn(async move {
+            let tree = DnsTree::new(nodes, links, sequence_number, &signing_key);
+            publisher.publish(&domain, &tree).await?;
+            Ok(tree)
+        }));
+    }
+
+    /// Returns the time to wait before retrying after the given number of failed publications.
+    fn backoff(&self) -> Duration {
+        let exp = self.failures.saturating_sub(1).min(16);
+        self.config.republish_interval.saturating_mul(1 << exp).min(MAX_PUBLISH_BACKOFF)
+    }
+
+    /// Drives the publication of changes of the node set.
+    ///
+    /// Resolves to the sequence number of the published tree once a publication finished. If
+    /// publishing failed it is retried after a backoff.
+    pub fn poll(&mut self, cx: &mut Context<'_>) -> Poll<Result<u64, PublishError>> {
+        if let Some(in_progress) = self.in_progress.as_mut() {
+            let outcome = ready!(Pin::new(in_progress).poll(cx))
+                .unwrap_or_else(|err| Err(PublishError::Other(err.to_string())));
+            self.in_progress = None;
+
+            let now = Instant::now();
+            return match outcome {
+                Ok(tree) => {
+                    self.failures = 0;
+                    self.delay.as_mut().reset(now + self.config.republish_interval);
+                    let sequence_number = tree.root().sequence_number;
+                    self.tree = Some(tree);
+                    Poll::Ready(Ok(sequence_number))
+                }
+                Err(err) => {
+                    // the failed changes are published with the next attempt
+                    self.changed_since.get_or_insert(now);
+                    self.failures += 1;
+                    self.delay.as_mut().reset(now + self.backoff());
+                    Poll::Ready(Err(err))
+                }
+            }
+        }
+
+        loop {
+            let Some(changed_since) = self.changed_since else { return Poll::Pending };
+            ready!(self.delay.as_mut().poll(cx));
+
+            // wait for the set to settle, unless the change has been pending for too long, or
+            // this is the first tree
+            let settled = self.last_change + self.config.debounce;
+            let now = Instant::now();
+            if self.tree.is_some() &&
+                now < settled &&
+                now < changed_since + self.config.republish_interval
+            {
+                self.delay.as_mut().reset(settled);
+                continue
+            }
+
+            self.spawn_publish();
+            // poll the task once to register the waker
+            return self.poll(cx)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DnsDiscoveryEvent, DnsDiscoveryService};
+    use secp256k1::rand::thread_rng;
+    use std::{
+        future::poll_fn,
+        net::Ipv4Addr,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use tokio_stream::StreamExt;
+
+    fn enr(port: u16) -> Enr<SecretKey> {
+        let key = SecretKey::new(&mut thread_rng());
+        Enr::builder().ip4(Ipv4Addr::LOCALHOST).tcp4(port).udp4(port).build(&key).unwrap()
+    }
+
+    #[tokio::test]
+    async fn publish_on_change() {
+        let resolver = Arc::new(MapResolver::default());
+        let config = DnsTreePublisherConfig::new(
+            "nodes.example.org",
+            SecretKey::new(&mut thread_rng()),
+            Arc::clone(&resolver),
+        )
+        .with_republish_interval(Duration::from_millis(10))
+        .with_debounce(Duration::from_millis(10));
+        let mut publisher = NodeSetPublisher::new(config);
+
+        // the initial, empty tree is published right away
+        let seq = poll_fn(|cx| publisher.poll(cx)).await.unwrap();
+        assert!(resolver.get("nodes.example.org").unwrap().contains(&format!("seq={seq}")));
+
+        // nothing changed
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        poll_fn(|cx| {
+            assert!(publisher.poll(cx).is_pending());
+            Poll::Ready(())
+        })
+        .await;
+
+        let node = enr(30303);
+        publisher.insert(node.clone());
+        publisher.insert(node.clone());
+        assert_eq!(publisher.len(), 1);
+
+        let next = poll_fn(|cx| publisher.poll(cx)).await.unwrap();
+        assert!(next > seq);
+        let tree = publisher.tree().unwrap();
+        assert!(tree.root().verify::<SecretKey>(&publisher.link().pubkey));
+        assert_eq!(resolver.get("nodes.example.org").unwrap(), tree.root().to_string());
+    }
+
+    /// Fails the given number of publications, then publishes into the map.
+    #[derive(Debug)]
+    struct FlakyPublisher {
+        failures: AtomicUsize,
+        resolver: MapResolver,
+    }
+
+    impl DnsTreePublisher for FlakyPublisher {
+        fn publish<'a>(&'a self, domain: &'a str, tree: &'a DnsTree) -> PublishFuture<'a> {
+            if self
+                .failures
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+                .is_ok()
+            {
+                return Box::pin(async { Err(PublishError::Other("unavailable".to_string())) })
+            }
+            self.resolver.publish(domain, tree)
+        }
+    }
+
+    #[tokio::test]
+    async fn debounce_changes() {
+        let resolver = Arc::new(MapResolver::default());
+        let config = DnsTreePublisherConfig::new(
+            "nodes.example.org",
+            SecretKey::new(&mut thread_rng()),
+            Arc::clone(&resolver),
+        )
+        .with_republish_interval(Duration::from_millis(500))
+        .with_debounce(Duration::from_millis(100));
+        let mut publisher = NodeSetPublisher::new(config);
+        let seq = poll_fn(|cx| publisher.poll(cx)).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        // a burst of changes is published once, after the set settled
+        for port in 30303..30308 {
+            publisher.insert(enr(port));
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        let last_change = Instant::now();
+        let next = poll_fn(|cx| publisher.poll(cx)).await.unwrap();
+        assert!(last_change.elapsed() >= Duration::from_millis(70));
+        assert!(next > seq);
+        assert!(!publisher.is_publishing());
+        assert_eq!(
+            resolver.get("nodes.example.org").unwrap(),
+            publisher.tree().unwrap().root().to_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn retry_failed_publication() {
+        let publisher = Arc::new(FlakyPublisher {
+            failures: AtomicUsize::new(2),
+            resolver: MapResolver::default(),
+        });
+        let config = DnsTreePublisherConfig::new(
+            "nodes.example.org",
+            SecretKey::new(&mut thread_rng()),
+            Arc::clone(&publisher),
+        )
+        .with_republish_interval(Duration::from_millis(20));
+        let mut node_set = NodeSetPublisher::new(config);
+        node_set.insert(enr(30303));
+
+        let start = Instant::now();
+        assert!(poll_fn(|cx| node_set.poll(cx)).await.is_err());
+        // retried after the interval
+        assert!(poll_fn(|cx| node_set.poll(cx)).await.is_err());
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert!(node_set.tree().is_none());
+        // and after twice the interval
+        let seq = poll_fn(|cx| node_set.poll(cx)).await.unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(60));
+
+        let tree = node_set.tree().unwrap();
+        assert_eq!(tree.root().sequence_number, seq);
+        assert_eq!(publisher.resolver.get("nodes.example.org").unwrap(), tree.root().to_string());
+        assert_eq!(node_set.backoff(), Duration::from_millis(20));
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let config = DnsTreePublisherConfig::new(
+            "nodes.example.org",
+            SecretKey::new(&mut thread_rng()),
+            MapResolver::default(),
+        );
+        let rt = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        let _guard = rt.enter();
+        let mut publisher = NodeSetPublisher::new(config);
+
+        publisher.failures = 1;
+        assert_eq!(publisher.backoff(), DEFAULT_REPUBLISH_INTERVAL);
+        publisher.failures = 3;
+        assert_eq!(publisher.backoff(), DEFAULT_REPUBLISH_INTERVAL * 4);
+        publisher.failures = 100;
+        assert_eq!(publisher.backoff(), MAX_PUBLISH_BACKOFF);
+    }
+
+    #[tokio::test]
+    async fn zone_file() {
+        let dir = std::env::temp_dir().join(format!("reth-dns-zone-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("nodes.zone");
+
+        let key = SecretKey::new(&mut thread_rng());
+        let tree = DnsTree::new(vec![enr(30303), enr(30304)], Vec::new(), 1, &key);
+        ZoneFilePublisher::new(&path)
+            .with_ttl(60)
+            .publish("nodes.example.org", &tree)
+            .await
+            .unwrap();
+        assert!(!path.with_extension("tmp").exists());
+
+        let zone = fs::read_to_string(&path).unwrap();
+        let records = tree.to_txt_records("nodes.example.org");
+        assert_eq!(zone.lines().count(), records.len());
+        for line in zone.lines() {
+            let (name, txt) = line.split_once(" 60 IN TXT ").unwrap();
+            let txt = txt.split(' ').map(|chunk| chunk.trim_matches('"')).collect::<String>();
+            assert_eq!(records[name.trim_end_matches('.')], txt);
+        }
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn sync_published_tree() {
+        reth_tracing::init_test_tracing();
+
+        let resolver = Arc::new(MapResolver::default());
+        let config = DnsTreePublisherConfig::new(
+            "nodes.example.org",
+            SecretKey::new(&mut thread_rng()),
+            Arc::clone(&resolver),
+        );
+        let mut publisher = NodeSetPublisher::new(config);
+        let nodes = (0..20).map(|i| enr(30303 + i)).collect::<Vec<_>>();
+        for node in &nodes {
+            publisher.insert(node.clone());
+        }
+        publisher.publish().await.unwrap();
+
+        let mut service = DnsDiscoveryService::new(resolver, Default::default());
+        service.sync_tree_with_link(publisher.link());
+
+        let mut discovered = Vec::new();
+        while discovered.len() < nodes.len() {
+            match service.next().await.unwrap() {
+                DnsDiscoveryEvent::Enr(enr) => discovered.push(enr),
+            }
+        }
+        discovered.sort_by_key(|enr| enr.node_id());
+        let mut nodes = nodes;
+        nodes.sort_by_key(|enr| enr.node_id());
+        assert_eq!(discovered, nodes);
+    }
+}
@@ -1,7 +1,7 @@
 //! Perform DNS lookups
 
 use parking_lot::RwLock;
-use std::{collections::HashMap, future::Future};
+use std::{collections::HashMap, fmt, future::Future, pin::Pin, sync::Arc};
 use tracing::trace;
 pub use trust_dns_resolver::{error::ResolveError, TokioAsyncResolver};
 use trust_dns_resolver::{name_server::ConnectionProvider, AsyncResolver};
@@ -31,6 +31,57 @@ impl<P: ConnectionProvider> Resolver for AsyncResolver<P> {
     }
 }
 
+impl<R: Resolver> Resolver for Arc<R> {
+    fn lookup_txt(&self, query: &str) -> impl Future<Output = Option<String>> + Send {
+        (**self).lookup_txt(query)
+    }
+}
+
+/// Object safe version of [Resolver], see [BoxedResolver].
+trait DynResolver: Send + Sync + 'static {
+    fn lookup_txt_boxed<'a>(
+        &'a self,
+        query: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Option<String>> + Send + 'a>>;
+}
+
+impl<R: Resolver> DynResolver for R {
+    fn lookup_txt_boxed<'a>(
+        &'a self,
+        query: &'a str,
+    ) -> Pin<Box<dyn Future<Output = Option<String>> + Send + 'a>> {
+        Box::pin(self.lookup_txt(query))
+    }
+}
+
+/// A type erased [Resolver].
+///
+/// This allows to configure the resolver of a DNS discovery service that is set up by another
+/// component, like the network.
+#[derive(Clone)]
+pub struct BoxedResolver(Arc<dyn DynResolver>);
+
+// === impl BoxedResolver ===
+
+impl BoxedResolver {
+    /// Wraps the given [Resolver].
+    pub fn new(resolver: impl Resolver) -> Self {
+        Self(Arc::new(resolver))
+    }
+}
+
+impl fmt::Debug for BoxedResolver {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BoxedResolver").finish_non_exhaustive()
+    }
+}
+
+impl Resolver for BoxedResolver {
+    fn lookup_txt(&self, query: &str) -> impl Future<Output = Option<String>> + Send {
+        self.0.lookup_txt_boxed(query)
+    }
+}
+
 /// An asynchronous DNS resolver
 ///
 /// See also [TokioAsyncResolver]
@@ -102,29 +102,26 @@ impl<K: EnrKeyUnambiguous> SyncTree<K> {
 
     /// Updates the root and returns what changed
     pub(crate) fn update_root(&mut self, root: TreeRootEntry) {
-        let enr = root.enr_root == self.root.enr_root;
-        let link = root.link_root == self.root.link_root;
+        let enr_changed = root.enr_root != self.root.enr_root;
+        let link_changed = root.link_root != self.root.link_root;
 
         self.root = root;
         self.root_updated = Instant::now();
 
-        let state = match (enr, link) {
-            (true, true) => {
-                self.unresolved_nodes.clear();
-                self.unresolved_links.clear();
-                SyncState::Pending
-            }
-            (true, _) => {
+        let state = match (enr_changed, link_changed) {
+            (true, false) => {
                 self.unresolved_nodes.clear();
                 SyncState::Enr
             }
-            (_, true) => {
+            (false, true) => {
                 self.unresolved_links.clear();
                 SyncState::Link
             }
             _ => {
-                // unchanged
-                return
+                // both subtrees changed, or the tree is rechecked: resync everything
+                self.unresolved_nodes.clear();
+                self.unresolved_links.clear();
+                SyncState::Pending
             }
         };
         self.sync_state = state;
@@ -160,3 +157,93 @@ impl ResolveKind {
         matches!(self, ResolveKind::Link)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UPDATE_TIMEOUT: Duration = Duration::from_secs(60);
+
+    fn root(enr_root: &str, link_root: &str, sequence_number: u64) -> TreeRootEntry {
+        TreeRootEntry {
+            enr_root: enr_root.to_string(),
+            link_root: link_root.to_string(),
+            sequence_number,
+            signature: Default::default(),
+        }
+    }
+
+    /// Returns a tree that synced the given root and has unresolved children in both subtrees.
+    fn synced_tree(root: TreeRootEntry) -> SyncTree {
+        let link =
+            "enrtree://AM5FCQLWIZX2QFPNJAP7VUERCCRNGRHWZG3YYHIUV7BVDQ5FDPRT2@nodes.example.org"
+                .parse()
+                .unwrap();
+        let mut tree = SyncTree::new(root, link);
+        let now = Instant::now();
+        assert!(matches!(tree.poll(now, UPDATE_TIMEOUT), Some(SyncAction::Link(_))));
+        assert!(matches!(tree.poll(now, UPDATE_TIMEOUT), Some(SyncAction::Enr(_))));
+        tree.extend_children(ResolveKind::Enr, ["ENR1".to_string()]);
+        tree.extend_children(ResolveKind::Link, ["LINK1".to_string()]);
+        tree
+    }
+
+    fn next_action(tree: &mut SyncTree) -> Option<String> {
+        match tree.poll(Instant::now(), UPDATE_TIMEOUT)? {
+            SyncAction::UpdateRoot => Some("update".to_string()),
+            SyncAction::Enr(hash) => Some(format!("enr:{hash}")),
+            SyncAction::Link(hash) => Some(format!("link:{hash}")),
+        }
+    }
+
+    #[test]
+    fn update_root_enr_changed() {
+        let mut tree = synced_tree(root("E1", "L1", 1));
+        tree.update_root(root("E2", "L1", 2));
+
+        // only the enr subtree is resynced, unresolved links are kept
+        assert_eq!(next_action(&mut tree).as_deref(), Some("enr:E2"));
+        assert_eq!(next_action(&mut tree).as_deref(), Some("link:LINK1"));
+        assert_eq!(next_action(&mut tree), None);
+    }
+
+    #[test]
+    fn update_root_link_changed() {
+        let mut tree = synced_tree(root("E1", "L1", 1));
+        tree.update_root(root("E1", "L2", 2));
+
+        // only the link subtree is resynced, unresolved nodes are kept
+        assert_eq!(next_action(&mut tree).as_deref(), Some("link:L2"));
+        assert_eq!(next_action(&mut tree).as_deref(), Some("enr:ENR1"));
+        assert_eq!(next_action(&mut tree), None);
+    }
+
+    #[test]
+    fn update_root_both_changed() {
+        let mut tree = synced_tree(root("E1", "L1", 1));
+        tree.update_root(root("E2", "L2", 2));
+
+        assert_eq!(next_action(&mut tree).as_deref(), Some("link:L2"));
+        assert_eq!(next_action(&mut tree).as_deref(), Some("enr:E2"));
+        assert_eq!(next_action(&mut tree), None);
+        assert_eq!(tree.root().sequence_number, 2);
+    }
+
+    #[test]
+    fn update_root_unchanged() {
+        let mut tree = synced_tree(root("E1", "L1", 1));
+
+        // the root is rechecked once the update timeout elapsed
+        assert!(matches!(
+            tree.poll(Instant::now() + UPDATE_TIMEOUT * 2, UPDATE_TIMEOUT),
+            Some(SyncAction::UpdateRoot)
+        ));
+        assert_eq!(next_action(&mut tree), None);
+
+        // an unchanged root resyncs the whole tree instead of stalling in the root update
+        tree.update_root(root("E1", "L1", 1));
+        assert_eq!(next_action(&mut tree).as_deref(), Some("link:L1"));
+        assert_eq!(next_action(&mut tree).as_deref(), Some("enr:E1"));
+        assert_eq!(next_action(&mut tree), None);
+    }
+}
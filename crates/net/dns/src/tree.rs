@@ -23,11 +23,12 @@ use crate::error::{
 };
 use data_encoding::{BASE32_NOPAD, BASE64URL_NOPAD};
 use enr::{Enr, EnrError, EnrKey, EnrKeyUnambiguous, EnrPublicKey};
-use reth_primitives::{hex, Bytes};
-use secp256k1::SecretKey;
+use reth_primitives::{hex, keccak256, Bytes};
+use secp256k1::{Message, PublicKey, SecretKey, SECP256K1};
 #[cfg(feature = "serde")]
 use serde_with::{DeserializeFromStr, SerializeDisplay};
 use std::{
+    collections::BTreeMap,
     fmt,
     hash::{Hash, Hasher},
     str::FromStr,
@@ -41,6 +42,10 @@ const LINK_PREFIX: &str = "enrtree://";
 const BRANCH_PREFIX: &str = "enrtree-branch:";
 /// Prefix used for ENR entries in the ENR tree.
 const ENR_PREFIX: &str = "enr:";
+/// The maximum number of children of a branch entry, so that it fits into a single TXT record.
+///
+/// See also <https://github.com/ethereum/go-ethereum/blob/9244d5cd61f3ea5a7645fdf2a1a96d53421e412f/p2p/dnsdisc/tree.go#L99-L102>
+const MAX_BRANCH_CHILDREN: usize = 13;
 
 /// Represents all variants of DNS entries for Ethereum node lists.
 #[derive(Debug, Clone)]
@@ -210,6 +215,11 @@ impl BranchEntry {
             Ok(hash.to_string())
         }
 
+        // a branch without children is used for empty subtrees
+        if input.trim().is_empty() {
+            return Ok(Self { children: Vec::new() })
+        }
+
         let children =
             input.trim().split(',').map(ensure_valid_hash).collect::<ParseEntryResult<Vec<_>>>()?;
         Ok(Self { children })
@@ -341,6 +351,135 @@ impl<K: EnrKeyUnambiguous> fmt::Display for NodeEntry<K> {
     }
 }
 
+/// A signed tree of node records and links that can be published via DNS.
+///
+/// Every entry of the tree is stored in a TXT record at `<hash>.<domain>`, where `<hash>` is the
+/// base32 encoded truncated keccak256 hash of the entry, and the [`TreeRootEntry`] is stored at the
+/// domain itself.
+#[derive(Debug, Clone)]
+pub struct DnsTree {
+    /// The signed root of the tree.
+    root: TreeRootEntry,
+    /// All entries of the tree, keyed by their hash.
+    entries: BTreeMap<String, DnsEntry<SecretKey>>,
+    /// The public key of the key that signed the tree.
+    public_key: PublicKey,
+}
+
+// === impl DnsTree ===
+
+impl DnsTree {
+    /// Creates a new tree of the given records and links, signed with the given key.
+    ///
+    /// Records are ordered by node id and links by their text, so the same set of records
+    /// produces the same tree.
+    pub fn new(
+        mut records: Vec<Enr<SecretKey>>,
+        mut links: Vec<LinkEntry<SecretKey>>,
+        sequence_number: u64,
+        key: &SecretKey,
+    ) -> Self {
+        records.sort_by_key(|enr| enr.node_id());
+        records.dedup_by_key(|enr| enr.node_id());
+        links.sort_by_key(|link| link.to_string());
+        links.dedup();
+
+        let mut entries = BTreeMap::new();
+        let enr_root = Self::build_subtree(
+            records.into_iter().map(|enr| DnsEntry::Node(NodeEntry { enr })).collect(),
+            &mut entries,
+        );
+        let link_root =
+            Self::build_subtree(links.into_iter().map(DnsEntry::Link).collect(), &mut entries);
+
+        let mut root = TreeRootEntry {
+            enr_root: entry_hash(&enr_root),
+            link_root: entry_hash(&link_root),
+            sequence_number,
+            signature: Bytes::new(),
+        };
+        entries.insert(root.enr_root.clone(), enr_root);
+        entries.insert(root.link_root.clone(), link_root);
+
+        // EIP-1459 requires the signature to include the recovery id, unlike `sign`
+        let hash = keccak256(root.content());
+        let msg = Message::from_slice(hash.as_slice()).expect("is 32 bytes");
+        let (recovery_id, signature) =
+            SECP256K1.sign_ecdsa_recoverable(&msg, key).serialize_compact();
+        let mut sig = signature.to_vec();
+        sig.push(recovery_id.to_i32() as u8);
+        root.signature = sig.into();
+
+        Self { root, entries, public_key: key.public_key(SECP256K1) }
+    }
+
+    /// Returns the signed root of the tree.
+    pub fn root(&self) -> &TreeRootEntry {
+        &self.root
+    }
+
+    /// Returns all entries of the tree except for the root, keyed by their hash.
+    pub fn entries(&self) -> &BTreeMap<String, DnsEntry<SecretKey>> {
+        &self.entries
+    }
+
+    /// Returns the TXT records of the tree when published at the given domain, keyed by their
+    /// fully qualified name.
+    pub fn to_txt_records(&self, domain: &str) -> BTreeMap<String, String> {
+        let mut records = BTreeMap::new();
+        records.insert(domain.to_string(), self.root.to_string());
+        for (hash, entry) in &self.entries {
+            records.insert(format!("{hash}.{domain}"), entry.to_string());
+        }
+        records
+    }
+
+    /// Returns the `enrtree://` link of the tree when published at the given domain.
+    pub fn link(&self, domain: &str) -> LinkEntry<SecretKey> {
+        LinkEntry { domain: domain.to_string(), pubkey: self.public_key }
+    }
+
+    /// Arranges the given entries in a tree of branch entries and returns its root.
+    ///
+    /// All entries below the root are added to `out`.
+    fn build_subtree(
+        entries: Vec<DnsEntry<SecretKey>>,
+        out: &mut BTreeMap<String, DnsEntry<SecretKey>>,
+    ) -> DnsEntry<SecretKey> {
+        if entries.len() == 1 {
+            return entries.into_iter().next().expect("exists")
+        }
+        if entries.len() <= MAX_BRANCH_CHILDREN {
+            let children = entries
+                .into_iter()
+                .map(|entry| {
+                    let hash = entry_hash(&entry);
+                    out.insert(hash.clone(), entry);
+                    hash
+                })
+                .collect();
+            return DnsEntry::Branch(BranchEntry { children })
+        }
+
+        let mut entries = entries.into_iter();
+        let mut subtrees = Vec::new();
+        loop {
+            let chunk = entries.by_ref().take(MAX_BRANCH_CHILDREN).collect::<Vec<_>>();
+            if chunk.is_empty() {
+                break
+            }
+            subtrees.push(Self::build_subtree(chunk, out));
+        }
+        Self::build_subtree(subtrees, out)
+    }
+}
+
+/// Returns the subdomain of the entry, the base32 encoded first 16 bytes of the keccak256 hash of
+/// its text.
+fn entry_hash<K: EnrKeyUnambiguous>(entry: &DnsEntry<K>) -> String {
+    BASE32_NOPAD.encode(&keccak256(entry.to_string())[..16])
+}
+
 /// Parses the value of the key value pair
 fn parse_value<F, V>(input: &mut &str, key: &str, err: &'static str, f: F) -> ParseEntryResult<V>
 where
@@ -424,6 +563,55 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn parse_empty_branch_entry() {
+        let s = "enrtree-branch:";
+        let entry: BranchEntry = s.parse().unwrap();
+        assert!(entry.children.is_empty());
+        assert_eq!(entry.to_string(), s);
+    }
+
+    #[test]
+    fn build_tree() {
+        let key = SecretKey::new(&mut secp256k1::rand::thread_rng());
+        let records = (0..30)
+            .map(|_| {
+                let key = SecretKey::new(&mut secp256k1::rand::thread_rng());
+                Enr::builder().build(&key).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let tree = DnsTree::new(records.clone(), vec![], 1, &key);
+        let root = tree.root();
+        assert_eq!(root.signature.len(), 65);
+        assert!(root.verify::<SecretKey>(&key.public_key(SECP256K1)));
+        assert_eq!(root.to_string().parse::<TreeRootEntry>().unwrap(), *root);
+
+        // every record is reachable from the root
+        let mut found = 0;
+        let mut queue = vec![root.enr_root.clone()];
+        while let Some(hash) = queue.pop() {
+            let entry = &tree.entries()[&hash];
+            assert_eq!(entry_hash(entry), hash);
+            match entry.to_string().parse::<DnsEntry<SecretKey>>().unwrap() {
+                DnsEntry::Branch(branch) => {
+                    assert!(branch.children.len() <= MAX_BRANCH_CHILDREN);
+                    queue.extend(branch.children);
+                }
+                DnsEntry::Node(_) => found += 1,
+                _ => unreachable!(),
+            }
+        }
+        assert_eq!(found, records.len());
+
+        let link_root = &tree.entries()[&root.link_root];
+        assert_eq!(link_root.to_string(), "enrtree-branch:");
+
+        let txt = tree.to_txt_records("nodes.example.org");
+        assert_eq!(txt.len(), tree.entries().len() + 1);
+        assert_eq!(txt["nodes.example.org"], root.to_string());
+    }
+
     #[test]
     fn parse_link_entry() {
         let s = "enrtree://AM5FCQLWIZX2QFPNJAP7VUERCCRNGRHWZG3YYHIUV7BVDQ5FDPRT2@nodes.example.org";
@@ -70,6 +70,28 @@ impl From<NewBlockHashes> for Vec<BlockHashNumber> {
     }
 }
 
+/// Announces the range of blocks the peer can serve, introduced in `eth/69`.
+///
+/// This is sent whenever the range changes, but at most once per epoch.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockRangeUpdate {
+    /// The earliest block the peer can serve.
+    pub earliest: u64,
+    /// The latest block of the peer.
+    pub latest: u64,
+    /// The hash of the latest block.
+    pub latest_hash: B256,
+}
+
+impl BlockRangeUpdate {
+    /// Returns `true` if the range is not empty, i.e. `earliest <= latest`.
+    pub const fn is_valid(&self) -> bool {
+        self.earliest <= self.latest
+    }
+}
+
 /// A new block with the current total difficulty, which includes the difficulty of the returned
 /// block.
 #[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
@@ -151,7 +173,7 @@ impl NewPooledTransactionHashes {
                 matches!(version, EthVersion::Eth67 | EthVersion::Eth66)
             }
             NewPooledTransactionHashes::Eth68(_) => {
-                matches!(version, EthVersion::Eth68)
+                matches!(version, EthVersion::Eth68 | EthVersion::Eth69)
             }
         }
     }
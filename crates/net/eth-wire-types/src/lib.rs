@@ -11,7 +11,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 mod status;
-pub use status::{Status, StatusBuilder};
+pub use status::{Status, StatusBuilder, StatusEth69, StatusMessage};
 
 pub mod version;
 pub use version::EthVersion;
@@ -1,4 +1,4 @@
-//! Implements Ethereum wire protocol for versions 66, 67, and 68.
+//! Implements Ethereum wire protocol for versions 66, 67, 68 and 69.
 //! Defines structs/enums for messages, request-response pairs, and broadcasts.
 //! Handles compatibility with [`EthVersion`].
 //!
@@ -7,9 +7,10 @@
 //! Reference: [Ethereum Wire Protocol](https://github.com/ethereum/wiki/wiki/Ethereum-Wire-Protocol).
 
 use super::{
-    broadcast::NewBlockHashes, BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders,
-    GetNodeData, GetPooledTransactions, GetReceipts, NewBlock, NewPooledTransactionHashes66,
-    NewPooledTransactionHashes68, NodeData, PooledTransactions, Receipts, Status, Transactions,
+    broadcast::NewBlockHashes, BlockBodies, BlockHeaders, BlockRangeUpdate, GetBlockBodies,
+    GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts, NewBlock,
+    NewPooledTransactionHashes66, NewPooledTransactionHashes68, NodeData, PooledTransactions,
+    Receipts, Receipts69, Status, StatusEth69, StatusMessage, Transactions,
 };
 use crate::{EthVersion, SharedTransactions};
 
@@ -50,11 +51,26 @@ impl ProtocolMessage {
         let message_type = EthMessageID::decode(buf)?;
 
         let message = match message_type {
-            EthMessageID::Status => EthMessage::Status(Status::decode(buf)?),
+            EthMessageID::Status => {
+                let status = if version >= EthVersion::Eth69 {
+                    StatusMessage::Eth69(StatusEth69::decode(buf)?)
+                } else {
+                    StatusMessage::Legacy(Status::decode(buf)?)
+                };
+                EthMessage::Status(status)
+            }
             EthMessageID::NewBlockHashes => {
+                if version >= EthVersion::Eth69 {
+                    return Err(MessageError::Invalid(version, EthMessageID::NewBlockHashes))
+                }
                 EthMessage::NewBlockHashes(NewBlockHashes::decode(buf)?)
             }
-            EthMessageID::NewBlock => EthMessage::NewBlock(Box::new(NewBlock::decode(buf)?)),
+            EthMessageID::NewBlock => {
+                if version >= EthVersion::Eth69 {
+                    return Err(MessageError::Invalid(version, EthMessageID::NewBlock))
+                }
+                EthMessage::NewBlock(Box::new(NewBlock::decode(buf)?))
+            }
             EthMessageID::Transactions => EthMessage::Transactions(Transactions::decode(buf)?),
             EthMessageID::NewPooledTransactionHashes => {
                 if version >= EthVersion::Eth68 {
@@ -110,8 +126,17 @@ impl ProtocolMessage {
                 EthMessage::GetReceipts(request_pair)
             }
             EthMessageID::Receipts => {
-                let request_pair = RequestPair::<Receipts>::decode(buf)?;
-                EthMessage::Receipts(request_pair)
+                if version >= EthVersion::Eth69 {
+                    EthMessage::Receipts69(RequestPair::<Receipts69>::decode(buf)?)
+                } else {
+                    EthMessage::Receipts(RequestPair::<Receipts>::decode(buf)?)
+                }
+            }
+            EthMessageID::BlockRangeUpdate => {
+                if version < EthVersion::Eth69 {
+                    return Err(MessageError::Invalid(version, EthMessageID::BlockRangeUpdate))
+                }
+                EthMessage::BlockRangeUpdate(BlockRangeUpdate::decode(buf)?)
             }
         };
         Ok(ProtocolMessage { message_type, message })
@@ -164,7 +189,7 @@ impl From<EthBroadcastMessage> for ProtocolBroadcastMessage {
     }
 }
 
-/// Represents a message in the eth wire protocol, versions 66, 67 and 68.
+/// Represents a message in the eth wire protocol, versions 66, 67, 68 and 69.
 ///
 /// The ethereum wire protocol is a set of messages that are broadcast to the network in two
 /// styles:
@@ -181,11 +206,16 @@ impl From<EthBroadcastMessage> for ProtocolBroadcastMessage {
 /// The `eth/68` changes only NewPooledTransactionHashes to include `types` and `sized`. For
 /// it, NewPooledTransactionHashes is renamed as [`NewPooledTransactionHashes66`] and
 /// [`NewPooledTransactionHashes68`] is defined.
+///
+/// The `eth/69` replaces the total difficulty in the [`Status`] with the range of blocks the peer
+/// can serve, see [`StatusEth69`], which is updated via [`BlockRangeUpdate`]. It removes the bloom
+/// filter from receipts, see [`Receipts69`], and the [`NewBlockHashes`] and [`NewBlock`]
+/// announcements.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum EthMessage {
     /// Represents a Status message required for the protocol handshake.
-    Status(Status),
+    Status(StatusMessage),
     /// Represents a NewBlockHashes message broadcast to the network.
     NewBlockHashes(NewBlockHashes),
     /// Represents a NewBlock message broadcast to the network.
@@ -217,6 +247,10 @@ pub enum EthMessage {
     GetReceipts(RequestPair<GetReceipts>),
     /// Represents a Receipts request-response pair.
     Receipts(RequestPair<Receipts>),
+    /// Represents a Receipts request-response pair for eth/69 version.
+    Receipts69(RequestPair<Receipts69>),
+    /// Represents a BlockRangeUpdate message broadcast to the network, since eth/69.
+    BlockRangeUpdate(BlockRangeUpdate),
 }
 
 impl EthMessage {
@@ -238,7 +272,8 @@ impl EthMessage {
             EthMessage::GetNodeData(_) => EthMessageID::GetNodeData,
             EthMessage::NodeData(_) => EthMessageID::NodeData,
             EthMessage::GetReceipts(_) => EthMessageID::GetReceipts,
-            EthMessage::Receipts(_) => EthMessageID::Receipts,
+            EthMessage::Receipts(_) | EthMessage::Receipts69(_) => EthMessageID::Receipts,
+            EthMessage::BlockRangeUpdate(_) => EthMessageID::BlockRangeUpdate,
         }
     }
 }
@@ -262,6 +297,8 @@ impl Encodable for EthMessage {
             EthMessage::NodeData(data) => data.encode(out),
             EthMessage::GetReceipts(request) => request.encode(out),
             EthMessage::Receipts(receipts) => receipts.encode(out),
+            EthMessage::Receipts69(receipts) => receipts.encode(out),
+            EthMessage::BlockRangeUpdate(update) => update.encode(out),
         }
     }
     fn length(&self) -> usize {
@@ -282,6 +319,8 @@ impl Encodable for EthMessage {
             EthMessage::NodeData(data) => data.length(),
             EthMessage::GetReceipts(request) => request.length(),
             EthMessage::Receipts(receipts) => receipts.length(),
+            EthMessage::Receipts69(receipts) => receipts.length(),
+            EthMessage::BlockRangeUpdate(update) => update.length(),
         }
     }
 }
@@ -364,12 +403,29 @@ pub enum EthMessageID {
     GetReceipts = 0x0f,
     /// Represents receipts.
     Receipts = 0x10,
+    /// Block range update, since eth/69.
+    BlockRangeUpdate = 0x11,
 }
 
 impl EthMessageID {
-    /// Returns the max value.
-    pub const fn max() -> u8 {
-        Self::Receipts as u8
+    /// Returns the max value for the given version.
+    ///
+    /// Since `eth/69` this is [`EthMessageID::BlockRangeUpdate`], before it is
+    /// [`EthMessageID::Receipts`].
+    pub const fn max(version: EthVersion) -> u8 {
+        if version.is_eth69() {
+            Self::BlockRangeUpdate as u8
+        } else {
+            Self::Receipts as u8
+        }
+    }
+
+    /// Returns the number of message IDs used by the given version.
+    ///
+    /// The IDs of messages that are not supported by the version, like
+    /// [`EthMessageID::GetNodeData`] since `eth/67`, are still reserved.
+    pub const fn message_count(version: EthVersion) -> u8 {
+        Self::max(version) + 1
     }
 }
 
@@ -400,6 +456,7 @@ impl Decodable for EthMessageID {
             0x0e => EthMessageID::NodeData,
             0x0f => EthMessageID::GetReceipts,
             0x10 => EthMessageID::Receipts,
+            0x11 => EthMessageID::BlockRangeUpdate,
             _ => return Err(alloy_rlp::Error::Custom("Invalid message ID")),
         };
         buf.advance(1);
@@ -427,6 +484,7 @@ impl TryFrom<usize> for EthMessageID {
             0x0e => Ok(EthMessageID::NodeData),
             0x0f => Ok(EthMessageID::GetReceipts),
             0x10 => Ok(EthMessageID::Receipts),
+            0x11 => Ok(EthMessageID::BlockRangeUpdate),
             _ => Err("Invalid message ID"),
         }
     }
@@ -495,7 +553,8 @@ where
 mod tests {
     use super::MessageError;
     use crate::{
-        message::RequestPair, EthMessage, EthMessageID, GetNodeData, NodeData, ProtocolMessage,
+        message::RequestPair, BlockRangeUpdate, EthMessage, EthMessageID, EthVersion, GetNodeData,
+        NewBlockHashes, NodeData, ProtocolMessage, Receipts, Receipts69, Status, StatusMessage,
     };
     use alloy_rlp::{Decodable, Encodable, Error};
     use reth_primitives::hex;
@@ -525,6 +584,41 @@ mod tests {
         assert!(matches!(msg, Err(MessageError::Invalid(..))));
     }
 
+    #[test]
+    fn test_eth69_messages() {
+        let new_block_hashes = EthMessage::NewBlockHashes(NewBlockHashes(vec![]));
+        let buf = encode(ProtocolMessage::from(new_block_hashes.clone()));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]);
+        assert!(matches!(msg, Err(MessageError::Invalid(..))));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth68, &mut &buf[..]).unwrap();
+        assert_eq!(msg.message, new_block_hashes);
+
+        let update = EthMessage::BlockRangeUpdate(BlockRangeUpdate {
+            earliest: 0,
+            latest: 100,
+            latest_hash: Default::default(),
+        });
+        let buf = encode(ProtocolMessage::from(update.clone()));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth68, &mut &buf[..]);
+        assert!(matches!(msg, Err(MessageError::Invalid(..))));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]).unwrap();
+        assert_eq!(msg.message, update);
+
+        let receipts = EthMessage::Receipts69(RequestPair {
+            request_id: 1,
+            message: Receipts69::from(Receipts(vec![vec![]])),
+        });
+        let buf = encode(ProtocolMessage::from(receipts.clone()));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]).unwrap();
+        assert_eq!(msg.message, receipts);
+
+        let status = Status { version: EthVersion::Eth69 as u8, ..Default::default() }
+            .into_eth69(BlockRangeUpdate::default());
+        let buf = encode(ProtocolMessage::from(EthMessage::Status(status.into())));
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]).unwrap();
+        assert_eq!(msg.message, EthMessage::Status(StatusMessage::Eth69(status)));
+    }
+
     #[test]
     fn request_pair_encode() {
         let request_pair = RequestPair { request_id: 1337, message: vec![5u8] };
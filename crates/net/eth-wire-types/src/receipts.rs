@@ -1,8 +1,8 @@
 //! Implements the `GetReceipts` and `Receipts` message types.
 
-use alloy_rlp::{RlpDecodableWrapper, RlpEncodableWrapper};
+use alloy_rlp::{BufMut, Decodable, Encodable, Header, RlpDecodableWrapper, RlpEncodableWrapper};
 use reth_codecs::derive_arbitrary;
-use reth_primitives::{ReceiptWithBloom, B256};
+use reth_primitives::{Receipt, ReceiptWithBloom, TxType, B256};
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -32,9 +32,114 @@ pub struct Receipts(
     pub Vec<Vec<ReceiptWithBloom>>,
 );
 
+/// The response to [`GetReceipts`] in `eth/69`, which omits the bloom filters of the receipts.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodableWrapper, RlpDecodableWrapper, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Receipts69(
+    /// Each receipt hash should correspond to a block hash in the request.
+    pub Vec<Vec<Receipt69>>,
+);
+
+impl Receipts69 {
+    /// Converts the receipts into [`Receipts`] by computing the bloom filter of every receipt.
+    pub fn into_receipts_with_bloom(self) -> Receipts {
+        Receipts(
+            self.0
+                .into_iter()
+                .map(|receipts| {
+                    receipts.into_iter().map(|receipt| receipt.0.with_bloom()).collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl From<Receipts> for Receipts69 {
+    fn from(receipts: Receipts) -> Self {
+        Self(
+            receipts
+                .0
+                .into_iter()
+                .map(|receipts| {
+                    receipts.into_iter().map(|receipt| Receipt69(receipt.receipt)).collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+/// A [`Receipt`] in the `eth/69` encoding.
+///
+/// Receipts of all transaction types are encoded as the list
+/// `[tx-type, post-state-or-status, cumulative-gas, logs]`, without the bloom filter.
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Receipt69(pub Receipt);
+
+impl Receipt69 {
+    fn payload_length(&self) -> usize {
+        u8::from(self.0.tx_type).length() +
+            self.0.success.length() +
+            self.0.cumulative_gas_used.length() +
+            self.0.logs.length()
+    }
+}
+
+impl From<Receipt> for Receipt69 {
+    fn from(receipt: Receipt) -> Self {
+        Self(receipt)
+    }
+}
+
+impl Encodable for Receipt69 {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        u8::from(self.0.tx_type).encode(out);
+        self.0.success.encode(out);
+        self.0.cumulative_gas_used.encode(out);
+        self.0.logs.encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for Receipt69 {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let tx_type = TxType::try_from(u8::decode(buf)?).map_err(alloy_rlp::Error::Custom)?;
+        let receipt = Receipt {
+            tx_type,
+            success: Decodable::decode(buf)?,
+            cumulative_gas_used: Decodable::decode(buf)?,
+            logs: Decodable::decode(buf)?,
+            #[cfg(feature = "optimism")]
+            deposit_nonce: None,
+            #[cfg(feature = "optimism")]
+            deposit_receipt_version: None,
+        };
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+        Ok(Self(receipt))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{message::RequestPair, GetReceipts, Receipts};
+    use crate::{message::RequestPair, GetReceipts, Receipt69, Receipts, Receipts69};
     use alloy_rlp::{Decodable, Encodable};
     use reth_primitives::{hex, Log, Receipt, ReceiptWithBloom, TxType};
 
@@ -63,6 +168,32 @@ mod tests {
         assert_eq!(receipts, decoded);
     }
 
+    #[test]
+    fn roundtrip_eth69() {
+        let receipt = Receipt {
+            tx_type: TxType::Legacy,
+            success: true,
+            cumulative_gas_used: 21_000,
+            logs: vec![Log { address: Default::default(), topics: vec![], data: vec![1].into() }],
+            #[cfg(feature = "optimism")]
+            deposit_nonce: None,
+            #[cfg(feature = "optimism")]
+            deposit_receipt_version: None,
+        };
+        let receipts = Receipts69(vec![vec![Receipt69(receipt.clone())], vec![]]);
+
+        let mut out = vec![];
+        receipts.encode(&mut out);
+        assert_eq!(out.len(), receipts.length());
+
+        let decoded = Receipts69::decode(&mut out.as_slice()).unwrap();
+        assert_eq!(receipts, decoded);
+
+        let with_bloom = decoded.into_receipts_with_bloom();
+        assert_eq!(with_bloom.0[0][0], receipt.with_bloom());
+        assert_eq!(Receipts69::from(with_bloom), receipts);
+    }
+
     #[test]
     // Test vector from: https://eips.ethereum.org/EIPS/eip-2481
     fn encode_get_receipts() {
@@ -1,5 +1,5 @@
-use crate::EthVersion;
-use alloy_rlp::{RlpDecodable, RlpEncodable};
+use crate::{BlockRangeUpdate, EthVersion};
+use alloy_rlp::{BufMut, Encodable, RlpDecodable, RlpEncodable};
 use reth_codecs::derive_arbitrary;
 use reth_primitives::{
     hex, Chain, ChainSpec, ForkId, Genesis, Hardfork, Head, NamedChain, B256, MAINNET, U256,
@@ -70,6 +70,20 @@ impl Status {
         self.version = version as u8;
     }
 
+    /// Converts the status into the `eth/69` [`StatusEth69`] that announces the given range of
+    /// blocks instead of the total difficulty.
+    pub fn into_eth69(self, block_range: BlockRangeUpdate) -> StatusEth69 {
+        StatusEth69 {
+            version: self.version,
+            chain: self.chain,
+            genesis: self.genesis,
+            forkid: self.forkid,
+            earliest: block_range.earliest,
+            latest: block_range.latest,
+            blockhash: block_range.latest_hash,
+        }
+    }
+
     /// Create a [`StatusBuilder`] from the given [`ChainSpec`] and head block.
     ///
     /// Sets the `chain` and `genesis`, `blockhash`, and `forkid` fields based on the [`ChainSpec`]
@@ -148,6 +162,182 @@ impl Default for Status {
     }
 }
 
+/// The status message of `eth/69`, which replaces the total difficulty of [`Status`] with the
+/// range of blocks the peer can serve.
+///
+/// See also <https://eips.ethereum.org/EIPS/eip-7642>
+#[derive_arbitrary(rlp)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct StatusEth69 {
+    /// The current protocol version, 69.
+    pub version: u8,
+    /// The chain id, as introduced in
+    /// [EIP155](https://eips.ethereum.org/EIPS/eip-155#list-of-chain-ids).
+    pub chain: Chain,
+    /// The genesis hash of the peer's chain.
+    pub genesis: B256,
+    /// The fork identifier as defined by
+    /// [EIP-2124](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-2124.md).
+    pub forkid: ForkId,
+    /// The earliest block the peer can serve.
+    pub earliest: u64,
+    /// The latest block of the peer.
+    pub latest: u64,
+    /// The hash of the latest block.
+    pub blockhash: B256,
+}
+
+impl StatusEth69 {
+    /// Returns the announced range of blocks the peer can serve.
+    pub const fn block_range(&self) -> BlockRangeUpdate {
+        BlockRangeUpdate {
+            earliest: self.earliest,
+            latest: self.latest,
+            latest_hash: self.blockhash,
+        }
+    }
+}
+
+impl Display for StatusEth69 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Status {{ version: {}, chain: {}, genesis: {}, forkid: {:X?}, earliest: {}, latest: {}, blockhash: {} }}",
+            self.version,
+            self.chain,
+            hex::encode(self.genesis),
+            self.forkid,
+            self.earliest,
+            self.latest,
+            hex::encode(self.blockhash),
+        )
+    }
+}
+
+/// The status message exchanged in the `eth` handshake, whose format depends on the negotiated
+/// [`EthVersion`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum StatusMessage {
+    /// The status message of `eth/66` to `eth/68`.
+    Legacy(Status),
+    /// The status message of `eth/69`.
+    Eth69(StatusEth69),
+}
+
+impl StatusMessage {
+    /// Returns the protocol version.
+    pub const fn version(&self) -> u8 {
+        match self {
+            Self::Legacy(status) => status.version,
+            Self::Eth69(status) => status.version,
+        }
+    }
+
+    /// Returns the chain id.
+    pub const fn chain(&self) -> &Chain {
+        match self {
+            Self::Legacy(status) => &status.chain,
+            Self::Eth69(status) => &status.chain,
+        }
+    }
+
+    /// Returns the genesis hash.
+    pub const fn genesis(&self) -> B256 {
+        match self {
+            Self::Legacy(status) => status.genesis,
+            Self::Eth69(status) => status.genesis,
+        }
+    }
+
+    /// Returns the fork id.
+    pub const fn forkid(&self) -> ForkId {
+        match self {
+            Self::Legacy(status) => status.forkid,
+            Self::Eth69(status) => status.forkid,
+        }
+    }
+
+    /// Returns the hash of the best block.
+    pub const fn blockhash(&self) -> B256 {
+        match self {
+            Self::Legacy(status) => status.blockhash,
+            Self::Eth69(status) => status.blockhash,
+        }
+    }
+
+    /// Returns the total difficulty, which is not part of the `eth/69` status.
+    pub const fn total_difficulty(&self) -> Option<U256> {
+        match self {
+            Self::Legacy(status) => Some(status.total_difficulty),
+            Self::Eth69(_) => None,
+        }
+    }
+
+    /// Returns the announced range of blocks, which is only part of the `eth/69` status.
+    pub const fn block_range(&self) -> Option<BlockRangeUpdate> {
+        match self {
+            Self::Legacy(_) => None,
+            Self::Eth69(status) => Some(status.block_range()),
+        }
+    }
+
+    /// Converts the message into a [`Status`].
+    ///
+    /// Note: the total difficulty is zero for `eth/69`.
+    pub const fn into_legacy(self) -> Status {
+        match self {
+            Self::Legacy(status) => status,
+            Self::Eth69(status) => Status {
+                version: status.version,
+                chain: status.chain,
+                total_difficulty: U256::ZERO,
+                blockhash: status.blockhash,
+                genesis: status.genesis,
+                forkid: status.forkid,
+            },
+        }
+    }
+}
+
+impl Encodable for StatusMessage {
+    fn encode(&self, out: &mut dyn BufMut) {
+        match self {
+            Self::Legacy(status) => status.encode(out),
+            Self::Eth69(status) => status.encode(out),
+        }
+    }
+
+    fn length(&self) -> usize {
+        match self {
+            Self::Legacy(status) => status.length(),
+            Self::Eth69(status) => status.length(),
+        }
+    }
+}
+
+impl Display for StatusMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Legacy(status) => Display::fmt(status, f),
+            Self::Eth69(status) => Display::fmt(status, f),
+        }
+    }
+}
+
+impl From<Status> for StatusMessage {
+    fn from(status: Status) -> Self {
+        Self::Legacy(status)
+    }
+}
+
+impl From<StatusEth69> for StatusMessage {
+    fn from(status: StatusEth69) -> Self {
+        Self::Eth69(status)
+    }
+}
+
 /// Builder for [`Status`] messages.
 ///
 /// # Example
@@ -227,7 +417,7 @@ impl StatusBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::{EthVersion, Status};
+    use crate::{BlockRangeUpdate, EthVersion, Status, StatusEth69};
     use alloy_rlp::{Decodable, Encodable};
     use rand::Rng;
     use reth_primitives::{
@@ -280,6 +470,23 @@ mod tests {
         assert_eq!(status, expected);
     }
 
+    #[test]
+    fn eth69_status_roundtrip() {
+        let status = Status { version: EthVersion::Eth69 as u8, ..Default::default() };
+        let block_range = BlockRangeUpdate {
+            earliest: 0,
+            latest: 19_000_000,
+            latest_hash: B256::with_last_byte(1),
+        };
+        let status = status.into_eth69(block_range);
+        assert_eq!(status.block_range(), block_range);
+
+        let mut rlp_status = vec![];
+        status.encode(&mut rlp_status);
+        assert_eq!(StatusEth69::decode(&mut &rlp_status[..]).unwrap(), status);
+        assert!(Status::decode(&mut &rlp_status[..]).is_err());
+    }
+
     #[test]
     fn encode_network_status_message() {
         let expected = hex!("f850423884024190faa0f8514c4680ef27700751b08f37645309ce65a449616a3ea966bf39dd935bb27ba00d21840abff46b96c84b2ac9e10e4f5cdaeb5693cb665db62a2f3b02d2d57b5bc6845d43d2fd80");
@@ -0,0 +1,304 @@
+//! Bandwidth limits for [`P2PStream`](crate::P2PStream)s.
+//!
+//! Limits are enforced with token buckets that refill at the configured rate. A message is only
+//! sent or returned once all relevant buckets are out of debt, afterwards its size is taken from
+//! the buckets, which may put them into debt. This way messages larger than the burst size of a
+//! bucket can still pass, but the average rate is kept.
+//!
+//! The debt of a bucket is capped at [`MAX_DEBT`] worth of tokens, so a single large message never
+//! stalls a session for longer than that. Reserved `p2p` messages, like pings, are accounted for
+//! but never delayed.
+
+use crate::capability::SharedCapabilities;
+use reth_eth_wire_types::EthMessageID;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::time::Instant;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The maximum debt of a token bucket, as the time it takes to pay it off.
+///
+/// This must stay well below the `p2p` ping timeout.
+pub const MAX_DEBT: Duration = Duration::from_secs(5);
+
+/// Byte rate limits of the `RLPx` sessions, in bytes per second.
+///
+/// `None` means unlimited, which is the default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct BandwidthLimits {
+    /// Maximum rate at which messages are read from all peers combined.
+    pub global_ingress: Option<u64>,
+    /// Maximum rate at which messages are sent to all peers combined.
+    pub global_egress: Option<u64>,
+    /// Maximum rate at which messages are read from a single peer.
+    pub peer_ingress: Option<u64>,
+    /// Maximum rate at which messages are sent to a single peer.
+    pub peer_egress: Option<u64>,
+    /// Maximum rate at which transaction gossip is sent to all peers combined.
+    ///
+    /// See [`TrafficClass::TxGossip`].
+    pub tx_gossip_egress: Option<u64>,
+    /// Maximum rate at which blocks, headers and receipts are served to all peers combined.
+    ///
+    /// See [`TrafficClass::BlockServing`].
+    pub block_serving_egress: Option<u64>,
+}
+
+impl BandwidthLimits {
+    /// Returns `true` if no limit is configured.
+    pub fn is_unlimited(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// The classes of messages with separate bandwidth budgets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrafficClass {
+    /// Transaction announcements, requests and bodies.
+    TxGossip,
+    /// Responses to block header, block body, receipt and node data requests.
+    BlockServing,
+    /// All other messages.
+    Other,
+}
+
+impl TrafficClass {
+    /// Returns the class of the given `eth` message.
+    pub const fn of_eth_message(id: EthMessageID) -> Self {
+        match id {
+            EthMessageID::Transactions |
+            EthMessageID::NewPooledTransactionHashes |
+            EthMessageID::GetPooledTransactions |
+            EthMessageID::PooledTransactions => Self::TxGossip,
+            EthMessageID::BlockHeaders |
+            EthMessageID::BlockBodies |
+            EthMessageID::NodeData |
+            EthMessageID::Receipts => Self::BlockServing,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// Returns the [`TrafficClass`] and the name used for metrics of the message with the given
+/// multiplexed message id, which starts with the `p2p` reserved message ids.
+pub(crate) fn classify_message(
+    shared_capabilities: &SharedCapabilities,
+    id: u8,
+) -> (TrafficClass, &'static str) {
+    let Some(cap) = shared_capabilities.find_by_offset(id) else {
+        return (TrafficClass::Other, "p2p")
+    };
+    if !cap.is_eth() {
+        return (TrafficClass::Other, "other")
+    }
+    match EthMessageID::try_from((id - cap.message_id_offset()) as usize) {
+        Ok(id) => (TrafficClass::of_eth_message(id), eth_message_name(id)),
+        Err(_) => (TrafficClass::Other, "unknown"),
+    }
+}
+
+/// Returns the name of the `eth` message used for metrics.
+const fn eth_message_name(id: EthMessageID) -> &'static str {
+    match id {
+        EthMessageID::Status => "status",
+        EthMessageID::NewBlockHashes => "new_block_hashes",
+        EthMessageID::Transactions => "transactions",
+        EthMessageID::GetBlockHeaders => "get_block_headers",
+        EthMessageID::BlockHeaders => "block_headers",
+        EthMessageID::GetBlockBodies => "get_block_bodies",
+        EthMessageID::BlockBodies => "block_bodies",
+        EthMessageID::NewBlock => "new_block",
+        EthMessageID::NewPooledTransactionHashes => "new_pooled_transaction_hashes",
+        EthMessageID::GetPooledTransactions => "get_pooled_transactions",
+        EthMessageID::PooledTransactions => "pooled_transactions",
+        EthMessageID::GetNodeData => "get_node_data",
+        EthMessageID::NodeData => "node_data",
+        EthMessageID::GetReceipts => "get_receipts",
+        EthMessageID::Receipts => "receipts",
+        EthMessageID::BlockRangeUpdate => "block_range_update",
+    }
+}
+
+/// Enforces the [`BandwidthLimits`] that are shared by all sessions.
+///
+/// This type is cheap to clone, all clones share the same budgets. Use
+/// [`BandwidthLimiter::peer_limiter`] to create the limiter of a single session.
+#[derive(Debug, Clone, Default)]
+pub struct BandwidthLimiter {
+    limits: BandwidthLimits,
+    global_ingress: Option<TokenBucket>,
+    global_egress: Option<TokenBucket>,
+    tx_gossip_egress: Option<TokenBucket>,
+    block_serving_egress: Option<TokenBucket>,
+}
+
+impl BandwidthLimiter {
+    /// Creates a new limiter that enforces the given limits.
+    pub fn new(limits: BandwidthLimits) -> Self {
+        Self {
+            limits,
+            global_ingress: limits.global_ingress.map(TokenBucket::new),
+            global_egress: limits.global_egress.map(TokenBucket::new),
+            tx_gossip_egress: limits.tx_gossip_egress.map(TokenBucket::new),
+            block_serving_egress: limits.block_serving_egress.map(TokenBucket::new),
+        }
+    }
+
+    /// Returns the configured limits.
+    pub const fn limits(&self) -> &BandwidthLimits {
+        &self.limits
+    }
+
+    /// Returns a new limiter for a single session, which enforces the per-peer limits in
+    /// addition to the shared limits.
+    pub fn peer_limiter(&self) -> PeerBandwidthLimiter {
+        PeerBandwidthLimiter {
+            ingress: self.limits.peer_ingress.map(TokenBucket::new),
+            egress: self.limits.peer_egress.map(TokenBucket::new),
+            shared: self.clone(),
+        }
+    }
+}
+
+/// Enforces the [`BandwidthLimits`] of a single session.
+#[derive(Debug, Clone, Default)]
+pub struct PeerBandwidthLimiter {
+    ingress: Option<TokenBucket>,
+    egress: Option<TokenBucket>,
+    shared: BandwidthLimiter,
+}
+
+impl PeerBandwidthLimiter {
+    /// Returns how long to wait until the next message can be read, if at all.
+    pub(crate) fn ingress_delay(&self) -> Option<Duration> {
+        self.ingress_buckets().filter_map(TokenBucket::delay).max()
+    }
+
+    /// Records a read message of the given size.
+    pub(crate) fn on_ingress(&self, num_bytes: usize) {
+        self.ingress_buckets().for_each(|bucket| bucket.consume(num_bytes));
+    }
+
+    /// Returns how long to wait until the next message of the given class can be sent, if at all.
+    pub(crate) fn egress_delay(&self, class: TrafficClass) -> Option<Duration> {
+        self.egress_buckets(class).filter_map(TokenBucket::delay).max()
+    }
+
+    /// Records a sent message of the given class and size.
+    pub(crate) fn on_egress(&self, class: TrafficClass, num_bytes: usize) {
+        self.egress_buckets(class).for_each(|bucket| bucket.consume(num_bytes));
+    }
+
+    fn ingress_buckets(&self) -> impl Iterator<Item = &TokenBucket> {
+        self.ingress.iter().chain(self.shared.global_ingress.iter())
+    }
+
+    fn egress_buckets(&self, class: TrafficClass) -> impl Iterator<Item = &TokenBucket> {
+        let class_bucket = match class {
+            TrafficClass::TxGossip => self.shared.tx_gossip_egress.as_ref(),
+            TrafficClass::BlockServing => self.shared.block_serving_egress.as_ref(),
+            TrafficClass::Other => None,
+        };
+        self.egress.iter().chain(self.shared.global_egress.iter()).chain(class_bucket)
+    }
+}
+
+/// A token bucket that holds up to one second worth of tokens and can go into up to [`MAX_DEBT`]
+/// worth of debt.
+#[derive(Debug, Clone)]
+struct TokenBucket(Arc<Mutex<TokenBucketState>>);
+
+#[derive(Debug)]
+struct TokenBucketState {
+    /// Tokens per second.
+    rate: f64,
+    /// Currently available tokens, negative if in debt.
+    tokens: f64,
+    /// When the tokens were last refilled.
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_second: u64) -> Self {
+        let rate = bytes_per_second.max(1) as f64;
+        Self(Arc::new(Mutex::new(TokenBucketState {
+            rate,
+            tokens: rate,
+            last_refill: Instant::now(),
+        })))
+    }
+
+    /// Returns how long it takes until the bucket is out of debt, if it is in debt.
+    fn delay(&self) -> Option<Duration> {
+        let mut state = self.0.lock().expect("not poisoned");
+        state.refill();
+        (state.tokens < 0.).then(|| Duration::from_secs_f64(-state.tokens / state.rate))
+    }
+
+    /// Takes the given number of tokens from the bucket, without exceeding the [`MAX_DEBT`].
+    fn consume(&self, num_bytes: usize) {
+        let mut state = self.0.lock().expect("not poisoned");
+        state.refill();
+        let max_debt = state.rate * MAX_DEBT.as_secs_f64();
+        state.tokens = (state.tokens - num_bytes as f64).max(-max_debt);
+    }
+}
+
+impl TokenBucketState {
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate);
+        self.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket_debt() {
+        let bucket = TokenBucket::new(100);
+        assert_eq!(bucket.delay(), None);
+
+        bucket.consume(100);
+        bucket.consume(200);
+        let delay = bucket.delay().unwrap();
+        assert!(delay > Duration::from_millis(1_900) && delay <= Duration::from_secs(2));
+    }
+
+    #[test]
+    fn token_bucket_debt_is_capped() {
+        let bucket = TokenBucket::new(100);
+
+        bucket.consume(100 * 60);
+        let delay = bucket.delay().unwrap();
+        assert!(delay > MAX_DEBT - Duration::from_millis(100) && delay <= MAX_DEBT);
+    }
+
+    #[test]
+    fn shared_and_class_budgets() {
+        let limiter = BandwidthLimiter::new(BandwidthLimits {
+            tx_gossip_egress: Some(100),
+            peer_ingress: Some(100),
+            ..Default::default()
+        });
+        let peer_a = limiter.peer_limiter();
+        let peer_b = limiter.peer_limiter();
+
+        peer_a.on_egress(TrafficClass::TxGossip, 200);
+        assert!(peer_b.egress_delay(TrafficClass::TxGossip).is_some());
+        assert_eq!(peer_b.egress_delay(TrafficClass::BlockServing), None);
+
+        peer_a.on_ingress(200);
+        assert!(peer_a.ingress_delay().is_some());
+        assert_eq!(peer_b.ingress_delay(), None);
+    }
+}
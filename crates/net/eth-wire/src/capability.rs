@@ -82,6 +82,11 @@ impl Capability {
         Self::eth(EthVersion::Eth68)
     }
 
+    /// Returns the [EthVersion::Eth69] capability.
+    pub const fn eth_69() -> Self {
+        Self::eth(EthVersion::Eth69)
+    }
+
     /// Whether this is eth v66 protocol.
     #[inline]
     pub fn is_eth_v66(&self) -> bool {
@@ -100,10 +105,16 @@ impl Capability {
         self.name == "eth" && self.version == 68
     }
 
+    /// Whether this is eth v69.
+    #[inline]
+    pub fn is_eth_v69(&self) -> bool {
+        self.name == "eth" && self.version == 69
+    }
+
     /// Whether this is any eth version.
     #[inline]
     pub fn is_eth(&self) -> bool {
-        self.is_eth_v66() || self.is_eth_v67() || self.is_eth_v68()
+        self.is_eth_v66() || self.is_eth_v67() || self.is_eth_v68() || self.is_eth_v69()
     }
 }
 
@@ -153,6 +164,7 @@ pub struct Capabilities {
     eth_66: bool,
     eth_67: bool,
     eth_68: bool,
+    eth_69: bool,
 }
 
 impl Capabilities {
@@ -171,7 +183,7 @@ impl Capabilities {
     /// Whether the peer supports `eth` sub-protocol.
     #[inline]
     pub fn supports_eth(&self) -> bool {
-        self.eth_68 || self.eth_67 || self.eth_66
+        self.eth_69 || self.eth_68 || self.eth_67 || self.eth_66
     }
 
     /// Whether this peer supports eth v66 protocol.
@@ -191,6 +203,12 @@ impl Capabilities {
     pub fn supports_eth_v68(&self) -> bool {
         self.eth_68
     }
+
+    /// Whether this peer supports eth v69 protocol.
+    #[inline]
+    pub fn supports_eth_v69(&self) -> bool {
+        self.eth_69
+    }
 }
 
 impl From<Vec<Capability>> for Capabilities {
@@ -199,6 +217,7 @@ impl From<Vec<Capability>> for Capabilities {
             eth_66: value.iter().any(Capability::is_eth_v66),
             eth_67: value.iter().any(Capability::is_eth_v67),
             eth_68: value.iter().any(Capability::is_eth_v68),
+            eth_69: value.iter().any(Capability::is_eth_v69),
             inner: value,
         }
     }
@@ -218,6 +237,7 @@ impl Decodable for Capabilities {
             eth_66: inner.iter().any(Capability::is_eth_v66),
             eth_67: inner.iter().any(Capability::is_eth_v67),
             eth_68: inner.iter().any(Capability::is_eth_v68),
+            eth_69: inner.iter().any(Capability::is_eth_v69),
             inner,
         })
     }
@@ -345,7 +365,7 @@ impl SharedCapability {
     /// Returns the number of protocol messages supported by this capability.
     pub fn num_messages(&self) -> u8 {
         match self {
-            SharedCapability::Eth { version: _version, .. } => EthMessageID::max() + 1,
+            SharedCapability::Eth { version, .. } => EthMessageID::message_count(*version),
             SharedCapability::UnknownCapability { messages, .. } => *messages,
         }
     }
@@ -700,6 +720,24 @@ mod tests {
         assert!(shared.find_by_offset(MAX_RESERVED_MESSAGE_ID).is_none());
     }
 
+    #[test]
+    fn test_eth_message_id_space_by_version() {
+        let cap = Capability::new_static("zzz", 1);
+        for (version, messages) in [(EthVersion::Eth68, 17), (EthVersion::Eth69, 18)] {
+            let local_capabilities = vec![version.into(), Protocol::new(cap.clone(), 5)];
+            let peer_capabilities = vec![version.into(), cap.clone()];
+
+            let shared =
+                SharedCapabilities::try_new(local_capabilities, peer_capabilities).unwrap();
+            assert_eq!(shared.eth().unwrap().num_messages(), messages);
+            assert_eq!(Protocol::from(version).messages(), messages);
+
+            // the message ids of the next capability start after the ones of eth
+            assert_eq!(shared.find_by_relative_offset(messages - 1).unwrap().name(), "eth");
+            assert_eq!(shared.find_by_relative_offset(messages).unwrap().name(), "zzz");
+        }
+    }
+
     #[test]
     fn test_find_by_offset_many() {
         let cap = Capability::new_static("aaa", 1);
@@ -43,6 +43,14 @@ pub enum EthStreamError {
     /// Error when data is not received from peer for a prolonged period.
     #[error("never received data from remote peer")]
     StreamTimeout,
+    /// Received an `eth/69` block range whose earliest block is after its latest block.
+    #[error("invalid block range: earliest block {earliest} is after latest block {latest}")]
+    InvalidBlockRange {
+        /// The announced earliest block.
+        earliest: u64,
+        /// The announced latest block.
+        latest: u64,
+    },
 }
 
 // === impl EthStreamError ===
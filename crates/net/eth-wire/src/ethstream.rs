@@ -2,7 +2,7 @@ use crate::{
     errors::{EthHandshakeError, EthStreamError},
     message::{EthBroadcastMessage, ProtocolBroadcastMessage},
     p2pstream::HANDSHAKE_TIMEOUT,
-    CanDisconnect, DisconnectReason, EthMessage, EthVersion, ProtocolMessage, Status,
+    CanDisconnect, DisconnectReason, EthMessage, EthVersion, ProtocolMessage, StatusMessage,
 };
 use futures::{ready, Sink, SinkExt, StreamExt};
 use pin_project::pin_project;
@@ -52,22 +52,25 @@ where
     /// Consumes the [`UnauthedEthStream`] and returns an [`EthStream`] after the `Status`
     /// handshake is completed successfully. This also returns the `Status` message sent by the
     /// remote peer.
+    ///
+    /// The given status must match the negotiated version: a [`StatusMessage::Eth69`] for
+    /// `eth/69` and a [`StatusMessage::Legacy`] otherwise.
     pub async fn handshake(
         self,
-        status: Status,
+        status: impl Into<StatusMessage>,
         fork_filter: ForkFilter,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
         self.handshake_with_timeout(status, fork_filter, HANDSHAKE_TIMEOUT).await
     }
 
     /// Wrapper around handshake which enforces a timeout.
     pub async fn handshake_with_timeout(
         self,
-        status: Status,
+        status: impl Into<StatusMessage>,
         fork_filter: ForkFilter,
         timeout_limit: Duration,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
-        timeout(timeout_limit, Self::handshake_without_timeout(self, status, fork_filter))
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
+        timeout(timeout_limit, Self::handshake_without_timeout(self, status.into(), fork_filter))
             .await
             .map_err(|_| EthStreamError::StreamTimeout)?
     }
@@ -75,9 +78,9 @@ where
     /// Handshake with no timeout
     pub async fn handshake_without_timeout(
         mut self,
-        status: Status,
+        status: StatusMessage,
         fork_filter: ForkFilter,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
         trace!(
             %status,
             "sending eth status to peer"
@@ -104,7 +107,7 @@ where
             return Err(EthStreamError::MessageTooBig(their_msg.len()))
         }
 
-        let version = EthVersion::try_from(status.version)?;
+        let version = EthVersion::try_from(status.version())?;
         let msg = match ProtocolMessage::decode_message(version, &mut their_msg.as_ref()) {
             Ok(m) => m,
             Err(err) => {
@@ -122,45 +125,57 @@ where
                     status=%resp,
                     "validating incoming eth status from peer"
                 );
-                if status.genesis != resp.genesis {
+                if status.genesis() != resp.genesis() {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(EthHandshakeError::MismatchedGenesis(
-                        GotExpected { expected: status.genesis, got: resp.genesis }.into(),
+                        GotExpected { expected: status.genesis(), got: resp.genesis() }.into(),
                     )
                     .into())
                 }
 
-                if status.version != resp.version {
+                if status.version() != resp.version() {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(EthHandshakeError::MismatchedProtocolVersion(GotExpected {
-                        got: resp.version,
-                        expected: status.version,
+                        got: resp.version(),
+                        expected: status.version(),
                     })
                     .into())
                 }
 
-                if status.chain != resp.chain {
+                if status.chain() != resp.chain() {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(EthHandshakeError::MismatchedChain(GotExpected {
-                        got: resp.chain,
-                        expected: status.chain,
+                        got: *resp.chain(),
+                        expected: *status.chain(),
                     })
                     .into())
                 }
 
                 // TD at mainnet block #7753254 is 76 bits. If it becomes 100 million times
                 // larger, it will still fit within 100 bits
-                if status.total_difficulty.bit_len() > 100 {
-                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
-                    return Err(EthHandshakeError::TotalDifficultyBitLenTooLarge {
-                        got: status.total_difficulty.bit_len(),
-                        maximum: 100,
+                if let Some(total_difficulty) = status.total_difficulty() {
+                    if total_difficulty.bit_len() > 100 {
+                        self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                        return Err(EthHandshakeError::TotalDifficultyBitLenTooLarge {
+                            got: total_difficulty.bit_len(),
+                            maximum: 100,
+                        }
+                        .into())
+                    }
+                }
+
+                if let Some(block_range) = resp.block_range() {
+                    if !block_range.is_valid() {
+                        self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                        return Err(EthStreamError::InvalidBlockRange {
+                            earliest: block_range.earliest,
+                            latest: block_range.latest,
+                        })
                     }
-                    .into())
                 }
 
                 if let Err(err) =
-                    fork_filter.validate(resp.forkid).map_err(EthHandshakeError::InvalidFork)
+                    fork_filter.validate(resp.forkid()).map_err(EthHandshakeError::InvalidFork)
                 {
                     self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
                     return Err(err.into())
@@ -349,7 +364,8 @@ mod tests {
         broadcast::BlockHashNumber,
         errors::{EthHandshakeError, EthStreamError},
         p2pstream::{ProtocolVersion, UnauthedP2PStream},
-        EthMessage, EthStream, EthVersion, HelloMessageWithProtocols, PassthroughCodec, Status,
+        BlockRangeUpdate, EthMessage, EthStream, EthVersion, HelloMessageWithProtocols,
+        PassthroughCodec, Status, StatusEth69, StatusMessage,
     };
     use futures::{SinkExt, StreamExt};
     use reth_discv4::DEFAULT_DISCOVERY_PORT;
@@ -390,7 +406,7 @@ mod tests {
                 .unwrap();
 
             // just make sure it equals our status (our status is a clone of their status)
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::Legacy(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -401,7 +417,7 @@ mod tests {
             UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
 
         // their status is a clone of our status, these should be equal
-        assert_eq!(their_status, status);
+        assert_eq!(their_status, StatusMessage::Legacy(status));
 
         // wait for it to finish
         handle.await.unwrap();
@@ -437,7 +453,7 @@ mod tests {
                 .unwrap();
 
             // just make sure it equals our status, and that the handshake succeeded
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::Legacy(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -448,7 +464,7 @@ mod tests {
             UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
 
         // their status is a clone of our status, these should be equal
-        assert_eq!(their_status, status);
+        assert_eq!(their_status, StatusMessage::Legacy(status));
 
         // await the other handshake
         handle.await.unwrap();
@@ -508,6 +524,63 @@ mod tests {
         handle.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn can_handshake_eth69() {
+        let genesis = B256::random();
+        let fork_filter = ForkFilter::new(Head::default(), genesis, 0, Vec::new());
+
+        let status = Status {
+            version: EthVersion::Eth69 as u8,
+            chain: NamedChain::Mainnet.into(),
+            total_difficulty: U256::ZERO,
+            blockhash: B256::random(),
+            genesis,
+            forkid: fork_filter.current(),
+        };
+        let block_range =
+            BlockRangeUpdate { earliest: 10, latest: 100, latest_hash: B256::random() };
+        let status = status.into_eth69(block_range);
+        let invalid_status = StatusEth69 { earliest: 100, latest: 10, ..status };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let fork_filter_clone = fork_filter.clone();
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = PassthroughCodec::default().framed(incoming);
+            let (_, their_status) =
+                UnauthedEthStream::new(stream).handshake(status, fork_filter_clone).await.unwrap();
+            assert_eq!(their_status.block_range(), Some(block_range));
+            assert_eq!(their_status.total_difficulty(), None);
+
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = PassthroughCodec::default().framed(incoming);
+            let _ = UnauthedEthStream::new(stream).handshake(invalid_status, fork_filter).await;
+        });
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink = PassthroughCodec::default().framed(outgoing);
+        let (_, their_status) = UnauthedEthStream::new(sink)
+            .handshake(status, ForkFilter::new(Head::default(), genesis, 0, Vec::new()))
+            .await
+            .unwrap();
+        assert_eq!(their_status, StatusMessage::Eth69(status));
+
+        // the peer announces an empty block range
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink = PassthroughCodec::default().framed(outgoing);
+        let handshake_res = UnauthedEthStream::new(sink)
+            .handshake(status, ForkFilter::new(Head::default(), genesis, 0, Vec::new()))
+            .await;
+        assert!(matches!(
+            handshake_res,
+            Err(EthStreamError::InvalidBlockRange { earliest: 100, latest: 10 })
+        ));
+
+        handle.await.unwrap();
+    }
+
     #[tokio::test]
     async fn can_write_and_read_cleartext() {
         let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -698,7 +771,7 @@ mod tests {
                 .unwrap();
 
             // just make sure it equals our status (our status is a clone of their status)
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::Legacy(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
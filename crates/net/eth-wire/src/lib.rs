@@ -15,6 +15,7 @@
 #![allow(unknown_lints, non_local_definitions)]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+pub mod bandwidth;
 pub mod capability;
 mod disconnect;
 pub mod errors;
@@ -35,6 +36,7 @@ pub use tokio_util::codec::{
 };
 
 pub use crate::{
+    bandwidth::{BandwidthLimiter, BandwidthLimits},
     capability::Capability,
     disconnect::{CanDisconnect, DisconnectReason},
     ethstream::{EthStream, UnauthedEthStream, MAX_MESSAGE_SIZE},
@@ -1,4 +1,5 @@
 use crate::{
+    bandwidth::{classify_message, PeerBandwidthLimiter},
     capability::SharedCapabilities,
     disconnect::CanDisconnect,
     errors::{P2PHandshakeError, P2PStreamError},
@@ -16,11 +17,14 @@ use reth_primitives::{
 };
 use std::{
     collections::VecDeque,
-    fmt, io,
+    fmt,
+    future::Future,
+    io,
     pin::Pin,
     task::{ready, Context, Poll},
     time::Duration,
 };
+use tokio::time::{Instant, Sleep};
 use tokio_stream::Stream;
 use tracing::{debug, trace};
 
@@ -63,6 +67,15 @@ const GRACE_PERIOD: Duration = Duration::from_secs(2);
 /// encoded data.
 const MAX_P2P_CAPACITY: usize = 2;
 
+/// [`MAX_THROTTLED_INGRESS_SIZE`] is the maximum number of bytes of subprotocol messages that are
+/// buffered while an ingress bandwidth limit is exceeded.
+///
+/// Messages are still read while the ingress is throttled, so that reserved `p2p` messages like
+/// pings are answered in time. The messages are buffered as they were received, so this bounds
+/// their compressed size. Once this many bytes are buffered, reading stops until the limits allow
+/// the buffered messages to be returned.
+const MAX_THROTTLED_INGRESS_SIZE: usize = 256 * 1024;
+
 /// An un-authenticated [`P2PStream`]. This is consumed and returns a [`P2PStream`] after the
 /// `Hello` handshake is completed.
 #[pin_project]
@@ -248,6 +261,10 @@ pub struct P2PStream<S> {
     /// Outgoing messages buffered for sending to the underlying stream.
     outgoing_messages: VecDeque<Bytes>,
 
+    /// Outgoing reserved `p2p` messages, like pongs, which are sent before any buffered
+    /// subprotocol messages and are never delayed by the bandwidth limits.
+    outgoing_reserved_messages: VecDeque<Bytes>,
+
     /// Maximum number of messages that we can buffer here before the [Sink] impl returns
     /// [Poll::Pending].
     outgoing_message_buffer_capacity: usize,
@@ -255,6 +272,19 @@ pub struct P2PStream<S> {
     /// Whether this stream is currently in the process of disconnecting by sending a disconnect
     /// message.
     disconnecting: bool,
+
+    /// Enforces the bandwidth limits of this stream.
+    bandwidth_limiter: PeerBandwidthLimiter,
+
+    /// Set while returning messages is delayed because an ingress bandwidth limit is exceeded.
+    ingress_delay: Option<Pin<Box<Sleep>>>,
+
+    /// Subprotocol messages that were read while an ingress bandwidth limit is exceeded, still
+    /// snappy compressed.
+    throttled_ingress: VecDeque<BytesMut>,
+
+    /// Set while sending messages is delayed because an egress bandwidth limit is exceeded.
+    egress_delay: Option<Pin<Box<Sleep>>>,
 }
 
 impl<S> P2PStream<S> {
@@ -269,8 +299,13 @@ impl<S> P2PStream<S> {
             pinger: Pinger::new(PING_INTERVAL, PING_TIMEOUT),
             shared_capabilities,
             outgoing_messages: VecDeque::new(),
+            outgoing_reserved_messages: VecDeque::new(),
             outgoing_message_buffer_capacity: MAX_P2P_CAPACITY,
             disconnecting: false,
+            bandwidth_limiter: Default::default(),
+            ingress_delay: None,
+            throttled_ingress: VecDeque::new(),
+            egress_delay: None,
         }
     }
 
@@ -288,6 +323,13 @@ impl<S> P2PStream<S> {
         self.outgoing_message_buffer_capacity = capacity;
     }
 
+    /// Sets the limiter that enforces the bandwidth limits of this stream.
+    ///
+    /// By default, the bandwidth is not limited.
+    pub fn set_bandwidth_limiter(&mut self, bandwidth_limiter: PeerBandwidthLimiter) {
+        self.bandwidth_limiter = bandwidth_limiter;
+    }
+
     /// Returns the shared capabilities for this stream.
     ///
     /// This includes all the shared capabilities that were negotiated during the handshake and
@@ -303,12 +345,12 @@ impl<S> P2PStream<S> {
 
     /// Queues in a _snappy_ encoded [`P2PMessage::Pong`] message.
     fn send_pong(&mut self) {
-        self.outgoing_messages.push_back(Bytes::from(alloy_rlp::encode(P2PMessage::Pong)));
+        self.outgoing_reserved_messages.push_back(Bytes::from(alloy_rlp::encode(P2PMessage::Pong)));
     }
 
     /// Queues in a _snappy_ encoded [`P2PMessage::Ping`] message.
     fn send_ping(&mut self) {
-        self.outgoing_messages.push_back(Bytes::from(alloy_rlp::encode(P2PMessage::Ping)));
+        self.outgoing_reserved_messages.push_back(Bytes::from(alloy_rlp::encode(P2PMessage::Ping)));
     }
 }
 
@@ -334,6 +376,7 @@ impl<S> DisconnectP2P for P2PStream<S> {
     fn start_disconnect(&mut self, reason: DisconnectReason) -> Result<(), P2PStreamError> {
         // clear any buffered messages and queue in
         self.outgoing_messages.clear();
+        self.outgoing_reserved_messages.clear();
         let disconnect = P2PMessage::Disconnect(reason);
         let mut buf = Vec::with_capacity(disconnect.length());
         disconnect.encode(&mut buf);
@@ -357,7 +400,7 @@ impl<S> DisconnectP2P for P2PStream<S> {
         // message
         compressed[0] = buf[0];
 
-        self.outgoing_messages.push_back(compressed.into());
+        self.outgoing_reserved_messages.push_back(compressed.into());
         self.disconnecting = true;
         Ok(())
     }
@@ -399,7 +442,32 @@ where
 
         // we should loop here to ensure we don't return Poll::Pending if we have a message to
         // return behind any pings we need to respond to
-        while let Poll::Ready(res) = this.inner.poll_next_unpin(cx) {
+        loop {
+            // return the messages read while throttled once the ingress limits allow it
+            if !this.throttled_ingress.is_empty() &&
+                poll_bandwidth_delay(
+                    &mut this.ingress_delay,
+                    || this.bandwidth_limiter.ingress_delay(),
+                    cx,
+                )
+                .is_ready()
+            {
+                let bytes = this.throttled_ingress.pop_front().expect("message exists");
+                this.bandwidth_limiter.on_ingress(bytes.len());
+                let mut message = decompress_message(&mut this.decoder, &bytes)?;
+                message[0] = bytes[0] - MAX_RESERVED_MESSAGE_ID - 1;
+                return Poll::Ready(Some(Ok(message)))
+            }
+
+            // keep reading while throttled, so that reserved messages like pings are still
+            // answered, until the buffer of throttled messages is full
+            let throttled_size =
+                this.throttled_ingress.iter().map(|bytes| bytes.len()).sum::<usize>();
+            if throttled_size >= MAX_THROTTLED_INGRESS_SIZE {
+                return Poll::Pending
+            }
+
+            let Poll::Ready(res) = this.inner.poll_next_unpin(cx) else { return Poll::Pending };
             let bytes = match res {
                 Some(Ok(bytes)) => bytes,
                 Some(Err(err)) => return Poll::Ready(Some(Err(err.into()))),
@@ -411,6 +479,13 @@ where
                 return Poll::Ready(Some(Err(P2PStreamError::EmptyProtocolMessage)))
             }
 
+            let (_, message) = classify_message(&this.shared_capabilities, bytes[0]);
+            counter!("p2pstream.ingress_bytes", bytes.len() as u64, "message" => message);
+            // reserved messages are accounted for, but never delayed
+            if bytes[0] <= MAX_RESERVED_MESSAGE_ID {
+                this.bandwidth_limiter.on_ingress(bytes.len());
+            }
+
             // first decode disconnect reasons, because they can be encoded in a variety of forms
             // over the wire, in both snappy compressed and uncompressed forms.
             //
@@ -443,20 +518,17 @@ where
                 })))
             }
 
-            // create a buffer to hold the decompressed message, adding a byte to the length for
-            // the message ID byte, which is the first byte in this buffer
-            let mut decompress_buf = BytesMut::zeroed(decompressed_len + 1);
+            // an ingress bandwidth limit is exceeded, so the subprotocol message is buffered as it
+            // was received and only decompressed once the limits allow it to be returned
+            if id > MAX_RESERVED_MESSAGE_ID &&
+                (!this.throttled_ingress.is_empty() ||
+                    this.bandwidth_limiter.ingress_delay().is_some())
+            {
+                this.throttled_ingress.push_back(bytes);
+                continue
+            }
 
-            // each message following a successful handshake is compressed with snappy, so we need
-            // to decompress the message before we can decode it.
-            this.decoder.decompress(&bytes[1..], &mut decompress_buf[1..]).map_err(|err| {
-                debug!(
-                    %err,
-                    msg=%hex::encode(&bytes[1..]),
-                    "error decompressing p2p message"
-                );
-                err
-            })?;
+            let mut decompress_buf = decompress_message(&mut this.decoder, &bytes)?;
 
             match id {
                 _ if id == P2PMessageID::Ping as u8 => {
@@ -521,12 +593,11 @@ where
                     //
                     decompress_buf[0] = bytes[0] - MAX_RESERVED_MESSAGE_ID - 1;
 
+                    this.bandwidth_limiter.on_ingress(bytes.len());
                     return Poll::Ready(Some(Ok(decompress_buf)))
                 }
             }
         }
-
-        Poll::Pending
     }
 }
 
@@ -623,9 +694,31 @@ where
             match ready!(this.inner.as_mut().poll_flush(cx)) {
                 Err(err) => return Poll::Ready(Err(err.into())),
                 Ok(()) => {
-                    let Some(message) = this.outgoing_messages.pop_front() else {
-                        return Poll::Ready(Ok(()))
+                    // reserved `p2p` messages, like pongs, have their own lane and are sent ahead
+                    // of any delayed subprotocol messages
+                    let message = if let Some(message) = this.outgoing_reserved_messages.pop_front()
+                    {
+                        message
+                    } else {
+                        let Some(id) = this.outgoing_messages.front().map(|message| message[0])
+                        else {
+                            return Poll::Ready(Ok(()))
+                        };
+
+                        if id > MAX_RESERVED_MESSAGE_ID {
+                            let class = classify_message(this.shared_capabilities, id).0;
+                            ready!(poll_bandwidth_delay(
+                                this.egress_delay,
+                                || this.bandwidth_limiter.egress_delay(class),
+                                cx
+                            ));
+                        }
+
+                        this.outgoing_messages.pop_front().expect("message exists")
                     };
+                    let (class, name) = classify_message(this.shared_capabilities, message[0]);
+                    counter!("p2pstream.egress_bytes", message.len() as u64, "message" => name);
+                    this.bandwidth_limiter.on_egress(class, message.len());
                     if let Err(err) = this.inner.as_mut().start_send(message) {
                         return Poll::Ready(Err(err.into()))
                     }
@@ -642,6 +735,50 @@ where
     }
 }
 
+/// Returns [`Poll::Pending`] until `next_delay` no longer returns a delay, using `sleep` to
+/// schedule the wakeup.
+fn poll_bandwidth_delay(
+    sleep: &mut Option<Pin<Box<Sleep>>>,
+    next_delay: impl Fn() -> Option<Duration>,
+    cx: &mut Context<'_>,
+) -> Poll<()> {
+    while let Some(delay) = next_delay() {
+        let deadline = Instant::now() + delay;
+        let sleep = sleep.get_or_insert_with(|| Box::pin(tokio::time::sleep_until(deadline)));
+        sleep.as_mut().reset(deadline);
+        ready!(sleep.as_mut().poll(cx));
+    }
+    *sleep = None;
+    Poll::Ready(())
+}
+
+/// Decompresses a snappy compressed message read from the stream.
+///
+/// The message ID is kept as the first byte of the returned buffer.
+fn decompress_message(
+    decoder: &mut snap::raw::Decoder,
+    bytes: &[u8],
+) -> Result<BytesMut, P2PStreamError> {
+    // create a buffer to hold the decompressed message, adding a byte to the length for the
+    // message ID byte, which is the first byte in this buffer
+    let decompressed_len = snap::raw::decompress_len(&bytes[1..])?;
+    let mut decompress_buf = BytesMut::zeroed(decompressed_len + 1);
+    decompress_buf[0] = bytes[0];
+
+    // each message following a successful handshake is compressed with snappy, so we need to
+    // decompress the message before we can decode it.
+    decoder.decompress(&bytes[1..], &mut decompress_buf[1..]).map_err(|err| {
+        debug!(
+            %err,
+            msg=%hex::encode(&bytes[1..]),
+            "error decompressing p2p message"
+        );
+        err
+    })?;
+
+    Ok(decompress_buf)
+}
+
 /// This represents only the reserved `p2p` subprotocol messages.
 #[derive_arbitrary(rlp)]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -832,7 +969,12 @@ impl Decodable for ProtocolVersion {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{capability::SharedCapability, test_utils::eth_hello, EthVersion};
+    use crate::{
+        bandwidth::{BandwidthLimiter, BandwidthLimits, MAX_DEBT},
+        capability::SharedCapability,
+        test_utils::eth_hello,
+        EthVersion,
+    };
     use tokio::net::{TcpListener, TcpStream};
     use tokio_util::codec::Decoder;
 
@@ -919,6 +1061,90 @@ mod tests {
         handle.await.unwrap();
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_pings_are_answered_while_throttled() {
+        reth_tracing::init_test_tracing();
+        // an in-memory stream, so that the paused clock is only advanced once both sides are idle
+        let (outgoing, incoming) = tokio::io::duplex(MAX_PAYLOAD_SIZE);
+
+        let first = Bytes::from_static(&[0x01, 0x01, 0x02]);
+        let second = Bytes::from_static(&[0x01, 0x03, 0x04]);
+
+        let handle = tokio::spawn({
+            let (first, second) = (first.clone(), second.clone());
+            async move {
+                let stream = crate::PassthroughCodec::default().framed(incoming);
+
+                let (server_hello, _) = eth_hello();
+
+                let (mut p2p_stream, _) =
+                    UnauthedP2PStream::new(stream).handshake(server_hello).await.unwrap();
+
+                // every subprotocol message exceeds these limits
+                let limiter = BandwidthLimiter::new(BandwidthLimits {
+                    peer_ingress: Some(2),
+                    peer_egress: Some(2),
+                    ..Default::default()
+                });
+                p2p_stream.set_bandwidth_limiter(limiter.peer_limiter());
+
+                // the first message puts the egress into debt, so the second one is delayed
+                p2p_stream.send(first.clone()).await.unwrap();
+                p2p_stream.feed(second.clone()).await.unwrap();
+
+                // the first message puts the ingress into debt
+                let message = p2p_stream.next().await.unwrap().unwrap();
+                assert_eq!(message[..], first[..]);
+
+                // the second message is throttled, but the ping behind it is still read
+                tokio::select! {
+                    _ = p2p_stream.next() => panic!("throttled message returned early"),
+                    _ = tokio::time::sleep(Duration::from_millis(500)) => {}
+                }
+
+                // the pong is sent ahead of the delayed subprotocol message
+                let flush = tokio::time::timeout(Duration::from_millis(500), p2p_stream.flush());
+                assert!(flush.await.is_err());
+
+                // the throttled message is returned once the limits allow it
+                let message = p2p_stream.next().await.unwrap().unwrap();
+                assert_eq!(message[..], second[..]);
+
+                p2p_stream
+            }
+        });
+
+        let sink = crate::PassthroughCodec::default().framed(outgoing);
+
+        let (client_hello, _) = eth_hello();
+
+        let (mut p2p_stream, _) =
+            UnauthedP2PStream::new(sink).handshake(client_hello).await.unwrap();
+
+        p2p_stream.send(first).await.unwrap();
+        p2p_stream.send(second).await.unwrap();
+        // bypass the pinger, which doesn't expect a pong for this ping
+        p2p_stream.inner.send(Bytes::from(alloy_rlp::encode(P2PMessage::Ping))).await.unwrap();
+
+        // the pong arrives while the server is throttled, behind the first message only
+        let pong = alloy_rlp::encode(P2PMessage::Pong);
+        let subprotocol_messages = tokio::time::timeout(MAX_DEBT, async {
+            let mut subprotocol_messages = 0;
+            loop {
+                let frame = p2p_stream.inner.next().await.unwrap().unwrap();
+                if frame[..] == pong[..] {
+                    return subprotocol_messages
+                }
+                subprotocol_messages += 1;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(subprotocol_messages, 1);
+
+        handle.await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_handshake_passthrough() {
         // create a p2p stream and server, then confirm that the two are authed
@@ -0,0 +1,221 @@
+//! The followed chain of headers.
+
+use reth_primitives::{BlockNumHash, Header, SealedHeader, B256};
+use std::collections::VecDeque;
+
+/// Errors when extending a [`HeaderChain`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HeaderChainError {
+    /// The first header does not build on the tip of the chain.
+    ///
+    /// This happens on reorgs and is not necessarily misbehavior of the peer that sent the
+    /// headers.
+    #[error("header {header:?} does not build on tip {tip:?}")]
+    Disconnected {
+        /// The tip of the chain.
+        tip: BlockNumHash,
+        /// The first header.
+        header: BlockNumHash,
+    },
+    /// A header does not build on its predecessor in the batch.
+    #[error("header {header:?} does not build on its parent {parent:?}")]
+    InvalidParent {
+        /// The predecessor of the header.
+        parent: BlockNumHash,
+        /// The invalid header.
+        header: BlockNumHash,
+    },
+    /// The timestamp of a header is not after the timestamp of its parent.
+    #[error(
+        "header {header:?} timestamp {timestamp} is not after the parent's {parent_timestamp}"
+    )]
+    TimestampNotAfterParent {
+        /// The invalid header.
+        header: BlockNumHash,
+        /// The timestamp of the header.
+        timestamp: u64,
+        /// The timestamp of the parent.
+        parent_timestamp: u64,
+    },
+    /// A header used more gas than its limit.
+    #[error("header {header:?} used {gas_used} gas, more than its limit {gas_limit}")]
+    GasUsedExceedsLimit {
+        /// The invalid header.
+        header: BlockNumHash,
+        /// The gas used by the block.
+        gas_used: u64,
+        /// The gas limit of the block.
+        gas_limit: u64,
+    },
+}
+
+/// The most recent headers of the followed chain.
+///
+/// The chain starts at a trusted checkpoint and is only extended by headers that build on its tip.
+/// At most `max_len` headers are retained, older headers are dropped.
+#[derive(Debug, Clone)]
+pub struct HeaderChain {
+    /// The retained headers, ordered by number, never empty.
+    headers: VecDeque<SealedHeader>,
+    max_len: usize,
+}
+
+impl HeaderChain {
+    /// Creates a new chain that starts at the given checkpoint.
+    pub fn new(checkpoint: SealedHeader, max_len: usize) -> Self {
+        Self { headers: VecDeque::from([checkpoint]), max_len: max_len.max(1) }
+    }
+
+    /// Returns the tip of the chain.
+    pub fn tip(&self) -> &SealedHeader {
+        self.headers.back().expect("never empty")
+    }
+
+    /// Returns the oldest retained header.
+    pub fn earliest(&self) -> &SealedHeader {
+        self.headers.front().expect("never empty")
+    }
+
+    /// Returns the header with the given number, if it is retained.
+    pub fn header_by_number(&self, number: u64) -> Option<&SealedHeader> {
+        let index = number.checked_sub(self.earliest().number)?;
+        self.headers.get(index as usize)
+    }
+
+    /// Returns the header with the given hash, if it is retained.
+    pub fn header_by_hash(&self, hash: B256) -> Option<&SealedHeader> {
+        self.headers.iter().rev().find(|header| header.hash() == hash)
+    }
+
+    /// Extends the chain with the given headers, in ascending order.
+    ///
+    /// Every header must build on its predecessor: its parent hash and number must match, its
+    /// timestamp must be after the parent's and it must not use more gas than its limit. The
+    /// headers are only appended if all of them are valid, and the number of appended headers is
+    /// returned.
+    pub fn extend(
+        &mut self,
+        headers: impl IntoIterator<Item = Header>,
+    ) -> Result<usize, HeaderChainError> {
+        let mut sealed = Vec::new();
+        for header in headers {
+            let header = header.seal_slow();
+            let parent = sealed.last().unwrap_or_else(|| self.tip());
+            if header.parent_hash != parent.hash() || header.number != parent.number + 1 {
+                return Err(if sealed.is_empty() {
+                    HeaderChainError::Disconnected {
+                        tip: parent.num_hash(),
+                        header: header.num_hash(),
+                    }
+                } else {
+                    HeaderChainError::InvalidParent {
+                        parent: parent.num_hash(),
+                        header: header.num_hash(),
+                    }
+                })
+            }
+            if header.timestamp <= parent.timestamp {
+                return Err(HeaderChainError::TimestampNotAfterParent {
+                    header: header.num_hash(),
+                    timestamp: header.timestamp,
+                    parent_timestamp: parent.timestamp,
+                })
+            }
+            if header.gas_used > header.gas_limit {
+                return Err(HeaderChainError::GasUsedExceedsLimit {
+                    header: header.num_hash(),
+                    gas_used: header.gas_used,
+                    gas_limit: header.gas_limit,
+                })
+            }
+            sealed.push(header);
+        }
+
+        let appended = sealed.len();
+        for header in sealed {
+            self.headers.push_back(header);
+            if self.headers.len() > self.max_len {
+                self.headers.pop_front();
+            }
+        }
+        Ok(appended)
+    }
+
+    /// Removes the tip of the chain, unless it is the only retained header.
+    ///
+    /// This is used to walk back on reorgs.
+    pub fn unwind_tip(&mut self) -> Option<SealedHeader> {
+        if self.headers.len() == 1 {
+            return None
+        }
+        self.headers.pop_back()
+    }
+
+    /// Removes all headers above the given number, but never the earliest retained header.
+    ///
+    /// This is used to walk back to the common ancestor on reorgs.
+    pub fn unwind_to(&mut self, number: u64) {
+        while self.tip().number > number && self.unwind_tip().is_some() {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn child(parent: &SealedHeader, extra: u8) -> Header {
+        Header {
+            number: parent.number + 1,
+            parent_hash: parent.hash(),
+            timestamp: parent.timestamp + 1,
+            extra_data: vec![extra].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn extend_and_unwind() {
+        let checkpoint = Header { number: 10, ..Default::default() }.seal_slow();
+        let mut chain = HeaderChain::new(checkpoint.clone(), 3);
+
+        let a = child(&checkpoint, 0);
+        let b = child(&a.clone().seal_slow(), 0);
+        let fork = child(&checkpoint, 1);
+        // nothing is appended if a header of the batch is invalid
+        assert_eq!(
+            chain.extend([a.clone(), b.clone(), fork.clone()]),
+            Err(HeaderChainError::InvalidParent {
+                parent: b.clone().seal_slow().num_hash(),
+                header: fork.clone().seal_slow().num_hash(),
+            })
+        );
+        assert_eq!(chain.tip().number, 10);
+        assert_eq!(chain.extend([a.clone(), b.clone()]), Ok(2));
+        assert_eq!(chain.tip().number, 12);
+        assert_eq!(chain.header_by_number(11).unwrap().hash(), a.clone().seal_slow().hash());
+        assert!(matches!(chain.extend([fork]), Err(HeaderChainError::Disconnected { .. })));
+
+        let c = child(&b.seal_slow(), 0);
+        let same_timestamp = Header { timestamp: c.timestamp - 1, ..c.clone() };
+        assert!(matches!(
+            chain.extend([same_timestamp]),
+            Err(HeaderChainError::TimestampNotAfterParent { .. })
+        ));
+        let too_much_gas = Header { gas_used: 1, ..c.clone() };
+        assert!(matches!(
+            chain.extend([too_much_gas]),
+            Err(HeaderChainError::GasUsedExceedsLimit { .. })
+        ));
+        assert_eq!(chain.extend([c]), Ok(1));
+        assert_eq!(chain.earliest().number, 11);
+        assert!(chain.header_by_number(10).is_none());
+
+        assert_eq!(chain.unwind_tip().unwrap().number, 13);
+        chain.unwind_to(12);
+        assert_eq!(chain.tip().number, 12);
+        chain.unwind_to(0);
+        assert_eq!(chain.tip().number, 11);
+        assert!(chain.unwind_tip().is_none());
+        assert_eq!(chain.tip().hash(), a.seal_slow().hash());
+    }
+}
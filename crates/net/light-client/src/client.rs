@@ -0,0 +1,826 @@
+//! The light client service and its handle.
+
+use crate::{
+    connection::ConnectionEvent, HeaderChain, HeaderChainError, LightClientError,
+    LightClientProtocol,
+};
+use futures::StreamExt;
+use reth_eth_wire::GetBlockHeaders;
+use reth_light_protocol::{
+    cht::{self, verify_cht_proof},
+    message::{
+        Announcement, BlockHeadersResponse, GetBlockHeadersRequest, GetHeaderProofsRequest,
+        GetReceiptProofsRequest, HeaderProofsResponse, ReceiptProofRequest, ReceiptProofsResponse,
+    },
+    receipts::verify_receipt_proof,
+    Credits, LightMessage, LightStatus, ServingQuota,
+};
+use reth_network::peers::PeersHandle;
+use reth_network_api::ReputationChangeKind;
+use reth_primitives::{
+    BlockHashOrNumber, Header, HeadersDirection, PeerId, ReceiptWithBloom, SealedHeader, B256,
+};
+use std::{
+    collections::HashMap,
+    future::Future,
+    pin::Pin,
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    sync::{mpsc, oneshot, watch},
+    time::{Instant, Sleep},
+};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::{debug, trace};
+
+/// The default maximum number of headers requested at once.
+const DEFAULT_MAX_HEADERS_PER_REQUEST: u64 = 192;
+
+/// The default number of retained headers.
+const DEFAULT_MAX_RETAINED_HEADERS: usize = 8_192;
+
+/// The default time a peer has to respond to a request.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Configures a [`LightClient`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightClientConfig {
+    /// The chain id, servers of other chains are ignored.
+    pub chain_id: u64,
+    /// The genesis hash of the chain, servers of other chains are ignored.
+    pub genesis_hash: B256,
+    /// The trusted header the followed chain starts at.
+    pub checkpoint: SealedHeader,
+    /// The maximum number of headers requested at once.
+    pub max_headers_per_request: u64,
+    /// The number of most recent headers to retain.
+    pub max_retained_headers: usize,
+    /// The time a peer has to respond to a request before the request fails and the peer is
+    /// penalized.
+    pub request_timeout: Duration,
+}
+
+impl LightClientConfig {
+    /// Creates a new config for the given chain that starts following at the checkpoint.
+    pub fn new(chain_id: u64, genesis_hash: B256, checkpoint: SealedHeader) -> Self {
+        Self {
+            chain_id,
+            genesis_hash,
+            checkpoint,
+            max_headers_per_request: DEFAULT_MAX_HEADERS_PER_REQUEST,
+            max_retained_headers: DEFAULT_MAX_RETAINED_HEADERS,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+}
+
+/// Follows the chain head of the connected `rlight` servers.
+///
+/// This is a future that runs until it is dropped and should be spawned. Its
+/// [`LightClientProtocol`] must be added to the network, requests are sent via the
+/// [`LightClientHandle`].
+///
+/// Servers are only used once they have proven to be on the chain of the checkpoint, by serving
+/// the checkpoint header. Servers that send invalid headers or proofs are disconnected, and
+/// penalized if a [`PeersHandle`] is set with [`LightClient::with_peers_handle`]. Servers that
+/// don't respond in time are penalized as well.
+///
+/// The announced head of a server is not trusted: if the server doesn't serve the headers up to
+/// its head, it is not synced from until it announces a new head.
+#[derive(Debug)]
+#[must_use = "the light client does nothing unless polled"]
+pub struct LightClient {
+    config: LightClientConfig,
+    /// The followed chain.
+    chain: HeaderChain,
+    /// Publishes the tip of the followed chain.
+    head: watch::Sender<SealedHeader>,
+    /// The connected servers.
+    peers: HashMap<PeerId, LightPeer>,
+    /// Events of the `rlight` connections.
+    events: UnboundedReceiverStream<ConnectionEvent>,
+    events_tx: mpsc::UnboundedSender<ConnectionEvent>,
+    /// Commands sent via the [`LightClientHandle`].
+    commands: UnboundedReceiverStream<Command>,
+    commands_tx: mpsc::UnboundedSender<Command>,
+    /// Requests that await a response.
+    inflight: HashMap<u64, InflightRequest>,
+    /// Fires at the deadline of the inflight request that times out first.
+    request_timeout: Option<Pin<Box<Sleep>>>,
+    next_request_id: u64,
+    /// Whether a request to extend the followed chain is in flight.
+    syncing: bool,
+    /// Set while syncing waits for the credits of the peers to recharge.
+    sync_delay: Option<Pin<Box<Sleep>>>,
+    /// Used to penalize misbehaving peers.
+    peers_handle: Option<PeersHandle>,
+}
+
+impl LightClient {
+    /// Creates a new light client.
+    pub fn new(config: LightClientConfig) -> Self {
+        let (events_tx, events) = mpsc::unbounded_channel();
+        let (commands_tx, commands) = mpsc::unbounded_channel();
+        let chain = HeaderChain::new(config.checkpoint.clone(), config.max_retained_headers);
+        Self {
+            head: watch::channel(config.checkpoint.clone()).0,
+            chain,
+            config,
+            peers: Default::default(),
+            events: UnboundedReceiverStream::new(events),
+            events_tx,
+            commands: UnboundedReceiverStream::new(commands),
+            commands_tx,
+            inflight: Default::default(),
+            request_timeout: None,
+            next_request_id: 0,
+            syncing: false,
+            sync_delay: None,
+            peers_handle: None,
+        }
+    }
+
+    /// Sets the [`PeersHandle`] of the network, which is used to penalize peers that send invalid
+    /// data.
+    pub fn with_peers_handle(mut self, peers_handle: PeersHandle) -> Self {
+        self.peers_handle = Some(peers_handle);
+        self
+    }
+
+    /// Returns the protocol handler that must be added to the network.
+    pub fn protocol(&self) -> LightClientProtocol {
+        let tip = self.chain.tip();
+        LightClientProtocol {
+            status: LightStatus {
+                chain_id: self.config.chain_id,
+                genesis_hash: self.config.genesis_hash,
+                head_hash: tip.hash(),
+                head_number: tip.number,
+                cht_section_size: cht::DEFAULT_CHT_SECTION_SIZE,
+                quota: ServingQuota::none(),
+            },
+            events: self.events_tx.clone(),
+        }
+    }
+
+    /// Returns a handle to the client.
+    pub fn handle(&self) -> LightClientHandle {
+        LightClientHandle { to_client: self.commands_tx.clone(), head: self.head.subscribe() }
+    }
+
+    fn next_request_id(&mut self) -> u64 {
+        let id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1);
+        id
+    }
+
+    /// Returns the verified serving peer with the highest head, if any.
+    fn best_peer(&self) -> Option<(PeerId, &LightPeer)> {
+        self.peers
+            .iter()
+            .filter(|(_, peer)| peer.verified && peer.is_serving())
+            .max_by_key(|(_, peer)| peer.head.head_number)
+            .map(|(peer_id, peer)| (*peer_id, peer))
+    }
+
+    /// Sends a request to the peer, charging its estimated credits.
+    fn send_request(
+        &mut self,
+        peer_id: PeerId,
+        cost: u64,
+        request_id: u64,
+        request: LightMessage,
+        kind: Kind,
+    ) {
+        let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+        if let Some(credits) = peer.credits.as_mut() {
+            credits.try_consume(cost);
+        }
+        if peer.to_connection.send(request).is_ok() {
+            let deadline = Instant::now() + self.config.request_timeout;
+            self.inflight.insert(request_id, InflightRequest { peer_id, deadline, kind });
+        } else {
+            self.on_request_failed(
+                InflightRequest { peer_id, deadline: Instant::now(), kind },
+                LightClientError::PeerDisconnected,
+            );
+        }
+    }
+
+    /// Requests headers that extend the followed chain from the best peer, if it is ahead.
+    ///
+    /// If the peer is on a different fork, its headers below the tip are requested instead, to
+    /// find the common ancestor.
+    fn maybe_sync(&mut self) {
+        if self.syncing || self.sync_delay.is_some() {
+            return
+        }
+        let Some((peer_id, peer)) = self.best_peer() else { return };
+        let head = peer.head;
+        let tip = self.chain.tip().num_hash();
+
+        let forked = peer.forked || (head.head_number == tip.number && head.head_hash != tip.hash);
+        if !forked && head.head_number <= tip.number {
+            return
+        }
+
+        let quota = peer.quota();
+        let wanted = if forked {
+            tip.number - self.chain.earliest().number + 1
+        } else {
+            head.head_number - tip.number
+        };
+        // never request more than the peer's capacity allows
+        let limit = wanted
+            .min(self.config.max_headers_per_request)
+            .min(quota.capacity / quota.header_cost.max(1))
+            .max(1);
+        let cost = limit * quota.header_cost;
+        if let Some(delay) = self.peers.get_mut(&peer_id).and_then(|peer| peer.delay_for(cost)) {
+            trace!(target: "net::light", ?peer_id, ?delay, "waiting for credits to recharge");
+            if delay != Duration::MAX {
+                self.sync_delay = Some(Box::pin(tokio::time::sleep(delay)));
+            }
+            return
+        }
+
+        let request_id = self.next_request_id();
+        let (request, kind) = if forked {
+            let request = headers_request(request_id, tip.number, limit, HeadersDirection::Falling);
+            (request, Kind::Ancestors { start: tip.number, limit })
+        } else {
+            let start = tip.number + 1;
+            let request = headers_request(request_id, start, limit, HeadersDirection::Rising);
+            (request, Kind::Sync { start, limit })
+        };
+        self.syncing = true;
+        self.send_request(peer_id, cost, request_id, request, kind);
+    }
+
+    /// Requests the checkpoint header from the peer, to verify that it is on the same chain.
+    fn verify_checkpoint(&mut self, peer_id: PeerId) {
+        let Some(peer) = self.peers.get(&peer_id) else { return };
+        let checkpoint = self.config.checkpoint.number;
+        if peer.head.head_number < checkpoint {
+            debug!(target: "net::light", ?peer_id, "peer is behind the checkpoint");
+            return
+        }
+
+        let cost = peer.quota().header_cost;
+        let request_id = self.next_request_id();
+        let request = headers_request(request_id, checkpoint, 1, HeadersDirection::Rising);
+        self.send_request(peer_id, cost, request_id, request, Kind::Checkpoint);
+    }
+
+    /// Penalizes a peer that sent invalid data and disconnects it.
+    fn on_bad_peer(&mut self, peer_id: PeerId, reason: &dyn std::fmt::Display) {
+        debug!(target: "net::light", ?peer_id, %reason, "disconnecting misbehaving peer");
+        if let Some(peers_handle) = self.peers_handle.as_ref() {
+            peers_handle.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+        }
+        // dropping the sender closes the connection
+        self.remove_peer(peer_id);
+    }
+
+    /// Stops syncing from the peer until it announces a new head, because it did not serve the
+    /// headers up to its announced head.
+    fn reset_peer_head(&mut self, peer_id: PeerId) {
+        let tip = self.chain.tip().num_hash();
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            peer.head = Announcement { head_hash: tip.hash, head_number: tip.number };
+            peer.forked = false;
+        }
+    }
+
+    /// Removes the peer and fails its inflight requests.
+    fn remove_peer(&mut self, peer_id: PeerId) {
+        self.peers.remove(&peer_id);
+        let closed = self
+            .inflight
+            .iter()
+            .filter(|(_, request)| request.peer_id == peer_id)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in closed {
+            if let Some(request) = self.inflight.remove(&id) {
+                self.on_request_failed(request, LightClientError::PeerDisconnected);
+            }
+        }
+    }
+
+    fn on_command(&mut self, command: Command) {
+        match command {
+            Command::HeaderByNumber { number, tx } => {
+                let _ = tx.send(self.chain.header_by_number(number).cloned());
+            }
+            Command::CanonicalHeader { number, cht_root, tx } => {
+                let Some((peer_id, peer)) = self.best_peer() else {
+                    let _ = tx.send(Err(LightClientError::NoPeers));
+                    return
+                };
+                let cost = peer.quota().header_proof_cost;
+                let request_id = self.next_request_id();
+                let request = LightMessage::GetHeaderProofs(GetHeaderProofsRequest {
+                    request_id,
+                    numbers: vec![number],
+                });
+                let kind = Kind::HeaderProof { number, cht_root, tx };
+                self.send_request(peer_id, cost, request_id, request, kind);
+            }
+            Command::Receipt { block_hash, index, tx } => {
+                let Some(header) = self.chain.header_by_hash(block_hash).cloned() else {
+                    let _ = tx.send(Err(LightClientError::UnknownBlock(block_hash)));
+                    return
+                };
+                let Some((peer_id, peer)) = self.best_peer() else {
+                    let _ = tx.send(Err(LightClientError::NoPeers));
+                    return
+                };
+                let cost = peer.quota().receipt_proof_cost;
+                let request_id = self.next_request_id();
+                let request = LightMessage::GetReceiptProofs(GetReceiptProofsRequest {
+                    request_id,
+                    receipts: vec![ReceiptProofRequest { block_hash, index }],
+                });
+                let kind = Kind::Receipt { receipts_root: header.receipts_root, index, tx };
+                self.send_request(peer_id, cost, request_id, request, kind);
+            }
+        }
+    }
+
+    fn on_event(&mut self, event: ConnectionEvent) {
+        match event {
+            ConnectionEvent::Established { peer_id, to_connection } => {
+                self.peers.insert(peer_id, LightPeer::new(to_connection));
+            }
+            ConnectionEvent::Message { peer_id, msg } => self.on_message(peer_id, msg),
+            ConnectionEvent::Closed { peer_id } => self.remove_peer(peer_id),
+        }
+    }
+
+    fn on_message(&mut self, peer_id: PeerId, msg: LightMessage) {
+        let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+        match msg {
+            LightMessage::Status(status) => {
+                if status.chain_id != self.config.chain_id ||
+                    status.genesis_hash != self.config.genesis_hash
+                {
+                    debug!(target: "net::light", ?peer_id, "ignoring peer of another chain");
+                    return
+                }
+                peer.head =
+                    Announcement { head_hash: status.head_hash, head_number: status.head_number };
+                peer.credits = Some(Credits::new(status.quota));
+                let first_status = peer.status.replace(status).is_none();
+                if first_status {
+                    self.verify_checkpoint(peer_id);
+                }
+            }
+            LightMessage::Announce(announcement) => {
+                peer.head = announcement;
+                peer.forked = false;
+            }
+            LightMessage::BlockHeaders(BlockHeadersResponse { request_id, credits, headers }) => {
+                peer.set_credits(credits);
+                let Some(request) = self.take_inflight(peer_id, request_id) else { return };
+                match request.kind {
+                    Kind::Checkpoint => self.on_checkpoint_header(peer_id, headers),
+                    Kind::Sync { start, limit } => {
+                        self.on_headers(peer_id, start, limit, headers);
+                    }
+                    Kind::Ancestors { start, limit } => {
+                        self.on_ancestors(peer_id, start, limit, headers);
+                    }
+                    _ => {}
+                }
+            }
+            LightMessage::HeaderProofs(HeaderProofsResponse { request_id, credits, proofs }) => {
+                peer.set_credits(credits);
+                let Some(request) = self.take_inflight(peer_id, request_id) else { return };
+                let Kind::HeaderProof { number, cht_root, tx } = request.kind else { return };
+                let result =
+                    proofs.into_iter().next().ok_or(LightClientError::NotAvailable).and_then(
+                        |proof| {
+                            let header = proof.header.seal_slow();
+                            if header.number != number {
+                                return Err(LightClientError::UnexpectedHeader(header.num_hash()))
+                            }
+                            verify_cht_proof(cht_root, number, header.hash(), &proof.proof)?;
+                            Ok(header)
+                        },
+                    );
+                if let Err(
+                    err @ (LightClientError::UnexpectedHeader(_) |
+                    LightClientError::InvalidResponse(_)),
+                ) = &result
+                {
+                    self.on_bad_peer(peer_id, err);
+                }
+                let _ = tx.send(result);
+            }
+            LightMessage::ReceiptProofs(ReceiptProofsResponse { request_id, credits, proofs }) => {
+                peer.set_credits(credits);
+                let Some(request) = self.take_inflight(peer_id, request_id) else { return };
+                let Kind::Receipt { receipts_root, index, tx } = request.kind else { return };
+                let result = proofs
+                    .into_iter()
+                    .next()
+                    .ok_or(LightClientError::NotAvailable)
+                    .and_then(|proof| {
+                        verify_receipt_proof(receipts_root, index, &proof.receipt, &proof.proof)?;
+                        Ok(proof.receipt)
+                    });
+                if let Err(err @ LightClientError::InvalidResponse(_)) = &result {
+                    self.on_bad_peer(peer_id, err);
+                }
+                let _ = tx.send(result);
+            }
+            // we don't serve requests
+            LightMessage::GetBlockHeaders(_) |
+            LightMessage::GetHeaderProofs(_) |
+            LightMessage::GetReceiptProofs(_) => {}
+        }
+    }
+
+    /// Removes the inflight request with the given id if it was sent to the peer.
+    fn take_inflight(&mut self, peer_id: PeerId, request_id: u64) -> Option<InflightRequest> {
+        if self.inflight.get(&request_id)?.peer_id != peer_id {
+            return None
+        }
+        self.inflight.remove(&request_id)
+    }
+
+    /// Marks the peer as verified if it served the checkpoint header.
+    fn on_checkpoint_header(&mut self, peer_id: PeerId, headers: Vec<Header>) {
+        let checkpoint = self.config.checkpoint.num_hash();
+        let [header] = headers.as_slice() else {
+            // the peer doesn't have the checkpoint (anymore) or the quota was exceeded
+            debug!(target: "net::light", ?peer_id, "peer did not serve the checkpoint header");
+            return
+        };
+        let header = header.clone().seal_slow();
+        if header.num_hash() != checkpoint {
+            self.on_bad_peer(peer_id, &LightClientError::UnexpectedHeader(header.num_hash()));
+            return
+        }
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            trace!(target: "net::light", ?peer_id, "verified checkpoint");
+            peer.verified = true;
+        }
+    }
+
+    /// Extends the followed chain with the headers of a sync request for `limit` headers starting
+    /// at `start`.
+    fn on_headers(&mut self, peer_id: PeerId, start: u64, limit: u64, headers: Vec<Header>) {
+        self.syncing = false;
+        let Some(first) = headers.first() else {
+            // the peer doesn't have the headers (anymore) or the quota was exceeded, wait for the
+            // next announcement
+            trace!(target: "net::light", ?peer_id, start, "peer did not serve headers");
+            self.reset_peer_head(peer_id);
+            return
+        };
+        if first.number != start || headers.len() as u64 > limit {
+            let err = LightClientError::UnexpectedHeader(first.clone().seal_slow().num_hash());
+            self.on_bad_peer(peer_id, &err);
+            return
+        }
+
+        match self.chain.extend(headers) {
+            Ok(appended) => {
+                let tip = self.chain.tip().num_hash();
+                trace!(target: "net::light", appended, ?tip, "extended chain");
+                self.head.send_replace(self.chain.tip().clone());
+            }
+            Err(HeaderChainError::Disconnected { .. }) => {
+                // reorg, the common ancestor is searched with the next request
+                if let Some(peer) = self.peers.get_mut(&peer_id) {
+                    peer.forked = true;
+                }
+            }
+            Err(err) => self.on_bad_peer(peer_id, &err),
+        }
+    }
+
+    /// Walks back to the common ancestor with the peer, given the headers of an ancestors request
+    /// for `limit` headers in descending order starting at `start`.
+    ///
+    /// If the common ancestor is found, the chain is unwound to it and extended with the headers
+    /// of the peer. Otherwise, the chain is unwound below the received headers and the search
+    /// continues with the next request.
+    fn on_ancestors(&mut self, peer_id: PeerId, start: u64, limit: u64, headers: Vec<Header>) {
+        self.syncing = false;
+        let headers = headers.into_iter().map(Header::seal_slow).collect::<Vec<_>>();
+        let Some(first) = headers.first() else {
+            trace!(target: "net::light", ?peer_id, start, "peer did not serve ancestors");
+            self.reset_peer_head(peer_id);
+            return
+        };
+        if first.number != start || headers.len() as u64 > limit {
+            self.on_bad_peer(peer_id, &LightClientError::UnexpectedHeader(first.num_hash()));
+            return
+        }
+        if let Some(pair) = headers.windows(2).find(|pair| {
+            pair[0].parent_hash != pair[1].hash() || pair[0].number != pair[1].number + 1
+        }) {
+            self.on_bad_peer(peer_id, &LightClientError::UnexpectedHeader(pair[1].num_hash()));
+            return
+        }
+        let Some(peer) = self.peers.get_mut(&peer_id) else { return };
+        peer.forked = false;
+
+        let ancestor = headers.iter().position(|header| {
+            self.chain
+                .header_by_number(header.number)
+                .is_some_and(|ours| ours.hash() == header.hash())
+        });
+        match ancestor {
+            Some(index) => {
+                self.chain.unwind_to(headers[index].number);
+                trace!(target: "net::light", tip=?self.chain.tip().num_hash(), "unwound to common ancestor");
+                let new = headers[..index].iter().rev().map(|header| header.header().clone());
+                if let Err(err) = self.chain.extend(new) {
+                    self.on_bad_peer(peer_id, &err);
+                }
+            }
+            None => {
+                let lowest = headers[headers.len() - 1].number;
+                if lowest <= self.chain.earliest().number {
+                    debug!(target: "net::light", ?peer_id, "peer is on a fork older than retained headers");
+                    peer.verified = false;
+                    return
+                }
+                self.chain.unwind_to(lowest - 1);
+                peer.forked = true;
+            }
+        }
+        self.head.send_replace(self.chain.tip().clone());
+    }
+
+    fn on_request_failed(&mut self, request: InflightRequest, err: LightClientError) {
+        if matches!(request.kind, Kind::Sync { .. } | Kind::Ancestors { .. }) {
+            self.syncing = false;
+        }
+        request.kind.fail(err);
+    }
+
+    /// Fails all requests whose deadline has passed and penalizes their peers.
+    ///
+    /// Returns `Poll::Ready` if requests timed out.
+    fn poll_request_timeouts(&mut self, cx: &mut Context<'_>) -> Poll<()> {
+        let Some(deadline) = self.inflight.values().map(|request| request.deadline).min() else {
+            self.request_timeout = None;
+            return Poll::Pending
+        };
+        let timeout = self
+            .request_timeout
+            .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(deadline)));
+        if timeout.deadline() != deadline {
+            timeout.as_mut().reset(deadline);
+        }
+        ready!(timeout.as_mut().poll(cx));
+        self.request_timeout = None;
+
+        let now = Instant::now();
+        let timed_out = self
+            .inflight
+            .iter()
+            .filter(|(_, request)| request.deadline <= now)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in timed_out {
+            let Some(request) = self.inflight.remove(&id) else { continue };
+            let peer_id = request.peer_id;
+            debug!(target: "net::light", ?peer_id, "request timed out");
+            if let Some(peers_handle) = self.peers_handle.as_ref() {
+                peers_handle.reputation_change(peer_id, ReputationChangeKind::Timeout);
+            }
+            if matches!(request.kind, Kind::Sync { .. } | Kind::Ancestors { .. }) {
+                self.reset_peer_head(peer_id);
+            }
+            self.on_request_failed(request, LightClientError::Timeout);
+        }
+        Poll::Ready(())
+    }
+}
+
+impl Future for LightClient {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            while let Poll::Ready(Some(command)) = this.commands.poll_next_unpin(cx) {
+                this.on_command(command);
+            }
+
+            while let Poll::Ready(Some(event)) = this.events.poll_next_unpin(cx) {
+                this.on_event(event);
+            }
+
+            this.maybe_sync();
+
+            // polled after all requests of this iteration were sent, to register their deadlines
+            if this.poll_request_timeouts(cx).is_ready() {
+                continue
+            }
+
+            if let Some(delay) = this.sync_delay.as_mut() {
+                if delay.as_mut().poll(cx).is_ready() {
+                    this.sync_delay = None;
+                    continue
+                }
+            }
+
+            return Poll::Pending
+        }
+    }
+}
+
+/// A handle to interact with a [`LightClient`].
+#[derive(Debug, Clone)]
+pub struct LightClientHandle {
+    to_client: mpsc::UnboundedSender<Command>,
+    head: watch::Receiver<SealedHeader>,
+}
+
+impl LightClientHandle {
+    /// Returns the tip of the followed chain.
+    pub fn head(&self) -> SealedHeader {
+        self.head.borrow().clone()
+    }
+
+    /// Returns a receiver that is notified when the tip of the followed chain changes.
+    pub fn subscribe_head(&self) -> watch::Receiver<SealedHeader> {
+        self.head.clone()
+    }
+
+    /// Returns the header of the followed chain with the given number, if it is retained.
+    pub async fn header_by_number(
+        &self,
+        number: u64,
+    ) -> Result<Option<SealedHeader>, LightClientError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::HeaderByNumber { number, tx })?;
+        rx.await.map_err(|_| LightClientError::ClientClosed)
+    }
+
+    /// Fetches the canonical header with the given number and verifies it against the trusted
+    /// root of the CHT of its section.
+    pub async fn canonical_header(
+        &self,
+        number: u64,
+        cht_root: B256,
+    ) -> Result<SealedHeader, LightClientError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::CanonicalHeader { number, cht_root, tx })?;
+        rx.await.map_err(|_| LightClientError::ClientClosed)?
+    }
+
+    /// Fetches the receipt with the given index of a block of the followed chain and verifies it
+    /// against the receipts root of the block.
+    pub async fn receipt(
+        &self,
+        block_hash: B256,
+        index: u64,
+    ) -> Result<ReceiptWithBloom, LightClientError> {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::Receipt { block_hash, index, tx })?;
+        rx.await.map_err(|_| LightClientError::ClientClosed)?
+    }
+
+    fn send(&self, command: Command) -> Result<(), LightClientError> {
+        self.to_client.send(command).map_err(|_| LightClientError::ClientClosed)
+    }
+}
+
+/// Returns a request for `limit` consecutive headers starting at `start` in the given direction.
+fn headers_request(
+    request_id: u64,
+    start: u64,
+    limit: u64,
+    direction: HeadersDirection,
+) -> LightMessage {
+    LightMessage::GetBlockHeaders(GetBlockHeadersRequest {
+        request_id,
+        request: GetBlockHeaders {
+            start_block: BlockHashOrNumber::Number(start),
+            limit,
+            skip: 0,
+            direction,
+        },
+    })
+}
+
+/// Commands sent by the [`LightClientHandle`].
+#[derive(Debug)]
+enum Command {
+    HeaderByNumber {
+        number: u64,
+        tx: oneshot::Sender<Option<SealedHeader>>,
+    },
+    CanonicalHeader {
+        number: u64,
+        cht_root: B256,
+        tx: oneshot::Sender<Result<SealedHeader, LightClientError>>,
+    },
+    Receipt {
+        block_hash: B256,
+        index: u64,
+        tx: oneshot::Sender<Result<ReceiptWithBloom, LightClientError>>,
+    },
+}
+
+/// A connected `rlight` server.
+#[derive(Debug)]
+struct LightPeer {
+    to_connection: mpsc::UnboundedSender<LightMessage>,
+    /// The status of the peer, `None` until received.
+    status: Option<LightStatus>,
+    /// The latest head of the peer.
+    head: Announcement,
+    /// The estimated credits of the peer.
+    credits: Option<Credits>,
+    /// Whether the peer has served the checkpoint header.
+    verified: bool,
+    /// Whether the peer served headers that don't build on the tip of the followed chain.
+    forked: bool,
+}
+
+impl LightPeer {
+    fn new(to_connection: mpsc::UnboundedSender<LightMessage>) -> Self {
+        Self {
+            to_connection,
+            status: None,
+            head: Announcement { head_hash: B256::ZERO, head_number: 0 },
+            credits: None,
+            verified: false,
+            forked: false,
+        }
+    }
+
+    /// Returns `true` if the peer is on our chain and serves requests.
+    fn is_serving(&self) -> bool {
+        self.status.as_ref().is_some_and(|status| status.quota.is_serving())
+    }
+
+    fn quota(&self) -> ServingQuota {
+        self.status.as_ref().map(|status| status.quota).unwrap_or_else(ServingQuota::none)
+    }
+
+    fn set_credits(&mut self, credits: u64) {
+        if let Some(estimate) = self.credits.as_mut() {
+            estimate.set(credits);
+        }
+    }
+
+    fn delay_for(&mut self, cost: u64) -> Option<Duration> {
+        self.credits.as_mut()?.delay_for(cost)
+    }
+}
+
+/// A request that awaits a response.
+#[derive(Debug)]
+struct InflightRequest {
+    peer_id: PeerId,
+    /// The request fails if the peer doesn't respond until then.
+    deadline: Instant,
+    kind: Kind,
+}
+
+#[derive(Debug)]
+enum Kind {
+    /// Extends the followed chain with `limit` headers starting at `start`.
+    Sync { start: u64, limit: u64 },
+    /// Searches the common ancestor with a peer on another fork, with `limit` headers in
+    /// descending order starting at `start`.
+    Ancestors { start: u64, limit: u64 },
+    /// Verifies that the peer is on the chain of the checkpoint.
+    Checkpoint,
+    HeaderProof {
+        number: u64,
+        cht_root: B256,
+        tx: oneshot::Sender<Result<SealedHeader, LightClientError>>,
+    },
+    Receipt {
+        receipts_root: B256,
+        index: u64,
+        tx: oneshot::Sender<Result<ReceiptWithBloom, LightClientError>>,
+    },
+}
+
+impl Kind {
+    fn fail(self, err: LightClientError) {
+        match self {
+            Self::Sync { .. } | Self::Ancestors { .. } | Self::Checkpoint => {}
+            Self::HeaderProof { tx, .. } => {
+                let _ = tx.send(Err(err));
+            }
+            Self::Receipt { tx, .. } => {
+                let _ = tx.send(Err(err));
+            }
+        }
+    }
+}
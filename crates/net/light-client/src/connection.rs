@@ -0,0 +1,159 @@
+//! `rlight` connections of the light client.
+
+use futures::{Stream, StreamExt};
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol,
+};
+use reth_light_protocol::{LightMessage, LightStatus};
+use reth_network::protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler};
+use reth_network_api::Direction;
+use reth_primitives::{BytesMut, PeerId};
+use std::{
+    net::SocketAddr,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::trace;
+
+/// Events of the `rlight` connections, reported to the [`LightClient`](crate::LightClient).
+#[derive(Debug)]
+pub(crate) enum ConnectionEvent {
+    /// A connection with a peer was established.
+    Established {
+        peer_id: PeerId,
+        /// Sends messages to the peer.
+        to_connection: mpsc::UnboundedSender<LightMessage>,
+    },
+    /// A message was received from the peer.
+    Message { peer_id: PeerId, msg: LightMessage },
+    /// The connection with the peer was closed.
+    Closed { peer_id: PeerId },
+}
+
+/// The `rlight` protocol handler of a [`LightClient`](crate::LightClient).
+///
+/// This must be added to the network as an additional RLPx sub-protocol.
+#[derive(Debug, Clone)]
+pub struct LightClientProtocol {
+    pub(crate) status: LightStatus,
+    pub(crate) events: mpsc::UnboundedSender<ConnectionEvent>,
+}
+
+impl LightClientProtocol {
+    fn connection_handler(&self) -> LightClientConnectionHandler {
+        LightClientConnectionHandler { status: self.status.clone(), events: self.events.clone() }
+    }
+}
+
+impl ProtocolHandler for LightClientProtocol {
+    type ConnectionHandler = LightClientConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+}
+
+/// Establishes `rlight` connections on behalf of a [`LightClient`](crate::LightClient).
+#[derive(Debug)]
+pub struct LightClientConnectionHandler {
+    status: LightStatus,
+    events: mpsc::UnboundedSender<ConnectionEvent>,
+}
+
+impl ConnectionHandler for LightClientConnectionHandler {
+    type Connection = LightClientConnection;
+
+    fn protocol(&self) -> Protocol {
+        LightMessage::protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let _ = self.events.send(ConnectionEvent::Established { peer_id, to_connection: tx });
+        LightClientConnection {
+            peer_id,
+            conn,
+            status: Some(self.status),
+            outgoing: UnboundedReceiverStream::new(rx),
+            events: self.events,
+        }
+    }
+}
+
+/// An `rlight` connection of the light client with a server.
+///
+/// Forwards all messages of the server to the [`LightClient`](crate::LightClient) and sends its
+/// requests.
+#[derive(Debug)]
+pub struct LightClientConnection {
+    peer_id: PeerId,
+    conn: ProtocolConnection,
+    /// The status to send once the connection is established.
+    status: Option<LightStatus>,
+    /// Messages to send to the peer.
+    outgoing: UnboundedReceiverStream<LightMessage>,
+    events: mpsc::UnboundedSender<ConnectionEvent>,
+}
+
+impl LightClientConnection {
+    fn close(&self) -> Poll<Option<BytesMut>> {
+        let _ = self.events.send(ConnectionEvent::Closed { peer_id: self.peer_id });
+        Poll::Ready(None)
+    }
+}
+
+impl Stream for LightClientConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(status) = this.status.take() {
+            return Poll::Ready(Some(LightMessage::Status(status).encoded()))
+        }
+
+        loop {
+            match this.outgoing.poll_next_unpin(cx) {
+                Poll::Ready(Some(msg)) => return Poll::Ready(Some(msg.encoded())),
+                // the client dropped the peer
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => {}
+            }
+
+            let Some(msg) = ready!(this.conn.poll_next_unpin(cx)) else { return this.close() };
+            match LightMessage::decode_message(&mut &msg[..]) {
+                Ok(msg) => {
+                    let _ =
+                        this.events.send(ConnectionEvent::Message { peer_id: this.peer_id, msg });
+                }
+                Err(err) => {
+                    trace!(target: "net::light", peer_id=?this.peer_id, %err, "invalid message");
+                    return this.close()
+                }
+            }
+        }
+    }
+}
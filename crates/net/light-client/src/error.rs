@@ -0,0 +1,32 @@
+//! Error types of the light client.
+
+use reth_primitives::{trie::ProofVerificationError, BlockNumHash, B256};
+
+/// Errors returned by the [`LightClientHandle`](crate::LightClientHandle).
+#[derive(Debug, thiserror::Error)]
+pub enum LightClientError {
+    /// There is no connected peer that can serve the request.
+    #[error("no peer available")]
+    NoPeers,
+    /// The peer disconnected before responding.
+    #[error("peer disconnected")]
+    PeerDisconnected,
+    /// The peer did not respond in time.
+    #[error("request timed out")]
+    Timeout,
+    /// The peer doesn't have the requested data or its quota was exceeded.
+    #[error("data not available")]
+    NotAvailable,
+    /// The requested block is not part of the followed chain.
+    #[error("unknown block {0}")]
+    UnknownBlock(B256),
+    /// The peer responded with a header that was not requested.
+    #[error("unexpected header {0:?}")]
+    UnexpectedHeader(BlockNumHash),
+    /// The peer responded with invalid data.
+    #[error("invalid response: {0}")]
+    InvalidResponse(#[from] ProofVerificationError),
+    /// The client was dropped.
+    #[error("light client closed")]
+    ClientClosed,
+}
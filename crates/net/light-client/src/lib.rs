@@ -0,0 +1,40 @@
+//! A light client that follows the chain head via the `rlight` sub-protocol.
+//!
+//! The [`LightClient`] connects to full nodes that serve the `rlight` protocol, see
+//! [`reth_light_protocol`], and follows their chain head by downloading headers that extend its
+//! local chain, starting at a trusted checkpoint. It can fetch receipts of followed blocks, which
+//! are verified against the receipts root of their header, and canonical headers that are
+//! verified against trusted CHT roots.
+//!
+//! Servers are only followed once they served the checkpoint header. Headers are checked to form
+//! a chain that starts at the checkpoint, with increasing timestamps and no more gas used than
+//! their limit, and servers that send invalid headers or proofs are penalized and disconnected.
+//!
+//! Note: the consensus validity of headers is not verified, which means the client trusts its
+//! servers to follow the canonical chain.
+//!
+//! ```ignore
+//! let client = LightClient::new(LightClientConfig::new(chain_id, genesis_hash, checkpoint))
+//!     .with_peers_handle(network.peers_handle().clone());
+//! network.add_rlpx_sub_protocol(client.protocol().into_rlpx_sub_protocol());
+//! let handle = client.handle();
+//! tokio::spawn(client);
+//! ```
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+mod chain;
+mod client;
+mod connection;
+mod error;
+
+pub use chain::{HeaderChain, HeaderChainError};
+pub use client::{LightClient, LightClientConfig, LightClientHandle};
+pub use connection::{LightClientConnection, LightClientConnectionHandler, LightClientProtocol};
+pub use error::LightClientError;
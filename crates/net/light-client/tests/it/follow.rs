@@ -0,0 +1,136 @@
+use reth_light_client::{LightClient, LightClientConfig, LightClientHandle};
+use reth_light_protocol::{cht::cht_root, LightServer, LightServerConfig};
+use reth_network::test_utils::Testnet;
+use reth_primitives::{Block, Header, SealedHeader, B256};
+use reth_provider::test_utils::MockEthProvider;
+use std::time::Duration;
+
+/// Appends `num` blocks to the chain of the provider and returns the new tip.
+fn extend_chain(provider: &MockEthProvider, hashes: &mut Vec<B256>, num: u64) -> SealedHeader {
+    let mut tip = None;
+    for _ in 0..num {
+        let header = Header {
+            number: hashes.len() as u64,
+            parent_hash: hashes.last().copied().unwrap_or_default(),
+            timestamp: hashes.len() as u64,
+            gas_limit: 30_000_000,
+            ..Default::default()
+        }
+        .seal_slow();
+        provider.add_block(
+            header.hash(),
+            Block { header: header.header.clone(), ..Default::default() },
+        );
+        hashes.push(header.hash());
+        tip = Some(header);
+    }
+    tip.expect("num > 0")
+}
+
+async fn wait_for_head(handle: &LightClientHandle, number: u64) -> SealedHeader {
+    let mut head = handle.subscribe_head();
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let current = head.borrow_and_update().clone();
+            if current.number >= number {
+                return current
+            }
+            head.changed().await.unwrap();
+        }
+    })
+    .await
+    .expect("light client did not reach the head")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn follow_chain_head() {
+    reth_tracing::init_test_tracing();
+    let provider = MockEthProvider::default();
+    let mut hashes = Vec::new();
+    let checkpoint = extend_chain(&provider, &mut hashes, 1);
+    let tip = extend_chain(&provider, &mut hashes, 9);
+
+    let mut net = Testnet::create_with(2, provider.clone()).await;
+
+    let server = LightServer::new(
+        provider.clone(),
+        LightServerConfig { cht_section_size: 4, ..Default::default() },
+    );
+    server.set_head(tip.num_hash());
+    net.peers_mut()[0].add_rlpx_sub_protocol(server.clone());
+
+    let client = LightClient::new(LightClientConfig::new(
+        provider.chain_spec.chain.id(),
+        provider.chain_spec.genesis_hash(),
+        checkpoint,
+    ));
+    net.peers_mut()[1].add_rlpx_sub_protocol(client.protocol());
+    let handle = client.handle();
+    tokio::spawn(client);
+
+    let net = net.spawn();
+    net.connect_peers().await;
+
+    let head = wait_for_head(&handle, 9).await;
+    assert_eq!(head, tip);
+
+    // the client follows new blocks announced by the server
+    let tip = extend_chain(&provider, &mut hashes, 5);
+    server.set_head(tip.num_hash());
+    let head = wait_for_head(&handle, 14).await;
+    assert_eq!(head, tip);
+    assert_eq!(handle.header_by_number(12).await.unwrap().unwrap().hash(), hashes[12]);
+
+    // canonical headers of complete sections are verified against the CHT root
+    let header = handle.canonical_header(5, cht_root(4, &hashes[4..8])).await.unwrap();
+    assert_eq!(header.hash(), hashes[5]);
+    assert!(handle.canonical_header(5, cht_root(0, &hashes[0..4])).await.is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn penalize_server_on_another_chain() {
+    reth_tracing::init_test_tracing();
+    let provider = MockEthProvider::default();
+    let mut hashes = Vec::new();
+    extend_chain(&provider, &mut hashes, 1);
+    let tip = extend_chain(&provider, &mut hashes, 9);
+
+    let mut net = Testnet::create_with(2, provider.clone()).await;
+
+    let server = LightServer::new(provider.clone(), LightServerConfig::default());
+    server.set_head(tip.num_hash());
+    net.peers_mut()[0].add_rlpx_sub_protocol(server);
+    let server_id = net.peers()[0].peer_id();
+
+    // a checkpoint the server can't serve
+    let checkpoint = Header { number: 0, extra_data: vec![1].into(), ..Default::default() };
+    let checkpoint = checkpoint.seal_slow();
+    let peers_handle = net.peers()[1].handle().peers_handle().clone();
+    let client = LightClient::new(LightClientConfig::new(
+        provider.chain_spec.chain.id(),
+        provider.chain_spec.genesis_hash(),
+        checkpoint.clone(),
+    ))
+    .with_peers_handle(peers_handle.clone());
+    net.peers_mut()[1].add_rlpx_sub_protocol(client.protocol());
+    let handle = client.handle();
+    tokio::spawn(client);
+
+    let net = net.spawn();
+    net.connect_peers().await;
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let peer = peers_handle.peer_by_id(server_id).await;
+            if peer.is_some_and(|peer| peer.reputation() < 0) {
+                return
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("server was not penalized");
+
+    // the server is never followed
+    assert_eq!(handle.head(), checkpoint);
+}
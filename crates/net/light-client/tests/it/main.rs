@@ -0,0 +1,3 @@
+mod follow;
+
+fn main() {}
@@ -0,0 +1,278 @@
+//! Canonical hash tries (CHT).
+//!
+//! The canonical chain is split into sections of a fixed number of blocks. The CHT of a section
+//! is a merkle patricia trie that maps the big-endian encoded number of every block in the section
+//! to the RLP encoded hash of the canonical block with that number.
+//!
+//! Given the trusted root of a section's CHT, a client can verify that a header is canonical
+//! without downloading the headers of the section.
+
+use alloy_rlp::{Encodable, Header, EMPTY_STRING_CODE};
+use reth_primitives::{
+    keccak256,
+    trie::{verify_proof, HashBuilder, Nibbles, ProofVerificationError, EMPTY_ROOT_HASH},
+    Bytes, B256,
+};
+use std::collections::BTreeMap;
+
+/// The default number of blocks per CHT section.
+pub const DEFAULT_CHT_SECTION_SIZE: u64 = 32_768;
+
+/// Returns the section of the given block.
+pub const fn section_of(number: u64, section_size: u64) -> u64 {
+    number / section_size
+}
+
+/// Returns the number of the first block of the given section.
+pub const fn section_start(section: u64, section_size: u64) -> u64 {
+    section * section_size
+}
+
+/// Computes the root of the CHT of a section.
+///
+/// `hashes` are the canonical hashes of all blocks of the section, starting at block
+/// `section_start`.
+pub fn cht_root(section_start: u64, hashes: &[B256]) -> B256 {
+    cht_root_with_proof(section_start, hashes, None).0
+}
+
+/// Computes the root of the CHT of a section and the proof for the given block, if any.
+///
+/// `hashes` are the canonical hashes of all blocks of the section, starting at block
+/// `section_start`.
+pub fn cht_root_with_proof(
+    section_start: u64,
+    hashes: &[B256],
+    target: Option<u64>,
+) -> (B256, Vec<Bytes>) {
+    let (root, proofs) = cht_root_with_proofs(section_start, hashes, target.as_slice());
+    (root, proofs.into_iter().next().unwrap_or_default())
+}
+
+/// Computes the root of the CHT of a section and the proofs for all given blocks, in the order of
+/// the given blocks.
+///
+/// This builds the trie of the section only once, regardless of the number of proofs.
+///
+/// `hashes` are the canonical hashes of all blocks of the section, starting at block
+/// `section_start`.
+pub fn cht_root_with_proofs(
+    section_start: u64,
+    hashes: &[B256],
+    targets: &[u64],
+) -> (B256, Vec<Vec<Bytes>>) {
+    let targets = targets.iter().copied().map(cht_key).collect::<Vec<_>>();
+    let mut hash_builder = HashBuilder::default().with_proof_retainer(targets.clone());
+    for (number, hash) in (section_start..).zip(hashes) {
+        hash_builder.add_leaf(cht_key(number), &alloy_rlp::encode(hash));
+    }
+    let root = hash_builder.root();
+
+    let nodes = hash_builder.take_proofs();
+    let proofs = targets
+        .iter()
+        .map(|target| {
+            nodes
+                .iter()
+                .filter(|(path, _)| target.starts_with(path))
+                .map(|(_, node)| node.clone())
+                .collect()
+        })
+        .collect();
+    (root, proofs)
+}
+
+/// The CHT of a section with all of its nodes.
+///
+/// Unlike [`cht_root_with_proofs`], this keeps the whole trie, so the proofs of any blocks of the
+/// section can be served without building the trie again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionTrie {
+    root: B256,
+    /// The RLP encoded nodes of the trie by their path.
+    nodes: BTreeMap<Nibbles, Bytes>,
+}
+
+impl SectionTrie {
+    /// Builds the CHT of a section.
+    ///
+    /// `hashes` are the canonical hashes of all blocks of the section, starting at block
+    /// `section_start`.
+    pub fn new(section_start: u64, hashes: &[B256]) -> Self {
+        let leaves = (section_start..)
+            .zip(hashes)
+            .map(|(number, hash)| (cht_key(number), alloy_rlp::encode(hash)))
+            .collect::<Vec<_>>();
+        let mut nodes = BTreeMap::new();
+        let root = if leaves.is_empty() {
+            EMPTY_ROOT_HASH
+        } else {
+            keccak256(encode_node(&leaves, 0, &mut nodes))
+        };
+        Self { root, nodes }
+    }
+
+    /// Returns the root of the trie.
+    pub const fn root(&self) -> B256 {
+        self.root
+    }
+
+    /// Returns the proof of the given block, which consists of the nodes on the path of its key.
+    pub fn proof(&self, number: u64) -> Vec<Bytes> {
+        let key = cht_key(number);
+        (0..=key.len())
+            .filter_map(|len| self.nodes.get(&Nibbles::from_nibbles_unchecked(&key[..len])))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Verifies that the block with the given number and hash is canonical, given the root of the CHT
+/// of the block's section.
+pub fn verify_cht_proof(
+    cht_root: B256,
+    number: u64,
+    hash: B256,
+    proof: &[Bytes],
+) -> Result<(), ProofVerificationError> {
+    verify_proof(cht_root, &cht_key(number), Some(&alloy_rlp::encode(hash)), proof)
+}
+
+/// Returns the key of a block in the CHT.
+fn cht_key(number: u64) -> Nibbles {
+    Nibbles::unpack(number.to_be_bytes())
+}
+
+/// Returns the RLP encoding of the node of the given sorted leaves, which share the first `depth`
+/// nibbles of their keys, and adds the node and all nodes below it to `nodes`.
+fn encode_node(
+    leaves: &[(Nibbles, Vec<u8>)],
+    depth: usize,
+    nodes: &mut BTreeMap<Nibbles, Bytes>,
+) -> Vec<u8> {
+    let (first, value) = &leaves[0];
+    let last = &leaves[leaves.len() - 1].0;
+    let encoded = if leaves.len() == 1 {
+        encode_list(&[encode_bytes(&encode_path(&first[depth..], true)), encode_bytes(value)])
+    } else {
+        // the keys are sorted, so all of them share the prefix of the first and last key
+        let shared = first[depth..].iter().zip(&last[depth..]).take_while(|(a, b)| a == b).count();
+        if shared > 0 {
+            let child = encode_node(leaves, depth + shared, nodes);
+            encode_list(&[
+                encode_bytes(&encode_path(&first[depth..depth + shared], false)),
+                node_ref(child),
+            ])
+        } else {
+            let mut items = Vec::with_capacity(17);
+            let mut rest = leaves;
+            for nibble in 0..16 {
+                let len = rest.iter().take_while(|(key, _)| key[depth] == nibble).count();
+                let (children, remaining) = rest.split_at(len);
+                rest = remaining;
+                items.push(if children.is_empty() {
+                    vec![EMPTY_STRING_CODE]
+                } else {
+                    node_ref(encode_node(children, depth + 1, nodes))
+                });
+            }
+            // the keys have the same length, so branches never have a value
+            items.push(vec![EMPTY_STRING_CODE]);
+            encode_list(&items)
+        }
+    };
+    nodes.insert(Nibbles::from_nibbles_unchecked(&first[..depth]), encoded.clone().into());
+    encoded
+}
+
+/// Returns the hex prefix encoding of the path of a leaf or extension node.
+fn encode_path(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 0x20 } else { 0x00 };
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        encoded.push(flag | 0x10 | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        encoded.push(flag);
+        nibbles
+    };
+    encoded.extend(rest.chunks_exact(2).map(|pair| (pair[0] << 4) | pair[1]));
+    encoded
+}
+
+/// Returns the RLP encoding of the byte string.
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(bytes.len() + 1);
+    bytes.encode(&mut encoded);
+    encoded
+}
+
+/// Returns the RLP list of the already encoded items.
+fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload_length = items.iter().map(Vec::len).sum();
+    let mut encoded = Vec::with_capacity(payload_length + 3);
+    Header { list: true, payload_length }.encode(&mut encoded);
+    for item in items {
+        encoded.extend_from_slice(item);
+    }
+    encoded
+}
+
+/// Returns the reference to an encoded node in its parent, the node itself if its encoding is
+/// shorter than 32 bytes and its hash otherwise.
+fn node_ref(encoded: Vec<u8>) -> Vec<u8> {
+    if encoded.len() < B256::len_bytes() {
+        return encoded
+    }
+    let mut hash_ref = Vec::with_capacity(B256::len_bytes() + 1);
+    keccak256(&encoded).encode(&mut hash_ref);
+    hash_ref
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::keccak256;
+
+    #[test]
+    fn prove_canonical_hash() {
+        let section_size = 64;
+        let start = section_start(3, section_size);
+        let hashes = (start..start + section_size)
+            .map(|number| keccak256(number.to_be_bytes()))
+            .collect::<Vec<_>>();
+        let root = cht_root(start, &hashes);
+
+        let number = start + 17;
+        assert_eq!(section_of(number, section_size), 3);
+        let (proof_root, proof) = cht_root_with_proof(start, &hashes, Some(number));
+        assert_eq!(proof_root, root);
+
+        assert_eq!(verify_cht_proof(root, number, hashes[17], &proof), Ok(()));
+        assert!(verify_cht_proof(root, number, hashes[18], &proof).is_err());
+        assert!(verify_cht_proof(root, number + 1, hashes[17], &proof).is_err());
+
+        let (proofs_root, proofs) = cht_root_with_proofs(start, &hashes, &[start + 3, number]);
+        assert_eq!(proofs_root, root);
+        assert_eq!(verify_cht_proof(root, start + 3, hashes[3], &proofs[0]), Ok(()));
+        assert_eq!(proofs[1], proof);
+    }
+
+    #[test]
+    fn section_trie() {
+        for section_size in [1, 2, 17, 64, 300] {
+            let start = section_start(5, section_size);
+            let hashes = (start..start + section_size)
+                .map(|number| keccak256(number.to_be_bytes()))
+                .collect::<Vec<_>>();
+            let trie = SectionTrie::new(start, &hashes);
+            assert_eq!(trie.root(), cht_root(start, &hashes));
+
+            for (number, hash) in (start..).zip(&hashes) {
+                let proof = trie.proof(number);
+                assert_eq!(verify_cht_proof(trie.root(), number, *hash, &proof), Ok(()));
+                assert_eq!(proof, cht_root_with_proof(start, &hashes, Some(number)).1);
+            }
+        }
+    }
+}
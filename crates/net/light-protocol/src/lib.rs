@@ -0,0 +1,41 @@
+//! An RLPx sub-protocol for serving light clients.
+//!
+//! The `rlight` protocol lets clients that don't run a full node follow the chain head and fetch
+//! verifiable data from a full node:
+//!
+//! - block headers, requested like in the `eth` protocol
+//! - canonical hash trie (CHT) proofs, which prove that a header is canonical with respect to the
+//!   root of the CHT of its section, see [`cht`]
+//! - receipt proofs against the receipts root of a header, see [`receipts`]
+//! - announcements of the server's new chain head
+//!
+//! Serving is subject to per-peer quotas, see [`ServingQuota`].
+//!
+//! The server side is implemented by [`LightServer`], which can be installed on a node as an
+//! additional RLPx sub-protocol:
+//!
+//! ```ignore
+//! let server = LightServer::new(provider, LightServerConfig::default());
+//! network.add_rlpx_sub_protocol(server.clone().into_rlpx_sub_protocol());
+//! tokio::spawn(server.track_canonical_head(provider.canonical_state_stream()));
+//! ```
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+pub mod cht;
+pub mod message;
+mod quota;
+pub mod receipts;
+mod server;
+
+pub use message::{LightMessage, LightMessageId, LightStatus};
+pub use quota::{Credits, ServingQuota};
+pub use server::{
+    LightServer, LightServerConfig, LightServerConnection, LightServerConnectionHandler,
+};
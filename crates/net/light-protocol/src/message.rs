@@ -0,0 +1,298 @@
+//! Messages of the `rlight` protocol.
+
+use crate::ServingQuota;
+use alloy_rlp::{Decodable, Encodable, RlpDecodable, RlpEncodable};
+use reth_eth_wire::{capability::Capability, protocol::Protocol, GetBlockHeaders};
+use reth_primitives::{BufMut, Bytes, BytesMut, Header, ReceiptWithBloom, B256};
+
+/// The ids of the messages of the `rlight` protocol.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LightMessageId {
+    /// See [`LightMessage::Status`].
+    Status = 0x00,
+    /// See [`LightMessage::GetBlockHeaders`].
+    GetBlockHeaders = 0x01,
+    /// See [`LightMessage::BlockHeaders`].
+    BlockHeaders = 0x02,
+    /// See [`LightMessage::GetHeaderProofs`].
+    GetHeaderProofs = 0x03,
+    /// See [`LightMessage::HeaderProofs`].
+    HeaderProofs = 0x04,
+    /// See [`LightMessage::GetReceiptProofs`].
+    GetReceiptProofs = 0x05,
+    /// See [`LightMessage::ReceiptProofs`].
+    ReceiptProofs = 0x06,
+    /// See [`LightMessage::Announce`].
+    Announce = 0x07,
+}
+
+impl LightMessageId {
+    /// The number of message ids used by the protocol.
+    pub const COUNT: u8 = 8;
+}
+
+impl TryFrom<u8> for LightMessageId {
+    type Error = alloy_rlp::Error;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        Ok(match id {
+            0x00 => Self::Status,
+            0x01 => Self::GetBlockHeaders,
+            0x02 => Self::BlockHeaders,
+            0x03 => Self::GetHeaderProofs,
+            0x04 => Self::HeaderProofs,
+            0x05 => Self::GetReceiptProofs,
+            0x06 => Self::ReceiptProofs,
+            0x07 => Self::Announce,
+            _ => return Err(alloy_rlp::Error::Custom("unknown rlight message id")),
+        })
+    }
+}
+
+/// A message of the `rlight` protocol.
+///
+/// Responses carry the remaining [`Credits`](crate::Credits) of the requesting peer, so clients
+/// can keep their estimate in sync with the server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LightMessage {
+    /// Sent by both sides once the connection is established.
+    Status(LightStatus),
+    /// Requests block headers.
+    GetBlockHeaders(GetBlockHeadersRequest),
+    /// The response to [`LightMessage::GetBlockHeaders`].
+    BlockHeaders(BlockHeadersResponse),
+    /// Requests CHT proofs for the canonical headers with the given numbers.
+    GetHeaderProofs(GetHeaderProofsRequest),
+    /// The response to [`LightMessage::GetHeaderProofs`].
+    HeaderProofs(HeaderProofsResponse),
+    /// Requests receipt proofs.
+    GetReceiptProofs(GetReceiptProofsRequest),
+    /// The response to [`LightMessage::GetReceiptProofs`].
+    ReceiptProofs(ReceiptProofsResponse),
+    /// Announces a new chain head of the server.
+    Announce(Announcement),
+}
+
+impl LightMessage {
+    /// Returns the capability of the `rlight` protocol.
+    pub const fn capability() -> Capability {
+        Capability::new_static("rlight", 1)
+    }
+
+    /// Returns the `rlight` protocol.
+    pub const fn protocol() -> Protocol {
+        Protocol::new(Self::capability(), LightMessageId::COUNT)
+    }
+
+    /// Returns the id of the message.
+    pub const fn message_id(&self) -> LightMessageId {
+        match self {
+            Self::Status(_) => LightMessageId::Status,
+            Self::GetBlockHeaders(_) => LightMessageId::GetBlockHeaders,
+            Self::BlockHeaders(_) => LightMessageId::BlockHeaders,
+            Self::GetHeaderProofs(_) => LightMessageId::GetHeaderProofs,
+            Self::HeaderProofs(_) => LightMessageId::HeaderProofs,
+            Self::GetReceiptProofs(_) => LightMessageId::GetReceiptProofs,
+            Self::ReceiptProofs(_) => LightMessageId::ReceiptProofs,
+            Self::Announce(_) => LightMessageId::Announce,
+        }
+    }
+
+    /// Encodes the message, prefixed with its id.
+    pub fn encoded(&self) -> BytesMut {
+        let mut buf = BytesMut::new();
+        buf.put_u8(self.message_id() as u8);
+        match self {
+            Self::Status(msg) => msg.encode(&mut buf),
+            Self::GetBlockHeaders(msg) => msg.encode(&mut buf),
+            Self::BlockHeaders(msg) => msg.encode(&mut buf),
+            Self::GetHeaderProofs(msg) => msg.encode(&mut buf),
+            Self::HeaderProofs(msg) => msg.encode(&mut buf),
+            Self::GetReceiptProofs(msg) => msg.encode(&mut buf),
+            Self::ReceiptProofs(msg) => msg.encode(&mut buf),
+            Self::Announce(msg) => msg.encode(&mut buf),
+        }
+        buf
+    }
+
+    /// Decodes a message that is prefixed with its id.
+    pub fn decode_message(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let (&id, rest) = buf.split_first().ok_or(alloy_rlp::Error::InputTooShort)?;
+        *buf = rest;
+        let msg = match LightMessageId::try_from(id)? {
+            LightMessageId::Status => Self::Status(Decodable::decode(buf)?),
+            LightMessageId::GetBlockHeaders => Self::GetBlockHeaders(Decodable::decode(buf)?),
+            LightMessageId::BlockHeaders => Self::BlockHeaders(Decodable::decode(buf)?),
+            LightMessageId::GetHeaderProofs => Self::GetHeaderProofs(Decodable::decode(buf)?),
+            LightMessageId::HeaderProofs => Self::HeaderProofs(Decodable::decode(buf)?),
+            LightMessageId::GetReceiptProofs => Self::GetReceiptProofs(Decodable::decode(buf)?),
+            LightMessageId::ReceiptProofs => Self::ReceiptProofs(Decodable::decode(buf)?),
+            LightMessageId::Announce => Self::Announce(Decodable::decode(buf)?),
+        };
+        Ok(msg)
+    }
+}
+
+/// The status a peer sends once the connection is established.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct LightStatus {
+    /// The chain id of the peer.
+    pub chain_id: u64,
+    /// The genesis hash of the peer's chain.
+    pub genesis_hash: B256,
+    /// The hash of the peer's chain head.
+    pub head_hash: B256,
+    /// The number of the peer's chain head.
+    pub head_number: u64,
+    /// The number of blocks per CHT section.
+    pub cht_section_size: u64,
+    /// The quota the peer serves requests with, all zero if the peer doesn't serve requests.
+    pub quota: ServingQuota,
+}
+
+/// Announces a new chain head.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct Announcement {
+    /// The hash of the new head.
+    pub head_hash: B256,
+    /// The number of the new head.
+    pub head_number: u64,
+}
+
+/// Requests block headers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct GetBlockHeadersRequest {
+    /// The id of the request.
+    pub request_id: u64,
+    /// Which headers to return.
+    pub request: GetBlockHeaders,
+}
+
+/// The response to [`GetBlockHeadersRequest`].
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct BlockHeadersResponse {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The remaining credits of the requesting peer.
+    pub credits: u64,
+    /// The requested headers.
+    pub headers: Vec<Header>,
+}
+
+/// Requests CHT proofs of canonical headers.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct GetHeaderProofsRequest {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The numbers of the requested headers.
+    pub numbers: Vec<u64>,
+}
+
+/// A header and the CHT proof of its hash.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct HeaderProof {
+    /// The canonical header.
+    pub header: Header,
+    /// The proof of the header hash in the CHT of the header's section.
+    pub proof: Vec<Bytes>,
+}
+
+/// The response to [`GetHeaderProofsRequest`].
+///
+/// The proofs are in the order of the requested numbers. The response ends early at the first
+/// number that can't be proven, for example because its section isn't complete yet.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct HeaderProofsResponse {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The remaining credits of the requesting peer.
+    pub credits: u64,
+    /// The requested proofs.
+    pub proofs: Vec<HeaderProof>,
+}
+
+/// Identifies a receipt by its block and index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct ReceiptProofRequest {
+    /// The hash of the block that contains the receipt.
+    pub block_hash: B256,
+    /// The index of the receipt in the block.
+    pub index: u64,
+}
+
+/// Requests receipt proofs.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct GetReceiptProofsRequest {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The requested receipts.
+    pub receipts: Vec<ReceiptProofRequest>,
+}
+
+/// A receipt and the proof of it in the receipts trie of its block.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct ReceiptProof {
+    /// The receipt.
+    pub receipt: ReceiptWithBloom,
+    /// The proof of the receipt against the receipts root of its block.
+    pub proof: Vec<Bytes>,
+}
+
+/// The response to [`GetReceiptProofsRequest`].
+///
+/// The proofs are in the order of the requested receipts. The response ends early at the first
+/// receipt that isn't available.
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct ReceiptProofsResponse {
+    /// The id of the request.
+    pub request_id: u64,
+    /// The remaining credits of the requesting peer.
+    pub credits: u64,
+    /// The requested proofs.
+    pub proofs: Vec<ReceiptProof>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{BlockHashOrNumber, HeadersDirection};
+
+    #[test]
+    fn roundtrip_messages() {
+        let messages = [
+            LightMessage::Status(LightStatus {
+                chain_id: 1,
+                genesis_hash: B256::with_last_byte(1),
+                head_hash: B256::with_last_byte(2),
+                head_number: 100,
+                cht_section_size: 32_768,
+                quota: ServingQuota::default(),
+            }),
+            LightMessage::GetBlockHeaders(GetBlockHeadersRequest {
+                request_id: 7,
+                request: GetBlockHeaders {
+                    start_block: BlockHashOrNumber::Number(10),
+                    limit: 5,
+                    skip: 0,
+                    direction: HeadersDirection::Rising,
+                },
+            }),
+            LightMessage::HeaderProofs(HeaderProofsResponse {
+                request_id: 8,
+                credits: 100,
+                proofs: vec![HeaderProof {
+                    header: Header::default(),
+                    proof: vec![Bytes::from_static(&[1, 2, 3])],
+                }],
+            }),
+            LightMessage::Announce(Announcement { head_hash: B256::ZERO, head_number: 1 }),
+        ];
+
+        for message in messages {
+            let encoded = message.encoded();
+            assert_eq!(encoded[0], message.message_id() as u8);
+            assert_eq!(LightMessage::decode_message(&mut &encoded[..]).unwrap(), message);
+        }
+    }
+}
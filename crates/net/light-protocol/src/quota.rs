@@ -0,0 +1,144 @@
+//! Per-peer serving quotas.
+
+use alloy_rlp::{RlpDecodable, RlpEncodable};
+use std::time::{Duration, Instant};
+
+/// The quota a server serves requests of a single peer with.
+///
+/// Every peer starts with `capacity` credits, which recharge at `recharge_per_sec` up to the
+/// capacity. Each served item costs the credits configured for its kind. Requests that cost more
+/// than the peer's remaining credits are answered with an empty response.
+///
+/// The quota is announced in the [`LightStatus`](crate::LightStatus), so clients can pace their
+/// requests.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct ServingQuota {
+    /// The maximum number of credits of a peer.
+    pub capacity: u64,
+    /// The number of credits a peer regains per second.
+    pub recharge_per_sec: u64,
+    /// The cost of a single header.
+    pub header_cost: u64,
+    /// The cost of a single CHT proof.
+    pub header_proof_cost: u64,
+    /// The cost of a single receipt proof.
+    pub receipt_proof_cost: u64,
+}
+
+impl ServingQuota {
+    /// A quota that doesn't allow any requests, announced by peers that don't serve.
+    pub const fn none() -> Self {
+        Self {
+            capacity: 0,
+            recharge_per_sec: 0,
+            header_cost: 0,
+            header_proof_cost: 0,
+            receipt_proof_cost: 0,
+        }
+    }
+
+    /// Returns `true` if requests are served with this quota.
+    pub const fn is_serving(&self) -> bool {
+        self.capacity > 0
+    }
+}
+
+impl Default for ServingQuota {
+    fn default() -> Self {
+        Self {
+            capacity: 20_000,
+            recharge_per_sec: 2_000,
+            header_cost: 1,
+            // proving a header may require building the CHT of its section
+            header_proof_cost: 500,
+            receipt_proof_cost: 20,
+        }
+    }
+}
+
+/// Tracks the credits of a peer for a [`ServingQuota`].
+///
+/// Servers use this to enforce the quota, clients to estimate their remaining credits.
+#[derive(Debug, Clone)]
+pub struct Credits {
+    quota: ServingQuota,
+    credits: u64,
+    last_recharge: Instant,
+}
+
+impl Credits {
+    /// Creates new credits that start at the full capacity of the quota.
+    pub fn new(quota: ServingQuota) -> Self {
+        Self { quota, credits: quota.capacity, last_recharge: Instant::now() }
+    }
+
+    /// Returns the quota the credits are tracked for.
+    pub const fn quota(&self) -> &ServingQuota {
+        &self.quota
+    }
+
+    /// Returns the currently available credits.
+    pub fn available(&mut self) -> u64 {
+        self.recharge();
+        self.credits
+    }
+
+    /// Takes the given cost from the credits if they suffice.
+    pub fn try_consume(&mut self, cost: u64) -> bool {
+        self.recharge();
+        if cost > self.credits {
+            return false
+        }
+        self.credits -= cost;
+        true
+    }
+
+    /// Overrides the available credits, for example with the value reported by the server.
+    pub fn set(&mut self, credits: u64) {
+        self.credits = credits.min(self.quota.capacity);
+        self.last_recharge = Instant::now();
+    }
+
+    /// Returns how long it takes until the credits suffice for the given cost, `None` if they
+    /// already do.
+    ///
+    /// Returns [`Duration::MAX`] if the credits never suffice.
+    pub fn delay_for(&mut self, cost: u64) -> Option<Duration> {
+        self.recharge();
+        let missing = cost.checked_sub(self.credits).filter(|missing| *missing > 0)?;
+        if cost > self.quota.capacity || self.quota.recharge_per_sec == 0 {
+            return Some(Duration::MAX)
+        }
+        Some(Duration::from_millis(missing.saturating_mul(1_000) / self.quota.recharge_per_sec + 1))
+    }
+
+    fn recharge(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_recharge).as_millis() as u64;
+        let recharged = elapsed.saturating_mul(self.quota.recharge_per_sec) / 1_000;
+        if recharged > 0 {
+            self.credits = self.credits.saturating_add(recharged).min(self.quota.capacity);
+            self.last_recharge = now;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn consume_credits() {
+        let quota = ServingQuota { capacity: 100, recharge_per_sec: 10, ..Default::default() };
+        let mut credits = Credits::new(quota);
+
+        assert!(credits.try_consume(60));
+        assert!(!credits.try_consume(60));
+        assert!(credits.available() >= 40);
+
+        let delay = credits.delay_for(60).unwrap();
+        assert!(delay > Duration::from_millis(1_500) && delay <= Duration::from_millis(2_001));
+        assert_eq!(credits.delay_for(101), Some(Duration::MAX));
+        assert_eq!(credits.delay_for(40), None);
+    }
+}
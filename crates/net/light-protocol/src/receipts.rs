@@ -0,0 +1,85 @@
+//! Proofs of receipts against the receipts root of their block.
+
+use alloy_rlp::Encodable;
+use reth_primitives::{
+    proofs::adjust_index_for_rlp,
+    trie::{verify_proof, HashBuilder, Nibbles, ProofVerificationError},
+    Bytes, ReceiptWithBloom, B256,
+};
+
+/// Computes the receipts root of a block and the proof of the receipt with the given index.
+///
+/// The root matches [`calculate_receipt_root`](reth_primitives::proofs::calculate_receipt_root).
+pub fn receipt_root_with_proof(receipts: &[ReceiptWithBloom], index: usize) -> (B256, Vec<Bytes>) {
+    let target = receipt_key(index as u64);
+    let mut hash_builder = HashBuilder::default().with_proof_retainer(vec![target.clone()]);
+
+    // leaves must be added in the order of their keys, which is not the order of the indices
+    // because of the RLP encoding
+    let mut value = Vec::new();
+    for i in 0..receipts.len() {
+        let index = adjust_index_for_rlp(i, receipts.len());
+        value.clear();
+        receipts[index].encode_enveloped(&mut value);
+        hash_builder.add_leaf(receipt_key(index as u64), &value);
+    }
+    let root = hash_builder.root();
+
+    let proof = hash_builder
+        .take_proofs()
+        .into_iter()
+        .filter(|(path, _)| target.starts_with(path))
+        .map(|(_, node)| node)
+        .collect();
+    (root, proof)
+}
+
+/// Verifies that `receipt` is the receipt with the given index of the block with the given
+/// receipts root.
+pub fn verify_receipt_proof(
+    receipts_root: B256,
+    index: u64,
+    receipt: &ReceiptWithBloom,
+    proof: &[Bytes],
+) -> Result<(), ProofVerificationError> {
+    let mut value = Vec::new();
+    receipt.encode_enveloped(&mut value);
+    verify_proof(receipts_root, &receipt_key(index), Some(&value), proof)
+}
+
+/// Returns the key of a receipt in the receipts trie.
+fn receipt_key(index: u64) -> Nibbles {
+    let mut buf = Vec::new();
+    index.encode(&mut buf);
+    Nibbles::unpack(buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{proofs::calculate_receipt_root, Receipt, TxType};
+
+    #[test]
+    fn prove_receipts() {
+        let receipts = (0..200u64)
+            .map(|i| {
+                Receipt {
+                    tx_type: if i % 2 == 0 { TxType::Legacy } else { TxType::Eip1559 },
+                    success: true,
+                    cumulative_gas_used: 21_000 * (i + 1),
+                    ..Default::default()
+                }
+                .with_bloom()
+            })
+            .collect::<Vec<_>>();
+        let root = calculate_receipt_root(&receipts);
+
+        for index in [0, 1, 126, 127, 128, 199] {
+            let (proof_root, proof) = receipt_root_with_proof(&receipts, index);
+            assert_eq!(proof_root, root);
+            assert_eq!(verify_receipt_proof(root, index as u64, &receipts[index], &proof), Ok(()));
+            assert!(verify_receipt_proof(root, index as u64, &receipts[(index + 1) % 200], &proof)
+                .is_err());
+        }
+    }
+}
@@ -0,0 +1,602 @@
+//! Serving the `rlight` protocol.
+
+use crate::{
+    cht::{self, SectionTrie, DEFAULT_CHT_SECTION_SIZE},
+    message::{
+        Announcement, BlockHeadersResponse, GetBlockHeadersRequest, GetHeaderProofsRequest,
+        GetReceiptProofsRequest, HeaderProof, HeaderProofsResponse, ReceiptProof,
+        ReceiptProofRequest, ReceiptProofsResponse,
+    },
+    receipts::receipt_root_with_proof,
+    Credits, LightMessage, LightStatus, ServingQuota,
+};
+use futures::{FutureExt, Stream, StreamExt};
+use reth_eth_wire::{
+    capability::SharedCapabilities, multiplex::ProtocolConnection, protocol::Protocol,
+    GetBlockHeaders,
+};
+use reth_network::protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler};
+use reth_network_api::Direction;
+use reth_primitives::{
+    BlockHashOrNumber, BlockNumHash, Bytes, BytesMut, Header, HeadersDirection, PeerId, B256,
+};
+use reth_provider::{BlockReader, CanonStateNotificationStream, ChainSpecProvider};
+use reth_tasks::{TaskSpawner, TokioTaskExecutor};
+use schnellru::{ByLength, LruMap};
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{ready, Context, Poll},
+};
+use tokio::sync::{oneshot, watch};
+use tokio_stream::wrappers::WatchStream;
+use tracing::trace;
+
+/// Configures how a [`LightServer`] serves requests.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LightServerConfig {
+    /// The quota every peer is served with.
+    pub quota: ServingQuota,
+    /// The number of blocks per CHT section.
+    pub cht_section_size: u64,
+    /// The maximum number of headers served per request.
+    pub max_headers: u64,
+    /// The maximum number of proofs served per request.
+    pub max_proofs: usize,
+    /// The maximum number of CHTs of complete sections that are kept in memory to serve proofs.
+    pub max_cached_sections: u32,
+}
+
+impl Default for LightServerConfig {
+    fn default() -> Self {
+        Self {
+            quota: ServingQuota::default(),
+            cht_section_size: DEFAULT_CHT_SECTION_SIZE,
+            max_headers: 1024,
+            max_proofs: 64,
+            max_cached_sections: 4,
+        }
+    }
+}
+
+/// Serves the `rlight` protocol to all peers that support it.
+///
+/// The server announces its chain head to connected peers. The head must be kept up to date with
+/// [`LightServer::set_head`] or [`LightServer::track_canonical_head`].
+///
+/// Requests are served on blocking tasks, since they read from the database and proving headers
+/// requires building the CHT of their section.
+///
+/// The credits of a peer are kept across its connections, so reconnecting doesn't restore them.
+///
+/// This type is cheap to clone, all clones share the same head.
+#[derive(Clone)]
+pub struct LightServer<P> {
+    provider: P,
+    config: Arc<LightServerConfig>,
+    head: Arc<watch::Sender<BlockNumHash>>,
+    /// The roots of the CHTs of complete sections that have been built, by section.
+    cht_roots: Arc<Mutex<HashMap<u64, B256>>>,
+    /// The most recently used CHTs of complete sections, by section.
+    cht_tries: Arc<Mutex<LruMap<u64, Arc<SectionTrie>, ByLength>>>,
+    /// The credits of the peers that are connected or still recharging.
+    credits: Arc<Mutex<HashMap<PeerId, Credits>>>,
+    /// The type that's used to spawn the tasks that serve requests.
+    task_spawner: Box<dyn TaskSpawner>,
+}
+
+impl<P> LightServer<P>
+where
+    P: BlockReader + Clone,
+{
+    /// Creates a new server that serves the data of the given provider.
+    ///
+    /// The head is initialized with the provider's best block. Requests are served on tasks
+    /// spawned via [tokio::task::spawn_blocking].
+    pub fn new(provider: P, config: LightServerConfig) -> Self {
+        let head = provider
+            .chain_info()
+            .map(|info| BlockNumHash::new(info.best_number, info.best_hash))
+            .unwrap_or_default();
+        let cht_tries = LruMap::new(ByLength::new(config.max_cached_sections));
+        Self {
+            provider,
+            config: Arc::new(config),
+            head: Arc::new(watch::channel(head).0),
+            cht_roots: Default::default(),
+            cht_tries: Arc::new(Mutex::new(cht_tries)),
+            credits: Default::default(),
+            task_spawner: Box::<TokioTaskExecutor>::default(),
+        }
+    }
+
+    /// Returns the root of the CHT of the given section, if the section is complete.
+    ///
+    /// Roots are cached once built, this only reads the canonical hashes of the section and builds
+    /// its CHT on a cache miss. This is blocking and should not be called on the async runtime.
+    pub fn cht_root(&self, section: u64) -> Option<B256> {
+        let (root, _) = RequestServer::from(self).section_proofs(section, &[])?;
+        Some(root)
+    }
+}
+
+impl<P> LightServer<P> {
+    /// Sets the type that's used to spawn the tasks that serve requests.
+    pub fn with_task_spawner(mut self, task_spawner: Box<dyn TaskSpawner>) -> Self {
+        self.task_spawner = task_spawner;
+        self
+    }
+
+    /// Returns the current head of the server.
+    pub fn head(&self) -> BlockNumHash {
+        *self.head.borrow()
+    }
+
+    /// Sets the head of the server and announces it to all connected peers.
+    pub fn set_head(&self, head: BlockNumHash) {
+        self.head.send_if_modified(|current| {
+            let modified = *current != head;
+            *current = head;
+            modified
+        });
+    }
+
+    /// Keeps the head of the server in sync with the canonical chain.
+    pub async fn track_canonical_head(self, notifications: CanonStateNotificationStream) {
+        let mut notifications = std::pin::pin!(notifications);
+        while let Some(notification) = notifications.next().await {
+            self.set_head(notification.tip().header.num_hash());
+        }
+    }
+}
+
+impl<P> fmt::Debug for LightServer<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LightServer")
+            .field("config", &self.config)
+            .field("head", &self.head())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P> ProtocolHandler for LightServer<P>
+where
+    P: BlockReader + ChainSpecProvider + Clone + Unpin + 'static,
+{
+    type ConnectionHandler = LightServerConnectionHandler<P>;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(LightServerConnectionHandler { server: self.clone() })
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(LightServerConnectionHandler { server: self.clone() })
+    }
+}
+
+/// Establishes `rlight` connections on behalf of a [`LightServer`].
+#[derive(Debug)]
+pub struct LightServerConnectionHandler<P> {
+    server: LightServer<P>,
+}
+
+impl<P> ConnectionHandler for LightServerConnectionHandler<P>
+where
+    P: BlockReader + ChainSpecProvider + Clone + Unpin + 'static,
+{
+    type Connection = LightServerConnection<P>;
+
+    fn protocol(&self) -> Protocol {
+        LightMessage::protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        let server = RequestServer::from(&self.server);
+        let LightServer { provider, config, head, credits, task_spawner, .. } = self.server;
+        let chain_spec = provider.chain_spec();
+        let current_head = *head.borrow();
+        let status = LightStatus {
+            chain_id: chain_spec.chain.id(),
+            genesis_hash: chain_spec.genesis_hash(),
+            head_hash: current_head.hash,
+            head_number: current_head.number,
+            cht_section_size: config.cht_section_size,
+            quota: config.quota,
+        };
+
+        LightServerConnection {
+            peer_id,
+            conn,
+            credits,
+            status: Some(status),
+            head_updates: WatchStream::from_changes(head.subscribe()),
+            head: current_head,
+            server,
+            config,
+            task_spawner,
+            pending_response: None,
+        }
+    }
+}
+
+/// An `rlight` connection with a peer, served by a [`LightServer`].
+///
+/// Requests are answered in order, the connection is closed if the peer sends an invalid message.
+/// While a request is served on a blocking task, no further requests are read from the peer.
+pub struct LightServerConnection<P> {
+    peer_id: PeerId,
+    conn: ProtocolConnection,
+    /// Serves the requests of the peer.
+    server: RequestServer<P>,
+    config: Arc<LightServerConfig>,
+    /// The type that's used to spawn the tasks that serve requests.
+    task_spawner: Box<dyn TaskSpawner>,
+    /// The credits of all peers of the server.
+    credits: Arc<Mutex<HashMap<PeerId, Credits>>>,
+    /// The status to send once the connection is established.
+    status: Option<LightStatus>,
+    /// Yields new heads of the server.
+    head_updates: WatchStream<BlockNumHash>,
+    /// The last head announced to the peer.
+    head: BlockNumHash,
+    /// The response to the request that is currently served, if any.
+    pending_response: Option<oneshot::Receiver<LightMessage>>,
+}
+
+impl<P> LightServerConnection<P>
+where
+    P: BlockReader + Clone + 'static,
+{
+    /// Handles a message of the peer.
+    ///
+    /// Requests are charged to the credits of the peer and served on a blocking task. If the
+    /// credits don't suffice, the empty response is returned right away.
+    fn on_message(&mut self, msg: LightMessage) -> Option<LightMessage> {
+        let request = match msg {
+            LightMessage::GetBlockHeaders(GetBlockHeadersRequest { request_id, request }) => {
+                let limit = request.limit.min(self.config.max_headers);
+                let cost = limit * self.config.quota.header_cost;
+                if !self.try_consume(cost) {
+                    return Some(LightMessage::BlockHeaders(BlockHeadersResponse {
+                        request_id,
+                        credits: self.available_credits(),
+                        headers: Vec::new(),
+                    }))
+                }
+                Request::Headers { request_id, request: GetBlockHeaders { limit, ..request } }
+            }
+            LightMessage::GetHeaderProofs(GetHeaderProofsRequest { request_id, mut numbers }) => {
+                numbers.truncate(self.config.max_proofs);
+                let cost = numbers.len() as u64 * self.config.quota.header_proof_cost;
+                if !self.try_consume(cost) {
+                    return Some(LightMessage::HeaderProofs(HeaderProofsResponse {
+                        request_id,
+                        credits: self.available_credits(),
+                        proofs: Vec::new(),
+                    }))
+                }
+                Request::HeaderProofs { request_id, numbers, head: self.head.number }
+            }
+            LightMessage::GetReceiptProofs(GetReceiptProofsRequest {
+                request_id,
+                mut receipts,
+            }) => {
+                receipts.truncate(self.config.max_proofs);
+                let cost = receipts.len() as u64 * self.config.quota.receipt_proof_cost;
+                if !self.try_consume(cost) {
+                    return Some(LightMessage::ReceiptProofs(ReceiptProofsResponse {
+                        request_id,
+                        credits: self.available_credits(),
+                        proofs: Vec::new(),
+                    }))
+                }
+                Request::ReceiptProofs { request_id, receipts }
+            }
+            // nothing to do for status updates and announcements of the peer, responses are
+            // unsolicited since we never send requests
+            _ => return None,
+        };
+
+        let (tx, rx) = oneshot::channel();
+        let server = self.server.clone();
+        let credits = self.available_credits();
+        self.task_spawner.spawn_blocking(Box::pin(async move {
+            let _ = tx.send(server.serve(request, credits));
+        }));
+        self.pending_response = Some(rx);
+        None
+    }
+
+    fn try_consume(&self, cost: u64) -> bool {
+        let consumed = self.with_credits(|credits| credits.try_consume(cost));
+        if !consumed {
+            trace!(target: "net::light", peer_id=?self.peer_id, "quota exceeded");
+        }
+        consumed
+    }
+
+    fn available_credits(&self) -> u64 {
+        self.with_credits(Credits::available)
+    }
+
+    /// Calls the closure with the credits of the peer, which start at the full capacity of the
+    /// quota.
+    fn with_credits<R>(&self, f: impl FnOnce(&mut Credits) -> R) -> R {
+        let mut credits = self.credits.lock().expect("not poisoned");
+        f(credits.entry(self.peer_id).or_insert_with(|| Credits::new(self.config.quota)))
+    }
+}
+
+/// A request that was charged to the credits of a peer and is served on a blocking task.
+#[derive(Debug)]
+enum Request {
+    Headers { request_id: u64, request: GetBlockHeaders },
+    HeaderProofs { request_id: u64, numbers: Vec<u64>, head: u64 },
+    ReceiptProofs { request_id: u64, receipts: Vec<ReceiptProofRequest> },
+}
+
+/// Reads the data of requests from the provider, shared by the connections of a [`LightServer`].
+#[derive(Clone)]
+struct RequestServer<P> {
+    provider: P,
+    config: Arc<LightServerConfig>,
+    cht_roots: Arc<Mutex<HashMap<u64, B256>>>,
+    cht_tries: Arc<Mutex<LruMap<u64, Arc<SectionTrie>, ByLength>>>,
+}
+
+impl<P: Clone> From<&LightServer<P>> for RequestServer<P> {
+    fn from(server: &LightServer<P>) -> Self {
+        Self {
+            provider: server.provider.clone(),
+            config: server.config.clone(),
+            cht_roots: server.cht_roots.clone(),
+            cht_tries: server.cht_tries.clone(),
+        }
+    }
+}
+
+impl<P> RequestServer<P>
+where
+    P: BlockReader,
+{
+    /// Serves the request, `credits` are the remaining credits of the peer.
+    ///
+    /// This is blocking.
+    fn serve(&self, request: Request, credits: u64) -> LightMessage {
+        match request {
+            Request::Headers { request_id, request } => {
+                let headers = self.get_headers(request);
+                LightMessage::BlockHeaders(BlockHeadersResponse { request_id, credits, headers })
+            }
+            Request::HeaderProofs { request_id, numbers, head } => {
+                let proofs = self.header_proofs(numbers, head);
+                LightMessage::HeaderProofs(HeaderProofsResponse { request_id, credits, proofs })
+            }
+            Request::ReceiptProofs { request_id, receipts } => {
+                let proofs = self.receipt_proofs(receipts);
+                LightMessage::ReceiptProofs(ReceiptProofsResponse { request_id, credits, proofs })
+            }
+        }
+    }
+
+    /// Returns the CHT proofs of the given canonical headers, up to the first header that can't be
+    /// proven.
+    ///
+    /// Only sections that are complete with respect to the given head are proven.
+    fn header_proofs(&self, numbers: Vec<u64>, head: u64) -> Vec<HeaderProof> {
+        let section_size = self.config.cht_section_size;
+        let mut proofs = Vec::with_capacity(numbers.len());
+
+        // consecutive requested numbers of the same section are proven with a single trie
+        let mut numbers = numbers.into_iter().peekable();
+        while let Some(first) = numbers.next() {
+            let section = cht::section_of(first, section_size);
+            let mut targets = vec![first];
+            while let Some(number) =
+                numbers.next_if(|number| cht::section_of(*number, section_size) == section)
+            {
+                targets.push(number);
+            }
+
+            let start = cht::section_start(section, section_size);
+            if start + section_size > head + 1 {
+                break
+            }
+            let Some((_, section_proofs)) = self.section_proofs(section, &targets) else { break };
+            for (number, proof) in targets.into_iter().zip(section_proofs) {
+                let Some(header) = self.provider.header_by_number(number).unwrap_or_default()
+                else {
+                    return proofs
+                };
+                proofs.push(HeaderProof { header, proof });
+            }
+        }
+
+        proofs
+    }
+
+    /// Returns the root of the CHT of a complete section and the proofs of the given blocks.
+    ///
+    /// The CHTs of the most recently used sections are kept in memory, the CHT of any other
+    /// section is built from its canonical hashes. Returns `None` if the canonical hashes of the
+    /// section are not available.
+    fn section_proofs(&self, section: u64, targets: &[u64]) -> Option<(B256, Vec<Vec<Bytes>>)> {
+        let cached = self.cht_roots.lock().expect("not poisoned").get(&section).copied();
+        if let (Some(root), true) = (cached, targets.is_empty()) {
+            return Some((root, Vec::new()))
+        }
+
+        let trie = self.cht_tries.lock().expect("not poisoned").get(&section).cloned();
+        let trie = match trie {
+            Some(trie) => trie,
+            None => {
+                let section_size = self.config.cht_section_size;
+                let start = cht::section_start(section, section_size);
+                let hashes = self
+                    .provider
+                    .canonical_hashes_range(start, start + section_size)
+                    .unwrap_or_default();
+                if hashes.len() as u64 != section_size {
+                    return None
+                }
+
+                let trie = Arc::new(SectionTrie::new(start, &hashes));
+                self.cht_tries.lock().expect("not poisoned").insert(section, trie.clone());
+                trie
+            }
+        };
+
+        let root = trie.root();
+        if cached != Some(root) {
+            self.cht_roots.lock().expect("not poisoned").insert(section, root);
+        }
+        Some((root, targets.iter().map(|number| trie.proof(*number)).collect()))
+    }
+
+    /// Returns the requested receipt proofs, up to the first receipt that is not available.
+    fn receipt_proofs(&self, receipts: Vec<ReceiptProofRequest>) -> Vec<ReceiptProof> {
+        let mut proofs = Vec::with_capacity(receipts.len());
+        for request in receipts {
+            let Some(block_receipts) =
+                self.provider.receipts_by_block(request.block_hash.into()).unwrap_or_default()
+            else {
+                break
+            };
+            let index = request.index as usize;
+            if index >= block_receipts.len() {
+                break
+            }
+            let block_receipts =
+                block_receipts.into_iter().map(|receipt| receipt.with_bloom()).collect::<Vec<_>>();
+            let (_, proof) = receipt_root_with_proof(&block_receipts, index);
+            proofs.push(ReceiptProof { receipt: block_receipts[index].clone(), proof });
+        }
+        proofs
+    }
+
+    /// Returns the requested headers, like the `eth` request handler.
+    fn get_headers(&self, request: GetBlockHeaders) -> Vec<Header> {
+        let GetBlockHeaders { start_block, limit, skip, direction } = request;
+        let mut headers = Vec::new();
+
+        let mut block = start_block;
+        let skip = skip as u64;
+        for _ in 0..limit {
+            let Some(header) = self.provider.header_by_hash_or_number(block).unwrap_or_default()
+            else {
+                break
+            };
+            let next = match direction {
+                HeadersDirection::Rising => {
+                    (header.number + 1).checked_add(skip).map(BlockHashOrNumber::Number)
+                }
+                HeadersDirection::Falling if skip == 0 => Some(header.parent_hash.into()),
+                HeadersDirection::Falling => header
+                    .number
+                    .checked_sub(1)
+                    .and_then(|num| num.checked_sub(skip))
+                    .map(BlockHashOrNumber::Number),
+            };
+            headers.push(header);
+            match next {
+                Some(next) => block = next,
+                None => break,
+            }
+        }
+
+        headers
+    }
+}
+
+impl<P> fmt::Debug for RequestServer<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestServer").field("config", &self.config).finish_non_exhaustive()
+    }
+}
+
+impl<P> Stream for LightServerConnection<P>
+where
+    P: BlockReader + Clone + Unpin + 'static,
+{
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        if let Some(status) = this.status.take() {
+            return Poll::Ready(Some(LightMessage::Status(status).encoded()))
+        }
+
+        loop {
+            if let Poll::Ready(Some(head)) = this.head_updates.poll_next_unpin(cx) {
+                this.head = head;
+                let announcement = Announcement { head_hash: head.hash, head_number: head.number };
+                return Poll::Ready(Some(LightMessage::Announce(announcement).encoded()))
+            }
+
+            // requests are answered in order, so the peer is not read while serving a request
+            if let Some(pending) = this.pending_response.as_mut() {
+                let response = ready!(pending.poll_unpin(cx));
+                this.pending_response = None;
+                match response {
+                    Ok(response) => return Poll::Ready(Some(response.encoded())),
+                    // the task was dropped, the runtime is shutting down
+                    Err(_) => return Poll::Ready(None),
+                }
+            }
+
+            let Some(msg) = ready!(this.conn.poll_next_unpin(cx)) else { return Poll::Ready(None) };
+            let msg = match LightMessage::decode_message(&mut &msg[..]) {
+                Ok(msg) => msg,
+                Err(err) => {
+                    trace!(target: "net::light", peer_id=?this.peer_id, %err, "invalid message");
+                    return Poll::Ready(None)
+                }
+            };
+
+            if let Some(response) = this.on_message(msg) {
+                return Poll::Ready(Some(response.encoded()))
+            }
+        }
+    }
+}
+
+impl<P> Drop for LightServerConnection<P> {
+    fn drop(&mut self) {
+        // peers that recharged to the full capacity start over with full credits anyway
+        let capacity = self.config.quota.capacity;
+        self.credits
+            .lock()
+            .expect("not poisoned")
+            .retain(|_, credits| credits.available() < capacity);
+    }
+}
+
+impl<P> fmt::Debug for LightServerConnection<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LightServerConnection")
+            .field("peer_id", &self.peer_id)
+            .field("head", &self.head)
+            .finish_non_exhaustive()
+    }
+}
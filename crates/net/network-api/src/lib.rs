@@ -15,7 +15,13 @@
 
 use reth_eth_wire::{DisconnectReason, EthVersion, Status};
 use reth_primitives::{NodeRecord, PeerId};
-use std::{future::Future, net::SocketAddr, sync::Arc, time::Instant};
+use std::{
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc::UnboundedReceiver;
 
 pub use error::NetworkError;
 pub use reputation::{Reputation, ReputationChangeKind};
@@ -137,6 +143,9 @@ pub trait Peers: PeersInfo {
         &self,
         peer_id: PeerId,
     ) -> impl Future<Output = Result<Option<Reputation>, NetworkError>> + Send;
+
+    /// Returns a new receiver for [PeerEvent]s.
+    fn peer_events(&self) -> UnboundedReceiver<PeerEvent>;
 }
 
 /// Represents the kind of peer
@@ -182,6 +191,84 @@ pub struct PeerInfo {
     pub status: Arc<Status>,
     /// The timestamp when the session to that peer has been established.
     pub session_established: Instant,
+    /// The current reputation of the peer
+    pub reputation: Reputation,
+    /// The kind of the peer
+    pub kind: PeerKind,
+    /// The number of bytes received from the peer during the session
+    pub ingress_bytes: u64,
+    /// The number of bytes sent to the peer during the session
+    pub egress_bytes: u64,
+    /// The time it took the peer to respond to the last request sent to it, if any
+    pub last_request_latency: Option<Duration>,
+    /// Statistics on the header and body requests served by the peer during the session
+    pub request_stats: PeerRequestStats,
+}
+
+/// Statistics on the header and body requests a peer served during its session.
+///
+/// These are used to prefer fast and reliable peers when dispatching sync requests.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PeerRequestStats {
+    /// The number of requests sent to the peer.
+    pub requests: u64,
+    /// The number of requests that timed out.
+    pub timeouts: u64,
+    /// The number of requests that were answered with an error or an unusable response.
+    pub bad_responses: u64,
+    /// The total RLP encoded size of all successful responses.
+    pub bytes_served: u64,
+    /// The rolling average latency of successful responses, where a timed out request counts with
+    /// the timeout. `None` if no request was answered or timed out yet.
+    pub avg_latency: Option<Duration>,
+    /// The score the peer is ranked by, lower is better.
+    ///
+    /// This is the average latency in milliseconds, inflated by the rolling rate of failed
+    /// requests. Peers without a latency sample are assumed to be as fast as the fastest peer.
+    pub score: f64,
+}
+
+/// Events about the peers of the network.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PeerEvent {
+    /// Established a new session with the peer.
+    SessionEstablished {
+        /// The identifier of the peer.
+        peer_id: PeerId,
+        /// The remote address of the session.
+        remote_addr: SocketAddr,
+        /// The direction of the session.
+        direction: Direction,
+    },
+    /// Closed the session with the peer.
+    SessionClosed {
+        /// The identifier of the peer.
+        peer_id: PeerId,
+        /// Why the session was closed, if known.
+        reason: Option<DisconnectReason>,
+    },
+    /// The peer was added to the peer set.
+    PeerAdded(PeerId),
+    /// The peer was removed from the peer set.
+    PeerRemoved(PeerId),
+    /// The peer was banned.
+    PeerBanned(PeerId),
+    /// The peer was unbanned.
+    PeerUnbanned(PeerId),
+}
+
+impl PeerEvent {
+    /// Returns the identifier of the peer this event is about.
+    pub const fn peer_id(&self) -> &PeerId {
+        match self {
+            PeerEvent::SessionEstablished { peer_id, .. } |
+            PeerEvent::SessionClosed { peer_id, .. } |
+            PeerEvent::PeerAdded(peer_id) |
+            PeerEvent::PeerRemoved(peer_id) |
+            PeerEvent::PeerBanned(peer_id) |
+            PeerEvent::PeerUnbanned(peer_id) => peer_id,
+        }
+    }
 }
 
 /// The direction of the connection.
@@ -4,7 +4,7 @@
 //! generic over it.
 
 use crate::{
-    NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
+    NetworkError, NetworkInfo, PeerEvent, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
     ReputationChangeKind,
 };
 use enr::{secp256k1::SecretKey, Enr};
@@ -13,6 +13,7 @@ use reth_eth_wire::{DisconnectReason, ProtocolVersion};
 use reth_primitives::{Chain, NodeRecord, PeerId};
 use reth_rpc_types::{admin::EthProtocolInfo, NetworkStatus};
 use std::net::{IpAddr, SocketAddr};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
 /// A type that implements all network trait that does nothing.
 ///
@@ -100,4 +101,9 @@ impl Peers for NoopNetwork {
     async fn reputation_by_id(&self, _peer_id: PeerId) -> Result<Option<Reputation>, NetworkError> {
         Ok(None)
     }
+
+    fn peer_events(&self) -> UnboundedReceiver<PeerEvent> {
+        // there are no peers, so there are no events either
+        unbounded_channel().1
+    }
 }
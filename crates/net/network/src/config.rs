@@ -4,17 +4,18 @@ use crate::{
     error::NetworkError,
     import::{BlockImport, ProofOfStakeBlockImport},
     peers::PeersConfig,
-    session::SessionsConfig,
+    session::{earliest_block, SessionsConfig},
     transactions::TransactionsManagerConfig,
+    transport::{TcpTransport, Transport},
     NetworkHandle, NetworkManager,
 };
 use reth_discv4::{Discv4Config, Discv4ConfigBuilder, DEFAULT_DISCOVERY_ADDRESS};
 use reth_discv5::config::OPSTACK;
-use reth_dns_discovery::DnsDiscoveryConfig;
-use reth_eth_wire::{HelloMessage, HelloMessageWithProtocols, Status};
+use reth_dns_discovery::{BoxedResolver, DnsDiscoveryConfig, DnsTreePublisherConfig, Resolver};
+use reth_eth_wire::{BlockRangeUpdate, HelloMessage, HelloMessageWithProtocols, Status};
 use reth_primitives::{
     mainnet_nodes, pk2id, sepolia_nodes, Chain, ChainSpec, ForkFilter, Head, NamedChain,
-    NodeRecord, PeerId, MAINNET,
+    NodeRecord, PeerId, PruneModes, MAINNET,
 };
 use reth_provider::{BlockReader, HeaderProvider};
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
@@ -43,6 +44,10 @@ pub struct NetworkConfig<C> {
     pub boot_nodes: HashSet<NodeRecord>,
     /// How to set up discovery over DNS.
     pub dns_discovery_config: Option<DnsDiscoveryConfig>,
+    /// The resolver used by the DNS discovery, the system resolver if not set.
+    pub dns_resolver: Option<BoxedResolver>,
+    /// How to publish the active peers as DNS discovery tree, if at all.
+    pub dns_tree_publisher_config: Option<DnsTreePublisherConfig>,
     /// Address to use for discovery v4.
     pub discovery_v4_addr: SocketAddr,
     /// How to set up discovery.
@@ -72,6 +77,11 @@ pub struct NetworkConfig<C> {
     pub executor: Box<dyn TaskSpawner>,
     /// The `Status` message to send to peers at the beginning.
     pub status: Status,
+    /// The range of blocks announced to `eth/69` peers at the beginning.
+    pub block_range: BlockRangeUpdate,
+    /// The prune modes of the node, which determine the earliest block announced to `eth/69`
+    /// peers as the head advances.
+    pub prune_modes: PruneModes,
     /// Sets the hello message for the p2p handshake in RLPx
     pub hello_message: HelloMessageWithProtocols,
     /// Additional protocols to announce and handle in RLPx
@@ -80,6 +90,8 @@ pub struct NetworkConfig<C> {
     pub tx_gossip_disabled: bool,
     /// How to instantiate transactions manager.
     pub transactions_manager_config: TransactionsManagerConfig,
+    /// The transport used to dial and accept RLPx connections.
+    pub transport: Arc<dyn Transport>,
 }
 
 // === impl NetworkConfig ===
@@ -181,6 +193,12 @@ pub struct NetworkConfigBuilder {
     secret_key: SecretKey,
     /// How to configure discovery over DNS.
     dns_discovery_config: Option<DnsDiscoveryConfig>,
+    /// The resolver used by the DNS discovery.
+    #[serde(skip)]
+    dns_resolver: Option<BoxedResolver>,
+    /// How to publish the active peers as DNS discovery tree.
+    #[serde(skip)]
+    dns_tree_publisher_config: Option<DnsTreePublisherConfig>,
     /// How to set up discovery version 4.
     discovery_v4_builder: Option<Discv4ConfigBuilder>,
     /// Whether to enable discovery version 5. Disabled by default.
@@ -209,6 +227,9 @@ pub struct NetworkConfigBuilder {
     extra_protocols: RlpxSubProtocols,
     /// Head used to start set for the fork filter and status.
     head: Option<Head>,
+    /// The prune modes of the node, which determine the earliest block announced to `eth/69`
+    /// peers.
+    prune_modes: PruneModes,
     /// Whether tx gossip is disabled
     tx_gossip_disabled: bool,
     /// The block importer type
@@ -216,6 +237,9 @@ pub struct NetworkConfigBuilder {
     block_import: Option<Box<dyn BlockImport>>,
     /// How to instantiate transactions manager.
     transactions_manager_config: TransactionsManagerConfig,
+    /// The transport used to dial and accept RLPx connections.
+    #[serde(skip)]
+    transport: Option<Arc<dyn Transport>>,
 }
 
 // === impl NetworkConfigBuilder ===
@@ -226,6 +250,8 @@ impl NetworkConfigBuilder {
         Self {
             secret_key,
             dns_discovery_config: Some(Default::default()),
+            dns_resolver: None,
+            dns_tree_publisher_config: None,
             discovery_v4_builder: Some(Default::default()),
             enable_discovery_v5: false,
             boot_nodes: Default::default(),
@@ -239,9 +265,11 @@ impl NetworkConfigBuilder {
             hello_message: None,
             extra_protocols: Default::default(),
             head: None,
+            prune_modes: PruneModes::none(),
             tx_gossip_disabled: false,
             block_import: None,
             transactions_manager_config: Default::default(),
+            transport: None,
         }
     }
 
@@ -272,6 +300,15 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the prune modes of the node, which determine the earliest block announced to `eth/69`
+    /// peers.
+    ///
+    /// If not set, nothing is pruned and the genesis block is announced as the earliest block.
+    pub fn prune_modes(mut self, prune_modes: PruneModes) -> Self {
+        self.prune_modes = prune_modes;
+        self
+    }
+
     /// Sets the `HelloMessage` to send when connecting to peers.
     ///
     /// ```
@@ -372,6 +409,21 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the resolver the DNS discovery looks up the trees with, instead of the system resolver.
+    pub fn dns_resolver(mut self, resolver: impl Resolver) -> Self {
+        self.dns_resolver = Some(BoxedResolver::new(resolver));
+        self
+    }
+
+    /// Publishes the local node and all peers with an active session as signed
+    /// [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) tree.
+    ///
+    /// Only peers whose ENR was received via discovery can be included.
+    pub fn publish_dns_tree(mut self, config: DnsTreePublisherConfig) -> Self {
+        self.dns_tree_publisher_config = Some(config);
+        self
+    }
+
     /// Convenience function for setting [Self::boot_nodes] to the mainnet boot nodes.
     pub fn mainnet_boot_nodes(self) -> Self {
         self.boot_nodes(mainnet_nodes())
@@ -456,6 +508,14 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the transport used to dial and accept RLPx connections.
+    ///
+    /// Defaults to [`TcpTransport`].
+    pub fn transport(mut self, transport: impl Transport) -> Self {
+        self.transport = Some(Arc::new(transport));
+        self
+    }
+
     /// Convenience function for creating a [NetworkConfig] with a noop provider that does nothing.
     #[cfg(any(test, feature = "test-utils"))]
     pub fn build_with_noop_provider(
@@ -475,6 +535,8 @@ impl NetworkConfigBuilder {
         let Self {
             secret_key,
             mut dns_discovery_config,
+            dns_resolver,
+            dns_tree_publisher_config,
             discovery_v4_builder,
             enable_discovery_v5: _,
             boot_nodes,
@@ -488,9 +550,11 @@ impl NetworkConfigBuilder {
             hello_message,
             extra_protocols,
             head,
+            prune_modes,
             tx_gossip_disabled,
             block_import,
             transactions_manager_config,
+            transport,
         } = self;
 
         let listener_addr = listener_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS);
@@ -509,6 +573,11 @@ impl NetworkConfigBuilder {
 
         // set the status
         let status = Status::spec_builder(&chain_spec, &head).build();
+        let block_range = BlockRangeUpdate {
+            earliest: earliest_block(&prune_modes, head.number),
+            latest: head.number,
+            latest_hash: head.hash,
+        };
 
         // set a fork filter based on the chain spec and head
         let fork_filter = chain_spec.fork_filter(head);
@@ -529,6 +598,8 @@ impl NetworkConfigBuilder {
             secret_key,
             boot_nodes,
             dns_discovery_config,
+            dns_resolver,
+            dns_tree_publisher_config,
             discovery_v4_config: discovery_v4_builder.map(|builder| builder.build()),
             discovery_v5_config: None,
             discovery_v4_addr: discovery_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS),
@@ -540,11 +611,14 @@ impl NetworkConfigBuilder {
             network_mode,
             executor: executor.unwrap_or_else(|| Box::<TokioTaskExecutor>::default()),
             status,
+            block_range,
+            prune_modes,
             hello_message,
             extra_protocols,
             fork_filter,
             tx_gossip_disabled,
             transactions_manager_config,
+            transport: transport.unwrap_or_else(|| Arc::new(TcpTransport)),
         }
     }
 }
@@ -578,7 +652,7 @@ mod tests {
     use super::*;
     use rand::thread_rng;
     use reth_dns_discovery::tree::LinkEntry;
-    use reth_primitives::ForkHash;
+    use reth_primitives::{ForkHash, PruneMode};
     use reth_provider::test_utils::NoopProvider;
     use std::collections::BTreeMap;
 
@@ -625,4 +699,24 @@ mod tests {
         assert_eq!(status.forkid.hash, genesis_fork_hash);
         assert_eq!(fork_filter.current().hash, genesis_fork_hash);
     }
+
+    #[test]
+    fn test_block_range_earliest_follows_prune_modes() {
+        let prune_modes =
+            PruneModes { receipts: Some(PruneMode::Distance(100)), ..PruneModes::none() };
+        let head = Head { number: 1000, ..Default::default() };
+
+        let config = builder()
+            .set_head(head)
+            .prune_modes(prune_modes.clone())
+            .build(NoopProvider::default());
+        assert_eq!(config.block_range.earliest, 900);
+
+        // the earliest block advances with the head
+        assert_eq!(earliest_block(&prune_modes, 2000), 1900);
+
+        // nothing is pruned before the head passed the distance
+        assert_eq!(earliest_block(&prune_modes, 50), 0);
+        assert_eq!(earliest_block(&PruneModes::none(), 2000), 0);
+    }
 }
@@ -8,11 +8,12 @@ use crate::{
 use enr::Enr;
 use futures::StreamExt;
 use reth_discv4::{DiscoveryUpdate, Discv4, Discv4Config, EnrForkIdEntry};
-use reth_discv5::{DiscoveredPeer, Discv5};
+use reth_discv5::{enr::EnrCombinedKeyWrapper, DiscoveredPeer, Discv5};
 use reth_dns_discovery::{
-    DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
+    BoxedResolver, DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService,
+    DnsNodeRecordUpdate, DnsResolver,
 };
-use reth_primitives::{ForkId, NodeRecord, PeerId};
+use reth_primitives::{bytes::Bytes, pk2id, ForkId, NodeRecord, PeerId};
 use secp256k1::SecretKey;
 use std::{
     collections::VecDeque,
@@ -40,6 +41,8 @@ pub struct Discovery {
     ///
     /// These nodes can be ephemeral and are updated via the discovery protocol.
     discovered_nodes: LruMap<PeerId, SocketAddr>,
+    /// Signed ENRs of discovered nodes, received via any discovery protocol.
+    enrs: LruMap<PeerId, Enr<SecretKey>>,
     /// Local ENR of the discovery v4 service (discv5 ENR has same [`PeerId`]).
     local_enr: NodeRecord,
     /// Handler to interact with the Discovery v4 service
@@ -75,6 +78,7 @@ impl Discovery {
         discv4_config: Option<Discv4Config>,
         discv5_config: Option<reth_discv5::Config>, // contains discv5 listen address
         dns_discovery_config: Option<DnsDiscoveryConfig>,
+        dns_resolver: Option<BoxedResolver>,
     ) -> Result<Self, NetworkError> {
         // setup discv4
         let local_enr = NodeRecord::from_secret_key(discovery_v4_addr, &sk);
@@ -105,10 +109,12 @@ impl Discovery {
         // setup DNS discovery
         let (_dns_discovery, dns_discovery_updates, _dns_disc_service) =
             if let Some(dns_config) = dns_discovery_config {
-                let (mut service, dns_disc) = DnsDiscoveryService::new_pair(
-                    Arc::new(DnsResolver::from_system_conf()?),
-                    dns_config,
-                );
+                let resolver = match dns_resolver {
+                    Some(resolver) => resolver,
+                    None => BoxedResolver::new(DnsResolver::from_system_conf()?),
+                };
+                let (mut service, dns_disc) =
+                    DnsDiscoveryService::new_pair(Arc::new(resolver), dns_config);
                 let dns_discovery_updates = service.node_record_stream();
                 let dns_disc_service = service.spawn();
                 (Some(dns_disc), Some(dns_discovery_updates), Some(dns_disc_service))
@@ -125,6 +131,7 @@ impl Discovery {
             discv5,
             discv5_updates,
             discovered_nodes: LruMap::new(DEFAULT_MAX_CAPACITY_DISCOVERED_PEERS_CACHE),
+            enrs: LruMap::new(DEFAULT_MAX_CAPACITY_DISCOVERED_PEERS_CACHE),
             queued_events: Default::default(),
             _dns_disc_service,
             _dns_discovery,
@@ -182,6 +189,42 @@ impl Discovery {
         self.local_enr.id // local discv4 and discv5 have same id, since signed with same secret key
     }
 
+    /// Returns the signed ENR of the node, if it was received via discovery.
+    pub(crate) fn enr(&self, peer_id: &PeerId) -> Option<Enr<SecretKey>> {
+        self.enrs.peek(peer_id).cloned()
+    }
+
+    /// Returns a signed ENR of the local node that announces the given RLPx address and
+    /// [`ForkId`].
+    ///
+    /// The IP address tracked by discv4 takes precedence over the address of the listener. Returns
+    /// `None` if neither is specified.
+    pub(crate) fn local_signed_enr(
+        &self,
+        sk: &SecretKey,
+        listener_addr: SocketAddr,
+        fork_id: ForkId,
+    ) -> Option<Enr<SecretKey>> {
+        let record =
+            self.discv4.as_ref().map(|discv4| discv4.node_record()).unwrap_or(self.local_enr);
+        let ip = if record.address.is_unspecified() { listener_addr.ip() } else { record.address };
+        if ip.is_unspecified() {
+            return None
+        }
+
+        let mut builder = Enr::builder();
+        builder.ip(ip);
+        if ip.is_ipv4() {
+            builder.udp4(record.udp_port);
+            builder.tcp4(listener_addr.port());
+        } else {
+            builder.udp6(record.udp_port);
+            builder.tcp6(listener_addr.port());
+        }
+        builder.add_value_rlp("eth", Bytes::from(alloy_rlp::encode(EnrForkIdEntry::from(fork_id))));
+        builder.build(sk).ok()
+    }
+
     /// Add a node to the discv4 table.
     pub(crate) fn add_discv4_node(&self, node: NodeRecord) {
         if let Some(discv4) = &self.discv4 {
@@ -220,6 +263,9 @@ impl Discovery {
             DiscoveryUpdate::EnrForkId(node, fork_id) => {
                 self.queued_events.push_back(DiscoveryEvent::EnrForkId(node.id, fork_id))
             }
+            DiscoveryUpdate::Enr(node, enr) => {
+                self.enrs.insert(node.id, enr);
+            }
             DiscoveryUpdate::Removed(node) => {
                 self.discovered_nodes.remove(&node);
             }
@@ -253,6 +299,10 @@ impl Discovery {
             while let Some(Poll::Ready(Some(update))) =
                 self.discv5_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
             {
+                if let discv5::Event::SessionEstablished(ref enr, _) = update {
+                    let enr: Enr<SecretKey> = EnrCombinedKeyWrapper(enr.clone()).into();
+                    self.enrs.insert(pk2id(&enr.public_key()), enr);
+                }
                 if let Some(discv5) = self.discv5.as_mut() {
                     if let Some(DiscoveredPeer { node_record, fork_id }) =
                         discv5.on_discv5_update(update)
@@ -267,6 +317,7 @@ impl Discovery {
                 self.dns_discovery_updates.as_mut().map(|updates| updates.poll_next_unpin(cx))
             {
                 self.add_discv4_node(update.node_record);
+                self.enrs.insert(update.node_record.id, update.enr.clone());
                 if let Err(err) = self.add_discv5_node(update.enr) {
                     trace!(target: "net::discovery",
                         %err,
@@ -302,6 +353,7 @@ impl Discovery {
 
         Self {
             discovered_nodes: LruMap::new(0),
+            enrs: LruMap::new(0),
             local_enr: NodeRecord {
                 address: IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED),
                 tcp_port: 0,
@@ -349,6 +401,7 @@ mod tests {
             Default::default(),
             None,
             Default::default(),
+            None,
         )
         .await
         .unwrap();
@@ -372,9 +425,16 @@ mod tests {
             .discv5_config(discv5::ConfigBuilder::new(discv5_listen_config).build())
             .build();
 
-        Discovery::new(discv4_addr, secret_key, Some(discv4_config), Some(discv5_config), None)
-            .await
-            .expect("should build discv5 with discv4 downgrade")
+        Discovery::new(
+            discv4_addr,
+            secret_key,
+            Some(discv4_config),
+            Some(discv5_config),
+            None,
+            None,
+        )
+        .await
+        .expect("should build discv5 with discv4 downgrade")
     }
 
     #[tokio::test(flavor = "multi_thread")]
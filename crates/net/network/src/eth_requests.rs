@@ -8,10 +8,10 @@ use alloy_rlp::Encodable;
 use futures::StreamExt;
 use reth_eth_wire::{
     BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders, GetNodeData, GetReceipts, NodeData,
-    Receipts,
+    Receipt69, Receipts, Receipts69,
 };
 use reth_interfaces::p2p::error::RequestResult;
-use reth_primitives::{BlockBody, BlockHashOrNumber, Header, HeadersDirection, PeerId};
+use reth_primitives::{BlockBody, BlockHashOrNumber, Header, HeadersDirection, PeerId, Receipt};
 use reth_provider::{BlockReader, HeaderProvider, ReceiptProvider};
 use std::{
     future::Future,
@@ -191,6 +191,28 @@ where
         request: GetReceipts,
         response: oneshot::Sender<RequestResult<Receipts>>,
     ) {
+        let receipts = self.get_receipts_response(request, |receipt| receipt.with_bloom());
+        let _ = response.send(Ok(Receipts(receipts)));
+    }
+
+    /// Handles a receipts request of an `eth/69` peer, which expects receipts without bloom
+    /// filters.
+    fn on_receipts69_request(
+        &mut self,
+        _peer_id: PeerId,
+        request: GetReceipts,
+        response: oneshot::Sender<RequestResult<Receipts69>>,
+    ) {
+        let receipts = self.get_receipts_response(request, Receipt69);
+        let _ = response.send(Ok(Receipts69(receipts)));
+    }
+
+    /// Returns the receipts of the requested blocks, encoded with the given function.
+    fn get_receipts_response<R, F>(&self, request: GetReceipts, encode: F) -> Vec<Vec<R>>
+    where
+        R: Encodable,
+        F: Fn(Receipt) -> R,
+    {
         let mut receipts = Vec::new();
 
         let mut total_bytes = 0;
@@ -199,10 +221,7 @@ where
             if let Some(receipts_by_block) =
                 self.client.receipts_by_block(BlockHashOrNumber::Hash(hash)).unwrap_or_default()
             {
-                let receipt = receipts_by_block
-                    .into_iter()
-                    .map(|receipt| receipt.with_bloom())
-                    .collect::<Vec<_>>();
+                let receipt = receipts_by_block.into_iter().map(&encode).collect::<Vec<_>>();
 
                 total_bytes += receipt.length();
                 receipts.push(receipt);
@@ -219,7 +238,7 @@ where
             }
         }
 
-        let _ = response.send(Ok(Receipts(receipts)));
+        receipts
     }
 }
 
@@ -252,6 +271,9 @@ where
                     IncomingEthRequest::GetReceipts { peer_id, request, response } => {
                         this.on_receipts_request(peer_id, request, response)
                     }
+                    IncomingEthRequest::GetReceipts69 { peer_id, request, response } => {
+                        this.on_receipts69_request(peer_id, request, response)
+                    }
                 }
             },
         );
@@ -314,4 +336,15 @@ pub enum IncomingEthRequest {
         /// The channel sender for the response containing receipts.
         response: oneshot::Sender<RequestResult<Receipts>>,
     },
+    /// Request Receipts without bloom filters from an `eth/69` peer.
+    ///
+    /// The response should be sent through the channel.
+    GetReceipts69 {
+        /// The ID of the peer to request receipts from.
+        peer_id: PeerId,
+        /// The specific receipts requested.
+        request: GetReceipts,
+        /// The channel sender for the response containing receipts.
+        response: oneshot::Sender<RequestResult<Receipts69>>,
+    },
 }
@@ -1,14 +1,16 @@
 //! Fetch data from the network.
 
-use crate::{message::BlockRequest, peers::PeersHandle};
+use crate::{message::BlockRequest, metrics::StateFetcherMetrics, peers::PeersHandle};
+use alloy_rlp::Encodable;
 use futures::StreamExt;
+use rand::Rng;
 use reth_eth_wire::{GetBlockBodies, GetBlockHeaders};
 use reth_interfaces::p2p::{
     error::{EthResponseValidator, PeerRequestResult, RequestError, RequestResult},
     headers::client::HeadersRequest,
     priority::Priority,
 };
-use reth_network_api::ReputationChangeKind;
+use reth_network_api::{PeerRequestStats, ReputationChangeKind};
 use reth_primitives::{BlockBody, Header, PeerId, B256};
 use std::{
     collections::{HashMap, VecDeque},
@@ -17,12 +19,16 @@ use std::{
         Arc,
     },
     task::{Context, Poll},
+    time::{Duration, Instant},
 };
 use tokio::sync::{mpsc, mpsc::UnboundedSender, oneshot};
 use tokio_stream::wrappers::UnboundedReceiverStream;
 
 mod client;
+mod stats;
+
 pub use client::FetchClient;
+use stats::PeerRequestTracker;
 
 /// Manages data fetching operations.
 ///
@@ -50,6 +56,8 @@ pub struct StateFetcher {
     download_requests_rx: UnboundedReceiverStream<DownloadRequest>,
     /// Sender for download requests, used to detach a [`FetchClient`]
     download_requests_tx: UnboundedSender<DownloadRequest>,
+    /// Metrics on served requests
+    metrics: StateFetcherMetrics,
 }
 
 // === impl StateSyncer ===
@@ -66,6 +74,7 @@ impl StateFetcher {
             queued_requests: Default::default(),
             download_requests_rx: UnboundedReceiverStream::new(download_requests_rx),
             download_requests_tx,
+            metrics: Default::default(),
         }
     }
 
@@ -77,8 +86,22 @@ impl StateFetcher {
         best_number: u64,
         timeout: Arc<AtomicU64>,
     ) {
-        self.peers
-            .insert(peer_id, Peer { state: PeerState::Idle, best_hash, best_number, timeout });
+        self.peers.insert(
+            peer_id,
+            Peer {
+                state: PeerState::Idle,
+                best_hash,
+                best_number,
+                timeout,
+                requests: Default::default(),
+            },
+        );
+    }
+
+    /// Returns the request statistics of the peer, if it's an active peer.
+    pub(crate) fn peer_stats(&self, peer_id: &PeerId) -> Option<PeerRequestStats> {
+        let best_latency = self.best_latency_ms();
+        self.peers.get(peer_id).map(|peer| peer.requests.stats(peer.prior_latency_ms(best_latency)))
     }
 
     /// Removes the peer from the peer list, after which it is no longer available for future
@@ -118,15 +141,42 @@ impl StateFetcher {
         }
     }
 
-    /// Returns the _next_ idle peer that's ready to accept a request,
-    /// prioritizing those with the lowest timeout/latency.
-    /// Once a peer has been yielded, it will be moved to the end of the map
+    /// Returns the lowest rolling latency of all peers in milliseconds, if any peer answered or
+    /// timed out yet.
+    fn best_latency_ms(&self) -> Option<f64> {
+        self.peers.values().filter_map(|peer| peer.requests.latency_ms()).min_by(f64::total_cmp)
+    }
+
+    /// Returns the _next_ idle peer that's ready to accept a request.
+    ///
+    /// The peer is picked at random, weighted by the inverse square of its score, so a peer that
+    /// is twice as fast gets four times as many requests, while slower peers keep getting the
+    /// occasional request to update their statistics. The score is the rolling latency of the
+    /// peer, weighted by its rate of timeouts and bad responses, see
+    /// [`PeerRequestTracker::score`]. Peers that haven't been measured yet are assumed to be as
+    /// fast as the fastest peer, so new peers are tried early.
     fn next_peer(&mut self) -> Option<PeerId> {
-        self.peers
+        let best_latency = self.best_latency_ms();
+        let candidates = self
+            .peers
             .iter()
             .filter(|(_, peer)| peer.state.is_idle())
-            .min_by_key(|(_, peer)| peer.timeout())
-            .map(|(id, _)| *id)
+            .map(|(id, peer)| (*id, 1.0 / peer.score(best_latency).max(1.0).powi(2)))
+            .collect::<Vec<_>>();
+        let total = candidates.iter().map(|(_, weight)| weight).sum::<f64>();
+        if candidates.is_empty() || !total.is_normal() {
+            return candidates.first().map(|(id, _)| *id)
+        }
+
+        let mut pick = rand::thread_rng().gen_range(0.0..total);
+        for (id, weight) in &candidates {
+            if pick < *weight {
+                return Some(*id)
+            }
+            pick -= weight;
+        }
+        // rounding errors
+        candidates.last().map(|(id, _)| *id)
     }
 
     /// Returns the next action to return
@@ -192,11 +242,14 @@ impl StateFetcher {
         // update the peer's state
         if let Some(peer) = self.peers.get_mut(&peer_id) {
             peer.state = req.peer_state();
+            peer.requests.on_request();
         }
+        self.metrics.block_requests.increment(1);
 
+        let started = Instant::now();
         match req {
             DownloadRequest::GetBlockHeaders { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight = Request { request: request.clone(), response, started };
                 self.inflight_headers_requests.insert(peer_id, inflight);
                 let HeadersRequest { start, limit, direction } = request;
                 BlockRequest::GetBlockHeaders(GetBlockHeaders {
@@ -207,7 +260,7 @@ impl StateFetcher {
                 })
             }
             DownloadRequest::GetBlockBodies { request, response, .. } => {
-                let inflight = Request { request: request.clone(), response };
+                let inflight = Request { request: request.clone(), response, started };
                 self.inflight_bodies_requests.insert(peer_id, inflight);
                 BlockRequest::GetBlockBodies(GetBlockBodies(request))
             }
@@ -243,6 +296,14 @@ impl StateFetcher {
             .map(|r| res.is_likely_bad_headers_response(&r.request))
             .unwrap_or_default();
 
+        if let Some(resp) = resp.as_ref() {
+            let bytes = res
+                .as_ref()
+                .map(|headers| headers.iter().map(|h| h.length() as u64).sum())
+                .unwrap_or_default();
+            self.on_response_served(&peer_id, resp.started, &res, is_likely_bad_response, bytes);
+        }
+
         if let Some(resp) = resp {
             // delegate the response
             let _ = resp.response.send(res.map(|h| (peer_id, h).into()));
@@ -269,6 +330,11 @@ impl StateFetcher {
         res: RequestResult<Vec<BlockBody>>,
     ) -> Option<BlockResponseOutcome> {
         if let Some(resp) = self.inflight_bodies_requests.remove(&peer_id) {
+            let bytes = res
+                .as_ref()
+                .map(|bodies| bodies.iter().map(|b| b.length() as u64).sum())
+                .unwrap_or_default();
+            self.on_response_served(&peer_id, resp.started, &res, false, bytes);
             let _ = resp.response.send(res.map(|b| (peer_id, b).into()));
         }
         if let Some(peer) = self.peers.get_mut(&peer_id) {
@@ -279,6 +345,47 @@ impl StateFetcher {
         None
     }
 
+    /// Records the outcome of a request in the peer's statistics and the metrics.
+    fn on_response_served<T>(
+        &mut self,
+        peer_id: &PeerId,
+        started: Instant,
+        res: &RequestResult<T>,
+        is_likely_bad_response: bool,
+        bytes: u64,
+    ) {
+        let peer = self.peers.get_mut(peer_id);
+        match res {
+            Err(RequestError::Timeout) => {
+                self.metrics.block_request_timeouts.increment(1);
+                if let Some(peer) = peer {
+                    let timeout = Duration::from_millis(peer.timeout());
+                    peer.requests.on_timeout(timeout);
+                }
+            }
+            Err(_) => {
+                self.metrics.bad_block_responses.increment(1);
+                if let Some(peer) = peer {
+                    peer.requests.on_bad_response();
+                }
+            }
+            Ok(_) if is_likely_bad_response => {
+                self.metrics.bad_block_responses.increment(1);
+                if let Some(peer) = peer {
+                    peer.requests.on_bad_response();
+                }
+            }
+            Ok(_) => {
+                let latency = started.elapsed();
+                self.metrics.block_request_latency.record(latency.as_secs_f64());
+                self.metrics.block_response_bytes.increment(bytes);
+                if let Some(peer) = peer {
+                    peer.requests.on_response(latency, bytes);
+                }
+            }
+        }
+    }
+
     /// Returns a new [`FetchClient`] that can send requests to this type.
     pub(crate) fn client(&self) -> FetchClient {
         FetchClient {
@@ -307,12 +414,26 @@ struct Peer {
     best_number: u64,
     /// Tracks the current timeout value we use for the peer.
     timeout: Arc<AtomicU64>,
+    /// Statistics on the requests the peer served.
+    requests: PeerRequestTracker,
 }
 
 impl Peer {
     fn timeout(&self) -> u64 {
         self.timeout.load(Ordering::Relaxed)
     }
+
+    /// Returns the latency assumed for the peer as long as it hasn't been measured: the given
+    /// lowest latency of all peers, but at most the session's request timeout.
+    fn prior_latency_ms(&self, best_latency_ms: Option<f64>) -> f64 {
+        let timeout = self.timeout() as f64;
+        best_latency_ms.map_or(timeout, |best| best.min(timeout))
+    }
+
+    /// The score the peer is ranked by, lower is better.
+    fn score(&self, best_latency_ms: Option<f64>) -> f64 {
+        self.requests.score(self.prior_latency_ms(best_latency_ms))
+    }
 }
 
 /// Tracks the state of an individual peer
@@ -359,6 +480,8 @@ struct Request<Req, Resp> {
     #[allow(dead_code)]
     request: Req,
     response: oneshot::Sender<Resp>,
+    /// When the request was dispatched to the peer.
+    started: Instant,
 }
 
 /// Requests that can be sent to the Syncer from a [`FetchClient`]
@@ -428,7 +551,7 @@ pub(crate) enum BlockResponseOutcome {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{peers::PeersManager, PeersConfig};
+    use crate::{peers::PeersManager, PeersConfig, SessionsConfig};
     use reth_primitives::{SealedHeader, B512};
     use std::future::poll_fn;
 
@@ -475,6 +598,15 @@ mod tests {
         assert_eq!(fetcher.next_peer(), None);
     }
 
+    /// Returns how often each peer is picked in the given number of draws.
+    fn pick_counts(fetcher: &mut StateFetcher, draws: usize) -> HashMap<PeerId, usize> {
+        let mut counts = HashMap::new();
+        for _ in 0..draws {
+            *counts.entry(fetcher.next_peer().unwrap()).or_default() += 1;
+        }
+        counts
+    }
+
     #[tokio::test]
     async fn test_peer_prioritization() {
         let manager = PeersManager::new(PeersConfig::default());
@@ -490,14 +622,101 @@ mod tests {
         fetcher.new_active_peer(peer2, B256::random(), 2, Arc::clone(&peer2_timeout));
         fetcher.new_active_peer(peer3, B256::random(), 3, Arc::new(AtomicU64::new(50)));
 
-        // Must always get peer1 (lowest timeout)
-        assert_eq!(fetcher.next_peer(), Some(peer1));
-        assert_eq!(fetcher.next_peer(), Some(peer1));
+        // without statistics, peers are weighted by their timeout, peer1 has the lowest
+        let counts = pick_counts(&mut fetcher, 1000);
+        assert!(counts[&peer1] > counts[&peer3]);
+        assert!(counts[&peer3] > counts.get(&peer2).copied().unwrap_or_default());
+
         // peer2's timeout changes below peer1's
         peer2_timeout.store(10, Ordering::Relaxed);
-        // Then we get peer 2 always (now lowest)
-        assert_eq!(fetcher.next_peer(), Some(peer2));
-        assert_eq!(fetcher.next_peer(), Some(peer2));
+        // then peer2 is picked most often
+        let counts = pick_counts(&mut fetcher, 1000);
+        assert!(counts[&peer2] > counts[&peer1]);
+        assert!(counts[&peer1] > counts.get(&peer3).copied().unwrap_or_default());
+    }
+
+    #[tokio::test]
+    async fn test_peer_scoring() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let slow = B512::random();
+        let fast = B512::random();
+        // the timeout every session starts with
+        let default_timeout = SessionsConfig::default().initial_internal_request_timeout;
+        let timeout = || Arc::new(AtomicU64::new(default_timeout.as_millis() as u64));
+
+        fetcher.new_active_peer(slow, B256::random(), 1, timeout());
+        fetcher.new_active_peer(fast, B256::random(), 1, timeout());
+
+        fetcher.peers.get_mut(&slow).unwrap().requests.on_response(Duration::from_millis(80), 1);
+        fetcher.peers.get_mut(&fast).unwrap().requests.on_response(Duration::from_millis(10), 1);
+
+        // the faster peer is preferred
+        let counts = pick_counts(&mut fetcher, 1000);
+        assert!(counts[&fast] > counts.get(&slow).copied().unwrap_or_default() * 10);
+
+        // timeouts count as latency samples and outweigh the latency advantage
+        fetcher.on_response_served::<()>(
+            &fast,
+            Instant::now(),
+            &Err(RequestError::Timeout),
+            false,
+            0,
+        );
+        let stats = fetcher.peer_stats(&fast).unwrap();
+        assert_eq!(stats.timeouts, 1);
+        assert!(stats.avg_latency.unwrap() > Duration::from_secs(1));
+        assert!(stats.score > fetcher.peer_stats(&slow).unwrap().score);
+        let counts = pick_counts(&mut fetcher, 1000);
+        assert!(counts[&slow] > counts.get(&fast).copied().unwrap_or_default() * 10);
+
+        // a new peer is assumed to be as fast as the fastest peer, instead of as slow as its
+        // timeout
+        let new = B512::random();
+        fetcher.new_active_peer(new, B256::random(), 1, timeout());
+        let new_stats = fetcher.peer_stats(&new).unwrap();
+        assert_eq!(new_stats.avg_latency, None);
+        assert_eq!(new_stats.score, fetcher.peer_stats(&slow).unwrap().score);
+        let counts = pick_counts(&mut fetcher, 1000);
+        assert!(counts[&new] > counts.get(&fast).copied().unwrap_or_default());
+    }
+
+    #[tokio::test]
+    async fn test_request_stats() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let peer_id = B512::random();
+        fetcher.new_active_peer(peer_id, B256::random(), 1, Arc::new(AtomicU64::new(100)));
+
+        let header_request = || {
+            let (tx, _rx) = oneshot::channel();
+            DownloadRequest::GetBlockHeaders {
+                request: HeadersRequest {
+                    start: 0u64.into(),
+                    limit: 1,
+                    direction: Default::default(),
+                },
+                response: tx,
+                priority: Priority::default(),
+            }
+        };
+
+        let header = Header::default();
+        fetcher.prepare_block_request(peer_id, header_request());
+        fetcher.on_block_headers_response(peer_id, Ok(vec![header.clone()]));
+
+        fetcher.prepare_block_request(peer_id, header_request());
+        fetcher.on_block_headers_response(peer_id, Err(RequestError::Timeout));
+
+        fetcher.prepare_block_request(peer_id, header_request());
+        fetcher.on_block_headers_response(peer_id, Err(RequestError::BadResponse));
+
+        let stats = fetcher.peer_stats(&peer_id).unwrap();
+        assert_eq!(stats.requests, 3);
+        assert_eq!(stats.timeouts, 1);
+        assert_eq!(stats.bad_responses, 1);
+        assert_eq!(stats.bytes_served, header.length() as u64);
+        assert!(stats.avg_latency.is_some());
     }
 
     #[tokio::test]
@@ -545,6 +764,7 @@ mod tests {
                     direction: Default::default(),
                 },
                 response: tx,
+                started: Instant::now(),
             };
             let mut header = SealedHeader::default().unseal();
             header.number = 0u64;
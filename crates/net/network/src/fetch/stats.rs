@@ -0,0 +1,105 @@
+//! Per-peer statistics on served header and body requests.
+
+use reth_network_api::PeerRequestStats;
+use std::time::Duration;
+
+/// Weight of a new sample in the rolling average latency.
+const LATENCY_SMOOTHING: f64 = 0.2;
+
+/// Weight of a new outcome in the rolling failure rate.
+const FAILURE_SMOOTHING: f64 = 0.1;
+
+/// Factor by which a failure rate of 100% inflates the expected latency of a peer.
+const FAILURE_PENALTY: f64 = 10.0;
+
+/// Tracks how fast and reliably a peer answers header and body requests.
+///
+/// Latency and failure rate are exponentially weighted, so the score follows the recent
+/// performance of the peer.
+#[derive(Debug, Default)]
+pub(crate) struct PeerRequestTracker {
+    /// Number of requests sent to the peer.
+    requests: u64,
+    /// Number of requests that timed out.
+    timeouts: u64,
+    /// Number of requests answered with an error or an unusable response.
+    bad_responses: u64,
+    /// Total encoded size of successful responses.
+    bytes_served: u64,
+    /// Rolling average latency of successful responses and timed out requests in milliseconds.
+    latency_ms: Option<f64>,
+    /// Rolling rate of failed requests, in `[0, 1]`.
+    failure_rate: f64,
+}
+
+impl PeerRequestTracker {
+    /// Records a request sent to the peer.
+    pub(crate) fn on_request(&mut self) {
+        self.requests += 1;
+    }
+
+    /// Records a successful response.
+    pub(crate) fn on_response(&mut self, latency: Duration, bytes: u64) {
+        self.record_latency(latency);
+        self.bytes_served += bytes;
+        self.record_outcome(false);
+    }
+
+    /// Records a request that timed out after the given timeout.
+    ///
+    /// The timeout is recorded as latency sample, since the peer took at least that long to
+    /// answer.
+    pub(crate) fn on_timeout(&mut self, timeout: Duration) {
+        self.timeouts += 1;
+        self.record_latency(timeout);
+        self.record_outcome(true);
+    }
+
+    /// Records a request that was answered with an error or an unusable response.
+    pub(crate) fn on_bad_response(&mut self) {
+        self.bad_responses += 1;
+        self.record_outcome(true);
+    }
+
+    fn record_latency(&mut self, latency: Duration) {
+        let sample = latency.as_secs_f64() * 1000.0;
+        self.latency_ms = Some(match self.latency_ms {
+            Some(avg) => avg + LATENCY_SMOOTHING * (sample - avg),
+            None => sample,
+        });
+    }
+
+    /// Returns the rolling average latency in milliseconds, if the peer answered or timed out at
+    /// least once.
+    pub(crate) const fn latency_ms(&self) -> Option<f64> {
+        self.latency_ms
+    }
+
+    fn record_outcome(&mut self, failed: bool) {
+        let sample = if failed { 1.0 } else { 0.0 };
+        self.failure_rate += FAILURE_SMOOTHING * (sample - self.failure_rate);
+    }
+
+    /// Returns the score of the peer, lower is better.
+    ///
+    /// This is the expected latency of the peer in milliseconds, inflated by its failure rate. If
+    /// the peer didn't answer any request yet, the given prior is used as its expected latency.
+    pub(crate) fn score(&self, prior_latency_ms: f64) -> f64 {
+        let latency = self.latency_ms.unwrap_or(prior_latency_ms);
+        latency * (1.0 + FAILURE_PENALTY * self.failure_rate)
+    }
+
+    /// Returns the statistics of the peer.
+    pub(crate) fn stats(&self, prior_latency_ms: f64) -> PeerRequestStats {
+        PeerRequestStats {
+            requests: self.requests,
+            timeouts: self.timeouts,
+            bad_responses: self.bad_responses,
+            bytes_served: self.bytes_served,
+            avg_latency: self
+                .latency_ms
+                .map(|ms| Duration::from_micros((ms * 1000.0).round() as u64)),
+            score: self.score(prior_latency_ms),
+        }
+    }
+}
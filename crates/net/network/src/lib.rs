@@ -135,6 +135,7 @@ mod session;
 mod state;
 mod swarm;
 pub mod transactions;
+pub mod transport;
 
 pub use builder::NetworkBuilder;
 pub use config::{NetworkConfig, NetworkConfigBuilder};
@@ -150,5 +151,9 @@ pub use session::{
     SessionLimits, SessionManager, SessionsConfig,
 };
 pub use transactions::{FilterAnnouncement, MessageFilter, ValidateTx68};
+pub use transport::{
+    BoxedTransportStream, MemoryTransport, TcpTransport, Transport, TransportListener,
+    TransportStream,
+};
 
-pub use reth_eth_wire::{DisconnectReason, HelloMessageWithProtocols};
+pub use reth_eth_wire::{BandwidthLimits, DisconnectReason, HelloMessageWithProtocols};
@@ -1,5 +1,6 @@
 //! Contains connection-oriented interfaces.
 
+use crate::transport::{BoxedTransportStream, TcpTransport, Transport, TransportListener};
 use futures::{ready, Stream};
 
 use std::{
@@ -8,33 +9,40 @@ use std::{
     pin::Pin,
     task::{Context, Poll},
 };
-use tokio::net::{TcpListener, TcpStream};
 
-/// A tcp connection listener.
+/// A connection listener.
 ///
-/// Listens for incoming connections.
+/// Listens for incoming connections of a [`Transport`].
 #[must_use = "Transport does nothing unless polled."]
 #[pin_project::pin_project]
 #[derive(Debug)]
 pub struct ConnectionListener {
     /// Local address of the listener stream.
     local_address: SocketAddr,
-    /// The active tcp listener for incoming connections.
+    /// The active listener for incoming connections.
     #[pin]
-    incoming: TcpListenerStream,
+    incoming: TransportListenerStream,
 }
 
 impl ConnectionListener {
-    /// Creates a new [`TcpListener`] that listens for incoming connections.
+    /// Creates a new TCP listener that listens for incoming connections.
     pub async fn bind(addr: SocketAddr) -> io::Result<Self> {
-        let listener = TcpListener::bind(addr).await?;
+        Self::bind_with(&TcpTransport, addr).await
+    }
+
+    /// Creates a new listener of the given [`Transport`] that listens for incoming connections.
+    pub async fn bind_with<T: Transport + ?Sized>(
+        transport: &T,
+        addr: SocketAddr,
+    ) -> io::Result<Self> {
+        let listener = transport.listen(addr).await?;
         let local_addr = listener.local_addr()?;
         Ok(Self::new(listener, local_addr))
     }
 
     /// Creates a new connection listener stream.
-    pub(crate) fn new(listener: TcpListener, local_address: SocketAddr) -> Self {
-        Self { local_address, incoming: TcpListenerStream { inner: listener } }
+    pub(crate) fn new(listener: Box<dyn TransportListener>, local_address: SocketAddr) -> Self {
+        Self { local_address, incoming: TransportListenerStream { inner: listener } }
     }
 
     /// Polls the type to make progress.
@@ -42,9 +50,6 @@ impl ConnectionListener {
         let this = self.project();
         match ready!(this.incoming.poll_next(cx)) {
             Some(Ok((stream, remote_addr))) => {
-                if let Err(err) = stream.set_nodelay(true) {
-                    tracing::warn!(target: "net", "set nodelay failed: {:?}", err);
-                }
                 Poll::Ready(ListenerEvent::Incoming { stream, remote_addr })
             }
             Some(Err(err)) => Poll::Ready(ListenerEvent::Error(err)),
@@ -60,18 +65,18 @@ impl ConnectionListener {
     }
 }
 
-/// Event type produced by the [`TcpListenerStream`].
+/// Event type produced by the [`TransportListenerStream`].
 pub enum ListenerEvent {
     /// Received a new incoming.
     Incoming {
         /// Accepted connection
-        stream: TcpStream,
+        stream: BoxedTransportStream,
         /// Address of the remote peer.
         remote_addr: SocketAddr,
     },
     /// Returned when the underlying connection listener has been closed.
     ///
-    /// This is the case if the [`TcpListenerStream`] should ever return `None`
+    /// This is the case if the [`TransportListenerStream`] should ever return `None`
     ListenerClosed {
         /// Address of the closed listener.
         local_address: SocketAddr,
@@ -82,17 +87,17 @@ pub enum ListenerEvent {
     Error(io::Error),
 }
 
-/// A stream of incoming [`TcpStream`]s.
+/// A stream of incoming [`BoxedTransportStream`]s.
 #[derive(Debug)]
-struct TcpListenerStream {
+struct TransportListenerStream {
     /// listener for incoming connections.
-    inner: TcpListener,
+    inner: Box<dyn TransportListener>,
 }
 
-impl Stream for TcpListenerStream {
-    type Item = io::Result<(TcpStream, SocketAddr)>;
+impl Stream for TransportListenerStream {
+    type Item = io::Result<(BoxedTransportStream, SocketAddr)>;
 
-    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         match self.inner.poll_accept(cx) {
             Poll::Ready(Ok(conn)) => Poll::Ready(Some(Ok(conn))),
             Poll::Ready(Err(err)) => Poll::Ready(Some(Err(err))),
@@ -104,9 +109,11 @@ impl Stream for TcpListenerStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transport::MemoryTransport;
     use futures::pin_mut;
+    use reth_net_common::stream::HasRemoteAddr;
     use std::net::{Ipv4Addr, SocketAddrV4};
-    use tokio::macros::support::poll_fn;
+    use tokio::{macros::support::poll_fn, net::TcpStream};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_incoming_listener() {
@@ -128,4 +135,29 @@ mod tests {
 
         let _ = TcpStream::connect(local_addr).await.unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_incoming_memory_listener() {
+        let transport = MemoryTransport::new();
+        let listener = ConnectionListener::bind_with(
+            &transport,
+            SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)),
+        )
+        .await
+        .unwrap();
+        let local_addr = listener.local_address();
+
+        let _stream = transport.dial(local_addr).await.unwrap();
+
+        pin_mut!(listener);
+        match poll_fn(|cx| listener.as_mut().poll(cx)).await {
+            ListenerEvent::Incoming { stream, remote_addr } => {
+                assert_eq!(stream.remote_addr(), Some(remote_addr));
+                assert_eq!(stream.local_addr(), Some(local_addr));
+            }
+            _ => {
+                panic!("unexpected event")
+            }
+        }
+    }
 }
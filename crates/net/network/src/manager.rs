@@ -26,7 +26,7 @@ use crate::{
     message::{NewBlockMessage, PeerMessage, PeerRequest, PeerRequestSender},
     metrics::{DisconnectMetrics, NetworkMetrics, NETWORK_POOL_TRANSACTIONS_SCOPE},
     network::{NetworkHandle, NetworkHandleMessage},
-    peers::{PeersHandle, PeersManager},
+    peers::{PeerStore, PeersHandle, PeersManager},
     poll_nested_stream_with_budget,
     protocol::IntoRlpxSubProtocol,
     session::SessionManager,
@@ -37,13 +37,14 @@ use crate::{
 };
 use futures::{pin_mut, Future, StreamExt};
 use parking_lot::Mutex;
+use reth_dns_discovery::{tree::DnsTree, NodeSetPublisher};
 use reth_eth_wire::{
     capability::{Capabilities, CapabilityMessage},
     DisconnectReason, EthVersion, Status,
 };
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
 use reth_net_common::bandwidth_meter::BandwidthMeter;
-use reth_network_api::ReputationChangeKind;
+use reth_network_api::{PeerEvent, PeerInfo, PeerKind, ReputationChangeKind};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use reth_provider::{BlockNumReader, BlockReader};
 use reth_rpc_types::{admin::EthProtocolInfo, NetworkStatus};
@@ -85,6 +86,8 @@ pub struct NetworkManager<C> {
     block_import: Box<dyn BlockImport>,
     /// All listeners for high level network events.
     event_listeners: EventListeners<NetworkEvent>,
+    /// All listeners for peer events, see [`Peers::peer_events`](reth_network_api::Peers).
+    peer_event_listeners: EventListeners<PeerEvent>,
     /// Sender half to send events to the
     /// [`TransactionsManager`](crate::transactions::TransactionsManager) task, if configured.
     to_transactions_manager: Option<UnboundedMeteredSender<NetworkTransactionEvent>>,
@@ -111,6 +114,8 @@ pub struct NetworkManager<C> {
     metrics: NetworkMetrics,
     /// Disconnect metrics for the Network
     disconnect_metrics: DisconnectMetrics,
+    /// Publishes the local node and the active peers as DNS discovery tree, if configured.
+    dns_tree_publisher: Option<NodeSetPublisher>,
 }
 
 // === impl NetworkManager ===
@@ -190,19 +195,25 @@ where
             executor,
             hello_message,
             status,
+            block_range,
+            prune_modes,
             fork_filter,
             dns_discovery_config,
+            dns_resolver,
+            dns_tree_publisher_config,
             extra_protocols,
             tx_gossip_disabled,
             transactions_manager_config: _,
+            transport,
         } = config;
 
         let peers_manager = PeersManager::new(peers_config);
         let peers_handle = peers_manager.handle();
 
-        let incoming = ConnectionListener::bind(listener_addr).await.map_err(|err| {
-            NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr))
-        })?;
+        let incoming =
+            ConnectionListener::bind_with(&*transport, listener_addr).await.map_err(|err| {
+                NetworkError::from_io_error(err, ServiceKind::Listener(listener_addr))
+            })?;
         let listener_address = Arc::new(Mutex::new(incoming.local_address()));
 
         discovery_v4_config = discovery_v4_config.map(|mut disc_config| {
@@ -218,6 +229,7 @@ where
             discovery_v4_config,
             discovery_v5_config,
             dns_discovery_config,
+            dns_resolver,
         )
         .await?;
         // need to retrieve the addr here since provided port could be `0`
@@ -232,10 +244,13 @@ where
             sessions_config,
             executor,
             status,
+            block_range,
+            prune_modes,
             hello_message,
             fork_filter,
             extra_protocols,
             bandwidth_meter.clone(),
+            transport,
         );
 
         let state =
@@ -259,18 +274,23 @@ where
             discv4,
         );
 
-        Ok(Self {
+        let mut manager = Self {
             swarm,
             handle,
             from_handle_rx: UnboundedReceiverStream::new(from_handle_rx),
             block_import,
             event_listeners: Default::default(),
+            peer_event_listeners: Default::default(),
             to_transactions_manager: None,
             to_eth_request_handler: None,
             num_active_peers,
             metrics: Default::default(),
             disconnect_metrics: Default::default(),
-        })
+            dns_tree_publisher: dns_tree_publisher_config.map(NodeSetPublisher::new),
+        };
+        manager.update_local_dns_tree_record(status.forkid);
+
+        Ok(manager)
     }
 
     /// Create a new [`NetworkManager`] instance and start a [`NetworkBuilder`] to configure all
@@ -333,6 +353,17 @@ where
         self.swarm.state().peers().iter_peers()
     }
 
+    /// Returns a snapshot of the peer set that can be persisted and restored on the next launch,
+    /// see [`PeersConfig::with_basic_nodes_from_file`](crate::PeersConfig).
+    pub fn peer_store(&self) -> PeerStore {
+        self.swarm.state().peers().peer_store()
+    }
+
+    /// Returns the most recently published DNS discovery tree, if publishing is configured.
+    pub fn dns_tree(&self) -> Option<&DnsTree> {
+        self.dns_tree_publisher.as_ref().and_then(|publisher| publisher.tree())
+    }
+
     /// Returns a new [`PeersHandle`] that can be cloned and shared.
     ///
     /// The [`PeersHandle`] can be used to interact with the network's peer set.
@@ -366,6 +397,19 @@ where
         }
     }
 
+    /// Updates the record of the local node in the published DNS tree, if configured.
+    fn update_local_dns_tree_record(&mut self, fork_id: ForkId) {
+        let Some(publisher) = self.dns_tree_publisher.as_mut() else { return };
+        let secret_key = self.swarm.sessions().secret_key();
+        let listener_addr = self.swarm.listener().local_address();
+        match self.swarm.state().discovery().local_signed_enr(&secret_key, listener_addr, fork_id) {
+            Some(enr) => publisher.insert(enr),
+            None => {
+                debug!(target: "net", ?listener_addr, "No address of the local node to publish")
+            }
+        }
+    }
+
     /// Event hook for an unexpected message from the peer.
     fn on_invalid_message(
         &mut self,
@@ -432,6 +476,13 @@ where
                     response,
                 })
             }
+            PeerRequest::GetReceipts69 { request, response } => {
+                self.delegate_eth_request(IncomingEthRequest::GetReceipts69 {
+                    peer_id,
+                    request,
+                    response,
+                })
+            }
             PeerRequest::GetPooledTransactions { request, response } => {
                 self.notify_tx_manager(NetworkTransactionEvent::GetPooledTransactions {
                     peer_id,
@@ -509,6 +560,13 @@ where
             PeerMessage::EthRequest(req) => {
                 self.on_eth_request(peer_id, req);
             }
+            PeerMessage::BlockRangeUpdated(update) => {
+                self.swarm.state_mut().update_peer_block(
+                    &peer_id,
+                    update.latest_hash,
+                    update.latest,
+                );
+            }
             PeerMessage::ReceivedTransaction(msg) => {
                 self.notify_tx_manager(NetworkTransactionEvent::IncomingTransactions {
                     peer_id,
@@ -524,12 +582,25 @@ where
         }
     }
 
+    /// Sets the reputation and kind the [`PeersManager`](crate::peers::PeersManager) tracks for
+    /// the peer of the session's [`PeerInfo`], and the statistics on the requests it served.
+    fn set_peer_state(&self, info: &mut PeerInfo) {
+        let peers = self.swarm.state().peers();
+        info.reputation = peers.get_reputation(&info.remote_id).unwrap_or_default();
+        info.kind = peers.get_kind(&info.remote_id).unwrap_or_default();
+        info.request_stats =
+            self.swarm.state().peer_request_stats(&info.remote_id).unwrap_or_default();
+    }
+
     /// Handler for received messages from a handle
     fn on_handle_message(&mut self, msg: NetworkHandleMessage) {
         match msg {
             NetworkHandleMessage::EventListener(tx) => {
                 self.event_listeners.push_listener(tx);
             }
+            NetworkHandleMessage::PeerEventListener(tx) => {
+                self.peer_event_listeners.push_listener(tx);
+            }
             NetworkHandleMessage::DiscoveryListener(tx) => {
                 self.swarm.state_mut().discovery_mut().add_listener(tx);
             }
@@ -601,20 +672,29 @@ where
             NetworkHandleMessage::StatusUpdate { head } => {
                 if let Some(transition) = self.swarm.sessions_mut().on_status_update(head) {
                     self.swarm.state_mut().update_fork_id(transition.current);
+                    self.update_local_dns_tree_record(transition.current);
                 }
             }
             NetworkHandleMessage::GetPeerInfos(tx) => {
-                let _ = tx.send(self.swarm.sessions_mut().get_peer_info());
+                let mut infos = self.swarm.sessions_mut().get_peer_info();
+                infos.iter_mut().for_each(|info| self.set_peer_state(info));
+                let _ = tx.send(infos);
             }
             NetworkHandleMessage::GetPeerInfoById(peer_id, tx) => {
-                let _ = tx.send(self.swarm.sessions_mut().get_peer_info_by_id(peer_id));
+                let mut info = self.swarm.sessions_mut().get_peer_info_by_id(peer_id);
+                info.iter_mut().for_each(|info| self.set_peer_state(info));
+                let _ = tx.send(info);
             }
             NetworkHandleMessage::GetPeerInfosByIds(peer_ids, tx) => {
-                let _ = tx.send(self.swarm.sessions().get_peer_infos_by_ids(peer_ids));
+                let mut infos = self.swarm.sessions().get_peer_infos_by_ids(peer_ids);
+                infos.iter_mut().for_each(|info| self.set_peer_state(info));
+                let _ = tx.send(infos);
             }
             NetworkHandleMessage::GetPeerInfosByPeerKind(kind, tx) => {
                 let peers = self.swarm.state().peers().peers_by_kind(kind);
-                let _ = tx.send(self.swarm.sessions().get_peer_infos_by_ids(peers));
+                let mut infos = self.swarm.sessions().get_peer_infos_by_ids(peers);
+                infos.iter_mut().for_each(|info| self.set_peer_state(info));
+                let _ = tx.send(infos);
             }
             NetworkHandleMessage::AddRlpxSubProtocol(proto) => self.add_rlpx_sub_protocol(proto),
             NetworkHandleMessage::GetTransactionsHandle(tx) => {
@@ -689,6 +769,19 @@ where
 
                 self.update_active_connection_metrics();
 
+                let peer_kind = self.swarm.state().peers().session_peer_kind(&peer_id);
+
+                if let Some(publisher) = self.dns_tree_publisher.as_mut() {
+                    if let Some(enr) = self.swarm.state().discovery().enr(&peer_id) {
+                        publisher.insert(enr);
+                    }
+                }
+
+                self.peer_event_listeners.notify(PeerEvent::SessionEstablished {
+                    peer_id,
+                    remote_addr,
+                    direction,
+                });
                 self.event_listeners.notify(NetworkEvent::SessionEstablished {
                     peer_id,
                     remote_addr,
@@ -697,18 +790,29 @@ where
                     version,
                     status,
                     messages,
+                    peer_kind,
                 });
             }
             SwarmEvent::PeerAdded(peer_id) => {
                 trace!(target: "net", ?peer_id, "Peer added");
                 self.event_listeners.notify(NetworkEvent::PeerAdded(peer_id));
+                self.peer_event_listeners.notify(PeerEvent::PeerAdded(peer_id));
                 self.metrics.tracked_peers.set(self.swarm.state().peers().num_known_peers() as f64);
             }
             SwarmEvent::PeerRemoved(peer_id) => {
                 trace!(target: "net", ?peer_id, "Peer dropped");
                 self.event_listeners.notify(NetworkEvent::PeerRemoved(peer_id));
+                self.peer_event_listeners.notify(PeerEvent::PeerRemoved(peer_id));
                 self.metrics.tracked_peers.set(self.swarm.state().peers().num_known_peers() as f64);
             }
+            SwarmEvent::PeerBanned(peer_id) => {
+                trace!(target: "net", ?peer_id, "Peer banned");
+                self.peer_event_listeners.notify(PeerEvent::PeerBanned(peer_id));
+            }
+            SwarmEvent::PeerUnbanned(peer_id) => {
+                trace!(target: "net", ?peer_id, "Peer unbanned");
+                self.peer_event_listeners.notify(PeerEvent::PeerUnbanned(peer_id));
+            }
             SwarmEvent::SessionClosed { peer_id, remote_addr, error } => {
                 let total_active = self.num_active_peers.fetch_sub(1, Ordering::Relaxed) - 1;
                 self.metrics.connected_peers.set(total_active as f64);
@@ -741,6 +845,9 @@ where
                 if let Some(reason) = reason {
                     self.disconnect_metrics.increment(reason);
                 }
+                if let Some(publisher) = self.dns_tree_publisher.as_mut() {
+                    publisher.remove(&peer_id);
+                }
                 self.metrics.backed_off_peers.set(
                         self.swarm
                             .state()
@@ -749,6 +856,7 @@ where
                             .saturating_sub(1)
                             as f64,
                     );
+                self.peer_event_listeners.notify(PeerEvent::SessionClosed { peer_id, reason });
                 self.event_listeners.notify(NetworkEvent::SessionClosed { peer_id, reason });
             }
             SwarmEvent::IncomingPendingSessionClosed { remote_addr, error } => {
@@ -978,6 +1086,18 @@ where
         poll_durations.acc_swarm =
             start_network_handle.elapsed() - poll_durations.acc_network_handle;
 
+        // publish changes of the active peers
+        if let Some(publisher) = this.dns_tree_publisher.as_mut() {
+            while let Poll::Ready(outcome) = publisher.poll(cx) {
+                match outcome {
+                    Ok(seq) => {
+                        debug!(target: "net", seq, nodes=publisher.len(), "Published DNS tree")
+                    }
+                    Err(err) => warn!(target: "net", %err, "Failed to publish DNS tree"),
+                }
+            }
+        }
+
         // all streams are fully drained and import futures pending
         if maybe_more_handle_messages || maybe_more_swarm_events {
             // make sure we're woken up again
@@ -1020,6 +1140,12 @@ pub enum NetworkEvent {
         status: Arc<Status>,
         /// negotiated eth version of the session
         version: EthVersion,
+        /// The kind of the peer for this session, [`PeerKind::Trusted`] if the peer is in the
+        /// trusted set when the session is established.
+        ///
+        /// NOTE: This field was added to the variant, so exhaustive patterns and struct
+        /// expressions of `SessionEstablished` in downstream code must include it.
+        peer_kind: PeerKind,
     },
     /// Event emitted when a new peer is added
     PeerAdded(PeerId),
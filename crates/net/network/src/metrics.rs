@@ -306,6 +306,22 @@ impl DisconnectMetrics {
     }
 }
 
+/// Metrics for the [`StateFetcher`](crate::fetch::StateFetcher)
+#[derive(Metrics)]
+#[metrics(scope = "network")]
+pub struct StateFetcherMetrics {
+    /// Total number of header and body requests sent to peers
+    pub(crate) block_requests: Counter,
+    /// Total number of header and body requests that timed out
+    pub(crate) block_request_timeouts: Counter,
+    /// Total number of header and body requests answered with an error or an unusable response
+    pub(crate) bad_block_responses: Counter,
+    /// Total size of all successful header and body responses, in bytes
+    pub(crate) block_response_bytes: Counter,
+    /// Latency of successful header and body responses, in seconds
+    pub(crate) block_request_latency: Histogram,
+}
+
 /// Metrics for the EthRequestHandler
 #[derive(Metrics)]
 #[metrics(scope = "network")]
@@ -10,7 +10,7 @@ use reth_eth_wire::{DisconnectReason, NewBlock, NewPooledTransactionHashes, Shar
 use reth_interfaces::sync::{NetworkSyncUpdater, SyncState, SyncStateProvider};
 use reth_net_common::bandwidth_meter::BandwidthMeter;
 use reth_network_api::{
-    NetworkError, NetworkInfo, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
+    NetworkError, NetworkInfo, PeerEvent, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
     ReputationChangeKind,
 };
 use reth_primitives::{Head, NodeRecord, PeerId, TransactionSigned, B256};
@@ -317,6 +317,12 @@ impl Peers for NetworkHandle {
         let _ = self.manager().send(NetworkHandleMessage::GetReputationById(peer_id, tx));
         Ok(rx.await?)
     }
+
+    fn peer_events(&self) -> mpsc::UnboundedReceiver<PeerEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let _ = self.manager().send(NetworkHandleMessage::PeerEventListener(tx));
+        rx
+    }
 }
 
 impl NetworkInfo for NetworkHandle {
@@ -431,6 +437,8 @@ pub(crate) enum NetworkHandleMessage {
     DisconnectPeer(PeerId, Option<DisconnectReason>),
     /// Adds a new listener for `NetworkEvent`.
     EventListener(UnboundedSender<NetworkEvent>),
+    /// Adds a new listener for `PeerEvent`.
+    PeerEventListener(UnboundedSender<PeerEvent>),
     /// Broadcasts an event to announce a new block to all nodes.
     AnnounceBlock(NewBlock, B256),
     /// Sends a list of transactions to the given peer.
@@ -4,6 +4,7 @@ use crate::{
         reputation::{
             is_banned_reputation, DEFAULT_REPUTATION, MAX_TRUSTED_PEER_REPUTATION_CHANGE,
         },
+        store::{unix_secs, PeerStore, PeerStoreConfig, PersistedBan, PersistedPeer},
         ReputationChangeWeights, DEFAULT_MAX_COUNT_CONCURRENT_OUTBOUND_DIALS,
         DEFAULT_MAX_COUNT_PEERS_INBOUND, DEFAULT_MAX_COUNT_PEERS_OUTBOUND,
     },
@@ -16,13 +17,14 @@ use reth_net_common::ban_list::BanList;
 use reth_network_api::{PeerKind, ReputationChangeKind};
 use reth_primitives::{ForkId, NodeRecord, PeerId};
 use std::{
+    cmp::Reverse,
     collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
     fmt::Display,
     io::{self, ErrorKind},
     net::{IpAddr, SocketAddr},
     path::Path,
     task::{Context, Poll},
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
 use tokio::{
@@ -76,6 +78,16 @@ impl PeersHandle {
 
         rx.await.unwrap_or_default()
     }
+
+    /// Returns a snapshot of the peerset that can be persisted, see [`PeerStore`].
+    ///
+    /// Returns `None` if the [`PeersManager`] is no longer running.
+    pub async fn peer_store(&self) -> Option<PeerStore> {
+        let (tx, rx) = oneshot::channel();
+        self.send(PeerCommand::GetPeerStore(tx));
+
+        rx.await.ok()
+    }
 }
 
 /// Maintains the state of _all_ the peers known to the network.
@@ -125,6 +137,8 @@ pub struct PeersManager {
     max_backoff_count: u8,
     /// Tracks the connection state of the node
     net_connection_state: NetworkConnectionState,
+    /// Which entries to keep when creating a [`PeerStore`].
+    store_config: PeerStoreConfig,
 }
 
 impl PeersManager {
@@ -134,13 +148,15 @@ impl PeersManager {
             refill_slots_interval,
             connection_info,
             reputation_weights,
-            ban_list,
+            mut ban_list,
             ban_duration,
             backoff_durations,
             trusted_nodes,
             trusted_nodes_only,
             basic_nodes,
             max_backoff_count,
+            mut persisted_peers,
+            store: store_config,
         } = config;
         let (manager_tx, handle_rx) = mpsc::unbounded_channel();
         let now = Instant::now();
@@ -160,6 +176,39 @@ impl PeersManager {
             peers.entry(id).or_insert_with(|| Peer::new(SocketAddr::from((address, tcp_port))));
         }
 
+        // restore the state of the previous run
+        let unix_now = unix_secs(SystemTime::now());
+        let instant_now = std::time::Instant::now();
+        let mut backed_off_peers = HashMap::new();
+        persisted_peers.compact(unix_now, &store_config);
+        for PersistedBan { target, expires_at } in persisted_peers.banned_peers {
+            ban_list
+                .ban_peer_until(target, instant_now + Duration::from_secs(expires_at - unix_now));
+        }
+        for PersistedBan { target, expires_at } in persisted_peers.banned_ips {
+            ban_list.ban_ip_until(target, instant_now + Duration::from_secs(expires_at - unix_now));
+        }
+        for PersistedPeer { record, reputation, last_seen, failures, backoff_until, fork_id } in
+            persisted_peers.peers
+        {
+            let peer = peers.entry(record.id).or_insert_with(|| Peer::new(record.tcp_addr()));
+            // the ban may have expired while the node was offline
+            peer.reputation =
+                if is_banned_reputation(reputation) && !ban_list.is_banned_peer(&record.id) {
+                    DEFAULT_REPUTATION
+                } else {
+                    reputation
+                };
+            peer.severe_backoff_counter = failures;
+            if let Some(until) = backoff_until.filter(|until| *until > unix_now) {
+                peer.backed_off = true;
+                backed_off_peers
+                    .insert(record.id, instant_now + Duration::from_secs(until - unix_now));
+            }
+            peer.fork_id = fork_id;
+            peer.last_seen = last_seen.map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        }
+
         Self {
             peers,
             trusted_peer_ids,
@@ -171,13 +220,14 @@ impl PeersManager {
             release_interval: tokio::time::interval_at(now + unban_interval, unban_interval),
             connection_info,
             ban_list,
-            backed_off_peers: Default::default(),
+            backed_off_peers,
             ban_duration,
             backoff_durations,
             trusted_nodes_only,
             last_tick: Instant::now(),
             max_backoff_count,
             net_connection_state: NetworkConnectionState::default(),
+            store_config,
         }
     }
 
@@ -197,6 +247,53 @@ impl PeersManager {
         self.peers.iter().map(|(peer_id, v)| NodeRecord::new(v.addr, *peer_id))
     }
 
+    /// Returns a snapshot of the peer set that can be persisted and restored on the next launch.
+    ///
+    /// This includes the reputation, backoff and fork id of all peers with a known address and all
+    /// temporary bans. The snapshot is compacted according to the [`PeerStoreConfig`].
+    pub(crate) fn peer_store(&self) -> PeerStore {
+        let unix_now = unix_secs(SystemTime::now());
+        let instant_now = std::time::Instant::now();
+        let to_unix = |until: std::time::Instant| {
+            unix_now + until.saturating_duration_since(instant_now).as_secs()
+        };
+        // indefinite bans are configured and not persisted
+        let expires_at = |until: Option<std::time::Instant>| until.map(to_unix);
+
+        let mut store = PeerStore {
+            peers: self
+                .peers
+                .iter()
+                // we only know the outgoing port of these peers
+                .filter(|(_, peer)| !peer.remove_after_disconnect)
+                .map(|(peer_id, peer)| PersistedPeer {
+                    record: NodeRecord::new(peer.addr, *peer_id),
+                    reputation: peer.reputation,
+                    last_seen: peer.last_seen.map(unix_secs),
+                    failures: peer.severe_backoff_counter,
+                    backoff_until: self.backed_off_peers.get(peer_id).copied().map(to_unix),
+                    fork_id: peer.fork_id,
+                })
+                .collect(),
+            banned_peers: self
+                .ban_list
+                .banned_peers()
+                .filter_map(|(target, until)| {
+                    Some(PersistedBan { target, expires_at: expires_at(until)? })
+                })
+                .collect(),
+            banned_ips: self
+                .ban_list
+                .banned_ips()
+                .filter_map(|(target, until)| {
+                    Some(PersistedBan { target, expires_at: expires_at(until)? })
+                })
+                .collect(),
+        };
+        store.compact(unix_now, &self.store_config);
+        store
+    }
+
     /// Returns an iterator over all peer ids for peers with the given kind
     pub(crate) fn peers_by_kind(&self, kind: PeerKind) -> impl Iterator<Item = PeerId> + '_ {
         self.peers.iter().filter_map(move |(peer_id, peer)| (peer.kind == kind).then_some(*peer_id))
@@ -321,6 +418,7 @@ impl PeersManager {
                 }
 
                 peer.state = PeerConnectionState::In;
+                peer.last_seen = Some(SystemTime::now());
 
                 is_trusted = is_trusted || peer.is_trusted();
 
@@ -338,6 +436,7 @@ impl PeersManager {
                 // disconnect, because we only know the outgoing port
                 let mut peer = Peer::with_state(addr, PeerConnectionState::In);
                 peer.remove_after_disconnect = true;
+                peer.last_seen = Some(SystemTime::now());
                 entry.insert(peer);
                 self.queued_actions.push_back(PeerAction::PeerAdded(peer_id));
 
@@ -415,6 +514,22 @@ impl PeersManager {
         self.peers.get(peer_id).map(|peer| peer.reputation)
     }
 
+    /// Returns the kind of the peer, if it is tracked.
+    pub(crate) fn get_kind(&self, peer_id: &PeerId) -> Option<PeerKind> {
+        self.peers.get(peer_id).map(|peer| peer.kind)
+    }
+
+    /// Returns the kind of the peer for a newly established session.
+    ///
+    /// Peers in the trusted set are trusted for the session, even if they connected to us and are
+    /// not tracked as trusted peers. This is not stored, so it applies to the current session only.
+    pub(crate) fn session_peer_kind(&self, peer_id: &PeerId) -> PeerKind {
+        if self.trusted_peer_ids.contains(peer_id) {
+            return PeerKind::Trusted
+        }
+        self.get_kind(peer_id).unwrap_or_default()
+    }
+
     /// Apply the corresponding reputation change to the given peer.
     ///
     /// If the peer is a trusted peer, it will be exempt from reputation slashing for certain
@@ -502,6 +617,7 @@ impl PeersManager {
                     // session to that peer
                     entry.get_mut().severe_backoff_counter = 0;
                     entry.get_mut().state = PeerConnectionState::Idle;
+                    entry.get_mut().last_seen = Some(SystemTime::now());
                     return
                 }
             }
@@ -517,6 +633,7 @@ impl PeersManager {
             self.connection_info.decr_state(peer.state);
             self.connection_info.inc_out();
             peer.state = PeerConnectionState::Out;
+            peer.last_seen = Some(SystemTime::now());
         }
     }
 
@@ -760,7 +877,7 @@ impl PeersManager {
         self.trusted_peer_ids.remove(&peer_id);
     }
 
-    /// Returns the idle peer with the highest reputation.
+    /// Returns the idle peer with the highest [`Peer::dial_priority`].
     ///
     /// Peers that are `trusted`, see [PeerKind], are prioritized as long as they're not currently
     /// marked as banned or backed off.
@@ -790,8 +907,8 @@ impl PeersManager {
                 return Some((*maybe_better.0, maybe_better.1))
             }
 
-            // otherwise we keep track of the best peer using the reputation
-            if maybe_better.1.reputation > best_peer.1.reputation {
+            // otherwise we keep track of the best peer using the reputation and history
+            if maybe_better.1.dial_priority() > best_peer.1.dial_priority() {
                 best_peer = maybe_better;
             }
         }
@@ -872,6 +989,9 @@ impl PeersManager {
                     PeerCommand::GetPeers(tx) => {
                         let _ = tx.send(self.iter_peers().collect());
                     }
+                    PeerCommand::GetPeerStore(tx) => {
+                        let _ = tx.send(self.peer_store());
+                    }
                 }
             }
 
@@ -1032,6 +1152,8 @@ pub struct Peer {
     backed_off: bool,
     /// Counts number of times the peer was backed off due to a severe [BackoffKind].
     severe_backoff_counter: u8,
+    /// When we last had a session with the peer, if ever.
+    last_seen: Option<SystemTime>,
 }
 
 // === impl Peer ===
@@ -1060,6 +1182,7 @@ impl Peer {
             kind: Default::default(),
             backed_off: false,
             severe_backoff_counter: 0,
+            last_seen: None,
         }
     }
 
@@ -1121,6 +1244,14 @@ impl Peer {
     fn is_trusted(&self) -> bool {
         matches!(self.kind, PeerKind::Trusted)
     }
+
+    /// Returns the key by which peers are prioritized for outbound dials, higher is better.
+    ///
+    /// Same as [`PersistedPeer::dial_priority`].
+    #[inline]
+    fn dial_priority(&self) -> (i32, Reverse<u8>, Option<SystemTime>) {
+        (self.reputation, Reverse(self.severe_backoff_counter), self.last_seen)
+    }
 }
 
 /// Outcomes when a reputation change is applied to a peer
@@ -1209,6 +1340,8 @@ pub(crate) enum PeerCommand {
     GetPeer(PeerId, oneshot::Sender<Option<Peer>>),
     /// Get node information on all peers
     GetPeers(oneshot::Sender<Vec<NodeRecord>>),
+    /// Get a snapshot of the peerset that can be persisted
+    GetPeerStore(oneshot::Sender<PeerStore>),
 }
 
 /// Actions the peer manager can trigger.
@@ -1308,6 +1441,11 @@ pub struct PeersConfig {
     ///
     /// The backoff duration increases with number of backoff attempts.
     pub backoff_durations: PeerBackoffDurations,
+    /// The peer set persisted by a previous run.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub persisted_peers: PeerStore,
+    /// Which peers to persist and how often.
+    pub store: PeerStoreConfig,
 }
 
 impl Default for PeersConfig {
@@ -1324,6 +1462,8 @@ impl Default for PeersConfig {
             trusted_nodes_only: false,
             basic_nodes: Default::default(),
             max_backoff_count: 5,
+            persisted_peers: Default::default(),
+            store: Default::default(),
         }
     }
 }
@@ -1405,6 +1545,18 @@ impl PeersConfig {
         self
     }
 
+    /// The peer set persisted by a previous run, see [`PeersManager::peer_store`].
+    pub fn with_persisted_peers(mut self, persisted_peers: PeerStore) -> Self {
+        self.persisted_peers = persisted_peers;
+        self
+    }
+
+    /// Configures which peers to persist and how often.
+    pub fn with_store_config(mut self, store: PeerStoreConfig) -> Self {
+        self.store = store;
+        self
+    }
+
     /// Configures the max allowed backoff count.
     pub fn with_max_backoff_count(mut self, max_backoff_count: u8) -> Self {
         self.max_backoff_count = max_backoff_count;
@@ -1429,6 +1581,9 @@ impl PeersConfig {
     }
 
     /// Read from file nodes available at launch. Ignored if None.
+    ///
+    /// The file either contains a [`PeerStore`] or, if it was written by an older version, a
+    /// plain list of [`NodeRecord`]s.
     pub fn with_basic_nodes_from_file(
         self,
         optional_file: Option<impl AsRef<Path>>,
@@ -1440,8 +1595,18 @@ impl PeersConfig {
             Err(e) => Err(e)?,
         };
         info!(target: "net::peers", file = %file_path.as_ref().display(), "Loading saved peers");
-        let nodes: HashSet<NodeRecord> = serde_json::from_reader(reader)?;
-        Ok(self.with_basic_nodes(nodes))
+
+        #[derive(serde::Deserialize)]
+        #[serde(untagged)]
+        enum SavedPeers {
+            Nodes(HashSet<NodeRecord>),
+            Store(PeerStore),
+        }
+
+        match serde_json::from_reader(reader)? {
+            SavedPeers::Nodes(nodes) => Ok(self.with_basic_nodes(nodes)),
+            SavedPeers::Store(store) => Ok(self.with_persisted_peers(store)),
+        }
     }
 
     /// Returns settings for testing
@@ -1557,7 +1722,7 @@ mod tests {
         DisconnectReason,
     };
     use reth_net_common::ban_list::BanList;
-    use reth_network_api::{Direction, ReputationChangeKind};
+    use reth_network_api::{Direction, PeerKind, ReputationChangeKind};
     use reth_primitives::{PeerId, B512};
     use std::{
         collections::HashSet,
@@ -2438,6 +2603,25 @@ mod tests {
         assert_eq!(peer_id, given_peer_id)
     }
 
+    #[tokio::test]
+    async fn test_incoming_trusted_session_kind() {
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let peer_id = PeerId::random();
+        let mut peer_manager = PeersManager::new(PeersConfig::test());
+        peer_manager.add_trusted_peer_id(peer_id);
+
+        assert!(peer_manager.on_incoming_pending_session(socket_addr.ip()).is_ok());
+        peer_manager.on_incoming_session_established(peer_id, socket_addr);
+
+        // the session is trusted, but the tracked peer is not marked as trusted
+        assert_eq!(peer_manager.session_peer_kind(&peer_id), PeerKind::Trusted);
+        assert_eq!(peer_manager.get_kind(&peer_id), Some(PeerKind::Basic));
+
+        // once removed from the trusted set, the next session is not trusted anymore
+        peer_manager.trusted_peer_ids.remove(&peer_id);
+        assert_eq!(peer_manager.session_peer_kind(&peer_id), PeerKind::Basic);
+    }
+
     #[test]
     fn test_connection_limits() {
         let mut info = ConnectionInfo::default();
@@ -2839,4 +3023,62 @@ mod tests {
         // no more pending outbound connections
         assert_eq!(peer_manager.connection_info.num_pending_out, 0);
     }
+
+    #[tokio::test]
+    async fn test_restore_peer_store() {
+        let peer = PeerId::random();
+        let banned = PeerId::random();
+        let backed_off = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::default();
+        peers.add_peer(peer, socket_addr, None);
+        peers.add_peer(banned, socket_addr, None);
+        peers.add_peer(backed_off, socket_addr, None);
+        peers.backoff_peer_until(
+            backed_off,
+            std::time::Instant::now() + Duration::from_secs(60 * 60),
+        );
+        peers.apply_reputation_change(&peer, ReputationChangeKind::Timeout);
+        peers.apply_reputation_change(&banned, ReputationChangeKind::BadProtocol);
+        let reputation = peers.get_reputation(&peer).unwrap();
+        assert_ne!(reputation, DEFAULT_REPUTATION);
+
+        let store = peers.peer_store();
+        assert_eq!(store.peers.len(), 3);
+        assert_eq!(store.banned_peers.len(), 1);
+
+        let restored = PeersManager::new(PeersConfig::test().with_persisted_peers(store));
+        assert_eq!(restored.get_reputation(&peer), Some(reputation));
+        assert!(restored.ban_list.is_banned_peer(&banned));
+        assert!(restored.peers.get(&banned).unwrap().is_banned());
+        assert!(restored.backed_off_peers.contains_key(&backed_off));
+        assert!(restored.peers.get(&backed_off).unwrap().is_backed_off());
+        assert!(!restored.peers.get(&peer).unwrap().is_backed_off());
+    }
+
+    #[test]
+    fn test_load_peers_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let record = NodeRecord::new(
+            SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 30303),
+            PeerId::random(),
+        );
+
+        // the plain list of nodes written by older versions
+        let legacy_file = dir.path().join("legacy.json");
+        std::fs::write(&legacy_file, format!("[\"{record}\"]")).unwrap();
+        let config = PeersConfig::default().with_basic_nodes_from_file(Some(&legacy_file)).unwrap();
+        assert_eq!(config.basic_nodes, HashSet::from([record]));
+        assert!(config.persisted_peers.peers.is_empty());
+
+        // the peer store written by this version
+        let mut peers = PeersManager::default();
+        peers.add_peer(record.id, record.tcp_addr(), None);
+        let store_file = dir.path().join("store.json");
+        std::fs::write(&store_file, serde_json::to_string(&peers.peer_store()).unwrap()).unwrap();
+        let config = PeersConfig::default().with_basic_nodes_from_file(Some(&store_file)).unwrap();
+        assert!(config.basic_nodes.is_empty());
+        assert_eq!(config.persisted_peers.peers.len(), 1);
+        assert_eq!(config.persisted_peers.peers[0].record, record);
+    }
 }
@@ -2,11 +2,13 @@
 
 mod manager;
 mod reputation;
+mod store;
 
 pub(crate) use manager::InboundConnectionError;
 pub use manager::{ConnectionInfo, Peer, PeerAction, PeersConfig, PeersHandle, PeersManager};
 pub use reputation::ReputationChangeWeights;
 pub use reth_network_api::PeerKind;
+pub use store::{PeerStore, PeerStoreConfig, PersistedBan, PersistedPeer};
 
 /// Maximum number of available slots for outbound sessions.
 pub const DEFAULT_MAX_COUNT_PEERS_OUTBOUND: u32 = 100;
@@ -0,0 +1,165 @@
+//! Persistence of the peer set across restarts.
+
+use reth_primitives::{ForkId, NodeRecord, PeerId};
+use std::{
+    cmp::Reverse,
+    net::IpAddr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A snapshot of the state of the peer set that is persisted across restarts.
+///
+/// This is created by [`NetworkManager::peer_store`](crate::NetworkManager::peer_store) and
+/// loaded via [`PeersConfig::with_basic_nodes_from_file`](crate::peers::PeersConfig).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PeerStore {
+    /// All known peers.
+    pub peers: Vec<PersistedPeer>,
+    /// Peers that are temporarily banned.
+    pub banned_peers: Vec<PersistedBan<PeerId>>,
+    /// IPs that are temporarily banned.
+    pub banned_ips: Vec<PersistedBan<IpAddr>>,
+}
+
+impl PeerStore {
+    /// Removes expired bans and stale peers, and caps the number of peers to the configured
+    /// maximum by dropping the peers with the lowest [`PersistedPeer::dial_priority`].
+    ///
+    /// `now` is the current unix timestamp in seconds.
+    pub fn compact(&mut self, now: u64, config: &PeerStoreConfig) {
+        self.banned_peers.retain(|ban| ban.expires_at > now);
+        self.banned_ips.retain(|ban| ban.expires_at > now);
+
+        let max_age = config.max_age.as_secs();
+        self.peers.retain(|peer| {
+            peer.last_seen.map_or(true, |last_seen| now.saturating_sub(last_seen) <= max_age)
+        });
+
+        if self.peers.len() > config.max_peers {
+            self.peers.sort_unstable_by_key(|peer| Reverse(peer.dial_priority()));
+            self.peers.truncate(config.max_peers);
+        }
+    }
+}
+
+/// A persisted entry of the peer set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedPeer {
+    /// Where to reach the peer.
+    pub record: NodeRecord,
+    /// The reputation of the peer.
+    pub reputation: i32,
+    /// Unix timestamp in seconds of when we last had a session with the peer, if ever.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub last_seen: Option<u64>,
+    /// How often in a row we failed to connect to the peer due to severe errors.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub failures: u8,
+    /// Unix timestamp in seconds until which the peer is backed off, if it is.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub backoff_until: Option<u64>,
+    /// The [`ForkId`] the peer announced via discovery.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    pub fork_id: Option<ForkId>,
+}
+
+impl PersistedPeer {
+    /// Returns the key by which peers are prioritized for outbound dials, higher is better.
+    ///
+    /// Peers with a higher reputation are preferred, followed by fewer failures and then the peers
+    /// we had a session with most recently.
+    pub fn dial_priority(&self) -> (i32, Reverse<u8>, Option<u64>) {
+        (self.reputation, Reverse(self.failures), self.last_seen)
+    }
+}
+
+/// A persisted temporary ban.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PersistedBan<T> {
+    /// The banned peer or ip.
+    pub target: T,
+    /// Unix timestamp in seconds of when the ban expires.
+    pub expires_at: u64,
+}
+
+/// Configures which entries of the [`PeerStore`] are kept and how often it is persisted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct PeerStoreConfig {
+    /// Peers we haven't had a session with for longer than this are dropped from the store.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub max_age: Duration,
+    /// The maximum number of peers to persist.
+    pub max_peers: usize,
+    /// How often the store is written to disk while the node is running.
+    ///
+    /// A zero interval disables the periodic writes, the store is then only written on shutdown.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub persist_interval: Duration,
+}
+
+impl Default for PeerStoreConfig {
+    fn default() -> Self {
+        Self {
+            // 7 days
+            max_age: Duration::from_secs(60 * 60 * 24 * 7),
+            max_peers: 5_000,
+            // 10min
+            persist_interval: Duration::from_secs(60 * 10),
+        }
+    }
+}
+
+/// Returns the given time as unix timestamp in seconds.
+pub(crate) fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+
+    fn peer(reputation: i32, last_seen: Option<u64>) -> PersistedPeer {
+        PersistedPeer {
+            record: NodeRecord::new(SocketAddr::from(([127, 0, 0, 1], 30303)), PeerId::random()),
+            reputation,
+            last_seen,
+            failures: 0,
+            backoff_until: None,
+            fork_id: None,
+        }
+    }
+
+    #[test]
+    fn compact_store() {
+        let config = PeerStoreConfig {
+            max_age: Duration::from_secs(100),
+            max_peers: 2,
+            ..Default::default()
+        };
+        let stale = peer(10, Some(0));
+        let best = peer(10, Some(950));
+        let never_seen = peer(0, None);
+        let worst = peer(-10, Some(1_000));
+        let mut store = PeerStore {
+            peers: vec![stale, worst, never_seen.clone(), best.clone()],
+            banned_peers: vec![
+                PersistedBan { target: PeerId::random(), expires_at: 999 },
+                PersistedBan { target: PeerId::random(), expires_at: 1_001 },
+            ],
+            banned_ips: vec![],
+        };
+
+        store.compact(1_000, &config);
+
+        assert_eq!(store.peers, vec![best, never_seen]);
+        assert_eq!(store.banned_peers.len(), 1);
+        assert_eq!(store.banned_peers[0].expires_at, 1_001);
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     session::{
         config::INITIAL_REQUEST_TIMEOUT,
         conn::EthRlpxConnection,
-        handle::{ActiveSessionMessage, SessionCommand},
+        handle::{ActiveSessionMessage, RequestLatency, SessionCommand},
         SessionId,
     },
 };
@@ -90,6 +90,8 @@ pub(crate) struct ActiveSession {
     pub(crate) internal_request_timeout: Arc<AtomicU64>,
     /// Interval when to check for timed out requests.
     pub(crate) internal_request_timeout_interval: Interval,
+    /// The latency of the last request the peer responded to.
+    pub(crate) last_request_latency: RequestLatency,
     /// If an [ActiveSession] does not receive a response at all within this duration then it is
     /// considered a protocol violation and the session will initiate a drop.
     pub(crate) protocol_breach_request_timeout: Duration,
@@ -226,11 +228,32 @@ impl ActiveSession {
                 on_response!(resp, GetNodeData)
             }
             EthMessage::GetReceipts(req) => {
-                on_request!(req, Receipts, GetReceipts)
+                if self.conn.version().is_eth69() {
+                    on_request!(req, Receipts69, GetReceipts69)
+                } else {
+                    on_request!(req, Receipts, GetReceipts)
+                }
             }
             EthMessage::Receipts(resp) => {
                 on_response!(resp, GetReceipts)
             }
+            EthMessage::Receipts69(resp) => {
+                let RequestPair { request_id, message } = resp;
+                let resp = RequestPair { request_id, message: message.into_receipts_with_bloom() };
+                on_response!(resp, GetReceipts)
+            }
+            EthMessage::BlockRangeUpdate(update) => {
+                if !update.is_valid() {
+                    return OnIncomingMessageOutcome::BadMessage {
+                        error: EthStreamError::InvalidBlockRange {
+                            earliest: update.earliest,
+                            latest: update.latest,
+                        },
+                        message: EthMessage::BlockRangeUpdate(update),
+                    }
+                }
+                self.try_emit_broadcast(PeerMessage::BlockRangeUpdated(update)).into()
+            }
         }
     }
 
@@ -250,12 +273,23 @@ impl ActiveSession {
     /// Handle a message received from the internal network
     fn on_internal_peer_message(&mut self, msg: PeerMessage) {
         match msg {
+            // block announcements were removed in eth/69
+            PeerMessage::NewBlockHashes(_) | PeerMessage::NewBlock(_)
+                if self.conn.version().is_eth69() =>
+            {
+                trace!(target: "net::session", "Skipping block announcement to eth/69 peer");
+            }
             PeerMessage::NewBlockHashes(msg) => {
                 self.queued_outgoing.push_back(EthMessage::NewBlockHashes(msg).into());
             }
             PeerMessage::NewBlock(msg) => {
                 self.queued_outgoing.push_back(EthBroadcastMessage::NewBlock(msg.block).into());
             }
+            PeerMessage::BlockRangeUpdated(update) => {
+                if self.conn.version().is_eth69() {
+                    self.queued_outgoing.push_back(EthMessage::BlockRangeUpdate(update).into());
+                }
+            }
             PeerMessage::PooledTransactions(msg) => {
                 if msg.is_valid_for_version(self.conn.version()) {
                     self.queued_outgoing.push_back(EthMessage::from(msg).into());
@@ -440,6 +474,7 @@ impl ActiveSession {
     /// Updates the request timeout with a request's timestamps
     fn update_request_timeout(&mut self, sent: Instant, received: Instant) {
         let elapsed = received.saturating_duration_since(sent);
+        self.last_request_latency.set(elapsed);
 
         let current = Duration::from_millis(self.internal_request_timeout.load(Ordering::Relaxed));
         let request_timeout = calculate_new_timeout(current, elapsed);
@@ -759,17 +794,21 @@ fn calculate_new_timeout(current_timeout: Duration, estimated_rtt: Duration) ->
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::session::{
-        config::PROTOCOL_BREACH_REQUEST_TIMEOUT, handle::PendingSessionEvent,
-        start_pending_incoming_session,
+    use crate::{
+        session::{
+            config::PROTOCOL_BREACH_REQUEST_TIMEOUT, handle::PendingSessionEvent,
+            start_pending_incoming_session,
+        },
+        transport::BoxedTransportStream,
     };
     use reth_ecies::stream::ECIESStream;
     use reth_eth_wire::{
-        EthStream, GetBlockBodies, HelloMessageWithProtocols, P2PStream, Status, StatusBuilder,
+        BlockHashNumber, BlockRangeUpdate, EthStream, GetBlockBodies, HelloMessageWithProtocols,
+        NewBlock, NewBlockHashes, P2PStream, Status, StatusBuilder, StatusMessage,
         UnauthedEthStream, UnauthedP2PStream,
     };
     use reth_net_common::bandwidth_meter::{BandwidthMeter, MeteredStream};
-    use reth_primitives::{pk2id, ForkFilter, Hardfork, MAINNET};
+    use reth_primitives::{pk2id, ForkFilter, Hardfork, B256, MAINNET};
     use secp256k1::{SecretKey, SECP256K1};
     use tokio::{
         net::{TcpListener, TcpStream},
@@ -812,7 +851,7 @@ mod tests {
             F: FnOnce(EthStream<P2PStream<ECIESStream<TcpStream>>>) -> O + Send + 'static,
             O: Future<Output = ()> + Send + Sync,
         {
-            let status = self.status;
+            let mut status = self.status;
             let fork_filter = self.fork_filter.clone();
             let local_peer_id = self.local_peer_id;
             let mut hello = self.hello.clone();
@@ -824,6 +863,14 @@ mod tests {
 
                 let (p2p_stream, _) = UnauthedP2PStream::new(sink).handshake(hello).await.unwrap();
 
+                let eth_version = p2p_stream.shared_capabilities().eth_version().unwrap();
+                status.set_eth_version(eth_version);
+                let status = if eth_version.is_eth69() {
+                    StatusMessage::Eth69(status.into_eth69(Default::default()))
+                } else {
+                    StatusMessage::Legacy(status)
+                };
+
                 let (client_stream, _) = UnauthedEthStream::new(p2p_stream)
                     .handshake(status, fork_filter)
                     .await
@@ -837,8 +884,10 @@ mod tests {
             let session_id = self.next_id();
             let (_disconnect_tx, disconnect_rx) = oneshot::channel();
             let (pending_sessions_tx, pending_sessions_rx) = mpsc::channel(1);
-            let metered_stream =
-                MeteredStream::new_with_meter(stream, self.bandwidth_meter.clone());
+            let metered_stream = MeteredStream::new_with_meter(
+                Box::new(stream) as BoxedTransportStream,
+                self.bandwidth_meter.clone(),
+            );
 
             tokio::task::spawn(start_pending_incoming_session(
                 disconnect_rx,
@@ -849,8 +898,10 @@ mod tests {
                 self.secret_key,
                 self.hello.clone(),
                 self.status,
+                Default::default(),
                 self.fork_filter.clone(),
                 Default::default(),
+                Default::default(),
             ));
 
             let mut stream = ReceiverStream::new(pending_sessions_rx);
@@ -893,6 +944,7 @@ mod tests {
                         internal_request_timeout: Arc::new(AtomicU64::new(
                             INITIAL_REQUEST_TIMEOUT.as_millis() as u64,
                         )),
+                        last_request_latency: Default::default(),
                         protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
                         terminate_message: None,
                     }
@@ -1141,6 +1193,42 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_eth69_skips_block_announcements() {
+        let mut builder = SessionBuilder::default();
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let update = BlockRangeUpdate { earliest: 0, latest: 1, latest_hash: B256::random() };
+
+        let fut = builder.with_client_stream(local_addr, move |mut client_stream| async move {
+            assert!(client_stream.version().is_eth69());
+
+            // the block announcements are skipped, so the block range update is the first message
+            let message = client_stream.next().await.unwrap().unwrap();
+            assert_eq!(message, EthMessage::BlockRangeUpdate(update));
+        });
+        let client = tokio::task::spawn(fut);
+
+        let (incoming, _) = listener.accept().await.unwrap();
+        let session = builder.connect_incoming(incoming).await;
+        let commands = builder.to_sessions.pop().unwrap();
+
+        let hashes = NewBlockHashes(vec![BlockHashNumber { hash: B256::random(), number: 1 }]);
+        let block = NewBlockMessage { hash: B256::random(), block: Arc::new(NewBlock::default()) };
+        for message in [
+            PeerMessage::NewBlockHashes(hashes),
+            PeerMessage::NewBlock(block),
+            PeerMessage::BlockRangeUpdated(update),
+        ] {
+            commands.send(SessionCommand::Message(message)).await.unwrap();
+        }
+        tokio::task::spawn(session);
+
+        client.await.unwrap();
+    }
+
     #[test]
     fn timeout_calculation_sanity_tests() {
         let rtt = Duration::from_secs(5);
@@ -4,6 +4,7 @@ use crate::{
     peers::{DEFAULT_MAX_COUNT_PEERS_INBOUND, DEFAULT_MAX_COUNT_PEERS_OUTBOUND},
     session::{Direction, ExceedsSessionLimit},
 };
+use reth_eth_wire::BandwidthLimits;
 use std::time::Duration;
 
 /// Default request timeout for a single request.
@@ -53,6 +54,10 @@ pub struct SessionsConfig {
     pub protocol_breach_request_timeout: Duration,
     /// The timeout after which a pending session attempt is considered failed.
     pub pending_session_timeout: Duration,
+    /// Byte rate limits to enforce on the sessions.
+    ///
+    /// By default, the bandwidth is not limited.
+    pub bandwidth_limits: BandwidthLimits,
 }
 
 impl Default for SessionsConfig {
@@ -72,6 +77,7 @@ impl Default for SessionsConfig {
             initial_internal_request_timeout: INITIAL_REQUEST_TIMEOUT,
             protocol_breach_request_timeout: PROTOCOL_BREACH_REQUEST_TIMEOUT,
             pending_session_timeout: PENDING_SESSION_TIMEOUT,
+            bandwidth_limits: Default::default(),
         }
     }
 }
@@ -103,6 +109,12 @@ impl SessionsConfig {
         }
         self
     }
+
+    /// Sets the byte rate limits to enforce on the sessions.
+    pub fn with_bandwidth_limits(mut self, limits: BandwidthLimits) -> Self {
+        self.bandwidth_limits = limits;
+        self
+    }
 }
 
 /// Limits for sessions.
@@ -1,5 +1,6 @@
 //! Connection types for a session
 
+use crate::transport::BoxedTransportStream;
 use futures::{Sink, Stream};
 use reth_ecies::stream::ECIESStream;
 use reth_eth_wire::{
@@ -13,14 +14,13 @@ use std::{
     pin::Pin,
     task::{Context, Poll},
 };
-use tokio::net::TcpStream;
 
 /// The type of the underlying peer network connection.
-pub type EthPeerConnection = EthStream<P2PStream<ECIESStream<MeteredStream<TcpStream>>>>;
+pub type EthPeerConnection = EthStream<P2PStream<ECIESStream<MeteredStream<BoxedTransportStream>>>>;
 
 /// Various connection types that at least support the ETH protocol.
 pub type EthSatelliteConnection =
-    RlpxSatelliteStream<ECIESStream<MeteredStream<TcpStream>>, EthStream<ProtocolProxy>>;
+    RlpxSatelliteStream<ECIESStream<MeteredStream<BoxedTransportStream>>, EthStream<ProtocolProxy>>;
 
 /// Connection types that support the ETH protocol.
 ///
@@ -47,7 +47,7 @@ impl EthRlpxConnection {
 
     /// Consumes this type and returns the wrapped [P2PStream].
     #[inline]
-    pub(crate) fn into_inner(self) -> P2PStream<ECIESStream<MeteredStream<TcpStream>>> {
+    pub(crate) fn into_inner(self) -> P2PStream<ECIESStream<MeteredStream<BoxedTransportStream>>> {
         match self {
             Self::EthOnly(conn) => conn.into_inner(),
             Self::Satellite(conn) => conn.into_inner(),
@@ -56,7 +56,9 @@ impl EthRlpxConnection {
 
     /// Returns mutable access to the underlying stream.
     #[inline]
-    pub(crate) fn inner_mut(&mut self) -> &mut P2PStream<ECIESStream<MeteredStream<TcpStream>>> {
+    pub(crate) fn inner_mut(
+        &mut self,
+    ) -> &mut P2PStream<ECIESStream<MeteredStream<BoxedTransportStream>>> {
         match self {
             Self::EthOnly(conn) => conn.inner_mut(),
             Self::Satellite(conn) => conn.inner_mut(),
@@ -65,7 +67,7 @@ impl EthRlpxConnection {
 
     /// Returns  access to the underlying stream.
     #[inline]
-    pub(crate) fn inner(&self) -> &P2PStream<ECIESStream<MeteredStream<TcpStream>>> {
+    pub(crate) fn inner(&self) -> &P2PStream<ECIESStream<MeteredStream<BoxedTransportStream>>> {
         match self {
             Self::EthOnly(conn) => conn.inner(),
             Self::Satellite(conn) => conn.inner(),
@@ -11,9 +11,18 @@ use reth_eth_wire::{
     errors::EthStreamError,
     DisconnectReason, EthVersion, Status,
 };
-use reth_network_api::PeerInfo;
+use reth_net_common::bandwidth_meter::BandwidthMeter;
+use reth_network_api::{PeerInfo, PeerKind};
 use reth_primitives::PeerId;
-use std::{io, net::SocketAddr, sync::Arc, time::Instant};
+use std::{
+    io,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 use tokio::sync::{
     mpsc::{self, error::SendError},
     oneshot,
@@ -29,6 +38,8 @@ pub struct PendingSessionHandle {
     pub(crate) disconnect_tx: Option<oneshot::Sender<()>>,
     /// The direction of the session
     pub(crate) direction: Direction,
+    /// Meters the bandwidth of the session's connection
+    pub(crate) bandwidth_meter: BandwidthMeter,
 }
 
 // === impl PendingSessionHandle ===
@@ -75,6 +86,10 @@ pub struct ActiveSessionHandle {
     pub(crate) local_addr: Option<SocketAddr>,
     /// The Status message the peer sent for the `eth` handshake
     pub(crate) status: Arc<Status>,
+    /// Meters the bandwidth of the session's connection
+    pub(crate) bandwidth_meter: BandwidthMeter,
+    /// The latency of the last request the peer responded to
+    pub(crate) last_request_latency: RequestLatency,
 }
 
 // === impl ActiveSessionHandle ===
@@ -135,7 +150,20 @@ impl ActiveSessionHandle {
         self.remote_addr
     }
 
+    /// Returns the bandwidth meter of the session's connection.
+    pub fn bandwidth_meter(&self) -> &BandwidthMeter {
+        &self.bandwidth_meter
+    }
+
+    /// Returns the latency of the last request the peer responded to, if any.
+    pub fn last_request_latency(&self) -> Option<Duration> {
+        self.last_request_latency.get()
+    }
+
     /// Extracts the [PeerInfo] from the session handle.
+    ///
+    /// Note: the session doesn't know the reputation, kind and request statistics of the peer,
+    /// these are set to their defaults.
     pub(crate) fn peer_info(&self) -> PeerInfo {
         PeerInfo {
             remote_id: self.remote_id,
@@ -147,10 +175,46 @@ impl ActiveSessionHandle {
             eth_version: self.version,
             status: self.status.clone(),
             session_established: self.established,
+            reputation: Default::default(),
+            kind: PeerKind::default(),
+            ingress_bytes: self.bandwidth_meter.total_inbound(),
+            egress_bytes: self.bandwidth_meter.total_outbound(),
+            last_request_latency: self.last_request_latency(),
+            request_stats: Default::default(),
         }
     }
 }
 
+/// The latency of the last request of a session that was answered by the remote peer.
+///
+/// This is shared between the [ActiveSession](super::active::ActiveSession) and its
+/// [ActiveSessionHandle].
+#[derive(Debug, Clone)]
+pub(crate) struct RequestLatency(Arc<AtomicU64>);
+
+impl RequestLatency {
+    /// Marker for no recorded latency.
+    const UNSET: u64 = u64::MAX;
+
+    /// Records the latency of a request.
+    pub(crate) fn set(&self, latency: Duration) {
+        let micros = u64::try_from(latency.as_micros()).unwrap_or(Self::UNSET - 1);
+        self.0.store(micros.min(Self::UNSET - 1), Ordering::Relaxed);
+    }
+
+    /// Returns the latency of the last request, if any.
+    pub(crate) fn get(&self) -> Option<Duration> {
+        let micros = self.0.load(Ordering::Relaxed);
+        (micros != Self::UNSET).then(|| Duration::from_micros(micros))
+    }
+}
+
+impl Default for RequestLatency {
+    fn default() -> Self {
+        Self(Arc::new(AtomicU64::new(Self::UNSET)))
+    }
+}
+
 /// Events a pending session can produce.
 ///
 /// This represents the state changes a session can undergo until it is ready to send capability messages <https://github.com/ethereum/devp2p/blob/6b0abc3d956a626c28dce1307ee9f546db17b6bd/rlpx.md>.
@@ -4,22 +4,27 @@ use crate::{
     message::PeerMessage,
     metrics::SessionManagerMetrics,
     session::{active::ActiveSession, config::SessionCounter},
+    transport::{BoxedTransportStream, Transport},
 };
 use fnv::FnvHashMap;
-use futures::{future::Either, io, FutureExt, StreamExt};
+use futures::{
+    future::{BoxFuture, Either},
+    io, FutureExt, StreamExt,
+};
 use reth_ecies::{stream::ECIESStream, ECIESError};
 use reth_eth_wire::{
+    bandwidth::{BandwidthLimiter, PeerBandwidthLimiter},
     capability::{Capabilities, CapabilityMessage},
     errors::EthStreamError,
-    DisconnectReason, EthVersion, HelloMessageWithProtocols, Status, UnauthedEthStream,
-    UnauthedP2PStream,
+    BlockRangeUpdate, DisconnectReason, EthVersion, HelloMessageWithProtocols, Status,
+    StatusMessage, UnauthedEthStream, UnauthedP2PStream,
 };
 use reth_metrics::common::mpsc::MeteredPollSender;
 use reth_net_common::{
     bandwidth_meter::{BandwidthMeter, MeteredStream},
     stream::HasRemoteAddr,
 };
-use reth_primitives::{ForkFilter, ForkId, ForkTransition, Head, PeerId};
+use reth_primitives::{BlockNumber, ForkFilter, ForkId, ForkTransition, Head, PeerId, PruneModes};
 use reth_tasks::TaskSpawner;
 use secp256k1::SecretKey;
 use std::{
@@ -32,7 +37,6 @@ use std::{
 };
 use tokio::{
     io::{AsyncRead, AsyncWrite},
-    net::TcpStream,
     sync::{mpsc, oneshot},
 };
 use tokio_stream::wrappers::ReceiverStream;
@@ -46,6 +50,7 @@ mod handle;
 pub use crate::message::PeerRequestSender;
 use crate::protocol::{IntoRlpxSubProtocol, RlpxSubProtocolHandlers, RlpxSubProtocols};
 pub use config::{SessionLimits, SessionsConfig};
+use handle::RequestLatency;
 pub use handle::{
     ActiveSessionHandle, ActiveSessionMessage, PendingSessionEvent, PendingSessionHandle,
     SessionCommand,
@@ -53,6 +58,9 @@ pub use handle::{
 use reth_eth_wire::multiplex::RlpxProtocolMultiplexer;
 pub use reth_network_api::{Direction, PeerInfo};
 
+/// The number of blocks after which an updated block range is announced to `eth/69` peers.
+const BLOCK_RANGE_UPDATE_INTERVAL: u64 = 32;
+
 /// Internal identifier for active sessions.
 #[derive(Debug, Clone, Copy, PartialOrd, PartialEq, Eq, Hash)]
 pub struct SessionId(usize);
@@ -77,6 +85,12 @@ pub struct SessionManager {
     secret_key: SecretKey,
     /// The `Status` message to send to peers.
     status: Status,
+    /// The range of blocks announced to `eth/69` peers.
+    block_range: BlockRangeUpdate,
+    /// The latest block of the last [`BlockRangeUpdate`] announced to active sessions.
+    announced_block_range_latest: u64,
+    /// The prune modes of the node, which determine the earliest block of the block range.
+    prune_modes: PruneModes,
     /// The `HelloMessage` message to send to peers.
     hello_message: HelloMessageWithProtocols,
     /// The [`ForkFilter`] used to validate the peer's `Status` message.
@@ -110,6 +124,10 @@ pub struct SessionManager {
     extra_protocols: RlpxSubProtocols,
     /// Used to measure inbound & outbound bandwidth across all managed streams
     bandwidth_meter: BandwidthMeter,
+    /// Enforces the configured bandwidth limits across all managed streams.
+    bandwidth_limiter: BandwidthLimiter,
+    /// The transport used to dial outbound connections.
+    transport: Arc<dyn Transport>,
     /// Metrics for the session manager.
     metrics: SessionManagerMetrics,
 }
@@ -124,10 +142,13 @@ impl SessionManager {
         config: SessionsConfig,
         executor: Box<dyn TaskSpawner>,
         status: Status,
+        block_range: BlockRangeUpdate,
+        prune_modes: PruneModes,
         hello_message: HelloMessageWithProtocols,
         fork_filter: ForkFilter,
         extra_protocols: RlpxSubProtocols,
         bandwidth_meter: BandwidthMeter,
+        transport: Arc<dyn Transport>,
     ) -> Self {
         let (pending_sessions_tx, pending_sessions_rx) = mpsc::channel(config.session_event_buffer);
         let (active_session_tx, active_session_rx) = mpsc::channel(config.session_event_buffer);
@@ -141,6 +162,9 @@ impl SessionManager {
             pending_session_timeout: config.pending_session_timeout,
             secret_key,
             status,
+            block_range,
+            announced_block_range_latest: block_range.latest,
+            prune_modes,
             hello_message,
             fork_filter,
             session_command_buffer: config.session_command_buffer,
@@ -152,7 +176,9 @@ impl SessionManager {
             active_session_tx: MeteredPollSender::new(active_session_tx, "network_active_session"),
             active_session_rx: ReceiverStream::new(active_session_rx),
             bandwidth_meter,
+            bandwidth_limiter: BandwidthLimiter::new(config.bandwidth_limits),
             extra_protocols,
+            transport,
             metrics: Default::default(),
         }
     }
@@ -175,6 +201,11 @@ impl SessionManager {
         self.status
     }
 
+    /// Returns the range of blocks announced to `eth/69` peers.
+    pub const fn block_range(&self) -> BlockRangeUpdate {
+        self.block_range
+    }
+
     /// Returns the secret key used for authenticating sessions.
     pub fn secret_key(&self) -> SecretKey {
         self.secret_key
@@ -209,21 +240,39 @@ impl SessionManager {
     ///
     /// If the updated activated another fork, this will return a [ForkTransition] and updates the
     /// active [ForkId]. See also [ForkFilter::set_head].
+    ///
+    /// Once the head advanced by [`BLOCK_RANGE_UPDATE_INTERVAL`] blocks since the last
+    /// announcement, the new block range is sent to all `eth/69` sessions.
     pub(crate) fn on_status_update(&mut self, head: Head) -> Option<ForkTransition> {
         self.status.blockhash = head.hash;
         self.status.total_difficulty = head.total_difficulty;
         let transition = self.fork_filter.set_head(head);
         self.status.forkid = self.fork_filter.current();
+
+        self.block_range.latest = head.number;
+        self.block_range.latest_hash = head.hash;
+        self.block_range.earliest = earliest_block(&self.prune_modes, head.number);
+        if head.number.abs_diff(self.announced_block_range_latest) >= BLOCK_RANGE_UPDATE_INTERVAL {
+            self.announced_block_range_latest = head.number;
+            for session in self.active_sessions.values() {
+                if session.version.is_eth69() {
+                    let _ = session.commands_to_session.try_send(SessionCommand::Message(
+                        PeerMessage::BlockRangeUpdated(self.block_range),
+                    ));
+                }
+            }
+        }
+
         transition
     }
 
-    /// An incoming TCP connection was received. This starts the authentication process to turn this
+    /// An incoming connection was received. This starts the authentication process to turn this
     /// stream into an active peer session.
     ///
     /// Returns an error if the configured limit has been reached.
     pub(crate) fn on_incoming(
         &mut self,
-        stream: TcpStream,
+        stream: BoxedTransportStream,
         remote_addr: SocketAddr,
     ) -> Result<SessionId, ExceedsSessionLimit> {
         self.counter.ensure_pending_inbound()?;
@@ -239,12 +288,15 @@ impl SessionManager {
 
         let (disconnect_tx, disconnect_rx) = oneshot::channel();
         let pending_events = self.pending_sessions_tx.clone();
-        let metered_stream = MeteredStream::new_with_meter(stream, self.bandwidth_meter.clone());
+        let bandwidth_meter = self.bandwidth_meter.child();
+        let metered_stream = MeteredStream::new_with_meter(stream, bandwidth_meter.clone());
         let secret_key = self.secret_key;
         let hello_message = self.hello_message.clone();
         let status = self.status;
+        let block_range = self.block_range;
         let fork_filter = self.fork_filter.clone();
         let extra_handlers = self.extra_protocols.on_incoming(remote_addr);
+        let bandwidth_limiter = self.bandwidth_limiter.peer_limiter();
         self.spawn(pending_session_with_timeout(
             self.pending_session_timeout,
             session_id,
@@ -260,14 +312,17 @@ impl SessionManager {
                 secret_key,
                 hello_message,
                 status,
+                block_range,
                 fork_filter,
                 extra_handlers,
+                bandwidth_limiter,
             ),
         ));
 
         let handle = PendingSessionHandle {
             disconnect_tx: Some(disconnect_tx),
             direction: Direction::Incoming,
+            bandwidth_meter,
         };
         self.pending_sessions.insert(session_id, handle);
         self.counter.inc_pending_inbound();
@@ -285,8 +340,11 @@ impl SessionManager {
             let hello_message = self.hello_message.clone();
             let fork_filter = self.fork_filter.clone();
             let status = self.status;
-            let band_with_meter = self.bandwidth_meter.clone();
+            let block_range = self.block_range;
+            let bandwidth_meter = self.bandwidth_meter.child();
             let extra_handlers = self.extra_protocols.on_outgoing(remote_addr, remote_peer_id);
+            let bandwidth_limiter = self.bandwidth_limiter.peer_limiter();
+            let dial = self.transport.dial(remote_addr);
             self.spawn(pending_session_with_timeout(
                 self.pending_session_timeout,
                 session_id,
@@ -294,6 +352,7 @@ impl SessionManager {
                 Direction::Outgoing(remote_peer_id),
                 pending_events.clone(),
                 start_pending_outbound_session(
+                    dial,
                     disconnect_rx,
                     pending_events,
                     session_id,
@@ -302,15 +361,18 @@ impl SessionManager {
                     secret_key,
                     hello_message,
                     status,
+                    block_range,
                     fork_filter,
-                    band_with_meter,
+                    bandwidth_meter.clone(),
                     extra_handlers,
+                    bandwidth_limiter,
                 ),
             ));
 
             let handle = PendingSessionHandle {
                 disconnect_tx: Some(disconnect_tx),
                 direction: Direction::Outgoing(remote_peer_id),
+                bandwidth_meter,
             };
             self.pending_sessions.insert(session_id, handle);
             self.counter.inc_pending_outbound();
@@ -434,7 +496,10 @@ impl SessionManager {
                 client_id,
             } => {
                 // move from pending to established.
-                self.remove_pending_session(&session_id);
+                let bandwidth_meter = self
+                    .remove_pending_session(&session_id)
+                    .map(|session| session.bandwidth_meter)
+                    .unwrap_or_default();
 
                 // If there's already a session to the peer then we disconnect right away
                 if self.active_sessions.contains_key(&peer_id) {
@@ -470,6 +535,8 @@ impl SessionManager {
                     self.initial_internal_request_timeout.as_millis() as u64,
                 ));
 
+                let last_request_latency = RequestLatency::default();
+
                 // negotiated version
                 let version = conn.version();
 
@@ -491,6 +558,7 @@ impl SessionManager {
                         self.initial_internal_request_timeout,
                     ),
                     internal_request_timeout: Arc::clone(&timeout),
+                    last_request_latency: last_request_latency.clone(),
                     protocol_breach_request_timeout: self.protocol_breach_request_timeout,
                     terminate_message: None,
                 };
@@ -510,6 +578,8 @@ impl SessionManager {
                     client_version: Arc::clone(&client_version),
                     remote_addr,
                     local_addr,
+                    bandwidth_meter,
+                    last_request_latency,
                 };
 
                 self.active_sessions.insert(peer_id, handle);
@@ -766,6 +836,12 @@ impl PendingSessionHandshakeError {
 #[error("session limit reached {0}")]
 pub struct ExceedsSessionLimit(pub(crate) u32);
 
+/// Returns the earliest block announced to `eth/69` peers at the given head, the first block whose
+/// receipts are all retained under the prune modes.
+pub(crate) fn earliest_block(prune_modes: &PruneModes, head: BlockNumber) -> BlockNumber {
+    prune_modes.earliest_full_receipts_block(head).unwrap_or_default().min(head)
+}
+
 /// Starts a pending session authentication with a timeout.
 pub(crate) async fn pending_session_with_timeout<F>(
     timeout: Duration,
@@ -796,14 +872,16 @@ pub(crate) async fn pending_session_with_timeout<F>(
 pub(crate) async fn start_pending_incoming_session(
     disconnect_rx: oneshot::Receiver<()>,
     session_id: SessionId,
-    stream: MeteredStream<TcpStream>,
+    stream: MeteredStream<BoxedTransportStream>,
     events: mpsc::Sender<PendingSessionEvent>,
     remote_addr: SocketAddr,
     secret_key: SecretKey,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    bandwidth_limiter: PeerBandwidthLimiter,
 ) {
     authenticate(
         disconnect_rx,
@@ -815,8 +893,10 @@ pub(crate) async fn start_pending_incoming_session(
         Direction::Incoming,
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
+        bandwidth_limiter,
     )
     .await
 }
@@ -825,6 +905,7 @@ pub(crate) async fn start_pending_incoming_session(
 #[instrument(skip_all, fields(%remote_addr, peer_id), target = "net")]
 #[allow(clippy::too_many_arguments)]
 async fn start_pending_outbound_session(
+    dial: BoxFuture<'static, io::Result<BoxedTransportStream>>,
     disconnect_rx: oneshot::Receiver<()>,
     events: mpsc::Sender<PendingSessionEvent>,
     session_id: SessionId,
@@ -833,17 +914,14 @@ async fn start_pending_outbound_session(
     secret_key: SecretKey,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     bandwidth_meter: BandwidthMeter,
     extra_handlers: RlpxSubProtocolHandlers,
+    bandwidth_limiter: PeerBandwidthLimiter,
 ) {
-    let stream = match TcpStream::connect(remote_addr).await {
-        Ok(stream) => {
-            if let Err(err) = stream.set_nodelay(true) {
-                tracing::warn!(target: "net::session", "set nodelay failed: {:?}", err);
-            }
-            MeteredStream::new_with_meter(stream, bandwidth_meter)
-        }
+    let stream = match dial.await {
+        Ok(stream) => MeteredStream::new_with_meter(stream, bandwidth_meter),
         Err(error) => {
             let _ = events
                 .send(PendingSessionEvent::OutgoingConnectionError {
@@ -866,8 +944,10 @@ async fn start_pending_outbound_session(
         Direction::Outgoing(remote_peer_id),
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
+        bandwidth_limiter,
     )
     .await
 }
@@ -877,17 +957,19 @@ async fn start_pending_outbound_session(
 async fn authenticate(
     disconnect_rx: oneshot::Receiver<()>,
     events: mpsc::Sender<PendingSessionEvent>,
-    stream: MeteredStream<TcpStream>,
+    stream: MeteredStream<BoxedTransportStream>,
     session_id: SessionId,
     remote_addr: SocketAddr,
     secret_key: SecretKey,
     direction: Direction,
     hello: HelloMessageWithProtocols,
     status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     extra_handlers: RlpxSubProtocolHandlers,
+    bandwidth_limiter: PeerBandwidthLimiter,
 ) {
-    let local_addr = stream.inner().local_addr().ok();
+    let local_addr = stream.inner().local_addr();
     let stream = match get_eciess_stream(stream, secret_key, direction).await {
         Ok(stream) => stream,
         Err(error) => {
@@ -913,8 +995,10 @@ async fn authenticate(
         direction,
         hello,
         status,
+        block_range,
         fork_filter,
         extra_handlers,
+        bandwidth_limiter,
     )
     .boxed();
 
@@ -958,21 +1042,23 @@ async fn get_eciess_stream<Io: AsyncRead + AsyncWrite + Unpin + HasRemoteAddr>(
 /// negotiate the additional protocols.
 #[allow(clippy::too_many_arguments)]
 async fn authenticate_stream(
-    stream: UnauthedP2PStream<ECIESStream<MeteredStream<TcpStream>>>,
+    stream: UnauthedP2PStream<ECIESStream<MeteredStream<BoxedTransportStream>>>,
     session_id: SessionId,
     remote_addr: SocketAddr,
     local_addr: Option<SocketAddr>,
     direction: Direction,
     mut hello: HelloMessageWithProtocols,
     mut status: Status,
+    block_range: BlockRangeUpdate,
     fork_filter: ForkFilter,
     mut extra_handlers: RlpxSubProtocolHandlers,
+    bandwidth_limiter: PeerBandwidthLimiter,
 ) -> PendingSessionEvent {
     // Add extra protocols to the hello message
     extra_handlers.retain(|handler| hello.try_add_protocol(handler.protocol()).is_ok());
 
     // conduct the p2p handshake and return the authenticated stream
-    let (p2p_stream, their_hello) = match stream.handshake(hello).await {
+    let (mut p2p_stream, their_hello) = match stream.handshake(hello).await {
         Ok(stream_res) => stream_res,
         Err(err) => {
             return PendingSessionEvent::Disconnected {
@@ -983,6 +1069,7 @@ async fn authenticate_stream(
             }
         }
     };
+    p2p_stream.set_bandwidth_limiter(bandwidth_limiter);
 
     // Ensure we negotiated mandatory eth protocol
     let eth_version = match p2p_stream.shared_capabilities().eth_version() {
@@ -997,11 +1084,16 @@ async fn authenticate_stream(
         }
     };
 
+    // Before trying status handshake, set up the version to negotiated shared version
+    status.set_eth_version(eth_version);
+    let status = if eth_version.is_eth69() {
+        StatusMessage::Eth69(status.into_eth69(block_range))
+    } else {
+        StatusMessage::Legacy(status)
+    };
+
     let (conn, their_status) = if p2p_stream.shared_capabilities().len() == 1 {
         // if the hello handshake was successful we can try status handshake
-        //
-        // Before trying status handshake, set up the version to negotiated shared version
-        status.set_eth_version(eth_version);
         let eth_unauthed = UnauthedEthStream::new(p2p_stream);
         let (eth_stream, their_status) = match eth_unauthed.handshake(status, fork_filter).await {
             Ok(stream_res) => stream_res,
@@ -1042,7 +1134,8 @@ async fn authenticate_stream(
         local_addr,
         peer_id: their_hello.id,
         capabilities: Arc::new(Capabilities::from(their_hello.capabilities)),
-        status: Arc::new(their_status),
+        // eth/69 peers don't announce their total difficulty, which is reported as zero
+        status: Arc::new(their_status.into_legacy()),
         conn,
         direction,
         client_id: their_hello.client_version,
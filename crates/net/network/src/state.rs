@@ -17,7 +17,7 @@ use rand::seq::SliceRandom;
 use reth_eth_wire::{
     capability::Capabilities, BlockHashNumber, DisconnectReason, NewBlockHashes, Status,
 };
-use reth_network_api::PeerKind;
+use reth_network_api::{PeerKind, PeerRequestStats};
 use reth_primitives::{ForkId, PeerId, B256};
 use reth_provider::BlockNumReader;
 use std::{
@@ -95,6 +95,11 @@ where
         &mut self.peers_manager
     }
 
+    /// Returns access to the [`Discovery`]
+    pub(crate) fn discovery(&self) -> &Discovery {
+        &self.discovery
+    }
+
     /// Returns mutable access to the [`Discovery`]
     pub(crate) fn discovery_mut(&mut self) -> &mut Discovery {
         &mut self.discovery
@@ -110,6 +115,11 @@ where
         self.state_fetcher.client()
     }
 
+    /// Returns the statistics on header and body requests served by the active peer.
+    pub(crate) fn peer_request_stats(&self, peer_id: &PeerId) -> Option<PeerRequestStats> {
+        self.state_fetcher.peer_stats(peer_id)
+    }
+
     /// How many peers we're currently connected to.
     pub fn num_active_peers(&self) -> usize {
         self.active_peers.len()
@@ -333,8 +343,12 @@ where
             PeerAction::PeerRemoved(peer_id) => {
                 self.queued_messages.push_back(StateAction::PeerRemoved(peer_id))
             }
-            PeerAction::BanPeer { .. } => {}
-            PeerAction::UnBanPeer { .. } => {}
+            PeerAction::BanPeer { peer_id } => {
+                self.queued_messages.push_back(StateAction::PeerBanned(peer_id))
+            }
+            PeerAction::UnBanPeer { peer_id } => {
+                self.queued_messages.push_back(StateAction::PeerUnbanned(peer_id))
+            }
         }
     }
 
@@ -524,6 +538,10 @@ pub(crate) enum StateAction {
     PeerAdded(PeerId),
     /// A peer was dropped
     PeerRemoved(PeerId),
+    /// A peer was banned
+    PeerBanned(PeerId),
+    /// A peer was unbanned
+    PeerUnbanned(PeerId),
 }
 
 #[cfg(test)]
@@ -247,6 +247,8 @@ where
             }
             StateAction::PeerAdded(peer_id) => return Some(SwarmEvent::PeerAdded(peer_id)),
             StateAction::PeerRemoved(peer_id) => return Some(SwarmEvent::PeerRemoved(peer_id)),
+            StateAction::PeerBanned(peer_id) => return Some(SwarmEvent::PeerBanned(peer_id)),
+            StateAction::PeerUnbanned(peer_id) => return Some(SwarmEvent::PeerUnbanned(peer_id)),
             StateAction::DiscoveredNode { peer_id, socket_addr, fork_id } => {
                 // Don't try to connect to peer if node is shutting down
                 if self.is_shutting_down() {
@@ -410,6 +412,10 @@ pub(crate) enum SwarmEvent {
     PeerAdded(PeerId),
     /// Admin rpc: peer removed
     PeerRemoved(PeerId),
+    /// Admin rpc: peer banned
+    PeerBanned(PeerId),
+    /// Admin rpc: peer unbanned
+    PeerUnbanned(PeerId),
     /// Closed an incoming pending session during authentication.
     IncomingPendingSessionClosed {
         remote_addr: SocketAddr,
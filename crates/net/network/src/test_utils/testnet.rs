@@ -7,6 +7,7 @@ use crate::{
     peers::PeersHandle,
     protocol::IntoRlpxSubProtocol,
     transactions::{TransactionsHandle, TransactionsManager, TransactionsManagerConfig},
+    transport::MemoryTransport,
     NetworkConfig, NetworkConfigBuilder, NetworkEvent, NetworkEvents, NetworkHandle,
     NetworkManager,
 };
@@ -174,6 +175,15 @@ where
 {
     /// Installs an eth pool on each peer
     pub fn with_eth_pool(self) -> Testnet<C, EthTransactionPool<C, InMemoryBlobStore>> {
+        self.with_eth_pool_config(TransactionsManagerConfig::default())
+    }
+
+    /// Installs an eth pool on each peer, with a transactions manager configured with the given
+    /// [`TransactionsManagerConfig`].
+    pub fn with_eth_pool_config(
+        self,
+        config: TransactionsManagerConfig,
+    ) -> Testnet<C, EthTransactionPool<C, InMemoryBlobStore>> {
         self.map_pool(|peer| {
             let blob_store = InMemoryBlobStore::default();
             let pool = TransactionValidationTaskExecutor::eth(
@@ -182,11 +192,10 @@ where
                 blob_store.clone(),
                 TokioTaskExecutor::default(),
             );
-            peer.map_transactions_manager(EthTransactionPool::eth_pool(
-                pool,
-                blob_store,
-                Default::default(),
-            ))
+            peer.map_transactions_manager_with_config(
+                EthTransactionPool::eth_pool(pool, blob_store, Default::default()),
+                config.clone(),
+            )
         })
     }
 }
@@ -412,18 +421,27 @@ where
 
     /// Set a new transactions manager that's connected to the peer's network
     pub fn map_transactions_manager<P>(self, pool: P) -> Peer<C, P>
+    where
+        P: TransactionPool,
+    {
+        self.map_transactions_manager_with_config(pool, TransactionsManagerConfig::default())
+    }
+
+    /// Set a new transactions manager with the given [`TransactionsManagerConfig`] that's
+    /// connected to the peer's network
+    pub fn map_transactions_manager_with_config<P>(
+        self,
+        pool: P,
+        config: TransactionsManagerConfig,
+    ) -> Peer<C, P>
     where
         P: TransactionPool,
     {
         let Self { mut network, request_handler, client, secret_key, .. } = self;
         let (tx, rx) = unbounded_channel();
         network.set_transactions(tx);
-        let transactions_manager = TransactionsManager::new(
-            network.handle().clone(),
-            pool.clone(),
-            rx,
-            TransactionsManagerConfig::default(),
-        );
+        let transactions_manager =
+            TransactionsManager::new(network.handle().clone(), pool.clone(), rx, config);
         Peer {
             network,
             request_handler,
@@ -570,8 +588,10 @@ where
         Self { config, client, secret_key }
     }
 
+    /// Peers are connected over the process wide [`MemoryTransport`], so no RLPx ports are bound.
     fn network_config_builder(secret_key: SecretKey) -> NetworkConfigBuilder {
         NetworkConfigBuilder::new(secret_key)
+            .transport(MemoryTransport::global())
             .listener_addr(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)))
             .discovery_addr(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0)))
             .disable_dns_discovery()
@@ -3,17 +3,140 @@ use super::{
     SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
 };
 use derive_more::Constructor;
+use reth_network_api::PeerKind;
+use std::{fmt, str::FromStr, time::Duration};
 
 /// Configuration for managing transactions within the network.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct TransactionsManagerConfig {
     /// Configuration for fetching transactions.
     pub transaction_fetcher_config: TransactionFetcherConfig,
+    /// Which peers new __pending__ transactions are propagated to.
+    pub propagation_mode: TransactionPropagationMode,
+    /// If set, transactions are only ever announced as hashes, even to the fraction of peers
+    /// that would otherwise receive full transactions.
+    pub announce_only: bool,
+    /// Delay before new __pending__ transactions are propagated.
+    ///
+    /// Zero propagates immediately.
+    #[cfg_attr(feature = "serde", serde(with = "humantime_serde"))]
+    pub propagation_delay: Duration,
+    /// Which peers incoming transaction gossip is accepted from.
+    pub ingress_policy: TransactionIngressPolicy,
 }
 
+/// Determines which peers transactions are propagated to.
+///
+/// This only applies to automatic propagation of new pending transactions and to the pool
+/// announcement sent on session establishment, transactions explicitly requested by a peer are
+/// still served.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum TransactionPropagationMode {
+    /// Propagate to all connected peers.
+    #[default]
+    All,
+    /// Only propagate to trusted peers.
+    Trusted,
+    /// Never propagate transactions.
+    Disabled,
+}
+
+impl TransactionPropagationMode {
+    /// Returns `true` if transactions may be propagated to a peer of the given kind.
+    pub const fn allows(&self, kind: PeerKind) -> bool {
+        match self {
+            Self::All => true,
+            Self::Trusted => kind.is_trusted(),
+            Self::Disabled => false,
+        }
+    }
+
+    /// Returns `true` if propagation is disabled entirely.
+    pub const fn is_disabled(&self) -> bool {
+        matches!(self, Self::Disabled)
+    }
+}
+
+impl fmt::Display for TransactionPropagationMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::Trusted => f.write_str("trusted"),
+            Self::Disabled => f.write_str("disabled"),
+        }
+    }
+}
+
+impl FromStr for TransactionPropagationMode {
+    type Err = ParseGossipPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "trusted" => Ok(Self::Trusted),
+            "disabled" => Ok(Self::Disabled),
+            s => Err(ParseGossipPolicyError(s.to_string())),
+        }
+    }
+}
+
+/// Determines which peers incoming transaction gossip is accepted from.
+///
+/// Gossip from peers that aren't accepted, `Transactions` broadcasts and
+/// `NewPooledTransactionHashes` announcements, is silently dropped.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
+pub enum TransactionIngressPolicy {
+    /// Accept gossip from all connected peers.
+    #[default]
+    All,
+    /// Only accept gossip from trusted peers.
+    Trusted,
+}
+
+impl TransactionIngressPolicy {
+    /// Returns `true` if gossip from a peer of the given kind is accepted.
+    pub const fn allows(&self, kind: PeerKind) -> bool {
+        match self {
+            Self::All => true,
+            Self::Trusted => kind.is_trusted(),
+        }
+    }
+}
+
+impl fmt::Display for TransactionIngressPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::All => f.write_str("all"),
+            Self::Trusted => f.write_str("trusted"),
+        }
+    }
+}
+
+impl FromStr for TransactionIngressPolicy {
+    type Err = ParseGossipPolicyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "all" => Ok(Self::All),
+            "trusted" => Ok(Self::Trusted),
+            s => Err(ParseGossipPolicyError(s.to_string())),
+        }
+    }
+}
+
+/// Error when parsing a [TransactionPropagationMode] or [TransactionIngressPolicy].
+#[derive(Debug, thiserror::Error)]
+#[error("unknown transaction gossip policy: {0}")]
+pub struct ParseGossipPolicyError(String);
+
 /// Configuration for fetching transactions.
-#[derive(Debug, Constructor, Clone)]
+#[derive(Debug, Constructor, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TransactionFetcherConfig {
     /// Soft limit for the byte size of a
@@ -25,7 +25,7 @@ use reth_interfaces::{
     sync::SyncStateProvider,
 };
 use reth_metrics::common::mpsc::UnboundedMeteredReceiver;
-use reth_network_api::{Peers, ReputationChangeKind};
+use reth_network_api::{PeerKind, Peers, ReputationChangeKind};
 use reth_primitives::{
     FromRecoveredPooledTransaction, PeerId, PooledTransactionsElement, TransactionSigned, TxHash,
     B256,
@@ -57,7 +57,10 @@ pub mod constants;
 /// Component responsible for fetching transactions from [`NewPooledTransactionHashes`].
 pub mod fetcher;
 pub mod validation;
-pub use config::{TransactionFetcherConfig, TransactionsManagerConfig};
+pub use config::{
+    ParseGossipPolicyError, TransactionFetcherConfig, TransactionIngressPolicy,
+    TransactionPropagationMode, TransactionsManagerConfig,
+};
 
 use constants::SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE;
 pub(crate) use fetcher::{FetchEvent, TransactionFetcher};
@@ -74,6 +77,10 @@ use self::constants::{tx_manager::*, DEFAULT_SOFT_LIMIT_BYTE_SIZE_TRANSACTIONS_B
 /// Resolves with the result of each transaction import.
 pub type PoolImportFuture = Pin<Box<dyn Future<Output = Vec<PoolResult<TxHash>>> + Send + 'static>>;
 
+/// The future that resolves with the hashes of new __pending__ transactions once the configured
+/// [`propagation_delay`](TransactionsManagerConfig::propagation_delay) has elapsed.
+type DelayedPropagationFuture = Pin<Box<dyn Future<Output = Vec<TxHash>> + Send + 'static>>;
+
 /// Api to interact with [TransactionsManager] task.
 ///
 /// This can be obtained via [TransactionsManager::handle] and can be used to manually interact with
@@ -244,6 +251,11 @@ pub struct TransactionsManager<Pool> {
     pending_transactions: ReceiverStream<TxHash>,
     /// Incoming events from the [`NetworkManager`](crate::NetworkManager).
     transaction_events: UnboundedMeteredReceiver<NetworkTransactionEvent>,
+    /// New __pending__ transactions that are held back until the configured propagation delay
+    /// has elapsed.
+    delayed_propagations: FuturesUnordered<DelayedPropagationFuture>,
+    /// Propagation and ingress policies.
+    config: TransactionsManagerConfig,
     /// TransactionsManager metrics
     metrics: TransactionsManagerMetrics,
 }
@@ -296,6 +308,8 @@ impl<Pool: TransactionPool> TransactionsManager<Pool> {
                 from_network,
                 NETWORK_POOL_TRANSACTIONS_SCOPE,
             ),
+            delayed_propagations: Default::default(),
+            config: transactions_manager_config,
             metrics,
         }
     }
@@ -390,7 +404,25 @@ where
         if self.network.tx_gossip_disabled() {
             return
         }
+        if self.config.propagation_mode.is_disabled() {
+            return
+        }
 
+        let delay = self.config.propagation_delay;
+        if !delay.is_zero() {
+            trace!(target: "net::tx", num_hashes=?hashes.len(), ?delay, "Delaying propagation of transactions");
+            self.delayed_propagations.push(Box::pin(async move {
+                tokio::time::sleep(delay).await;
+                hashes
+            }));
+            return
+        }
+
+        self.propagate_pending_transactions(hashes)
+    }
+
+    /// Propagates the given __pending__ transactions to connected peers.
+    fn propagate_pending_transactions(&mut self, hashes: Vec<TxHash>) {
         trace!(target: "net::tx", num_hashes=?hashes.len(), "Start propagating transactions");
 
         // This fetches all transaction from the pool, including the 4844 blob transactions but
@@ -424,6 +456,10 @@ where
 
         // Note: Assuming ~random~ order due to random state of the peers map hasher
         for (peer_idx, (peer_id, peer)) in self.peers.iter_mut().enumerate() {
+            if !self.config.propagation_mode.allows(peer.peer_kind) {
+                continue
+            }
+
             // filter all transactions unknown to the peer
             let mut hashes = PooledTransactionsHashesBuilder::new(peer.version);
             let mut full_transactions = FullTransactionsBuilder::default();
@@ -453,7 +489,10 @@ where
             if !new_pooled_hashes.is_empty() {
                 // determine whether to send full tx objects or hashes. If there are no full
                 // transactions, try to send hashes.
-                if peer_idx > max_num_full || full_transactions.is_empty() {
+                if self.config.announce_only ||
+                    peer_idx > max_num_full ||
+                    full_transactions.is_empty()
+                {
                     // enforce tx soft limit per message for the (unlikely) event the number of
                     // hashes exceeds it
                     new_pooled_hashes.truncate(
@@ -810,10 +849,25 @@ where
         }
     }
 
+    /// Returns `true` if incoming gossip from the given peer is accepted by the configured
+    /// [`TransactionIngressPolicy`].
+    ///
+    /// Gossip from peers without an active session is handled by the respective handlers.
+    fn accepts_gossip_from(&self, peer_id: &PeerId) -> bool {
+        self.peers
+            .get(peer_id)
+            .map_or(true, |peer| self.config.ingress_policy.allows(peer.peer_kind))
+    }
+
     /// Handles dedicated transaction events related to the `eth` protocol.
     fn on_network_tx_event(&mut self, event: NetworkTransactionEvent) {
         match event {
             NetworkTransactionEvent::IncomingTransactions { peer_id, msg } => {
+                if !self.accepts_gossip_from(&peer_id) {
+                    trace!(target: "net::tx", ?peer_id, "ignoring transactions from untrusted peer");
+                    return
+                }
+
                 // ensure we didn't receive any blob transactions as these are disallowed to be
                 // broadcasted in full
 
@@ -834,6 +888,10 @@ where
                 }
             }
             NetworkTransactionEvent::IncomingPooledTransactionHashes { peer_id, msg } => {
+                if !self.accepts_gossip_from(&peer_id) {
+                    trace!(target: "net::tx", ?peer_id, "ignoring announcement from untrusted peer");
+                    return
+                }
                 self.on_new_pooled_transaction_hashes(peer_id, msg)
             }
             NetworkTransactionEvent::GetPooledTransactions { peer_id, request, response } => {
@@ -890,10 +948,15 @@ where
                 self.peers.remove(&peer_id);
             }
             NetworkEvent::SessionEstablished {
-                peer_id, client_version, messages, version, ..
+                peer_id,
+                client_version,
+                messages,
+                version,
+                peer_kind,
+                ..
             } => {
                 // Insert a new peer into the peerset.
-                let peer = PeerMetadata::new(messages, version, client_version);
+                let peer = PeerMetadata::new(messages, version, client_version, peer_kind);
                 let peer = match self.peers.entry(peer_id) {
                     Entry::Occupied(mut entry) => {
                         entry.insert(peer);
@@ -908,6 +971,9 @@ where
                 if self.network.is_initially_syncing() || self.network.tx_gossip_disabled() {
                     return
                 }
+                if !self.config.propagation_mode.allows(peer_kind) {
+                    return
+                }
 
                 let pooled_txs = self.pool.pooled_transactions_max(
                     SOFT_LIMIT_COUNT_HASHES_IN_NEW_POOLED_TRANSACTIONS_BROADCAST_MESSAGE,
@@ -1346,6 +1412,12 @@ where
             |cmd| this.on_command(cmd)
         );
 
+        // Propagate transactions whose propagation delay has elapsed. This is polled last so
+        // that delays queued by the streams above are registered with the waker.
+        while let Poll::Ready(Some(hashes)) = this.delayed_propagations.poll_next_unpin(cx) {
+            this.propagate_pending_transactions(hashes);
+        }
+
         this.transaction_fetcher.update_metrics();
 
         // all channels are fully drained and import futures pending
@@ -1466,7 +1538,9 @@ impl PooledTransactionsHashesBuilder {
             EthVersion::Eth66 | EthVersion::Eth67 => {
                 PooledTransactionsHashesBuilder::Eth66(Default::default())
             }
-            EthVersion::Eth68 => PooledTransactionsHashesBuilder::Eth68(Default::default()),
+            EthVersion::Eth68 | EthVersion::Eth69 => {
+                PooledTransactionsHashesBuilder::Eth68(Default::default())
+            }
         }
     }
 
@@ -1508,11 +1582,18 @@ pub struct PeerMetadata {
     version: EthVersion,
     /// The peer's client version.
     client_version: Arc<str>,
+    /// The kind of peer, used to apply the configured propagation and ingress policies.
+    peer_kind: PeerKind,
 }
 
 impl PeerMetadata {
     /// Returns a new instance of [`PeerMetadata`].
-    fn new(request_tx: PeerRequestSender, version: EthVersion, client_version: Arc<str>) -> Self {
+    fn new(
+        request_tx: PeerRequestSender,
+        version: EthVersion,
+        client_version: Arc<str>,
+        peer_kind: PeerKind,
+    ) -> Self {
         Self {
             seen_transactions: LruCache::new(
                 NonZeroUsize::new(DEFAULT_CAPACITY_CACHE_SEEN_BY_PEER).unwrap(),
@@ -1520,6 +1601,7 @@ impl PeerMetadata {
             request_tx,
             version,
             client_version,
+            peer_kind,
         }
     }
 }
@@ -1673,11 +1755,87 @@ mod tests {
                 PeerRequestSender::new(peer_id, to_mock_session_tx),
                 version,
                 Arc::from(""),
+                PeerKind::default(),
             ),
             to_mock_session_rx,
         )
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_propagation_mode() {
+        reth_tracing::init_test_tracing();
+
+        let secret_key = SecretKey::new(&mut rand::thread_rng());
+        let config = NetworkConfigBuilder::new(secret_key)
+            .listener_port(0)
+            .disable_discovery()
+            .build(NoopProvider::default());
+        let pool = testing_pool();
+        let (_network_handle, _network, mut tx_manager, _) = NetworkManager::new(config)
+            .await
+            .unwrap()
+            .into_builder()
+            .transactions(pool.clone(), TransactionsManagerConfig::default())
+            .split_with_handle();
+
+        // peer_1 is trusted, peer_2 is not
+        let peer_id_1 = PeerId::new([1; 64]);
+        let peer_id_2 = PeerId::new([2; 64]);
+        let (mut peer_1, _to_mock_session_rx_1) = new_mock_session(peer_id_1, EthVersion::Eth68);
+        peer_1.peer_kind = PeerKind::Trusted;
+        let (peer_2, _to_mock_session_rx_2) = new_mock_session(peer_id_2, EthVersion::Eth68);
+        tx_manager.peers.insert(peer_id_1, peer_1);
+        tx_manager.peers.insert(peer_id_2, peer_2);
+
+        for (mode, expected) in [
+            (TransactionPropagationMode::All, [true, true]),
+            (TransactionPropagationMode::Trusted, [true, false]),
+            (TransactionPropagationMode::Disabled, [false, false]),
+        ] {
+            let tx = MockTransaction::eip1559();
+            let hash = *tx.hash();
+            pool.add_external_transaction(tx).await.unwrap();
+
+            tx_manager.config.propagation_mode = mode;
+            tx_manager.on_new_pending_transactions(vec![hash]);
+            let propagated = [peer_id_1, peer_id_2]
+                .map(|peer_id| tx_manager.peers[&peer_id].seen_transactions.contains(&hash));
+            assert_eq!(propagated, expected, "{mode:?}");
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ingress_policy() {
+        reth_tracing::init_test_tracing();
+
+        let mut tx_manager = new_tx_manager().await;
+        tx_manager.config.ingress_policy = TransactionIngressPolicy::Trusted;
+
+        let peer_id = PeerId::new([1; 64]);
+        let (peer, mut to_mock_session_rx) = new_mock_session(peer_id, EthVersion::Eth66);
+        tx_manager.peers.insert(peer_id, peer);
+
+        let hash = B256::from_slice(&[1; 32]);
+        let announce = || NetworkTransactionEvent::IncomingPooledTransactionHashes {
+            peer_id,
+            msg: NewPooledTransactionHashes::Eth66(NewPooledTransactionHashes66(vec![hash])),
+        };
+
+        // the announcement of the untrusted peer is dropped
+        tx_manager.on_network_tx_event(announce());
+        assert!(tx_manager.transaction_fetcher.hashes_fetch_inflight_and_pending_fetch.is_empty());
+        assert!(to_mock_session_rx.try_recv().is_err());
+
+        // the announced transaction is requested once the peer is trusted
+        tx_manager.peers.get_mut(&peer_id).unwrap().peer_kind = PeerKind::Trusted;
+        tx_manager.on_network_tx_event(announce());
+        let Ok(PeerRequest::GetPooledTransactions { request, .. }) = to_mock_session_rx.try_recv()
+        else {
+            panic!("the announced transaction should be requested")
+        };
+        assert_eq!(request.0, vec![hash]);
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_ignored_tx_broadcasts_while_initially_syncing() {
         reth_tracing::init_test_tracing();
@@ -1727,6 +1885,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -1737,6 +1896,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -1813,6 +1973,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -1823,6 +1984,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -1897,6 +2059,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => {
                     // to insert a new peer in transactions peerset
                     transactions.on_network_event(NetworkEvent::SessionEstablished {
@@ -1907,6 +2070,7 @@ mod tests {
                         messages,
                         status,
                         version,
+                        peer_kind,
                     })
                 }
                 NetworkEvent::PeerAdded(_peer_id) => continue,
@@ -1988,6 +2152,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 } => transactions.on_network_event(NetworkEvent::SessionEstablished {
                     peer_id,
                     remote_addr,
@@ -1996,6 +2161,7 @@ mod tests {
                     messages,
                     status,
                     version,
+                    peer_kind,
                 }),
                 NetworkEvent::PeerAdded(_peer_id) => continue,
                 ev => {
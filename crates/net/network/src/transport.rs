@@ -0,0 +1,442 @@
+//! Pluggable transports for RLPx sessions.
+//!
+//! The [`SessionManager`](crate::SessionManager) and the connection listener don't depend on TCP
+//! directly, but dial and accept connections through a [`Transport`]. Any stream that implements
+//! [`TransportStream`] can carry an [`ECIESStream`](reth_ecies::stream::ECIESStream).
+//!
+//! Two transports are provided:
+//!  - [`TcpTransport`]: plain TCP, the default.
+//!  - [`MemoryTransport`]: in-process duplex streams, useful for tests that shouldn't bind any
+//!    ports.
+
+use futures::{future::BoxFuture, FutureExt};
+use parking_lot::Mutex;
+use reth_net_common::stream::HasRemoteAddr;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, io,
+    net::{Ipv4Addr, SocketAddr},
+    pin::Pin,
+    sync::{Arc, OnceLock, Weak},
+    task::{Context, Poll},
+};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf},
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, mpsc::error::SendError},
+};
+
+/// A connection to a remote peer, established by a [`Transport`].
+pub trait TransportStream:
+    AsyncRead + AsyncWrite + HasRemoteAddr + Unpin + Send + Sync + fmt::Debug + 'static
+{
+    /// Returns the local address of the connection, if known.
+    fn local_addr(&self) -> Option<SocketAddr>;
+}
+
+/// A type erased [`TransportStream`].
+pub type BoxedTransportStream = Box<dyn TransportStream>;
+
+impl HasRemoteAddr for BoxedTransportStream {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        (**self).remote_addr()
+    }
+}
+
+impl TransportStream for TcpStream {
+    fn local_addr(&self) -> Option<SocketAddr> {
+        TcpStream::local_addr(self).ok()
+    }
+}
+
+/// Accepts incoming connections of a [`Transport`].
+pub trait TransportListener: Send + Sync + fmt::Debug + 'static {
+    /// Polls for the next incoming connection and the address of the remote peer.
+    fn poll_accept(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<(BoxedTransportStream, SocketAddr)>>;
+
+    /// Returns the address the listener is bound to.
+    fn local_addr(&self) -> io::Result<SocketAddr>;
+}
+
+/// Establishes connections to peers and listens for incoming connections.
+pub trait Transport: Send + Sync + fmt::Debug + 'static {
+    /// Opens a connection to the given address.
+    fn dial(&self, addr: SocketAddr) -> BoxFuture<'static, io::Result<BoxedTransportStream>>;
+
+    /// Starts listening for incoming connections on the given address.
+    fn listen(
+        &self,
+        addr: SocketAddr,
+    ) -> BoxFuture<'static, io::Result<Box<dyn TransportListener>>>;
+}
+
+impl<T: Transport + ?Sized> Transport for Arc<T> {
+    fn dial(&self, addr: SocketAddr) -> BoxFuture<'static, io::Result<BoxedTransportStream>> {
+        (**self).dial(addr)
+    }
+
+    fn listen(
+        &self,
+        addr: SocketAddr,
+    ) -> BoxFuture<'static, io::Result<Box<dyn TransportListener>>> {
+        (**self).listen(addr)
+    }
+}
+
+/// Plain TCP [`Transport`].
+///
+/// `TCP_NODELAY` is set on all connections.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct TcpTransport;
+
+impl TcpTransport {
+    fn set_nodelay(stream: &TcpStream) {
+        if let Err(err) = stream.set_nodelay(true) {
+            tracing::warn!(target: "net", "set nodelay failed: {:?}", err);
+        }
+    }
+}
+
+impl Transport for TcpTransport {
+    fn dial(&self, addr: SocketAddr) -> BoxFuture<'static, io::Result<BoxedTransportStream>> {
+        async move {
+            let stream = TcpStream::connect(addr).await?;
+            Self::set_nodelay(&stream);
+            Ok(Box::new(stream) as BoxedTransportStream)
+        }
+        .boxed()
+    }
+
+    fn listen(
+        &self,
+        addr: SocketAddr,
+    ) -> BoxFuture<'static, io::Result<Box<dyn TransportListener>>> {
+        async move {
+            let listener = TcpListener::bind(addr).await?;
+            Ok(Box::new(TcpTransportListener(listener)) as Box<dyn TransportListener>)
+        }
+        .boxed()
+    }
+}
+
+/// [`TransportListener`] of the [`TcpTransport`].
+#[derive(Debug)]
+struct TcpTransportListener(TcpListener);
+
+impl TransportListener for TcpTransportListener {
+    fn poll_accept(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<(BoxedTransportStream, SocketAddr)>> {
+        self.0.poll_accept(cx).map_ok(|(stream, remote_addr)| {
+            TcpTransport::set_nodelay(&stream);
+            (Box::new(stream) as BoxedTransportStream, remote_addr)
+        })
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.local_addr()
+    }
+}
+
+/// The capacity of the buffer in each direction of a [`MemoryStream`].
+const MEMORY_STREAM_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// In-process [`Transport`] that connects peers over [`tokio::io::duplex`] streams.
+///
+/// Listeners and dialers only find each other if they use clones of the same instance. Ports
+/// identify listeners within that instance, the IP of an address is ignored. Binding to port `0`
+/// assigns an unused port, no actual socket is opened. Like with TCP, the local end of a dialed
+/// connection occupies a port of its own until the connection is closed.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryTransport {
+    inner: Arc<Mutex<MemoryNetwork>>,
+}
+
+impl MemoryTransport {
+    /// Creates a new transport, isolated from all other [`MemoryTransport`]s.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the process wide [`MemoryTransport`].
+    pub fn global() -> Self {
+        static GLOBAL: OnceLock<MemoryTransport> = OnceLock::new();
+        GLOBAL.get_or_init(Self::new).clone()
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn dial(&self, addr: SocketAddr) -> BoxFuture<'static, io::Result<BoxedTransportStream>> {
+        let res = self.inner.lock().connect(addr, &self.inner);
+        async move { res.map(|stream| Box::new(stream) as BoxedTransportStream) }.boxed()
+    }
+
+    fn listen(
+        &self,
+        addr: SocketAddr,
+    ) -> BoxFuture<'static, io::Result<Box<dyn TransportListener>>> {
+        let res = self.inner.lock().bind(addr).map(|(local_addr, incoming)| {
+            Box::new(MemoryListener { local_addr, incoming, network: self.inner.clone() })
+                as Box<dyn TransportListener>
+        });
+        async move { res }.boxed()
+    }
+}
+
+/// The listeners and open connections of a [`MemoryTransport`].
+#[derive(Debug, Default)]
+struct MemoryNetwork {
+    /// The last assigned port.
+    last_port: u16,
+    /// Listeners by port.
+    listeners: HashMap<u16, mpsc::UnboundedSender<MemoryStream>>,
+    /// Ports of the dialing ends of open connections.
+    connections: HashSet<u16>,
+}
+
+impl MemoryNetwork {
+    /// Returns `true` if the port is used by a listener or the dialing end of a connection.
+    fn is_in_use(&self, port: u16) -> bool {
+        self.listeners.contains_key(&port) || self.connections.contains(&port)
+    }
+
+    /// Returns a port that's not in use by any listener or connection.
+    fn next_port(&mut self) -> io::Result<u16> {
+        for _ in 0..u16::MAX {
+            self.last_port = self.last_port.checked_add(1).unwrap_or(1);
+            if !self.is_in_use(self.last_port) {
+                return Ok(self.last_port)
+            }
+        }
+        Err(io::Error::new(io::ErrorKind::AddrInUse, "no free memory port"))
+    }
+
+    fn bind(
+        &mut self,
+        mut addr: SocketAddr,
+    ) -> io::Result<(SocketAddr, mpsc::UnboundedReceiver<MemoryStream>)> {
+        if addr.port() == 0 {
+            addr.set_port(self.next_port()?);
+        } else if self.is_in_use(addr.port()) {
+            return Err(io::Error::new(io::ErrorKind::AddrInUse, "memory port in use"))
+        }
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.listeners.insert(addr.port(), tx);
+        Ok((addr, rx))
+    }
+
+    /// Connects to the listener at the address.
+    ///
+    /// The dialer's end is identified by a port of its own, which is reserved until both ends of
+    /// the connection are dropped.
+    fn connect(
+        &mut self,
+        remote_addr: SocketAddr,
+        network: &Arc<Mutex<Self>>,
+    ) -> io::Result<MemoryStream> {
+        let listener = self
+            .listeners
+            .get(&remote_addr.port())
+            .ok_or_else(|| io::Error::from(io::ErrorKind::ConnectionRefused))?
+            .clone();
+
+        let port = self.next_port()?;
+        let local_addr = SocketAddr::new(Ipv4Addr::LOCALHOST.into(), port);
+        let (local, remote) = tokio::io::duplex(MEMORY_STREAM_BUFFER_SIZE);
+        self.connections.insert(port);
+        let reservation = Arc::new(PortReservation { port, network: Arc::downgrade(network) });
+
+        let incoming = MemoryStream {
+            inner: remote,
+            local_addr: remote_addr,
+            remote_addr: local_addr,
+            _port: Arc::clone(&reservation),
+        };
+        if let Err(SendError(incoming)) = listener.send(incoming) {
+            // release the port right away, the network is locked already
+            drop(incoming);
+            if let Ok(mut reservation) = Arc::try_unwrap(reservation) {
+                reservation.network = Weak::new();
+            }
+            self.connections.remove(&port);
+            return Err(io::ErrorKind::ConnectionRefused.into())
+        }
+
+        Ok(MemoryStream { inner: local, local_addr, remote_addr, _port: reservation })
+    }
+}
+
+/// Releases the port of the dialing end of a connection once dropped.
+#[derive(Debug)]
+struct PortReservation {
+    port: u16,
+    network: Weak<Mutex<MemoryNetwork>>,
+}
+
+impl Drop for PortReservation {
+    fn drop(&mut self) {
+        if let Some(network) = self.network.upgrade() {
+            network.lock().connections.remove(&self.port);
+        }
+    }
+}
+
+/// [`TransportListener`] of the [`MemoryTransport`].
+#[derive(Debug)]
+struct MemoryListener {
+    local_addr: SocketAddr,
+    incoming: mpsc::UnboundedReceiver<MemoryStream>,
+    network: Arc<Mutex<MemoryNetwork>>,
+}
+
+impl TransportListener for MemoryListener {
+    fn poll_accept(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<io::Result<(BoxedTransportStream, SocketAddr)>> {
+        match self.incoming.poll_recv(cx) {
+            Poll::Ready(Some(stream)) => {
+                let remote_addr = stream.remote_addr;
+                Poll::Ready(Ok((Box::new(stream) as BoxedTransportStream, remote_addr)))
+            }
+            Poll::Ready(None) => Poll::Ready(Err(io::ErrorKind::BrokenPipe.into())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<SocketAddr> {
+        Ok(self.local_addr)
+    }
+}
+
+impl Drop for MemoryListener {
+    fn drop(&mut self) {
+        self.network.lock().listeners.remove(&self.local_addr.port());
+    }
+}
+
+/// One end of an in-memory connection established by a [`MemoryTransport`].
+#[derive(Debug)]
+pub struct MemoryStream {
+    inner: DuplexStream,
+    local_addr: SocketAddr,
+    remote_addr: SocketAddr,
+    /// The reserved port of the dialing end, shared by both ends.
+    _port: Arc<PortReservation>,
+}
+
+impl HasRemoteAddr for MemoryStream {
+    fn remote_addr(&self) -> Option<SocketAddr> {
+        Some(self.remote_addr)
+    }
+}
+
+impl TransportStream for MemoryStream {
+    fn local_addr(&self) -> Option<SocketAddr> {
+        Some(self.local_addr)
+    }
+}
+
+impl AsyncRead for MemoryStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for MemoryStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.get_mut().inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.get_mut().inner).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::poll_fn;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn memory_transport_roundtrip() {
+        let transport = MemoryTransport::new();
+        let mut listener =
+            transport.listen(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0)).await.unwrap();
+        let listener_addr = listener.local_addr().unwrap();
+        assert_ne!(listener_addr.port(), 0);
+
+        let mut outgoing = transport.dial(listener_addr).await.unwrap();
+        let (mut incoming, remote_addr) = poll_fn(|cx| listener.poll_accept(cx)).await.unwrap();
+
+        assert_eq!(outgoing.local_addr(), Some(remote_addr));
+        assert_eq!(incoming.remote_addr(), Some(remote_addr));
+        assert_eq!(outgoing.remote_addr(), Some(listener_addr));
+
+        outgoing.write_all(b"ping").await.unwrap();
+        let mut buf = [0u8; 4];
+        incoming.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+    }
+
+    #[tokio::test]
+    async fn memory_transport_isolated() {
+        let transport = MemoryTransport::new();
+        let listener =
+            transport.listen(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0)).await.unwrap();
+        let listener_addr = listener.local_addr().unwrap();
+
+        // a separate instance doesn't see the listener
+        let err = MemoryTransport::new().dial(listener_addr).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+
+        // the port is released once the listener is dropped
+        drop(listener);
+        let err = transport.dial(listener_addr).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::ConnectionRefused);
+    }
+
+    #[tokio::test]
+    async fn memory_transport_reserves_dialer_ports() {
+        let transport = MemoryTransport::new();
+        let mut listener =
+            transport.listen(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0)).await.unwrap();
+        let outgoing = transport.dial(listener.local_addr().unwrap()).await.unwrap();
+        let (incoming, dialer_addr) = poll_fn(|cx| listener.poll_accept(cx)).await.unwrap();
+
+        // the port of the dialer can't be bound while the connection is open
+        let err = transport.listen(dialer_addr).await.unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::AddrInUse);
+
+        // and is not assigned to another listener or connection
+        let other =
+            transport.listen(SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0)).await.unwrap();
+        assert_ne!(other.local_addr().unwrap(), dialer_addr);
+        let second = transport.dial(listener.local_addr().unwrap()).await.unwrap();
+        assert_ne!(second.local_addr(), Some(dialer_addr));
+
+        // the port is released once both ends are closed
+        drop(outgoing);
+        assert!(transport.listen(dialer_addr).await.is_err());
+        drop(incoming);
+        assert!(transport.listen(dialer_addr).await.is_ok());
+    }
+}
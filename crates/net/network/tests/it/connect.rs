@@ -12,9 +12,10 @@ use reth_interfaces::{
 use reth_net_common::ban_list::BanList;
 use reth_network::{
     test_utils::{enr_to_peer_id, NetworkEventStream, PeerConfig, Testnet, GETH_TIMEOUT},
-    NetworkConfigBuilder, NetworkEvent, NetworkEvents, NetworkManager, PeersConfig,
+    MemoryTransport, NetworkConfigBuilder, NetworkEvent, NetworkEvents, NetworkManager,
+    PeersConfig,
 };
-use reth_network_api::{NetworkInfo, Peers, PeersInfo};
+use reth_network_api::{NetworkInfo, PeerEvent, PeerKind, Peers, PeersInfo, ReputationChangeKind};
 use reth_primitives::{mainnet_nodes, HeadersDirection, NodeRecord};
 use reth_provider::test_utils::NoopProvider;
 use reth_transaction_pool::test_utils::testing_pool;
@@ -82,6 +83,56 @@ async fn test_establish_connections() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_peer_events() {
+    reth_tracing::init_test_tracing();
+    let net = Testnet::create(2).await;
+
+    let mut handles = net.handles();
+    let handle0 = handles.next().unwrap();
+    let handle1 = handles.next().unwrap();
+    drop(handles);
+    let _handle = net.spawn();
+
+    let mut events = handle0.peer_events();
+    let peer_id = *handle1.peer_id();
+
+    handle0.add_peer(peer_id, handle1.local_addr());
+    assert_eq!(events.recv().await, Some(PeerEvent::PeerAdded(peer_id)));
+    let event = events.recv().await.unwrap();
+    assert!(matches!(event, PeerEvent::SessionEstablished { .. }));
+    assert_eq!(*event.peer_id(), peer_id);
+
+    // a misbehaving peer is banned and its session is dropped
+    handle0.reputation_change(peer_id, ReputationChangeKind::BadProtocol);
+    let mut banned = false;
+    let mut closed = false;
+    while !(banned && closed) {
+        match events.recv().await.unwrap() {
+            PeerEvent::PeerBanned(id) if id == peer_id => banned = true,
+            PeerEvent::SessionClosed { peer_id: id, .. } if id == peer_id => closed = true,
+            event => panic!("unexpected event {event:?}"),
+        }
+    }
+
+    // restoring the reputation lifts the ban
+    handle0.reputation_change(peer_id, ReputationChangeKind::Other(i32::MAX));
+    assert_eq!(events.recv().await, Some(PeerEvent::PeerUnbanned(peer_id)));
+
+    handle0.remove_peer(peer_id, PeerKind::Basic);
+    loop {
+        match events.recv().await.unwrap() {
+            PeerEvent::PeerRemoved(id) => {
+                assert_eq!(id, peer_id);
+                break
+            }
+            // the peers may have reconnected after the ban was lifted
+            PeerEvent::SessionEstablished { .. } | PeerEvent::SessionClosed { .. } => {}
+            event => panic!("unexpected event {event:?}"),
+        }
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_already_connected() {
     reth_tracing::init_test_tracing();
@@ -578,6 +629,7 @@ async fn test_disconnect_incoming_when_exceeded_incoming_connections() {
         .listener_port(0)
         .disable_discovery()
         .peer_config(peers_config)
+        .transport(MemoryTransport::global())
         .build(NoopProvider::default());
 
     let network = NetworkManager::new(config).await.unwrap();
@@ -690,6 +742,7 @@ async fn new_random_peer(
         .listener_port(0)
         .disable_discovery()
         .peer_config(peers_config)
+        .transport(MemoryTransport::global())
         .build(NoopProvider::default());
 
     NetworkManager::new(config).await.unwrap()
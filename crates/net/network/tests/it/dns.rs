@@ -0,0 +1,82 @@
+//! Tests for publishing the active peers as DNS discovery tree
+
+use futures::StreamExt;
+use reth_dns_discovery::{
+    DnsDiscoveryConfig, DnsDiscoveryService, DnsTreePublisherConfig, MapResolver,
+};
+use reth_network::{
+    test_utils::NetworkEventStream, NetworkConfigBuilder, NetworkEvents, NetworkManager,
+};
+use reth_network_api::NetworkInfo;
+use reth_provider::test_utils::NoopProvider;
+use secp256k1::SecretKey;
+use std::{collections::HashSet, net::Ipv4Addr, sync::Arc, time::Duration};
+
+fn local_network_config(secret_key: SecretKey) -> NetworkConfigBuilder {
+    NetworkConfigBuilder::new(secret_key)
+        .listener_addr((Ipv4Addr::LOCALHOST, 0).into())
+        .discovery_addr((Ipv4Addr::LOCALHOST, 0).into())
+        .disable_discovery()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_bootstrap_from_published_tree() {
+    reth_tracing::init_test_tracing();
+
+    // stands in for the DNS server the tree is published to
+    let resolver = Arc::new(MapResolver::default());
+    let publisher_config = DnsTreePublisherConfig::new(
+        "nodes.example.org",
+        SecretKey::new(&mut rand::thread_rng()),
+        Arc::clone(&resolver),
+    );
+    let link = publisher_config.link();
+
+    let config = local_network_config(SecretKey::new(&mut rand::thread_rng()))
+        .publish_dns_tree(publisher_config)
+        .build(NoopProvider::default());
+    let network = NetworkManager::new(config).await.unwrap();
+    let published = network.handle().clone();
+    tokio::task::spawn(network);
+
+    // the local node is published on the first poll of the network
+    while resolver.get(&link.domain).is_none() {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // the published tree can be synced
+    let mut dns = DnsDiscoveryService::new(Arc::clone(&resolver), Default::default());
+    let mut records = dns.node_record_stream();
+    dns.sync_tree_with_link(link.clone());
+    let _dns = dns.spawn();
+
+    let record = tokio::time::timeout(Duration::from_secs(10), records.next())
+        .await
+        .expect("tree is published and synced")
+        .unwrap();
+    assert_eq!(record.node_record.id, *published.peer_id());
+    assert_eq!(record.node_record.tcp_addr(), published.local_addr());
+
+    // a new node bootstraps from the tree via DNS discovery and connects to the published node
+    let dns_config = DnsDiscoveryConfig {
+        bootstrap_dns_networks: Some(HashSet::from([link])),
+        ..Default::default()
+    };
+    let config = NetworkConfigBuilder::new(SecretKey::new(&mut rand::thread_rng()))
+        .listener_addr((Ipv4Addr::LOCALHOST, 0).into())
+        .discovery_addr((Ipv4Addr::LOCALHOST, 0).into())
+        .disable_discv4_discovery()
+        .dns_discovery(dns_config)
+        .dns_resolver(resolver)
+        .build(NoopProvider::default());
+    let network = NetworkManager::new(config).await.unwrap();
+    let handle = network.handle().clone();
+    let mut events = NetworkEventStream::new(handle.event_listener());
+    tokio::task::spawn(network);
+
+    let established =
+        tokio::time::timeout(Duration::from_secs(10), events.next_session_established())
+            .await
+            .expect("published node is discovered and dialed");
+    assert_eq!(established, Some(*published.peer_id()));
+}
@@ -1,5 +1,6 @@
 mod big_pooled_txs_req;
 mod connect;
+mod dns;
 mod multiplex;
 mod requests;
 mod session;
@@ -2,21 +2,23 @@
 //! Tests for eth related requests
 
 use rand::Rng;
+use reth_eth_wire::{GetReceipts, Receipts};
 use reth_interfaces::p2p::{
     bodies::client::BodiesClient,
     headers::client::{HeadersClient, HeadersRequest},
 };
 use reth_network::{
     test_utils::{NetworkEventStream, Testnet},
-    NetworkEvents,
+    NetworkEvents, PeerRequest,
 };
 use reth_network_api::{NetworkInfo, Peers};
 use reth_primitives::{
-    Block, BlockBody, Bytes, Header, HeadersDirection, Signature, Transaction, TransactionKind,
-    TransactionSigned, TxEip2930, U256,
+    Block, BlockBody, Bytes, Header, HeadersDirection, Log, Receipt, Signature, Transaction,
+    TransactionKind, TransactionSigned, TxEip2930, TxType, U256,
 };
 use reth_provider::test_utils::MockEthProvider;
 use std::sync::Arc;
+use tokio::sync::oneshot;
 
 /// Returns a new [`TransactionSigned`] with some random parameters
 pub fn rng_transaction(rng: &mut impl rand::RngCore) -> TransactionSigned {
@@ -124,3 +126,58 @@ async fn test_get_header() {
         assert_eq!(headers[0], header);
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_get_receipts_eth69() {
+    reth_tracing::init_test_tracing();
+    let mut rng = rand::thread_rng();
+    let mock_provider = Arc::new(MockEthProvider::default());
+
+    let mut net = Testnet::create_with(2, mock_provider.clone()).await;
+
+    // install request handlers
+    net.for_each_mut(|peer| peer.install_request_handler());
+
+    let handle0 = net.peers()[0].handle();
+    let mut events0 = NetworkEventStream::new(handle0.event_listener());
+
+    let handle1 = net.peers()[1].handle();
+
+    let _handle = net.spawn();
+
+    handle0.add_peer(*handle1.peer_id(), handle1.local_addr());
+    let connected = events0.next_session_established().await.unwrap();
+    assert_eq!(connected, *handle1.peer_id());
+
+    let peer = handle0.get_peer_by_id(*handle1.peer_id()).await.unwrap().unwrap();
+    assert!(peer.eth_version.is_eth69());
+
+    // request some receipts
+    for _ in 0..10 {
+        // Set new random receipts to the mock storage and request them via the network
+        let block_hash = rng.gen();
+        let receipts = vec![Receipt {
+            tx_type: TxType::Eip1559,
+            success: true,
+            cumulative_gas_used: rng.gen(),
+            logs: vec![Log { address: rng.gen(), topics: vec![rng.gen()], data: Bytes::new() }],
+            ..Default::default()
+        }];
+
+        mock_provider.add_receipts(block_hash, receipts.clone());
+
+        // the receipts are served without bloom filters, which are recomputed by the requester
+        let (tx, rx) = oneshot::channel();
+        handle0.send_request(
+            *handle1.peer_id(),
+            PeerRequest::GetReceipts { request: GetReceipts(vec![block_hash]), response: tx },
+        );
+
+        let res = rx.await.unwrap();
+        assert!(res.is_ok(), "{res:?}");
+        assert_eq!(
+            res.unwrap(),
+            Receipts(vec![receipts.into_iter().map(Receipt::with_bloom).collect()])
+        );
+    }
+}
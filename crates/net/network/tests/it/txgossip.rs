@@ -2,12 +2,32 @@
 
 use futures::StreamExt;
 use rand::thread_rng;
-use reth_network::{test_utils::Testnet, NetworkEvent, NetworkEvents};
-use reth_network_api::PeersInfo;
+use reth_network::{
+    test_utils::{NetworkEventStream, PeerHandle, Testnet},
+    transactions::{
+        TransactionIngressPolicy, TransactionPropagationMode, TransactionsManagerConfig,
+    },
+    NetworkEvent, NetworkEvents,
+};
+use reth_network_api::{Peers, PeersInfo};
 use reth_primitives::{TransactionSigned, TxLegacy, U256};
 use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
-use reth_transaction_pool::{test_utils::TransactionGenerator, PoolTransaction, TransactionPool};
-use std::sync::Arc;
+use reth_transaction_pool::{
+    test_utils::TransactionGenerator, FullTransactionEvent, PoolTransaction, PropagateKind,
+    TransactionPool,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// Waits until the transactions manager of the peer tracks `num` active peers.
+async fn wait_for_tx_peers<Pool>(peer: &PeerHandle<Pool>, num: usize) {
+    let transactions = peer.transactions().unwrap();
+    while transactions.get_active_peers().await.unwrap().len() < num {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_tx_gossip() {
@@ -154,3 +174,158 @@ async fn test_sending_invalid_transactions() {
     // ensure txs never made it to the pool
     assert!(tx_listener.try_recv().is_err());
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tx_gossip_trusted_peers_only() {
+    reth_tracing::init_test_tracing();
+
+    let provider = MockEthProvider::default();
+    let net = Testnet::create_with(3, provider.clone()).await;
+
+    let config = TransactionsManagerConfig {
+        propagation_mode: TransactionPropagationMode::Trusted,
+        ..Default::default()
+    };
+    let net = net.with_eth_pool_config(config);
+    let handle = net.spawn();
+
+    let peer0 = &handle.peers()[0];
+    let peer1 = &handle.peers()[1];
+    let peer2 = &handle.peers()[2];
+
+    // peer0 trusts peer1, but not peer2
+    let mut peer0_events = NetworkEventStream::new(peer0.event_listener());
+    peer0.network().add_trusted_peer(*peer1.peer_id(), peer1.local_addr());
+    peer0.network().add_peer(*peer2.peer_id(), peer2.local_addr());
+    peer0_events.take_session_established(2).await;
+    wait_for_tx_peers(peer0, 2).await;
+
+    let peer0_pool = peer0.pool().unwrap();
+    let mut peer0_events = peer0_pool.all_transactions_event_listener();
+    let mut peer1_tx_listener = peer1.pool().unwrap().pending_transactions_listener();
+
+    let mut gen = TransactionGenerator::new(thread_rng());
+    let tx = gen.gen_eip1559_pooled();
+    provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(100_000_000)));
+
+    let hash = peer0_pool.add_external_transaction(tx).await.unwrap();
+
+    // only the trusted peer receives the transaction
+    assert_eq!(peer1_tx_listener.recv().await.unwrap(), hash);
+    loop {
+        if let Some(FullTransactionEvent::Propagated(kinds)) = peer0_events.next().await {
+            assert!(!kinds.is_empty());
+            assert!(kinds.iter().all(|kind| kind.peer() == peer1.peer_id()));
+            break
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tx_gossip_announce_only() {
+    reth_tracing::init_test_tracing();
+
+    let provider = MockEthProvider::default();
+    let net = Testnet::create_with(2, provider.clone()).await;
+
+    let config = TransactionsManagerConfig { announce_only: true, ..Default::default() };
+    let net = net.with_eth_pool_config(config);
+    let handle = net.spawn();
+    handle.connect_peers().await;
+
+    let peer0 = &handle.peers()[0];
+    let peer1 = &handle.peers()[1];
+    wait_for_tx_peers(peer0, 1).await;
+
+    let peer0_pool = peer0.pool().unwrap();
+    let mut peer0_events = peer0_pool.all_transactions_event_listener();
+    let mut peer1_tx_listener = peer1.pool().unwrap().pending_transactions_listener();
+
+    let mut gen = TransactionGenerator::new(thread_rng());
+    let tx = gen.gen_eip1559_pooled();
+    provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(100_000_000)));
+
+    let hash = peer0_pool.add_external_transaction(tx).await.unwrap();
+
+    // peer1 still fetches the announced transaction
+    assert_eq!(peer1_tx_listener.recv().await.unwrap(), hash);
+
+    // with a single peer the transaction would usually be sent in full, ensure it was announced
+    loop {
+        if let Some(FullTransactionEvent::Propagated(kinds)) = peer0_events.next().await {
+            assert!(!kinds.is_empty());
+            assert!(kinds.iter().all(|kind| matches!(kind, PropagateKind::Hash(_))));
+            break
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tx_gossip_propagation_delay() {
+    reth_tracing::init_test_tracing();
+
+    let delay = Duration::from_millis(500);
+    let provider = MockEthProvider::default();
+    let net = Testnet::create_with(2, provider.clone()).await;
+
+    let config = TransactionsManagerConfig { propagation_delay: delay, ..Default::default() };
+    let net = net.with_eth_pool_config(config);
+    let handle = net.spawn();
+    handle.connect_peers().await;
+
+    let peer0 = &handle.peers()[0];
+    let peer1 = &handle.peers()[1];
+    wait_for_tx_peers(peer0, 1).await;
+
+    let peer0_pool = peer0.pool().unwrap();
+    let mut peer1_tx_listener = peer1.pool().unwrap().pending_transactions_listener();
+
+    let mut gen = TransactionGenerator::new(thread_rng());
+    let tx = gen.gen_eip1559_pooled();
+    provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(100_000_000)));
+
+    let start = Instant::now();
+    let hash = peer0_pool.add_external_transaction(tx).await.unwrap();
+
+    assert_eq!(peer1_tx_listener.recv().await.unwrap(), hash);
+    assert!(start.elapsed() >= delay);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_tx_gossip_ingress_trusted_peers_only() {
+    reth_tracing::init_test_tracing();
+
+    let provider = MockEthProvider::default();
+    let net = Testnet::create_with(2, provider.clone()).await;
+
+    let config = TransactionsManagerConfig {
+        ingress_policy: TransactionIngressPolicy::Trusted,
+        ..Default::default()
+    };
+    let net = net.with_eth_pool_config(config);
+    let handle = net.spawn();
+
+    let peer0 = &handle.peers()[0];
+    let peer1 = &handle.peers()[1];
+
+    // peer1 trusts peer0 for the session peer0 establishes, without tracking it as trusted peer
+    peer1.network().add_trusted_peer_id(*peer0.peer_id());
+
+    let mut peer0_events = NetworkEventStream::new(peer0.event_listener());
+    peer0.network().add_peer(*peer1.peer_id(), peer1.local_addr());
+    peer0_events.take_session_established(1).await;
+    wait_for_tx_peers(peer0, 1).await;
+    wait_for_tx_peers(peer1, 1).await;
+
+    let peer0_pool = peer0.pool().unwrap();
+    let mut peer1_tx_listener = peer1.pool().unwrap().pending_transactions_listener();
+
+    let mut gen = TransactionGenerator::new(thread_rng());
+    let tx = gen.gen_eip1559_pooled();
+    provider.add_account(tx.sender(), ExtendedAccount::new(0, U256::from(100_000_000)));
+
+    let hash = peer0_pool.add_external_transaction(tx).await.unwrap();
+
+    // the gossip of the trusted peer is accepted
+    assert_eq!(peer1_tx_listener.recv().await.unwrap(), hash);
+}
@@ -27,8 +27,11 @@ use reth_db::{
     test_utils::{create_test_rw_db, TempDatabase},
     DatabaseEnv,
 };
-use reth_exex::{ExExContext, ExExHandle, ExExManager};
+#[cfg(unix)]
+use reth_exex::IpcExEx;
+use reth_exex::{BackfillJobFactory, ExExContext, ExExHandle, ExExManager, ExExRpcModules, Wal};
 use reth_interfaces::p2p::either::EitherDownloader;
+use reth_light_protocol::{LightServer, LightServerConfig};
 use reth_network::{NetworkBuilder, NetworkConfig, NetworkEvents, NetworkHandle};
 use reth_node_api::{
     FullNodeComponents, FullNodeComponentsAdapter, FullNodeTypes, FullNodeTypesAdapter, NodeTypes,
@@ -42,9 +45,12 @@ use reth_node_core::{
     init::init_genesis,
     node_config::NodeConfig,
     primitives::{kzg::KzgSettings, Head},
-    utils::write_peers_to_file,
+    utils::{write_peer_store_to_file, write_peers_to_file},
+};
+use reth_primitives::{
+    constants::eip4844::{BLOB_SIDECARS_RETENTION_BLOCKS, MAINNET_KZG_TRUSTED_SETUP},
+    format_ether, ChainSpec, PruneMode,
 };
-use reth_primitives::{constants::eip4844::MAINNET_KZG_TRUSTED_SETUP, format_ether, ChainSpec};
 use reth_provider::{
     providers::BlockchainProvider, CanonStateSubscriptions, ChainSpecProvider, ProviderFactory,
 };
@@ -504,7 +510,8 @@ where
             Arc::clone(&config.chain),
             data_dir.static_files_path(),
         )?
-        .with_static_files_metrics();
+        .with_static_files_metrics()
+        .with_trie_changesets(reth_config.stages.merkle.trie_changesets);
         info!(target: "reth::cli", "Database opened");
 
         let prometheus_handle = config.install_prometheus_recorder()?;
@@ -529,11 +536,23 @@ where
         let sync_metrics_listener = reth_stages::MetricsListener::new(sync_metrics_rx);
         executor.spawn_critical("stages metrics listener task", sync_metrics_listener);
 
-        let prune_config = config.prune_config()?.or_else(|| reth_config.prune.clone());
+        let mut prune_config = config.prune_config()?.or_else(|| reth_config.prune.clone());
+        if config.txpool.blob_archive {
+            // keep archived blob sidecars for the consensus layer's retention window by default
+            prune_config
+                .get_or_insert_with(Default::default)
+                .segments
+                .blob_sidecars
+                .get_or_insert(PruneMode::Distance(BLOB_SIDECARS_RETENTION_BLOCKS));
+        } else if let Some(prune_config) = prune_config.as_mut() {
+            // there is nothing to prune without the blob archive
+            prune_config.segments.blob_sidecars = None;
+        }
 
         // Configure the blockchain tree for the node
         let evm_config = types.evm_config();
-        let tree_config = BlockchainTreeConfig::default();
+        let tree_config =
+            BlockchainTreeConfig::default().with_sparse_trie(config.engine.sparse_trie);
         let tree_externals = TreeExternals::new(
             provider_factory.clone(),
             consensus.clone(),
@@ -581,6 +600,19 @@ where
             ..
         } = ctx;
 
+        if config.txpool.blob_archive {
+            executor.spawn_critical(
+                "blob archive task",
+                reth_transaction_pool::blobstore::maintain_blob_archive_future(
+                    transaction_pool.clone(),
+                    provider_factory.clone(),
+                    blockchain_db.canonical_state_stream(),
+                    executor.clone(),
+                ),
+            );
+            debug!(target: "reth::cli", "Spawned blob archive task");
+        }
+
         let NodeHooks { on_component_initialized, on_node_started, .. } = hooks;
 
         let node_components = FullNodeComponentsAdapter {
@@ -595,12 +627,27 @@ where
         on_component_initialized.on_event(node_components.clone())?;
 
         // spawn exexs
-        let mut exex_handles = Vec::with_capacity(self.state.exexs.len());
-        let mut exexs = Vec::with_capacity(self.state.exexs.len());
-        for (id, exex) in self.state.exexs {
-            // create a new exex handle
+        let exex_rpc_modules = ExExRpcModules::new();
+        let installed_exexs = self.state.exexs;
+        // install the built-in exex that streams the notifications to another process
+        #[cfg(unix)]
+        let installed_exexs = {
+            let mut installed_exexs = installed_exexs;
+            if let Some(path) = config.exex.ipc.clone() {
+                installed_exexs.push((
+                    "ipc".to_string(),
+                    Box::new(move |ctx| async move { Ok(IpcExEx::new(ctx, path)?.run()) }),
+                ));
+            }
+            installed_exexs
+        };
+        let mut exex_handles = Vec::with_capacity(installed_exexs.len());
+        let mut exexs = Vec::with_capacity(installed_exexs.len());
+        for (id, exex) in installed_exexs {
+            // create a new exex handle that persists the notifications sent to the exex
             let (handle, events, notifications) = ExExHandle::new(id.clone());
-            exex_handles.push(handle);
+            let wal = Wal::new(data_dir.exex_wal_path().join(&id))?;
+            exex_handles.push(handle.with_wal(wal));
 
             // create the launch context for the exex
             let context = ExExContext {
@@ -613,6 +660,8 @@ where
                 pool: transaction_pool.clone(),
                 events,
                 notifications,
+                components: node_components.clone(),
+                rpc_modules: exex_rpc_modules.clone(),
             };
 
             let executor = executor.clone();
@@ -641,7 +690,12 @@ where
         if !exex_handles.is_empty() {
             debug!(target: "reth::cli", "spawning exex manager");
             // todo(onbjerg): rm magic number
-            let exex_manager = ExExManager::new(exex_handles, 1024);
+            let exex_manager =
+                ExExManager::new(exex_handles, 1024).with_backfill(BackfillJobFactory::new(
+                    provider_factory.clone(),
+                    EvmProcessorFactory::new(config.chain.clone(), evm_config.clone()),
+                    executor.clone(),
+                ));
             let mut exex_manager_handle = exex_manager.handle();
             executor.spawn_critical("exex manager", async move {
                 exex_manager.await.expect("exex manager crashed");
@@ -836,6 +890,7 @@ where
             &config,
             jwt_secret,
             rpc,
+            exex_rpc_modules,
         )
         .await?;
 
@@ -1303,14 +1358,25 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     ///
     /// Spawns the configured network and associated tasks and returns the [NetworkHandle] connected
     /// to that network.
+    ///
+    /// If enabled, this also serves the `rlight` sub-protocol to light clients.
     pub fn start_network<Pool>(
         &self,
-        builder: NetworkBuilder<Node::Provider, (), ()>,
+        mut builder: NetworkBuilder<Node::Provider, (), ()>,
         pool: Pool,
     ) -> NetworkHandle
     where
         Pool: TransactionPool + Unpin + 'static,
     {
+        if self.config.network.light_serve {
+            let server = LightServer::new(self.provider().clone(), LightServerConfig::default())
+                .with_task_spawner(Box::new(self.executor.clone()));
+            builder.network_mut().add_rlpx_sub_protocol(server.clone());
+            let notifications = self.provider().canonical_state_stream();
+            self.executor.spawn(Box::pin(server.track_canonical_head(notifications)));
+            info!(target: "reth::cli", "Serving light clients");
+        }
+
         let (handle, network, txpool, eth) = builder
             .transactions(pool, Default::default())
             .request_handler(self.provider().clone())
@@ -1321,6 +1387,29 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
 
         let default_peers_path = self.data_dir().known_peers_path();
         let known_peers_file = self.config.network.persistent_peers_file(default_peers_path);
+
+        // periodically persist the peers, so their state survives a crash, unless disabled with a
+        // zero interval
+        let persist_interval = self.reth_config.peers.store.persist_interval;
+        if let Some(file_path) = known_peers_file.clone().filter(|_| !persist_interval.is_zero()) {
+            let peers = handle.peers_handle().clone();
+            self.executor.spawn(Box::pin(async move {
+                let mut interval = tokio::time::interval(persist_interval);
+                // the first tick completes immediately
+                interval.tick().await;
+                loop {
+                    interval.tick().await;
+                    // the network has shut down, the peers are written on shutdown instead
+                    let Some(peer_store) = peers.peer_store().await else { break };
+                    let file_path = file_path.clone();
+                    let _ = tokio::task::spawn_blocking(move || {
+                        write_peer_store_to_file(&peer_store, &file_path)
+                    })
+                    .await;
+                }
+            }));
+        }
+
         self.executor.spawn_critical_with_graceful_shutdown_signal(
             "p2p network task",
             |shutdown| {
@@ -1,6 +1,7 @@
 //! Builder support for rpc components.
 
 use futures::TryFutureExt;
+use reth_exex::ExExRpcModules;
 use reth_network::NetworkHandle;
 use reth_node_api::FullNodeComponents;
 use reth_node_core::{
@@ -253,6 +254,7 @@ pub(crate) async fn launch_rpc_servers<Node, Engine>(
     config: &NodeConfig,
     jwt_secret: JwtSecret,
     hooks: RpcHooks<Node>,
+    exex_rpc_modules: ExExRpcModules,
 ) -> eyre::Result<(RethRpcServerHandles, RpcRegistry<Node>)>
 where
     Node: FullNodeComponents + Clone,
@@ -284,6 +286,11 @@ where
 
     extend_rpc_modules.extend_rpc_modules(ctx)?;
 
+    // merge the modules registered by the exexs, no more modules can be registered after this
+    if let Some(methods) = exex_rpc_modules.take() {
+        modules.merge_configured(methods)?;
+    }
+
     let server_config = config.rpc.rpc_server_config();
     let launch_rpc = modules.clone().start_server(server_config).map_ok(|handle| {
         if let Some(url) = handle.ipc_endpoint() {
@@ -179,7 +179,10 @@ where
                 stage_config.storage_hashing.commit_threshold,
                 stage_config.etl.clone(),
             ))
-            .set(MerkleStage::new_execution(stage_config.merkle.clean_threshold))
+            .set(MerkleStage::new_execution(
+                stage_config.merkle.clean_threshold,
+                stage_config.etl.clone(),
+            ))
             .set(TransactionLookupStage::new(
                 stage_config.transaction_lookup.chunk_size,
                 stage_config.etl.clone(),
@@ -0,0 +1,38 @@
+//! clap [Args](clap::Args) for engine configuration
+
+use clap::Args;
+
+/// Parameters for configuring the engine
+#[derive(Debug, Clone, Copy, Args, PartialEq, Eq, Default)]
+#[command(next_help_heading = "Engine")]
+pub struct EngineArgs {
+    /// Compute the state root of new payloads from an in-memory sparse trie that is kept across
+    /// blocks.
+    ///
+    /// Trie nodes that are not in memory are loaded from the database. The trie updates of the
+    /// blocks are computed once they are made canonical instead.
+    #[arg(long = "engine.sparse-trie", help_heading = "Engine")]
+    pub sparse_trie: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_engine_args() {
+        let args = CommandParser::<EngineArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, EngineArgs::default());
+
+        let args = CommandParser::<EngineArgs>::parse_from(["reth", "--engine.sparse-trie"]).args;
+        assert_eq!(args, EngineArgs { sparse_trie: true });
+    }
+}
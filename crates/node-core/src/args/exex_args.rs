@@ -0,0 +1,40 @@
+//! clap [Args](clap::Args) for ExEx configuration
+
+use clap::Args;
+use std::path::PathBuf;
+
+/// Parameters for configuring the execution extensions (ExEx)
+#[derive(Debug, Clone, Args, PartialEq, Eq, Default)]
+#[command(next_help_heading = "ExEx")]
+pub struct ExExArgs {
+    /// Stream the canonical chain notifications to an out-of-process ExEx over a Unix socket at
+    /// the given path.
+    ///
+    /// The client acknowledges processed blocks on the same socket, which determines what state
+    /// can be pruned.
+    #[arg(long = "exex.ipc", value_name = "PATH", help_heading = "ExEx")]
+    pub ipc: Option<PathBuf>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    /// A helper type to parse Args more easily
+    #[derive(Parser)]
+    struct CommandParser<T: Args> {
+        #[command(flatten)]
+        args: T,
+    }
+
+    #[test]
+    fn test_parse_exex_args() {
+        let args = CommandParser::<ExExArgs>::parse_from(["reth"]).args;
+        assert_eq!(args, ExExArgs::default());
+
+        let args =
+            CommandParser::<ExExArgs>::parse_from(["reth", "--exex.ipc", "/tmp/exex.ipc"]).args;
+        assert_eq!(args, ExExArgs { ipc: Some("/tmp/exex.ipc".into()) });
+    }
+}
@@ -1,6 +1,8 @@
 use crate::primitives::U256;
 use clap::Args;
-use reth_rpc::eth::gas_oracle::GasPriceOracleConfig;
+use reth_rpc::eth::gas_oracle::{
+    GasPriceOracleConfig, GasPriceOracleStrategy, DEFAULT_FEE_PROJECTION_BLOCKS,
+};
 use reth_rpc_builder::constants::{
     DEFAULT_GAS_PRICE_BLOCKS, DEFAULT_GAS_PRICE_PERCENTILE, DEFAULT_IGNORE_GAS_PRICE,
     DEFAULT_MAX_GAS_PRICE,
@@ -25,17 +27,28 @@ pub struct GasPriceOracleArgs {
     /// The percentile of gas prices to use for the estimate
     #[arg(long = "gpo.percentile", default_value_t = DEFAULT_GAS_PRICE_PERCENTILE)]
     pub percentile: u32,
+
+    /// The strategy used to suggest priority fees: `percentile` of recent blocks or `mempool`
+    #[arg(long = "gpo.strategy", default_value_t = GasPriceOracleStrategy::Percentile)]
+    pub strategy: GasPriceOracleStrategy,
+
+    /// Number of consecutive full blocks the suggested max fees of `reth_feeEstimates` account for
+    #[arg(long = "gpo.projectionblocks", default_value_t = DEFAULT_FEE_PROJECTION_BLOCKS)]
+    pub projection_blocks: u64,
 }
 
 impl GasPriceOracleArgs {
     /// Returns a [GasPriceOracleConfig] from the arguments.
     pub fn gas_price_oracle_config(&self) -> GasPriceOracleConfig {
-        let Self { blocks, ignore_price, max_price, percentile } = self;
+        let Self { blocks, ignore_price, max_price, percentile, strategy, projection_blocks } =
+            self;
         GasPriceOracleConfig {
             max_price: Some(U256::from(*max_price)),
             ignore_price: Some(U256::from(*ignore_price)),
             percentile: *percentile,
             blocks: *blocks,
+            strategy: *strategy,
+            projection_blocks: *projection_blocks,
             ..Default::default()
         }
     }
@@ -48,6 +61,8 @@ impl Default for GasPriceOracleArgs {
             ignore_price: DEFAULT_IGNORE_GAS_PRICE.to(),
             max_price: DEFAULT_MAX_GAS_PRICE.to(),
             percentile: DEFAULT_GAS_PRICE_PERCENTILE,
+            strategy: GasPriceOracleStrategy::Percentile,
+            projection_blocks: DEFAULT_FEE_PROJECTION_BLOCKS,
         }
     }
 }
@@ -73,10 +88,27 @@ mod tests {
                 ignore_price: DEFAULT_IGNORE_GAS_PRICE.to(),
                 max_price: DEFAULT_MAX_GAS_PRICE.to(),
                 percentile: DEFAULT_GAS_PRICE_PERCENTILE,
+                strategy: GasPriceOracleStrategy::Percentile,
+                projection_blocks: DEFAULT_FEE_PROJECTION_BLOCKS,
             }
         );
     }
 
+    #[test]
+    fn test_parse_gpo_strategy() {
+        let args = CommandParser::<GasPriceOracleArgs>::parse_from([
+            "reth",
+            "--gpo.strategy",
+            "mempool",
+            "--gpo.projectionblocks",
+            "5",
+        ])
+        .args;
+        let config = args.gas_price_oracle_config();
+        assert_eq!(config.strategy, GasPriceOracleStrategy::Mempool);
+        assert_eq!(config.projection_blocks, 5);
+    }
+
     #[test]
     fn gpo_args_default_sanity_test() {
         let default_args = GasPriceOracleArgs::default();
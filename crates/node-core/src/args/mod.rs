@@ -2,7 +2,7 @@
 
 /// NetworkArg struct for configuring the network
 mod network_args;
-pub use network_args::{DiscoveryArgs, NetworkArgs};
+pub use network_args::{BandwidthArgs, DiscoveryArgs, DnsPublishArgs, NetworkArgs};
 
 /// RpcServerArg struct for configuring the RPC
 mod rpc_server_args;
@@ -51,6 +51,14 @@ pub use dev_args::DevArgs;
 mod pruning_args;
 pub use pruning_args::PruningArgs;
 
+/// EngineArgs for configuring the engine
+mod engine_args;
+pub use engine_args::EngineArgs;
+
+/// ExExArgs for configuring the execution extensions
+mod exex_args;
+pub use exex_args::ExExArgs;
+
 pub mod utils;
 
 pub mod types;
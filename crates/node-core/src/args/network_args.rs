@@ -1,24 +1,29 @@
 //! clap [Args](clap::Args) for network related arguments.
 
-use crate::version::P2P_CLIENT_VERSION;
+use crate::{
+    args::{get_secret_key, utils::parse_duration_from_secs, SecretKeyError},
+    version::P2P_CLIENT_VERSION,
+};
 use clap::Args;
 use reth_config::Config;
 use reth_discv4::{
     DEFAULT_DISCOVERY_ADDR, DEFAULT_DISCOVERY_PORT, DEFAULT_DISCOVERY_V5_ADDR,
     DEFAULT_DISCOVERY_V5_PORT,
 };
+use reth_dns_discovery::{DnsTreePublisherConfig, ZoneFilePublisher};
 use reth_net_nat::NatResolver;
 use reth_network::{
     transactions::{
-        TransactionFetcherConfig, TransactionsManagerConfig,
+        TransactionFetcherConfig, TransactionIngressPolicy, TransactionPropagationMode,
+        TransactionsManagerConfig,
         DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
         SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
     },
-    HelloMessageWithProtocols, NetworkConfigBuilder, SessionsConfig,
+    BandwidthLimits, HelloMessageWithProtocols, NetworkConfigBuilder, SessionsConfig,
 };
 use reth_primitives::{mainnet_nodes, ChainSpec, NodeRecord};
 use secp256k1::SecretKey;
-use std::{net::IpAddr, path::PathBuf, sync::Arc};
+use std::{net::IpAddr, path::PathBuf, sync::Arc, time::Duration};
 
 /// Parameters for configuring the network more granularity via CLI
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
@@ -98,6 +103,22 @@ pub struct NetworkArgs {
     /// is 128 KiB.
     #[arg(long = "pooled-tx-pack-soft-limit", value_name = "BYTES", default_value_t = DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ)]
     pub soft_limit_byte_size_pooled_transactions_response_on_pack_request: usize,
+
+    /// Arguments to limit the bandwidth used by peer sessions.
+    #[command(flatten)]
+    pub bandwidth: BandwidthArgs,
+
+    /// Arguments to restrict transaction gossip.
+    #[command(flatten)]
+    pub tx_gossip: TxGossipArgs,
+
+    /// Serve headers and proofs to light clients via the `rlight` sub-protocol.
+    #[arg(id = "light.serve", long = "light.serve")]
+    pub light_serve: bool,
+
+    /// Arguments to publish the active peers as DNS discovery tree.
+    #[command(flatten)]
+    pub dns_publish: DnsPublishArgs,
 }
 
 impl NetworkArgs {
@@ -124,18 +145,23 @@ impl NetworkArgs {
             .with_max_outbound_opt(self.max_outbound_peers);
 
         // Configure transactions manager
-        let transactions_manager_config = TransactionsManagerConfig {
+        let transactions_manager_config = self.tx_gossip.apply_to(TransactionsManagerConfig {
             transaction_fetcher_config: TransactionFetcherConfig::new(
                 self.soft_limit_byte_size_pooled_transactions_response,
                 self.soft_limit_byte_size_pooled_transactions_response_on_pack_request,
             ),
-        };
+            ..config.transactions.clone()
+        });
 
         // Configure basic network stack
         let mut network_config_builder = config
             .network_config(self.nat, self.persistent_peers_file(peers_file), secret_key)
             .sessions_config(
-                SessionsConfig::default().with_upscaled_event_buffer(peers_config.max_peers()),
+                SessionsConfig::default()
+                    .with_upscaled_event_buffer(peers_config.max_peers())
+                    .with_bandwidth_limits(
+                        self.bandwidth.apply_to(config.sessions.bandwidth_limits),
+                    ),
             )
             .peer_config(peers_config)
             .boot_nodes(self.bootnodes.clone().unwrap_or(chain_bootnodes))
@@ -195,10 +221,199 @@ impl Default for NetworkArgs {
             soft_limit_byte_size_pooled_transactions_response:
                 SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
             soft_limit_byte_size_pooled_transactions_response_on_pack_request: DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
+            bandwidth: BandwidthArgs::default(),
+            tx_gossip: TxGossipArgs::default(),
+            light_serve: false,
+            dns_publish: DnsPublishArgs::default(),
+        }
+    }
+}
+
+/// Arguments to limit the bandwidth used by peer sessions, in bytes per second.
+///
+/// Unset limits fall back to the `[sessions.bandwidth_limits]` section of the config file.
+#[derive(Debug, Clone, Default, Args, PartialEq, Eq)]
+pub struct BandwidthArgs {
+    /// Maximum rate at which messages are read from all peers combined.
+    #[arg(
+        id = "bandwidth.global-ingress",
+        long = "bandwidth.global-ingress",
+        value_name = "BYTES_PER_SEC"
+    )]
+    pub global_ingress: Option<u64>,
+
+    /// Maximum rate at which messages are sent to all peers combined.
+    #[arg(
+        id = "bandwidth.global-egress",
+        long = "bandwidth.global-egress",
+        value_name = "BYTES_PER_SEC"
+    )]
+    pub global_egress: Option<u64>,
+
+    /// Maximum rate at which messages are read from a single peer.
+    #[arg(
+        id = "bandwidth.peer-ingress",
+        long = "bandwidth.peer-ingress",
+        value_name = "BYTES_PER_SEC"
+    )]
+    pub peer_ingress: Option<u64>,
+
+    /// Maximum rate at which messages are sent to a single peer.
+    #[arg(
+        id = "bandwidth.peer-egress",
+        long = "bandwidth.peer-egress",
+        value_name = "BYTES_PER_SEC"
+    )]
+    pub peer_egress: Option<u64>,
+
+    /// Maximum rate at which transaction gossip is sent to all peers combined.
+    #[arg(
+        id = "bandwidth.tx-gossip-egress",
+        long = "bandwidth.tx-gossip-egress",
+        value_name = "BYTES_PER_SEC"
+    )]
+    pub tx_gossip_egress: Option<u64>,
+
+    /// Maximum rate at which blocks, headers and receipts are served to all peers combined.
+    #[arg(
+        id = "bandwidth.block-serving-egress",
+        long = "bandwidth.block-serving-egress",
+        value_name = "BYTES_PER_SEC"
+    )]
+    pub block_serving_egress: Option<u64>,
+}
+
+impl BandwidthArgs {
+    /// Overrides the given limits with the limits set via CLI.
+    pub fn apply_to(&self, limits: BandwidthLimits) -> BandwidthLimits {
+        BandwidthLimits {
+            global_ingress: self.global_ingress.or(limits.global_ingress),
+            global_egress: self.global_egress.or(limits.global_egress),
+            peer_ingress: self.peer_ingress.or(limits.peer_ingress),
+            peer_egress: self.peer_egress.or(limits.peer_egress),
+            tx_gossip_egress: self.tx_gossip_egress.or(limits.tx_gossip_egress),
+            block_serving_egress: self.block_serving_egress.or(limits.block_serving_egress),
+        }
+    }
+}
+
+/// Arguments to restrict which peers transactions are gossiped with.
+///
+/// Unset options fall back to the `[transactions]` section of the config file.
+#[derive(Debug, Clone, Default, Args, PartialEq, Eq)]
+pub struct TxGossipArgs {
+    /// Which peers new pending transactions are propagated to (all|trusted|disabled).
+    #[arg(
+        id = "tx-gossip.propagation-mode",
+        long = "tx-gossip.propagation-mode",
+        value_name = "MODE"
+    )]
+    pub propagation_mode: Option<TransactionPropagationMode>,
+
+    /// Only announce transaction hashes, never send full transactions.
+    #[arg(id = "tx-gossip.announce-only", long = "tx-gossip.announce-only")]
+    pub announce_only: bool,
+
+    /// Delay before new pending transactions are propagated, in seconds.
+    #[arg(
+        id = "tx-gossip.propagation-delay",
+        long = "tx-gossip.propagation-delay",
+        value_name = "SECONDS",
+        value_parser = parse_duration_from_secs
+    )]
+    pub propagation_delay: Option<Duration>,
+
+    /// Which peers incoming transaction gossip is accepted from (all|trusted).
+    #[arg(
+        id = "tx-gossip.ingress-policy",
+        long = "tx-gossip.ingress-policy",
+        value_name = "POLICY"
+    )]
+    pub ingress_policy: Option<TransactionIngressPolicy>,
+}
+
+impl TxGossipArgs {
+    /// Overrides the gossip policies of the given config with the ones set via CLI.
+    pub fn apply_to(&self, config: TransactionsManagerConfig) -> TransactionsManagerConfig {
+        TransactionsManagerConfig {
+            propagation_mode: self.propagation_mode.unwrap_or(config.propagation_mode),
+            announce_only: self.announce_only || config.announce_only,
+            propagation_delay: self.propagation_delay.unwrap_or(config.propagation_delay),
+            ingress_policy: self.ingress_policy.unwrap_or(config.ingress_policy),
+            ..config
         }
     }
 }
 
+/// Arguments to publish the local node and the active peers as signed
+/// [EIP-1459](https://eips.ethereum.org/EIPS/eip-1459) DNS discovery tree.
+///
+/// The tree is written as zone file, which must be served by an authoritative DNS server for the
+/// domain.
+#[derive(Debug, Clone, Default, Args, PartialEq, Eq)]
+pub struct DnsPublishArgs {
+    /// The domain the DNS discovery tree is published at.
+    #[arg(
+        id = "dns-publish.domain",
+        long = "dns-publish.domain",
+        value_name = "DOMAIN",
+        requires_all = ["dns-publish.key", "dns-publish.zone-file"]
+    )]
+    pub domain: Option<String>,
+
+    /// The secret key the root of the tree is signed with.
+    ///
+    /// A new key is generated at the path if the file does not exist. The public key is part of
+    /// the `enrtree://` link clients use to sync the tree, so it should not be the p2p secret key.
+    #[arg(
+        id = "dns-publish.key",
+        long = "dns-publish.key",
+        value_name = "PATH",
+        requires = "dns-publish.domain"
+    )]
+    pub key: Option<PathBuf>,
+
+    /// The zone file the records of the tree are written to.
+    #[arg(
+        id = "dns-publish.zone-file",
+        long = "dns-publish.zone-file",
+        value_name = "FILE",
+        requires = "dns-publish.domain"
+    )]
+    pub zone_file: Option<PathBuf>,
+
+    /// The minimum time between two publications of the tree, in seconds.
+    #[arg(
+        id = "dns-publish.interval",
+        long = "dns-publish.interval",
+        value_name = "SECONDS",
+        value_parser = parse_duration_from_secs,
+        requires = "dns-publish.domain"
+    )]
+    pub interval: Option<Duration>,
+}
+
+impl DnsPublishArgs {
+    /// Returns the config to publish the DNS discovery tree with, if a domain is set.
+    ///
+    /// Loads or generates the signing key.
+    pub fn publisher_config(&self) -> Result<Option<DnsTreePublisherConfig>, SecretKeyError> {
+        let (Some(domain), Some(key), Some(zone_file)) = (&self.domain, &self.key, &self.zone_file)
+        else {
+            return Ok(None)
+        };
+        let mut config = DnsTreePublisherConfig::new(
+            domain.clone(),
+            get_secret_key(key)?,
+            ZoneFilePublisher::new(zone_file),
+        );
+        if let Some(interval) = self.interval {
+            config = config.with_republish_interval(interval);
+        }
+        Ok(Some(config))
+    }
+}
+
 /// Arguments to setup discovery
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
 pub struct DiscoveryArgs {
@@ -321,6 +536,105 @@ mod tests {
         assert_eq!(args.max_inbound_peers, Some(15));
     }
 
+    #[test]
+    fn parse_bandwidth_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--bandwidth.peer-egress",
+            "1000",
+            "--bandwidth.tx-gossip-egress",
+            "500",
+        ])
+        .args;
+        let config = BandwidthLimits {
+            peer_egress: Some(2000),
+            global_ingress: Some(3000),
+            ..Default::default()
+        };
+        assert_eq!(
+            args.bandwidth.apply_to(config),
+            BandwidthLimits {
+                peer_egress: Some(1000),
+                tx_gossip_egress: Some(500),
+                global_ingress: Some(3000),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn parse_tx_gossip_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--tx-gossip.propagation-mode",
+            "trusted",
+            "--tx-gossip.propagation-delay",
+            "2",
+        ])
+        .args;
+        let config = TransactionsManagerConfig {
+            propagation_mode: TransactionPropagationMode::Disabled,
+            ingress_policy: TransactionIngressPolicy::Trusted,
+            ..Default::default()
+        };
+        assert_eq!(
+            args.tx_gossip.apply_to(config),
+            TransactionsManagerConfig {
+                propagation_mode: TransactionPropagationMode::Trusted,
+                propagation_delay: Duration::from_secs(2),
+                ingress_policy: TransactionIngressPolicy::Trusted,
+                ..Default::default()
+            }
+        );
+
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--tx-gossip.ingress-policy",
+            "disabled"
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn parse_dns_publish_args() {
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth"]).args;
+        assert_eq!(args.dns_publish.publisher_config().unwrap().map(|config| config.domain), None);
+
+        // the key and the zone file are required
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--dns-publish.domain",
+            "nodes.example.org",
+        ])
+        .is_err());
+        assert!(CommandParser::<NetworkArgs>::try_parse_from([
+            "reth",
+            "--dns-publish.zone-file",
+            "nodes.zone",
+        ])
+        .is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let key = dir.path().join("dns-key");
+        let args = CommandParser::<NetworkArgs>::parse_from([
+            "reth",
+            "--dns-publish.domain",
+            "nodes.example.org",
+            "--dns-publish.key",
+            key.to_str().unwrap(),
+            "--dns-publish.zone-file",
+            "nodes.zone",
+            "--dns-publish.interval",
+            "120",
+        ])
+        .args;
+        let config = args.dns_publish.publisher_config().unwrap().unwrap();
+        assert_eq!(config.domain, "nodes.example.org");
+        assert_eq!(config.republish_interval, Duration::from_secs(120));
+        // the generated key is reused
+        assert_eq!(config.signing_key, get_secret_key(&key).unwrap());
+    }
+
     #[test]
     fn parse_trusted_peer_args() {
         let args =
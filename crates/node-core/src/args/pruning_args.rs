@@ -40,6 +40,8 @@ impl PruningArgs {
                             .into_iter()
                             .collect(),
                     ),
+                    // only pruned if the blob archive is enabled, see `--blobpool.archive`
+                    blob_sidecars: None,
                 },
             })
         } else {
@@ -16,11 +16,14 @@ use rand::Rng;
 use reth_network_api::{NetworkInfo, Peers};
 use reth_node_api::{ConfigureEvm, EngineTypes};
 use reth_provider::{
-    AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, HeaderProvider, StateProviderFactory,
+    AccountReader, BlobSidecarReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
+    ChangeSetReader, EvmEnvProvider, HeaderProvider, StateProviderFactory,
 };
 use reth_rpc::{
-    eth::{cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, RPC_DEFAULT_GAS_CAP},
+    eth::{
+        cache::EthStateCacheConfig, gas_oracle::GasPriceOracleConfig, DEFAULT_ETH_PROOF_WINDOW,
+        DEFAULT_MAX_SIMULATE_BLOCKS, RPC_DEFAULT_GAS_CAP,
+    },
     JwtError, JwtSecret,
 };
 use reth_rpc_builder::{
@@ -169,6 +172,30 @@ pub struct RpcServerArgs {
     )]
     pub rpc_gas_cap: u64,
 
+    /// Maximum number of blocks that can be simulated in a single `eth_simulateV1` call.
+    #[arg(
+        long,
+        value_name = "COUNT",
+        value_parser = RangedU64ValueParser::<u64>::new().range(1..),
+        default_value_t = DEFAULT_MAX_SIMULATE_BLOCKS
+    )]
+    pub rpc_max_simulate_blocks: u64,
+
+    /// Maximum distance to the tip, in blocks, of the state `eth_getProof` is served for.
+    ///
+    /// Proofs for older blocks are generated by reverting the state, which gets more expensive the
+    /// further back the block is. This also bounds `debug_executionWitness`, which proves the state
+    /// of the parent block.
+    #[arg(long, value_name = "BLOCKS", default_value_t = DEFAULT_ETH_PROOF_WINDOW)]
+    pub rpc_eth_proof_window: u64,
+
+    /// Continuously rebuild the pending block from the transaction pool in the background.
+    ///
+    /// Pending-state `eth_call`, `eth_estimateGas` and `eth_getBalance` requests are then served
+    /// from the post-state of this block, and `newPendingBlock` subscriptions are enabled.
+    #[arg(long)]
+    pub rpc_continuous_pending_block: bool,
+
     /// State cache configuration.
     #[command(flatten)]
     pub rpc_state_cache: RpcStateCacheArgs,
@@ -295,6 +322,7 @@ impl RpcServerArgs {
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + BlobSidecarReader
             + Clone
             + Unpin
             + 'static,
@@ -376,6 +404,9 @@ impl RethRpcConfig for RpcServerArgs {
             .max_blocks_per_filter(self.rpc_max_blocks_per_filter.unwrap_or_max())
             .max_logs_per_response(self.rpc_max_logs_per_response.unwrap_or_max() as usize)
             .rpc_gas_cap(self.rpc_gas_cap)
+            .max_simulate_blocks(self.rpc_max_simulate_blocks)
+            .eth_proof_window(self.rpc_eth_proof_window)
+            .continuous_pending_block(self.rpc_continuous_pending_block)
             .state_cache(self.state_cache_config())
             .gpo_config(self.gas_price_oracle_config())
     }
@@ -523,6 +554,9 @@ impl Default for RpcServerArgs {
             rpc_max_blocks_per_filter: constants::DEFAULT_MAX_BLOCKS_PER_FILTER.into(),
             rpc_max_logs_per_response: (constants::DEFAULT_MAX_LOGS_PER_RESPONSE as u64).into(),
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            rpc_max_simulate_blocks: DEFAULT_MAX_SIMULATE_BLOCKS,
+            rpc_eth_proof_window: DEFAULT_ETH_PROOF_WINDOW,
+            rpc_continuous_pending_block: false,
             gas_price_oracle: GasPriceOracleArgs::default(),
             rpc_state_cache: RpcStateCacheArgs::default(),
         }
@@ -590,6 +624,39 @@ mod tests {
         assert!(args.is_err());
     }
 
+    #[test]
+    fn test_rpc_max_simulate_blocks() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config();
+        assert_eq!(config.max_simulate_blocks, DEFAULT_MAX_SIMULATE_BLOCKS);
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc-max-simulate-blocks", "16"])
+                .args;
+        let config = args.eth_config();
+        assert_eq!(config.max_simulate_blocks, 16);
+
+        let args = CommandParser::<RpcServerArgs>::try_parse_from([
+            "reth",
+            "--rpc-max-simulate-blocks",
+            "0",
+        ]);
+        assert!(args.is_err());
+    }
+
+    #[test]
+    fn test_rpc_eth_proof_window() {
+        let args = CommandParser::<RpcServerArgs>::parse_from(["reth"]).args;
+        let config = args.eth_config();
+        assert_eq!(config.eth_proof_window, DEFAULT_ETH_PROOF_WINDOW);
+
+        let args =
+            CommandParser::<RpcServerArgs>::parse_from(["reth", "--rpc-eth-proof-window", "128"])
+                .args;
+        let config = args.eth_config();
+        assert_eq!(config.eth_proof_window, 128);
+    }
+
     #[test]
     fn test_rpc_server_args_parser() {
         let args =
@@ -7,7 +7,8 @@ use reth_transaction_pool::{
     blobstore::disk::DEFAULT_MAX_CACHED_BLOBS, validate::DEFAULT_MAX_TX_INPUT_BYTES,
     LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
     REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
-    TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+    TXPOOL_MAX_REMOVAL_HISTORY_DEFAULT, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+    TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
 };
 /// Parameters for debugging purposes
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
@@ -54,6 +55,19 @@ pub struct TxPoolArgs {
     #[arg(long = "txpool.max_cached_entries", default_value_t = DEFAULT_MAX_CACHED_BLOBS)]
     pub max_cached_entries: u32,
 
+    /// Max number of removed transactions to keep a record of, exposed via
+    /// `txpool_removalReason` and `txpool_history`
+    #[arg(long = "txpool.max_removal_history", default_value_t = TXPOOL_MAX_REMOVAL_HISTORY_DEFAULT)]
+    pub max_removal_history: usize,
+
+    /// Archive the blob sidecars of included blob transactions, exposed via
+    /// `reth_getBlobSidecars`.
+    ///
+    /// Unless configured otherwise, archived sidecars are pruned after the blob retention window
+    /// of the consensus layer.
+    #[arg(long = "blobpool.archive")]
+    pub blob_archive: bool,
+
     /// Flag to disable local transaction exemptions.
     #[arg(long = "txpool.nolocals")]
     pub no_locals: bool,
@@ -79,6 +93,8 @@ impl Default for TxPoolArgs {
             blob_transaction_price_bump: REPLACE_BLOB_PRICE_BUMP,
             max_tx_input_bytes: DEFAULT_MAX_TX_INPUT_BYTES,
             max_cached_entries: DEFAULT_MAX_CACHED_BLOBS,
+            max_removal_history: TXPOOL_MAX_REMOVAL_HISTORY_DEFAULT,
+            blob_archive: false,
             no_locals: false,
             locals: Default::default(),
             no_local_transactions_propagation: false,
@@ -116,6 +132,7 @@ impl RethTransactionPoolConfig for TxPoolArgs {
                 default_price_bump: self.price_bump,
                 replace_blob_tx_price_bump: self.blob_transaction_price_bump,
             },
+            max_removal_history: self.max_removal_history,
         }
     }
 }
@@ -316,6 +316,13 @@ impl<D> ChainPath<D> {
         self.0.join("txpool-transactions-backup.rlp").into()
     }
 
+    /// Returns the path to the directory of the write-ahead logs of the ExEx's for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/exex/wal`
+    pub fn exex_wal_path(&self) -> PathBuf {
+        self.0.join("exex").join("wal").into()
+    }
+
     /// Returns the path to the config file for this chain.
     ///
     /// `<DIR>/<CHAIN_ID>/reth.toml`
@@ -2,8 +2,8 @@
 
 use crate::{
     args::{
-        get_secret_key, DatabaseArgs, DebugArgs, DevArgs, DiscoveryArgs, NetworkArgs,
-        PayloadBuilderArgs, PruningArgs, RpcServerArgs, TxPoolArgs,
+        get_secret_key, DatabaseArgs, DebugArgs, DevArgs, DiscoveryArgs, EngineArgs, ExExArgs,
+        NetworkArgs, PayloadBuilderArgs, PruningArgs, RpcServerArgs, TxPoolArgs,
     },
     dirs::{ChainPath, DataDirPath},
     metrics::prometheus_exporter,
@@ -146,6 +146,12 @@ pub struct NodeConfig {
 
     /// All pruning related arguments
     pub pruning: PruningArgs,
+
+    /// All engine related arguments with --engine prefix
+    pub engine: EngineArgs,
+
+    /// All ExEx related arguments with --exex prefix
+    pub exex: ExExArgs,
 }
 
 impl NodeConfig {
@@ -234,6 +240,18 @@ impl NodeConfig {
         self
     }
 
+    /// Set the engine args for the node
+    pub fn with_engine(mut self, engine: EngineArgs) -> Self {
+        self.engine = engine;
+        self
+    }
+
+    /// Set the ExEx args for the node
+    pub fn with_exex(mut self, exex: ExExArgs) -> Self {
+        self.exex = exex;
+        self
+    }
+
     /// Get the network secret from the given data dir
     pub fn network_secret(&self, data_dir: &ChainPath<DataDirPath>) -> eyre::Result<SecretKey> {
         let network_secret_path =
@@ -315,7 +333,16 @@ impl NodeConfig {
         info!(target: "reth::cli", "Connecting to P2P network");
         let secret_key = self.network_secret(data_dir)?;
         let default_peers_path = data_dir.known_peers_path();
-        Ok(self.load_network_config(config, client, executor, head, secret_key, default_peers_path))
+        let mut network_config = self.load_network_config(
+            config,
+            client,
+            executor,
+            head,
+            secret_key,
+            default_peers_path,
+        );
+        network_config.dns_tree_publisher_config = self.network.dns_publish.publisher_config()?;
+        Ok(network_config)
     }
 
     /// Create the [NetworkBuilder].
@@ -473,7 +500,7 @@ impl NodeConfig {
         secret_key: SecretKey,
         default_peers_path: PathBuf,
     ) -> NetworkConfig<C> {
-        let cfg_builder = self
+        let mut cfg_builder = self
             .network
             .network_config(config, self.chain.clone(), secret_key, default_peers_path)
             .with_task_executor(Box::new(executor))
@@ -489,6 +516,12 @@ impl NodeConfig {
                 self.network.discovery.port + self.instance - 1,
             ));
 
+        // eth/69 peers are told from which block on receipts can be requested
+        let prune_config = self.prune_config().ok().flatten().or_else(|| config.prune.clone());
+        if let Some(prune_config) = prune_config {
+            cfg_builder = cfg_builder.prune_modes(prune_config.segments);
+        }
+
         let config = cfg_builder.build(client);
 
         if !self.network.discovery.enable_discv5_discovery {
@@ -540,6 +573,8 @@ impl Default for NodeConfig {
             db: DatabaseArgs::default(),
             dev: DevArgs::default(),
             pruning: PruningArgs::default(),
+            engine: EngineArgs::default(),
+            exex: ExExArgs::default(),
         }
     }
 }
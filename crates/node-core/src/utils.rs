@@ -8,7 +8,7 @@ use reth_interfaces::p2p::{
     headers::client::{HeadersClient, HeadersRequest},
     priority::Priority,
 };
-use reth_network::NetworkManager;
+use reth_network::{peers::PeerStore, NetworkManager};
 use reth_primitives::{
     fs, BlockHashOrNumber, ChainSpec, HeadersDirection, SealedBlock, SealedHeader,
 };
@@ -17,7 +17,7 @@ use reth_rpc::{JwtError, JwtSecret};
 use std::{
     env::VarError,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex, PoisonError},
 };
 use tracing::{debug, info, trace, warn};
 
@@ -45,17 +45,37 @@ where
     C: BlockReader + Unpin,
 {
     if let Some(file_path) = persistent_peers_file {
-        let known_peers = network.all_peers().collect::<Vec<_>>();
-        if let Ok(known_peers) = serde_json::to_string_pretty(&known_peers) {
-            trace!(target: "reth::cli", peers_file =?file_path, num_peers=%known_peers.len(), "Saving current peers");
-            let parent_dir = file_path.parent().map(fs::create_dir_all).transpose();
-            match parent_dir.and_then(|_| fs::write(&file_path, known_peers)) {
-                Ok(_) => {
-                    info!(target: "reth::cli", peers_file=?file_path, "Wrote network peers to file");
-                }
-                Err(err) => {
-                    warn!(target: "reth::cli", %err, peers_file=?file_path, "Failed to write network peers to file");
-                }
+        write_peer_store_to_file(&network.peer_store(), &file_path);
+    }
+}
+
+/// Writes the [PeerStore] to the given file.
+///
+/// The store is written to a temporary file next to the given file first, which then replaces the
+/// given file, so a crash during the write never leaves a truncated file behind. Concurrent writes,
+/// like the periodic one and the one on shutdown, are serialized so they never share the temporary
+/// file.
+///
+/// Note: this is blocking and should not be called on the async runtime.
+pub fn write_peer_store_to_file(peer_store: &PeerStore, file_path: &Path) {
+    static WRITE_LOCK: Mutex<()> = Mutex::new(());
+
+    if let Ok(known_peers) = serde_json::to_string_pretty(peer_store) {
+        let _guard = WRITE_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+        trace!(target: "reth::cli", peers_file =?file_path, num_peers=%peer_store.peers.len(), "Saving current peers");
+        let mut tmp_path = file_path.as_os_str().to_owned();
+        tmp_path.push(".tmp");
+        let tmp_path = PathBuf::from(tmp_path);
+        let parent_dir = file_path.parent().map(fs::create_dir_all).transpose();
+        match parent_dir
+            .and_then(|_| fs::write(&tmp_path, known_peers))
+            .and_then(|_| fs::rename(&tmp_path, file_path))
+        {
+            Ok(_) => {
+                info!(target: "reth::cli", peers_file=?file_path, "Wrote network peers to file");
+            }
+            Err(err) => {
+                warn!(target: "reth::cli", %err, peers_file=?file_path, "Failed to write network peers to file");
             }
         }
     }
@@ -0,0 +1,52 @@
+use futures_util::future;
+use jsonrpsee::{core::client::ClientT, rpc_params, RpcModule};
+use reth::{
+    builder::{NodeBuilder, NodeHandle},
+    tasks::TaskManager,
+};
+use reth_node_core::{args::RpcServerArgs, node_config::NodeConfig};
+use reth_node_ethereum::EthereumNode;
+use tokio::sync::mpsc;
+
+#[tokio::test]
+async fn serves_exex_rpc_module() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let tasks = TaskManager::current();
+
+    let node_config =
+        NodeConfig::test().with_rpc(RpcServerArgs::default().with_unused_ports().with_http());
+
+    let (rpc_modules_tx, mut rpc_modules_rx) = mpsc::unbounded_channel();
+    let NodeHandle { node, node_exit_future: _ } = NodeBuilder::new(node_config)
+        .testing_node(tasks.executor())
+        .node(EthereumNode::default())
+        .install_exex("rpc", move |ctx| {
+            let rpc_modules_tx = rpc_modules_tx.clone();
+            async move {
+                let mut module = RpcModule::new(());
+                module.register_method("exex_hello", |_, _| "world")?;
+                ctx.register_rpc_module(module)?;
+                let _ = rpc_modules_tx.send(ctx.rpc_modules.clone());
+
+                Ok::<_, eyre::Report>(async move {
+                    let _ctx = ctx;
+                    future::pending::<eyre::Result<()>>().await
+                })
+            }
+        })
+        .launch()
+        .await?;
+
+    // the module registered while the exex was launched is served
+    let client = node.rpc_server_handles.rpc.http_client().expect("http server is enabled");
+    let response: String = client.request("exex_hello", rpc_params![]).await?;
+    assert_eq!(response, "world");
+
+    // modules can't be registered once the rpc server is launched
+    let rpc_modules = rpc_modules_rx.recv().await.expect("exex was launched");
+    let mut module = RpcModule::new(());
+    module.register_method("exex_late", |_, _| "late")?;
+    assert!(rpc_modules.register(module).is_err());
+
+    Ok(())
+}
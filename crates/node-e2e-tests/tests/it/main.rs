@@ -1,4 +1,5 @@
 mod dev;
 mod eth;
+mod exex;
 
 fn main() {}
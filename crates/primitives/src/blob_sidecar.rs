@@ -0,0 +1,106 @@
+use crate::{Bytes, B256};
+use alloy_primitives::FixedBytes;
+use reth_codecs::{derive_arbitrary, Compact};
+use serde::{Deserialize, Serialize};
+
+/// Size of the fixed-length prefix of an encoded [StoredBlobSidecar]:
+/// `versioned_hash` (32) + `tx_hash` (32) + `index` (8) + `commitment` (48) + `proof` (48).
+const STORED_BLOB_SIDECAR_PREFIX_LEN: usize = 32 + 32 + 8 + 48 + 48;
+
+/// A single blob of an included blob transaction, as archived in the `BlobSidecars` table.
+#[derive_arbitrary(compact)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredBlobSidecar {
+    /// Versioned hash of the blob's KZG commitment.
+    pub versioned_hash: B256,
+    /// Hash of the transaction that carried the blob.
+    pub tx_hash: B256,
+    /// Index of the blob within the block.
+    pub index: u64,
+    /// KZG commitment of the blob.
+    pub commitment: FixedBytes<48>,
+    /// KZG proof of the blob.
+    pub proof: FixedBytes<48>,
+    /// The blob data.
+    pub blob: Bytes,
+}
+
+#[cfg(feature = "c-kzg")]
+impl StoredBlobSidecar {
+    /// Splits the sidecar of the given blob transaction into one [StoredBlobSidecar] per blob.
+    ///
+    /// `first_index` is the block-wide index of the first blob of the transaction.
+    pub fn from_transaction_sidecar(
+        tx_hash: B256,
+        versioned_hashes: &[B256],
+        sidecar: &crate::BlobTransactionSidecar,
+        first_index: u64,
+    ) -> Vec<Self> {
+        versioned_hashes
+            .iter()
+            .zip(sidecar.blobs.iter())
+            .zip(sidecar.commitments.iter().zip(sidecar.proofs.iter()))
+            .enumerate()
+            .map(|(idx, ((versioned_hash, blob), (commitment, proof)))| Self {
+                versioned_hash: *versioned_hash,
+                tx_hash,
+                index: first_index + idx as u64,
+                commitment: FixedBytes::from_slice(commitment.as_slice()),
+                proof: FixedBytes::from_slice(proof.as_slice()),
+                blob: Bytes::copy_from_slice(blob.as_slice()),
+            })
+            .collect()
+    }
+}
+
+// NOTE: Manually encode the fixed-size fields, followed by the blob without a length prefix, so
+// that a full-size blob isn't copied again by the bitflag-based codec.
+impl Compact for StoredBlobSidecar {
+    fn to_compact<B>(self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        buf.put_slice(self.versioned_hash.as_slice());
+        buf.put_slice(self.tx_hash.as_slice());
+        buf.put_u64(self.index);
+        buf.put_slice(self.commitment.as_slice());
+        buf.put_slice(self.proof.as_slice());
+        buf.put_slice(&self.blob);
+        STORED_BLOB_SIDECAR_PREFIX_LEN + self.blob.len()
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let versioned_hash = B256::from_slice(&buf[..32]);
+        let tx_hash = B256::from_slice(&buf[32..64]);
+        let index = u64::from_be_bytes(buf[64..72].try_into().expect("slice has 8 bytes"));
+        let commitment = FixedBytes::from_slice(&buf[72..120]);
+        let proof = FixedBytes::from_slice(&buf[120..STORED_BLOB_SIDECAR_PREFIX_LEN]);
+        let blob = Bytes::copy_from_slice(&buf[STORED_BLOB_SIDECAR_PREFIX_LEN..len]);
+        (Self { versioned_hash, tx_hash, index, commitment, proof, blob }, &buf[len..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stored_blob_sidecar_roundtrip() {
+        let sidecar = StoredBlobSidecar {
+            versioned_hash: B256::with_last_byte(1),
+            tx_hash: B256::with_last_byte(2),
+            index: 3,
+            commitment: FixedBytes::repeat_byte(4),
+            proof: FixedBytes::repeat_byte(5),
+            blob: Bytes::from_static(&[6; 64]),
+        };
+
+        let mut buf = Vec::new();
+        let len = sidecar.clone().to_compact(&mut buf);
+        assert_eq!(len, buf.len());
+
+        let (decoded, rest) = StoredBlobSidecar::from_compact(&buf, len);
+        assert_eq!(decoded, sidecar);
+        assert!(rest.is_empty());
+    }
+}
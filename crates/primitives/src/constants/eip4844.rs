@@ -8,6 +8,16 @@ pub use alloy_eips::eip4844::{
     TARGET_BLOBS_PER_BLOCK, TARGET_DATA_GAS_PER_BLOCK, VERSIONED_HASH_VERSION_KZG,
 };
 
+/// Minimum number of epochs for which consensus clients have to serve blob sidecars.
+///
+/// See `MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS` in the Deneb p2p specification.
+pub const MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS: u64 = 4096;
+
+/// Number of blocks covered by [MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS], assuming a block in every
+/// slot.
+pub const BLOB_SIDECARS_RETENTION_BLOCKS: u64 =
+    MIN_EPOCHS_FOR_BLOB_SIDECARS_REQUESTS * super::EPOCH_SLOTS;
+
 #[cfg(feature = "c-kzg")]
 mod trusted_setup {
     use crate::kzg::KzgSettings;
@@ -19,6 +19,7 @@
 
 mod account;
 pub mod basefee;
+mod blob_sidecar;
 mod block;
 mod chain;
 #[cfg(feature = "zstd-codec")]
@@ -48,6 +49,7 @@ pub mod trie;
 mod withdrawal;
 
 pub use account::{Account, Bytecode};
+pub use blob_sidecar::StoredBlobSidecar;
 #[cfg(any(test, feature = "arbitrary"))]
 pub use block::{generate_valid_header, valid_header_strategy};
 pub use block::{
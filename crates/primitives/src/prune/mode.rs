@@ -57,6 +57,16 @@ impl PruneMode {
         }
     }
 
+    /// Returns the lowest block that is not pruned according to the prune mode and the provided
+    /// tip.
+    pub fn lowest_retained_block(&self, tip: BlockNumber) -> BlockNumber {
+        match self {
+            PruneMode::Full => tip + 1,
+            PruneMode::Distance(distance) => tip.saturating_sub(*distance),
+            PruneMode::Before(n) => *n,
+        }
+    }
+
     /// Returns true if the prune mode is [`PruneMode::Full`].
     pub fn is_full(&self) -> bool {
         matches!(self, Self::Full)
@@ -121,6 +131,19 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lowest_retained_block() {
+        let tip = 20000;
+        assert_eq!(PruneMode::Full.lowest_retained_block(tip), tip + 1);
+        assert_eq!(PruneMode::Distance(tip + 1).lowest_retained_block(tip), 0);
+
+        for mode in [PruneMode::Distance(100), PruneMode::Before(100)] {
+            let lowest = mode.lowest_retained_block(tip);
+            assert!(mode.should_prune(lowest - 1, tip));
+            assert!(!mode.should_prune(lowest, tip));
+        }
+    }
+
     #[test]
     fn test_should_prune() {
         let tip = 20000;
@@ -24,15 +24,24 @@ pub enum PruneSegment {
     Headers,
     /// Prune segment responsible for the `Transactions` table.
     Transactions,
+    /// Prune segment responsible for the `BlobSidecars` table.
+    BlobSidecars,
+    /// Segment of the `AccountsTrieChangeSets`, `StoragesTrieChangeSets`, `AccountsTrieHistory`
+    /// and `StoragesTrieHistory` tables. Not pruned, its checkpoint records the block before the
+    /// first one with trie changesets.
+    TrieHistory,
 }
 
 impl PruneSegment {
     /// Returns minimum number of blocks to left in the database for this segment.
     pub fn min_blocks(&self, purpose: PrunePurpose) -> u64 {
         match self {
-            Self::SenderRecovery | Self::TransactionLookup | Self::Headers | Self::Transactions => {
-                0
-            }
+            Self::SenderRecovery |
+            Self::TransactionLookup |
+            Self::Headers |
+            Self::Transactions |
+            Self::BlobSidecars |
+            Self::TrieHistory => 0,
             Self::Receipts if purpose.is_static_file() => 0,
             Self::ContractLogs | Self::AccountHistory | Self::StorageHistory => {
                 MINIMUM_PRUNING_DISTANCE
@@ -1,4 +1,4 @@
-use crate::{PruneMode, ReceiptsLogPruneConfig};
+use crate::{BlockNumber, PruneMode, ReceiptsLogPruneConfig};
 use serde::{Deserialize, Deserializer, Serialize};
 
 /// Minimum distance from the tip necessary for the node to work correctly:
@@ -43,6 +43,9 @@ pub struct PruneModes {
     /// The [BlockNumber](`crate::BlockNumber`) represents the starting block from which point
     /// onwards the receipts are preserved.
     pub receipts_log_filter: ReceiptsLogPruneConfig,
+    /// Blob sidecar archive pruning configuration.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob_sidecars: Option<PruneMode>,
 }
 
 impl PruneModes {
@@ -51,6 +54,21 @@ impl PruneModes {
         PruneModes::default()
     }
 
+    /// Returns the earliest block whose receipts are all retained at the provided tip, or `None` if
+    /// receipts are not pruned.
+    pub fn earliest_full_receipts_block(&self, tip: BlockNumber) -> Option<BlockNumber> {
+        match self.receipts {
+            Some(mode) => Some(mode.lowest_retained_block(tip)),
+            // below the blocks of the filter, only the receipts of its addresses are retained
+            None => self
+                .receipts_log_filter
+                .0
+                .values()
+                .map(|mode| mode.lowest_retained_block(tip))
+                .max(),
+        }
+    }
+
     /// Sets pruning to all targets.
     pub fn all() -> Self {
         Self {
@@ -60,6 +78,7 @@ impl PruneModes {
             account_history: Some(PruneMode::Full),
             storage_history: Some(PruneMode::Full),
             receipts_log_filter: Default::default(),
+            blob_sidecars: Some(PruneMode::Full),
         }
     }
 }
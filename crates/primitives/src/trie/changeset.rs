@@ -0,0 +1,59 @@
+use super::{BranchNodeCompact, StoredBranchNode, StoredNibblesSubKey};
+use reth_codecs::Compact;
+use serde::{Deserialize, Serialize};
+
+/// Trie node as it was before the block that changed it.
+///
+/// A missing node means that the node did not exist before the block and was created by it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, PartialOrd, Ord)]
+pub struct TrieChangeSetEntry {
+    /// The nibbles of the intermediate node
+    pub nibbles: StoredNibblesSubKey,
+    /// Encoded node before the change, if any.
+    pub node: Option<BranchNodeCompact>,
+}
+
+// NOTE: Manually encoded for the same reason as `StorageTrieEntry`, the subkey must stay
+// uncompressed for `seek_by_key_subkey` to work.
+impl Compact for TrieChangeSetEntry {
+    fn to_compact<B>(self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        let nibbles_len = self.nibbles.to_compact(buf);
+        let node_len = self.node.map_or(0, |node| StoredBranchNode(node).to_compact(buf));
+        nibbles_len + node_len
+    }
+
+    fn from_compact(buf: &[u8], len: usize) -> (Self, &[u8]) {
+        let (nibbles, buf) = StoredNibblesSubKey::from_compact(buf, 65);
+        if len <= 65 {
+            return (Self { nibbles, node: None }, buf)
+        }
+        let (node, buf) = StoredBranchNode::from_compact(buf, len - 65);
+        (Self { nibbles, node: Some(node.0) }, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{trie::Nibbles, B256};
+
+    #[test]
+    fn trie_changeset_entry_roundtrip() {
+        let nibbles = StoredNibblesSubKey(Nibbles::from_nibbles_unchecked([0x1, 0x2, 0x3]));
+        let node = BranchNodeCompact::new(0b11, 0b01, 0b10, vec![B256::random()], None);
+
+        for entry in [
+            TrieChangeSetEntry { nibbles: nibbles.clone(), node: Some(node) },
+            TrieChangeSetEntry { nibbles, node: None },
+        ] {
+            let mut buf = Vec::new();
+            let len = entry.clone().to_compact(&mut buf);
+            let (decoded, rest) = TrieChangeSetEntry::from_compact(&buf, len);
+            assert_eq!(decoded, entry);
+            assert!(rest.is_empty());
+        }
+    }
+}
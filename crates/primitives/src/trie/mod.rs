@@ -6,6 +6,9 @@ pub mod hash_builder;
 mod account;
 pub use account::TrieAccount;
 
+mod changeset;
+pub use changeset::TrieChangeSetEntry;
+
 mod mask;
 pub(crate) use mask::StoredTrieMask;
 
@@ -16,7 +19,12 @@ pub mod nodes;
 pub use nodes::StoredBranchNode;
 
 mod proofs;
-pub use proofs::{AccountProof, StorageProof};
+pub use proofs::{
+    lookup_trie_value, verify_proof, AccountProof, ProofVerificationError, StorageProof,
+};
+
+mod sparse;
+pub use sparse::{SparseTrie, SparseTrieError, SparseTrieUpdates, TrieNodeProvider};
 
 mod storage;
 pub use storage::StorageTrieEntry;
@@ -24,4 +32,7 @@ pub use storage::StorageTrieEntry;
 mod subnode;
 pub use subnode::StoredSubNode;
 
+mod witness;
+pub use witness::{ExecutionWitness, WitnessError};
+
 pub use alloy_trie::{BranchNodeCompact, HashBuilder, TrieMask, EMPTY_ROOT_HASH};
@@ -1,7 +1,10 @@
 //! Merkle trie proofs.
 
-use super::Nibbles;
+use super::{Nibbles, EMPTY_ROOT_HASH};
 use crate::{keccak256, Account, Address, Bytes, B256, U256};
+use alloy_rlp::Header;
+use bytes::Buf;
+use std::collections::HashMap;
 
 /// The merkle proof with the relevant account info.
 #[derive(PartialEq, Eq, Default, Debug)]
@@ -84,3 +87,267 @@ impl StorageProof {
         self.proof = proof;
     }
 }
+
+/// Errors that can occur when verifying a merkle trie proof with [`verify_proof`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ProofVerificationError {
+    /// The proof ended before the key was resolved.
+    #[error("proof is missing nodes")]
+    MissingNode,
+    /// A proof node does not match the reference of its parent.
+    #[error("proof node hash mismatch: expected {expected}, got {got}")]
+    NodeHashMismatch {
+        /// The hash referenced by the parent node, or the root.
+        expected: B256,
+        /// The hash of the proof node.
+        got: B256,
+    },
+    /// A proof node is not a valid trie node.
+    #[error("invalid proof node")]
+    InvalidNode,
+    /// A proof node is not valid RLP.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The proven value does not match the expected value.
+    #[error("proven value does not match the expected value")]
+    ValueMismatch,
+}
+
+/// Verifies that `proof` proves the `value` of `key` in the trie with the given `root`.
+///
+/// The proof nodes are expected in order, starting with the root node, as returned by the
+/// [`HashBuilder`](super::HashBuilder) proof retainer. A `value` of `None` verifies that the key is
+/// absent from the trie.
+pub fn verify_proof<'a>(
+    root: B256,
+    key: &Nibbles,
+    value: Option<&[u8]>,
+    proof: impl IntoIterator<Item = &'a Bytes>,
+) -> Result<(), ProofVerificationError> {
+    let proven = if root == EMPTY_ROOT_HASH {
+        None
+    } else {
+        let mut proof = proof.into_iter().peekable();
+        walk_trie(root, key.as_slice(), |node| match node {
+            NodeRef::Hash(expected) => {
+                let node = proof.next().ok_or(ProofVerificationError::MissingNode)?;
+                let got = keccak256(node);
+                if got != expected {
+                    return Err(ProofVerificationError::NodeHashMismatch { expected, got })
+                }
+                Ok(node.to_vec())
+            }
+            NodeRef::Inline(node) => {
+                // inlined nodes are not necessarily part of the proof
+                if proof.peek().is_some_and(|proof_node| proof_node[..] == node[..]) {
+                    proof.next();
+                }
+                Ok(node)
+            }
+        })?
+    };
+    if proven.as_deref() != value {
+        return Err(ProofVerificationError::ValueMismatch)
+    }
+    Ok(())
+}
+
+/// Returns the value stored at `key` in the trie with the given root, resolving the nodes on the
+/// path by their hash from `nodes`.
+///
+/// Returns [`ProofVerificationError::MissingNode`] if a node on the path is not part of `nodes`.
+pub fn lookup_trie_value(
+    root: B256,
+    key: &Nibbles,
+    nodes: &HashMap<B256, Bytes>,
+) -> Result<Option<Vec<u8>>, ProofVerificationError> {
+    if root == EMPTY_ROOT_HASH {
+        return Ok(None)
+    }
+    walk_trie(root, key.as_slice(), |node| match node {
+        NodeRef::Hash(hash) => {
+            nodes.get(&hash).map(|node| node.to_vec()).ok_or(ProofVerificationError::MissingNode)
+        }
+        NodeRef::Inline(node) => Ok(node),
+    })
+}
+
+/// A reference to a child node, either by hash or the node itself if it is inlined.
+pub(super) enum NodeRef {
+    Hash(B256),
+    Inline(Vec<u8>),
+}
+
+/// Follows the path of `key` from the root and returns the value stored at the key.
+///
+/// Every node on the path is obtained from `resolve`.
+fn walk_trie(
+    root: B256,
+    key: &[u8],
+    mut resolve: impl FnMut(NodeRef) -> Result<Vec<u8>, ProofVerificationError>,
+) -> Result<Option<Vec<u8>>, ProofVerificationError> {
+    let mut next = NodeRef::Hash(root);
+    let mut walked = 0;
+    loop {
+        let node = resolve(next)?;
+
+        let items = rlp_list_items(&node)?;
+        match items.len() {
+            // branch node
+            17 => {
+                let Some(&nibble) = key.get(walked) else {
+                    let value = rlp_string(items[16])?;
+                    return Ok((!value.is_empty()).then(|| value.to_vec()))
+                };
+                walked += 1;
+                match child_ref(items[nibble as usize])? {
+                    Some(child) => next = child,
+                    None => return Ok(None),
+                }
+            }
+            // leaf or extension node
+            2 => {
+                let (is_leaf, path) = decode_path(rlp_string(items[0])?)?;
+                let rest = &key[walked..];
+                if is_leaf {
+                    if rest != path.as_slice() {
+                        return Ok(None)
+                    }
+                    return Ok(Some(rlp_string(items[1])?.to_vec()))
+                }
+                if !rest.starts_with(&path) {
+                    return Ok(None)
+                }
+                walked += path.len();
+                next = child_ref(items[1])?.ok_or(ProofVerificationError::InvalidNode)?;
+            }
+            _ => return Err(ProofVerificationError::InvalidNode),
+        }
+    }
+}
+
+/// Splits an RLP list into its raw encoded items.
+pub(super) fn rlp_list_items(mut buf: &[u8]) -> Result<Vec<&[u8]>, ProofVerificationError> {
+    let header = Header::decode(&mut buf)?;
+    if !header.list || header.payload_length != buf.len() {
+        return Err(ProofVerificationError::InvalidNode)
+    }
+    let mut items = Vec::with_capacity(17);
+    while !buf.is_empty() {
+        let item = buf;
+        let header = Header::decode(&mut buf)?;
+        if header.payload_length > buf.len() {
+            return Err(alloy_rlp::Error::InputTooShort.into())
+        }
+        buf.advance(header.payload_length);
+        items.push(&item[..item.len() - buf.len()]);
+    }
+    Ok(items)
+}
+
+/// Returns the payload of an RLP string.
+pub(super) fn rlp_string(mut item: &[u8]) -> Result<&[u8], ProofVerificationError> {
+    let header = Header::decode(&mut item)?;
+    if header.list {
+        return Err(alloy_rlp::Error::UnexpectedList.into())
+    }
+    Ok(&item[..header.payload_length])
+}
+
+/// Decodes the reference to a child node, `None` if there is no child.
+pub(super) fn child_ref(item: &[u8]) -> Result<Option<NodeRef>, ProofVerificationError> {
+    if item.first().is_some_and(|first| *first >= alloy_rlp::EMPTY_LIST_CODE) {
+        return Ok(Some(NodeRef::Inline(item.to_vec())))
+    }
+    match rlp_string(item)? {
+        [] => Ok(None),
+        hash if hash.len() == 32 => Ok(Some(NodeRef::Hash(B256::from_slice(hash)))),
+        _ => Err(ProofVerificationError::InvalidNode),
+    }
+}
+
+/// Decodes a hex-prefix encoded path, returns whether it is the path of a leaf node.
+pub(super) fn decode_path(encoded: &[u8]) -> Result<(bool, Vec<u8>), ProofVerificationError> {
+    let Some(&first) = encoded.first() else { return Err(ProofVerificationError::InvalidNode) };
+    let flag = first >> 4;
+    if flag > 3 {
+        return Err(ProofVerificationError::InvalidNode)
+    }
+    let mut path = Vec::with_capacity(encoded.len() * 2);
+    if flag & 1 == 1 {
+        path.push(first & 0x0f);
+    }
+    for byte in &encoded[1..] {
+        path.extend([byte >> 4, byte & 0x0f]);
+    }
+    Ok((flag & 2 == 2, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::HashBuilder;
+
+    #[test]
+    fn verify_retained_proofs() {
+        let leaves = (0u64..100)
+            .map(|i| (Nibbles::unpack(i.to_be_bytes()), keccak256(i.to_be_bytes()).to_vec()))
+            .collect::<Vec<_>>();
+        let targets = [0, 42, 99].map(|i| leaves[i].0.clone());
+        let absent = Nibbles::unpack(1_000u64.to_be_bytes());
+
+        let mut hash_builder = HashBuilder::default()
+            .with_proof_retainer(targets.iter().cloned().chain([absent.clone()]).collect());
+        for (key, value) in &leaves {
+            hash_builder.add_leaf(key.clone(), value);
+        }
+        let root = hash_builder.root();
+        let proofs = hash_builder.take_proofs();
+        let proof_of = |key: &Nibbles| {
+            proofs
+                .iter()
+                .filter(|(path, _)| key.starts_with(path))
+                .map(|(_, node)| node.clone())
+                .collect::<Vec<_>>()
+        };
+
+        for (target, i) in targets.iter().zip([0, 42, 99]) {
+            let proof = proof_of(target);
+            assert_eq!(verify_proof(root, target, Some(&leaves[i].1), &proof), Ok(()));
+            assert_eq!(
+                verify_proof(root, target, Some(&[1, 2, 3]), &proof),
+                Err(ProofVerificationError::ValueMismatch)
+            );
+            assert!(verify_proof(B256::ZERO, target, Some(&leaves[i].1), &proof).is_err());
+        }
+
+        assert_eq!(verify_proof(root, &absent, None, &proof_of(&absent)), Ok(()));
+        assert_eq!(verify_proof(EMPTY_ROOT_HASH, &absent, None, &[]), Ok(()));
+    }
+
+    #[test]
+    fn lookup_in_proof_nodes() {
+        let leaves = (0u64..100)
+            .map(|i| (Nibbles::unpack(i.to_be_bytes()), keccak256(i.to_be_bytes()).to_vec()))
+            .collect::<Vec<_>>();
+        let retained = Nibbles::unpack(7u64.to_be_bytes());
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(vec![retained.clone()]);
+        for (key, value) in &leaves {
+            hash_builder.add_leaf(key.clone(), value);
+        }
+        let root = hash_builder.root();
+        let nodes = hash_builder
+            .take_proofs()
+            .into_values()
+            .map(|node| (keccak256(&node), node))
+            .collect::<HashMap<_, _>>();
+
+        assert_eq!(lookup_trie_value(root, &retained, &nodes), Ok(Some(leaves[7].1.clone())));
+        assert_eq!(lookup_trie_value(EMPTY_ROOT_HASH, &retained, &HashMap::new()), Ok(None));
+        // the path to a leaf that is far away from the retained one is not part of the nodes
+        assert_eq!(
+            lookup_trie_value(root, &leaves[99].0, &nodes),
+            Err(ProofVerificationError::MissingNode)
+        );
+    }
+}
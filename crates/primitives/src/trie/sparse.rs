@@ -0,0 +1,894 @@
+//! Sparse merkle patricia trie.
+
+use super::{
+    proofs::{child_ref, decode_path, rlp_list_items, rlp_string, NodeRef},
+    BranchNodeCompact, Nibbles, ProofVerificationError, TrieMask, EMPTY_ROOT_HASH,
+};
+use crate::{keccak256, Bytes, B256};
+use alloy_rlp::{Encodable, Header, EMPTY_STRING_CODE};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Provides the RLP encoded trie nodes that are not revealed in a [SparseTrie].
+pub trait TrieNodeProvider {
+    /// Returns the node with the given hash at the given path, `None` if the node is unknown.
+    fn trie_node(&self, path: &Nibbles, hash: B256) -> Option<Bytes>;
+
+    /// Returns the branch node that is stored at the given path in the trie tables of the
+    /// database, `None` if no branch node is stored there.
+    ///
+    /// Only required to compute the updates of the trie tables, see
+    /// [SparseTrie::root_with_updates]. Fails with [SparseTrieError::StoredBranchNode] by default.
+    fn stored_branch_node(
+        &self,
+        path: &Nibbles,
+    ) -> Result<Option<BranchNodeCompact>, SparseTrieError> {
+        Err(SparseTrieError::StoredBranchNode(path.clone()))
+    }
+}
+
+impl TrieNodeProvider for HashMap<B256, Bytes> {
+    fn trie_node(&self, _path: &Nibbles, hash: B256) -> Option<Bytes> {
+        self.get(&hash).cloned()
+    }
+}
+
+/// Errors that can occur when accessing a [SparseTrie].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SparseTrieError {
+    /// A node that is required for the operation is not revealed and is not known to the node
+    /// provider.
+    #[error("blinded node {hash} at path {path:?}")]
+    BlindedNode {
+        /// The path of the node.
+        path: Nibbles,
+        /// The hash of the node.
+        hash: B256,
+    },
+    /// A revealed node is not a valid trie node.
+    #[error(transparent)]
+    InvalidNode(#[from] ProofVerificationError),
+    /// The key is a prefix of another key in the trie, or the other way around.
+    #[error("keys of variable length are not supported")]
+    KeyLength,
+    /// The stored branch node at the path could not be loaded to compute the trie updates.
+    #[error("stored branch node at path {0:?} is not available")]
+    StoredBranchNode(Nibbles),
+}
+
+impl From<alloy_rlp::Error> for SparseTrieError {
+    fn from(err: alloy_rlp::Error) -> Self {
+        Self::InvalidNode(err.into())
+    }
+}
+
+/// An in-memory merkle patricia trie of which only some nodes are known.
+///
+/// All subtries that are not revealed are represented by their hash. Nodes are revealed from a
+/// [TrieNodeProvider] when a key on their path is accessed, which makes it possible to read and
+/// update a trie given only the nodes on the paths of the keys that are touched, for example the
+/// nodes of a merkle proof.
+///
+/// Removing a key can require revealing the sibling of the removed leaf, if its parent branch node
+/// is left with a single child.
+///
+/// Only tries with keys of the same length are supported, branch nodes never hold a value.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseTrie {
+    root: SparseNode,
+    /// The paths of the branch nodes that have been removed since the updates were last computed.
+    removed_branches: BTreeSet<Nibbles>,
+}
+
+/// The changes to the branch nodes of a [SparseTrie] that are stored in the trie tables of the
+/// database, see [SparseTrie::root_with_updates].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseTrieUpdates {
+    /// The branch nodes that are inserted or updated, keyed by their path.
+    pub updated: BTreeMap<Nibbles, BranchNodeCompact>,
+    /// The paths of the branch nodes that are no longer stored.
+    pub removed: BTreeSet<Nibbles>,
+}
+
+impl SparseTrie {
+    /// Creates a new sparse trie with the given root hash and no revealed nodes.
+    pub fn new(root: B256) -> Self {
+        let root = if root == EMPTY_ROOT_HASH { SparseNode::Empty } else { SparseNode::Hash(root) };
+        Self { root, removed_branches: BTreeSet::new() }
+    }
+
+    /// Returns the value stored at the key.
+    pub fn get(
+        &mut self,
+        key: &Nibbles,
+        provider: &dyn TrieNodeProvider,
+    ) -> Result<Option<Vec<u8>>, SparseTrieError> {
+        get(&mut self.root, &mut Vec::new(), key.as_slice(), provider)
+    }
+
+    /// Inserts the value at the key, replacing the existing value.
+    pub fn insert(
+        &mut self,
+        key: &Nibbles,
+        value: Vec<u8>,
+        provider: &dyn TrieNodeProvider,
+    ) -> Result<(), SparseTrieError> {
+        insert(&mut self.root, &mut Vec::new(), key.as_slice(), value, provider)
+    }
+
+    /// Removes the key from the trie.
+    pub fn remove(
+        &mut self,
+        key: &Nibbles,
+        provider: &dyn TrieNodeProvider,
+    ) -> Result<(), SparseTrieError> {
+        remove(
+            &mut self.root,
+            &mut Vec::new(),
+            key.as_slice(),
+            provider,
+            &mut self.removed_branches,
+        )
+    }
+
+    /// Returns the root hash of the trie.
+    ///
+    /// The references of all modified nodes are recomputed and cached.
+    pub fn root(&mut self) -> B256 {
+        match self.root {
+            SparseNode::Empty => EMPTY_ROOT_HASH,
+            SparseNode::Hash(hash) => hash,
+            _ => {
+                let rlp = self.root.rlp();
+                if rlp.len() == B256::len_bytes() + 1 {
+                    B256::from_slice(&rlp[1..])
+                } else {
+                    keccak256(&rlp)
+                }
+            }
+        }
+    }
+
+    /// Returns the root hash of the trie and the changes to its stored branch nodes since the
+    /// updates were last computed.
+    ///
+    /// Branch nodes are stored if they have a child that is a branch node or a stored branch node
+    /// below them, with the same masks as the [HashBuilder](super::HashBuilder) computes. The masks
+    /// of unmodified children are taken from the stored parent, which is loaded from
+    /// [TrieNodeProvider::stored_branch_node] if the trie did not compute it itself.
+    ///
+    /// Once the updates of a trie are tracked, its root must only be computed with this method, as
+    /// [Self::root] does not keep track of the modified nodes.
+    pub fn root_with_updates(
+        &mut self,
+        provider: &dyn TrieNodeProvider,
+    ) -> Result<(B256, SparseTrieUpdates), SparseTrieError> {
+        let mut updates = SparseTrieUpdates::default();
+        update_masks(&mut self.root, &mut Vec::new(), provider, &mut updates)?;
+        let root = self.root();
+        if let Some(node) = updates.updated.get_mut(&Nibbles::default()) {
+            node.root_hash = Some(root);
+        }
+
+        let removed = std::mem::take(&mut self.removed_branches);
+        updates
+            .removed
+            .extend(removed.into_iter().filter(|path| !updates.updated.contains_key(path)));
+        Ok((root, updates))
+    }
+
+    /// Replaces the revealed nodes at the given depth or deeper with their hashes.
+    ///
+    /// Nodes that have been modified since the root was last computed and nodes that are embedded
+    /// in their parent are kept, so pruning never changes the root of the trie.
+    pub fn prune(&mut self, depth: usize) {
+        prune(&mut self.root, depth)
+    }
+}
+
+/// A node of the [SparseTrie].
+///
+/// Revealed nodes cache the reference to themselves in their parent, which is reset whenever the
+/// node or one of its descendants is modified.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+enum SparseNode {
+    /// The empty trie.
+    #[default]
+    Empty,
+    /// A node that is not revealed.
+    Hash(B256),
+    /// A leaf node with the remaining key.
+    Leaf { key: Vec<u8>, value: Vec<u8>, rlp: Option<Vec<u8>> },
+    /// An extension node.
+    Extension { key: Vec<u8>, child: Box<SparseNode>, rlp: Option<Vec<u8>> },
+    /// A branch node.
+    Branch { children: Box<[SparseNode; 16]>, rlp: Option<Vec<u8>>, masks: BranchMasks },
+}
+
+/// What is known about the masks of a branch node in the trie tables of the database, see
+/// [BranchNodeCompact].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum BranchMasks {
+    /// The branch node is revealed from the node provider, its stored masks are loaded when
+    /// needed.
+    Stored,
+    /// The branch node has been created by an update, its masks are derived from its children.
+    New,
+    /// The masks as of the last computation of the trie updates, which still hold for all children
+    /// that have not been modified since.
+    Known { tree_mask: TrieMask, hash_mask: TrieMask },
+}
+
+/// The bits of a node in the masks of its parent branch node.
+#[derive(Clone, Copy, Debug, Default)]
+struct ChildMasks {
+    /// The topmost branch node of the subtrie of the node is stored.
+    tree: bool,
+    /// The node is a branch node, whose hash is stored in its parent.
+    hash: bool,
+}
+
+impl SparseNode {
+    fn leaf(key: Vec<u8>, value: Vec<u8>) -> Self {
+        Self::Leaf { key, value, rlp: None }
+    }
+
+    /// Returns an extension with the given key to the child, or the child itself if the key is
+    /// empty.
+    fn extension(key: Vec<u8>, child: Self) -> Self {
+        if key.is_empty() {
+            return child
+        }
+        Self::Extension { key, child: Box::new(child), rlp: None }
+    }
+
+    /// Returns a branch node that is revealed from the node provider.
+    fn branch(children: [Self; 16]) -> Self {
+        Self::Branch { children: Box::new(children), rlp: None, masks: BranchMasks::Stored }
+    }
+
+    /// Returns a branch node that is created by an update.
+    fn new_branch(children: [Self; 16]) -> Self {
+        Self::Branch { children: Box::new(children), rlp: None, masks: BranchMasks::New }
+    }
+
+    /// Returns `true` if the node has been modified since the root was last computed.
+    fn is_modified(&self) -> bool {
+        match self {
+            Self::Leaf { rlp, .. } | Self::Extension { rlp, .. } => rlp.is_none(),
+            Self::Branch { rlp, masks, .. } => rlp.is_none() || *masks == BranchMasks::New,
+            Self::Empty | Self::Hash(_) => false,
+        }
+    }
+
+    /// Sets the cached reference of a revealed node.
+    fn with_rlp(mut self, reference: Vec<u8>) -> Self {
+        if let Self::Leaf { rlp, .. } | Self::Extension { rlp, .. } | Self::Branch { rlp, .. } =
+            &mut self
+        {
+            *rlp = Some(reference);
+        }
+        self
+    }
+
+    /// Resets the cached reference of the node.
+    fn reset_rlp(&mut self) {
+        if let Self::Leaf { rlp, .. } | Self::Extension { rlp, .. } | Self::Branch { rlp, .. } =
+            self
+        {
+            *rlp = None;
+        }
+    }
+
+    /// Returns the reference to this node in its parent, either the hash of the node or the node
+    /// itself if its encoding is shorter than 32 bytes.
+    fn rlp(&mut self) -> Vec<u8> {
+        match self {
+            Self::Empty => vec![EMPTY_STRING_CODE],
+            Self::Hash(hash) => hash_ref(*hash),
+            Self::Leaf { key, value, rlp } => rlp
+                .get_or_insert_with(|| {
+                    node_ref(encode_list(&[
+                        encode_bytes(&encode_path(key, true)),
+                        encode_bytes(value),
+                    ]))
+                })
+                .clone(),
+            Self::Extension { key, child, rlp } => {
+                if rlp.is_none() {
+                    let child = child.rlp();
+                    *rlp = Some(node_ref(encode_list(&[
+                        encode_bytes(&encode_path(key, false)),
+                        child,
+                    ])));
+                }
+                rlp.clone().unwrap_or_default()
+            }
+            Self::Branch { children, rlp, .. } => {
+                if rlp.is_none() {
+                    let mut items = children.iter_mut().map(Self::rlp).collect::<Vec<_>>();
+                    items.push(vec![EMPTY_STRING_CODE]);
+                    *rlp = Some(node_ref(encode_list(&items)));
+                }
+                rlp.clone().unwrap_or_default()
+            }
+        }
+    }
+}
+
+/// Reveals the node if it is only known by its hash.
+fn reveal(
+    node: &mut SparseNode,
+    path: &[u8],
+    provider: &dyn TrieNodeProvider,
+) -> Result<(), SparseTrieError> {
+    let SparseNode::Hash(hash) = *node else { return Ok(()) };
+    let path = Nibbles::from_nibbles_unchecked(path);
+    let Some(encoded) = provider.trie_node(&path, hash) else {
+        return Err(SparseTrieError::BlindedNode { path, hash })
+    };
+    let got = keccak256(&encoded);
+    if got != hash {
+        return Err(ProofVerificationError::NodeHashMismatch { expected: hash, got }.into())
+    }
+    *node = decode_node(&encoded)?.with_rlp(hash_ref(hash));
+    Ok(())
+}
+
+fn get(
+    node: &mut SparseNode,
+    path: &mut Vec<u8>,
+    key: &[u8],
+    provider: &dyn TrieNodeProvider,
+) -> Result<Option<Vec<u8>>, SparseTrieError> {
+    reveal(node, path, provider)?;
+    match node {
+        SparseNode::Empty | SparseNode::Hash(_) => Ok(None),
+        SparseNode::Leaf { key: leaf_key, value, .. } => {
+            Ok((leaf_key.as_slice() == key).then(|| value.clone()))
+        }
+        SparseNode::Extension { key: ext_key, child, .. } => {
+            let Some(rest) = key.strip_prefix(ext_key.as_slice()) else { return Ok(None) };
+            path.extend_from_slice(ext_key);
+            let value = get(child, path, rest, provider)?;
+            path.truncate(path.len() - ext_key.len());
+            Ok(value)
+        }
+        SparseNode::Branch { children, .. } => {
+            let Some((&nibble, rest)) = key.split_first() else { return Ok(None) };
+            path.push(nibble);
+            let value = get(&mut children[nibble as usize], path, rest, provider)?;
+            path.pop();
+            Ok(value)
+        }
+    }
+}
+
+fn insert(
+    node: &mut SparseNode,
+    path: &mut Vec<u8>,
+    key: &[u8],
+    value: Vec<u8>,
+    provider: &dyn TrieNodeProvider,
+) -> Result<(), SparseTrieError> {
+    reveal(node, path, provider)?;
+    node.reset_rlp();
+    match node {
+        SparseNode::Empty | SparseNode::Hash(_) => *node = SparseNode::leaf(key.to_vec(), value),
+        SparseNode::Leaf { key: leaf_key, value: leaf_value, .. } => {
+            if leaf_key.as_slice() == key {
+                *leaf_value = value;
+                return Ok(())
+            }
+            let common = common_prefix_length(leaf_key, key);
+            if common == leaf_key.len() || common == key.len() {
+                return Err(SparseTrieError::KeyLength)
+            }
+            let mut children = empty_children();
+            children[leaf_key[common] as usize] =
+                SparseNode::leaf(leaf_key[common + 1..].to_vec(), std::mem::take(leaf_value));
+            children[key[common] as usize] = SparseNode::leaf(key[common + 1..].to_vec(), value);
+            *node = SparseNode::extension(key[..common].to_vec(), SparseNode::new_branch(children));
+        }
+        SparseNode::Extension { key: ext_key, child, .. } => {
+            let common = common_prefix_length(ext_key, key);
+            if common == ext_key.len() {
+                path.extend_from_slice(ext_key);
+                insert(child, path, &key[common..], value, provider)?;
+                path.truncate(path.len() - ext_key.len());
+                return Ok(())
+            }
+            if common == key.len() {
+                return Err(SparseTrieError::KeyLength)
+            }
+            // split the extension at the first differing nibble
+            let mut children = empty_children();
+            children[ext_key[common] as usize] = SparseNode::extension(
+                ext_key[common + 1..].to_vec(),
+                std::mem::take(child.as_mut()),
+            );
+            children[key[common] as usize] = SparseNode::leaf(key[common + 1..].to_vec(), value);
+            *node = SparseNode::extension(key[..common].to_vec(), SparseNode::new_branch(children));
+        }
+        SparseNode::Branch { children, .. } => {
+            let Some((&nibble, rest)) = key.split_first() else {
+                return Err(SparseTrieError::KeyLength)
+            };
+            path.push(nibble);
+            insert(&mut children[nibble as usize], path, rest, value, provider)?;
+            path.pop();
+        }
+    }
+    Ok(())
+}
+
+fn remove(
+    node: &mut SparseNode,
+    path: &mut Vec<u8>,
+    key: &[u8],
+    provider: &dyn TrieNodeProvider,
+    removed_branches: &mut BTreeSet<Nibbles>,
+) -> Result<(), SparseTrieError> {
+    reveal(node, path, provider)?;
+    match node {
+        SparseNode::Empty | SparseNode::Hash(_) => {}
+        SparseNode::Leaf { key: leaf_key, .. } => {
+            if leaf_key.as_slice() == key {
+                *node = SparseNode::Empty;
+            }
+        }
+        SparseNode::Extension { key: ext_key, child, rlp } => {
+            let Some(rest) = key.strip_prefix(ext_key.as_slice()) else { return Ok(()) };
+            *rlp = None;
+            path.extend_from_slice(ext_key);
+            remove(child, path, rest, provider, removed_branches)?;
+            path.truncate(path.len() - ext_key.len());
+
+            // merge with the child if it is no longer a branch node
+            let merged = match child.as_mut() {
+                SparseNode::Empty => Some(SparseNode::Empty),
+                SparseNode::Leaf { key: child_key, value, .. } => Some(SparseNode::leaf(
+                    [ext_key.as_slice(), child_key.as_slice()].concat(),
+                    std::mem::take(value),
+                )),
+                SparseNode::Extension { key: child_key, child: grandchild, .. } => {
+                    Some(SparseNode::extension(
+                        [ext_key.as_slice(), child_key.as_slice()].concat(),
+                        std::mem::take(grandchild.as_mut()),
+                    ))
+                }
+                SparseNode::Hash(_) | SparseNode::Branch { .. } => None,
+            };
+            if let Some(merged) = merged {
+                *node = merged;
+            }
+        }
+        SparseNode::Branch { children, rlp, masks } => {
+            let Some((&nibble, rest)) = key.split_first() else { return Ok(()) };
+            *rlp = None;
+            path.push(nibble);
+            remove(&mut children[nibble as usize], path, rest, provider, removed_branches)?;
+            path.pop();
+
+            let mut non_empty = children
+                .iter()
+                .enumerate()
+                .filter(|(_, child)| !matches!(child, SparseNode::Empty))
+                .map(|(index, _)| index);
+            let (first, second) = (non_empty.next(), non_empty.next());
+            if second.is_none() && *masks != BranchMasks::New {
+                // the branch node may be stored in the database
+                removed_branches.insert(Nibbles::from_nibbles_unchecked(&path[..]));
+            }
+            match (first, second) {
+                (None, _) => *node = SparseNode::Empty,
+                (Some(index), None) => {
+                    // the branch node collapses into its only remaining child, which needs to be
+                    // revealed to merge their keys
+                    path.push(index as u8);
+                    reveal(&mut children[index], path, provider)?;
+                    path.pop();
+                    *node = match std::mem::take(&mut children[index]) {
+                        SparseNode::Leaf { key, value, .. } => {
+                            SparseNode::leaf([&[index as u8][..], key.as_slice()].concat(), value)
+                        }
+                        SparseNode::Extension { key, child, .. } => SparseNode::extension(
+                            [&[index as u8][..], key.as_slice()].concat(),
+                            *child,
+                        ),
+                        child => SparseNode::extension(vec![index as u8], child),
+                    };
+                }
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Computes the masks of the modified branch nodes and records the changes to the stored branch
+/// nodes, returning the bits of the node in the masks of its parent.
+fn update_masks(
+    node: &mut SparseNode,
+    path: &mut Vec<u8>,
+    provider: &dyn TrieNodeProvider,
+    updates: &mut SparseTrieUpdates,
+) -> Result<ChildMasks, SparseTrieError> {
+    match node {
+        SparseNode::Empty | SparseNode::Leaf { .. } => Ok(ChildMasks::default()),
+        SparseNode::Hash(_) => {
+            if provider.stored_branch_node(&Nibbles::from_nibbles_unchecked(&path[..]))?.is_some() {
+                return Ok(ChildMasks { tree: true, hash: true })
+            }
+            // the node is not a stored branch node, but may be a branch node or lead to one
+            reveal(node, path, provider)?;
+            if let SparseNode::Branch { .. } = node {
+                return Ok(ChildMasks { tree: false, hash: true })
+            }
+            update_masks(node, path, provider, updates)
+        }
+        SparseNode::Extension { key, child, .. } => {
+            path.extend_from_slice(key);
+            let child_masks = update_masks(child, path, provider, updates)?;
+            path.truncate(path.len() - key.len());
+            Ok(ChildMasks { tree: child_masks.tree, hash: false })
+        }
+        SparseNode::Branch { children, rlp, masks } => {
+            let nibbles = Nibbles::from_nibbles_unchecked(&path[..]);
+            let old_masks = match *masks {
+                BranchMasks::Known { tree_mask, hash_mask } => Some((tree_mask, hash_mask)),
+                // the root node is never stored, its masks are derived from its children
+                BranchMasks::Stored if !path.is_empty() => Some(
+                    provider
+                        .stored_branch_node(&nibbles)?
+                        .map(|node| (node.tree_mask, node.hash_mask))
+                        .unwrap_or_default(),
+                ),
+                BranchMasks::Stored | BranchMasks::New => None,
+            };
+            let was_stored =
+                old_masks.is_some_and(|(tree, hash)| !tree.is_empty() || !hash.is_empty());
+
+            if rlp.is_some() && *masks != BranchMasks::New {
+                return Ok(ChildMasks { tree: was_stored, hash: true })
+            }
+
+            let (mut state_mask, mut tree_mask, mut hash_mask) =
+                (TrieMask::default(), TrieMask::default(), TrieMask::default());
+            let mut hashes = Vec::new();
+            for (nibble, child) in children.iter_mut().enumerate() {
+                if matches!(child, SparseNode::Empty) {
+                    continue
+                }
+                let nibble = nibble as u8;
+                let child_masks = match old_masks {
+                    Some((tree, hash)) if !child.is_modified() => {
+                        ChildMasks { tree: tree.is_bit_set(nibble), hash: hash.is_bit_set(nibble) }
+                    }
+                    _ => {
+                        path.push(nibble);
+                        let child_masks = update_masks(child, path, provider, updates)?;
+                        path.pop();
+                        child_masks
+                    }
+                };
+
+                state_mask |= TrieMask::from_nibble(nibble);
+                if child_masks.tree {
+                    tree_mask |= TrieMask::from_nibble(nibble);
+                }
+                if child_masks.hash {
+                    hash_mask |= TrieMask::from_nibble(nibble);
+                    hashes.push(B256::from_slice(&child.rlp()[1..]));
+                }
+            }
+
+            let stored = !tree_mask.is_empty() || !hash_mask.is_empty();
+            if stored {
+                updates.updated.insert(
+                    nibbles,
+                    BranchNodeCompact::new(state_mask, tree_mask, hash_mask, hashes, None),
+                );
+            } else if was_stored {
+                updates.removed.insert(nibbles);
+            }
+            *masks = BranchMasks::Known { tree_mask, hash_mask };
+            Ok(ChildMasks { tree: stored, hash: true })
+        }
+    }
+}
+
+fn prune(node: &mut SparseNode, depth: usize) {
+    if depth == 0 {
+        let hash = match node {
+            SparseNode::Leaf { rlp, .. } |
+            SparseNode::Extension { rlp, .. } |
+            SparseNode::Branch { rlp, .. } => rlp
+                .as_ref()
+                .filter(|rlp| rlp.len() == B256::len_bytes() + 1)
+                .map(|rlp| B256::from_slice(&rlp[1..])),
+            SparseNode::Empty | SparseNode::Hash(_) => None,
+        };
+        if let Some(hash) = hash {
+            *node = SparseNode::Hash(hash);
+            return
+        }
+    }
+    match node {
+        SparseNode::Extension { child, .. } => prune(child, depth.saturating_sub(1)),
+        SparseNode::Branch { children, .. } => {
+            for child in children.iter_mut() {
+                prune(child, depth.saturating_sub(1));
+            }
+        }
+        SparseNode::Empty | SparseNode::Hash(_) | SparseNode::Leaf { .. } => {}
+    }
+}
+
+/// Decodes an RLP encoded trie node.
+fn decode_node(node: &[u8]) -> Result<SparseNode, ProofVerificationError> {
+    let items = rlp_list_items(node)?;
+    match items.len() {
+        17 => {
+            if !rlp_string(items[16])?.is_empty() {
+                return Err(ProofVerificationError::InvalidNode)
+            }
+            let mut children = empty_children();
+            for (child, item) in children.iter_mut().zip(&items[..16]) {
+                *child = decode_child(item)?;
+            }
+            Ok(SparseNode::branch(children))
+        }
+        2 => {
+            let (is_leaf, key) = decode_path(rlp_string(items[0])?)?;
+            if is_leaf {
+                return Ok(SparseNode::leaf(key, rlp_string(items[1])?.to_vec()))
+            }
+            match decode_child(items[1])? {
+                SparseNode::Empty => Err(ProofVerificationError::InvalidNode),
+                child => Ok(SparseNode::extension(key, child)),
+            }
+        }
+        _ => Err(ProofVerificationError::InvalidNode),
+    }
+}
+
+/// Decodes the reference to a child node.
+fn decode_child(item: &[u8]) -> Result<SparseNode, ProofVerificationError> {
+    Ok(match child_ref(item)? {
+        None => SparseNode::Empty,
+        Some(NodeRef::Hash(hash)) => SparseNode::Hash(hash),
+        Some(NodeRef::Inline(node)) => decode_node(&node)?.with_rlp(node),
+    })
+}
+
+fn empty_children() -> [SparseNode; 16] {
+    std::array::from_fn(|_| SparseNode::Empty)
+}
+
+fn common_prefix_length(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Encodes the nibbles with the hex-prefix encoding.
+fn encode_path(nibbles: &[u8], is_leaf: bool) -> Vec<u8> {
+    let flag = if is_leaf { 0x20 } else { 0x00 };
+    let mut encoded = Vec::with_capacity(nibbles.len() / 2 + 1);
+    let rest = if nibbles.len() % 2 == 1 {
+        encoded.push(flag | 0x10 | nibbles[0]);
+        &nibbles[1..]
+    } else {
+        encoded.push(flag);
+        nibbles
+    };
+    encoded.extend(rest.chunks_exact(2).map(|pair| (pair[0] << 4) | pair[1]));
+    encoded
+}
+
+/// Returns the RLP encoding of the byte string.
+fn encode_bytes(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(bytes.len() + 1);
+    bytes.encode(&mut encoded);
+    encoded
+}
+
+/// Returns the RLP list of the already encoded items.
+fn encode_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload_length = items.iter().map(Vec::len).sum();
+    let mut encoded = Vec::with_capacity(payload_length + 3);
+    Header { list: true, payload_length }.encode(&mut encoded);
+    for item in items {
+        encoded.extend_from_slice(item);
+    }
+    encoded
+}
+
+/// Returns the reference to an encoded node.
+fn node_ref(encoded: Vec<u8>) -> Vec<u8> {
+    if encoded.len() < B256::len_bytes() {
+        encoded
+    } else {
+        hash_ref(keccak256(&encoded))
+    }
+}
+
+/// Returns the RLP encoding of the hash.
+fn hash_ref(hash: B256) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(B256::len_bytes() + 1);
+    encoded.push(EMPTY_STRING_CODE + B256::len_bytes() as u8);
+    encoded.extend_from_slice(hash.as_slice());
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trie::HashBuilder;
+    use std::collections::BTreeMap;
+
+    /// Returns the root of the trie and its nodes on the paths of the targets.
+    fn trie(
+        leaves: &BTreeMap<Nibbles, Vec<u8>>,
+        targets: Vec<Nibbles>,
+    ) -> (B256, HashMap<B256, Bytes>) {
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(targets);
+        for (key, value) in leaves {
+            hash_builder.add_leaf(key.clone(), value);
+        }
+        let root = hash_builder.root();
+        let nodes =
+            hash_builder.take_proofs().into_values().map(|node| (keccak256(&node), node)).collect();
+        (root, nodes)
+    }
+
+    /// Returns the stored branch nodes of the trie.
+    fn branch_nodes(leaves: &BTreeMap<Nibbles, Vec<u8>>) -> HashMap<Nibbles, BranchNodeCompact> {
+        let mut hash_builder = HashBuilder::default().with_updates(true);
+        for (key, value) in leaves {
+            hash_builder.add_leaf(key.clone(), value);
+        }
+        hash_builder.root();
+        hash_builder.split().1
+    }
+
+    /// The trie nodes and the stored branch nodes of a trie in the database.
+    struct TestTrieNodes {
+        nodes: HashMap<B256, Bytes>,
+        branch_nodes: HashMap<Nibbles, BranchNodeCompact>,
+    }
+
+    impl TrieNodeProvider for TestTrieNodes {
+        fn trie_node(&self, path: &Nibbles, hash: B256) -> Option<Bytes> {
+            self.nodes.trie_node(path, hash)
+        }
+
+        fn stored_branch_node(
+            &self,
+            path: &Nibbles,
+        ) -> Result<Option<BranchNodeCompact>, SparseTrieError> {
+            Ok(self.branch_nodes.get(path).cloned())
+        }
+    }
+
+    #[test]
+    fn sparse_trie_matches_hash_builder() {
+        let mut leaves = (0u16..1000)
+            .step_by(3)
+            .map(|i| (Nibbles::unpack(i.to_be_bytes()), vec![i as u8 ^ 0x5a; 1 + i as usize % 40]))
+            .collect::<BTreeMap<_, _>>();
+        let (root, nodes) = trie(&leaves, leaves.keys().cloned().collect());
+
+        let mut sparse = SparseTrie::new(root);
+        for (key, value) in &leaves {
+            assert_eq!(sparse.get(key, &nodes).unwrap().as_ref(), Some(value));
+        }
+        assert_eq!(sparse.get(&Nibbles::unpack(1u16.to_be_bytes()), &nodes).unwrap(), None);
+        assert_eq!(sparse.root(), root);
+
+        for i in (0u16..1000).step_by(7) {
+            let key = Nibbles::unpack(i.to_be_bytes());
+            if i % 2 == 0 {
+                sparse.remove(&key, &nodes).unwrap();
+                leaves.remove(&key);
+            } else {
+                sparse.insert(&key, vec![i as u8; 3], &nodes).unwrap();
+                leaves.insert(key, vec![i as u8; 3]);
+            }
+        }
+        assert_eq!(sparse.root(), trie(&leaves, Vec::new()).0);
+
+        for key in leaves.keys() {
+            sparse.remove(key, &nodes).unwrap();
+        }
+        assert_eq!(sparse.root(), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn updates_match_hash_builder() {
+        let mut leaves = (0u16..2000)
+            .map(|i| (Nibbles::unpack(keccak256(i.to_be_bytes())), vec![i as u8; 32]))
+            .collect::<BTreeMap<_, _>>();
+        let (root, nodes) = trie(&leaves, leaves.keys().cloned().collect());
+        let mut db = TestTrieNodes { nodes, branch_nodes: branch_nodes(&leaves) };
+        let mut sparse = SparseTrie::new(root);
+
+        // update, insert and remove leaves
+        for i in (0u16..3000).step_by(5) {
+            let key = Nibbles::unpack(keccak256(i.to_be_bytes()));
+            if i % 3 == 0 {
+                sparse.remove(&key, &db).unwrap();
+                leaves.remove(&key);
+            } else {
+                sparse.insert(&key, vec![i as u8 ^ 0xff; 32], &db).unwrap();
+                leaves.insert(key, vec![i as u8 ^ 0xff; 32]);
+            }
+        }
+        // remove whole subtries, collapsing their stored branch nodes
+        let removed = leaves
+            .keys()
+            .filter(|key| (key[0] == 3 && key[1] < 8) || key[0] == 9)
+            .skip(1)
+            .cloned()
+            .collect::<Vec<_>>();
+        for key in removed {
+            sparse.remove(&key, &db).unwrap();
+            leaves.remove(&key);
+        }
+
+        for _ in 0..2 {
+            let (root, updates) = sparse.root_with_updates(&db).unwrap();
+            assert_eq!(root, trie(&leaves, Vec::new()).0);
+            for path in updates.removed {
+                db.branch_nodes.remove(&path);
+            }
+            db.branch_nodes.extend(updates.updated);
+            assert_eq!(db.branch_nodes, branch_nodes(&leaves));
+
+            // updates are only computed for the nodes that are modified since
+            let key = leaves.keys().nth(100).unwrap().clone();
+            sparse.insert(&key, vec![0; 32], &db).unwrap();
+            leaves.insert(key, vec![0; 32]);
+        }
+    }
+
+    #[test]
+    fn prune_keeps_root() {
+        let mut leaves = (0u16..500)
+            .map(|i| (Nibbles::unpack(keccak256(i.to_be_bytes())), vec![i as u8; 32]))
+            .collect::<BTreeMap<_, _>>();
+        let (root, nodes) = trie(&leaves, leaves.keys().cloned().collect());
+
+        let mut sparse = SparseTrie::new(root);
+        let key = Nibbles::unpack(keccak256(7u16.to_be_bytes()));
+        sparse.insert(&key, vec![1; 32], &nodes).unwrap();
+        let updated = sparse.root();
+
+        sparse.prune(1);
+        assert!(matches!(&sparse.root, SparseNode::Branch { .. }));
+        assert_eq!(sparse.root(), updated);
+
+        // modified nodes are kept until the root is recomputed
+        let mut sparse = SparseTrie::new(root);
+        sparse.insert(&key, vec![2; 32], &nodes).unwrap();
+        sparse.prune(0);
+        assert!(matches!(&sparse.root, SparseNode::Branch { .. }));
+        leaves.insert(key, vec![2; 32]);
+        assert_eq!(sparse.root(), trie(&leaves, Vec::new()).0);
+    }
+
+    #[test]
+    fn blinded_sibling() {
+        let removed = Nibbles::unpack(B256::with_last_byte(1));
+        let sibling = Nibbles::unpack(B256::with_last_byte(0x11));
+        let leaves =
+            BTreeMap::from([(removed.clone(), vec![1; 32]), (sibling.clone(), vec![2; 32])]);
+
+        // only reveal the path of the removed key
+        let (root, nodes) = trie(&leaves, vec![removed.clone()]);
+
+        let mut sparse = SparseTrie::new(root);
+        assert!(matches!(
+            sparse.remove(&removed, &nodes),
+            Err(SparseTrieError::BlindedNode { path, .. }) if path == Nibbles::from_nibbles_unchecked(&sibling[..63])
+        ));
+    }
+}
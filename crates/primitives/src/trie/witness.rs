@@ -0,0 +1,62 @@
+use super::{ProofVerificationError, SparseTrieError};
+use crate::{keccak256, BlockNumber, Bytes, GotExpected, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Everything that is required to execute a block without access to the database.
+///
+/// The witness contains the pre-state trie nodes on the paths of all accounts and storage slots
+/// that are accessed during execution of the block, the bytecodes of all contracts that are
+/// loaded, and the ancestor headers whose hashes are read with `BLOCKHASH`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionWitness {
+    /// The state root of the parent block, which is the root of the account trie the block is
+    /// executed on.
+    pub pre_state_root: B256,
+    /// RLP encoded account and storage trie nodes.
+    pub state: Vec<Bytes>,
+    /// Bytecodes of the contracts that are loaded during execution.
+    pub codes: Vec<Bytes>,
+    /// RLP encoded ancestor headers in ascending order, the last header is the parent of the
+    /// block.
+    pub headers: Vec<Bytes>,
+}
+
+impl ExecutionWitness {
+    /// Returns the trie nodes of the witness keyed by their hash.
+    pub fn state_nodes(&self) -> HashMap<B256, Bytes> {
+        self.state.iter().map(|node| (keccak256(node), node.clone())).collect()
+    }
+
+    /// Returns the bytecodes of the witness keyed by their code hash.
+    pub fn codes_by_hash(&self) -> HashMap<B256, Bytes> {
+        self.codes.iter().map(|code| (keccak256(code), code.clone())).collect()
+    }
+}
+
+/// Errors of an execution witness that does not contain the state required to execute a block.
+#[derive(Clone, Debug, thiserror::Error, PartialEq, Eq)]
+pub enum WitnessError {
+    /// The trie nodes of the witness do not resolve the accessed account or storage slot.
+    #[error(transparent)]
+    Trie(#[from] ProofVerificationError),
+    /// The state changes of the block can not be applied to the trie nodes of the witness.
+    #[error(transparent)]
+    SparseTrie(#[from] SparseTrieError),
+    /// A bytecode that is loaded during execution is not part of the witness.
+    #[error("bytecode {0} is missing")]
+    MissingBytecode(B256),
+    /// The hash of a block is read that is not covered by the witness headers.
+    #[error("no header for block #{0}")]
+    MissingBlockHash(BlockNumber),
+    /// A witness header is not a valid RLP encoded header.
+    #[error("header at index {0} is not a valid header")]
+    InvalidHeader(usize),
+    /// The witness headers do not form a chain that ends with the parent block.
+    #[error("headers do not connect to parent block {0}")]
+    DisconnectedHeaders(B256),
+    /// The pre-state root does not match the state root of the parent header.
+    #[error("pre-state root mismatch: {0}")]
+    PreStateRootMismatch(GotExpected<B256>),
+}
@@ -0,0 +1,165 @@
+use crate::{
+    segments::{PruneInput, PruneOutput, PruneOutputCheckpoint, Segment},
+    PrunerError,
+};
+use reth_db::{database::Database, models::BlockNumberVersionedHash, tables};
+use reth_primitives::{PruneMode, PruneProgress, PruneSegment};
+use reth_provider::DatabaseProviderRW;
+use tracing::{instrument, trace};
+
+#[derive(Debug)]
+pub struct BlobSidecars {
+    mode: PruneMode,
+}
+
+impl BlobSidecars {
+    pub fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<DB: Database> Segment<DB> for BlobSidecars {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::BlobSidecars
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<PruneOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No blob sidecars to prune");
+                return Ok(PruneOutput::done())
+            }
+        };
+        let range_end = *range.end();
+
+        let mut limiter = input.limiter;
+
+        let mut last_pruned_block = None;
+        let (pruned, done) = provider.prune_table_with_range::<tables::BlobSidecars>(
+            BlockNumberVersionedHash::range(range),
+            &mut limiter,
+            |_| false,
+            |row| last_pruned_block = Some(row.0.block_number()),
+        )?;
+        trace!(target: "pruner", %pruned, %done, "Pruned blob sidecars");
+
+        let last_pruned_block = match last_pruned_block {
+            // If there's more blob sidecars to prune, set the checkpoint block number to previous,
+            // so we could finish pruning its blob sidecars on the next run.
+            Some(block_number) if !done => block_number.checked_sub(1),
+            _ => Some(range_end),
+        };
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(PruneOutput {
+            progress,
+            pruned,
+            checkpoint: Some(PruneOutputCheckpoint {
+                block_number: last_pruned_block,
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{BlobSidecars, PruneInput, PruneOutput, Segment};
+    use assert_matches::assert_matches;
+    use reth_db::tables;
+    use reth_primitives::{
+        BlockNumber, PruneCheckpoint, PruneInterruptReason, PruneLimiter, PruneMode, PruneProgress,
+        PruneSegment, StoredBlobSidecar, B256,
+    };
+    use reth_provider::{BlobSidecarWriter, PruneCheckpointReader};
+    use reth_stages::test_utils::TestStageDB;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+
+        // Two blob sidecars for each of the blocks 1..=10
+        for block in 1..=10u64 {
+            let sidecars = (0..2)
+                .map(|index| StoredBlobSidecar {
+                    versioned_hash: B256::with_last_byte(index as u8),
+                    tx_hash: B256::with_last_byte(block as u8),
+                    index,
+                    ..Default::default()
+                })
+                .collect();
+            db.factory.insert_blob_sidecars(block, sidecars).expect("insert blob sidecars");
+        }
+        assert_eq!(db.table::<tables::BlobSidecars>().unwrap().len(), 20);
+
+        let test_prune = |to_block: BlockNumber,
+                          expected_result: (PruneProgress, usize),
+                          expected_checkpoint: Option<BlockNumber>,
+                          expected_remaining: usize| {
+            let prune_mode = PruneMode::Before(to_block);
+            let segment = BlobSidecars::new(prune_mode);
+            let input = PruneInput {
+                previous_checkpoint: db
+                    .factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::BlobSidecars)
+                    .unwrap(),
+                to_block,
+                limiter: PruneLimiter::default().set_deleted_entries_limit(10),
+            };
+
+            let provider = db.factory.provider_rw().unwrap();
+            let result = segment.prune(&provider, input).unwrap();
+
+            assert_matches!(
+                result,
+                PruneOutput {progress, pruned, checkpoint: Some(_)}
+                    if (progress, pruned) == expected_result
+            );
+
+            segment
+                .save_checkpoint(
+                    &provider,
+                    result.checkpoint.unwrap().as_prune_checkpoint(prune_mode),
+                )
+                .unwrap();
+            provider.commit().expect("commit");
+
+            assert_eq!(db.table::<tables::BlobSidecars>().unwrap().len(), expected_remaining);
+            assert_eq!(
+                db.factory
+                    .provider()
+                    .unwrap()
+                    .get_prune_checkpoint(PruneSegment::BlobSidecars)
+                    .unwrap(),
+                Some(PruneCheckpoint {
+                    block_number: expected_checkpoint,
+                    tx_number: None,
+                    prune_mode
+                })
+            );
+        };
+
+        // Blocks 1..=5 are pruned, but as the limit is reached, the checkpoint stays at block 4
+        test_prune(
+            6,
+            (PruneProgress::HasMoreData(PruneInterruptReason::DeletedEntriesLimitReached), 10),
+            Some(4),
+            10,
+        );
+        test_prune(6, (PruneProgress::Finished, 2), Some(6), 8);
+        test_prune(10, (PruneProgress::Finished, 8), Some(10), 0);
+    }
+}
@@ -1,6 +1,6 @@
 use crate::segments::{
-    AccountHistory, Receipts, ReceiptsByLogs, Segment, SenderRecovery, StorageHistory,
-    TransactionLookup,
+    AccountHistory, BlobSidecars, Receipts, ReceiptsByLogs, Segment, SenderRecovery,
+    StorageHistory, TransactionLookup,
 };
 use reth_db::database::Database;
 use reth_primitives::PruneModes;
@@ -45,6 +45,7 @@ impl<DB: Database> SegmentSet<DB> {
             account_history,
             storage_history,
             receipts_log_filter,
+            blob_sidecars,
         } = prune_modes;
 
         SegmentSet::default()
@@ -63,6 +64,8 @@ impl<DB: Database> SegmentSet<DB> {
             .segment_opt(account_history.map(AccountHistory::new))
             // Storage history
             .segment_opt(storage_history.map(StorageHistory::new))
+            // Blob sidecars
+            .segment_opt(blob_sidecars.map(BlobSidecars::new))
     }
 }
 
@@ -35,6 +35,9 @@ pub mod stack;
 #[cfg(feature = "optimism")]
 pub mod optimism;
 
+/// Execution witness generation and stateless block execution.
+pub mod witness;
+
 /// Common test helpers
 #[cfg(test)]
 pub mod test_utils;
@@ -1,8 +1,11 @@
 use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
 use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{
-    keccak256, revm::config::revm_spec, trie::AccountProof, Account, Address, BlockNumber,
-    Bytecode, Bytes, ChainSpec, Head, Header, StorageKey, Transaction, B256, U256,
+    keccak256,
+    revm::config::revm_spec,
+    trie::{AccountProof, Nibbles},
+    Account, Address, BlockNumber, Bytecode, Bytes, ChainSpec, Head, Header, StorageKey,
+    Transaction, B256, U256,
 };
 
 #[cfg(not(feature = "optimism"))]
@@ -104,6 +107,18 @@ impl StateProvider for StateProviderTest {
     fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
         unimplemented!("proof generation is not supported")
     }
+
+    fn account_trie_nodes(&self, _targets: Vec<Nibbles>) -> ProviderResult<Vec<Bytes>> {
+        unimplemented!("proof generation is not supported")
+    }
+
+    fn storage_trie_nodes(
+        &self,
+        _hashed_address: B256,
+        _targets: Vec<Nibbles>,
+    ) -> ProviderResult<Vec<Bytes>> {
+        unimplemented!("proof generation is not supported")
+    }
 }
 
 /// Test EVM configuration.
@@ -0,0 +1,505 @@
+//! Execution witness generation and stateless block execution.
+//!
+//! An [ExecutionWitness] is generated by executing a block on top of its parent state through a
+//! [RecordingDatabase] that records every account, storage slot, bytecode and block hash that is
+//! read during execution. The trie nodes on the paths of the accessed accounts and storage slots
+//! are then collected from the [StateProvider] proofs.
+//!
+//! The block is verified from the witness alone with [reth_stateless], see [execute_with_witness].
+//!
+//! Removing a key from the trie collapses its parent branch node if only one child is left, which
+//! requires the node of the remaining sibling to compute the post-state root. These nodes are not on
+//! the path of any accessed key, so the witness also contains every node that is loaded while
+//! applying the state changes of the block to a [SparseStateTrie] of the witness nodes.
+
+use crate::{database::StateProviderDatabase, processor::EVMProcessor};
+use alloy_rlp::Encodable;
+use reth_evm::ConfigureEvm;
+use reth_interfaces::{
+    executor::BlockExecutionError,
+    provider::{ProviderResult, RootMismatch},
+};
+use reth_primitives::{
+    keccak256,
+    trie::{ExecutionWitness, Nibbles, WitnessError},
+    Address, BlockNumber, BlockWithSenders, Bytes, ChainSpec, Header, B256, KECCAK_EMPTY, U256,
+};
+use reth_provider::{
+    BlockExecutor, BundleStateWithReceipts, HeaderProvider, ProviderError, StateProvider,
+};
+use reth_stateless::{StatelessError, WitnessDatabase};
+use reth_trie::sparse::{SparseStateTrie, StateTrieNodeProvider};
+use revm::{
+    primitives::{AccountInfo, Bytecode},
+    Database, State,
+};
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, BTreeSet, HashMap},
+    sync::Arc,
+};
+
+/// The state that is read from a [RecordingDatabase].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct StateAccesses {
+    /// The accessed accounts and the storage slots that are read from them.
+    pub accounts: BTreeMap<Address, BTreeSet<B256>>,
+    /// The bytecodes that are loaded, keyed by their code hash.
+    pub codes: BTreeMap<B256, Bytes>,
+    /// The block hashes that are read, keyed by block number.
+    pub block_hashes: BTreeMap<BlockNumber, B256>,
+}
+
+/// A [Database] that records all state that is read from the wrapped database.
+#[derive(Debug)]
+pub struct RecordingDatabase<DB> {
+    /// The wrapped database.
+    inner: DB,
+    /// The recorded state accesses.
+    accesses: StateAccesses,
+}
+
+impl<DB> RecordingDatabase<DB> {
+    /// Creates a new [RecordingDatabase] that wraps the given database.
+    pub fn new(inner: DB) -> Self {
+        Self { inner, accesses: StateAccesses::default() }
+    }
+
+    /// Returns the state accesses that have been recorded so far.
+    pub fn accesses(&self) -> &StateAccesses {
+        &self.accesses
+    }
+
+    /// Consumes the database and returns the recorded state accesses.
+    pub fn into_accesses(self) -> StateAccesses {
+        self.accesses
+    }
+}
+
+impl<DB: Database> Database for RecordingDatabase<DB> {
+    type Error = DB::Error;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        self.accesses.accounts.entry(address).or_default();
+        self.inner.basic(address)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        let code = self.inner.code_by_hash(code_hash)?;
+        if code_hash != KECCAK_EMPTY {
+            self.accesses.codes.insert(code_hash, code.original_bytes());
+        }
+        Ok(code)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.accesses.accounts.entry(address).or_default().insert(B256::new(index.to_be_bytes()));
+        self.inner.storage(address, index)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        let hash = self.inner.block_hash(number)?;
+        if let Ok(number) = number.try_into() {
+            self.accesses.block_hashes.insert(number, hash);
+        }
+        Ok(hash)
+    }
+}
+
+/// Executes the block on top of the given state of its parent and returns the
+/// [ExecutionWitness] of the block.
+///
+/// The headers of the parent and of all blocks whose hash is read during execution are loaded
+/// from the `provider`.
+pub fn generate_execution_witness<EvmConfig, SP, Provider>(
+    chain_spec: Arc<ChainSpec>,
+    evm_config: EvmConfig,
+    state: SP,
+    provider: &Provider,
+    block: &BlockWithSenders,
+    total_difficulty: U256,
+) -> Result<ExecutionWitness, BlockExecutionError>
+where
+    EvmConfig: ConfigureEvm,
+    SP: StateProvider,
+    Provider: HeaderProvider,
+{
+    let parent = provider
+        .header(&block.parent_hash)?
+        .ok_or_else(|| ProviderError::HeaderNotFound(block.parent_hash.into()))?;
+
+    let mut db = RecordingDatabase::new(StateProviderDatabase::new(&state));
+    let output = {
+        let revm_state = State::builder()
+            .with_database_boxed(Box::new(&mut db))
+            .with_bundle_update()
+            .without_state_clear()
+            .build();
+        let mut executor = EVMProcessor::new_with_state(chain_spec, revm_state, evm_config);
+        executor.execute_and_verify_receipt(block, total_difficulty)?;
+        executor.take_output_state()
+    };
+    let accesses = db.into_accesses();
+
+    let nodes = WitnessTrieNodes::new(&state);
+    for (address, slots) in &accesses.accounts {
+        let slots = slots.iter().copied().collect::<Vec<_>>();
+        let proof = state.proof(*address, &slots)?;
+        nodes.extend(proof.proof);
+        for storage_proof in proof.storage_proofs {
+            nodes.extend(storage_proof.proof);
+        }
+    }
+
+    // load the siblings of the collapsing branch nodes
+    let updated = SparseStateTrie::new(parent.state_root).update(&output.hash_state_slow(), &nodes);
+    let nodes = nodes.into_nodes()?;
+    updated.map_err(|err| ProviderError::from(WitnessError::from(err)))?;
+
+    // all headers from the lowest block whose hash is read up to the parent are required to
+    // verify the hash chain
+    let lowest = accesses.block_hashes.keys().next().copied().unwrap_or(parent.number);
+    let mut headers = Vec::new();
+    for number in lowest..parent.number {
+        let header = provider
+            .header_by_number(number)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(number.into()))?;
+        headers.push(encode_header(&header));
+    }
+    headers.push(encode_header(&parent));
+
+    Ok(ExecutionWitness {
+        pre_state_root: parent.state_root,
+        state: nodes,
+        codes: accesses.codes.into_values().collect(),
+        headers,
+    })
+}
+
+/// Re-executes the block using only the state of the given witness.
+///
+/// This verifies the gas used, receipts root and logs bloom of the block, and the state root of
+/// the block from the witness trie nodes. Returns the state changes of the block.
+pub fn execute_with_witness<EvmConfig>(
+    chain_spec: Arc<ChainSpec>,
+    evm_config: EvmConfig,
+    block: &BlockWithSenders,
+    total_difficulty: U256,
+    witness: &ExecutionWitness,
+) -> Result<BundleStateWithReceipts, BlockExecutionError>
+where
+    EvmConfig: ConfigureEvm,
+{
+    let verified = reth_stateless::verify_block(&block.header, witness, |db| {
+        let revm_state = State::builder()
+            .with_database_boxed(Box::new(WitnessProviderDatabase(db)))
+            .with_bundle_update()
+            .without_state_clear()
+            .build();
+        let mut executor = EVMProcessor::new_with_state(chain_spec, revm_state, evm_config);
+        executor.execute_and_verify_receipt(block, total_difficulty)?;
+        Ok::<_, BlockExecutionError>(executor.take_output_state())
+    });
+    verified.map_err(|err| match err {
+        StatelessError::Execution(err) => err,
+        StatelessError::Witness(err) => ProviderError::from(err).into(),
+        StatelessError::StateRootMismatch(root) => {
+            ProviderError::StateRootMismatch(Box::new(RootMismatch {
+                root,
+                block_number: block.number,
+                block_hash: block.hash_slow(),
+            }))
+            .into()
+        }
+    })
+}
+
+/// A [WitnessDatabase] that returns the errors of the block executor.
+#[derive(Debug)]
+struct WitnessProviderDatabase(WitnessDatabase);
+
+impl Database for WitnessProviderDatabase {
+    type Error = ProviderError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        Ok(self.0.basic(address)?)
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        Ok(self.0.code_by_hash(code_hash)?)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        Ok(self.0.storage(address, index)?)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        Ok(self.0.block_hash(number)?)
+    }
+}
+
+/// The trie nodes of an [ExecutionWitness] that is being generated.
+///
+/// Nodes that are not part of the witness yet are loaded from the [StateProvider] and added to the
+/// witness.
+#[derive(Debug)]
+struct WitnessTrieNodes<'a, SP> {
+    /// The state the block is executed on.
+    state: &'a SP,
+    /// The trie nodes keyed by their hash.
+    nodes: RefCell<HashMap<B256, Bytes>>,
+    /// The first error that occurred while loading a node.
+    error: RefCell<Option<ProviderError>>,
+}
+
+impl<'a, SP: StateProvider> WitnessTrieNodes<'a, SP> {
+    fn new(state: &'a SP) -> Self {
+        Self { state, nodes: RefCell::default(), error: RefCell::default() }
+    }
+
+    /// Adds the nodes to the witness.
+    fn extend(&self, nodes: impl IntoIterator<Item = Bytes>) {
+        self.nodes.borrow_mut().extend(nodes.into_iter().map(|node| (keccak256(&node), node)));
+    }
+
+    /// Returns the node with the given hash, or loads the nodes and adds them to the witness.
+    fn load_node(
+        &self,
+        hash: B256,
+        load: impl FnOnce() -> ProviderResult<Vec<Bytes>>,
+    ) -> Option<Bytes> {
+        if let Some(node) = self.nodes.borrow().get(&hash) {
+            return Some(node.clone())
+        }
+        match load() {
+            Ok(loaded) => {
+                self.extend(loaded);
+                self.nodes.borrow().get(&hash).cloned()
+            }
+            Err(err) => {
+                self.error.borrow_mut().get_or_insert(err);
+                None
+            }
+        }
+    }
+
+    /// Returns the nodes of the witness in a deterministic order, or the first error that occurred
+    /// while loading a node.
+    fn into_nodes(self) -> ProviderResult<Vec<Bytes>> {
+        if let Some(err) = self.error.into_inner() {
+            return Err(err)
+        }
+        let nodes = self.nodes.into_inner().into_values().collect::<BTreeSet<_>>();
+        Ok(nodes.into_iter().collect())
+    }
+}
+
+impl<'a, SP: StateProvider> StateTrieNodeProvider for WitnessTrieNodes<'a, SP> {
+    fn account_node(&self, path: &Nibbles, hash: B256) -> Option<Bytes> {
+        self.load_node(hash, || self.state.account_trie_nodes(vec![path.clone()]))
+    }
+
+    fn storage_node(&self, hashed_address: B256, path: &Nibbles, hash: B256) -> Option<Bytes> {
+        self.load_node(hash, || self.state.storage_trie_nodes(hashed_address, vec![path.clone()]))
+    }
+}
+
+/// Returns the RLP encoding of the header.
+fn encode_header(header: &Header) -> Bytes {
+    let mut buf = Vec::with_capacity(header.length());
+    header.encode(&mut buf);
+    buf.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{StateProviderTest, TestEvmConfig};
+    use reth_db::{
+        tables,
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{
+        proofs::calculate_receipt_root, Account, Block, ChainSpecBuilder, Signature, StorageEntry,
+        Transaction, TransactionKind, TransactionSigned, TxLegacy,
+    };
+    use reth_provider::{test_utils::create_test_provider_factory, StateRootProvider};
+    use reth_trie::StateRoot;
+
+    #[test]
+    fn records_state_accesses() {
+        let address = Address::with_last_byte(1);
+        let code = Bytes::from_static(&[0x60, 0x00]);
+        let mut state = StateProviderTest::default();
+        state.insert_account(
+            address,
+            Account { nonce: 1, ..Default::default() },
+            Some(code.clone()),
+            HashMap::from([(B256::with_last_byte(2), U256::from(3))]),
+        );
+
+        let mut db = RecordingDatabase::new(StateProviderDatabase::new(state));
+        let info = db.basic(address).unwrap().unwrap();
+        db.code_by_hash(info.code_hash).unwrap();
+        assert_eq!(db.storage(address, U256::from(2)).unwrap(), U256::from(3));
+        db.basic(Address::with_last_byte(2)).unwrap();
+
+        let accesses = db.into_accesses();
+        assert_eq!(
+            accesses.accounts,
+            BTreeMap::from([
+                (address, BTreeSet::from([B256::with_last_byte(2)])),
+                (Address::with_last_byte(2), BTreeSet::new()),
+            ])
+        );
+        assert_eq!(accesses.codes, BTreeMap::from([(keccak256(&code), code)]));
+    }
+
+    #[test]
+    fn execute_with_generated_witness() {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().paris_activated().build());
+        let sender = Address::with_last_byte(1);
+        let contract = Address::with_last_byte(2);
+        // clears storage slot 0
+        let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0x55, 0x00]);
+        let code_hash = keccak256(&code);
+
+        // write the genesis state and trie
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let mut accounts = (3..20)
+            .map(|i| {
+                let account = Account { nonce: 0, balance: U256::from(i), bytecode_hash: None };
+                (Address::with_last_byte(i), account, Vec::new())
+            })
+            .collect::<Vec<_>>();
+        accounts.push((
+            sender,
+            Account { nonce: 0, balance: U256::from(1), bytecode_hash: None },
+            Vec::new(),
+        ));
+        accounts.push((
+            contract,
+            Account { nonce: 1, balance: U256::ZERO, bytecode_hash: Some(code_hash) },
+            vec![(B256::ZERO, U256::from(1)), (B256::with_last_byte(1), U256::from(2))],
+        ));
+        for (address, account, storage) in accounts {
+            let hashed_address = keccak256(address);
+            tx.put::<tables::PlainAccountState>(address, account).unwrap();
+            tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+            for (key, value) in storage {
+                tx.put::<tables::PlainStorageState>(address, StorageEntry { key, value }).unwrap();
+                tx.put::<tables::HashedStorages>(
+                    hashed_address,
+                    StorageEntry { key: keccak256(key), value },
+                )
+                .unwrap();
+            }
+        }
+        tx.put::<tables::Bytecodes>(code_hash, reth_primitives::Bytecode::new_raw(code)).unwrap();
+        let (state_root, updates) = StateRoot::from_tx(&tx).root_with_updates().unwrap();
+        updates.flush(&tx).unwrap();
+
+        let genesis = Header {
+            state_root,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(0),
+            ..Default::default()
+        };
+        let genesis_hash = genesis.hash_slow();
+        tx.put::<tables::CanonicalHeaders>(0, genesis_hash).unwrap();
+        tx.put::<tables::HeaderNumbers>(genesis_hash, 0).unwrap();
+        tx.put::<tables::Headers>(0, genesis.clone()).unwrap();
+        tx.commit().unwrap();
+
+        let transaction = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy {
+                chain_id: Some(chain_spec.chain.id()),
+                gas_limit: 100_000,
+                to: TransactionKind::Call(contract),
+                ..Default::default()
+            }),
+            Signature::default(),
+        );
+        let mut block = BlockWithSenders {
+            block: Block {
+                header: Header {
+                    number: 1,
+                    parent_hash: genesis_hash,
+                    beneficiary: Address::with_last_byte(3),
+                    ..genesis
+                },
+                body: vec![transaction],
+                ..Default::default()
+            },
+            senders: vec![sender],
+        };
+
+        // fill in the execution results of the block
+        let state = factory.latest().unwrap();
+        let mut executor = EVMProcessor::new_with_db(
+            chain_spec.clone(),
+            StateProviderDatabase::new(&state),
+            TestEvmConfig::default(),
+        );
+        let (receipts, gas_used) = executor.execute_transactions(&block, U256::ZERO).unwrap();
+        block.block.header.gas_used = gas_used;
+        block.block.header.receipts_root = calculate_receipt_root(
+            &receipts.into_iter().map(|receipt| receipt.with_bloom()).collect::<Vec<_>>(),
+        );
+        let mut executor = EVMProcessor::new_with_db(
+            chain_spec.clone(),
+            StateProviderDatabase::new(&state),
+            TestEvmConfig::default(),
+        );
+        executor.execute_and_verify_receipt(&block, U256::ZERO).unwrap();
+        block.block.header.state_root =
+            state.state_root(executor.take_output_state().state()).unwrap();
+
+        let witness = generate_execution_witness(
+            chain_spec.clone(),
+            TestEvmConfig::default(),
+            &state,
+            &factory,
+            &block,
+            U256::ZERO,
+        )
+        .unwrap();
+        let output = execute_with_witness(
+            chain_spec.clone(),
+            TestEvmConfig::default(),
+            &block,
+            U256::ZERO,
+            &witness,
+        )
+        .unwrap();
+
+        // the slot is cleared and the storage trie collapses into the leaf of the other slot, which
+        // is not read during execution
+        let sibling = state.proof(contract, &[B256::with_last_byte(1)]).unwrap().storage_proofs[0]
+            .proof
+            .last()
+            .cloned()
+            .unwrap();
+        assert!(witness.state.contains(&sibling));
+
+        // the post-state root is computed from the witness alone
+        let post_state_root = SparseStateTrie::new(witness.pre_state_root)
+            .update(&output.hash_state_slow(), &witness.state_nodes())
+            .unwrap();
+        assert_eq!(post_state_root, block.state_root);
+
+        // a block with a different state root is rejected
+        block.block.header.state_root = B256::ZERO;
+        assert!(matches!(
+            execute_with_witness(
+                chain_spec,
+                TestEvmConfig::default(),
+                &block,
+                U256::ZERO,
+                &witness
+            ),
+            Err(BlockExecutionError::LatestBlock(ProviderError::StateRootMismatch(_)))
+        ));
+    }
+}
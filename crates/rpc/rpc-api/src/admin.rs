@@ -1,6 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{AnyNode, NodeRecord};
-use reth_rpc_types::{admin::NodeInfo, PeerInfo};
+use reth_rpc_types::{admin::NodeInfo, AdminPeerEvent, ExtendedPeerInfo, PeerInfo};
 
 /// Admin namespace rpc interface that gives access to several non-standard RPC methods.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "admin"))]
@@ -34,11 +34,18 @@ pub trait AdminApi {
     #[method(name = "peers")]
     async fn peers(&self) -> RpcResult<Vec<PeerInfo>>;
 
-    /// Creates an RPC subscription which serves events received from the network.
+    /// Returns the same peers as `admin_peers`, extended with the state reth tracks for each
+    /// peer: its reputation, the traffic of the session and the statistics of the requests sent to
+    /// it.
+    #[method(name = "peersExtended")]
+    async fn peers_extended(&self) -> RpcResult<Vec<ExtendedPeerInfo>>;
+
+    /// Creates an RPC subscription which serves peer connect, disconnect and ban events of the
+    /// network.
     #[subscription(
         name = "peerEvents",
         unsubscribe = "peerEvents_unsubscribe",
-        item = String
+        item = AdminPeerEvent
     )]
     async fn subscribe_peer_events(&self) -> jsonrpsee::core::SubscriptionResult;
 
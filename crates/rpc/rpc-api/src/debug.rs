@@ -1,5 +1,5 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, Bytes, B256};
+use reth_primitives::{trie::ExecutionWitness, Address, BlockId, BlockNumberOrTag, Bytes, B256};
 use reth_rpc_types::{
     trace::geth::{
         BlockTraceResult, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
@@ -38,6 +38,11 @@ pub trait DebugApi {
     #[method(name = "getBadBlocks")]
     async fn bad_blocks(&self) -> RpcResult<Vec<RichBlock>>;
 
+    /// Returns the witness of the given block: the pre-state trie nodes, contract bytecodes and
+    /// ancestor headers that are required to execute the block without access to the database.
+    #[method(name = "executionWitness")]
+    async fn debug_execution_witness(&self, block_id: BlockId) -> RpcResult<ExecutionWitness>;
+
     /// Returns the structured logs created during the execution of EVM between two blocks
     /// (excluding start) as a JSON object.
     #[method(name = "traceChain")]
@@ -6,7 +6,8 @@ use reth_primitives::{
 use reth_rpc_types::{
     state::StateOverride, AccessListWithGasUsed, AnyTransactionReceipt, BlockOverrides, Bundle,
     EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Header, Index, RichBlock,
-    StateContext, SyncStatus, Transaction, TransactionRequest, Work,
+    SimulatePayload, SimulatedBlock, StateContext, SyncStatus, Transaction, TransactionRequest,
+    Work,
 };
 
 /// Eth rpc interface: <https://ethereum.github.io/execution-apis/api-documentation/>
@@ -194,6 +195,18 @@ pub trait EthApi {
         state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<EthCallResponse>>;
 
+    /// Simulates a sequence of blocks with calls on top of the given block, with optional state
+    /// and block overrides for every simulated block.
+    ///
+    /// The state root of the simulated blocks is not computed and always zero, so their block
+    /// hashes differ from the hashes of the same blocks if they were actually built.
+    #[method(name = "simulateV1")]
+    async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> RpcResult<Vec<SimulatedBlock>>;
+
     /// Generates an access list for a transaction.
     ///
     /// This method creates an [EIP2930](https://eips.ethereum.org/EIPS/eip-2930) type accessList based on a given Transaction.
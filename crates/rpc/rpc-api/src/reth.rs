@@ -1,5 +1,6 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
 use reth_primitives::{Address, BlockId, U256};
+use reth_rpc_types::{beacon::sidecar::BlobSidecarsResponse, FeeEstimates};
 use std::collections::HashMap;
 
 /// Reth API namespace for reth-specific methods
@@ -12,4 +13,28 @@ pub trait RethApi {
         &self,
         block_id: BlockId,
     ) -> RpcResult<HashMap<Address, U256>>;
+
+    /// Returns the archived blob sidecars of all blob transactions in a block.
+    ///
+    /// Requires the blob archive to be enabled.
+    #[method(name = "getBlobSidecars")]
+    async fn reth_get_blob_sidecars(&self, block_id: BlockId) -> RpcResult<BlobSidecarsResponse>;
+
+    /// Returns slow, normal and fast fee suggestions for the next blocks.
+    ///
+    /// The suggested max fees account for the configured number of consecutive full blocks.
+    #[method(name = "feeEstimates")]
+    async fn reth_fee_estimates(&self) -> RpcResult<FeeEstimates>;
+
+    /// Creates a subscription that streams the state changes of every block that is added to or
+    /// removed from the canonical chain.
+    ///
+    /// The subscription is closed with an error if it lags behind the canonical chain, because the
+    /// changes of the skipped blocks can't be sent anymore.
+    #[subscription(
+        name = "subscribeStateDiffs" => "subscription",
+        unsubscribe = "unsubscribeStateDiffs",
+        item = reth_rpc_types::BlockStateDiff
+    )]
+    async fn reth_subscribe_state_diffs(&self) -> jsonrpsee::core::SubscriptionResult;
 }
@@ -1,6 +1,9 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
-use reth_primitives::Address;
-use reth_rpc_types::txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus};
+use reth_primitives::{Address, B256};
+use reth_rpc_types::{
+    txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolStatus},
+    TxpoolRemovedTransaction,
+};
 
 /// Txpool rpc interface.
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "txpool"))]
@@ -33,4 +36,20 @@ pub trait TxPoolApi {
     /// See [here](https://geth.ethereum.org/docs/rpc/ns-txpool#txpool_content) for more details
     #[method(name = "content")]
     async fn txpool_content(&self) -> RpcResult<TxpoolContent>;
+
+    /// Returns why the transaction with the given hash was removed from the pool, e.g. because it
+    /// was mined, replaced, evicted or became stale.
+    ///
+    /// The pool only retains a bounded number of removal records, so this returns `null` for
+    /// transactions that are unknown or whose record was already evicted.
+    #[method(name = "removalReason")]
+    async fn txpool_removal_reason(
+        &self,
+        hash: B256,
+    ) -> RpcResult<Option<TxpoolRemovedTransaction>>;
+
+    /// Returns the retained removal records of all transactions sent by this address, oldest
+    /// first.
+    #[method(name = "history")]
+    async fn txpool_history(&self, address: Address) -> RpcResult<Vec<TxpoolRemovedTransaction>>;
 }
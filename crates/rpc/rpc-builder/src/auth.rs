@@ -87,7 +87,7 @@ where
         network,
         eth_cache.clone(),
         gas_oracle,
-        EthConfig::default().rpc_gas_cap,
+        EthConfig::default().api_config(),
         Box::new(executor.clone()),
         BlockingTaskPool::build().expect("failed to build tracing pool"),
         fee_history_cache,
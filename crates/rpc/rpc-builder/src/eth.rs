@@ -5,7 +5,8 @@ use reth_rpc::{
     eth::{
         cache::{EthStateCache, EthStateCacheConfig},
         gas_oracle::GasPriceOracleConfig,
-        EthFilterConfig, FeeHistoryCacheConfig, RPC_DEFAULT_GAS_CAP,
+        EthApiConfig, EthFilterConfig, FeeHistoryCacheConfig, DEFAULT_ETH_PROOF_WINDOW,
+        DEFAULT_MAX_SIMULATE_BLOCKS, RPC_DEFAULT_GAS_CAP,
     },
     EthApi, EthFilter, EthPubSub,
 };
@@ -44,14 +45,32 @@ pub struct EthConfig {
     ///
     /// Defaults to [RPC_DEFAULT_GAS_CAP]
     pub rpc_gas_cap: u64,
+    /// Maximum number of blocks that can be simulated in a single `eth_simulateV1` call.
+    ///
+    /// Defaults to [DEFAULT_MAX_SIMULATE_BLOCKS]
+    pub max_simulate_blocks: u64,
+    /// Maximum distance to the tip, in blocks, of the state `eth_getProof` is served for.
+    ///
+    /// Defaults to [DEFAULT_ETH_PROOF_WINDOW]
+    pub eth_proof_window: u64,
     ///
     /// Sets TTL for stale filters
     pub stale_filter_ttl: std::time::Duration,
     /// Settings for the fee history cache
     pub fee_history_cache: FeeHistoryCacheConfig,
+    /// Whether to continuously rebuild the pending block in the background.
+    pub continuous_pending_block: bool,
 }
 
 impl EthConfig {
+    /// Returns the config for the `eth` API handler.
+    pub fn api_config(&self) -> EthApiConfig {
+        EthApiConfig::default()
+            .gas_cap(self.rpc_gas_cap)
+            .max_simulate_blocks(self.max_simulate_blocks)
+            .eth_proof_window(self.eth_proof_window)
+    }
+
     /// Returns the filter config for the `eth_filter` handler.
     pub fn filter_config(&self) -> EthFilterConfig {
         EthFilterConfig::default()
@@ -73,8 +92,11 @@ impl Default for EthConfig {
             max_blocks_per_filter: DEFAULT_MAX_BLOCKS_PER_FILTER,
             max_logs_per_response: DEFAULT_MAX_LOGS_PER_RESPONSE,
             rpc_gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            max_simulate_blocks: DEFAULT_MAX_SIMULATE_BLOCKS,
+            eth_proof_window: DEFAULT_ETH_PROOF_WINDOW,
             stale_filter_ttl: DEFAULT_STALE_FILTER_TTL,
             fee_history_cache: FeeHistoryCacheConfig::default(),
+            continuous_pending_block: false,
         }
     }
 }
@@ -115,4 +137,22 @@ impl EthConfig {
         self.rpc_gas_cap = rpc_gas_cap;
         self
     }
+
+    /// Configures the maximum number of blocks that can be simulated in `eth_simulateV1`
+    pub fn max_simulate_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_simulate_blocks = max_blocks;
+        self
+    }
+
+    /// Configures the maximum distance to the tip for `eth_getProof`
+    pub fn eth_proof_window(mut self, window: u64) -> Self {
+        self.eth_proof_window = window;
+        self
+    }
+
+    /// Configures whether the pending block is continuously rebuilt in the background
+    pub fn continuous_pending_block(mut self, enabled: bool) -> Self {
+        self.continuous_pending_block = enabled;
+        self
+    }
 }
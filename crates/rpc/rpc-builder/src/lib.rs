@@ -20,8 +20,8 @@
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_node_api::ConfigureEvm;
 //! use reth_provider::{
-//!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     AccountReader, BlobSidecarReader, BlockReaderIdExt, CanonStateSubscriptions,
+//!     ChainSpecProvider, ChangeSetReader, EvmEnvProvider, StateProviderFactory,
 //! };
 //! use reth_rpc_builder::{
 //!     RethRpcModule, RpcModuleBuilder, RpcServerConfig, ServerBuilder, TransportRpcModuleConfig,
@@ -39,6 +39,7 @@
 //!         + BlockReaderIdExt
 //!         + ChainSpecProvider
 //!         + ChangeSetReader
+//!         + BlobSidecarReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
 //!         + Clone
@@ -80,8 +81,8 @@
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_node_api::{ConfigureEvm, EngineTypes};
 //! use reth_provider::{
-//!     AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-//!     ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+//!     AccountReader, BlobSidecarReader, BlockReaderIdExt, CanonStateSubscriptions,
+//!     ChainSpecProvider, ChangeSetReader, EvmEnvProvider, StateProviderFactory,
 //! };
 //! use reth_rpc::JwtSecret;
 //! use reth_rpc_api::EngineApiServer;
@@ -104,6 +105,7 @@
 //!         + BlockReaderIdExt
 //!         + ChainSpecProvider
 //!         + ChangeSetReader
+//!         + BlobSidecarReader
 //!         + StateProviderFactory
 //!         + EvmEnvProvider
 //!         + Clone
@@ -172,8 +174,8 @@ pub use reth_ipc::server::{Builder as IpcServerBuilder, Endpoint};
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_node_api::{ConfigureEvm, EngineTypes};
 use reth_provider::{
-    AccountReader, BlockReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
-    ChangeSetReader, EvmEnvProvider, StateProviderFactory,
+    AccountReader, BlobSidecarReader, BlockReader, BlockReaderIdExt, CanonStateSubscriptions,
+    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, StateProviderFactory,
 };
 use reth_rpc::{
     eth::{
@@ -247,6 +249,7 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + BlobSidecarReader
         + Clone
         + Unpin
         + 'static,
@@ -438,6 +441,7 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + BlobSidecarReader
         + Clone
         + Unpin
         + 'static,
@@ -734,6 +738,7 @@ impl RpcModuleSelection {
             + EvmEnvProvider
             + ChainSpecProvider
             + ChangeSetReader
+            + BlobSidecarReader
             + Clone
             + Unpin
             + 'static,
@@ -1035,10 +1040,15 @@ impl<Provider: ChainSpecProvider, Pool, Network, Tasks, Events, EvmConfig>
     RethModuleRegistry<Provider, Pool, Network, Tasks, Events, EvmConfig>
 where
     Network: NetworkInfo + Peers + Clone + 'static,
+    Tasks: TaskSpawner + Clone + 'static,
 {
     /// Instantiates AdminApi
     pub fn admin_api(&mut self) -> AdminApi<Network> {
-        AdminApi::new(self.network.clone(), self.provider.chain_spec())
+        AdminApi::with_spawner(
+            self.network.clone(),
+            self.provider.chain_spec(),
+            Box::new(self.executor.clone()),
+        )
     }
 
     /// Instantiates Web3Api
@@ -1070,6 +1080,7 @@ where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + BlobSidecarReader
         + Clone
         + Unpin
         + 'static,
@@ -1218,11 +1229,13 @@ where
                 self.modules
                     .entry(namespace)
                     .or_insert_with(|| match namespace {
-                        RethRpcModule::Admin => {
-                            AdminApi::new(self.network.clone(), self.provider.chain_spec())
-                                .into_rpc()
-                                .into()
-                        }
+                        RethRpcModule::Admin => AdminApi::with_spawner(
+                            self.network.clone(),
+                            self.provider.chain_spec(),
+                            Box::new(self.executor.clone()),
+                        )
+                        .into_rpc()
+                        .into(),
                         RethRpcModule::Debug => DebugApi::new(
                             self.provider.clone(),
                             eth_api.clone(),
@@ -1261,11 +1274,14 @@ where
                         .into_rpc()
                         .into(),
                         RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
-                        RethRpcModule::Reth => {
-                            RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
-                                .into_rpc()
-                                .into()
-                        }
+                        RethRpcModule::Reth => RethApi::new(
+                            self.provider.clone(),
+                            eth_api.clone(),
+                            self.events.clone(),
+                            Box::new(self.executor.clone()),
+                        )
+                        .into_rpc()
+                        .into(),
                         RethRpcModule::EthCallBundle => {
                             EthBundle::new(eth_api.clone(), self.blocking_pool_guard.clone())
                                 .into_rpc()
@@ -1290,6 +1306,7 @@ where
     /// This will spawn the required service tasks for [EthApi] for:
     ///   - [EthStateCache]
     ///   - [FeeHistoryCache]
+    ///   - the continuously rebuilt pending block, if enabled
     fn with_eth<F, R>(&mut self, f: F) -> R
     where
         F: FnOnce(&EthHandlers<Provider, Pool, Network, Events, EvmConfig>) -> R,
@@ -1342,7 +1359,7 @@ where
             self.network.clone(),
             cache.clone(),
             gas_oracle,
-            self.config.eth.rpc_gas_cap,
+            self.config.eth.api_config(),
             executor.clone(),
             blocking_task_pool.clone(),
             fee_history_cache,
@@ -1357,7 +1374,7 @@ where
             executor.clone(),
         );
 
-        let pubsub = EthPubSub::with_spawner(
+        let mut pubsub = EthPubSub::with_spawner(
             self.provider.clone(),
             self.pool.clone(),
             self.events.clone(),
@@ -1365,6 +1382,19 @@ where
             executor,
         );
 
+        if self.config.eth.continuous_pending_block {
+            pubsub = pubsub.with_pending_blocks(api.pending_block_notifications());
+
+            let new_canonical_blocks = self.events.canonical_state_stream();
+            let api = api.clone();
+            self.executor.spawn_critical(
+                "pending block task",
+                Box::pin(async move {
+                    api.maintain_pending_block(new_canonical_blocks).await;
+                }),
+            );
+        }
+
         EthHandlers { api, cache, filter, pubsub, blocking_task_pool }
     }
 
@@ -1439,8 +1469,20 @@ where
     }
 
     /// Instantiates RethApi
-    pub fn reth_api(&mut self) -> RethApi<Provider> {
-        RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [Self::eth_api]
+    pub fn reth_api(
+        &mut self,
+    ) -> RethApi<Provider, EthApi<Provider, Pool, Network, EvmConfig>, Events> {
+        let eth_api = self.eth_api();
+        RethApi::new(
+            self.provider.clone(),
+            eth_api,
+            self.events.clone(),
+            Box::new(self.executor.clone()),
+        )
     }
 }
 
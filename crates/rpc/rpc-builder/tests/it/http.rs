@@ -144,6 +144,8 @@ where
     AdminApiClient::add_trusted_peer(client, node.into()).await.unwrap();
     AdminApiClient::remove_trusted_peer(client, node.into()).await.unwrap();
     AdminApiClient::node_info(client).await.unwrap();
+    AdminApiClient::peers(client).await.unwrap();
+    AdminApiClient::peers_extended(client).await.unwrap();
 }
 
 async fn test_basic_eth_calls<C>(client: &C)
@@ -229,6 +231,7 @@ where
     DebugApiClient::raw_transaction(client, B256::default()).await.unwrap();
     DebugApiClient::raw_receipts(client, block_id).await.unwrap();
     assert!(is_unimplemented(DebugApiClient::bad_blocks(client).await.err().unwrap()));
+    assert!(DebugApiClient::debug_execution_witness(client, block_id).await.is_err());
 }
 
 async fn test_basic_net_calls<C>(client: &C)
@@ -0,0 +1,71 @@
+//! Blob sidecar types.
+//!
+//! See also <https://ethereum.github.io/beacon-APIs/#/Beacon/getBlobSidecars>
+
+use alloy_primitives::{Bytes, FixedBytes, B256};
+use serde::{Deserialize, Serialize};
+use serde_with::{serde_as, DisplayFromStr};
+
+/// The response to a request for the blob sidecars of a block: `getBlobSidecars`
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub struct BlobSidecarsResponse {
+    /// All archived blob sidecars of the block, ordered by their index.
+    pub data: Vec<BlobSidecar>,
+}
+
+/// A single blob of a block, as archived by the execution client.
+///
+/// Unlike the consensus layer's `BlobSidecar`, this carries the execution layer context of the
+/// blob instead of the signed beacon block header and the commitment inclusion proof.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlobSidecar {
+    /// Index of the blob within the block.
+    #[serde_as(as = "DisplayFromStr")]
+    pub index: u64,
+    /// The blob data.
+    pub blob: Bytes,
+    /// KZG commitment of the blob.
+    pub kzg_commitment: FixedBytes<48>,
+    /// KZG proof of the blob.
+    pub kzg_proof: FixedBytes<48>,
+    /// Versioned hash of the blob's KZG commitment.
+    pub versioned_hash: B256,
+    /// Hash of the transaction that carried the blob.
+    pub transaction_hash: B256,
+    /// Hash of the block that included the transaction.
+    pub block_hash: B256,
+    /// Number of the block that included the transaction.
+    #[serde_as(as = "DisplayFromStr")]
+    pub block_number: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_blob_sidecars_response() {
+        let s = r#"{
+  "data": [
+    {
+      "index": "1",
+      "blob": "0x0102",
+      "kzg_commitment": "0x93247f2209abcacf57b75a51dafae777f9dd38bc7053d1af526f220a7489a6d3a2753e5f3e8b1cfe39b56f43611df74a",
+      "kzg_proof": "0xa8f0a8d3e6f3c9d5c6b1e4a0b6f9e3d2c7a5b4e1f0d9c8b7a6e5d4c3b2a1f0e9d8c7b6a5f4e3d2c1b0a9f8e7d6c5b4a3",
+      "versioned_hash": "0x01cf8e0d4e9587369b2301d0790347320302cc0943d5a1884560367e8208d920",
+      "transaction_hash": "0xcf8e0d4e9587369b2301d0790347320302cc0943d5a1884560367e8208d920f2",
+      "block_hash": "0xcf8e0d4e9587369b2301d0790347320302cc0943d5a1884560367e8208d920f2",
+      "block_number": "19426587"
+    }
+  ]
+}"#;
+        let response: BlobSidecarsResponse = serde_json::from_str(s).unwrap();
+        assert_eq!(response.data[0].index, 1);
+        assert_eq!(response.data[0].block_number, 19426587);
+
+        let serialized = serde_json::to_value(&response).unwrap();
+        let deserialized: BlobSidecarsResponse = serde_json::from_value(serialized).unwrap();
+        assert_eq!(deserialized, response);
+    }
+}
@@ -0,0 +1,52 @@
+//! Reth specific fee estimation types.
+
+use alloy_primitives::{U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// Fee suggestions for the next blocks, as returned by `reth_feeEstimates`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimates {
+    /// Number of the block the estimates are based on.
+    pub block_number: U64,
+    /// Base fee of the next block.
+    pub base_fee_per_gas: U256,
+    /// Blob base fee of the next block, if EIP-4844 is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blob_base_fee_per_gas: Option<U256>,
+    /// Number of blocks the max fees remain sufficient for, even if all of them are full.
+    pub projection_blocks: U64,
+    /// Estimate for transactions that can wait for inclusion.
+    pub slow: FeeEstimate,
+    /// Estimate for regular transactions.
+    pub normal: FeeEstimate,
+    /// Estimate for transactions that should be included as soon as possible.
+    pub fast: FeeEstimate,
+}
+
+/// A single fee suggestion tier.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimate {
+    /// Suggested priority fee (tip) per gas.
+    pub max_priority_fee_per_gas: U256,
+    /// Suggested max fee per gas.
+    pub max_fee_per_gas: U256,
+    /// Suggested max fee per blob gas, if EIP-4844 is active.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_blob_gas: Option<U256>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_fee_estimates() {
+        let s = r#"{"blockNumber":"0x1","baseFeePerGas":"0x7","projectionBlocks":"0x3","slow":{"maxPriorityFeePerGas":"0x1","maxFeePerGas":"0x9"},"normal":{"maxPriorityFeePerGas":"0x2","maxFeePerGas":"0xa"},"fast":{"maxPriorityFeePerGas":"0x3","maxFeePerGas":"0xb"}}"#;
+        let estimates: FeeEstimates = serde_json::from_str(s).unwrap();
+        assert_eq!(estimates.normal.max_fee_per_gas, U256::from(10));
+        assert_eq!(estimates.blob_base_fee_per_gas, None);
+        assert_eq!(serde_json::to_string(&estimates).unwrap(), s);
+    }
+}
@@ -11,11 +11,17 @@
 
 pub mod beacon;
 mod eth;
+mod fees;
 mod mev;
 mod net;
 mod peer;
+mod peers;
+mod pool;
 pub mod relay;
 mod rpc;
+mod simulate;
+mod state_diff;
+mod subscription;
 
 // re-export for convenience
 pub use alloy_rpc_types::serde_helpers;
@@ -40,7 +46,13 @@ pub use eth::{
     transaction::{self, TransactionKind, TransactionRequest, TypedTransactionRequest},
 };
 
+pub use fees::*;
 pub use mev::*;
 pub use net::*;
 pub use peer::*;
+pub use peers::*;
+pub use pool::*;
 pub use rpc::*;
+pub use simulate::*;
+pub use state_diff::*;
+pub use subscription::*;
@@ -0,0 +1,103 @@
+//! Reth specific peer types of the `admin` namespace.
+
+use crate::{PeerId, PeerInfo};
+use serde::{Deserialize, Serialize};
+
+/// The info about a connected peer, as returned by `admin_peersExtended`.
+///
+/// Extends the geth compatible [`PeerInfo`] with the state reth tracks for the peer and its
+/// session.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtendedPeerInfo {
+    /// The geth compatible peer info.
+    #[serde(flatten)]
+    pub inner: PeerInfo,
+    /// The current reputation score of the peer.
+    pub reputation: i32,
+    /// Whether the session was initiated by the peer.
+    pub inbound: bool,
+    /// Whether the peer is trusted.
+    pub trusted: bool,
+    /// Number of bytes received from the peer.
+    pub ingress_bytes: u64,
+    /// Number of bytes sent to the peer.
+    pub egress_bytes: u64,
+    /// How long it took the peer to respond to the last request, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_request_latency_ms: Option<u64>,
+    /// Statistics on the sync requests served by the peer.
+    #[serde(default)]
+    pub request_stats: PeerRequestStats,
+}
+
+/// Statistics on the header and body requests a peer served during its session.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerRequestStats {
+    /// Number of requests sent to the peer.
+    pub requests: u64,
+    /// Number of requests that timed out.
+    pub timeouts: u64,
+    /// Number of requests answered with an error or an unusable response.
+    pub bad_responses: u64,
+    /// Total size of all successful responses, in bytes.
+    pub bytes_served: u64,
+    /// Rolling average latency of successful responses, in milliseconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_latency_ms: Option<u64>,
+    /// The score the peer is ranked by for sync requests, lower is better.
+    pub score: f64,
+}
+
+/// An event emitted by the `admin_peerEvents` subscription.
+///
+/// Note: this follows Geth's `p2p.PeerEvent` format, extended with ban events.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AdminPeerEvent {
+    /// The kind of the event.
+    #[serde(rename = "type")]
+    pub kind: AdminPeerEventKind,
+    /// The peer the event is about.
+    pub peer: PeerId,
+    /// The reason the session was closed, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// The remote address of the peer, if known.
+    #[serde(default, rename = "remote_addr", skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+}
+
+/// The kind of an [`AdminPeerEvent`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AdminPeerEventKind {
+    /// A session with the peer was established.
+    Add,
+    /// The session with the peer was closed.
+    Drop,
+    /// The peer was banned.
+    Ban,
+    /// The peer was unbanned.
+    Unban,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_admin_peer_event() {
+        let event = AdminPeerEvent {
+            kind: AdminPeerEventKind::Drop,
+            peer: PeerId::with_last_byte(1),
+            error: Some("too many peers".to_string()),
+            remote: None,
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["type"], "drop");
+        assert_eq!(json["error"], "too many peers");
+        assert!(json.get("remote_addr").is_none());
+        assert_eq!(serde_json::from_value::<AdminPeerEvent>(json).unwrap(), event);
+    }
+}
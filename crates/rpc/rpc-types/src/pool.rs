@@ -0,0 +1,66 @@
+//! Reth specific `txpool` namespace types.
+
+use alloy_primitives::{Address, B256, U64};
+use serde::{Deserialize, Serialize};
+
+/// The reason a transaction was removed from the pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "reason", rename_all = "camelCase")]
+pub enum TxpoolRemovalReason {
+    /// Transaction was included in a block.
+    #[serde(rename_all = "camelCase")]
+    Mined {
+        /// Hash of the block that included the transaction.
+        block_hash: B256,
+    },
+    /// Transaction was replaced by another transaction with the same sender and nonce.
+    #[serde(rename_all = "camelCase")]
+    Replaced {
+        /// Hash of the replacement transaction.
+        replaced_by: B256,
+    },
+    /// Transaction was evicted because the pool exceeded its size limits.
+    Evicted,
+    /// Transaction became outdated after a state change, e.g. its nonce is too low.
+    Stale,
+    /// Transaction became outdated after a reorg of the canonical chain.
+    Reorg,
+    /// Transaction failed validation.
+    Invalid,
+    /// Transaction was explicitly removed from the pool.
+    Removed,
+}
+
+/// A transaction that was removed from the pool, as returned by `txpool_removalReason` and
+/// `txpool_history`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxpoolRemovedTransaction {
+    /// Hash of the removed transaction.
+    pub hash: B256,
+    /// Sender of the removed transaction.
+    pub from: Address,
+    /// Nonce of the removed transaction.
+    pub nonce: U64,
+    /// Why the transaction was removed.
+    #[serde(flatten)]
+    pub reason: TxpoolRemovalReason,
+    /// Unix timestamp (in seconds) at which the transaction was removed.
+    pub timestamp: U64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_removed_transaction() {
+        let s = r#"{"hash":"0x0000000000000000000000000000000000000000000000000000000000000001","from":"0x0000000000000000000000000000000000000002","nonce":"0x3","reason":"replaced","replacedBy":"0x0000000000000000000000000000000000000000000000000000000000000004","timestamp":"0x5"}"#;
+        let removed: TxpoolRemovedTransaction = serde_json::from_str(s).unwrap();
+        assert_eq!(
+            removed.reason,
+            TxpoolRemovalReason::Replaced { replaced_by: B256::with_last_byte(4) }
+        );
+        assert_eq!(serde_json::to_string(&removed).unwrap(), s);
+    }
+}
@@ -0,0 +1,101 @@
+//! Types for `eth_simulateV1`.
+//!
+//! See also <https://github.com/ethereum/execution-apis/pull/484>
+
+use alloy_primitives::{Bytes, U64};
+use alloy_rpc_types::{state::StateOverride, Block, BlockOverrides, Log, TransactionRequest};
+use serde::{Deserialize, Serialize};
+
+/// The payload of an `eth_simulateV1` request.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// The blocks to simulate, in order.
+    pub block_state_calls: Vec<SimBlock>,
+    /// Whether to add a log for every ETH transfer to the logs of the calls.
+    #[serde(default)]
+    pub trace_transfers: bool,
+    /// Whether to validate the calls like transactions, e.g. nonces, balances and base fees.
+    #[serde(default)]
+    pub validation: bool,
+    /// Whether to return full transaction objects instead of transaction hashes.
+    #[serde(default)]
+    pub return_full_transactions: bool,
+}
+
+/// A block to simulate, with the calls to execute in it.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimBlock {
+    /// Overrides of the block environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_overrides: Option<BlockOverrides>,
+    /// Overrides of the state before the calls are executed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_overrides: Option<StateOverride>,
+    /// The calls to execute in the block.
+    #[serde(default)]
+    pub calls: Vec<TransactionRequest>,
+}
+
+/// A simulated block, as returned by `eth_simulateV1`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+    /// The simulated block.
+    #[serde(flatten)]
+    pub inner: Block,
+    /// The results of the calls of the block.
+    pub calls: Vec<SimCallResult>,
+}
+
+/// The result of a single simulated call.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimCallResult {
+    /// The data returned by the call.
+    pub return_data: Bytes,
+    /// The logs emitted by the call, including ETH transfers if requested.
+    pub logs: Vec<Log>,
+    /// The gas used by the call.
+    pub gas_used: U64,
+    /// `1` if the call succeeded, `0` otherwise.
+    pub status: U64,
+    /// The reason the call failed, if it did.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulateError>,
+}
+
+/// The error of a failed simulated call.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimulateError {
+    /// The error code.
+    pub code: i32,
+    /// The error message.
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_simulate_payload() {
+        let s = r#"{"blockStateCalls":[{"blockOverrides":{"number":"0x10"},"calls":[{"from":"0x0000000000000000000000000000000000000001","to":"0x0000000000000000000000000000000000000002","value":"0x1"}]},{}],"traceTransfers":true}"#;
+        let payload: SimulatePayload = serde_json::from_str(s).unwrap();
+        assert_eq!(payload.block_state_calls.len(), 2);
+        assert!(payload.trace_transfers);
+        assert!(!payload.validation);
+        assert_eq!(payload.block_state_calls[0].calls.len(), 1);
+        assert!(payload.block_state_calls[1].calls.is_empty());
+    }
+
+    #[test]
+    fn serde_sim_call_result() {
+        let s = r#"{"returnData":"0x","logs":[],"gasUsed":"0x5208","status":"0x0","error":{"code":3,"message":"execution reverted"}}"#;
+        let result: SimCallResult = serde_json::from_str(s).unwrap();
+        assert_eq!(result.gas_used, U64::from(21000));
+        assert_eq!(result.error.as_ref().map(|err| err.code), Some(3));
+        assert_eq!(serde_json::to_string(&result).unwrap(), s);
+    }
+}
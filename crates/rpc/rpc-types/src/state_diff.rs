@@ -0,0 +1,76 @@
+//! Reth specific state diff types.
+
+use alloy_primitives::{Address, B256, U256, U64};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// The state changes of a canonical block, as sent by `reth_subscribeStateDiffs`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockStateDiff {
+    /// Number of the block.
+    pub block_number: U64,
+    /// Hash of the block.
+    pub block_hash: B256,
+    /// Whether the block was removed from the canonical chain.
+    ///
+    /// The changes of a reverted block undo the block, i.e. `from` is the state with the block
+    /// and `to` the state without it.
+    pub reverted: bool,
+    /// The changed accounts.
+    pub accounts: BTreeMap<Address, AccountStateDiff>,
+}
+
+/// The changes of a single account in a block.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateDiff {
+    /// The account before and after the block, if its balance, nonce or code hash changed.
+    ///
+    /// `null` on either side means that the account does not exist.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub info: Option<ValueChange<Option<StateDiffAccountInfo>>>,
+    /// Whether the storage of the account was wiped, e.g. by a selfdestruct.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub storage_wiped: bool,
+    /// The changed storage slots.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<B256, ValueChange<U256>>,
+}
+
+/// The balance, nonce and code hash of an account.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateDiffAccountInfo {
+    /// Balance of the account.
+    pub balance: U256,
+    /// Nonce of the account.
+    pub nonce: U64,
+    /// Hash of the code of the account.
+    pub code_hash: B256,
+}
+
+/// A value before and after a block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueChange<T> {
+    /// The value before the block.
+    pub from: T,
+    /// The value after the block.
+    pub to: T,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_block_state_diff() {
+        let s = r#"{"blockNumber":"0x2","blockHash":"0x0000000000000000000000000000000000000000000000000000000000000002","reverted":false,"accounts":{"0x0000000000000000000000000000000000000001":{"info":{"from":null,"to":{"balance":"0xa","nonce":"0x1","codeHash":"0xc5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"}},"storage":{"0x0000000000000000000000000000000000000000000000000000000000000001":{"from":"0x0","to":"0x7"}}}}}"#;
+        let diff: BlockStateDiff = serde_json::from_str(s).unwrap();
+        let account = &diff.accounts[&Address::with_last_byte(1)];
+        assert_eq!(account.info.unwrap().from, None);
+        assert_eq!(account.info.unwrap().to.unwrap().balance, U256::from(10));
+        assert!(!account.storage_wiped);
+        assert_eq!(serde_json::to_string(&diff).unwrap(), s);
+    }
+}
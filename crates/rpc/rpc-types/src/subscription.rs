@@ -0,0 +1,56 @@
+//! Reth specific `eth_subscribe` types.
+
+use alloy_rpc_types::pubsub::SubscriptionKind;
+use serde::{Deserialize, Serialize};
+
+/// The kind of an `eth_subscribe` subscription.
+///
+/// This extends the standard [SubscriptionKind]s with reth specific kinds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum EthSubscriptionKind {
+    /// A standard subscription kind.
+    Standard(SubscriptionKind),
+    /// A reth specific subscription kind.
+    Reth(RethSubscriptionKind),
+}
+
+impl From<SubscriptionKind> for EthSubscriptionKind {
+    fn from(kind: SubscriptionKind) -> Self {
+        Self::Standard(kind)
+    }
+}
+
+impl From<RethSubscriptionKind> for EthSubscriptionKind {
+    fn from(kind: RethSubscriptionKind) -> Self {
+        Self::Reth(kind)
+    }
+}
+
+/// Reth specific subscription kinds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RethSubscriptionKind {
+    /// New pending block subscription.
+    ///
+    /// Fires a notification each time the locally built pending block is rebuilt, e.g. because
+    /// new transactions became pending or a new block was added to the chain.
+    NewPendingBlock,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_subscription_kind() {
+        let kind: EthSubscriptionKind = serde_json::from_str(r#""newHeads""#).unwrap();
+        assert_eq!(kind, SubscriptionKind::NewHeads.into());
+
+        let kind: EthSubscriptionKind = serde_json::from_str(r#""newPendingBlock""#).unwrap();
+        assert_eq!(kind, RethSubscriptionKind::NewPendingBlock.into());
+        assert_eq!(serde_json::to_string(&kind).unwrap(), r#""newPendingBlock""#);
+
+        assert!(serde_json::from_str::<EthSubscriptionKind>(r#""unknown""#).is_err());
+    }
+}
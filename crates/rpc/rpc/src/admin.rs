@@ -1,15 +1,19 @@
-use crate::result::ToRpcResult;
+use crate::{eth::pipe_from_stream, result::ToRpcResult};
 use alloy_primitives::B256;
 use async_trait::async_trait;
+use futures::StreamExt;
 use jsonrpsee::core::RpcResult;
-use reth_network_api::{NetworkInfo, PeerKind, Peers};
+use reth_network_api::{NetworkInfo, PeerEvent, PeerKind, Peers};
 use reth_primitives::{AnyNode, ChainSpec, NodeRecord};
 use reth_rpc_api::AdminApiServer;
 use reth_rpc_types::{
     admin::{EthProtocolInfo, NodeInfo, Ports, ProtocolInfo},
-    PeerEthProtocolInfo, PeerInfo, PeerNetworkInfo, PeerProtocolsInfo,
+    AdminPeerEvent, AdminPeerEventKind, ExtendedPeerInfo, PeerEthProtocolInfo, PeerInfo,
+    PeerNetworkInfo, PeerProtocolsInfo, PeerRequestStats,
 };
+use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use std::sync::Arc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
 
 /// `admin` API implementation.
 ///
@@ -19,12 +23,53 @@ pub struct AdminApi<N> {
     network: N,
     /// The specification of the blockchain's configuration.
     chain_spec: Arc<ChainSpec>,
+    /// The type that's used to spawn subscription tasks.
+    subscription_task_spawner: Box<dyn TaskSpawner>,
 }
 
 impl<N> AdminApi<N> {
     /// Creates a new instance of `AdminApi`.
+    ///
+    /// Subscription tasks are spawned via [tokio::task::spawn]
     pub fn new(network: N, chain_spec: Arc<ChainSpec>) -> Self {
-        AdminApi { network, chain_spec }
+        Self::with_spawner(network, chain_spec, Box::<TokioTaskExecutor>::default())
+    }
+
+    /// Creates a new instance of `AdminApi` that spawns subscription tasks with the given
+    /// spawner.
+    pub fn with_spawner(
+        network: N,
+        chain_spec: Arc<ChainSpec>,
+        subscription_task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        AdminApi { network, chain_spec, subscription_task_spawner }
+    }
+}
+
+impl<N: NetworkInfo> AdminApi<N> {
+    /// Converts the network's [`reth_network_api::PeerInfo`] into the geth compatible
+    /// [`PeerInfo`].
+    fn peer_info(&self, peer: &reth_network_api::PeerInfo) -> PeerInfo {
+        PeerInfo {
+            id: Some(peer.remote_id.to_string()),
+            name: peer.client_version.to_string(),
+            caps: peer.capabilities.capabilities().iter().map(|cap| cap.to_string()).collect(),
+            network: PeerNetworkInfo {
+                remote_address: peer.remote_addr.to_string(),
+                local_address: peer
+                    .local_addr
+                    .unwrap_or_else(|| self.network.local_addr())
+                    .to_string(),
+            },
+            protocols: PeerProtocolsInfo {
+                eth: Some(PeerEthProtocolInfo {
+                    difficulty: Some(peer.status.total_difficulty),
+                    head: peer.status.blockhash.to_string(),
+                    version: peer.status.version as u32,
+                }),
+                pip: None,
+            },
+        }
     }
 }
 
@@ -60,28 +105,37 @@ where
         Ok(true)
     }
 
+    /// Handler for `admin_peers`
     async fn peers(&self) -> RpcResult<Vec<PeerInfo>> {
+        let peers = self.network.get_all_peers().await.to_rpc_result()?;
+        Ok(peers.iter().map(|peer| self.peer_info(peer)).collect())
+    }
+
+    /// Handler for `admin_peersExtended`
+    async fn peers_extended(&self) -> RpcResult<Vec<ExtendedPeerInfo>> {
         let peers = self.network.get_all_peers().await.to_rpc_result()?;
         let peers = peers
             .into_iter()
-            .map(|peer| PeerInfo {
-                id: Some(peer.remote_id.to_string()),
-                name: peer.client_version.to_string(),
-                caps: peer.capabilities.capabilities().iter().map(|cap| cap.to_string()).collect(),
-                network: PeerNetworkInfo {
-                    remote_address: peer.remote_addr.to_string(),
-                    local_address: peer
-                        .local_addr
-                        .unwrap_or_else(|| self.network.local_addr())
-                        .to_string(),
-                },
-                protocols: PeerProtocolsInfo {
-                    eth: Some(PeerEthProtocolInfo {
-                        difficulty: Some(peer.status.total_difficulty),
-                        head: peer.status.blockhash.to_string(),
-                        version: peer.status.version as u32,
-                    }),
-                    pip: None,
+            .map(|peer| ExtendedPeerInfo {
+                inner: self.peer_info(&peer),
+                reputation: peer.reputation,
+                inbound: peer.direction.is_incoming(),
+                trusted: peer.kind.is_trusted(),
+                ingress_bytes: peer.ingress_bytes,
+                egress_bytes: peer.egress_bytes,
+                last_request_latency_ms: peer
+                    .last_request_latency
+                    .map(|latency| latency.as_millis() as u64),
+                request_stats: PeerRequestStats {
+                    requests: peer.request_stats.requests,
+                    timeouts: peer.request_stats.timeouts,
+                    bad_responses: peer.request_stats.bad_responses,
+                    bytes_served: peer.request_stats.bytes_served,
+                    avg_latency_ms: peer
+                        .request_stats
+                        .avg_latency
+                        .map(|latency| latency.as_millis() as u64),
+                    score: peer.request_stats.score,
                 },
             })
             .collect();
@@ -121,12 +175,53 @@ where
     /// Handler for `admin_peerEvents`
     async fn subscribe_peer_events(
         &self,
-        _pending: jsonrpsee::PendingSubscriptionSink,
+        pending: jsonrpsee::PendingSubscriptionSink,
     ) -> jsonrpsee::core::SubscriptionResult {
-        Err("admin_peerEvents is not implemented yet".into())
+        let sink = pending.accept().await?;
+        let events = UnboundedReceiverStream::new(self.network.peer_events())
+            .filter_map(|event| futures::future::ready(to_admin_peer_event(event)));
+        self.subscription_task_spawner.spawn(Box::pin(async move {
+            let _ = pipe_from_stream(sink, events).await;
+        }));
+        Ok(())
     }
 }
 
+/// Converts a network [`PeerEvent`] into the [`AdminPeerEvent`] of the `admin_peerEvents`
+/// subscription.
+///
+/// Returns `None` for events that are not about sessions or bans.
+fn to_admin_peer_event(event: PeerEvent) -> Option<AdminPeerEvent> {
+    let event = match event {
+        PeerEvent::SessionEstablished { peer_id, remote_addr, .. } => AdminPeerEvent {
+            kind: AdminPeerEventKind::Add,
+            peer: peer_id,
+            error: None,
+            remote: Some(remote_addr.to_string()),
+        },
+        PeerEvent::SessionClosed { peer_id, reason } => AdminPeerEvent {
+            kind: AdminPeerEventKind::Drop,
+            peer: peer_id,
+            error: reason.map(|reason| reason.to_string()),
+            remote: None,
+        },
+        PeerEvent::PeerBanned(peer_id) => AdminPeerEvent {
+            kind: AdminPeerEventKind::Ban,
+            peer: peer_id,
+            error: None,
+            remote: None,
+        },
+        PeerEvent::PeerUnbanned(peer_id) => AdminPeerEvent {
+            kind: AdminPeerEventKind::Unban,
+            peer: peer_id,
+            error: None,
+            remote: None,
+        },
+        PeerEvent::PeerAdded(_) | PeerEvent::PeerRemoved(_) => return None,
+    };
+    Some(event)
+}
+
 impl<N> std::fmt::Debug for AdminApi<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AdminApi").finish_non_exhaustive()
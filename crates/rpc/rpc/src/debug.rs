@@ -11,8 +11,8 @@ use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_primitives::{
-    revm::env::tx_env_with_recovered, Address, Block, BlockId, BlockNumberOrTag, Bytes,
-    TransactionSignedEcRecovered, Withdrawals, B256, U256,
+    revm::env::tx_env_with_recovered, trie::ExecutionWitness, Address, Block, BlockId,
+    BlockNumberOrTag, Bytes, TransactionSignedEcRecovered, Withdrawals, B256, U256,
 };
 use reth_provider::{
     BlockReaderIdExt, ChainSpecProvider, HeaderProvider, StateProviderBox, TransactionVariant,
@@ -714,6 +714,17 @@ where
         Err(internal_rpc_err("unimplemented"))
     }
 
+    /// Handler for `debug_executionWitness`
+    async fn debug_execution_witness(&self, block_id: BlockId) -> RpcResult<ExecutionWitness> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(self
+            .inner
+            .eth_api
+            .execution_witness(block_id)
+            .await?
+            .ok_or(EthApiError::UnknownBlockNumber)?)
+    }
+
     /// Handler for `debug_traceChain`
     async fn debug_trace_chain(
         &self,
@@ -4,30 +4,41 @@ use crate::{
     eth::{
         error::{ensure_success, EthApiError, EthResult, RevertError, RpcInvalidTransactionError},
         revm_utils::{
-            apply_state_overrides, build_call_evm_env, caller_gas_allowance,
+            apply_block_overrides, apply_state_overrides, build_call_evm_env, caller_gas_allowance,
             cap_tx_gas_limit_with_caller_allowance, get_precompiles, prepare_call_env,
             EvmOverrides,
         },
+        simulate::{TransferInspector, SIMULATE_REVERT_ERROR_CODE, SIMULATE_VM_ERROR_CODE},
         EthTransactions,
     },
     EthApi,
 };
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
-use reth_primitives::{revm::env::tx_env_with_recovered, BlockId, BlockNumberOrTag, Bytes, U256};
+use reth_primitives::{
+    constants::{eip4844::DATA_GAS_PER_BLOB, BEACON_NONCE, EMPTY_OMMER_ROOT_HASH},
+    logs_bloom, proofs,
+    revm::env::tx_env_with_recovered,
+    Block, BlockId, BlockNumberOrTag, BlockWithSenders, Bytes, Header, Log, Receipt, Signature,
+    Transaction, TransactionKind, TransactionSigned, TxEip1559, TxType, Withdrawals, B256, U256,
+    U64,
+};
 use reth_provider::{
     BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProvider, StateProviderFactory,
 };
 use reth_revm::{access_list::AccessListInspector, database::StateProviderDatabase};
 use reth_rpc_types::{
-    state::StateOverride, AccessListWithGasUsed, Bundle, EthCallResponse, StateContext,
+    state::StateOverride, AccessListWithGasUsed, Bundle, EthCallResponse, Log as RpcLog, SimBlock,
+    SimCallResult, SimulateError, SimulatePayload, SimulatedBlock, StateContext,
     TransactionRequest,
 };
+use reth_rpc_types_compat::block::from_block;
 use reth_transaction_pool::TransactionPool;
 use revm::{
     db::{CacheDB, DatabaseRef},
     primitives::{
-        BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, HaltReason, TransactTo,
+        BlockEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ExecutionResult, HaltReason, SpecId,
+        TransactTo,
     },
     DatabaseCommit,
 };
@@ -39,6 +50,8 @@ const MIN_TRANSACTION_GAS: u64 = 21_000u64;
 /// Taken from Geth's implementation in order to pass the hive tests
 /// <https://github.com/ethereum/go-ethereum/blob/a5a4fa7032bb248f5a7c40f4e8df2b131c4186a4/internal/ethapi/api.go#L56>
 const ESTIMATE_GAS_ERROR_RATIO: f64 = 0.015;
+/// The default time between two simulated blocks in `eth_simulateV1`, in seconds.
+const SIMULATED_BLOCK_TIME: u64 = 12;
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
 where
@@ -107,7 +120,7 @@ where
         )?;
 
         let Some(block) = block else { return Err(EthApiError::UnknownBlockNumber) };
-        let gas_limit = self.inner.gas_cap;
+        let gas_limit = self.inner.config.gas_cap;
 
         // we're essentially replaying the transactions in the block here, hence we need the state
         // that points to the beginning of the block, which is the state at the parent block
@@ -179,6 +192,309 @@ where
         .await
     }
 
+    /// Simulates a sequence of blocks with calls on top of the given block (`eth_simulateV1`).
+    ///
+    /// Every simulated block is derived from its parent and can be modified with block overrides,
+    /// state overrides are applied before the calls of the block are executed. The total gas of
+    /// all simulated calls is capped by the configured gas cap.
+    ///
+    /// The state root of a simulated block is not computed, because that would require a state
+    /// root computation on top of the (possibly historical) target block for every simulated
+    /// block. It is [B256::ZERO] instead, which is also reflected in the block hash.
+    pub async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> EthResult<Vec<SimulatedBlock>> {
+        let SimulatePayload {
+            block_state_calls,
+            trace_transfers,
+            validation,
+            return_full_transactions,
+        } = payload;
+        if block_state_calls.is_empty() {
+            return Err(EthApiError::InvalidParams(String::from("blockStateCalls are empty.")))
+        }
+        if block_state_calls.len() as u64 > self.max_simulate_blocks() {
+            return Err(EthApiError::InvalidParams(format!(
+                "too many blocks to simulate, max is {}",
+                self.max_simulate_blocks()
+            )))
+        }
+
+        let target_block = block_number.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
+        let ((cfg, base_block_env, at), block) = futures::try_join!(
+            self.evm_env_at(target_block),
+            self.block_with_senders(target_block)
+        )?;
+        let Some(block) = block else { return Err(EthApiError::UnknownBlockNumber) };
+        // the pending block may not be available as state, in which case `evm_env_at` returns the
+        // state it is built on
+        let at = if target_block.is_pending() { at } else { block.hash().into() };
+        let total_difficulty =
+            self.provider().header_td_by_number(block.number)?.unwrap_or_default();
+        let chain_spec = self.provider().chain_spec();
+
+        let this = self.clone();
+        self.spawn_with_state_at_block(at, move |state| {
+            let mut db = CacheDB::new(StateProviderDatabase::new(state));
+            let mut parent = block.block.header;
+            let mut remaining_gas = this.gas_cap();
+            let mut blocks = Vec::with_capacity(block_state_calls.len());
+
+            for SimBlock { block_overrides, state_overrides, calls } in block_state_calls {
+                // derive the env of the simulated block from its parent
+                let timestamp = parent.timestamp + SIMULATED_BLOCK_TIME;
+                let mut block_env = base_block_env.clone();
+                block_env.number = U256::from(parent.number + 1);
+                block_env.timestamp = U256::from(timestamp);
+                block_env.gas_limit = U256::from(parent.gas_limit);
+                block_env.basefee = if validation {
+                    U256::from(
+                        parent
+                            .next_block_base_fee(chain_spec.base_fee_params(timestamp))
+                            .unwrap_or_default(),
+                    )
+                } else {
+                    U256::ZERO
+                };
+                if let Some(excess_blob_gas) = parent.next_block_excess_blob_gas() {
+                    block_env.set_blob_excess_gas_and_price(excess_blob_gas);
+                }
+
+                if let Some(mut block_overrides) = block_overrides {
+                    if let Some(block_hashes) = block_overrides.block_hash.take() {
+                        db.block_hashes.extend(
+                            block_hashes.into_iter().map(|(num, hash)| (U256::from(num), hash)),
+                        )
+                    }
+                    apply_block_overrides(block_overrides, &mut block_env);
+                }
+                let number = block_env.number.saturating_to::<u64>();
+                let timestamp = block_env.timestamp.saturating_to::<u64>();
+                if number <= parent.number {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "block number {number} is not greater than parent block number {}",
+                        parent.number
+                    )))
+                }
+                if timestamp <= parent.timestamp {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "block timestamp {timestamp} is not greater than parent timestamp {}",
+                        parent.timestamp
+                    )))
+                }
+
+                if let Some(state_overrides) = state_overrides {
+                    apply_state_overrides(state_overrides, &mut db)?;
+                }
+
+                let block_gas_limit = block_env.gas_limit.saturating_to::<u64>();
+                let mut cumulative_gas_used = 0u64;
+                let mut blob_gas_used = 0u64;
+                let mut transactions = Vec::with_capacity(calls.len());
+                let mut senders = Vec::with_capacity(calls.len());
+                let mut receipts = Vec::with_capacity(calls.len());
+                let mut results = Vec::with_capacity(calls.len());
+
+                for call in calls {
+                    let from = call.from.unwrap_or_default();
+                    let nonce = match call.nonce {
+                        Some(nonce) => nonce,
+                        None => db.basic_ref(from)?.map(|acc| acc.nonce).unwrap_or_default(),
+                    };
+                    let access_list = call.access_list.clone().unwrap_or_default();
+                    let has_gas_limit = call.gas.is_some();
+                    let gas_limit =
+                        block_gas_limit.saturating_sub(cumulative_gas_used).min(remaining_gas);
+
+                    let mut env = if validation {
+                        let mut env = build_call_evm_env(cfg.clone(), block_env.clone(), call)?;
+                        env.tx.nonce = Some(nonce);
+                        env
+                    } else {
+                        prepare_call_env(
+                            cfg.clone(),
+                            block_env.clone(),
+                            call,
+                            gas_limit,
+                            &mut db,
+                            EvmOverrides::default(),
+                        )?
+                    };
+                    if !has_gas_limit {
+                        env.tx.gas_limit = env.tx.gas_limit.min(gas_limit);
+                    }
+                    env.tx.gas_limit = env.tx.gas_limit.min(remaining_gas);
+
+                    let mut inspector = trace_transfers.then(TransferInspector::default);
+                    let (res, env) = match inspector.as_mut() {
+                        Some(inspector) => this.inspect(&mut db, env, inspector)?,
+                        None => this.transact(&mut db, env)?,
+                    };
+                    db.commit(res.state);
+
+                    let gas_used = res.result.gas_used();
+                    cumulative_gas_used += gas_used;
+                    remaining_gas = remaining_gas.saturating_sub(gas_used);
+                    blob_gas_used += env.tx.blob_hashes.len() as u64 * DATA_GAS_PER_BLOB;
+
+                    let success = res.result.is_success();
+                    let (return_data, logs, error) = match res.result {
+                        ExecutionResult::Success { output, logs, .. } => {
+                            (output.into_data(), logs, None)
+                        }
+                        ExecutionResult::Revert { output, .. } => {
+                            let error = SimulateError {
+                                code: SIMULATE_REVERT_ERROR_CODE,
+                                message: RevertError::new(output.clone()).to_string(),
+                            };
+                            (output, Vec::new(), Some(error))
+                        }
+                        ExecutionResult::Halt { reason, gas_used } => {
+                            let error = SimulateError {
+                                code: SIMULATE_VM_ERROR_CODE,
+                                message: RpcInvalidTransactionError::halt(reason, gas_used)
+                                    .to_string(),
+                            };
+                            (Bytes::new(), Vec::new(), Some(error))
+                        }
+                    };
+                    let logs: Vec<Log> = match inspector {
+                        Some(inspector) => inspector.into_logs(),
+                        None => logs.into_iter().map(Into::into).collect(),
+                    };
+
+                    let transaction = Transaction::Eip1559(TxEip1559 {
+                        chain_id: cfg.chain_id,
+                        nonce,
+                        gas_limit: env.tx.gas_limit,
+                        max_fee_per_gas: env.tx.gas_price.saturating_to(),
+                        max_priority_fee_per_gas: env
+                            .tx
+                            .gas_priority_fee
+                            .unwrap_or_default()
+                            .saturating_to(),
+                        to: match env.tx.transact_to {
+                            TransactTo::Call(to) => TransactionKind::Call(to),
+                            TransactTo::Create(_) => TransactionKind::Create,
+                        },
+                        value: env.tx.value,
+                        access_list,
+                        input: env.tx.data.clone(),
+                    });
+                    transactions.push(TransactionSigned::from_transaction_and_signature(
+                        transaction,
+                        Signature::default(),
+                    ));
+                    senders.push(from);
+                    receipts.push(Receipt {
+                        tx_type: TxType::Eip1559,
+                        success,
+                        cumulative_gas_used,
+                        logs,
+                        #[cfg(feature = "optimism")]
+                        deposit_nonce: None,
+                        #[cfg(feature = "optimism")]
+                        deposit_receipt_version: None,
+                    });
+                    results.push((return_data, gas_used, error));
+                }
+
+                let spec_id = cfg.handler_cfg.spec_id;
+                let withdrawals = (spec_id >= SpecId::SHANGHAI).then(Withdrawals::default);
+                let receipts_ref = receipts.iter().collect::<Vec<_>>();
+                #[cfg(not(feature = "optimism"))]
+                let receipts_root = proofs::calculate_receipt_root_ref(&receipts_ref);
+                #[cfg(feature = "optimism")]
+                let receipts_root = proofs::calculate_receipt_root_ref_optimism(
+                    &receipts_ref,
+                    chain_spec.as_ref(),
+                    timestamp,
+                );
+
+                let header = Header {
+                    parent_hash: parent.hash(),
+                    ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                    beneficiary: block_env.coinbase,
+                    // the state root is not computed for simulated blocks
+                    state_root: B256::ZERO,
+                    transactions_root: proofs::calculate_transaction_root(&transactions),
+                    receipts_root,
+                    withdrawals_root: withdrawals
+                        .as_ref()
+                        .map(|withdrawals| proofs::calculate_withdrawals_root(withdrawals)),
+                    logs_bloom: logs_bloom(receipts.iter().flat_map(|receipt| &receipt.logs)),
+                    timestamp,
+                    mix_hash: block_env.prevrandao.unwrap_or_default(),
+                    nonce: BEACON_NONCE,
+                    base_fee_per_gas: Some(block_env.basefee.saturating_to()),
+                    number,
+                    gas_limit: block_gas_limit,
+                    difficulty: block_env.difficulty,
+                    gas_used: cumulative_gas_used,
+                    blob_gas_used: (spec_id >= SpecId::CANCUN).then_some(blob_gas_used),
+                    excess_blob_gas: block_env.get_blob_excess_gas(),
+                    extra_data: Default::default(),
+                    parent_beacon_block_root: (spec_id >= SpecId::CANCUN).then_some(B256::ZERO),
+                };
+                let sealed =
+                    Block { header, body: transactions, ommers: vec![], withdrawals }.seal_slow();
+                let block_hash = sealed.hash();
+                // make the simulated block available to `BLOCKHASH` in the following blocks
+                db.block_hashes.insert(U256::from(number), block_hash);
+
+                let mut log_index = 0;
+                let calls = results
+                    .into_iter()
+                    .zip(receipts)
+                    .zip(sealed.body.iter())
+                    .enumerate()
+                    .map(|(idx, (((return_data, gas_used, error), receipt), tx))| {
+                        let logs = receipt
+                            .logs
+                            .into_iter()
+                            .map(|log| {
+                                let log = RpcLog {
+                                    inner: log.into(),
+                                    block_hash: Some(block_hash),
+                                    block_number: Some(number),
+                                    block_timestamp: Some(timestamp),
+                                    transaction_hash: Some(tx.hash()),
+                                    transaction_index: Some(idx as u64),
+                                    log_index: Some(log_index),
+                                    removed: false,
+                                };
+                                log_index += 1;
+                                log
+                            })
+                            .collect();
+                        SimCallResult {
+                            return_data,
+                            logs,
+                            gas_used: U64::from(gas_used),
+                            status: U64::from(receipt.success as u8),
+                            error,
+                        }
+                    })
+                    .collect();
+
+                parent = sealed.header.clone();
+                let block = BlockWithSenders { block: sealed.unseal(), senders };
+                let inner = from_block(
+                    block,
+                    total_difficulty,
+                    return_full_transactions.into(),
+                    Some(block_hash),
+                )?;
+                blocks.push(SimulatedBlock { inner, calls });
+            }
+
+            Ok(blocks)
+        })
+        .await
+    }
+
     /// Estimates the gas usage of the `request` with the state.
     ///
     /// This will execute the [TransactionRequest] and find the best gas limit via binary search
@@ -521,3 +837,190 @@ fn update_estimated_gas_range(
     };
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::{
+        cache::EthStateCache,
+        gas_oracle::GasPriceOracle,
+        simulate::{ETH_TRANSFER_LOG_ADDRESS, TRANSFER_EVENT_TOPIC},
+        EthApiConfig, FeeHistoryCache, FeeHistoryCacheConfig,
+    };
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::{
+        address, constants::ETHEREUM_BLOCK_GAS_LIMIT, hex_literal::hex, Address,
+    };
+    use reth_provider::test_utils::MockEthProvider;
+    use reth_tasks::pool::BlockingTaskPool;
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+    use serde_json::json;
+
+    const SENDER: Address = address!("0000000000000000000000000000000000000001");
+    const RECIPIENT: Address = address!("0000000000000000000000000000000000000002");
+    const FORWARDER: Address = address!("0000000000000000000000000000000000000003");
+    const REVERTER: Address = address!("0000000000000000000000000000000000000004");
+
+    fn build_test_eth_api(
+        provider: MockEthProvider,
+        config: EthApiConfig,
+    ) -> EthApi<MockEthProvider, TestPool, NoopNetwork, EthEvmConfig> {
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let fee_history_cache =
+            FeeHistoryCache::new(cache.clone(), FeeHistoryCacheConfig::default());
+
+        EthApi::new(
+            provider.clone(),
+            testing_pool(),
+            NoopNetwork::default(),
+            cache.clone(),
+            GasPriceOracle::new(provider, Default::default(), cache),
+            config,
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            fee_history_cache,
+            evm_config,
+            None,
+        )
+    }
+
+    /// Returns a provider with the parent block of the simulated blocks as the latest block.
+    fn provider_with_parent() -> (MockEthProvider, Header) {
+        let provider = MockEthProvider::default();
+        let header = Header {
+            number: 1,
+            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            timestamp: 1_000,
+            ..Default::default()
+        };
+        provider
+            .add_block(header.hash_slow(), Block { header: header.clone(), ..Default::default() });
+        (provider, header)
+    }
+
+    /// Returns the code of a contract that sends 1 wei to [RECIPIENT], and reverts afterwards if
+    /// `revert` is set.
+    fn transfer_code(revert: bool) -> Bytes {
+        let mut code = hex!("60006000600060006001").to_vec();
+        code.push(0x73);
+        code.extend_from_slice(RECIPIENT.as_slice());
+        // GAS CALL POP
+        code.extend_from_slice(&hex!("5af150"));
+        if revert {
+            // PUSH1 0 PUSH1 0 REVERT
+            code.extend_from_slice(&hex!("60006000fd"));
+        } else {
+            // STOP
+            code.push(0x00);
+        }
+        code.into()
+    }
+
+    fn assert_transfer_log(log: &RpcLog, from: Address, to: Address, value: u64) {
+        assert_eq!(log.inner.address, ETH_TRANSFER_LOG_ADDRESS);
+        assert_eq!(
+            log.inner.data.topics(),
+            [TRANSFER_EVENT_TOPIC, from.into_word(), to.into_word()]
+        );
+        assert_eq!(log.inner.data.data, Bytes::from(U256::from(value).to_be_bytes_vec()));
+    }
+
+    #[tokio::test]
+    async fn simulate_blocks_with_state_overrides_and_transfers() {
+        let (provider, parent) = provider_with_parent();
+        let eth_api = build_test_eth_api(provider, EthApiConfig::default());
+
+        let payload: SimulatePayload = serde_json::from_value(json!({
+            "blockStateCalls": [
+                {
+                    "stateOverrides": {
+                        SENDER.to_string(): { "balance": "0xde0b6b3a7640000" },
+                        FORWARDER.to_string(): { "code": transfer_code(false) }
+                    },
+                    "calls": [{ "from": SENDER, "to": FORWARDER, "value": "0x5" }]
+                },
+                {
+                    "stateOverrides": {
+                        REVERTER.to_string(): { "balance": "0x1", "code": transfer_code(true) }
+                    },
+                    "calls": [
+                        { "from": SENDER, "to": REVERTER },
+                        { "from": SENDER, "to": RECIPIENT, "value": "0x1" }
+                    ]
+                }
+            ],
+            "traceTransfers": true
+        }))
+        .unwrap();
+
+        let blocks = eth_api.simulate_v1(payload, None).await.unwrap();
+        assert_eq!(blocks.len(), 2);
+
+        // every block is derived from its parent
+        let (first, second) = (&blocks[0], &blocks[1]);
+        assert_eq!(first.inner.header.number, Some(2));
+        assert_eq!(first.inner.header.timestamp, parent.timestamp + SIMULATED_BLOCK_TIME);
+        assert_eq!(first.inner.header.parent_hash, parent.hash_slow());
+        assert_eq!(second.inner.header.number, Some(3));
+        assert_eq!(second.inner.header.timestamp, parent.timestamp + 2 * SIMULATED_BLOCK_TIME);
+        assert_eq!(Some(second.inner.header.parent_hash), first.inner.header.hash);
+        // the state root is not computed
+        assert_eq!(first.inner.header.state_root, B256::ZERO);
+
+        // the overridden balance pays for the transfer to the overridden code, which forwards 1 wei
+        let call = &first.calls[0];
+        assert_eq!(call.status, U64::from(1));
+        assert_eq!(call.logs.len(), 2);
+        assert_transfer_log(&call.logs[0], SENDER, FORWARDER, 5);
+        assert_transfer_log(&call.logs[1], FORWARDER, RECIPIENT, 1);
+
+        // the transfer of a reverted call is discarded
+        let call = &second.calls[0];
+        assert_eq!(call.status, U64::ZERO);
+        assert_eq!(call.error.as_ref().map(|err| err.code), Some(SIMULATE_REVERT_ERROR_CODE));
+        assert!(call.logs.is_empty());
+
+        // the overrides of the first block persist in the second block
+        let call = &second.calls[1];
+        assert_eq!(call.status, U64::from(1));
+        assert_eq!(call.logs.len(), 1);
+        assert_transfer_log(&call.logs[0], SENDER, RECIPIENT, 1);
+        assert_eq!(call.logs[0].log_index, Some(0));
+        assert_eq!(call.logs[0].transaction_index, Some(1));
+    }
+
+    #[tokio::test]
+    async fn simulate_without_transfer_tracing() {
+        let (provider, _) = provider_with_parent();
+        let eth_api = build_test_eth_api(provider, EthApiConfig::default());
+
+        let payload: SimulatePayload = serde_json::from_value(json!({
+            "blockStateCalls": [{
+                "stateOverrides": { SENDER.to_string(): { "balance": "0x1" } },
+                "calls": [{ "from": SENDER, "to": RECIPIENT, "value": "0x1" }]
+            }]
+        }))
+        .unwrap();
+
+        let blocks = eth_api.simulate_v1(payload, None).await.unwrap();
+        assert_eq!(blocks[0].calls[0].status, U64::from(1));
+        assert!(blocks[0].calls[0].logs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn simulate_too_many_blocks() {
+        let (provider, _) = provider_with_parent();
+        let eth_api = build_test_eth_api(provider, EthApiConfig::default().max_simulate_blocks(1));
+
+        let payload: SimulatePayload = serde_json::from_value(json!({
+            "blockStateCalls": [{}, {}]
+        }))
+        .unwrap();
+
+        assert!(matches!(
+            eth_api.simulate_v1(payload, None).await,
+            Err(EthApiError::InvalidParams(_))
+        ));
+    }
+}
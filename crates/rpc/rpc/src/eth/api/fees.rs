@@ -1,20 +1,41 @@
-//! Contains RPC handler implementations for fee history.
+//! Contains RPC handler implementations for fee history and fee estimates.
 
 use crate::{
     eth::{
         api::fee_history::{calculate_reward_percentiles_for_block, FeeHistoryEntry},
         error::{EthApiError, EthResult},
+        gas_oracle::{
+            percentile_of, project_base_fee, project_blob_fee, GasPriceOracleStrategy,
+            FAST_FEE_ESTIMATE_PERCENTILE, MAX_MEMPOOL_TIP_SAMPLES, SLOW_FEE_ESTIMATE_PERCENTILE,
+        },
     },
     EthApi,
 };
+use async_trait::async_trait;
 use reth_evm::ConfigureEvm;
 use reth_network_api::NetworkInfo;
-use reth_primitives::{basefee::calculate_next_block_base_fee, BlockNumberOrTag, U256};
+use reth_primitives::{
+    basefee::calculate_next_block_base_fee, eip4844::calc_blob_gasprice, BlockNumberOrTag,
+    SealedHeader, U256, U64,
+};
 use reth_provider::{BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderFactory};
-use reth_rpc_types::FeeHistory;
-use reth_transaction_pool::TransactionPool;
+use reth_rpc_types::{FeeEstimate, FeeEstimates, FeeHistory};
+use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
+use std::sync::Arc;
 use tracing::debug;
 
+/// Fee estimation functionality of the `eth` API implementation.
+///
+/// This is used by the `reth_feeEstimates` endpoint.
+#[async_trait]
+pub trait EthFees: Send + Sync {
+    /// Returns slow, normal and fast fee suggestions for the next blocks.
+    ///
+    /// The suggested max fees remain sufficient for the configured number of projection blocks,
+    /// even if all of them are full.
+    async fn fee_estimates(&self) -> EthResult<FeeEstimates>;
+}
+
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
 where
     Pool: TransactionPool + Clone + 'static,
@@ -45,9 +66,73 @@ where
 
     /// Returns a suggestion for the priority fee (the tip)
     pub(crate) async fn suggested_priority_fee(&self) -> EthResult<U256> {
+        if self.gas_oracle().config().strategy == GasPriceOracleStrategy::Mempool {
+            let header = self.latest_header()?;
+            let tips = self.mempool_tips(&header).await;
+            if let Some(tip) = percentile_of(&tips, self.gas_oracle().config().percentile) {
+                return Ok(self.gas_oracle().cap_price(tip))
+            }
+        }
+
         self.gas_oracle().suggest_tip_cap().await
     }
 
+    /// Returns the latest header.
+    fn latest_header(&self) -> EthResult<SealedHeader> {
+        self.provider()
+            .sealed_header_by_number_or_tag(BlockNumberOrTag::Latest)?
+            .ok_or(EthApiError::UnknownBlockNumber)
+    }
+
+    /// Returns the sorted effective tips of the pending transactions that fit into the block
+    /// following the given header.
+    ///
+    /// Tips under the configured ignore price are skipped. This is empty if the block following
+    /// the header has no base fee.
+    ///
+    /// At most [MAX_MEMPOOL_TIP_SAMPLES] transactions are visited, and the tips are cached per
+    /// block, so that the pool is only walked once per block.
+    async fn mempool_tips(&self, header: &SealedHeader) -> Arc<Vec<U256>> {
+        self.gas_oracle().mempool_tips(header.hash(), || self.sample_mempool_tips(header)).await
+    }
+
+    /// Samples the tips of [EthApi::mempool_tips].
+    fn sample_mempool_tips(&self, header: &SealedHeader) -> Vec<U256> {
+        let chain_spec = self.provider().chain_spec();
+        let Some(base_fee) =
+            header.next_block_base_fee(chain_spec.base_fee_params(header.timestamp))
+        else {
+            return Vec::new()
+        };
+        let ignore_price = self.gas_oracle().ignore_price();
+
+        let mut gas_remaining = header.gas_limit;
+        let mut tips = Vec::new();
+        for tx in self
+            .pool()
+            .best_transactions_with_attributes(BestTransactionsAttributes::base_fee(base_fee))
+            .take(MAX_MEMPOOL_TIP_SAMPLES)
+        {
+            if gas_remaining == 0 {
+                break
+            }
+            // skip transactions that would not fit into the block anymore
+            if tx.gas_limit() > gas_remaining {
+                continue
+            }
+            gas_remaining -= tx.gas_limit();
+
+            let Some(tip) = tx.effective_tip_per_gas(base_fee) else { continue };
+            if ignore_price.is_some_and(|ignore_price| tip < ignore_price) {
+                continue
+            }
+            tips.push(U256::from(tip));
+        }
+
+        tips.sort_unstable();
+        tips
+    }
+
     /// Reports the fee history, for the given amount of blocks, up until the given newest block.
     ///
     /// If `reward_percentiles` are provided the [FeeHistory] will include the _approximated_
@@ -224,3 +309,63 @@ where
         entry.rewards.get(index).cloned().unwrap_or_default()
     }
 }
+
+#[async_trait]
+impl<Provider, Pool, Network, EvmConfig> EthFees for EthApi<Provider, Pool, Network, EvmConfig>
+where
+    Pool: TransactionPool + Clone + 'static,
+    Provider:
+        BlockReaderIdExt + ChainSpecProvider + StateProviderFactory + EvmEnvProvider + 'static,
+    Network: NetworkInfo + Send + Sync + 'static,
+    EvmConfig: ConfigureEvm + 'static,
+{
+    async fn fee_estimates(&self) -> EthResult<FeeEstimates> {
+        let header = self.latest_header()?;
+        let config = self.gas_oracle().config();
+        let percentiles =
+            [SLOW_FEE_ESTIMATE_PERCENTILE, config.percentile, FAST_FEE_ESTIMATE_PERCENTILE];
+
+        // prefer the tips of the pending transactions, if configured and available
+        let mempool_tips = if config.strategy == GasPriceOracleStrategy::Mempool {
+            self.mempool_tips(&header).await
+        } else {
+            Default::default()
+        };
+        let tips = if mempool_tips.is_empty() {
+            self.gas_oracle().suggest_tip_caps(&percentiles).await?
+        } else {
+            percentiles
+                .iter()
+                .filter_map(|percentile| percentile_of(&mempool_tips, *percentile))
+                .map(|tip| self.gas_oracle().cap_price(tip))
+                .collect()
+        };
+
+        let base_fee_params = self.provider().chain_spec().base_fee_params(header.timestamp);
+        let base_fee = header.next_block_base_fee(base_fee_params).unwrap_or_default();
+        let max_base_fee =
+            project_base_fee(base_fee, header.gas_limit, base_fee_params, config.projection_blocks);
+
+        let next_excess_blob_gas = header.next_block_excess_blob_gas();
+        let blob_base_fee = next_excess_blob_gas.map(calc_blob_gasprice);
+        let max_fee_per_blob_gas = next_excess_blob_gas
+            .map(|excess_blob_gas| project_blob_fee(excess_blob_gas, config.projection_blocks))
+            .map(U256::from);
+
+        let mut tiers = tips.into_iter().map(|tip| FeeEstimate {
+            max_priority_fee_per_gas: tip,
+            max_fee_per_gas: U256::from(max_base_fee) + tip,
+            max_fee_per_blob_gas,
+        });
+
+        Ok(FeeEstimates {
+            block_number: U64::from(header.number),
+            base_fee_per_gas: U256::from(base_fee),
+            blob_base_fee_per_gas: blob_base_fee.map(U256::from),
+            projection_blocks: U64::from(config.projection_blocks),
+            slow: tiers.next().unwrap_or_default(),
+            normal: tiers.next().unwrap_or_default(),
+            fast: tiers.next().unwrap_or_default(),
+        })
+    }
+}
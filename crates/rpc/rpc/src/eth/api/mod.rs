@@ -4,7 +4,9 @@
 use crate::eth::{
     api::{
         fee_history::FeeHistoryCache,
-        pending_block::{PendingBlock, PendingBlockEnv, PendingBlockEnvOrigin},
+        pending_block::{
+            MaintainedPendingBlock, PendingBlock, PendingBlockEnv, PendingBlockEnvOrigin,
+        },
     },
     cache::EthStateCache,
     error::{EthApiError, EthResult},
@@ -13,6 +15,7 @@ use crate::eth::{
 };
 
 use async_trait::async_trait;
+use futures::{Stream, StreamExt};
 use reth_evm::ConfigureEvm;
 use reth_interfaces::RethResult;
 use reth_network_api::NetworkInfo;
@@ -22,7 +25,8 @@ use reth_primitives::{
     U256, U64,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, StateProviderBox, StateProviderFactory,
+    BlockReaderIdExt, CanonStateNotification, ChainSpecProvider, EvmEnvProvider, StateProviderBox,
+    StateProviderFactory,
 };
 use reth_rpc_types::{SyncInfo, SyncStatus};
 use reth_tasks::{pool::BlockingTaskPool, TaskSpawner, TokioTaskExecutor};
@@ -34,7 +38,11 @@ use std::{
     sync::Arc,
     time::{Duration, Instant},
 };
-use tokio::sync::{oneshot, Mutex};
+use tokio::{
+    sync::{broadcast, oneshot, Mutex},
+    time::MissedTickBehavior,
+};
+use tracing::debug;
 
 mod block;
 mod call;
@@ -50,6 +58,7 @@ mod state;
 mod transactions;
 
 use crate::eth::traits::RawTransactionForwarder;
+pub use fees::EthFees;
 pub use transactions::{EthTransactions, TransactionSource};
 
 /// `Eth` API trait.
@@ -101,7 +110,7 @@ where
         network: Network,
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
-        gas_cap: impl Into<GasCap>,
+        config: EthApiConfig,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
         evm_config: EvmConfig,
@@ -113,7 +122,7 @@ where
             network,
             eth_cache,
             gas_oracle,
-            gas_cap.into().into(),
+            config,
             Box::<TokioTaskExecutor>::default(),
             blocking_task_pool,
             fee_history_cache,
@@ -130,7 +139,7 @@ where
         network: Network,
         eth_cache: EthStateCache,
         gas_oracle: GasPriceOracle<Provider>,
-        gas_cap: u64,
+        config: EthApiConfig,
         task_spawner: Box<dyn TaskSpawner>,
         blocking_task_pool: BlockingTaskPool,
         fee_history_cache: FeeHistoryCache,
@@ -145,6 +154,9 @@ where
             .map(|header| header.number)
             .unwrap_or_default();
 
+        let (pending_block_notifications, _) =
+            broadcast::channel(PENDING_BLOCK_NOTIFICATION_CHANNEL_SIZE);
+
         let inner = EthApiInner {
             provider,
             pool,
@@ -152,10 +164,12 @@ where
             signers: parking_lot::RwLock::new(Default::default()),
             eth_cache,
             gas_oracle,
-            gas_cap,
+            config,
             starting_block: U256::from(latest_block),
             task_spawner,
             pending_block: Default::default(),
+            maintained_pending_block: Default::default(),
+            pending_block_notifications,
             blocking_task_pool,
             fee_history_cache,
             evm_config,
@@ -199,7 +213,17 @@ where
 
     /// Returns the configured gas limit cap for `eth_call` and tracing related calls
     pub fn gas_cap(&self) -> u64 {
-        self.inner.gas_cap
+        self.inner.config.gas_cap
+    }
+
+    /// Returns the configured maximum number of blocks for `eth_simulateV1`
+    pub fn max_simulate_blocks(&self) -> u64 {
+        self.inner.config.max_simulate_blocks
+    }
+
+    /// Returns the configured maximum distance to the tip for `eth_getProof`
+    pub fn eth_proof_window(&self) -> u64 {
+        self.inner.config.eth_proof_window
     }
 
     /// Returns the inner `Provider`
@@ -221,6 +245,14 @@ where
     pub fn fee_history_cache(&self) -> &FeeHistoryCache {
         &self.inner.fee_history_cache
     }
+
+    /// Returns the sender of the notifications about rebuilt pending blocks.
+    ///
+    /// Pending blocks are only announced if they are maintained by
+    /// [EthApi::maintain_pending_block].
+    pub fn pending_block_notifications(&self) -> broadcast::Sender<Arc<SealedBlockWithSenders>> {
+        self.inner.pending_block_notifications.clone()
+    }
 }
 
 // === State access helpers ===
@@ -233,7 +265,17 @@ where
     /// Returns the state at the given [BlockId] enum.
     ///
     /// Note: if not [BlockNumberOrTag::Pending] then this will only return canonical state. See also <https://github.com/paradigmxyz/reth/issues/4515>
+    ///
+    /// If the pending block is maintained by [EthApi::maintain_pending_block], the state for
+    /// [BlockNumberOrTag::Pending] is the post-state of that block.
     pub fn state_at_block_id(&self, at: BlockId) -> EthResult<StateProviderBox> {
+        if at.is_pending() {
+            if let Some(pending) = self.maintained_pending_block() {
+                let parent_state =
+                    self.provider().history_by_block_hash(pending.block.parent_hash)?;
+                return Ok(pending.state_provider(parent_state))
+            }
+        }
         Ok(self.provider().state_by_block_id(at)?)
     }
 
@@ -260,6 +302,13 @@ where
     pub fn latest_state(&self) -> RethResult<StateProviderBox> {
         Ok(self.provider().latest()?)
     }
+
+    /// Returns the pending block maintained by [EthApi::maintain_pending_block].
+    ///
+    /// The block is dropped once the canonical head moves away from its parent.
+    pub(crate) fn maintained_pending_block(&self) -> Option<MaintainedPendingBlock> {
+        self.inner.maintained_pending_block.read().clone()
+    }
 }
 
 impl<Provider, Pool, Network, EvmConfig> EthApi<Provider, Pool, Network, EvmConfig>
@@ -326,6 +375,14 @@ where
             return Ok(pending.origin.into_actual_pending())
         }
 
+        // use the continuously rebuilt pending block, if it is built on top of the latest block
+        if let Some(maintained) = self
+            .maintained_pending_block()
+            .filter(|maintained| maintained.block.parent_hash == pending.origin.header().hash())
+        {
+            return Ok(Some((*maintained.block).clone()))
+        }
+
         // no pending block from the CL yet, so we need to build it ourselves via txpool
         self.on_blocking_task(|this| async move {
             let mut lock = this.inner.pending_block.lock().await;
@@ -344,7 +401,7 @@ where
 
             // we rebuild the block
             let pending_block = match pending.build_block(this.provider(), this.pool()) {
-                Ok(block) => block,
+                Ok((block, _)) => block,
                 Err(err) => {
                     tracing::debug!(target: "rpc", "Failed to build pending block: {:?}", err);
                     return Ok(None)
@@ -361,6 +418,88 @@ where
         })
         .await
     }
+
+    /// Continuously rebuilds the pending block from the transaction pool.
+    ///
+    /// The pending block is rebuilt whenever new transactions become pending or the canonical
+    /// chain changes, at most once per [PENDING_BLOCK_REBUILD_INTERVAL]. Requests for the `pending`
+    /// state are then served from the post-state of this block, and every rebuilt block is sent
+    /// to the [pending block notifications](EthApi::pending_block_notifications). The block is
+    /// dropped as soon as the canonical chain moves away from its parent.
+    ///
+    /// This is intended to be spawned as a task and runs until either of the streams ends.
+    pub async fn maintain_pending_block<St>(self, mut canon_state_events: St)
+    where
+        St: Stream<Item = CanonStateNotification> + Send + Unpin + 'static,
+    {
+        let mut pending_transactions = self.pool().new_pending_pool_transactions_listener();
+        let mut interval = tokio::time::interval(PENDING_BLOCK_REBUILD_INTERVAL);
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        // build the initial pending block right away
+        let mut outdated = true;
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if outdated {
+                        outdated = false;
+                        self.rebuild_pending_block().await;
+                    }
+                }
+                event = canon_state_events.next() => {
+                    let Some(event) = event else { break };
+                    // the pending block is no longer served once it isn't built on top of the tip
+                    let tip = event.tip().hash();
+                    let mut maintained = self.inner.maintained_pending_block.write();
+                    if maintained.as_ref().is_some_and(|pending| pending.block.parent_hash != tip) {
+                        maintained.take();
+                    }
+                    outdated = true;
+                }
+                transaction = pending_transactions.next() => {
+                    if transaction.is_none() {
+                        break
+                    }
+                    outdated = true;
+                }
+            }
+        }
+    }
+
+    /// Rebuilds the maintained pending block on top of the current canonical head.
+    async fn rebuild_pending_block(&self) {
+        let pending = match self.pending_block_env_and_cfg() {
+            Ok(pending) => pending,
+            Err(err) => {
+                debug!(target: "rpc", %err, "Failed to configure pending block");
+                return
+            }
+        };
+
+        if pending.origin.is_actual_pending() {
+            // the pending block received from the CL takes precedence
+            self.inner.maintained_pending_block.write().take();
+            return
+        }
+
+        let result = self
+            .on_blocking_task(
+                |this| async move { pending.build_block(this.provider(), this.pool()) },
+            )
+            .await;
+        match result {
+            Ok((block, bundle)) => {
+                let block = Arc::new(block);
+                *self.inner.maintained_pending_block.write() =
+                    Some(MaintainedPendingBlock { block: block.clone(), bundle: Arc::new(bundle) });
+                // there may be no subscribers
+                let _ = self.inner.pending_block_notifications.send(block);
+            }
+            Err(err) => {
+                debug!(target: "rpc", %err, "Failed to build pending block");
+            }
+        }
+    }
 }
 
 impl<Provider, Pool, Events, EvmConfig> std::fmt::Debug
@@ -432,6 +571,13 @@ where
     }
 }
 
+/// The minimum interval between two rebuilds of the pending block by
+/// [EthApi::maintain_pending_block].
+pub const PENDING_BLOCK_REBUILD_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The capacity of the channel for notifications about rebuilt pending blocks.
+const PENDING_BLOCK_NOTIFICATION_CHANNEL_SIZE: usize = 16;
+
 /// The default gas limit for eth_call and adjacent calls.
 ///
 /// This is different from the default to regular 30M block gas limit
@@ -439,6 +585,15 @@ where
 /// more complex calls.
 pub const RPC_DEFAULT_GAS_CAP: GasCap = GasCap(50_000_000);
 
+/// The default maximum number of blocks that can be simulated in a single `eth_simulateV1` call.
+pub const DEFAULT_MAX_SIMULATE_BLOCKS: u64 = 256;
+
+/// The default maximum distance to the tip, in blocks, of the state `eth_getProof` is served for.
+///
+/// Proofs for historical blocks require reverting the state, so only the latest block is served
+/// by default.
+pub const DEFAULT_ETH_PROOF_WINDOW: u64 = 0;
+
 /// The wrapper type for gas limit
 #[derive(Debug, Clone, Copy)]
 pub struct GasCap(u64);
@@ -461,6 +616,47 @@ impl From<GasCap> for u64 {
     }
 }
 
+/// Config for the [EthApi]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthApiConfig {
+    /// Maximum gas limit for `eth_call` and call tracing RPC methods.
+    pub gas_cap: u64,
+    /// Maximum number of blocks that can be simulated in a single `eth_simulateV1` call.
+    pub max_simulate_blocks: u64,
+    /// Maximum distance to the tip, in blocks, of the state `eth_getProof` is served for.
+    pub eth_proof_window: u64,
+}
+
+impl EthApiConfig {
+    /// Sets the maximum gas limit for `eth_call` and call tracing RPC methods.
+    pub fn gas_cap(mut self, gas_cap: impl Into<GasCap>) -> Self {
+        self.gas_cap = gas_cap.into().into();
+        self
+    }
+
+    /// Sets the maximum number of blocks that can be simulated in a single `eth_simulateV1` call.
+    pub fn max_simulate_blocks(mut self, max_blocks: u64) -> Self {
+        self.max_simulate_blocks = max_blocks;
+        self
+    }
+
+    /// Sets the maximum distance to the tip, in blocks, of the state `eth_getProof` is served for.
+    pub fn eth_proof_window(mut self, window: u64) -> Self {
+        self.eth_proof_window = window;
+        self
+    }
+}
+
+impl Default for EthApiConfig {
+    fn default() -> Self {
+        Self {
+            gas_cap: RPC_DEFAULT_GAS_CAP.into(),
+            max_simulate_blocks: DEFAULT_MAX_SIMULATE_BLOCKS,
+            eth_proof_window: DEFAULT_ETH_PROOF_WINDOW,
+        }
+    }
+}
+
 /// Container type `EthApi`
 struct EthApiInner<Provider, Pool, Network, EvmConfig> {
     /// The transaction pool.
@@ -475,14 +671,18 @@ struct EthApiInner<Provider, Pool, Network, EvmConfig> {
     eth_cache: EthStateCache,
     /// The async gas oracle frontend for gas price suggestions
     gas_oracle: GasPriceOracle<Provider>,
-    /// Maximum gas limit for `eth_call` and call tracing RPC methods.
-    gas_cap: u64,
+    /// The configured limits of the API
+    config: EthApiConfig,
     /// The block number at which the node started
     starting_block: U256,
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
     /// Cached pending block if any
     pending_block: Mutex<Option<PendingBlock>>,
+    /// The pending block that is continuously rebuilt in the background, if enabled
+    maintained_pending_block: parking_lot::RwLock<Option<MaintainedPendingBlock>>,
+    /// Notifications about rebuilt pending blocks
+    pending_block_notifications: broadcast::Sender<Arc<SealedBlockWithSenders>>,
     /// A pool dedicated to blocking tasks.
     blocking_task_pool: BlockingTaskPool,
     /// Cache for block fees history
@@ -492,3 +692,146 @@ struct EthApiInner<Provider, Pool, Network, EvmConfig> {
     /// Allows forwarding received raw transactions
     raw_transaction_forwarder: Option<Arc<dyn RawTransactionForwarder>>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eth::{
+        cache::EthStateCache, gas_oracle::GasPriceOracle, revm_utils::EvmOverrides, EthApiConfig,
+        FeeHistoryCache, FeeHistoryCacheConfig,
+    };
+    use futures::channel::mpsc;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::{
+        address,
+        constants::{ETHEREUM_BLOCK_GAS_LIMIT, MIN_PROTOCOL_BASE_FEE},
+        hex_literal::hex,
+        Block, Bytes, ChainSpecBuilder, Header, TransactionKind,
+    };
+    use reth_provider::{
+        test_utils::{ExtendedAccount, MockEthProvider},
+        BundleStateWithReceipts, Chain,
+    };
+    use reth_rpc_types::TransactionRequest;
+    use reth_transaction_pool::{
+        test_utils::{testing_pool, MockTransaction, TestPool},
+        TransactionOrigin,
+    };
+
+    const SENDER: Address = address!("0000000000000000000000000000000000001000");
+    const RECIPIENT: Address = address!("0000000000000000000000000000000000002000");
+
+    /// Returns an api on a chain with all hardforks activated, and the latest block of the chain.
+    ///
+    /// The pool holds a transaction that sends 100 wei to [RECIPIENT], whose code returns its own
+    /// balance.
+    async fn eth_api_with_pending_transfer(
+    ) -> (EthApi<MockEthProvider, TestPool, NoopNetwork, EthEvmConfig>, SealedHeader) {
+        let chain_spec = Arc::new(ChainSpecBuilder::mainnet().cancun_activated().build());
+        let provider = MockEthProvider { chain_spec, ..Default::default() };
+        let header = Header {
+            number: 1,
+            gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+            timestamp: 1_000,
+            base_fee_per_gas: Some(MIN_PROTOCOL_BASE_FEE),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            ..Default::default()
+        };
+        let latest = header.clone().seal_slow();
+        provider.add_block(latest.hash(), Block { header, ..Default::default() });
+        provider.add_account(SENDER, ExtendedAccount::new(0, U256::from(1_000_000_000u64)));
+        // SELFBALANCE PUSH0 MSTORE PUSH1 0x20 PUSH0 RETURN
+        let code = Bytes::from_static(&hex!("475f5260205ff3"));
+        provider.add_account(RECIPIENT, ExtendedAccount::new(0, U256::ZERO).with_bytecode(code));
+
+        let pool = testing_pool();
+        let transfer = MockTransaction::Eip1559 {
+            hash: B256::random(),
+            sender: SENDER,
+            nonce: 0,
+            max_fee_per_gas: 2 * MIN_PROTOCOL_BASE_FEE as u128,
+            max_priority_fee_per_gas: MIN_PROTOCOL_BASE_FEE as u128,
+            gas_limit: 50_000,
+            to: TransactionKind::Call(RECIPIENT),
+            value: U256::from(100),
+            input: Bytes::new(),
+            accesslist: Default::default(),
+            size: Default::default(),
+        };
+        pool.add_transaction(TransactionOrigin::External, transfer).await.unwrap();
+
+        let evm_config = EthEvmConfig::default();
+        let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+        let eth_api = EthApi::new(
+            provider.clone(),
+            pool,
+            NoopNetwork::default(),
+            cache.clone(),
+            GasPriceOracle::new(provider, Default::default(), cache.clone()),
+            EthApiConfig::default(),
+            BlockingTaskPool::build().expect("failed to build tracing pool"),
+            FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+            evm_config,
+            None,
+        );
+        (eth_api, latest)
+    }
+
+    #[tokio::test]
+    async fn maintains_pending_block() {
+        let (eth_api, latest) = eth_api_with_pending_transfer().await;
+        let mut rebuilt = eth_api.pending_block_notifications().subscribe();
+        let (canon_tx, canon_rx) = mpsc::unbounded();
+        let task = tokio::spawn(eth_api.clone().maintain_pending_block(canon_rx));
+
+        // the initial pending block includes the pending transaction
+        let block = rebuilt.recv().await.unwrap();
+        assert_eq!(block.number, latest.number + 1);
+        assert_eq!(block.parent_hash, latest.hash());
+        assert_eq!(block.body.len(), 1);
+        let pending = eth_api.local_pending_block().await.unwrap();
+        assert_eq!(pending.map(|pending| pending.hash()), Some(block.hash()));
+
+        // the pending block is rebuilt on top of a new canonical block
+        let header = Header { number: 2, parent_hash: latest.hash(), ..latest.header().clone() };
+        let tip = Block { header, ..Default::default() }.seal_slow();
+        eth_api.provider().add_block(tip.hash(), tip.clone().unseal());
+        let chain = Chain::new(
+            [SealedBlockWithSenders { block: tip.clone(), senders: vec![] }],
+            BundleStateWithReceipts::default(),
+            None,
+        );
+        canon_tx.unbounded_send(CanonStateNotification::Commit { new: Arc::new(chain) }).unwrap();
+
+        let block = rebuilt.recv().await.unwrap();
+        assert_eq!(block.number, 3);
+        assert_eq!(block.parent_hash, tip.hash());
+        assert_eq!(block.body.len(), 1);
+
+        // the task ends with the canonical state stream
+        drop(canon_tx);
+        task.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn serves_pending_state_from_maintained_block() {
+        let (eth_api, _) = eth_api_with_pending_transfer().await;
+        let mut rebuilt = eth_api.pending_block_notifications().subscribe();
+        let (_canon_tx, canon_rx) = mpsc::unbounded();
+        tokio::spawn(eth_api.clone().maintain_pending_block(canon_rx));
+        rebuilt.recv().await.unwrap();
+
+        let pending = Some(BlockNumberOrTag::Pending.into());
+
+        // eth_getBalance
+        assert_eq!(eth_api.balance(RECIPIENT, pending).unwrap(), U256::from(100));
+        assert_eq!(eth_api.balance(RECIPIENT, None).unwrap(), U256::ZERO);
+
+        // eth_call
+        let request = TransactionRequest { to: Some(RECIPIENT), ..Default::default() };
+        let balance = eth_api.call(request, pending, EvmOverrides::default()).await.unwrap();
+        assert_eq!(balance, Bytes::from(U256::from(100).to_be_bytes_vec()));
+    }
+}
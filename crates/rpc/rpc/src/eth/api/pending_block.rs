@@ -8,10 +8,14 @@ use reth_primitives::{
     revm_primitives::{
         BlockEnv, CfgEnvWithHandlerCfg, EVMError, Env, InvalidTransaction, ResultAndState, SpecId,
     },
-    Block, BlockId, BlockNumberOrTag, ChainSpec, Header, IntoRecoveredTransaction, Receipt,
-    Receipts, SealedBlockWithSenders, SealedHeader, B256, EMPTY_OMMER_ROOT_HASH, U256,
+    Block, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag, ChainSpec, Header,
+    IntoRecoveredTransaction, Receipt, Receipts, SealedBlockWithSenders, SealedHeader, B256,
+    EMPTY_OMMER_ROOT_HASH, U256,
+};
+use reth_provider::{
+    BundleStateDataProvider, BundleStateProvider, BundleStateWithReceipts, ChainSpecProvider,
+    StateProviderBox, StateProviderFactory,
 };
-use reth_provider::{BundleStateWithReceipts, ChainSpecProvider, StateProviderFactory};
 use reth_revm::{
     database::StateProviderDatabase,
     state_change::{apply_beacon_root_contract_call, post_block_withdrawals_balance_increments},
@@ -19,7 +23,7 @@ use reth_revm::{
 use reth_transaction_pool::{BestTransactionsAttributes, TransactionPool};
 use revm::{db::states::bundle_state::BundleRetention, Database, DatabaseCommit, State};
 use revm_primitives::EnvWithHandlerCfg;
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 /// Configured [BlockEnv] and [CfgEnvWithHandlerCfg] for a pending block
 #[derive(Debug, Clone)]
@@ -39,11 +43,13 @@ impl PendingBlockEnv {
     ///
     /// After Cancun, if the origin is the actual pending block, the block includes the EIP-4788 pre
     /// block contract call using the parent beacon block root received from the CL.
+    ///
+    /// Returns the block together with its post-state.
     pub(crate) fn build_block<Client, Pool>(
         self,
         client: &Client,
         pool: &Pool,
-    ) -> EthResult<SealedBlockWithSenders>
+    ) -> EthResult<(SealedBlockWithSenders, BundleStateWithReceipts)>
     where
         Client: StateProviderFactory + ChainSpecProvider,
         Pool: TransactionPool,
@@ -264,7 +270,7 @@ impl PendingBlockEnv {
 
         // seal the block
         let block = Block { header, body: executed_txs, ommers: vec![], withdrawals };
-        Ok(SealedBlockWithSenders { block: block.seal_slow(), senders })
+        Ok((SealedBlockWithSenders { block: block.seal_slow(), senders }, bundle))
     }
 }
 
@@ -374,3 +380,42 @@ pub(crate) struct PendingBlock {
     /// Timestamp when the pending block is considered outdated
     pub(crate) expires_at: Instant,
 }
+
+/// The pending block that is continuously rebuilt in the background, together with its post-state.
+///
+/// See also [EthApi::maintain_pending_block](crate::EthApi::maintain_pending_block).
+#[derive(Debug, Clone)]
+pub(crate) struct MaintainedPendingBlock {
+    /// The pending block.
+    pub(crate) block: Arc<SealedBlockWithSenders>,
+    /// The state changes of the pending block on top of its parent.
+    pub(crate) bundle: Arc<BundleStateWithReceipts>,
+}
+
+impl MaintainedPendingBlock {
+    /// Returns the parent of the pending block.
+    pub(crate) fn parent(&self) -> BlockNumHash {
+        BlockNumHash { number: self.block.number.saturating_sub(1), hash: self.block.parent_hash }
+    }
+
+    /// Returns a state provider for the post-state of the pending block, on top of the state of
+    /// its parent.
+    pub(crate) fn state_provider(&self, parent_state: StateProviderBox) -> StateProviderBox {
+        Box::new(BundleStateProvider::new(parent_state, self.clone()))
+    }
+}
+
+impl BundleStateDataProvider for MaintainedPendingBlock {
+    fn state(&self) -> &BundleStateWithReceipts {
+        &self.bundle
+    }
+
+    fn block_hash(&self, _block_number: BlockNumber) -> Option<BlockHash> {
+        // all ancestors of the pending block are canonical
+        None
+    }
+
+    fn canonical_fork(&self) -> BlockNumHash {
+        self.parent()
+    }
+}
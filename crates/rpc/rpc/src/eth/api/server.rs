@@ -25,7 +25,7 @@ use reth_rpc_api::EthApiServer;
 use reth_rpc_types::{
     state::StateOverride, AccessListWithGasUsed, AnyTransactionReceipt, BlockOverrides, Bundle,
     EIP1186AccountProofResponse, EthCallResponse, FeeHistory, Header, Index, RichBlock,
-    StateContext, SyncStatus, TransactionRequest, Work,
+    SimulatePayload, SimulatedBlock, StateContext, SyncStatus, TransactionRequest, Work,
 };
 use reth_transaction_pool::TransactionPool;
 use serde_json::Value;
@@ -292,6 +292,16 @@ where
         Ok(EthApi::call_many(self, bundle, state_context, state_override).await?)
     }
 
+    /// Handler for: `eth_simulateV1`
+    async fn simulate_v1(
+        &self,
+        payload: SimulatePayload,
+        block_number: Option<BlockId>,
+    ) -> Result<Vec<SimulatedBlock>> {
+        trace!(target: "rpc::eth", ?block_number, "Serving eth_simulateV1");
+        Ok(EthApi::simulate_v1(self, payload, block_number).await?)
+    }
+
     /// Handler for: `eth_createAccessList`
     async fn create_access_list(
         &self,
@@ -437,7 +447,7 @@ where
 mod tests {
     use crate::{
         eth::{
-            cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache,
+            cache::EthStateCache, gas_oracle::GasPriceOracle, EthApiConfig, FeeHistoryCache,
             FeeHistoryCacheConfig,
         },
         EthApi,
@@ -482,7 +492,7 @@ mod tests {
             NoopNetwork::default(),
             cache.clone(),
             GasPriceOracle::new(provider, Default::default(), cache),
-            ETHEREUM_BLOCK_GAS_LIMIT,
+            EthApiConfig::default().gas_cap(ETHEREUM_BLOCK_GAS_LIMIT),
             BlockingTaskPool::build().expect("failed to build tracing pool"),
             fee_history_cache,
             evm_config,
@@ -83,23 +83,12 @@ where
         keys: Vec<JsonStorageKey>,
         block_id: Option<BlockId>,
     ) -> EthResult<EIP1186AccountProofResponse> {
-        let chain_info = self.provider().chain_info()?;
         let block_id = block_id.unwrap_or(BlockId::Number(BlockNumberOrTag::Latest));
-
-        // if we are trying to create a proof for the latest block, but have a BlockId as input
-        // that is not BlockNumberOrTag::Latest, then we need to figure out whether or not the
-        // BlockId corresponds to the latest block
-        let is_latest_block = match block_id {
-            BlockId::Number(BlockNumberOrTag::Number(num)) => num == chain_info.best_number,
-            BlockId::Hash(hash) => hash == chain_info.best_hash.into(),
-            BlockId::Number(BlockNumberOrTag::Latest) => true,
-            _ => false,
-        };
-
-        // TODO: remove when HistoricalStateProviderRef::proof is implemented
-        if !is_latest_block {
-            return Err(EthApiError::InvalidBlockRange)
-        }
+        let block_number = self
+            .provider()
+            .block_number_for_id(block_id)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        self.ensure_within_proof_window(block_number)?;
 
         let this = self.clone();
         self.inner
@@ -113,16 +102,30 @@ where
             .await
             .map_err(|_| EthApiError::InternalBlockingTaskError)?
     }
+
+    /// Returns [EthApiError::ExceedsMaxProofWindow] if proofs over the state at the end of the
+    /// given block are not served.
+    ///
+    /// Historical proofs revert the state down to the requested block, so the distance to the tip
+    /// is bounded by the configured window.
+    pub(crate) fn ensure_within_proof_window(&self, block_number: u64) -> EthResult<()> {
+        let chain_info = self.provider().chain_info()?;
+        if chain_info.best_number.saturating_sub(block_number) > self.eth_proof_window() {
+            return Err(EthApiError::ExceedsMaxProofWindow)
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::eth::{
-        cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache, FeeHistoryCacheConfig,
+        cache::EthStateCache, gas_oracle::GasPriceOracle, EthApiConfig, FeeHistoryCache,
+        FeeHistoryCacheConfig,
     };
     use reth_evm_ethereum::EthEvmConfig;
-    use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, StorageKey, StorageValue};
+    use reth_primitives::{constants::ETHEREUM_BLOCK_GAS_LIMIT, Header, StorageKey, StorageValue};
     use reth_provider::test_utils::{ExtendedAccount, MockEthProvider, NoopProvider};
     use reth_tasks::pool::BlockingTaskPool;
     use reth_transaction_pool::test_utils::testing_pool;
@@ -141,7 +144,7 @@ mod tests {
             (),
             cache.clone(),
             GasPriceOracle::new(NoopProvider::default(), Default::default(), cache.clone()),
-            ETHEREUM_BLOCK_GAS_LIMIT,
+            EthApiConfig::default().gas_cap(ETHEREUM_BLOCK_GAS_LIMIT),
             BlockingTaskPool::build().expect("failed to build tracing pool"),
             FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
             evm_config,
@@ -166,7 +169,7 @@ mod tests {
             (),
             cache.clone(),
             GasPriceOracle::new(mock_provider, Default::default(), cache.clone()),
-            ETHEREUM_BLOCK_GAS_LIMIT,
+            EthApiConfig::default().gas_cap(ETHEREUM_BLOCK_GAS_LIMIT),
             BlockingTaskPool::build().expect("failed to build tracing pool"),
             FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
             evm_config,
@@ -177,4 +180,44 @@ mod tests {
         let storage = eth_api.storage_at(address, storage_key.into(), None).unwrap();
         assert_eq!(storage, storage_value.to_be_bytes());
     }
+
+    #[tokio::test]
+    async fn test_proof_window() {
+        let mock_provider = MockEthProvider::default();
+        mock_provider.add_header(B256::random(), Header { number: 10, ..Default::default() });
+
+        let evm_config = EthEvmConfig::default();
+        let eth_api = |eth_proof_window| {
+            let cache = EthStateCache::spawn(mock_provider.clone(), Default::default(), evm_config);
+            EthApi::new(
+                mock_provider.clone(),
+                testing_pool(),
+                (),
+                cache.clone(),
+                GasPriceOracle::new(mock_provider.clone(), Default::default(), cache.clone()),
+                EthApiConfig::default()
+                    .gas_cap(ETHEREUM_BLOCK_GAS_LIMIT)
+                    .eth_proof_window(eth_proof_window),
+                BlockingTaskPool::build().expect("failed to build tracing pool"),
+                FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+                evm_config,
+                None,
+            )
+        };
+        let address = Address::random();
+        let block_id = Some(BlockId::Number(BlockNumberOrTag::Number(5)));
+
+        // the tip is always within the window
+        assert!(eth_api(0).get_proof(address, Vec::new(), None).await.is_ok());
+
+        assert!(matches!(
+            eth_api(0).get_proof(address, Vec::new(), block_id).await,
+            Err(EthApiError::ExceedsMaxProofWindow)
+        ));
+        assert!(matches!(
+            eth_api(4).get_proof(address, Vec::new(), block_id).await,
+            Err(EthApiError::ExceedsMaxProofWindow)
+        ));
+        assert!(eth_api(5).get_proof(address, Vec::new(), block_id).await.is_ok());
+    }
 }
@@ -10,10 +10,12 @@ use crate::{
 };
 use async_trait::async_trait;
 use reth_evm::ConfigureEvm;
+use reth_interfaces::RethError;
 use reth_network_api::NetworkInfo;
 use reth_primitives::{
     eip4844::calc_blob_gasprice,
     revm::env::{fill_block_env_with_coinbase, tx_env_with_recovered},
+    trie::ExecutionWitness,
     Address, BlockId, BlockNumberOrTag, Bytes, FromRecoveredPooledTransaction, Header,
     IntoRecoveredTransaction, Receipt, SealedBlock, SealedBlockWithSenders,
     TransactionKind::{Call, Create},
@@ -25,6 +27,7 @@ use reth_provider::{
 use reth_revm::{
     database::StateProviderDatabase,
     tracing::{TracingInspector, TracingInspectorConfig},
+    witness::generate_execution_witness,
 };
 use reth_rpc_types::{
     transaction::{
@@ -531,6 +534,13 @@ pub trait EthTransactions: Send + Sync {
         Setup: FnMut() -> Insp + Send + 'static,
         Insp: for<'a> Inspector<&'a mut StateCacheDB> + Send + 'static,
         R: Send + 'static;
+
+    /// Executes the block on top of its parent's state and returns the [ExecutionWitness] of the
+    /// block.
+    ///
+    /// Returns `None` if the block does not exist, and [EthApiError::ExceedsMaxProofWindow] if
+    /// the parent block is further from the tip than the proof window.
+    async fn execution_witness(&self, block_id: BlockId) -> EthResult<Option<ExecutionWitness>>;
 }
 
 #[async_trait]
@@ -624,7 +634,7 @@ where
     }
 
     fn call_gas_limit(&self) -> u64 {
-        self.inner.gas_cap
+        self.inner.config.gas_cap
     }
 
     async fn spawn_blocking_future<F, R>(&self, c: F) -> EthResult<R>
@@ -673,6 +683,14 @@ where
     ) -> EthResult<(CfgEnvWithHandlerCfg, BlockEnv, BlockId)> {
         if at.is_pending() {
             let PendingBlockEnv { cfg, block_env, origin } = self.pending_block_env_and_cfg()?;
+            // if the pending block is maintained for the same parent, its post-state is the state
+            // of the pending block
+            if !origin.is_actual_pending() &&
+                self.maintained_pending_block()
+                    .is_some_and(|pending| pending.block.parent_hash == origin.header().hash())
+            {
+                return Ok((cfg, block_env, BlockNumberOrTag::Pending.into()))
+            }
             Ok((cfg, block_env, origin.state_block_id()))
         } else {
             // Use cached values if there is no pending block
@@ -1331,6 +1349,35 @@ where
         .await
         .map(Some)
     }
+
+    async fn execution_witness(&self, block_id: BlockId) -> EthResult<Option<ExecutionWitness>> {
+        if block_id.is_pending() {
+            return Err(EthApiError::Unsupported("execution witness of pending block"))
+        }
+        let Some(block) = self.block_with_senders(block_id).await? else { return Ok(None) };
+        // the witness contains the proofs of the accessed state of the parent block
+        self.ensure_within_proof_window(block.number.saturating_sub(1))?;
+
+        self.spawn_tracing_task_with(move |this| {
+            let total_difficulty = this
+                .provider()
+                .header_td_by_number(block.number)?
+                .ok_or(EthApiError::UnknownBlockNumber)?;
+            // the block is executed on top of its parent block's state
+            let state = this.state_at(block.parent_hash.into())?;
+            let witness = generate_execution_witness(
+                this.provider().chain_spec(),
+                this.inner.evm_config.clone(),
+                state,
+                this.provider(),
+                &block.unseal(),
+                total_difficulty,
+            )
+            .map_err(RethError::from)?;
+            Ok(Some(witness))
+        })
+        .await
+    }
 }
 
 // === impl EthApi ===
@@ -1785,7 +1832,8 @@ pub(crate) fn build_transaction_receipt_with_block_receipts(
 mod tests {
     use super::*;
     use crate::eth::{
-        cache::EthStateCache, gas_oracle::GasPriceOracle, FeeHistoryCache, FeeHistoryCacheConfig,
+        cache::EthStateCache, gas_oracle::GasPriceOracle, EthApiConfig, FeeHistoryCache,
+        FeeHistoryCacheConfig,
     };
     use reth_evm_ethereum::EthEvmConfig;
     use reth_network_api::noop::NoopNetwork;
@@ -1811,7 +1859,7 @@ mod tests {
             noop_network_provider,
             cache.clone(),
             GasPriceOracle::new(noop_provider, Default::default(), cache.clone()),
-            ETHEREUM_BLOCK_GAS_LIMIT,
+            EthApiConfig::default().gas_cap(ETHEREUM_BLOCK_GAS_LIMIT),
             BlockingTaskPool::build().expect("failed to build tracing pool"),
             fee_history_cache,
             evm_config,
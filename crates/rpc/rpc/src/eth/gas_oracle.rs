@@ -6,11 +6,20 @@ use crate::eth::{
     error::{EthApiError, EthResult, RpcInvalidTransactionError},
 };
 use derive_more::{Deref, DerefMut};
-use reth_primitives::{constants::GWEI_TO_WEI, BlockNumberOrTag, B256, U256};
+use reth_primitives::{
+    basefee::calculate_next_block_base_fee,
+    constants::{eip4844::MAX_DATA_GAS_PER_BLOCK, GWEI_TO_WEI},
+    eip4844::{calc_blob_gasprice, calculate_excess_blob_gas},
+    BaseFeeParams, BlockNumberOrTag, SealedHeader, B256, U256,
+};
 use reth_provider::BlockReaderIdExt;
 use schnellru::{ByLength, LruMap};
 use serde::{Deserialize, Serialize};
-use std::fmt::{self, Debug, Formatter};
+use std::{
+    fmt::{self, Debug, Formatter},
+    str::FromStr,
+    sync::Arc,
+};
 use tokio::sync::Mutex;
 use tracing::warn;
 
@@ -33,6 +42,52 @@ pub const DEFAULT_MAX_GAS_PRICE: U256 = U256::from_limbs([500_000_000_000u64, 0,
 /// The default minimum gas price, under which the sample will be ignored
 pub const DEFAULT_IGNORE_GAS_PRICE: U256 = U256::from_limbs([2u64, 0, 0, 0]);
 
+/// The default number of blocks a suggested max fee should remain sufficient for
+pub const DEFAULT_FEE_PROJECTION_BLOCKS: u64 = 3;
+
+/// The maximum number of pending transactions that are visited to collect the mempool tips
+pub const MAX_MEMPOOL_TIP_SAMPLES: usize = 2048;
+
+/// The percentile of priority fees used for the `slow` fee estimate tier
+pub const SLOW_FEE_ESTIMATE_PERCENTILE: u32 = 25;
+
+/// The percentile of priority fees used for the `fast` fee estimate tier
+pub const FAST_FEE_ESTIMATE_PERCENTILE: u32 = 90;
+
+/// The strategy used by the [GasPriceOracle] to suggest priority fees.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GasPriceOracleStrategy {
+    /// Use the configured percentile of the lowest tips of recent blocks, like geth does.
+    #[default]
+    Percentile,
+    /// Use the configured percentile of the tips of the transactions in the pending subpool that
+    /// fit into the next block, falling back to [GasPriceOracleStrategy::Percentile] if the pool
+    /// has no pending transactions.
+    Mempool,
+}
+
+impl fmt::Display for GasPriceOracleStrategy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Percentile => f.write_str("percentile"),
+            Self::Mempool => f.write_str("mempool"),
+        }
+    }
+}
+
+impl FromStr for GasPriceOracleStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "percentile" => Ok(Self::Percentile),
+            "mempool" => Ok(Self::Mempool),
+            _ => Err(format!("invalid gas price oracle strategy: {s}")),
+        }
+    }
+}
+
 /// Settings for the [GasPriceOracle]
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -57,6 +112,18 @@ pub struct GasPriceOracleConfig {
 
     /// The minimum gas price, under which the sample will be ignored
     pub ignore_price: Option<U256>,
+
+    /// The strategy used to suggest priority fees
+    #[serde(default)]
+    pub strategy: GasPriceOracleStrategy,
+
+    /// The number of full blocks the suggested max fees should account for
+    #[serde(default = "default_projection_blocks")]
+    pub projection_blocks: u64,
+}
+
+const fn default_projection_blocks() -> u64 {
+    DEFAULT_FEE_PROJECTION_BLOCKS
 }
 
 impl Default for GasPriceOracleConfig {
@@ -69,6 +136,8 @@ impl Default for GasPriceOracleConfig {
             default: None,
             max_price: Some(DEFAULT_MAX_GAS_PRICE),
             ignore_price: Some(DEFAULT_IGNORE_GAS_PRICE),
+            strategy: GasPriceOracleStrategy::Percentile,
+            projection_blocks: DEFAULT_FEE_PROJECTION_BLOCKS,
         }
     }
 }
@@ -110,6 +179,7 @@ where
         let cached_values = (oracle_config.blocks * 5).max(oracle_config.max_block_history as u32);
         let inner = Mutex::new(GasPriceOracleInner {
             last_price: Default::default(),
+            last_mempool_tips: Default::default(),
             lowest_effective_tip_cache: EffectiveTipLruCache(LruMap::new(ByLength::new(
                 cached_values,
             ))),
@@ -123,6 +193,19 @@ where
         &self.oracle_config
     }
 
+    /// Returns the price under which samples are ignored.
+    pub fn ignore_price(&self) -> Option<u128> {
+        self.ignore_price
+    }
+
+    /// Constrains the given priority fee to the configured maximum price.
+    pub fn cap_price(&self, price: U256) -> U256 {
+        match self.oracle_config.max_price {
+            Some(max_price) => price.min(max_price),
+            None => price,
+        }
+    }
+
     /// Suggests a gas price estimate based on recent blocks, using the configured percentile.
     pub async fn suggest_tip_cap(&self) -> EthResult<U256> {
         let header = self
@@ -137,6 +220,65 @@ where
             return Ok(inner.last_price.price)
         }
 
+        let results = self.sample_tips(&header, &mut inner).await?;
+
+        // take the configured percentile result
+        let price = percentile_of(&results, self.oracle_config.percentile)
+            .unwrap_or(inner.last_price.price);
+
+        // constrain to the max price
+        let price = self.cap_price(price);
+
+        inner.last_price = GasPriceOracleResult { block_hash: header.hash(), price };
+
+        Ok(price)
+    }
+
+    /// Returns the sorted mempool tips sampled on top of the block with the given hash.
+    ///
+    /// The tips are only sampled with the given closure once per block, further calls for the same
+    /// block return the cached tips.
+    pub async fn mempool_tips(
+        &self,
+        block_hash: B256,
+        sample: impl FnOnce() -> Vec<U256>,
+    ) -> Arc<Vec<U256>> {
+        let mut inner = self.inner.lock().await;
+        if inner.last_mempool_tips.block_hash != block_hash {
+            inner.last_mempool_tips = MempoolTipsResult { block_hash, tips: Arc::new(sample()) };
+        }
+        inner.last_mempool_tips.tips.clone()
+    }
+
+    /// Suggests a priority fee for each of the given percentiles of the tips of recent blocks.
+    ///
+    /// Unlike [GasPriceOracle::suggest_tip_cap], the result is not cached.
+    pub async fn suggest_tip_caps(&self, percentiles: &[u32]) -> EthResult<Vec<U256>> {
+        let header = self
+            .provider
+            .sealed_header_by_number_or_tag(BlockNumberOrTag::Latest)?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+
+        let mut inner = self.inner.lock().await;
+        let results = self.sample_tips(&header, &mut inner).await?;
+
+        Ok(percentiles
+            .iter()
+            .map(|percentile| {
+                self.cap_price(
+                    percentile_of(&results, *percentile).unwrap_or(inner.last_price.price),
+                )
+            })
+            .collect())
+    }
+
+    /// Returns the sorted lowest effective tips of the recent populated blocks, starting at the
+    /// given header.
+    async fn sample_tips(
+        &self,
+        header: &SealedHeader,
+        inner: &mut GasPriceOracleInner,
+    ) -> EthResult<Vec<U256>> {
         // if all responses are empty, then we can return a maximum of 2*check_block blocks' worth
         // of prices
         //
@@ -185,26 +327,8 @@ where
             current_hash = parent_hash;
         }
 
-        // sort results then take the configured percentile result
-        let mut price = if !results.is_empty() {
-            results.sort_unstable();
-            *results.get((results.len() - 1) * self.oracle_config.percentile as usize / 100).expect(
-                "gas price index is a percent of nonzero array length, so a value always exists",
-            )
-        } else {
-            inner.last_price.price
-        };
-
-        // constrain to the max price
-        if let Some(max_price) = self.oracle_config.max_price {
-            if price > max_price {
-                price = max_price;
-            }
-        }
-
-        inner.last_price = GasPriceOracleResult { block_hash: header.hash(), price };
-
-        Ok(price)
+        results.sort_unstable();
+        Ok(results)
     }
 
     /// Get the `limit` lowest effective tip values for the given block. If the oracle has a
@@ -269,10 +393,46 @@ where
     }
 }
 
+/// Returns the value at the given percentile of the sorted values, or `None` if there are none.
+pub fn percentile_of(sorted: &[U256], percentile: u32) -> Option<U256> {
+    if sorted.is_empty() {
+        return None
+    }
+    let percentile = percentile.min(100) as usize;
+    sorted.get((sorted.len() - 1) * percentile / 100).copied()
+}
+
+/// Projects the base fee of the block `blocks` blocks after the next block, assuming all blocks in
+/// between use their entire gas limit.
+///
+/// With `blocks = 0` this returns the given base fee of the next block.
+pub fn project_base_fee(
+    next_base_fee: u64,
+    gas_limit: u64,
+    base_fee_params: BaseFeeParams,
+    blocks: u64,
+) -> u64 {
+    (0..blocks).fold(next_base_fee, |base_fee, _| {
+        calculate_next_block_base_fee(gas_limit, gas_limit, base_fee, base_fee_params)
+    })
+}
+
+/// Projects the blob base fee of the block `blocks` blocks after the next block, assuming all
+/// blocks in between use the maximum amount of blob gas.
+///
+/// With `blocks = 0` this returns the blob base fee of the next block.
+pub fn project_blob_fee(next_excess_blob_gas: u64, blocks: u64) -> u128 {
+    let excess_blob_gas = (0..blocks).fold(next_excess_blob_gas, |excess_blob_gas, _| {
+        calculate_excess_blob_gas(excess_blob_gas, MAX_DATA_GAS_PER_BLOCK)
+    });
+    calc_blob_gasprice(excess_blob_gas)
+}
+
 /// Container type for mutable inner state of the [GasPriceOracle]
 #[derive(Debug)]
 struct GasPriceOracleInner {
     last_price: GasPriceOracleResult,
+    last_mempool_tips: MempoolTipsResult,
     lowest_effective_tip_cache: EffectiveTipLruCache,
 }
 
@@ -304,9 +464,20 @@ impl Default for GasPriceOracleResult {
     }
 }
 
+/// Stores the last mempool tips that the oracle sampled
+#[derive(Debug, Clone, Default)]
+struct MempoolTipsResult {
+    /// The block hash on top of which the tips were sampled
+    block_hash: B256,
+    /// The sorted tips of the pending transactions
+    tips: Arc<Vec<U256>>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_provider::test_utils::NoopProvider;
 
     #[test]
     fn max_price_sanity() {
@@ -318,4 +489,55 @@ mod tests {
     fn ignore_price_sanity() {
         assert_eq!(DEFAULT_IGNORE_GAS_PRICE, U256::from(2u64));
     }
+
+    #[test]
+    fn strategy_from_str() {
+        for strategy in [GasPriceOracleStrategy::Percentile, GasPriceOracleStrategy::Mempool] {
+            assert_eq!(strategy.to_string().parse::<GasPriceOracleStrategy>(), Ok(strategy));
+        }
+        assert!("latest".parse::<GasPriceOracleStrategy>().is_err());
+    }
+
+    #[test]
+    fn percentile_of_sorted() {
+        assert_eq!(percentile_of(&[], 50), None);
+        let values = (1..=5u64).map(U256::from).collect::<Vec<_>>();
+        assert_eq!(percentile_of(&values, 0), Some(U256::from(1)));
+        assert_eq!(percentile_of(&values, 50), Some(U256::from(3)));
+        assert_eq!(percentile_of(&values, 100), Some(U256::from(5)));
+        assert_eq!(percentile_of(&values, 200), Some(U256::from(5)));
+    }
+
+    #[test]
+    fn project_full_blocks() {
+        let params = BaseFeeParams::ethereum();
+        assert_eq!(project_base_fee(1_000_000_000, 30_000_000, params, 0), 1_000_000_000);
+        // every full block increases the base fee by 12.5%
+        assert_eq!(project_base_fee(1_000_000_000, 30_000_000, params, 1), 1_125_000_000);
+        assert_eq!(project_base_fee(1_000_000_000, 30_000_000, params, 2), 1_265_625_000);
+
+        assert_eq!(project_blob_fee(0, 0), 1);
+        assert!(project_blob_fee(0, 10) > project_blob_fee(0, 1));
+    }
+
+    #[tokio::test]
+    async fn mempool_tips_cached_per_block() {
+        let cache = EthStateCache::spawn(
+            NoopProvider::default(),
+            Default::default(),
+            EthEvmConfig::default(),
+        );
+        let oracle = GasPriceOracle::new(NoopProvider::default(), Default::default(), cache);
+
+        let first_block = B256::with_last_byte(1);
+        let tips = oracle.mempool_tips(first_block, || vec![U256::from(1)]).await;
+        assert_eq!(*tips, vec![U256::from(1)]);
+
+        // the pool is not sampled again for the same block
+        let tips = oracle.mempool_tips(first_block, || unreachable!("tips are cached")).await;
+        assert_eq!(*tips, vec![U256::from(1)]);
+
+        let tips = oracle.mempool_tips(B256::with_last_byte(2), || vec![U256::from(2)]).await;
+        assert_eq!(*tips, vec![U256::from(2)]);
+    }
 }
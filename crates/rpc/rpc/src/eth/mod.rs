@@ -11,6 +11,7 @@ mod logs_utils;
 mod pubsub;
 pub mod revm_utils;
 mod signer;
+mod simulate;
 pub mod traits;
 pub(crate) mod utils;
 
@@ -19,10 +20,13 @@ pub mod optimism;
 
 pub use api::{
     fee_history::{fee_history_cache_new_blocks_task, FeeHistoryCache, FeeHistoryCacheConfig},
-    EthApi, EthApiSpec, EthTransactions, TransactionSource, RPC_DEFAULT_GAS_CAP,
+    EthApi, EthApiConfig, EthApiSpec, EthFees, EthTransactions, TransactionSource,
+    DEFAULT_ETH_PROOF_WINDOW, DEFAULT_MAX_SIMULATE_BLOCKS, PENDING_BLOCK_REBUILD_INTERVAL,
+    RPC_DEFAULT_GAS_CAP,
 };
 
 pub use bundle::EthBundle;
 pub use filter::{EthFilter, EthFilterConfig};
 pub use id_provider::EthSubscriptionIdProvider;
+pub(crate) use pubsub::pipe_from_stream;
 pub use pubsub::EthPubSub;
@@ -9,7 +9,7 @@ use jsonrpsee::{
     server::SubscriptionMessage, types::ErrorObject, PendingSubscriptionSink, SubscriptionSink,
 };
 use reth_network_api::NetworkInfo;
-use reth_primitives::{IntoRecoveredTransaction, TxHash};
+use reth_primitives::{IntoRecoveredTransaction, SealedBlockWithSenders, TxHash};
 use reth_provider::{BlockReader, CanonStateSubscriptions, EvmEnvProvider};
 use reth_rpc_api::EthPubSubApiServer;
 use reth_rpc_types::{
@@ -17,12 +17,13 @@ use reth_rpc_types::{
         Params, PubSubSyncStatus, SubscriptionKind, SubscriptionResult as EthSubscriptionResult,
         SyncStatusMetadata,
     },
-    FilteredParams, Header, Log,
+    Block, EthSubscriptionKind, FilteredParams, Header, Log, RethSubscriptionKind,
 };
 use reth_tasks::{TaskSpawner, TokioTaskExecutor};
 use reth_transaction_pool::{NewTransactionEvent, TransactionPool};
 use serde::Serialize;
 use std::sync::Arc;
+use tokio::sync::{broadcast, oneshot};
 use tokio_stream::{
     wrappers::{BroadcastStream, ReceiverStream},
     Stream,
@@ -37,6 +38,8 @@ pub struct EthPubSub<Provider, Pool, Events, Network> {
     inner: Arc<EthPubSubInner<Provider, Pool, Events, Network>>,
     /// The type that's used to spawn subscription tasks.
     subscription_task_spawner: Box<dyn TaskSpawner>,
+    /// Notifications about rebuilt pending blocks, if the pending block is maintained.
+    pending_blocks: Option<broadcast::Sender<Arc<SealedBlockWithSenders>>>,
 }
 
 // === impl EthPubSub ===
@@ -64,7 +67,19 @@ impl<Provider, Pool, Events, Network> EthPubSub<Provider, Pool, Events, Network>
         subscription_task_spawner: Box<dyn TaskSpawner>,
     ) -> Self {
         let inner = EthPubSubInner { provider, pool, chain_events, network };
-        Self { inner: Arc::new(inner), subscription_task_spawner }
+        Self { inner: Arc::new(inner), subscription_task_spawner, pending_blocks: None }
+    }
+
+    /// Enables `newPendingBlock` subscriptions, which are fed by the given sender of rebuilt
+    /// pending blocks.
+    ///
+    /// See also [EthApi::pending_block_notifications](crate::EthApi::pending_block_notifications).
+    pub fn with_pending_blocks(
+        mut self,
+        pending_blocks: broadcast::Sender<Arc<SealedBlockWithSenders>>,
+    ) -> Self {
+        self.pending_blocks = Some(pending_blocks);
+        self
     }
 }
 
@@ -81,13 +96,17 @@ where
     async fn subscribe(
         &self,
         pending: PendingSubscriptionSink,
-        kind: SubscriptionKind,
+        kind: EthSubscriptionKind,
         params: Option<Params>,
     ) -> jsonrpsee::core::SubscriptionResult {
         let sink = pending.accept().await?;
         let pubsub = self.inner.clone();
+        let pending_blocks = self.pending_blocks.clone();
+        let blocking_task_spawner = self.subscription_task_spawner.clone();
         self.subscription_task_spawner.spawn(Box::pin(async move {
-            let _ = handle_accepted(pubsub, sink, kind, params).await;
+            let _ =
+                handle_accepted(pubsub, pending_blocks, blocking_task_spawner, sink, kind, params)
+                    .await;
         }));
 
         Ok(())
@@ -97,8 +116,10 @@ where
 /// The actual handler for an accepted [`EthPubSub::subscribe`] call.
 async fn handle_accepted<Provider, Pool, Events, Network>(
     pubsub: Arc<EthPubSubInner<Provider, Pool, Events, Network>>,
+    pending_blocks: Option<broadcast::Sender<Arc<SealedBlockWithSenders>>>,
+    blocking_task_spawner: Box<dyn TaskSpawner>,
     accepted_sink: SubscriptionSink,
-    kind: SubscriptionKind,
+    kind: EthSubscriptionKind,
     params: Option<Params>,
 ) -> Result<(), ErrorObject<'static>>
 where
@@ -107,6 +128,28 @@ where
     Events: CanonStateSubscriptions + Clone + 'static,
     Network: NetworkInfo + Clone + 'static,
 {
+    let kind = match kind {
+        EthSubscriptionKind::Standard(kind) => kind,
+        EthSubscriptionKind::Reth(RethSubscriptionKind::NewPendingBlock) => {
+            let Some(pending_blocks) = pending_blocks else {
+                return Err(invalid_params_rpc_err("newPendingBlock subscriptions are not enabled"))
+            };
+            let full = match params {
+                Some(Params::Bool(full)) => full,
+                Some(Params::Logs(_)) => {
+                    return Err(invalid_params_rpc_err("Invalid params for newPendingBlock"))
+                }
+                _ => false,
+            };
+            let stream = pubsub.pending_block_stream(
+                pending_blocks.subscribe(),
+                full,
+                blocking_task_spawner,
+            );
+            return pipe_from_stream(accepted_sink, stream).await
+        }
+    };
+
     match kind {
         SubscriptionKind::NewHeads => {
             let stream = pubsub
@@ -212,7 +255,7 @@ impl From<SubscriptionSerializeError> for ErrorObject<'static> {
 }
 
 /// Pipes all stream items to the subscription sink.
-async fn pipe_from_stream<T, St>(
+pub(crate) async fn pipe_from_stream<T, St>(
     sink: SubscriptionSink,
     mut stream: St,
 ) -> Result<(), ErrorObject<'static>>
@@ -311,6 +354,53 @@ where
     Network: NetworkInfo + 'static,
     Pool: 'static,
 {
+    /// Returns a stream that yields all rebuilt pending blocks as RPC blocks.
+    ///
+    /// If `full` is true, the blocks contain all transaction objects, otherwise they only contain
+    /// the transaction hashes. Notifications that were missed because the subscriber lagged behind
+    /// are skipped.
+    ///
+    /// The total difficulty of a block is looked up with the given spawner, as a blocking task.
+    fn pending_block_stream(
+        &self,
+        pending_blocks: broadcast::Receiver<Arc<SealedBlockWithSenders>>,
+        full: bool,
+        blocking_task_spawner: Box<dyn TaskSpawner>,
+    ) -> impl Stream<Item = Block> + '_
+    where
+        Provider: Clone,
+    {
+        BroadcastStream::new(pending_blocks)
+            .filter_map(|block| futures::future::ready(block.ok()))
+            .then(move |block| {
+                let provider = self.provider.clone();
+                let (tx, rx) = oneshot::channel();
+                blocking_task_spawner.spawn_blocking(Box::pin(async move {
+                    // the total difficulty does not change after the merge
+                    let total_difficulty = provider
+                        .header_td_by_number(block.number.saturating_sub(1))
+                        .ok()
+                        .flatten()
+                        .unwrap_or_default();
+                    let _ = tx.send((block, total_difficulty));
+                }));
+                rx
+            })
+            .filter_map(move |res| {
+                let block = res.ok().and_then(|(block, total_difficulty)| {
+                    let block_hash = block.hash();
+                    reth_rpc_types_compat::block::from_block(
+                        Arc::unwrap_or_clone(block).unseal(),
+                        total_difficulty,
+                        full.into(),
+                        Some(block_hash),
+                    )
+                    .ok()
+                });
+                futures::future::ready(block)
+            })
+    }
+
     /// Returns a stream that yields all new RPC blocks.
     fn new_headers_stream(&self) -> impl Stream<Item = Header> {
         self.chain_events.canonical_state_stream().flat_map(|new_chain| {
@@ -339,3 +429,83 @@ where
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::{Block as PrimitiveBlock, Header as PrimitiveHeader, B256, U256};
+    use reth_provider::test_utils::{MockEthProvider, TestCanonStateSubscriptions};
+    use reth_rpc_types::BlockTransactions;
+
+    fn pubsub_inner(
+        provider: MockEthProvider,
+    ) -> EthPubSubInner<MockEthProvider, (), TestCanonStateSubscriptions, NoopNetwork> {
+        EthPubSubInner {
+            provider,
+            pool: (),
+            chain_events: TestCanonStateSubscriptions::default(),
+            network: NoopNetwork,
+        }
+    }
+
+    fn pending_block(number: u64) -> Arc<SealedBlockWithSenders> {
+        let block = PrimitiveBlock {
+            header: PrimitiveHeader { number, ..Default::default() },
+            ..Default::default()
+        };
+        Arc::new(SealedBlockWithSenders { block: block.seal_slow(), senders: vec![] })
+    }
+
+    #[tokio::test]
+    async fn pending_block_stream_yields_rebuilt_blocks() {
+        let provider = MockEthProvider::default();
+        provider.add_header(
+            B256::with_last_byte(1),
+            PrimitiveHeader { number: 1, difficulty: U256::from(7), ..Default::default() },
+        );
+        let inner = pubsub_inner(provider);
+
+        let (tx, rx) = broadcast::channel(4);
+        let mut stream =
+            Box::pin(inner.pending_block_stream(rx, false, Box::<TokioTaskExecutor>::default()));
+
+        let first = pending_block(2);
+        let second = pending_block(3);
+        tx.send(first.clone()).unwrap();
+        tx.send(second.clone()).unwrap();
+
+        let block = stream.next().await.unwrap();
+        assert_eq!(block.header.hash, Some(first.hash()));
+        assert_eq!(block.header.number, Some(2));
+        assert_eq!(block.header.total_difficulty, Some(U256::from(7)));
+        assert!(matches!(block.transactions, BlockTransactions::Hashes(_)));
+
+        let block = stream.next().await.unwrap();
+        assert_eq!(block.header.hash, Some(second.hash()));
+
+        // the stream ends with the sender
+        drop(tx);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn pending_block_stream_skips_lagged_blocks() {
+        let inner = pubsub_inner(MockEthProvider::default());
+
+        let (tx, rx) = broadcast::channel(1);
+        let mut stream =
+            Box::pin(inner.pending_block_stream(rx, true, Box::<TokioTaskExecutor>::default()));
+
+        // the first block is overwritten before the subscriber receives it
+        tx.send(pending_block(1)).unwrap();
+        let latest = pending_block(2);
+        tx.send(latest.clone()).unwrap();
+        drop(tx);
+
+        let block = stream.next().await.unwrap();
+        assert_eq!(block.header.hash, Some(latest.hash()));
+        assert!(matches!(block.transactions, BlockTransactions::Full(_)));
+        assert!(stream.next().await.is_none());
+    }
+}
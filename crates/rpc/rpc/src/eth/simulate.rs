@@ -0,0 +1,124 @@
+//! Helpers for `eth_simulateV1`.
+
+use reth_primitives::{address, b256, Address, Bytes, Log, B256, U256};
+use revm::{
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+    Database, EvmContext, Inspector,
+};
+
+/// The address that emits the synthetic ETH transfer logs, as defined by `eth_simulateV1`.
+pub(crate) const ETH_TRANSFER_LOG_ADDRESS: Address =
+    address!("EeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE");
+
+/// The topic of the ERC-20 `Transfer(address,address,uint256)` event.
+pub(crate) const TRANSFER_EVENT_TOPIC: B256 =
+    b256!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef");
+
+/// Error code of a reverted simulated call.
+pub(crate) const SIMULATE_REVERT_ERROR_CODE: i32 = 3;
+
+/// Error code of a simulated call that halted.
+pub(crate) const SIMULATE_VM_ERROR_CODE: i32 = -32015;
+
+/// An [Inspector] that collects the logs of a call and adds an ERC-20 style `Transfer` log for
+/// every ETH transfer, see `traceTransfers` of `eth_simulateV1`.
+///
+/// Logs of reverted call frames are discarded, including their transfers.
+#[derive(Debug, Default)]
+pub(crate) struct TransferInspector {
+    /// All logs collected so far, in execution order.
+    logs: Vec<Log>,
+    /// The number of logs at the start of every active call frame.
+    frames: Vec<usize>,
+}
+
+impl TransferInspector {
+    /// Consumes the inspector and returns the collected logs.
+    pub(crate) fn into_logs(self) -> Vec<Log> {
+        self.logs
+    }
+
+    /// Pops the current call frame and discards its logs if it failed.
+    fn exit_frame(&mut self, success: bool) {
+        if let Some(start) = self.frames.pop() {
+            if !success {
+                self.logs.truncate(start);
+            }
+        }
+    }
+}
+
+/// Returns the synthetic log of an ETH transfer.
+fn transfer_log(from: Address, to: Address, value: U256) -> Log {
+    Log {
+        address: ETH_TRANSFER_LOG_ADDRESS,
+        topics: vec![TRANSFER_EVENT_TOPIC, from.into_word(), to.into_word()],
+        data: Bytes::from(value.to_be_bytes_vec()),
+    }
+}
+
+impl<DB> Inspector<DB> for TransferInspector
+where
+    DB: Database,
+{
+    fn log(&mut self, _context: &mut EvmContext<DB>, log: &revm::primitives::Log) {
+        self.logs.push(log.clone().into());
+    }
+
+    fn call(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.frames.push(self.logs.len());
+        let transfer = &inputs.transfer;
+        // `CALLCODE` transfers to itself, and `DELEGATECALL` doesn't transfer at all
+        if transfer.value > U256::ZERO && transfer.source != transfer.target {
+            self.logs.push(transfer_log(transfer.source, transfer.target, transfer.value));
+        }
+        None
+    }
+
+    fn call_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &CallInputs,
+        outcome: CallOutcome,
+    ) -> CallOutcome {
+        self.exit_frame(outcome.result.result.is_ok());
+        outcome
+    }
+
+    fn create(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        _inputs: &mut CreateInputs,
+    ) -> Option<CreateOutcome> {
+        self.frames.push(self.logs.len());
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        let start = self.frames.last().copied().unwrap_or_default();
+        let success = outcome.result.result.is_ok();
+        self.exit_frame(success);
+        // the address is only known at the end of the create, but the transfer happens first
+        if let (true, Some(address)) = (success, outcome.address) {
+            if inputs.value > U256::ZERO {
+                self.logs.insert(start, transfer_log(inputs.caller, address, inputs.value));
+            }
+        }
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, value: U256) {
+        if value > U256::ZERO {
+            self.logs.push(transfer_log(contract, target, value));
+        }
+    }
+}
@@ -1,39 +1,61 @@
-use crate::eth::error::{EthApiError, EthResult};
+use crate::eth::{
+    error::{EthApiError, EthResult},
+    EthFees,
+};
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use futures::StreamExt;
+use jsonrpsee::{
+    core::RpcResult, server::SubscriptionMessage, PendingSubscriptionSink, SubscriptionSink,
+};
 use reth_interfaces::RethResult;
-use reth_primitives::{Address, BlockId, U256};
-use reth_provider::{BlockReaderIdExt, ChangeSetReader, StateProviderFactory};
+use reth_primitives::{Account, Address, BlockId, B256, U256, U64};
+use reth_provider::{
+    BlobSidecarReader, BlockReaderIdExt, CanonStateNotification, CanonStateSubscriptions, Chain,
+    ChangeSetReader, StateProviderFactory,
+};
 use reth_rpc_api::RethApiServer;
+use reth_rpc_types::{
+    beacon::sidecar::{BlobSidecar, BlobSidecarsResponse},
+    AccountStateDiff, BlockStateDiff, FeeEstimates, StateDiffAccountInfo, ValueChange,
+};
 use reth_tasks::TaskSpawner;
 use std::{collections::HashMap, future::Future, sync::Arc};
 use tokio::sync::oneshot;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 
 /// `reth` API implementation.
 ///
 /// This type provides the functionality for handling `reth` prototype RPC requests.
-pub struct RethApi<Provider> {
-    inner: Arc<RethApiInner<Provider>>,
+pub struct RethApi<Provider, Eth, Events> {
+    inner: Arc<RethApiInner<Provider, Eth, Events>>,
 }
 
 // === impl RethApi ===
 
-impl<Provider> RethApi<Provider> {
+impl<Provider, Eth, Events> RethApi<Provider, Eth, Events> {
     /// The provider that can interact with the chain.
     pub fn provider(&self) -> &Provider {
         &self.inner.provider
     }
 
     /// Create a new instance of the [RethApi]
-    pub fn new(provider: Provider, task_spawner: Box<dyn TaskSpawner>) -> Self {
-        let inner = Arc::new(RethApiInner { provider, task_spawner });
+    pub fn new(
+        provider: Provider,
+        eth_api: Eth,
+        chain_events: Events,
+        task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        let inner = Arc::new(RethApiInner { provider, eth_api, chain_events, task_spawner });
         Self { inner }
     }
 }
 
-impl<Provider> RethApi<Provider>
+impl<Provider, Eth, Events> RethApi<Provider, Eth, Events>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider:
+        BlockReaderIdExt + ChangeSetReader + BlobSidecarReader + StateProviderFactory + 'static,
+    Eth: EthFees + 'static,
+    Events: Send + Sync + 'static,
 {
     /// Executes the future on a new blocking task.
     async fn on_blocking_task<C, F, R>(&self, c: C) -> EthResult<R>
@@ -81,12 +103,51 @@ where
         )?;
         Ok(hash_map)
     }
+
+    /// Returns the archived blob sidecars of a particular block.
+    pub async fn blob_sidecars(&self, block_id: BlockId) -> EthResult<BlobSidecarsResponse> {
+        self.on_blocking_task(|this| async move { this.try_blob_sidecars(block_id) }).await
+    }
+
+    fn try_blob_sidecars(&self, block_id: BlockId) -> EthResult<BlobSidecarsResponse> {
+        let Some(block_number) = self.provider().block_number_for_id(block_id)? else {
+            return Err(EthApiError::UnknownBlockNumber)
+        };
+        let Some(block_hash) = self.provider().block_hash(block_number)? else {
+            return Err(EthApiError::UnknownBlockNumber)
+        };
+
+        let data = self
+            .provider()
+            .blob_sidecars(block_number)?
+            .into_iter()
+            .map(|sidecar| BlobSidecar {
+                index: sidecar.index,
+                blob: sidecar.blob,
+                kzg_commitment: sidecar.commitment,
+                kzg_proof: sidecar.proof,
+                versioned_hash: sidecar.versioned_hash,
+                transaction_hash: sidecar.tx_hash,
+                block_hash,
+                block_number,
+            })
+            .collect();
+        Ok(BlobSidecarsResponse { data })
+    }
+
+    /// Returns slow, normal and fast fee suggestions for the next blocks.
+    pub async fn fee_estimates(&self) -> EthResult<FeeEstimates> {
+        self.inner.eth_api.fee_estimates().await
+    }
 }
 
 #[async_trait]
-impl<Provider> RethApiServer for RethApi<Provider>
+impl<Provider, Eth, Events> RethApiServer for RethApi<Provider, Eth, Events>
 where
-    Provider: BlockReaderIdExt + ChangeSetReader + StateProviderFactory + 'static,
+    Provider:
+        BlockReaderIdExt + ChangeSetReader + BlobSidecarReader + StateProviderFactory + 'static,
+    Eth: EthFees + 'static,
+    Events: CanonStateSubscriptions + 'static,
 {
     /// Handler for `reth_getBalanceChangesInBlock`
     async fn reth_get_balance_changes_in_block(
@@ -95,23 +156,252 @@ where
     ) -> RpcResult<HashMap<Address, U256>> {
         Ok(RethApi::balance_changes_in_block(self, block_id).await?)
     }
+
+    /// Handler for `reth_getBlobSidecars`
+    async fn reth_get_blob_sidecars(&self, block_id: BlockId) -> RpcResult<BlobSidecarsResponse> {
+        Ok(RethApi::blob_sidecars(self, block_id).await?)
+    }
+
+    /// Handler for `reth_feeEstimates`
+    async fn reth_fee_estimates(&self) -> RpcResult<FeeEstimates> {
+        Ok(RethApi::fee_estimates(self).await?)
+    }
+
+    /// Handler for `reth_subscribeStateDiffs`
+    async fn reth_subscribe_state_diffs(
+        &self,
+        pending: PendingSubscriptionSink,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let sink = pending.accept().await?;
+        let notifications =
+            BroadcastStream::new(self.inner.chain_events.subscribe_to_canonical_state());
+        let (tx, rx) = oneshot::channel();
+        self.inner.task_spawner.spawn(Box::pin(async move {
+            let _ = tx.send(pipe_state_diffs(sink, notifications).await);
+        }));
+        // an error closes the subscription with the error message
+        rx.await?
+    }
 }
 
-impl<Provider> std::fmt::Debug for RethApi<Provider> {
+/// Sends the state changes of the canonical state notifications to the subscription sink.
+///
+/// Returns an error if the subscription lagged behind the notifications, because the changes of
+/// the skipped blocks are lost and the subscriber has to resync.
+async fn pipe_state_diffs(
+    sink: SubscriptionSink,
+    mut notifications: BroadcastStream<CanonStateNotification>,
+) -> jsonrpsee::core::SubscriptionResult {
+    loop {
+        tokio::select! {
+            _ = sink.closed() => {
+                // connection dropped
+                break Ok(())
+            },
+            maybe_notification = notifications.next() => {
+                let notification = match maybe_notification {
+                    Some(Ok(notification)) => notification,
+                    Some(Err(BroadcastStreamRecvError::Lagged(skipped))) => {
+                        let err = format!("lagged behind by {skipped} canonical states");
+                        break Err(err.into())
+                    }
+                    None => {
+                        // stream ended
+                        break Ok(())
+                    },
+                };
+                for diff in block_state_diffs(&notification) {
+                    let msg = SubscriptionMessage::from_json(&diff)?;
+                    if sink.send(msg).await.is_err() {
+                        return Ok(())
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the state changes of the blocks of a canonical state notification.
+///
+/// The reverted blocks come first, from the highest to the lowest block, followed by the committed
+/// blocks in ascending order.
+fn block_state_diffs(notification: &CanonStateNotification) -> Vec<BlockStateDiff> {
+    let mut diffs = Vec::new();
+    if let Some(reverted) = notification.reverted() {
+        diffs.extend(chain_state_diffs(&reverted, true).rev());
+    }
+    diffs.extend(chain_state_diffs(&notification.committed(), false));
+    diffs
+}
+
+/// Converts the state changes of every block of the chain.
+///
+/// The changes of reverted blocks are inverted.
+fn chain_state_diffs(
+    chain: &Chain,
+    reverted: bool,
+) -> impl DoubleEndedIterator<Item = BlockStateDiff> + '_ {
+    let blocks = chain.blocks();
+    chain.state().block_changes().into_iter().filter_map(move |changes| {
+        let block_hash = blocks.get(&changes.block_number)?.hash();
+        let accounts = changes
+            .accounts
+            .into_iter()
+            .map(|(address, account)| {
+                let account = AccountStateDiff {
+                    info: account.info.map(|change| {
+                        let change = value_change(change, reverted);
+                        ValueChange {
+                            from: change.from.map(account_info),
+                            to: change.to.map(account_info),
+                        }
+                    }),
+                    storage_wiped: account.storage_wiped,
+                    storage: account
+                        .storage
+                        .into_iter()
+                        .map(|(slot, change)| {
+                            (B256::new(slot.to_be_bytes()), value_change(change, reverted))
+                        })
+                        .collect(),
+                };
+                (address, account)
+            })
+            .collect();
+        Some(BlockStateDiff {
+            block_number: U64::from(changes.block_number),
+            block_hash,
+            reverted,
+            accounts,
+        })
+    })
+}
+
+/// Converts a change of a value, swapping the sides if the block was reverted.
+fn value_change<T>(change: reth_provider::Change<T>, reverted: bool) -> ValueChange<T> {
+    if reverted {
+        ValueChange { from: change.after, to: change.before }
+    } else {
+        ValueChange { from: change.before, to: change.after }
+    }
+}
+
+/// Converts an account to its balance, nonce and code hash.
+fn account_info(account: Account) -> StateDiffAccountInfo {
+    StateDiffAccountInfo {
+        balance: account.balance,
+        nonce: U64::from(account.nonce),
+        code_hash: account.get_bytecode_hash(),
+    }
+}
+
+impl<Provider, Eth, Events> std::fmt::Debug for RethApi<Provider, Eth, Events> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("RethApi").finish_non_exhaustive()
     }
 }
 
-impl<Provider> Clone for RethApi<Provider> {
+impl<Provider, Eth, Events> Clone for RethApi<Provider, Eth, Events> {
     fn clone(&self) -> Self {
         Self { inner: Arc::clone(&self.inner) }
     }
 }
 
-struct RethApiInner<Provider> {
+struct RethApiInner<Provider, Eth, Events> {
     /// The provider that can interact with the chain.
     provider: Provider,
+    /// The implementation of the `eth` API, used for fee estimates.
+    eth_api: Eth,
+    /// The type that can notify about changes of the canonical chain.
+    chain_events: Events,
     /// The type that can spawn tasks which would otherwise block.
     task_spawner: Box<dyn TaskSpawner>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Header, Receipts, SealedBlock, SealedBlockWithSenders, StorageEntry};
+    use reth_provider::BundleStateWithReceipts;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn reverted_block_state_diffs_are_inverted() {
+        let address = Address::random();
+        let slot = B256::with_last_byte(1);
+        let account = Account { nonce: 1, ..Default::default() };
+
+        // the account is created in block 1 with slot 0x01 set to 7
+        let state = BundleStateWithReceipts::new_init(
+            HashMap::from([(
+                address,
+                (None, Some(account), HashMap::from([(slot, (U256::ZERO, U256::from(7)))])),
+            )]),
+            HashMap::from([(
+                1,
+                HashMap::from([(
+                    address,
+                    (Some(None), vec![StorageEntry { key: slot, value: U256::ZERO }]),
+                )]),
+            )]),
+            vec![],
+            Receipts::from_vec(vec![vec![]]),
+            1,
+        );
+        let block = SealedBlockWithSenders {
+            block: SealedBlock {
+                header: Header { number: 1, ..Default::default() }.seal_slow(),
+                ..Default::default()
+            },
+            senders: vec![],
+        };
+        let block_hash = block.hash();
+        let chain = Arc::new(Chain::from_block(block, state, None));
+
+        let committed = block_state_diffs(&CanonStateNotification::Commit { new: chain.clone() });
+        assert_eq!(
+            committed,
+            vec![BlockStateDiff {
+                block_number: U64::from(1),
+                block_hash,
+                reverted: false,
+                accounts: BTreeMap::from([(
+                    address,
+                    AccountStateDiff {
+                        info: Some(ValueChange { from: None, to: Some(account_info(account)) }),
+                        storage_wiped: false,
+                        storage: BTreeMap::from([(
+                            slot,
+                            ValueChange { from: U256::ZERO, to: U256::from(7) },
+                        )]),
+                    },
+                )]),
+            }]
+        );
+
+        // reverting the block swaps the sides of every change
+        let reverted = block_state_diffs(&CanonStateNotification::Reorg {
+            old: chain,
+            new: Arc::new(Chain::default()),
+        });
+        assert_eq!(
+            reverted,
+            vec![BlockStateDiff {
+                block_number: U64::from(1),
+                block_hash,
+                reverted: true,
+                accounts: BTreeMap::from([(
+                    address,
+                    AccountStateDiff {
+                        info: Some(ValueChange { from: Some(account_info(account)), to: None }),
+                        storage_wiped: false,
+                        storage: BTreeMap::from([(
+                            slot,
+                            ValueChange { from: U256::from(7), to: U256::ZERO },
+                        )]),
+                    },
+                )]),
+            }]
+        );
+    }
+}
@@ -1,12 +1,15 @@
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult as Result;
-use reth_primitives::{Address, U256, U64};
+use reth_primitives::{Address, B256, U256, U64};
 use reth_rpc_api::TxPoolApiServer;
 use reth_rpc_types::{
     txpool::{TxpoolContent, TxpoolContentFrom, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus},
-    Transaction,
+    Transaction, TxpoolRemovalReason, TxpoolRemovedTransaction,
+};
+use reth_transaction_pool::{
+    AllPoolTransactions, PoolTransaction, RemovedTransaction, TransactionPool,
+    TransactionRemovalReason,
 };
-use reth_transaction_pool::{AllPoolTransactions, PoolTransaction, TransactionPool};
 use std::collections::BTreeMap;
 use tracing::trace;
 
@@ -135,6 +138,51 @@ where
         trace!(target: "rpc::eth", "Serving txpool_inspect");
         Ok(self.content())
     }
+
+    /// Returns why the transaction was removed from the pool, if the pool still has a record of
+    /// it.
+    ///
+    /// Handler for `txpool_removalReason`
+    async fn txpool_removal_reason(&self, hash: B256) -> Result<Option<TxpoolRemovedTransaction>> {
+        trace!(target: "rpc::eth", ?hash, "Serving txpool_removalReason");
+        Ok(self.pool.removal_reason(&hash).map(into_rpc_removed_transaction))
+    }
+
+    /// Returns all retained removal records for transactions of this address.
+    ///
+    /// Handler for `txpool_history`
+    async fn txpool_history(&self, address: Address) -> Result<Vec<TxpoolRemovedTransaction>> {
+        trace!(target: "rpc::eth", ?address, "Serving txpool_history");
+        Ok(self
+            .pool
+            .removed_transactions_by_sender(address)
+            .into_iter()
+            .map(into_rpc_removed_transaction)
+            .collect())
+    }
+}
+
+/// Converts a pool [RemovedTransaction] record into its RPC representation.
+fn into_rpc_removed_transaction(removed: RemovedTransaction) -> TxpoolRemovedTransaction {
+    let RemovedTransaction { hash, sender, nonce, reason, timestamp } = removed;
+    let reason = match reason {
+        TransactionRemovalReason::Mined(block_hash) => TxpoolRemovalReason::Mined { block_hash },
+        TransactionRemovalReason::Replaced(replaced_by) => {
+            TxpoolRemovalReason::Replaced { replaced_by }
+        }
+        TransactionRemovalReason::Evicted => TxpoolRemovalReason::Evicted,
+        TransactionRemovalReason::Stale => TxpoolRemovalReason::Stale,
+        TransactionRemovalReason::Reorg => TxpoolRemovalReason::Reorg,
+        TransactionRemovalReason::Invalid => TxpoolRemovalReason::Invalid,
+        TransactionRemovalReason::Removed => TxpoolRemovalReason::Removed,
+    };
+    TxpoolRemovedTransaction {
+        hash,
+        from: sender,
+        nonce: U64::from(nonce),
+        reason,
+        timestamp: U64::from(timestamp),
+    }
 }
 
 impl<Pool> std::fmt::Debug for TxPoolApi<Pool> {
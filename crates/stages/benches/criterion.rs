@@ -89,7 +89,7 @@ fn merkle(c: &mut Criterion) {
 
     let db = setup::txs_testdata(DEFAULT_NUM_BLOCKS);
 
-    let stage = MerkleStage::Both { clean_threshold: u64::MAX };
+    let stage = MerkleStage::Both { clean_threshold: u64::MAX, etl_config: EtlConfig::default() };
     measure_stage(
         &mut group,
         &db,
@@ -99,7 +99,7 @@ fn merkle(c: &mut Criterion) {
         "Merkle-incremental".to_string(),
     );
 
-    let stage = MerkleStage::Both { clean_threshold: 0 };
+    let stage = MerkleStage::Both { clean_threshold: 0, etl_config: EtlConfig::default() };
     measure_stage(
         &mut group,
         &db,
@@ -1,5 +1,6 @@
 use crate::{BlockErrorKind, ExecInput, ExecOutput, Stage, StageError, UnwindInput, UnwindOutput};
 use reth_codecs::Compact;
+use reth_config::config::EtlConfig;
 use reth_db::{
     database::Database,
     tables,
@@ -15,7 +16,7 @@ use reth_provider::{
     DatabaseProviderRW, HeaderProvider, ProviderError, StageCheckpointReader,
     StageCheckpointWriter, StatsReader,
 };
-use reth_trie::{IntermediateStateRootState, StateRoot, StateRootProgress};
+use reth_trie::{history::TrieHistory, IntermediateStateRootState, StateRoot, StateRootProgress};
 use std::fmt::Debug;
 use tracing::*;
 
@@ -51,6 +52,8 @@ pub enum MerkleStage {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// ETL configuration, used when recording the trie changesets of a block range.
+        etl_config: EtlConfig,
     },
     /// The unwind portion of the merkle stage.
     Unwind,
@@ -60,13 +63,18 @@ pub enum MerkleStage {
         /// The threshold (in number of blocks) for switching from incremental trie building
         /// of changes to whole rebuild.
         clean_threshold: u64,
+        /// ETL configuration, used when recording the trie changesets of a block range.
+        etl_config: EtlConfig,
     },
 }
 
 impl MerkleStage {
     /// Stage default for the [MerkleStage::Execution].
     pub fn default_execution() -> Self {
-        Self::Execution { clean_threshold: MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD }
+        Self::Execution {
+            clean_threshold: MERKLE_STAGE_DEFAULT_CLEAN_THRESHOLD,
+            etl_config: EtlConfig::default(),
+        }
     }
 
     /// Stage default for the [MerkleStage::Unwind].
@@ -75,8 +83,8 @@ impl MerkleStage {
     }
 
     /// Create new instance of [MerkleStage::Execution].
-    pub fn new_execution(clean_threshold: u64) -> Self {
-        Self::Execution { clean_threshold }
+    pub fn new_execution(clean_threshold: u64, etl_config: EtlConfig) -> Self {
+        Self::Execution { clean_threshold, etl_config }
     }
 
     /// Gets the hashing progress
@@ -131,15 +139,20 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         provider: &DatabaseProviderRW<DB>,
         input: ExecInput,
     ) -> Result<ExecOutput, StageError> {
-        let threshold = match self {
+        let (threshold, etl_config) = match self {
             MerkleStage::Unwind => {
                 info!(target: "sync::stages::merkle::unwind", "Stage is always skipped");
                 return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
             }
-            MerkleStage::Execution { clean_threshold } => *clean_threshold,
+            MerkleStage::Execution { clean_threshold, etl_config } => {
+                (*clean_threshold, etl_config.clone())
+            }
             #[cfg(any(test, feature = "test-utils"))]
-            MerkleStage::Both { clean_threshold } => *clean_threshold,
+            MerkleStage::Both { clean_threshold, etl_config } => {
+                (*clean_threshold, etl_config.clone())
+            }
         };
+        let trie_changesets = provider.trie_changesets_enabled()?;
 
         let range = input.next_block_range();
         let (from_block, to_block) = range.clone().into_inner();
@@ -153,8 +166,9 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         let mut checkpoint = self.get_execution_checkpoint(provider)?;
         let (trie_root, entities_checkpoint) = if range.is_empty() {
             (target_block_root, input.checkpoint().entities_stage_checkpoint().unwrap_or_default())
-        } else if to_block - from_block > threshold || from_block == 1 {
-            // if there are more blocks than threshold it is faster to rebuild the trie
+        } else if !trie_changesets && (to_block - from_block > threshold || from_block == 1) {
+            // if there are more blocks than threshold it is faster to rebuild the trie, unless the
+            // trie changesets are recorded, which a rebuild can't do
             let mut entities_checkpoint = if let Some(checkpoint) =
                 checkpoint.as_ref().filter(|c| c.target_block == to_block)
             {
@@ -225,11 +239,31 @@ impl<DB: Database> Stage<DB> for MerkleStage {
                 }
             }
         } else {
-            debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, "Updating trie");
-            let (root, updates) =
-                StateRoot::incremental_root_with_updates(provider.tx_ref(), range)
+            let root = if trie_changesets {
+                debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, "Updating trie and recording trie changesets");
+                let state_roots = TrieHistory::new(provider.tx_ref())
+                    .with_etl_config(etl_config.file_size, etl_config.dir)
+                    .write_blocks(range)
                     .map_err(|e| StageError::Fatal(Box::new(e)))?;
-            updates.flush(provider.tx_ref())?;
+
+                // The state root of the target block is validated below.
+                let (&(_, root), intermediate) =
+                    state_roots.split_last().expect("block range is not empty");
+                for &(block_number, state_root) in intermediate {
+                    let header = provider
+                        .sealed_header(block_number)?
+                        .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+                    validate_state_root(state_root, header, block_number)?;
+                }
+                root
+            } else {
+                debug!(target: "sync::stages::merkle::exec", current = ?current_block_number, target = ?to_block, "Updating trie");
+                let (root, updates) =
+                    StateRoot::incremental_root_with_updates(provider.tx_ref(), range.clone())
+                        .map_err(|e| StageError::Fatal(Box::new(e)))?;
+                provider.write_trie_updates(range, updates)?;
+                root
+            };
 
             let total_hashed_entries = (provider.count_entries::<tables::HashedAccounts>()? +
                 provider.count_entries::<tables::HashedStorages>()?)
@@ -281,6 +315,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
         if input.unwind_to == 0 {
             tx.clear::<tables::AccountsTrie>()?;
             tx.clear::<tables::StoragesTrie>()?;
+            TrieHistory::new(tx).clear()?;
 
             entities_checkpoint.processed = 0;
 
@@ -303,6 +338,7 @@ impl<DB: Database> Stage<DB> for MerkleStage {
 
             // Validation passed, apply unwind changes to the database.
             updates.flush(provider.tx_ref())?;
+            TrieHistory::new(tx).unwind(input.unwind_to)?;
 
             // TODO(alexey): update entities checkpoint
         } else {
@@ -341,7 +377,10 @@ mod tests {
         TestRunnerError, TestStageDB, UnwindStageTestRunner,
     };
     use assert_matches::assert_matches;
-    use reth_db::cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO};
+    use reth_db::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+        DatabaseError,
+    };
     use reth_interfaces::test_utils::{
         generators,
         generators::{
@@ -352,7 +391,10 @@ mod tests {
         keccak256, stage::StageUnitCheckpoint, SealedBlock, StaticFileSegment, StorageEntry, U256,
     };
     use reth_provider::providers::StaticFileWriter;
-    use reth_trie::test_utils::{state_root, state_root_prehashed};
+    use reth_trie::{
+        test_utils::{state_root, state_root_prehashed},
+        HashedPostState,
+    };
     use std::collections::BTreeMap;
 
     stage_test_suite_ext!(MerkleTestRunner, merkle);
@@ -438,14 +480,59 @@ mod tests {
         assert!(runner.validate_execution(input, result.ok()).is_ok(), "execution validation");
     }
 
+    /// Record the trie changesets of every block while updating the trie
+    #[tokio::test]
+    async fn execute_merkle_with_trie_changesets() {
+        let (previous_stage, stage_progress) = (20, 10);
+
+        // Set up the runner, the range is above the clean threshold
+        let mut runner =
+            MerkleTestRunner { clean_threshold: 1, ..Default::default() }.with_trie_changesets();
+        let input = ExecInput {
+            target: Some(previous_stage),
+            checkpoint: Some(StageCheckpoint::new(stage_progress)),
+        };
+
+        runner.seed_execution(input).expect("failed to seed execution");
+
+        let rx = runner.execute(input);
+
+        // Assert the successful result, the state root of every block is validated within the
+        // stage
+        let result = rx.await.unwrap();
+        assert_matches!(
+            result,
+            Ok(ExecOutput { checkpoint: StageCheckpoint { block_number, .. }, done: true })
+                if block_number == previous_stage
+        );
+
+        // The trie changesets are recorded from the first block of the range up to the target
+        let provider = runner.db.factory.provider().unwrap();
+        let tx = provider.tx_ref();
+        assert_eq!(TrieHistory::new(tx).first_block().unwrap(), Some(stage_progress + 1));
+        let first_changeset = tx.cursor_read::<tables::AccountsTrieChangeSets>().unwrap().first();
+        assert_eq!(first_changeset.unwrap().map(|(block, _)| block), Some(stage_progress + 1));
+        let last_changeset = tx.cursor_read::<tables::AccountsTrieChangeSets>().unwrap().last();
+        assert_eq!(last_changeset.unwrap().map(|(block, _)| block), Some(previous_stage));
+    }
+
     struct MerkleTestRunner {
         db: TestStageDB,
         clean_threshold: u64,
+        trie_changesets: bool,
     }
 
     impl Default for MerkleTestRunner {
         fn default() -> Self {
-            Self { db: TestStageDB::default(), clean_threshold: 10000 }
+            Self { db: TestStageDB::default(), clean_threshold: 10000, trie_changesets: false }
+        }
+    }
+
+    impl MerkleTestRunner {
+        fn with_trie_changesets(mut self) -> Self {
+            self.db.factory = self.db.factory.clone().with_trie_changesets(true);
+            self.trie_changesets = true;
+            self
         }
     }
 
@@ -457,7 +544,10 @@ mod tests {
         }
 
         fn stage(&self) -> Self::S {
-            Self::S::Both { clean_threshold: self.clean_threshold }
+            Self::S::Both {
+                clean_threshold: self.clean_threshold,
+                etl_config: EtlConfig::default(),
+            }
         }
     }
 
@@ -510,7 +600,6 @@ mod tests {
             let head_hash = sealed_head.hash();
             let mut blocks = vec![sealed_head];
             blocks.extend(random_block_range(&mut rng, start..=end, head_hash, 0..3));
-            let last_block = blocks.last().cloned().unwrap();
             self.db.insert_blocks(blocks.iter(), StorageKind::Static)?;
 
             let (transitions, final_state) = random_changeset_range(
@@ -548,15 +637,34 @@ mod tests {
                 Ok(state_root_prehashed(accounts.into_iter()))
             })?;
 
+            // The trie changesets are recorded one block at a time, validating the state root of
+            // every block.
+            let state_roots = if self.trie_changesets {
+                self.db.query(|tx| {
+                    (start..end)
+                        .map(|block_number| {
+                            Ok(HashedPostState::from_revert_range(tx, block_number + 1..=end)?
+                                .state_root(tx)?)
+                        })
+                        .collect::<Result<Vec<_>, DatabaseError>>()
+                })?
+            } else {
+                Vec::new()
+            };
+
             let static_file_provider = self.db.factory.static_file_provider();
             let mut writer =
                 static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
-            let mut last_header = last_block.header().clone();
-            last_header.state_root = root;
-
-            let hash = last_header.hash_slow();
-            writer.prune_headers(1).unwrap();
-            writer.append_header(last_header, U256::ZERO, hash).unwrap();
+            writer.prune_headers(state_roots.len() as u64 + 1).unwrap();
+            for (block, state_root) in blocks[blocks.len() - 1 - state_roots.len()..]
+                .iter()
+                .zip(state_roots.into_iter().chain([root]))
+            {
+                let mut header = block.header().clone();
+                header.state_root = state_root;
+                let hash = header.hash_slow();
+                writer.append_header(header, U256::ZERO, hash).unwrap();
+            }
             writer.commit().unwrap();
 
             Ok(blocks)
@@ -0,0 +1,172 @@
+use alloy_rlp::Decodable;
+use reth_primitives::{
+    keccak256,
+    trie::{
+        lookup_trie_value, ExecutionWitness, Nibbles, ProofVerificationError, TrieAccount,
+        WitnessError,
+    },
+    Address, BlockNumber, Bytes, GotExpected, Header, B256, KECCAK_EMPTY, U256,
+};
+use revm::{
+    primitives::{AccountInfo, Bytecode},
+    Database,
+};
+use std::collections::HashMap;
+
+/// A [Database] that serves all state from an [ExecutionWitness].
+///
+/// Accounts and storage slots are looked up in the witness trie nodes starting at the pre-state
+/// root, any read that can not be resolved from the witness fails with a [WitnessError].
+#[derive(Debug)]
+pub struct WitnessDatabase {
+    /// The state root of the parent block.
+    state_root: B256,
+    /// The trie nodes keyed by their hash.
+    nodes: HashMap<B256, Bytes>,
+    /// The bytecodes keyed by their code hash.
+    codes: HashMap<B256, Bytes>,
+    /// The verified hashes of the witness headers.
+    block_hashes: HashMap<BlockNumber, B256>,
+    /// The storage roots of the accounts that have been loaded.
+    storage_roots: HashMap<Address, B256>,
+}
+
+impl WitnessDatabase {
+    /// Creates a new [WitnessDatabase] for executing the child block of `parent_hash`.
+    ///
+    /// The witness headers must form a chain that ends with the parent block, and the state root
+    /// of the parent must match the pre-state root of the witness.
+    pub fn new(witness: &ExecutionWitness, parent_hash: B256) -> Result<Self, WitnessError> {
+        let mut block_hashes = HashMap::with_capacity(witness.headers.len());
+        let mut expected_hash = parent_hash;
+        for (index, encoded) in witness.headers.iter().enumerate().rev() {
+            let header = Header::decode(&mut encoded.as_ref())
+                .map_err(|_| WitnessError::InvalidHeader(index))?;
+            if header.hash_slow() != expected_hash {
+                return Err(WitnessError::DisconnectedHeaders(parent_hash))
+            }
+            if expected_hash == parent_hash && header.state_root != witness.pre_state_root {
+                return Err(WitnessError::PreStateRootMismatch(GotExpected::new(
+                    witness.pre_state_root,
+                    header.state_root,
+                )))
+            }
+            block_hashes.insert(header.number, expected_hash);
+            expected_hash = header.parent_hash;
+        }
+        if block_hashes.is_empty() {
+            return Err(WitnessError::DisconnectedHeaders(parent_hash))
+        }
+
+        Ok(Self {
+            state_root: witness.pre_state_root,
+            nodes: witness.state_nodes(),
+            codes: witness.codes_by_hash(),
+            block_hashes,
+            storage_roots: HashMap::new(),
+        })
+    }
+
+    /// Looks up the account in the account trie and caches its storage root.
+    fn account(&mut self, address: Address) -> Result<Option<TrieAccount>, WitnessError> {
+        let key = Nibbles::unpack(keccak256(address));
+        let Some(encoded) = lookup_trie_value(self.state_root, &key, &self.nodes)? else {
+            return Ok(None)
+        };
+        let account =
+            TrieAccount::decode(&mut encoded.as_slice()).map_err(ProofVerificationError::from)?;
+        self.storage_roots.insert(address, account.storage_root());
+        Ok(Some(account))
+    }
+}
+
+impl Database for WitnessDatabase {
+    type Error = WitnessError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        Ok(self.account(address)?.map(|account| AccountInfo {
+            balance: account.balance(),
+            nonce: account.nonce(),
+            code_hash: account.code_hash(),
+            code: None,
+        }))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if code_hash == KECCAK_EMPTY {
+            return Ok(Bytecode::new())
+        }
+        let code = self.codes.get(&code_hash).ok_or(WitnessError::MissingBytecode(code_hash))?;
+        Ok(Bytecode::new_raw(code.clone()))
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let storage_root = match self.storage_roots.get(&address) {
+            Some(storage_root) => *storage_root,
+            None => match self.account(address)? {
+                Some(account) => account.storage_root(),
+                None => return Ok(U256::ZERO),
+            },
+        };
+        let key = Nibbles::unpack(keccak256(B256::new(index.to_be_bytes())));
+        let Some(encoded) = lookup_trie_value(storage_root, &key, &self.nodes)? else {
+            return Ok(U256::ZERO)
+        };
+        Ok(U256::decode(&mut encoded.as_slice()).map_err(ProofVerificationError::from)?)
+    }
+
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        // numbers beyond `u64` can not be covered by the witness headers
+        let number = number.saturating_to::<BlockNumber>();
+        self.block_hashes.get(&number).copied().ok_or(WitnessError::MissingBlockHash(number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rlp::Encodable;
+    use reth_primitives::{
+        trie::{HashBuilder, EMPTY_ROOT_HASH},
+        Account,
+    };
+
+    #[test]
+    fn serves_state_from_witness() {
+        let address = Address::with_last_byte(1);
+        let account = Account { nonce: 7, balance: U256::from(100), bytecode_hash: None };
+        let key = Nibbles::unpack(keccak256(address));
+
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(vec![key.clone()]);
+        hash_builder
+            .add_leaf(key, &alloy_rlp::encode(TrieAccount::from((account, EMPTY_ROOT_HASH))));
+        let state_root = hash_builder.root();
+
+        let parent = Header { number: 10, state_root, ..Default::default() };
+        let mut encoded_parent = Vec::new();
+        parent.encode(&mut encoded_parent);
+        let witness = ExecutionWitness {
+            pre_state_root: state_root,
+            state: hash_builder.take_proofs().into_values().collect(),
+            codes: Vec::new(),
+            headers: vec![encoded_parent.into()],
+        };
+
+        let mut db = WitnessDatabase::new(&witness, parent.hash_slow()).unwrap();
+        let info = db.basic(address).unwrap().unwrap();
+        assert_eq!((info.nonce, info.balance), (7, U256::from(100)));
+        assert_eq!(db.basic(Address::with_last_byte(2)).unwrap(), None);
+        assert_eq!(db.storage(address, U256::from(1)).unwrap(), U256::ZERO);
+        assert_eq!(db.block_hash(U256::from(10)).unwrap(), parent.hash_slow());
+        assert_eq!(db.block_hash(U256::from(9)).unwrap_err(), WitnessError::MissingBlockHash(9));
+        assert_eq!(
+            db.code_by_hash(B256::with_last_byte(1)).unwrap_err(),
+            WitnessError::MissingBytecode(B256::with_last_byte(1))
+        );
+
+        assert_eq!(
+            WitnessDatabase::new(&witness, B256::with_last_byte(1)).unwrap_err(),
+            WitnessError::DisconnectedHeaders(B256::with_last_byte(1))
+        );
+    }
+}
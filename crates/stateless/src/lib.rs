@@ -0,0 +1,73 @@
+//! Stateless block verification.
+//!
+//! Verifies a block against its parent using only an [ExecutionWitness]: the pre-state trie nodes,
+//! bytecodes and ancestor headers the block accesses. The block is executed on a
+//! [WitnessDatabase] that serves all state from the witness, and the post-state root is computed
+//! by applying the state changes to sparse tries that are revealed from the witness nodes, see
+//! [post_state_root].
+//!
+//! The crate only depends on the primitives and revm, so it can be used in environments without a
+//! database, such as provers. Executing the block is left to the caller of [verify_block].
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use reth_primitives::{
+    trie::{ExecutionWitness, WitnessError},
+    GotExpected, Header, B256,
+};
+use revm::db::BundleState;
+
+mod database;
+pub use database::WitnessDatabase;
+
+mod state_root;
+pub use state_root::post_state_root;
+
+/// Errors that can occur when verifying a block statelessly.
+#[derive(Debug, thiserror::Error)]
+pub enum StatelessError<E> {
+    /// The witness does not contain the state that is required to verify the block.
+    #[error(transparent)]
+    Witness(#[from] WitnessError),
+    /// The block could not be executed.
+    #[error(transparent)]
+    Execution(E),
+    /// The computed state root does not match the state root of the block.
+    #[error("state root mismatch: {0}")]
+    StateRootMismatch(GotExpected<B256>),
+}
+
+/// Verifies the state transition of the block with the given header using only its witness.
+///
+/// The block is executed by `execute` on a [WitnessDatabase] of the witness, which is expected to
+/// verify the gas used and receipts of the block. The state changes of the execution output are
+/// then applied to the pre-state of the witness to check the state root of the block.
+///
+/// Returns the output of the execution.
+pub fn verify_block<T, E>(
+    header: &Header,
+    witness: &ExecutionWitness,
+    execute: impl FnOnce(WitnessDatabase) -> Result<T, E>,
+) -> Result<T, StatelessError<E>>
+where
+    T: AsRef<BundleState>,
+{
+    let db = WitnessDatabase::new(witness, header.parent_hash)?;
+    let output = execute(db).map_err(StatelessError::Execution)?;
+
+    let state_root = post_state_root(witness, output.as_ref()).map_err(WitnessError::from)?;
+    if state_root != header.state_root {
+        return Err(StatelessError::StateRootMismatch(GotExpected::new(
+            state_root,
+            header.state_root,
+        )))
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,62 @@
+use alloy_rlp::Decodable;
+use reth_primitives::{
+    keccak256,
+    trie::{ExecutionWitness, Nibbles, SparseTrie, SparseTrieError, TrieAccount, EMPTY_ROOT_HASH},
+    B256,
+};
+use revm::db::BundleState;
+
+/// Computes the state root after applying the state changes of a block to the pre-state of its
+/// witness.
+///
+/// The account and storage tries are revealed from the witness nodes. Values are inserted before
+/// keys are removed, so that a branch node only collapses if it is left with a single child after
+/// all changes, in which case the node of that child must be part of the witness.
+pub fn post_state_root(
+    witness: &ExecutionWitness,
+    state: &BundleState,
+) -> Result<B256, SparseTrieError> {
+    let nodes = witness.state_nodes();
+    let mut accounts = SparseTrie::new(witness.pre_state_root);
+
+    let mut updated = Vec::with_capacity(state.state.len());
+    let mut destroyed = Vec::new();
+    for (address, account) in &state.state {
+        let key = Nibbles::unpack(keccak256(address));
+        let Some(info) = &account.info else {
+            destroyed.push(key);
+            continue
+        };
+
+        // the storage of a destroyed account is rebuilt from the empty trie
+        let storage_root = if account.status.was_destroyed() {
+            EMPTY_ROOT_HASH
+        } else {
+            match accounts.get(&key, &nodes)? {
+                Some(encoded) => TrieAccount::decode(&mut encoded.as_slice())?.storage_root(),
+                None => EMPTY_ROOT_HASH,
+            }
+        };
+
+        let mut storage = SparseTrie::new(storage_root);
+        let (removed, changed): (Vec<_>, Vec<_>) =
+            account.storage.iter().partition(|(_, slot)| slot.present_value.is_zero());
+        for (slot, value) in changed {
+            let key = Nibbles::unpack(keccak256(B256::new(slot.to_be_bytes())));
+            storage.insert(&key, alloy_rlp::encode(value.present_value), &nodes)?;
+        }
+        for (slot, _) in removed {
+            storage.remove(&Nibbles::unpack(keccak256(B256::new(slot.to_be_bytes()))), &nodes)?;
+        }
+
+        updated.push((key, alloy_rlp::encode(TrieAccount::from((info.clone(), storage.root())))));
+    }
+
+    for (key, value) in updated {
+        accounts.insert(&key, value, &nodes)?;
+    }
+    for key in destroyed {
+        accounts.remove(&key, &nodes)?;
+    }
+    Ok(accounts.root())
+}
@@ -41,16 +41,19 @@ impl_compression_for_compact!(
     StoredNibbles,
     StoredNibblesSubKey,
     StorageTrieEntry,
+    TrieChangeSetEntry,
     StoredBlockBodyIndices,
     StoredBlockOmmers,
     StoredBlockWithdrawals,
     Bytecode,
     AccountBeforeTx,
+    StorageKeyPreimage,
     TransactionSignedNoHash,
     CompactU256,
     StageCheckpoint,
     PruneCheckpoint,
-    ClientVersion
+    ClientVersion,
+    StoredBlobSidecar
 );
 
 macro_rules! impl_compression_fixed_compact {
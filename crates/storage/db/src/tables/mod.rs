@@ -29,19 +29,25 @@ use crate::{
     tables::{
         codecs::CompactU256,
         models::{
-            accounts::{AccountBeforeTx, BlockNumberAddress},
-            blocks::{HeaderHash, StoredBlockOmmers},
+            accounts::{
+                AccountBeforeTx, BlockNumberAddress, BlockNumberHashedAddress, StorageKeyPreimage,
+            },
+            blocks::{BlockNumberVersionedHash, HeaderHash, StoredBlockOmmers},
             client_version::ClientVersion,
             storage_sharded_key::StorageShardedKey,
+            storage_trie_sharded_key::StorageTrieShardedKey,
             ShardedKey, StoredBlockBodyIndices, StoredBlockWithdrawals,
         },
     },
 };
 use reth_primitives::{
     stage::StageCheckpoint,
-    trie::{StorageTrieEntry, StoredBranchNode, StoredNibbles, StoredNibblesSubKey},
+    trie::{
+        StorageTrieEntry, StoredBranchNode, StoredNibbles, StoredNibblesSubKey, TrieChangeSetEntry,
+    },
     Account, Address, BlockHash, BlockNumber, Bytecode, Header, IntegerList, PruneCheckpoint,
-    PruneSegment, Receipt, StorageEntry, TransactionSignedNoHash, TxHash, TxNumber, B256,
+    PruneSegment, Receipt, StorageEntry, StoredBlobSidecar, TransactionSignedNoHash, TxHash,
+    TxNumber, B256,
 };
 use std::fmt;
 
@@ -360,6 +366,47 @@ tables! {
     /// From HashedAddress => NibblesSubKey => Intermediate value
     table StoragesTrie<Key = B256, Value = StorageTrieEntry, SubKey = StoredNibblesSubKey>;
 
+    /// Stores the account trie nodes as they were before a certain block changed them.
+    /// If [`TrieChangeSetEntry::node`] is `None`, the node was not existing and needs to be removed.
+    ///
+    /// Only populated when the trie changesets are enabled.
+    table AccountsTrieChangeSets<Key = BlockNumber, Value = TrieChangeSetEntry, SubKey = StoredNibblesSubKey>;
+
+    /// Stores the storage trie nodes as they were before a certain block changed them.
+    /// If [`TrieChangeSetEntry::node`] is `None`, the node was not existing and needs to be removed.
+    ///
+    /// Only populated when the trie changesets are enabled.
+    table StoragesTrieChangeSets<Key = BlockNumberHashedAddress, Value = TrieChangeSetEntry, SubKey = StoredNibblesSubKey>;
+
+    /// Stores pointers to the [`AccountsTrieChangeSets`] blocks that changed each account trie
+    /// node.
+    ///
+    /// Sharded like [`AccountsHistory`], the last shard of a node path has the `u64::MAX` block
+    /// number. The path is encoded with a fixed length, so that the shards of a path are not
+    /// interleaved with the ones of its children.
+    ///
+    /// Only populated when the trie changesets are enabled.
+    table AccountsTrieHistory<Key = ShardedKey<StoredNibblesSubKey>, Value = BlockNumberList>;
+
+    /// Stores pointers to the [`StoragesTrieChangeSets`] blocks that changed each storage trie
+    /// node.
+    ///
+    /// Sharded like [`StoragesHistory`], see [`AccountsTrieHistory`].
+    ///
+    /// Only populated when the trie changesets are enabled.
+    table StoragesTrieHistory<Key = StorageTrieShardedKey, Value = BlockNumberList>;
+
+    /// Stores the preimages of the hashed addresses changed by the blocks with trie changesets.
+    ///
+    /// Only populated when the trie changesets are enabled.
+    table HashedAccountPreimages<Key = B256, Value = Address>;
+
+    /// Stores the preimages of the hashed storage keys changed by the blocks with trie
+    /// changesets, indexed with `keccak256Address`.
+    ///
+    /// Only populated when the trie changesets are enabled.
+    table HashedStoragePreimages<Key = B256, Value = StorageKeyPreimage, SubKey = B256>;
+
     /// Stores the transaction sender for each canonical transaction.
     /// It is needed to speed up execution stage and allows fetching signer without doing
     /// transaction signed recovery
@@ -376,6 +423,14 @@ tables! {
 
     /// Stores the history of client versions that have accessed the database with write privileges by unix timestamp in seconds.
     table VersionHistory<Key = u64, Value = ClientVersion>;
+
+    /// Stores the blob sidecars of canonical blob transactions, keyed by block number and
+    /// versioned hash.
+    ///
+    /// Not a `DUPSORT` table, as a blob exceeds the maximum size of a duplicate value.
+    ///
+    /// Only populated when the blob archive is enabled.
+    table BlobSidecars<Key = BlockNumberVersionedHash, Value = StoredBlobSidecar>;
 }
 
 // Alias types.
@@ -8,7 +8,7 @@ use crate::{
     DatabaseError,
 };
 use reth_codecs::{derive_arbitrary, Compact};
-use reth_primitives::{Account, Address, BlockNumber, Buf, StorageKey};
+use reth_primitives::{Account, Address, BlockNumber, Buf, StorageKey, B256};
 use serde::{Deserialize, Serialize};
 
 /// Account as it is saved inside [`AccountChangeSets`][crate::tables::AccountChangeSets].
@@ -57,6 +57,38 @@ impl Compact for AccountBeforeTx {
     }
 }
 
+/// Storage key as it is saved inside
+/// [`HashedStoragePreimages`][crate::tables::HashedStoragePreimages].
+///
+/// The hashed storage key is the subkey.
+#[derive_arbitrary(compact)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Serialize)]
+pub struct StorageKeyPreimage {
+    /// Hashed storage key. Acts as `DupSort::SubKey`.
+    pub hashed_key: B256,
+    /// Storage key hashed to the subkey.
+    pub key: StorageKey,
+}
+
+// NOTE: Manually encode the subkey first, so that it can be used with seek_by_key_subkey.
+impl Compact for StorageKeyPreimage {
+    fn to_compact<B>(self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        buf.put_slice(self.hashed_key.as_slice());
+        buf.put_slice(self.key.as_slice());
+        64
+    }
+
+    fn from_compact(mut buf: &[u8], _len: usize) -> (Self, &[u8]) {
+        let hashed_key = B256::from_slice(&buf[..32]);
+        let key = StorageKey::from_slice(&buf[32..64]);
+        buf.advance(64);
+        (Self { hashed_key, key }, buf)
+    }
+}
+
 /// [`BlockNumber`] concatenated with [`Address`]. Used as the key for
 /// [`StorageChangeSets`](crate::tables::StorageChangeSets)
 ///
@@ -121,6 +153,70 @@ impl Decode for BlockNumberAddress {
     }
 }
 
+/// [`BlockNumber`] concatenated with the hashed address of an account. Used as the key for
+/// [`StoragesTrieChangeSets`](crate::tables::StoragesTrieChangeSets)
+///
+/// Since it's used as a key, it isn't compressed when encoding it.
+#[derive(
+    Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Ord, PartialOrd, Hash,
+)]
+pub struct BlockNumberHashedAddress(pub (BlockNumber, B256));
+
+impl BlockNumberHashedAddress {
+    /// Create a new Range from `start` to `end`
+    ///
+    /// Note: End is inclusive
+    pub fn range(range: RangeInclusive<BlockNumber>) -> Range<Self> {
+        (*range.start(), B256::ZERO).into()..(*range.end() + 1, B256::ZERO).into()
+    }
+
+    /// Return the block number
+    pub fn block_number(&self) -> BlockNumber {
+        self.0 .0
+    }
+
+    /// Return the hashed address
+    pub fn hashed_address(&self) -> B256 {
+        self.0 .1
+    }
+
+    /// Consumes `Self` and returns [`BlockNumber`] and the hashed address
+    pub fn take(self) -> (BlockNumber, B256) {
+        (self.0 .0, self.0 .1)
+    }
+}
+
+impl From<(BlockNumber, B256)> for BlockNumberHashedAddress {
+    fn from(tpl: (u64, B256)) -> Self {
+        BlockNumberHashedAddress(tpl)
+    }
+}
+
+impl Encode for BlockNumberHashedAddress {
+    type Encoded = [u8; 40];
+
+    fn encode(self) -> Self::Encoded {
+        let block_number = self.0 .0;
+        let hashed_address = self.0 .1;
+
+        let mut buf = [0u8; 40];
+
+        buf[..8].copy_from_slice(&block_number.to_be_bytes());
+        buf[8..].copy_from_slice(hashed_address.as_slice());
+        buf
+    }
+}
+
+impl Decode for BlockNumberHashedAddress {
+    fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, DatabaseError> {
+        let value = value.as_ref();
+        let num = u64::from_be_bytes(value[..8].try_into().map_err(|_| DatabaseError::Decode)?);
+        let hash = B256::from_slice(&value[8..]);
+
+        Ok(BlockNumberHashedAddress((num, hash)))
+    }
+}
+
 /// [`Address`] concatenated with [`StorageKey`]. Used by `reth_etl` and history stages.
 ///
 /// Since it's used as a key, it isn't compressed when encoding it.
@@ -154,7 +250,11 @@ impl Decode for AddressStorageKey {
     }
 }
 
-impl_fixed_arbitrary!((BlockNumberAddress, 28), (AddressStorageKey, 52));
+impl_fixed_arbitrary!(
+    (BlockNumberAddress, 28),
+    (BlockNumberHashedAddress, 40),
+    (AddressStorageKey, 52)
+);
 
 #[cfg(test)]
 mod tests {
@@ -187,6 +287,23 @@ mod tests {
         assert_eq!(bytes, Encode::encode(key));
     }
 
+    #[test]
+    fn test_block_number_hashed_address() {
+        let num = 1u64;
+        let hashed_address = B256::random();
+        let key = BlockNumberHashedAddress((num, hashed_address));
+
+        let mut bytes = [0u8; 40];
+        bytes[..8].copy_from_slice(&num.to_be_bytes());
+        bytes[8..].copy_from_slice(hashed_address.as_slice());
+
+        let encoded = Encode::encode(key);
+        assert_eq!(encoded, bytes);
+
+        let decoded: BlockNumberHashedAddress = Decode::decode(encoded).unwrap();
+        assert_eq!(decoded, key);
+    }
+
     #[test]
     fn test_address_storage_key() {
         let storage_key = StorageKey::random();
@@ -1,8 +1,14 @@
 //! Block related models and types.
 
+use crate::{
+    impl_fixed_arbitrary,
+    table::{Decode, Encode},
+    DatabaseError,
+};
 use reth_codecs::{main_codec, Compact};
-use reth_primitives::{Header, TxNumber, Withdrawals, B256};
-use std::ops::Range;
+use reth_primitives::{BlockNumber, Header, TxNumber, Withdrawals, B256};
+use serde::{Deserialize, Serialize};
+use std::ops::{Range, RangeInclusive};
 
 /// Total number of transactions.
 pub type NumTransactions = u64;
@@ -86,6 +92,67 @@ pub struct StoredBlockWithdrawals {
 /// Hash of the block header. Value for [`CanonicalHeaders`][crate::tables::CanonicalHeaders]
 pub type HeaderHash = B256;
 
+/// [`BlockNumber`] concatenated with the versioned hash of a blob. Used as the key for
+/// [`BlobSidecars`](crate::tables::BlobSidecars)
+///
+/// Since it's used as a key, it isn't compressed when encoding it.
+#[derive(
+    Debug, Default, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, Ord, PartialOrd, Hash,
+)]
+pub struct BlockNumberVersionedHash(pub (BlockNumber, B256));
+
+impl BlockNumberVersionedHash {
+    /// Create a new Range from `start` to `end`
+    ///
+    /// Note: End is inclusive
+    pub fn range(range: RangeInclusive<BlockNumber>) -> Range<Self> {
+        (*range.start(), B256::ZERO).into()..(*range.end() + 1, B256::ZERO).into()
+    }
+
+    /// Return the block number
+    pub fn block_number(&self) -> BlockNumber {
+        self.0 .0
+    }
+
+    /// Return the versioned hash
+    pub fn versioned_hash(&self) -> B256 {
+        self.0 .1
+    }
+}
+
+impl From<(BlockNumber, B256)> for BlockNumberVersionedHash {
+    fn from(tpl: (u64, B256)) -> Self {
+        BlockNumberVersionedHash(tpl)
+    }
+}
+
+impl Encode for BlockNumberVersionedHash {
+    type Encoded = [u8; 40];
+
+    fn encode(self) -> Self::Encoded {
+        let block_number = self.0 .0;
+        let versioned_hash = self.0 .1;
+
+        let mut buf = [0u8; 40];
+
+        buf[..8].copy_from_slice(&block_number.to_be_bytes());
+        buf[8..].copy_from_slice(versioned_hash.as_slice());
+        buf
+    }
+}
+
+impl Decode for BlockNumberVersionedHash {
+    fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, DatabaseError> {
+        let value = value.as_ref();
+        let num = u64::from_be_bytes(value[..8].try_into().map_err(|_| DatabaseError::Decode)?);
+        let hash = B256::from_slice(&value[8..]);
+
+        Ok(BlockNumberVersionedHash((num, hash)))
+    }
+}
+
+impl_fixed_arbitrary!((BlockNumberVersionedHash, 40));
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,4 +181,21 @@ mod tests {
         assert_eq!(block_indices.tx_count(), tx_count);
         assert_eq!(block_indices.tx_num_range(), first_tx_num..first_tx_num + tx_count);
     }
+
+    #[test]
+    fn test_block_number_versioned_hash() {
+        let num = 1u64;
+        let versioned_hash = B256::random();
+        let key = BlockNumberVersionedHash((num, versioned_hash));
+
+        let mut bytes = [0u8; 40];
+        bytes[..8].copy_from_slice(&num.to_be_bytes());
+        bytes[8..].copy_from_slice(versioned_hash.as_slice());
+
+        let encoded = Encode::encode(key);
+        assert_eq!(encoded, bytes);
+
+        let decoded: BlockNumberVersionedHash = Decode::decode(encoded).unwrap();
+        assert_eq!(decoded, key);
+    }
 }
@@ -16,6 +16,7 @@ pub mod client_version;
 pub mod integer_list;
 pub mod sharded_key;
 pub mod storage_sharded_key;
+pub mod storage_trie_sharded_key;
 
 pub use accounts::*;
 pub use blocks::*;
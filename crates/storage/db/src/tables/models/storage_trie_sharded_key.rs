@@ -0,0 +1,107 @@
+//! Storage trie sharded key
+
+use crate::{
+    table::{Decode, Encode},
+    DatabaseError,
+};
+use derive_more::AsRef;
+use reth_primitives::{trie::StoredNibblesSubKey, BlockNumber, B256};
+use serde::{Deserialize, Serialize};
+
+use super::ShardedKey;
+
+/// Sometimes data can be too big to be saved for a single key. This helps out by dividing the data
+/// into different shards. Example:
+///
+/// `HashedAddress | Nibbles | 200` -> data is from block 0 to 200.
+///
+/// `HashedAddress | Nibbles | 300` -> data is from block 201 to 300.
+///
+/// The nibbles are encoded with a fixed length, so that the shards of a path are contiguous.
+#[derive(Debug, Clone, Eq, Ord, PartialOrd, PartialEq, AsRef, Serialize, Deserialize, Hash)]
+pub struct StorageTrieShardedKey {
+    /// Hashed address of the account owning the storage trie.
+    pub hashed_address: B256,
+    /// Storage trie node path with highest block number.
+    #[as_ref]
+    pub sharded_key: ShardedKey<StoredNibblesSubKey>,
+}
+
+impl StorageTrieShardedKey {
+    /// Creates a new `StorageTrieShardedKey`.
+    pub fn new(
+        hashed_address: B256,
+        nibbles: StoredNibblesSubKey,
+        highest_block_number: BlockNumber,
+    ) -> Self {
+        Self { hashed_address, sharded_key: ShardedKey::new(nibbles, highest_block_number) }
+    }
+
+    /// Creates a new key with the highest block number set to maximum.
+    /// This is useful when we want to search the last value for a given key.
+    pub fn last(hashed_address: B256, nibbles: StoredNibblesSubKey) -> Self {
+        Self { hashed_address, sharded_key: ShardedKey::last(nibbles) }
+    }
+}
+
+impl Encode for StorageTrieShardedKey {
+    type Encoded = Vec<u8>;
+
+    fn encode(self) -> Self::Encoded {
+        let mut buf: Vec<u8> = Encode::encode(self.hashed_address).into();
+        buf.extend_from_slice(&Encode::encode(self.sharded_key.key));
+        buf.extend_from_slice(&self.sharded_key.highest_block_number.to_be_bytes());
+        buf
+    }
+}
+
+impl Decode for StorageTrieShardedKey {
+    fn decode<B: AsRef<[u8]>>(value: B) -> Result<Self, DatabaseError> {
+        let value = value.as_ref();
+        if value.len() != 32 + 65 + 8 {
+            return Err(DatabaseError::Decode)
+        }
+
+        let highest_block_number =
+            u64::from_be_bytes(value[97..].try_into().map_err(|_| DatabaseError::Decode)?);
+        let hashed_address = B256::decode(&value[..32])?;
+        let nibbles = StoredNibblesSubKey::decode(&value[32..97])?;
+
+        Ok(Self::new(hashed_address, nibbles, highest_block_number))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::trie::Nibbles;
+
+    #[test]
+    fn storage_trie_sharded_key_roundtrip() {
+        let key = StorageTrieShardedKey::new(
+            B256::random(),
+            StoredNibblesSubKey(Nibbles::from_nibbles_unchecked([0x1, 0x2, 0x3])),
+            42,
+        );
+        let encoded = Encode::encode(key.clone());
+        assert_eq!(encoded.len(), 32 + 65 + 8);
+        assert_eq!(StorageTrieShardedKey::decode(encoded).unwrap(), key);
+    }
+
+    #[test]
+    fn storage_trie_sharded_key_order() {
+        let hashed_address = B256::random();
+        let key = |nibbles: &[u8], block_number| {
+            Encode::encode(StorageTrieShardedKey::new(
+                hashed_address,
+                StoredNibblesSubKey(Nibbles::from_nibbles_unchecked(nibbles)),
+                block_number,
+            ))
+        };
+
+        // All shards of a path sort before the next path.
+        assert!(key(&[0x1], u64::MAX) < key(&[0x1, 0x0], 0));
+        assert!(key(&[0x1, 0x0], u64::MAX) < key(&[0x1, 0x1], 0));
+        assert!(key(&[0x1, 0x0, 0xf], u64::MAX) < key(&[0x1, 0x1], 0));
+    }
+}
@@ -0,0 +1,37 @@
+use reth_primitives::{Account, Address, BlockNumber, U256};
+use std::collections::BTreeMap;
+
+/// The changes of the state in a single block, see
+/// [BundleStateWithReceipts::block_changes](crate::BundleStateWithReceipts::block_changes).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockChanges {
+    /// The number of the block.
+    pub block_number: BlockNumber,
+    /// The accounts changed by the block.
+    pub accounts: BTreeMap<Address, AccountChanges>,
+}
+
+/// The changes of a single account in a block.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountChanges {
+    /// The account before and after the block, if its balance, nonce or code changed.
+    ///
+    /// `None` on either side means that the account did not exist.
+    pub info: Option<Change<Option<Account>>>,
+    /// Whether the storage of the account was wiped by the block, e.g. by a selfdestruct.
+    ///
+    /// Only the wiped slots that were changed in the same bundle state are part of
+    /// [AccountChanges::storage].
+    pub storage_wiped: bool,
+    /// The changed storage slots.
+    pub storage: BTreeMap<U256, Change<U256>>,
+}
+
+/// A value before and after a block.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Change<T> {
+    /// The value before the block.
+    pub before: T,
+    /// The value after the block.
+    pub after: T,
+}
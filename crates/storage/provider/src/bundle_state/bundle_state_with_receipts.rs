@@ -1,4 +1,6 @@
-use crate::{providers::StaticFileProviderRWRefMut, StateChanges, StateReverts};
+use crate::{
+    providers::StaticFileProviderRWRefMut, BlockChanges, Change, StateChanges, StateReverts,
+};
 use reth_db::{
     cursor::{DbCursorRO, DbCursorRW},
     tables,
@@ -14,13 +16,17 @@ use reth_primitives::{
 use reth_trie::HashedPostState;
 pub use revm::db::states::OriginalValuesKnown;
 use revm::{
-    db::{states::BundleState, BundleAccount},
+    db::{
+        states::{BundleState, PlainStateReverts, PlainStorageRevert},
+        BundleAccount,
+    },
     primitives::AccountInfo,
 };
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Bundle state of post execution changes and reverts
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BundleStateWithReceipts {
     /// Bundle state with reverts.
     bundle: BundleState,
@@ -130,6 +136,55 @@ impl BundleStateWithReceipts {
         self.bundle.bytecode(code_hash).map(Bytecode)
     }
 
+    /// Returns the changes of the state in every block of the bundle, in order.
+    ///
+    /// The changes are derived from the reverts of the bundle, walking back from the state after
+    /// the last block, so the bundle must retain the reverts of all of its blocks.
+    pub fn block_changes(&self) -> Vec<BlockChanges> {
+        let PlainStateReverts { accounts: account_reverts, storage: storage_reverts } =
+            self.bundle.reverts.clone().into_plain_state_reverts();
+
+        // the accounts and slots as of the block that is currently walked, if they differ from
+        // the present state of the bundle
+        let mut accounts = HashMap::<Address, Option<Account>>::new();
+        let mut storage = HashMap::<(Address, U256), U256>::new();
+
+        let mut blocks = Vec::with_capacity(account_reverts.len());
+        for (index, (account_reverts, storage_reverts)) in
+            account_reverts.into_iter().zip(storage_reverts).enumerate().rev()
+        {
+            let mut changes = BlockChanges {
+                block_number: self.first_block + index as BlockNumber,
+                accounts: Default::default(),
+            };
+
+            for (address, before) in account_reverts {
+                let before = before.map(into_reth_acc);
+                let after = accounts
+                    .insert(address, before)
+                    .unwrap_or_else(|| self.account(&address).flatten());
+                changes.accounts.entry(address).or_default().info = Some(Change { before, after });
+            }
+
+            for PlainStorageRevert { address, wiped, storage_revert } in storage_reverts {
+                let account = changes.accounts.entry(address).or_default();
+                account.storage_wiped = wiped;
+                for (slot, revert) in storage_revert {
+                    let before = revert.to_previous_value();
+                    let after = storage
+                        .insert((address, slot), before)
+                        .unwrap_or_else(|| self.storage(&address, slot).unwrap_or_default());
+                    account.storage.insert(slot, Change { before, after });
+                }
+            }
+
+            blocks.push(changes);
+        }
+
+        blocks.reverse();
+        blocks
+    }
+
     /// Returns [HashedPostState] for this bundle state.
     /// See [HashedPostState::from_bundle_state] for more info.
     pub fn hash_state_slow(&self) -> HashedPostState {
@@ -345,10 +400,16 @@ impl BundleStateWithReceipts {
     }
 }
 
+impl AsRef<BundleState> for BundleStateWithReceipts {
+    fn as_ref(&self) -> &BundleState {
+        &self.bundle
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{test_utils::create_test_provider_factory, AccountReader};
+    use crate::{test_utils::create_test_provider_factory, AccountChanges, AccountReader};
     use reth_db::{
         cursor::DbDupCursorRO,
         database::Database,
@@ -1340,4 +1401,114 @@ mod tests {
         // account2 got inserted
         assert_eq!(end_state.state.get(&address2).unwrap().info, Some(account2));
     }
+
+    #[test]
+    fn block_changes() {
+        let address = Address::random();
+        let slot = B256::with_last_byte(1);
+
+        let account1 = Account { nonce: 1, ..Default::default() };
+        let account2 = Account { nonce: 2, balance: U256::from(10), ..Default::default() };
+
+        // the account is created in block 1 and its nonce, balance and storage change in block 2
+        let state = BundleStateWithReceipts::new_init(
+            HashMap::from([(
+                address,
+                (None, Some(account2), HashMap::from([(slot, (U256::ZERO, U256::from(7)))])),
+            )]),
+            HashMap::from([
+                (1, HashMap::from([(address, (Some(None), vec![]))])),
+                (
+                    2,
+                    HashMap::from([(
+                        address,
+                        (Some(Some(account1)), vec![StorageEntry { key: slot, value: U256::ZERO }]),
+                    )]),
+                ),
+            ]),
+            vec![],
+            Receipts::from_vec(vec![vec![], vec![]]),
+            1,
+        );
+
+        assert_eq!(
+            state.block_changes(),
+            vec![
+                BlockChanges {
+                    block_number: 1,
+                    accounts: BTreeMap::from([(
+                        address,
+                        AccountChanges {
+                            info: Some(Change { before: None, after: Some(account1) }),
+                            ..Default::default()
+                        },
+                    )]),
+                },
+                BlockChanges {
+                    block_number: 2,
+                    accounts: BTreeMap::from([(
+                        address,
+                        AccountChanges {
+                            info: Some(Change { before: Some(account1), after: Some(account2) }),
+                            storage_wiped: false,
+                            storage: BTreeMap::from([(
+                                U256::from(1),
+                                Change { before: U256::ZERO, after: U256::from(7) },
+                            )]),
+                        },
+                    )]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn block_changes_wiped_storage() {
+        let address = Address::random();
+        let account = RevmAccountInfo { nonce: 1, ..Default::default() };
+
+        let mut state = State::builder().with_bundle_update().build();
+        state.insert_account_with_storage(
+            address,
+            account.clone(),
+            HashMap::from([(U256::ZERO, U256::from(1)), (U256::from(1), U256::from(2))]),
+        );
+
+        // Block #1: destroy, re-create and change slot 0x01.
+        state.commit(HashMap::from([(
+            address,
+            RevmAccount {
+                status: AccountStatus::Touched | AccountStatus::SelfDestructed,
+                info: account.clone(),
+                storage: HashMap::default(),
+            },
+        )]));
+        state.commit(HashMap::from([(
+            address,
+            RevmAccount {
+                status: AccountStatus::Touched | AccountStatus::Created,
+                info: account,
+                storage: HashMap::from([(
+                    U256::from(1),
+                    StorageSlot { present_value: U256::from(5), ..Default::default() },
+                )]),
+            },
+        )]));
+        state.merge_transitions(BundleRetention::Reverts);
+
+        let state =
+            BundleStateWithReceipts::new(state.take_bundle(), Receipts::from_vec(vec![vec![]]), 1);
+        let blocks = state.block_changes();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].block_number, 1);
+
+        let changes = &blocks[0].accounts[&address];
+        assert!(changes.storage_wiped);
+        assert_eq!(changes.storage[&U256::from(1)].after, U256::from(5));
+        // every other changed slot was cleared by the wipe
+        assert!(changes
+            .storage
+            .iter()
+            .all(|(slot, change)| *slot == U256::from(1) || change.after.is_zero()));
+    }
 }
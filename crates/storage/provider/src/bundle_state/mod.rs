@@ -1,10 +1,12 @@
 //! Bundle state module.
 //! This module contains all the logic related to bundle state.
+mod block_changes;
 mod bundle_state_with_receipts;
 mod hashed_state_changes;
 mod state_changes;
 mod state_reverts;
 
+pub use block_changes::{AccountChanges, BlockChanges, Change};
 pub use bundle_state_with_receipts::{
     AccountRevertInit, BundleStateInit, BundleStateWithReceipts, OriginalValuesKnown, RevertsInit,
 };
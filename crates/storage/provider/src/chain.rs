@@ -8,6 +8,7 @@ use reth_primitives::{
 };
 use reth_trie::updates::TrieUpdates;
 use revm::db::BundleState;
+use serde::{Deserialize, Serialize};
 use std::{borrow::Cow, collections::BTreeMap, fmt, ops::RangeInclusive};
 
 /// A chain of blocks and their final state.
@@ -16,7 +17,7 @@ use std::{borrow::Cow, collections::BTreeMap, fmt, ops::RangeInclusive};
 /// changesets for those blocks (and their transactions), as well as the blocks themselves.
 ///
 /// Used inside the BlockchainTree.
-#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Chain {
     /// All blocks in this chain.
     blocks: BTreeMap<BlockNumber, SealedBlockWithSenders>,
@@ -27,6 +28,9 @@ pub struct Chain {
     state: BundleStateWithReceipts,
     /// State trie updates after block is added to the chain.
     /// NOTE: Currently, trie updates are present only if the block extends canonical chain.
+    ///
+    /// The trie updates are not serialized.
+    #[serde(skip)]
     trie_updates: Option<TrieUpdates>,
 }
 
@@ -34,7 +34,10 @@ pub mod chain;
 pub use chain::{Chain, DisplayBlocksChain};
 
 pub mod bundle_state;
-pub use bundle_state::{BundleStateWithReceipts, OriginalValuesKnown, StateChanges, StateReverts};
+pub use bundle_state::{
+    AccountChanges, BlockChanges, BundleStateWithReceipts, Change, OriginalValuesKnown,
+    StateChanges, StateReverts,
+};
 
 pub(crate) fn to_range<R: std::ops::RangeBounds<u64>>(bounds: R) -> std::ops::Range<u64> {
     let start = match bounds.start_bound() {
@@ -2,7 +2,10 @@ use crate::{
     AccountReader, BlockHashReader, BundleStateDataProvider, StateProvider, StateRootProvider,
 };
 use reth_interfaces::provider::{ProviderError, ProviderResult};
-use reth_primitives::{trie::AccountProof, Account, Address, BlockNumber, Bytecode, B256};
+use reth_primitives::{
+    trie::{AccountProof, Nibbles},
+    Account, Address, BlockNumber, Bytecode, Bytes, B256,
+};
 use reth_trie::updates::TrieUpdates;
 use revm::db::BundleState;
 
@@ -105,4 +108,16 @@ impl<SP: StateProvider, BSDP: BundleStateDataProvider> StateProvider
     fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
         Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
     }
+
+    fn account_trie_nodes(&self, _targets: Vec<Nibbles>) -> ProviderResult<Vec<Bytes>> {
+        Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
+    }
+
+    fn storage_trie_nodes(
+        &self,
+        _hashed_address: B256,
+        _targets: Vec<Nibbles>,
+    ) -> ProviderResult<Vec<Bytes>> {
+        Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
+    }
 }
@@ -2,10 +2,11 @@ use crate::{
     providers::{state::latest::LatestStateProvider, StaticFileProvider},
     to_range,
     traits::{BlockSource, ReceiptProvider},
-    BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode,
-    ProviderError, PruneCheckpointReader, StageCheckpointReader, StateProviderBox,
-    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    BlobSidecarReader, BlobSidecarWriter, BlockHashReader, BlockNumReader, BlockReader,
+    ChainSpecProvider, DatabaseProviderFactory, EvmEnvProvider, HeaderProvider, HeaderSyncGap,
+    HeaderSyncGapProvider, HeaderSyncMode, ProviderError, PruneCheckpointReader,
+    StageCheckpointReader, StateProviderBox, TransactionVariant, TransactionsProvider,
+    WithdrawalsProvider,
 };
 use reth_db::{database::Database, init_db, models::StoredBlockBodyIndices, DatabaseEnv};
 use reth_evm::ConfigureEvmEnv;
@@ -14,8 +15,8 @@ use reth_primitives::{
     stage::{StageCheckpoint, StageId},
     Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, ChainInfo,
     ChainSpec, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders,
-    SealedHeader, StaticFileSegment, TransactionMeta, TransactionSigned, TransactionSignedNoHash,
-    TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
+    SealedHeader, StaticFileSegment, StoredBlobSidecar, TransactionMeta, TransactionSigned,
+    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
 };
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
@@ -42,6 +43,8 @@ pub struct ProviderFactory<DB> {
     chain_spec: Arc<ChainSpec>,
     /// Static File Provider
     static_file_provider: StaticFileProvider,
+    /// Whether the trie changesets are recorded when writing the trie.
+    trie_changesets: bool,
 }
 
 impl<DB> ProviderFactory<DB> {
@@ -55,6 +58,7 @@ impl<DB> ProviderFactory<DB> {
             db,
             chain_spec,
             static_file_provider: StaticFileProvider::new(static_files_path)?,
+            trie_changesets: false,
         })
     }
 
@@ -64,6 +68,13 @@ impl<DB> ProviderFactory<DB> {
         self
     }
 
+    /// Enables recording the trie changesets of every block in the providers created by this
+    /// factory, see [DatabaseProvider::with_trie_changesets].
+    pub fn with_trie_changesets(mut self, enabled: bool) -> Self {
+        self.trie_changesets = enabled;
+        self
+    }
+
     /// Returns reference to the underlying database.
     pub fn db_ref(&self) -> &DB {
         &self.db
@@ -94,6 +105,7 @@ impl ProviderFactory<DatabaseEnv> {
             db: init_db(path, args).map_err(|e| RethError::Custom(e.to_string()))?,
             chain_spec,
             static_file_provider: StaticFileProvider::new(static_files_path)?,
+            trie_changesets: false,
         })
     }
 }
@@ -108,7 +120,8 @@ impl<DB: Database> ProviderFactory<DB> {
             self.db.tx()?,
             self.chain_spec.clone(),
             self.static_file_provider.clone(),
-        ))
+        )
+        .with_trie_changesets(self.trie_changesets))
     }
 
     /// Returns a provider with a created `DbTxMut` inside, which allows fetching and updating
@@ -117,11 +130,14 @@ impl<DB: Database> ProviderFactory<DB> {
     /// open.
     #[track_caller]
     pub fn provider_rw(&self) -> ProviderResult<DatabaseProviderRW<DB>> {
-        Ok(DatabaseProviderRW(DatabaseProvider::new_rw(
-            self.db.tx_mut()?,
-            self.chain_spec.clone(),
-            self.static_file_provider.clone(),
-        )))
+        Ok(DatabaseProviderRW(
+            DatabaseProvider::new_rw(
+                self.db.tx_mut()?,
+                self.chain_spec.clone(),
+                self.static_file_provider.clone(),
+            )
+            .with_trie_changesets(self.trie_changesets),
+        ))
     }
 
     /// State provider for latest block
@@ -556,12 +572,47 @@ impl<DB: Database> PruneCheckpointReader for ProviderFactory<DB> {
     }
 }
 
+impl<DB: Database> BlobSidecarReader for ProviderFactory<DB> {
+    fn blob_sidecars(&self, block: BlockNumber) -> ProviderResult<Vec<StoredBlobSidecar>> {
+        self.provider()?.blob_sidecars(block)
+    }
+
+    fn blob_sidecar(
+        &self,
+        block: BlockNumber,
+        versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>> {
+        self.provider()?.blob_sidecar(block, versioned_hash)
+    }
+}
+
+impl<DB: Database> BlobSidecarWriter for ProviderFactory<DB> {
+    fn insert_blob_sidecars(
+        &self,
+        block: BlockNumber,
+        sidecars: Vec<StoredBlobSidecar>,
+    ) -> ProviderResult<()> {
+        let provider = self.provider_rw()?;
+        provider.insert_blob_sidecars(block, sidecars)?;
+        provider.commit()?;
+        Ok(())
+    }
+
+    fn remove_blob_sidecars_above(&self, block: BlockNumber) -> ProviderResult<()> {
+        let provider = self.provider_rw()?;
+        provider.remove_blob_sidecars_above(block)?;
+        provider.commit()?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::ProviderFactory;
     use crate::{
-        providers::StaticFileWriter, test_utils::create_test_provider_factory, BlockHashReader,
-        BlockNumReader, BlockWriter, HeaderSyncGapProvider, HeaderSyncMode, TransactionsProvider,
+        providers::StaticFileWriter, test_utils::create_test_provider_factory, BlobSidecarReader,
+        BlobSidecarWriter, BlockHashReader, BlockNumReader, BlockWriter, HeaderSyncGapProvider,
+        HeaderSyncMode, TransactionsProvider,
     };
     use alloy_rlp::Decodable;
     use assert_matches::assert_matches;
@@ -580,8 +631,10 @@ mod tests {
         RethError,
     };
     use reth_primitives::{
-        hex_literal::hex, ChainSpecBuilder, PruneMode, PruneModes, SealedBlock, StaticFileSegment,
-        TxNumber, B256, U256,
+        constants::eip4844::{FIELD_ELEMENTS_PER_BLOB, FIELD_ELEMENT_BYTES, MAX_BLOBS_PER_BLOCK},
+        hex_literal::hex,
+        Bytes, ChainSpecBuilder, PruneMode, PruneModes, SealedBlock, StaticFileSegment,
+        StoredBlobSidecar, TxNumber, B256, U256,
     };
     use std::{ops::RangeInclusive, sync::Arc};
     use tokio::sync::watch;
@@ -612,6 +665,61 @@ mod tests {
         provider.block_hash(0).unwrap();
     }
 
+    #[test]
+    fn blob_sidecars_roundtrip() {
+        let factory = create_test_provider_factory();
+        let sidecar = |block: u8, index: u64| StoredBlobSidecar {
+            versioned_hash: B256::with_last_byte(block * 16 + index as u8),
+            tx_hash: B256::with_last_byte(block),
+            index,
+            ..Default::default()
+        };
+
+        factory.insert_blob_sidecars(1, vec![sidecar(1, 1), sidecar(1, 0)]).unwrap();
+        factory.insert_blob_sidecars(2, vec![sidecar(2, 0)]).unwrap();
+
+        let sidecars = factory.blob_sidecars(1).unwrap();
+        assert_eq!(sidecars, vec![sidecar(1, 0), sidecar(1, 1)]);
+        assert_eq!(
+            factory.blob_sidecar(1, sidecar(1, 1).versioned_hash).unwrap(),
+            Some(sidecar(1, 1))
+        );
+        assert_eq!(factory.blob_sidecar(1, sidecar(2, 0).versioned_hash).unwrap(), None);
+
+        // re-inserting replaces the existing sidecars of the block
+        factory.insert_blob_sidecars(1, vec![sidecar(1, 0)]).unwrap();
+        assert_eq!(factory.blob_sidecars(1).unwrap(), vec![sidecar(1, 0)]);
+
+        factory.remove_blob_sidecars_above(1).unwrap();
+        assert!(factory.blob_sidecars(2).unwrap().is_empty());
+        assert_eq!(factory.blob_sidecars(1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn blob_sidecars_full_size() {
+        let factory = create_test_provider_factory();
+        let blob_size = (FIELD_ELEMENTS_PER_BLOB * FIELD_ELEMENT_BYTES) as usize;
+        let sidecars = (0..MAX_BLOBS_PER_BLOCK as u64)
+            .map(|index| StoredBlobSidecar {
+                versioned_hash: B256::with_last_byte(index as u8),
+                tx_hash: B256::with_last_byte(1),
+                index,
+                blob: Bytes::from(vec![index as u8 + 1; blob_size]),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        factory.insert_blob_sidecars(1, sidecars.clone()).unwrap();
+
+        let stored = factory.blob_sidecars(1).unwrap();
+        assert_eq!(stored, sidecars);
+        assert!(stored.iter().all(|sidecar| sidecar.blob.len() == blob_size));
+        assert_eq!(
+            factory.blob_sidecar(1, sidecars[2].versioned_hash).unwrap().as_ref(),
+            Some(&sidecars[2])
+        );
+    }
+
     #[test]
     fn provider_factory_with_database_path() {
         let chain_spec = ChainSpecBuilder::mainnet().build();
@@ -5,21 +5,22 @@ use crate::{
     traits::{
         AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
     },
-    AccountReader, BlockExecutionWriter, BlockHashReader, BlockNumReader, BlockReader, BlockWriter,
-    Chain, EvmEnvProvider, HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider,
-    HeaderSyncMode, HistoricalStateProvider, HistoryWriter, LatestStateProvider,
-    OriginalValuesKnown, ProviderError, PruneCheckpointReader, PruneCheckpointWriter,
-    StageCheckpointReader, StateProviderBox, StatsReader, StorageReader, TransactionVariant,
-    TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
+    AccountReader, BlobSidecarReader, BlobSidecarWriter, BlockExecutionWriter, BlockHashReader,
+    BlockNumReader, BlockReader, BlockWriter, Chain, EvmEnvProvider, HashingWriter, HeaderProvider,
+    HeaderSyncGap, HeaderSyncGapProvider, HeaderSyncMode, HistoricalStateProvider, HistoryWriter,
+    LatestStateProvider, OriginalValuesKnown, ProviderError, PruneCheckpointReader,
+    PruneCheckpointWriter, StageCheckpointReader, StateProviderBox, StatsReader, StorageReader,
+    TransactionVariant, TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
 };
 use itertools::{izip, Itertools};
 use reth_db::{
     common::KeyValue,
-    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, RangeWalker},
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, RangeWalker},
     database::Database,
     models::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
-        ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
+        BlockNumberVersionedHash, ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers,
+        StoredBlockWithdrawals,
     },
     table::{Table, TableRow},
     tables,
@@ -40,10 +41,12 @@ use reth_primitives::{
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders,
     ChainInfo, ChainSpec, GotExpected, Head, Header, PruneCheckpoint, PruneLimiter, PruneModes,
     PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment,
-    StorageEntry, TransactionMeta, TransactionSigned, TransactionSignedEcRecovered,
-    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
+    StorageEntry, StoredBlobSidecar, TransactionMeta, TransactionSigned,
+    TransactionSignedEcRecovered, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    Withdrawals, B256, U256,
 };
 use reth_trie::{
+    history::TrieHistory,
     prefix_set::{PrefixSet, PrefixSetMut, TriePrefixSets},
     updates::TrieUpdates,
     HashedPostState, StateRoot,
@@ -105,6 +108,8 @@ pub struct DatabaseProvider<TX> {
     chain_spec: Arc<ChainSpec>,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Whether the trie changesets are recorded when writing the trie.
+    trie_changesets: bool,
 }
 
 impl<TX> DatabaseProvider<TX> {
@@ -112,6 +117,16 @@ impl<TX> DatabaseProvider<TX> {
     pub fn static_file_provider(&self) -> &StaticFileProvider {
         &self.static_file_provider
     }
+
+    /// Enables recording the trie changesets of every block when writing the trie, see
+    /// [TrieHistory].
+    ///
+    /// Once recorded, the trie changesets keep being recorded regardless of this setting, see
+    /// [DatabaseProvider::trie_changesets_enabled].
+    pub fn with_trie_changesets(mut self, enabled: bool) -> Self {
+        self.trie_changesets = enabled;
+        self
+    }
 }
 
 impl<TX: DbTxMut> DatabaseProvider<TX> {
@@ -121,11 +136,21 @@ impl<TX: DbTxMut> DatabaseProvider<TX> {
         chain_spec: Arc<ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider }
+        Self { tx, chain_spec, static_file_provider, trie_changesets: false }
     }
 }
 
 impl<TX: DbTx> DatabaseProvider<TX> {
+    /// Returns `true` if the trie changesets are recorded when writing the trie.
+    ///
+    /// They are recorded if enabled with [DatabaseProvider::with_trie_changesets] or if the
+    /// database already has a trie history, so that the providers of commands that don't set it
+    /// keep the history intact. The history is only removed explicitly, e.g. by dropping the
+    /// merkle stage.
+    pub fn trie_changesets_enabled(&self) -> ProviderResult<bool> {
+        Ok(self.trie_changesets || TrieHistory::new(&self.tx).first_block()?.is_some())
+    }
+
     /// Iterates over read only values in the given table and collects them into a vector.
     ///
     /// Early-returns if the range is empty, without opening a cursor transaction.
@@ -298,7 +323,7 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         chain_spec: Arc<ChainSpec>,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, chain_spec, static_file_provider }
+        Self { tx, chain_spec, static_file_provider, trie_changesets: false }
     }
 
     /// Consume `DbTx` or `DbTxMut`.
@@ -362,6 +387,32 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         Ok(self.tx.commit()?)
     }
 
+    /// Writes the trie updates of the block range.
+    ///
+    /// If the trie changesets are enabled, see [DatabaseProvider::trie_changesets_enabled], they
+    /// are recorded for every block of the range. The trie updates of more than one block can't be
+    /// split by block, so in that case they are recomputed one block at a time and the state root
+    /// of each block is returned for validation.
+    pub fn write_trie_updates(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+        trie_updates: TrieUpdates,
+    ) -> ProviderResult<Vec<(BlockNumber, B256)>> {
+        if !self.trie_changesets_enabled()? {
+            trie_updates.flush(&self.tx)?;
+            return Ok(Vec::new())
+        }
+
+        let trie_history = TrieHistory::new(&self.tx);
+
+        if range.start() == range.end() {
+            trie_history.write_block_updates(*range.end(), trie_updates)?;
+            return Ok(Vec::new())
+        }
+
+        Ok(trie_history.write_blocks(range).map_err(Into::<reth_db::DatabaseError>::into)?)
+    }
+
     // TODO(joshie) TEMPORARY should be moved to trait providers
 
     /// Unwind or peek at last N blocks of state recreating the [`BundleStateWithReceipts`].
@@ -2246,7 +2297,16 @@ impl<TX: DbTxMut + DbTx> HashingWriter for DatabaseProvider<TX> {
                     block_hash: end_block_hash,
                 })))
             }
-            trie_updates.flush(&self.tx)?;
+            let state_roots = self.write_trie_updates(range.clone(), trie_updates)?;
+            if let Some(&(block_number, state_root)) = state_roots.last() {
+                if state_root != expected_state_root {
+                    return Err(ProviderError::StateRootMismatch(Box::new(RootMismatch {
+                        root: GotExpected { got: state_root, expected: expected_state_root },
+                        block_number,
+                        block_hash: end_block_hash,
+                    })))
+                }
+            }
         }
         durations_recorder.record_relative(metrics::Action::InsertMerkleTree);
 
@@ -2444,6 +2504,7 @@ impl<TX: DbTxMut + DbTx> BlockExecutionWriter for DatabaseProvider<TX> {
                 })))
             }
             trie_updates.flush(&self.tx)?;
+            TrieHistory::new(&self.tx).unwind(parent_number)?;
         }
 
         // get blocks
@@ -2621,6 +2682,10 @@ impl<TX: DbTxMut + DbTx> BlockWriter for DatabaseProvider<TX> {
         let last = blocks.last().unwrap();
         let last_block_number = last.number;
 
+        // Keep the state roots in case the trie has to be written one block at a time.
+        let expected_state_roots =
+            blocks.iter().map(|block| (block.hash(), block.state_root)).collect::<Vec<_>>();
+
         let mut durations_recorder = metrics::DurationsRecorder::default();
 
         // Insert the blocks
@@ -2637,7 +2702,19 @@ impl<TX: DbTxMut + DbTx> BlockWriter for DatabaseProvider<TX> {
         // insert hashes and intermediate merkle nodes
         {
             HashedStateChanges(hashed_state).write_to_db(&self.tx)?;
-            trie_updates.flush(&self.tx)?;
+            let state_roots =
+                self.write_trie_updates(first_number..=last_block_number, trie_updates)?;
+            for ((block_number, state_root), (block_hash, expected_state_root)) in
+                state_roots.into_iter().zip(expected_state_roots)
+            {
+                if state_root != expected_state_root {
+                    return Err(ProviderError::StateRootMismatch(Box::new(RootMismatch {
+                        root: GotExpected { got: state_root, expected: expected_state_root },
+                        block_number,
+                        block_hash,
+                    })))
+                }
+            }
         }
         durations_recorder.record_relative(metrics::Action::InsertHashes);
 
@@ -2673,6 +2750,50 @@ impl<TX: DbTxMut> PruneCheckpointWriter for DatabaseProvider<TX> {
     }
 }
 
+impl<TX: DbTx> BlobSidecarReader for DatabaseProvider<TX> {
+    fn blob_sidecars(&self, block: BlockNumber) -> ProviderResult<Vec<StoredBlobSidecar>> {
+        let mut sidecars = self
+            .tx
+            .cursor_read::<tables::BlobSidecars>()?
+            .walk_range(BlockNumberVersionedHash::range(block..=block))?
+            .map(|entry| entry.map(|(_, sidecar)| sidecar))
+            .collect::<Result<Vec<_>, _>>()?;
+        sidecars.sort_unstable_by_key(|sidecar| sidecar.index);
+        Ok(sidecars)
+    }
+
+    fn blob_sidecar(
+        &self,
+        block: BlockNumber,
+        versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>> {
+        Ok(self.tx.get::<tables::BlobSidecars>((block, versioned_hash).into())?)
+    }
+}
+
+impl<TX: DbTxMut + DbTx> BlobSidecarWriter for DatabaseProvider<TX> {
+    fn insert_blob_sidecars(
+        &self,
+        block: BlockNumber,
+        sidecars: Vec<StoredBlobSidecar>,
+    ) -> ProviderResult<()> {
+        let mut cursor = self.tx.cursor_write::<tables::BlobSidecars>()?;
+        let mut walker = cursor.walk_range(BlockNumberVersionedHash::range(block..=block))?;
+        while walker.next().transpose()?.is_some() {
+            walker.delete_current()?;
+        }
+        for sidecar in sidecars {
+            cursor.upsert((block, sidecar.versioned_hash).into(), sidecar)?;
+        }
+        Ok(())
+    }
+
+    fn remove_blob_sidecars_above(&self, block: BlockNumber) -> ProviderResult<()> {
+        self.unwind_table::<tables::BlobSidecars, _>(block, |key| key.block_number())?;
+        Ok(())
+    }
+}
+
 impl<TX: DbTx> StatsReader for DatabaseProvider<TX> {
     fn count_entries<T: Table>(&self) -> ProviderResult<usize> {
         let db_entries = self.tx.entries::<T>()?;
@@ -1,10 +1,11 @@
 use crate::{
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    BlockSource, BlockchainTreePendingStateProvider, BundleStateDataProvider, CanonChainTracker,
-    CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    DatabaseProviderFactory, EvmEnvProvider, HeaderProvider, ProviderError, PruneCheckpointReader,
-    ReceiptProvider, ReceiptProviderIdExt, StageCheckpointReader, StateProviderBox,
-    StateProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    AccountReader, BlobSidecarReader, BlobSidecarWriter, BlockHashReader, BlockIdReader,
+    BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource, BlockchainTreePendingStateProvider,
+    BundleStateDataProvider, CanonChainTracker, CanonStateNotifications, CanonStateSubscriptions,
+    ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory, EvmEnvProvider, HeaderProvider,
+    ProviderError, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
+    StageCheckpointReader, StateProviderBox, StateProviderFactory, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::{
     database::Database,
@@ -25,9 +26,9 @@ use reth_primitives::{
     stage::{StageCheckpoint, StageId},
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumHash, BlockNumber,
     BlockNumberOrTag, BlockWithSenders, ChainInfo, ChainSpec, Header, PruneCheckpoint,
-    PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    U256,
+    PruneSegment, Receipt, SealedBlock, SealedBlockWithSenders, SealedHeader, StoredBlobSidecar,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    Withdrawals, B256, U256,
 };
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
@@ -557,6 +558,42 @@ where
     }
 }
 
+impl<DB, Tree> BlobSidecarReader for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn blob_sidecars(&self, block: BlockNumber) -> ProviderResult<Vec<StoredBlobSidecar>> {
+        self.database.blob_sidecars(block)
+    }
+
+    fn blob_sidecar(
+        &self,
+        block: BlockNumber,
+        versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>> {
+        self.database.blob_sidecar(block, versioned_hash)
+    }
+}
+
+impl<DB, Tree> BlobSidecarWriter for BlockchainProvider<DB, Tree>
+where
+    DB: Database,
+    Tree: Send + Sync,
+{
+    fn insert_blob_sidecars(
+        &self,
+        block: BlockNumber,
+        sidecars: Vec<StoredBlobSidecar>,
+    ) -> ProviderResult<()> {
+        self.database.insert_blob_sidecars(block, sidecars)
+    }
+
+    fn remove_blob_sidecars_above(&self, block: BlockNumber) -> ProviderResult<()> {
+        self.database.remove_blob_sidecars_above(block)
+    }
+}
+
 impl<DB, Tree> ChainSpecProvider for BlockchainProvider<DB, Tree>
 where
     DB: Send + Sync,
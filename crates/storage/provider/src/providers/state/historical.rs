@@ -4,18 +4,26 @@ use crate::{
 };
 use reth_db::{
     cursor::{DbCursorRO, DbDupCursorRO},
-    models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+    models::{storage_sharded_key::StorageShardedKey, ShardedKey, StorageKeyPreimage},
     table::Table,
     tables,
     transaction::DbTx,
     BlockNumberList,
 };
-use reth_interfaces::provider::ProviderResult;
+use reth_interfaces::{
+    db::{DatabaseError, DatabaseErrorInfo},
+    provider::ProviderResult,
+};
 use reth_primitives::{
-    constants::EPOCH_SLOTS, trie::AccountProof, Account, Address, BlockNumber, Bytecode,
-    StaticFileSegment, StorageKey, StorageValue, B256,
+    constants::EPOCH_SLOTS,
+    trie::{AccountProof, Nibbles},
+    Account, Address, BlockNumber, Bytecode, Bytes, StaticFileSegment, StorageEntry, StorageKey,
+    StorageValue, B256, U256,
+};
+use reth_trie::{
+    hashed_cursor, history::TrieHistory, proof::Proof, trie_cursor::HistoricalTrieCursorFactory,
+    updates::TrieUpdates, HashedPostState,
 };
-use reth_trie::{updates::TrieUpdates, HashedPostState};
 use revm::db::BundleState;
 use std::fmt::Debug;
 
@@ -30,6 +38,12 @@ use std::fmt::Debug;
 /// - [tables::StoragesHistory]
 /// - [tables::AccountChangeSets]
 /// - [tables::StorageChangeSets]
+/// - [tables::AccountsTrieChangeSets]
+/// - [tables::StoragesTrieChangeSets]
+/// - [tables::AccountsTrieHistory]
+/// - [tables::StoragesTrieHistory]
+/// - [tables::HashedAccountPreimages]
+/// - [tables::HashedStoragePreimages]
 #[derive(Debug)]
 pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     /// Transaction
@@ -105,13 +119,19 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         )
     }
 
-    /// Retrieve revert hashed state for this history provider.
-    fn revert_state(&self) -> ProviderResult<HashedPostState> {
+    /// Checks that both the account and the storage history are available at this block.
+    fn check_history_available(&self) -> ProviderResult<()> {
         if !self.lowest_available_blocks.is_account_history_available(self.block_number) ||
             !self.lowest_available_blocks.is_storage_history_available(self.block_number)
         {
             return Err(ProviderError::StateAtBlockPruned(self.block_number))
         }
+        Ok(())
+    }
+
+    /// Retrieve revert hashed state for this history provider.
+    fn revert_state(&self) -> ProviderResult<HashedPostState> {
+        self.check_history_available()?;
 
         let tip = self
             .tx
@@ -309,8 +329,240 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
     }
 
     /// Get account and storage proofs.
-    fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
-        Err(ProviderError::StateRootNotAvailableForHistoricalBlock)
+    ///
+    /// If the trie changesets cover this block, the proof is read from the trie nodes and the
+    /// hashed state at this block, see [Self::historical_proof]. Otherwise it is generated over
+    /// the latest trie overlaid with the hashed state reverted to this block, so the cost grows
+    /// with the distance to the tip.
+    fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof> {
+        let proof = if TrieHistory::new(self.tx).is_available(self.block_number)? {
+            self.historical_proof()?.account_proof(address, keys)
+        } else {
+            self.revert_state()?.account_proof(self.tx, address, keys)
+        };
+        proof.map_err(|err| ProviderError::Database(err.into()))
+    }
+
+    fn account_trie_nodes(&self, targets: Vec<Nibbles>) -> ProviderResult<Vec<Bytes>> {
+        let nodes = if TrieHistory::new(self.tx).is_available(self.block_number)? {
+            self.historical_proof()?.account_trie_nodes(targets)
+        } else {
+            self.revert_state()?.account_trie_nodes(self.tx, targets)
+        };
+        Ok(nodes.map_err(|err| ProviderError::Database(err.into()))?.into_values().collect())
+    }
+
+    fn storage_trie_nodes(
+        &self,
+        hashed_address: B256,
+        targets: Vec<Nibbles>,
+    ) -> ProviderResult<Vec<Bytes>> {
+        let nodes = if TrieHistory::new(self.tx).is_available(self.block_number)? {
+            self.historical_proof()?.storage_trie_nodes(hashed_address, targets)
+        } else {
+            self.revert_state()?.storage_trie_nodes(self.tx, hashed_address, targets)
+        };
+        Ok(nodes.map_err(|err| ProviderError::Database(err.into()))?.into_values().collect())
+    }
+}
+
+impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
+    /// Returns the proof generator over the trie nodes and the hashed state at this block.
+    ///
+    /// The trie nodes are read through the trie history and the leaves through the account and
+    /// storage history, so only the nodes and keys on the paths of the proof are visited.
+    ///
+    /// NOTE: The trie changesets must be available for this block, see
+    /// [TrieHistory::is_available].
+    fn historical_proof(
+        &self,
+    ) -> ProviderResult<
+        Proof<HistoricalTrieCursorFactory<'b, TX>, HistoricalHashedCursorFactory<'_, 'b, TX>>,
+    > {
+        self.check_history_available()?;
+        Ok(Proof::new(self.tx)
+            .with_trie_cursor_factory(HistoricalTrieCursorFactory::new(self.tx, self.block_number))
+            .with_hashed_cursor_factory(HistoricalHashedCursorFactory { provider: self }))
+    }
+}
+
+/// The hashed cursor factory for the hashed state at the block of a [HistoricalStateProviderRef].
+///
+/// The hashed keys with recorded preimages were changed since the first block of the trie history
+/// and are read at the block through the account and storage history. All other keys are
+/// unchanged since then and read from the hashed state.
+#[derive(Debug)]
+struct HistoricalHashedCursorFactory<'a, 'b, TX: DbTx> {
+    provider: &'a HistoricalStateProviderRef<'b, TX>,
+}
+
+impl<'a, 'b, TX: DbTx> Clone for HistoricalHashedCursorFactory<'a, 'b, TX> {
+    fn clone(&self) -> Self {
+        Self { provider: self.provider }
+    }
+}
+
+impl<'a, 'b, TX: DbTx> hashed_cursor::HashedCursorFactory
+    for HistoricalHashedCursorFactory<'a, 'b, TX>
+{
+    type AccountCursor = HistoricalHashedAccountCursor<'a, 'b, TX>;
+    type StorageCursor = HistoricalHashedStorageCursor<'a, 'b, TX>;
+
+    fn hashed_account_cursor(&self) -> Result<Self::AccountCursor, DatabaseError> {
+        Ok(HistoricalHashedAccountCursor {
+            provider: self.provider,
+            hashed_account_cursor: self.provider.tx.cursor_read::<tables::HashedAccounts>()?,
+            preimage_cursor: self.provider.tx.cursor_read::<tables::HashedAccountPreimages>()?,
+            next_account: None,
+            next_preimage: None,
+        })
+    }
+
+    fn hashed_storage_cursor(&self) -> Result<Self::StorageCursor, DatabaseError> {
+        Ok(HistoricalHashedStorageCursor {
+            provider: self.provider,
+            hashed_storage_cursor: self.provider.tx.cursor_dup_read::<tables::HashedStorages>()?,
+            preimage_cursor: self
+                .provider
+                .tx
+                .cursor_dup_read::<tables::HashedStoragePreimages>()?,
+            address: None,
+            next_entry: None,
+            next_preimage: None,
+        })
+    }
+}
+
+/// The cursor over the hashed accounts at the block of a [HistoricalStateProviderRef].
+struct HistoricalHashedAccountCursor<'a, 'b, TX: DbTx> {
+    provider: &'a HistoricalStateProviderRef<'b, TX>,
+    hashed_account_cursor: TX::Cursor<tables::HashedAccounts>,
+    preimage_cursor: TX::Cursor<tables::HashedAccountPreimages>,
+    /// The next hashed account, not yet merged.
+    next_account: Option<(B256, Account)>,
+    /// The next preimage, not yet merged.
+    next_preimage: Option<(B256, Address)>,
+}
+
+impl<'a, 'b, TX: DbTx> HistoricalHashedAccountCursor<'a, 'b, TX> {
+    /// Merges the next hashed account and preimage, skipping the keys without an account at the
+    /// block.
+    fn next_historical(&mut self) -> Result<Option<(B256, Account)>, DatabaseError> {
+        loop {
+            let hashed_address = match (self.next_account, self.next_preimage) {
+                (Some((account_key, _)), Some((preimage_key, _))) => account_key.min(preimage_key),
+                (Some((hashed_address, _)), None) | (None, Some((hashed_address, _))) => {
+                    hashed_address
+                }
+                (None, None) => return Ok(None),
+            };
+
+            let mut account = None;
+            if let Some((_, current)) = self.next_account.filter(|(key, _)| *key == hashed_address)
+            {
+                account = Some(current);
+                self.next_account = self.hashed_account_cursor.next()?;
+            }
+            if let Some((_, address)) = self.next_preimage.filter(|(key, _)| *key == hashed_address)
+            {
+                account = self.provider.basic_account(address).map_err(into_database_error)?;
+                self.next_preimage = self.preimage_cursor.next()?;
+            }
+
+            if let Some(account) = account {
+                return Ok(Some((hashed_address, account)))
+            }
+        }
+    }
+}
+
+impl<'a, 'b, TX: DbTx> hashed_cursor::HashedAccountCursor
+    for HistoricalHashedAccountCursor<'a, 'b, TX>
+{
+    fn seek(&mut self, key: B256) -> Result<Option<(B256, Account)>, DatabaseError> {
+        self.next_account = self.hashed_account_cursor.seek(key)?;
+        self.next_preimage = self.preimage_cursor.seek(key)?;
+        self.next_historical()
+    }
+
+    fn next(&mut self) -> Result<Option<(B256, Account)>, DatabaseError> {
+        self.next_historical()
+    }
+}
+
+/// The cursor over the hashed storages at the block of a [HistoricalStateProviderRef].
+struct HistoricalHashedStorageCursor<'a, 'b, TX: DbTx> {
+    provider: &'a HistoricalStateProviderRef<'b, TX>,
+    hashed_storage_cursor: TX::DupCursor<tables::HashedStorages>,
+    preimage_cursor: TX::DupCursor<tables::HashedStoragePreimages>,
+    /// The preimage of the hashed address of the last seek, if recorded.
+    address: Option<Address>,
+    /// The next hashed storage entry, not yet merged.
+    next_entry: Option<StorageEntry>,
+    /// The next preimage, not yet merged.
+    next_preimage: Option<StorageKeyPreimage>,
+}
+
+impl<'a, 'b, TX: DbTx> HistoricalHashedStorageCursor<'a, 'b, TX> {
+    /// Merges the next hashed storage entry and preimage, skipping the keys with a zero value at
+    /// the block.
+    fn next_historical(&mut self) -> Result<Option<StorageEntry>, DatabaseError> {
+        loop {
+            let hashed_key = match (self.next_entry, self.next_preimage) {
+                (Some(entry), Some(preimage)) => entry.key.min(preimage.hashed_key),
+                (Some(entry), None) => entry.key,
+                (None, Some(preimage)) => preimage.hashed_key,
+                (None, None) => return Ok(None),
+            };
+
+            let mut value = U256::ZERO;
+            if let Some(entry) = self.next_entry.filter(|entry| entry.key == hashed_key) {
+                value = entry.value;
+                self.next_entry = self.hashed_storage_cursor.next_dup_val()?;
+            }
+            if let Some(preimage) = self.next_preimage.filter(|p| p.hashed_key == hashed_key) {
+                // The preimage of the address is recorded together with the storage keys.
+                if let Some(address) = self.address {
+                    value = self
+                        .provider
+                        .storage(address, preimage.key)
+                        .map_err(into_database_error)?
+                        .unwrap_or_default();
+                }
+                self.next_preimage = self.preimage_cursor.next_dup_val()?;
+            }
+
+            if !value.is_zero() {
+                return Ok(Some(StorageEntry { key: hashed_key, value }))
+            }
+        }
+    }
+}
+
+impl<'a, 'b, TX: DbTx> hashed_cursor::HashedStorageCursor
+    for HistoricalHashedStorageCursor<'a, 'b, TX>
+{
+    fn is_storage_empty(&mut self, key: B256) -> Result<bool, DatabaseError> {
+        Ok(hashed_cursor::HashedStorageCursor::seek(self, key, B256::ZERO)?.is_none())
+    }
+
+    fn seek(&mut self, key: B256, subkey: B256) -> Result<Option<StorageEntry>, DatabaseError> {
+        self.address = self.provider.tx.get::<tables::HashedAccountPreimages>(key)?;
+        self.next_entry = self.hashed_storage_cursor.seek_by_key_subkey(key, subkey)?;
+        self.next_preimage = self.preimage_cursor.seek_by_key_subkey(key, subkey)?;
+        self.next_historical()
+    }
+
+    fn next(&mut self) -> Result<Option<StorageEntry>, DatabaseError> {
+        self.next_historical()
+    }
+}
+
+/// Converts the error of a history lookup, since the hashed cursors only return database errors.
+fn into_database_error(err: ProviderError) -> DatabaseError {
+    match err {
+        ProviderError::Database(err) => err,
+        err => DatabaseError::Read(DatabaseErrorInfo { message: err.to_string(), code: 0 }),
     }
 }
 
@@ -407,13 +659,19 @@ mod tests {
         AccountReader, HistoricalStateProvider, HistoricalStateProviderRef, StateProvider,
     };
     use reth_db::{
+        cursor::DbCursorRO,
         models::{storage_sharded_key::StorageShardedKey, AccountBeforeTx, ShardedKey},
         tables,
         transaction::{DbTx, DbTxMut},
         BlockNumberList,
     };
     use reth_interfaces::provider::ProviderError;
-    use reth_primitives::{address, b256, Account, Address, StorageEntry, B256, U256};
+    use reth_primitives::{
+        address, b256, keccak256, trie::AccountProof, Account, Address, BlockNumber, StorageEntry,
+        B256, U256,
+    };
+    use reth_trie::{history::TrieHistory, proof::Proof, StateRoot};
+    use std::collections::{BTreeMap, BTreeSet};
 
     const ADDRESS: Address = address!("0000000000000000000000000000000000000001");
     const HIGHER_ADDRESS: Address = address!("0000000000000000000000000000000000000005");
@@ -716,4 +974,205 @@ mod tests {
             Ok(HistoryInfo::MaybeInPlainState)
         );
     }
+
+    /// The accounts and storages at the start of the block.
+    type TestState = BTreeMap<Address, (Account, BTreeMap<B256, U256>)>;
+
+    /// Returns the state at the start of each of the blocks `0..=3`.
+    fn proof_test_states() -> Vec<TestState> {
+        let account = |nonce| Account { nonce, balance: U256::from(nonce), bytecode_hash: None };
+        let storage = |value: u64| BTreeMap::from([(STORAGE, U256::from(value))]);
+
+        let mut state = TestState::from_iter(
+            (1..=20).map(|i| (Address::with_last_byte(i), (account(i as u64), storage(i as u64)))),
+        );
+        let mut states = vec![state.clone()];
+
+        // block 0 changes an account and its storage and creates another one
+        state.insert(ADDRESS, (account(100), storage(100)));
+        state.insert(HIGHER_ADDRESS, (account(5), BTreeMap::default()));
+        state.insert(Address::with_last_byte(21), (account(21), storage(21)));
+        states.push(state.clone());
+
+        // block 1 destroys an account
+        state.remove(&Address::with_last_byte(21));
+        states.push(state.clone());
+
+        // block 2 only changes storage
+        state.get_mut(&ADDRESS).unwrap().1.insert(STORAGE, U256::from(200));
+        states.push(state);
+
+        states
+    }
+
+    fn insert_hashed_state<TX: DbTxMut>(tx: &TX, state: &TestState) {
+        tx.clear::<tables::HashedAccounts>().unwrap();
+        tx.clear::<tables::HashedStorages>().unwrap();
+        for (address, (account, storage)) in state {
+            let hashed_address = keccak256(address);
+            tx.put::<tables::HashedAccounts>(hashed_address, *account).unwrap();
+            for (slot, value) in storage {
+                tx.put::<tables::HashedStorages>(
+                    hashed_address,
+                    StorageEntry { key: keccak256(slot), value: *value },
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    /// Writes the changesets that revert the state at the end of the block to the one at its
+    /// start.
+    fn insert_changesets<TX: DbTxMut>(
+        tx: &TX,
+        block_number: BlockNumber,
+        before: &TestState,
+        after: &TestState,
+    ) {
+        for address in before.keys().chain(after.keys()).collect::<BTreeSet<_>>() {
+            let (account_before, storage_before) = before.get(address).cloned().unzip();
+            let (account_after, storage_after) = after.get(address).cloned().unzip();
+            if account_before != account_after || storage_before != storage_after {
+                tx.put::<tables::AccountChangeSets>(
+                    block_number,
+                    AccountBeforeTx { address: *address, info: account_before },
+                )
+                .unwrap();
+            }
+
+            let storage_before = storage_before.unwrap_or_default();
+            let storage_after = storage_after.unwrap_or_default();
+            for slot in storage_before.keys().chain(storage_after.keys()) {
+                if storage_before.get(slot) != storage_after.get(slot) {
+                    tx.put::<tables::StorageChangeSets>(
+                        (block_number, *address).into(),
+                        StorageEntry {
+                            key: *slot,
+                            value: storage_before.get(slot).copied().unwrap_or_default(),
+                        },
+                    )
+                    .unwrap();
+                }
+            }
+        }
+    }
+
+    /// Writes the history indices of the written changesets and the plain state at the tip.
+    fn insert_history<TX: DbTx + DbTxMut>(tx: &TX, tip_state: &TestState) {
+        let mut account_history = BTreeMap::<Address, Vec<BlockNumber>>::new();
+        for entry in tx.cursor_read::<tables::AccountChangeSets>().unwrap().walk(None).unwrap() {
+            let (block_number, AccountBeforeTx { address, .. }) = entry.unwrap();
+            account_history.entry(address).or_default().push(block_number);
+        }
+        for (address, blocks) in account_history {
+            tx.put::<tables::AccountsHistory>(
+                ShardedKey::last(address),
+                BlockNumberList::new(blocks).unwrap(),
+            )
+            .unwrap();
+        }
+
+        let mut storage_history = BTreeMap::<(Address, B256), Vec<BlockNumber>>::new();
+        for entry in tx.cursor_read::<tables::StorageChangeSets>().unwrap().walk(None).unwrap() {
+            let (key, StorageEntry { key: slot, .. }) = entry.unwrap();
+            storage_history.entry((key.address(), slot)).or_default().push(key.block_number());
+        }
+        for ((address, slot), blocks) in storage_history {
+            tx.put::<tables::StoragesHistory>(
+                StorageShardedKey::last(address, slot),
+                BlockNumberList::new(blocks).unwrap(),
+            )
+            .unwrap();
+        }
+
+        for (address, (account, storage)) in tip_state {
+            tx.put::<tables::PlainAccountState>(*address, *account).unwrap();
+            for (slot, value) in storage {
+                tx.put::<tables::PlainStorageState>(
+                    *address,
+                    StorageEntry { key: *slot, value: *value },
+                )
+                .unwrap();
+            }
+        }
+    }
+
+    /// Generates the proof over a database holding only the given state.
+    fn expected_proof(state: &TestState, address: Address) -> AccountProof {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        insert_hashed_state(&tx, state);
+        Proof::new(&tx).account_proof(address, &[STORAGE]).unwrap()
+    }
+
+    fn assert_historical_proofs(with_trie_history: bool) {
+        let states = proof_test_states();
+        let tip = states.len() as BlockNumber - 2;
+
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+        let static_file_provider = factory.static_file_provider();
+
+        for block_number in 0..=tip {
+            tx.put::<tables::CanonicalHeaders>(block_number, B256::random()).unwrap();
+            insert_changesets(
+                &tx,
+                block_number,
+                &states[block_number as usize],
+                &states[block_number as usize + 1],
+            );
+        }
+        insert_history(&tx, states.last().unwrap());
+        if with_trie_history {
+            // the trie is at the start of the first block, the changesets record every block
+            insert_hashed_state(&tx, &states[0]);
+            let (_, updates) = StateRoot::from_tx(&tx).root_with_updates().unwrap();
+            updates.flush(&tx).unwrap();
+            insert_hashed_state(&tx, states.last().unwrap());
+            TrieHistory::new(&tx).write_blocks(0..=tip).unwrap();
+        } else {
+            insert_hashed_state(&tx, states.last().unwrap());
+        }
+        assert_eq!(TrieHistory::new(&tx).is_available(0).unwrap(), with_trie_history);
+
+        for block_number in 0..=tip {
+            let provider =
+                HistoricalStateProviderRef::new(&tx, block_number, static_file_provider.clone());
+            let state = &states[block_number as usize];
+            for address in [ADDRESS, HIGHER_ADDRESS, Address::with_last_byte(21)] {
+                assert_eq!(
+                    provider.proof(address, &[STORAGE]).unwrap(),
+                    expected_proof(state, address),
+                    "proof of {address} at block {block_number}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn history_provider_proof() {
+        assert_historical_proofs(false);
+    }
+
+    #[test]
+    fn history_provider_proof_from_trie_history() {
+        assert_historical_proofs(true);
+    }
+
+    #[test]
+    fn history_provider_proof_pruned() {
+        let factory = create_test_provider_factory();
+        let tx = factory.provider_rw().unwrap().into_tx();
+
+        let provider = HistoricalStateProviderRef::new_with_lowest_available_blocks(
+            &tx,
+            2,
+            LowestAvailableBlocks {
+                account_history_block_number: Some(3),
+                storage_history_block_number: Some(3),
+            },
+            factory.static_file_provider(),
+        );
+        assert_eq!(provider.proof(ADDRESS, &[STORAGE]), Err(ProviderError::StateAtBlockPruned(2)));
+    }
 }
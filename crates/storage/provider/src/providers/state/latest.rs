@@ -9,8 +9,9 @@ use reth_db::{
 };
 use reth_interfaces::provider::{ProviderError, ProviderResult};
 use reth_primitives::{
-    trie::AccountProof, Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey,
-    StorageValue, B256,
+    trie::{AccountProof, Nibbles},
+    Account, Address, BlockNumber, Bytecode, Bytes, StaticFileSegment, StorageKey, StorageValue,
+    B256,
 };
 use reth_trie::{proof::Proof, updates::TrieUpdates, HashedPostState};
 use revm::db::BundleState;
@@ -117,6 +118,26 @@ impl<'b, TX: DbTx> StateProvider for LatestStateProviderRef<'b, TX> {
             .account_proof(address, slots)
             .map_err(Into::<reth_db::DatabaseError>::into)?)
     }
+
+    fn account_trie_nodes(&self, targets: Vec<Nibbles>) -> ProviderResult<Vec<Bytes>> {
+        Ok(Proof::new(self.tx)
+            .account_trie_nodes(targets)
+            .map_err(Into::<reth_db::DatabaseError>::into)?
+            .into_values()
+            .collect())
+    }
+
+    fn storage_trie_nodes(
+        &self,
+        hashed_address: B256,
+        targets: Vec<Nibbles>,
+    ) -> ProviderResult<Vec<Bytes>> {
+        Ok(Proof::new(self.tx)
+            .storage_trie_nodes(hashed_address, targets)
+            .map_err(Into::<reth_db::DatabaseError>::into)?
+            .into_values()
+            .collect())
+    }
 }
 
 /// State provider for the latest state.
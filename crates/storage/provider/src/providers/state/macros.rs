@@ -44,6 +44,8 @@ macro_rules! delegate_provider_impls {
             StateProvider $(where [$($generics)*])?{
                 fn storage(&self, account: reth_primitives::Address, storage_key: reth_primitives::StorageKey) -> reth_interfaces::provider::ProviderResult<Option<reth_primitives::StorageValue>>;
                 fn proof(&self, address: reth_primitives::Address, keys: &[reth_primitives::B256]) -> reth_interfaces::provider::ProviderResult<reth_primitives::trie::AccountProof>;
+                fn account_trie_nodes(&self, targets: Vec<reth_primitives::trie::Nibbles>) -> reth_interfaces::provider::ProviderResult<Vec<reth_primitives::Bytes>>;
+                fn storage_trie_nodes(&self, hashed_address: reth_primitives::B256, targets: Vec<reth_primitives::trie::Nibbles>) -> reth_interfaces::provider::ProviderResult<Vec<reth_primitives::Bytes>>;
                 fn bytecode_by_hash(&self, code_hash: reth_primitives::B256) -> reth_interfaces::provider::ProviderResult<Option<reth_primitives::Bytecode>>;
             }
         );
@@ -1,20 +1,21 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    BundleStateDataProvider, ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider,
-    ReceiptProviderIdExt, StateProvider, StateProviderBox, StateProviderFactory, StateRootProvider,
-    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    AccountReader, BlobSidecarReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader,
+    BlockReaderIdExt, BundleStateDataProvider, ChainSpecProvider, ChangeSetReader, EvmEnvProvider,
+    HeaderProvider, ReceiptProviderIdExt, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRootProvider, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use parking_lot::Mutex;
 use reth_db::models::{AccountBeforeTx, StoredBlockBodyIndices};
 use reth_evm::ConfigureEvmEnv;
 use reth_interfaces::provider::{ProviderError, ProviderResult};
 use reth_primitives::{
-    keccak256, trie::AccountProof, Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId,
-    BlockNumber, BlockWithSenders, Bytecode, Bytes, ChainInfo, ChainSpec, Header, Receipt,
-    SealedBlock, SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    U256,
+    keccak256,
+    trie::{AccountProof, Nibbles},
+    Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber, BlockWithSenders,
+    Bytecode, Bytes, ChainInfo, ChainSpec, Header, Receipt, SealedBlock, SealedBlockWithSenders,
+    SealedHeader, StorageKey, StorageValue, StoredBlobSidecar, TransactionMeta, TransactionSigned,
+    TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256, U256,
 };
 use reth_trie::updates::TrieUpdates;
 use revm::{
@@ -36,6 +37,8 @@ pub struct MockEthProvider {
     pub headers: Arc<Mutex<HashMap<B256, Header>>>,
     /// Local account store
     pub accounts: Arc<Mutex<HashMap<Address, ExtendedAccount>>>,
+    /// Local receipt store
+    pub receipts: Arc<Mutex<HashMap<B256, Vec<Receipt>>>>,
     /// Local chain spec
     pub chain_spec: Arc<ChainSpec>,
 }
@@ -46,6 +49,7 @@ impl Default for MockEthProvider {
             blocks: Default::default(),
             headers: Default::default(),
             accounts: Default::default(),
+            receipts: Default::default(),
             chain_spec: Arc::new(reth_primitives::ChainSpecBuilder::mainnet().build()),
         }
     }
@@ -115,6 +119,11 @@ impl MockEthProvider {
         }
     }
 
+    /// Add the receipts of a block to local receipt store
+    pub fn add_receipts(&self, hash: B256, receipts: Vec<Receipt>) {
+        self.receipts.lock().insert(hash, receipts);
+    }
+
     /// Add account to local account store
     pub fn add_account(&self, address: Address, account: ExtendedAccount) {
         self.accounts.lock().insert(address, account);
@@ -346,8 +355,12 @@ impl ReceiptProvider for MockEthProvider {
         Ok(None)
     }
 
-    fn receipts_by_block(&self, _block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
-        Ok(None)
+    fn receipts_by_block(&self, block: BlockHashOrNumber) -> ProviderResult<Option<Vec<Receipt>>> {
+        let hash = match block {
+            BlockHashOrNumber::Hash(hash) => Some(hash),
+            BlockHashOrNumber::Number(number) => self.block_hash(number)?,
+        };
+        Ok(hash.and_then(|hash| self.receipts.lock().get(&hash).cloned()))
     }
 
     fn receipts_by_tx_range(
@@ -468,10 +481,10 @@ impl BlockReader for MockEthProvider {
 
     fn block_with_senders(
         &self,
-        _id: BlockHashOrNumber,
+        id: BlockHashOrNumber,
         _transaction_kind: TransactionVariant,
     ) -> ProviderResult<Option<BlockWithSenders>> {
-        Ok(None)
+        Ok(self.block(id)?.and_then(|block| block.with_recovered_senders()))
     }
 
     fn block_range(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<Vec<Block>> {
@@ -563,6 +576,18 @@ impl StateProvider for MockEthProvider {
     fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
         Ok(AccountProof::default())
     }
+
+    fn account_trie_nodes(&self, _targets: Vec<Nibbles>) -> ProviderResult<Vec<Bytes>> {
+        Ok(Vec::new())
+    }
+
+    fn storage_trie_nodes(
+        &self,
+        _hashed_address: B256,
+        _targets: Vec<Nibbles>,
+    ) -> ProviderResult<Vec<Bytes>> {
+        Ok(Vec::new())
+    }
 }
 
 impl EvmEnvProvider for MockEthProvider {
@@ -581,14 +606,22 @@ impl EvmEnvProvider for MockEthProvider {
 
     fn fill_env_with_header<EvmConfig>(
         &self,
-        _cfg: &mut CfgEnvWithHandlerCfg,
-        _block_env: &mut BlockEnv,
-        _header: &Header,
+        cfg: &mut CfgEnvWithHandlerCfg,
+        block_env: &mut BlockEnv,
+        header: &Header,
         _evm_config: EvmConfig,
     ) -> ProviderResult<()>
     where
         EvmConfig: ConfigureEvmEnv,
     {
+        let total_difficulty = self.header_td_by_number(header.number)?.unwrap_or_default();
+        EvmConfig::fill_cfg_and_block_env(
+            cfg,
+            block_env,
+            &self.chain_spec,
+            header,
+            total_difficulty,
+        );
         Ok(())
     }
 
@@ -679,6 +712,20 @@ impl WithdrawalsProvider for MockEthProvider {
     }
 }
 
+impl BlobSidecarReader for MockEthProvider {
+    fn blob_sidecars(&self, _block: BlockNumber) -> ProviderResult<Vec<StoredBlobSidecar>> {
+        Ok(Vec::new())
+    }
+
+    fn blob_sidecar(
+        &self,
+        _block: BlockNumber,
+        _versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>> {
+        Ok(None)
+    }
+}
+
 impl ChangeSetReader for MockEthProvider {
     fn account_block_changeset(
         &self,
@@ -1,22 +1,22 @@
 use crate::{
     traits::{BlockSource, ReceiptProvider},
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, PruneCheckpointReader,
-    ReceiptProviderIdExt, StageCheckpointReader, StateProvider, StateProviderBox,
-    StateProviderFactory, StateRootProvider, TransactionVariant, TransactionsProvider,
-    WithdrawalsProvider,
+    AccountReader, BlobSidecarReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader,
+    BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider,
+    PruneCheckpointReader, ReceiptProviderIdExt, StageCheckpointReader, StateProvider,
+    StateProviderBox, StateProviderFactory, StateRootProvider, TransactionVariant,
+    TransactionsProvider, WithdrawalsProvider,
 };
 use reth_db::models::{AccountBeforeTx, StoredBlockBodyIndices};
 use reth_evm::ConfigureEvmEnv;
 use reth_interfaces::provider::ProviderResult;
 use reth_primitives::{
     stage::{StageCheckpoint, StageId},
-    trie::AccountProof,
+    trie::{AccountProof, Nibbles},
     Account, Address, Block, BlockHash, BlockHashOrNumber, BlockId, BlockNumber, BlockWithSenders,
-    Bytecode, ChainInfo, ChainSpec, Header, PruneCheckpoint, PruneSegment, Receipt, SealedBlock,
-    SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    MAINNET, U256,
+    Bytecode, Bytes, ChainInfo, ChainSpec, Header, PruneCheckpoint, PruneSegment, Receipt,
+    SealedBlock, SealedBlockWithSenders, SealedHeader, StorageKey, StorageValue, StoredBlobSidecar,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    Withdrawals, B256, MAINNET, U256,
 };
 use reth_trie::updates::TrieUpdates;
 use revm::{
@@ -284,6 +284,20 @@ impl AccountReader for NoopProvider {
     }
 }
 
+impl BlobSidecarReader for NoopProvider {
+    fn blob_sidecars(&self, _block: BlockNumber) -> ProviderResult<Vec<StoredBlobSidecar>> {
+        Ok(Vec::new())
+    }
+
+    fn blob_sidecar(
+        &self,
+        _block: BlockNumber,
+        _versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>> {
+        Ok(None)
+    }
+}
+
 impl ChangeSetReader for NoopProvider {
     fn account_block_changeset(
         &self,
@@ -322,6 +336,18 @@ impl StateProvider for NoopProvider {
     fn proof(&self, _address: Address, _keys: &[B256]) -> ProviderResult<AccountProof> {
         Ok(AccountProof::default())
     }
+
+    fn account_trie_nodes(&self, _targets: Vec<Nibbles>) -> ProviderResult<Vec<Bytes>> {
+        Ok(Vec::new())
+    }
+
+    fn storage_trie_nodes(
+        &self,
+        _hashed_address: B256,
+        _targets: Vec<Nibbles>,
+    ) -> ProviderResult<Vec<Bytes>> {
+        Ok(Vec::new())
+    }
 }
 
 impl EvmEnvProvider for NoopProvider {
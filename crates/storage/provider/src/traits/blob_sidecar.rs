@@ -0,0 +1,32 @@
+use reth_interfaces::provider::ProviderResult;
+use reth_primitives::{BlockNumber, StoredBlobSidecar, B256};
+
+/// The trait for fetching archived blob sidecars.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait BlobSidecarReader: Send + Sync {
+    /// Returns all archived blob sidecars of the given block, ordered by their index in the block.
+    ///
+    /// Returns an empty list if the block has no archived sidecars.
+    fn blob_sidecars(&self, block: BlockNumber) -> ProviderResult<Vec<StoredBlobSidecar>>;
+
+    /// Returns the archived blob sidecar with the given versioned hash in the given block.
+    fn blob_sidecar(
+        &self,
+        block: BlockNumber,
+        versioned_hash: B256,
+    ) -> ProviderResult<Option<StoredBlobSidecar>>;
+}
+
+/// The trait for archiving blob sidecars.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait BlobSidecarWriter: Send + Sync {
+    /// Replaces all archived blob sidecars of the given block with the given ones.
+    fn insert_blob_sidecars(
+        &self,
+        block: BlockNumber,
+        sidecars: Vec<StoredBlobSidecar>,
+    ) -> ProviderResult<()>;
+
+    /// Removes all archived blob sidecars of blocks above the given block.
+    fn remove_blob_sidecars_above(&self, block: BlockNumber) -> ProviderResult<()>;
+}
@@ -1,8 +1,8 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    DatabaseProviderFactory, EvmEnvProvider, StateProviderFactory,
+    AccountReader, BlobSidecarReader, BlockReaderIdExt, CanonStateSubscriptions, ChainSpecProvider,
+    ChangeSetReader, DatabaseProviderFactory, EvmEnvProvider, StateProviderFactory,
 };
 use reth_db::database::Database;
 
@@ -15,6 +15,7 @@ pub trait FullProvider<DB: Database>:
     + EvmEnvProvider
     + ChainSpecProvider
     + ChangeSetReader
+    + BlobSidecarReader
     + CanonStateSubscriptions
     + Clone
     + Unpin
@@ -30,6 +31,7 @@ impl<T, DB: Database> FullProvider<DB> for T where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + BlobSidecarReader
         + CanonStateSubscriptions
         + Clone
         + Unpin
@@ -72,6 +72,9 @@ pub use history::HistoryWriter;
 mod prune_checkpoint;
 pub use prune_checkpoint::{PruneCheckpointReader, PruneCheckpointWriter};
 
+mod blob_sidecar;
+pub use blob_sidecar::{BlobSidecarReader, BlobSidecarWriter};
+
 mod database_provider;
 pub use database_provider::DatabaseProviderFactory;
 
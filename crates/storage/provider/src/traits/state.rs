@@ -3,8 +3,9 @@ use crate::{BlockHashReader, BlockIdReader, BundleStateWithReceipts, StateRootPr
 use auto_impl::auto_impl;
 use reth_interfaces::provider::{ProviderError, ProviderResult};
 use reth_primitives::{
-    trie::AccountProof, Address, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag,
-    Bytecode, StorageKey, StorageValue, B256, KECCAK_EMPTY, U256,
+    trie::{AccountProof, Nibbles},
+    Address, BlockHash, BlockId, BlockNumHash, BlockNumber, BlockNumberOrTag, Bytecode, Bytes,
+    StorageKey, StorageValue, B256, KECCAK_EMPTY, U256,
 };
 
 /// Type alias of boxed [StateProvider].
@@ -26,6 +27,22 @@ pub trait StateProvider: BlockHashReader + AccountReader + StateRootProvider + S
     /// Get account and storage proofs.
     fn proof(&self, address: Address, keys: &[B256]) -> ProviderResult<AccountProof>;
 
+    /// Get the RLP encoded nodes of the account trie on the paths to the given targets.
+    ///
+    /// Unlike [StateProvider::proof], the targets are paths in the trie and do not need to lead to
+    /// an existing key, which allows reading nodes that are not on the path of any known key.
+    fn account_trie_nodes(&self, targets: Vec<Nibbles>) -> ProviderResult<Vec<Bytes>>;
+
+    /// Get the RLP encoded nodes of the storage trie of the account on the paths to the given
+    /// targets.
+    ///
+    /// See [StateProvider::account_trie_nodes].
+    fn storage_trie_nodes(
+        &self,
+        hashed_address: B256,
+        targets: Vec<Nibbles>,
+    ) -> ProviderResult<Vec<Bytes>>;
+
     /// Get account code by its address.
     ///
     /// Returns `None` if the account doesn't exist or account is not a contract
@@ -0,0 +1,238 @@
+//! Support for archiving the blob sidecars of included blob transactions.
+
+use crate::TransactionPool;
+use futures_util::{future::BoxFuture, FutureExt, Stream, StreamExt};
+use reth_primitives::{SealedBlockWithSenders, StoredBlobSidecar};
+use reth_provider::{BlobSidecarWriter, CanonStateNotification};
+use reth_tasks::TaskSpawner;
+use std::collections::HashMap;
+use tokio::sync::oneshot;
+use tracing::{debug, error, trace};
+
+/// Returns a spawnable future for archiving the blob sidecars of canonical blob transactions.
+pub fn maintain_blob_archive_future<P, W, St, Tasks>(
+    pool: P,
+    writer: W,
+    events: St,
+    task_spawner: Tasks,
+) -> BoxFuture<'static, ()>
+where
+    P: TransactionPool + 'static,
+    W: BlobSidecarWriter + Clone + 'static,
+    St: Stream<Item = CanonStateNotification> + Send + Unpin + 'static,
+    Tasks: TaskSpawner + 'static,
+{
+    async move {
+        maintain_blob_archive(pool, writer, events, task_spawner).await;
+    }
+    .boxed()
+}
+
+/// Archives the blob sidecars of all blob transactions that are included in canonical blocks.
+///
+/// The sidecars are taken from the pool's blob store, which keeps them until the block that
+/// included the transaction is finalized. Blocks that are reorged out are removed from the
+/// archive before the sidecars of the new chain are written.
+///
+/// The archive writes are blocking database operations, so each notification is handled on a
+/// blocking task. Notifications are handled one at a time to keep the removal of reorged blocks
+/// ordered with the writes of the blocks before them.
+pub async fn maintain_blob_archive<P, W, St, Tasks>(
+    pool: P,
+    writer: W,
+    mut events: St,
+    task_spawner: Tasks,
+) where
+    P: TransactionPool + 'static,
+    W: BlobSidecarWriter + Clone + 'static,
+    St: Stream<Item = CanonStateNotification> + Send + Unpin + 'static,
+    Tasks: TaskSpawner + 'static,
+{
+    while let Some(event) = events.next().await {
+        let (tx, rx) = oneshot::channel();
+        let pool = pool.clone();
+        let writer = writer.clone();
+        task_spawner.spawn_blocking(Box::pin(async move {
+            archive_blob_sidecars(&pool, &writer, &event);
+            let _ = tx.send(());
+        }));
+
+        if rx.await.is_err() {
+            error!(target: "txpool::blob_archive", "blob archive task dropped");
+            return
+        }
+    }
+}
+
+/// Applies a single canonical state notification to the blob sidecar archive.
+fn archive_blob_sidecars<P, W>(pool: &P, writer: &W, event: &CanonStateNotification)
+where
+    P: TransactionPool,
+    W: BlobSidecarWriter,
+{
+    let new = event.committed();
+
+    if event.reverted().is_some() {
+        let fork_block = new.fork_block().number;
+        trace!(target: "txpool::blob_archive", %fork_block, "removing reorged blob sidecars");
+        if let Err(err) = writer.remove_blob_sidecars_above(fork_block) {
+            error!(target: "txpool::blob_archive", %err, %fork_block, "failed to remove reorged blob sidecars");
+        }
+    }
+
+    for block in new.blocks_iter() {
+        let sidecars = block_blob_sidecars(pool, block);
+        if sidecars.is_empty() {
+            continue
+        }
+
+        trace!(target: "txpool::blob_archive", block = block.number, blobs = sidecars.len(), "archiving blob sidecars");
+        if let Err(err) = writer.insert_blob_sidecars(block.number, sidecars) {
+            error!(target: "txpool::blob_archive", %err, block = block.number, "failed to archive blob sidecars");
+        }
+    }
+}
+
+/// Collects the sidecars of all blob transactions in the given block from the pool's blob store.
+///
+/// Sidecars that are not available in the blob store are skipped, but still account for the
+/// block-wide index of the following blobs.
+fn block_blob_sidecars<P: TransactionPool>(
+    pool: &P,
+    block: &SealedBlockWithSenders,
+) -> Vec<StoredBlobSidecar> {
+    let blob_txs = block
+        .body
+        .iter()
+        .filter_map(|tx| tx.blob_versioned_hashes().map(|hashes| (tx.hash, hashes)))
+        .collect::<Vec<_>>();
+    if blob_txs.is_empty() {
+        return Vec::new()
+    }
+
+    let mut blobs: HashMap<_, _> = match pool
+        .get_all_blobs(blob_txs.iter().map(|(hash, _)| *hash).collect())
+    {
+        Ok(blobs) => blobs.into_iter().collect(),
+        Err(err) => {
+            error!(target: "txpool::blob_archive", %err, block = block.number, "failed to fetch blob sidecars");
+            return Vec::new()
+        }
+    };
+
+    let mut sidecars = Vec::new();
+    let mut index = 0;
+    for (tx_hash, versioned_hashes) in blob_txs {
+        match blobs.remove(&tx_hash) {
+            Some(sidecar) => sidecars.extend(StoredBlobSidecar::from_transaction_sidecar(
+                tx_hash,
+                &versioned_hashes,
+                &sidecar,
+                index,
+            )),
+            None => {
+                debug!(target: "txpool::blob_archive", %tx_hash, block = block.number, "missing blob sidecar")
+            }
+        }
+        index += versioned_hashes.len() as u64;
+    }
+    sidecars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{blobstore::InMemoryBlobStore, test_utils::TestPoolBuilder, BlobStore};
+    use parking_lot::Mutex;
+    use reth_primitives::{
+        kzg::{Blob, BYTES_PER_BLOB},
+        transaction::generate_blob_sidecar,
+        Address, BlockNumber, Header, SealedBlock, Signature, Transaction, TransactionSigned,
+        TxEip4844, B256,
+    };
+    use reth_provider::{BundleStateWithReceipts, Chain, ProviderError};
+    use reth_tasks::TokioTaskExecutor;
+    use std::sync::Arc;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum WriterOp {
+        Insert(BlockNumber, Vec<StoredBlobSidecar>),
+        RemoveAbove(BlockNumber),
+    }
+
+    #[derive(Clone, Default)]
+    struct MockWriter(Arc<Mutex<Vec<WriterOp>>>);
+
+    impl BlobSidecarWriter for MockWriter {
+        fn insert_blob_sidecars(
+            &self,
+            block: BlockNumber,
+            sidecars: Vec<StoredBlobSidecar>,
+        ) -> Result<(), ProviderError> {
+            self.0.lock().push(WriterOp::Insert(block, sidecars));
+            Ok(())
+        }
+
+        fn remove_blob_sidecars_above(&self, block: BlockNumber) -> Result<(), ProviderError> {
+            self.0.lock().push(WriterOp::RemoveAbove(block));
+            Ok(())
+        }
+    }
+
+    fn blob_tx() -> TransactionSigned {
+        TransactionSigned::from_transaction_and_signature(
+            Transaction::Eip4844(TxEip4844 {
+                blob_versioned_hashes: vec![B256::random()],
+                ..Default::default()
+            }),
+            Signature::default(),
+        )
+    }
+
+    fn chain(number: BlockNumber, tx: TransactionSigned) -> Arc<Chain> {
+        let block = SealedBlock {
+            header: Header { number, ..Default::default() }.seal_slow(),
+            body: vec![tx],
+            ..Default::default()
+        };
+        let block = SealedBlockWithSenders::new(block, vec![Address::ZERO]).unwrap();
+        Arc::new(Chain::from_block(block, BundleStateWithReceipts::default(), None))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn archives_and_removes_reorged_sidecars() {
+        let blob_store = InMemoryBlobStore::default();
+        let pool = TestPoolBuilder::default().with_blob_store(blob_store.clone());
+        let sidecar = generate_blob_sidecar(vec![Blob::from([0; BYTES_PER_BLOB])]);
+
+        let (old_tx, new_tx) = (blob_tx(), blob_tx());
+        blob_store.insert(old_tx.hash, sidecar.clone()).unwrap();
+        blob_store.insert(new_tx.hash, sidecar.clone()).unwrap();
+        let expected = |tx: &TransactionSigned| {
+            StoredBlobSidecar::from_transaction_sidecar(
+                tx.hash,
+                &tx.blob_versioned_hashes().unwrap(),
+                &sidecar,
+                0,
+            )
+        };
+
+        let old = chain(1, old_tx.clone());
+        let events = futures_util::stream::iter(vec![
+            CanonStateNotification::Commit { new: old.clone() },
+            CanonStateNotification::Reorg { old, new: chain(1, new_tx.clone()) },
+        ]);
+
+        let writer = MockWriter::default();
+        maintain_blob_archive(pool, writer.clone(), events, TokioTaskExecutor::default()).await;
+
+        assert_eq!(
+            *writer.0.lock(),
+            vec![
+                WriterOp::Insert(1, expected(&old_tx)),
+                WriterOp::RemoveAbove(0),
+                WriterOp::Insert(1, expected(&new_tx)),
+            ]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 //! Storage for blob data of EIP4844 transactions.
 
+pub use archive::{maintain_blob_archive, maintain_blob_archive_future};
 pub use disk::{DiskFileBlobStore, DiskFileBlobStoreConfig, OpenDiskFileBlobStore};
 pub use mem::InMemoryBlobStore;
 pub use noop::NoopBlobStore;
@@ -10,6 +11,7 @@ use std::{
 };
 pub use tracker::{BlobStoreCanonTracker, BlobStoreUpdates};
 
+mod archive;
 pub mod disk;
 mod mem;
 mod noop;
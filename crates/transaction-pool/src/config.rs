@@ -18,6 +18,9 @@ pub const DEFAULT_PRICE_BUMP: u128 = 10;
 /// This enforces that a blob transaction requires a 100% price bump to be replaced
 pub const REPLACE_BLOB_PRICE_BUMP: u128 = 100;
 
+/// The default number of removed transactions the pool keeps a record of.
+pub const TXPOOL_MAX_REMOVAL_HISTORY_DEFAULT: usize = 4096;
+
 /// Configuration options for the Transaction pool.
 #[derive(Debug, Clone)]
 pub struct PoolConfig {
@@ -36,6 +39,9 @@ pub struct PoolConfig {
     /// How to handle locally received transactions:
     /// [TransactionOrigin::Local](crate::TransactionOrigin).
     pub local_transactions_config: LocalTransactionConfig,
+    /// Max number of removed transactions to keep a record of, including the reason why they
+    /// were removed.
+    pub max_removal_history: usize,
 }
 
 impl PoolConfig {
@@ -59,6 +65,7 @@ impl Default for PoolConfig {
             max_account_slots: TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
             price_bumps: Default::default(),
             local_transactions_config: Default::default(),
+            max_removal_history: TXPOOL_MAX_REMOVAL_HISTORY_DEFAULT,
         }
     }
 }
@@ -163,13 +163,14 @@ pub use crate::{
     config::{
         LocalTransactionConfig, PoolConfig, PriceBumpConfig, SubPoolLimit, DEFAULT_PRICE_BUMP,
         REPLACE_BLOB_PRICE_BUMP, TXPOOL_MAX_ACCOUNT_SLOTS_PER_SENDER,
-        TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT, TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
+        TXPOOL_MAX_REMOVAL_HISTORY_DEFAULT, TXPOOL_SUBPOOL_MAX_SIZE_MB_DEFAULT,
+        TXPOOL_SUBPOOL_MAX_TXS_DEFAULT,
     },
     error::PoolResult,
     ordering::{CoinbaseTipOrdering, Priority, TransactionOrdering},
     pool::{
         blob_tx_priority, fee_delta, state::SubPool, AllTransactionsEvents, FullTransactionEvent,
-        TransactionEvent, TransactionEvents,
+        RemovedTransaction, TransactionEvent, TransactionEvents, TransactionRemovalReason,
     },
     traits::*,
     validate::{
@@ -504,6 +505,14 @@ where
         self.pool.unique_senders()
     }
 
+    fn removal_reason(&self, tx_hash: &TxHash) -> Option<RemovedTransaction> {
+        self.pool.removal_reason(tx_hash)
+    }
+
+    fn removed_transactions_by_sender(&self, sender: Address) -> Vec<RemovedTransaction> {
+        self.pool.removed_transactions_by_sender(sender)
+    }
+
     fn get_blob(&self, tx_hash: TxHash) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
         self.pool.blob_store().get(tx_hash)
     }
@@ -348,6 +348,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                     changed_accounts,
                     // all transactions mined in the new chain need to be removed from the pool
                     mined_transactions: new_blocks.transaction_hashes().collect(),
+                    reorg: true,
                 };
                 pool.on_canonical_state_change(update);
 
@@ -427,6 +428,7 @@ pub async fn maintain_transaction_pool<Client, P, St, Tasks>(
                     pending_block_blob_fee,
                     changed_accounts,
                     mined_transactions,
+                    reorg: false,
                 };
                 pool.on_canonical_state_change(update);
 
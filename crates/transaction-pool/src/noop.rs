@@ -13,8 +13,8 @@ use crate::{
     validate::ValidTransaction,
     AllPoolTransactions, AllTransactionsEvents, BestTransactions, BlockInfo, EthPooledTransaction,
     NewTransactionEvent, PoolResult, PoolSize, PoolTransaction, PooledTransactionsElement,
-    PropagatedTransactions, TransactionEvents, TransactionOrigin, TransactionPool,
-    TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
+    PropagatedTransactions, RemovedTransaction, TransactionEvents, TransactionOrigin,
+    TransactionPool, TransactionValidationOutcome, TransactionValidator, ValidPoolTransaction,
 };
 use reth_eth_wire::HandleMempoolData;
 use reth_primitives::{Address, BlobTransactionSidecar, TxHash, U256};
@@ -222,6 +222,14 @@ impl TransactionPool for NoopTransactionPool {
         Default::default()
     }
 
+    fn removal_reason(&self, _tx_hash: &TxHash) -> Option<RemovedTransaction> {
+        None
+    }
+
+    fn removed_transactions_by_sender(&self, _sender: Address) -> Vec<RemovedTransaction> {
+        vec![]
+    }
+
     fn get_blob(&self, _tx_hash: TxHash) -> Result<Option<BlobTransactionSidecar>, BlobStoreError> {
         Ok(None)
     }
@@ -1,5 +1,5 @@
 use crate::{traits::PropagateKind, PoolTransaction, ValidPoolTransaction};
-use reth_primitives::{TxHash, B256};
+use reth_primitives::{Address, TxHash, B256};
 use std::sync::Arc;
 
 #[cfg(feature = "serde")]
@@ -88,3 +88,42 @@ impl TransactionEvent {
         )
     }
 }
+
+/// The reason a transaction was removed from the pool.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum TransactionRemovalReason {
+    /// Transaction has been included in the block belonging to this hash.
+    Mined(B256),
+    /// Transaction has been replaced by the transaction belonging to the hash.
+    ///
+    /// E.g. same (sender + nonce) pair
+    Replaced(TxHash),
+    /// Transaction was evicted because the pool exceeded its configured size limits.
+    Evicted,
+    /// Transaction became outdated after a state change, e.g. its nonce is now below the on-chain
+    /// nonce of the sender.
+    Stale,
+    /// Transaction became outdated after a reorg of the canonical chain.
+    Reorg,
+    /// Transaction failed validation and was never inserted.
+    Invalid,
+    /// Transaction was explicitly removed from the pool.
+    Removed,
+}
+
+/// A record of a transaction that left the pool.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RemovedTransaction {
+    /// The hash of the removed transaction.
+    pub hash: TxHash,
+    /// The sender of the removed transaction.
+    pub sender: Address,
+    /// The nonce of the removed transaction.
+    pub nonce: u64,
+    /// Why the transaction was removed.
+    pub reason: TransactionRemovalReason,
+    /// Unix timestamp (in seconds) at which the transaction was removed.
+    pub timestamp: u64,
+}
@@ -0,0 +1,170 @@
+//! Bounded history of transactions that were removed from the pool.
+
+use crate::{
+    pool::events::{RemovedTransaction, TransactionRemovalReason},
+    PoolTransaction,
+};
+use reth_primitives::{Address, TxHash};
+use std::{
+    collections::VecDeque,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Maximum number of records kept for transactions that failed validation.
+const MAX_INVALID_RECORDS: usize = 128;
+
+/// Keeps the most recent [RemovedTransaction] records.
+///
+/// Once the configured capacity is reached, the oldest record is evicted for every new one. A
+/// capacity of `0` disables tracking entirely.
+///
+/// Transactions that failed validation were never inserted into the pool, so they are kept in a
+/// separate, smaller bucket. Otherwise anyone could flush the history of the pool by submitting
+/// invalid transactions.
+#[derive(Debug, Default)]
+pub(crate) struct RemovalHistory {
+    /// Records of transactions that were removed from the pool, oldest first.
+    removed: RecordBuffer,
+    /// Records of transactions that failed validation, oldest first.
+    invalid: RecordBuffer,
+}
+
+impl RemovalHistory {
+    /// Creates a new history that retains at most `capacity` records of removed transactions.
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            removed: RecordBuffer::new(capacity),
+            invalid: RecordBuffer::new(capacity.min(MAX_INVALID_RECORDS)),
+        }
+    }
+
+    /// Records that the given transaction was removed for the given reason.
+    pub(crate) fn record<T: PoolTransaction>(
+        &mut self,
+        transaction: &T,
+        reason: TransactionRemovalReason,
+    ) {
+        let timestamp =
+            SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default();
+        let record = RemovedTransaction {
+            hash: *transaction.hash(),
+            sender: transaction.sender(),
+            nonce: transaction.nonce(),
+            reason,
+            timestamp,
+        };
+
+        if reason == TransactionRemovalReason::Invalid {
+            self.invalid.push(record)
+        } else {
+            self.removed.push(record)
+        }
+    }
+
+    /// Returns the most recent record for the given transaction hash.
+    pub(crate) fn get(&self, hash: &TxHash) -> Option<RemovedTransaction> {
+        self.removed.get(hash).or_else(|| self.invalid.get(hash))
+    }
+
+    /// Returns all retained records of the given sender, oldest first.
+    pub(crate) fn by_sender(&self, sender: Address) -> Vec<RemovedTransaction> {
+        let mut records = self.removed.by_sender(sender);
+        records.extend(self.invalid.by_sender(sender));
+        // stable, so records with the same timestamp keep their order
+        records.sort_by_key(|record| record.timestamp);
+        records
+    }
+
+    /// Returns the number of retained records of removed transactions.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.removed.records.len()
+    }
+}
+
+/// A ring buffer of [RemovedTransaction] records.
+#[derive(Debug, Default)]
+struct RecordBuffer {
+    /// Maximum number of records to keep.
+    capacity: usize,
+    /// All retained records, oldest first.
+    records: VecDeque<RemovedTransaction>,
+}
+
+impl RecordBuffer {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, records: VecDeque::with_capacity(capacity) }
+    }
+
+    fn push(&mut self, record: RemovedTransaction) {
+        if self.capacity == 0 {
+            return
+        }
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(record);
+    }
+
+    fn get(&self, hash: &TxHash) -> Option<RemovedTransaction> {
+        self.records.iter().rev().find(|record| record.hash == *hash).cloned()
+    }
+
+    fn by_sender(&self, sender: Address) -> Vec<RemovedTransaction> {
+        self.records.iter().filter(|record| record.sender == sender).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::MockTransaction;
+
+    #[test]
+    fn evicts_oldest_record() {
+        let mut history = RemovalHistory::new(2);
+        let first = MockTransaction::eip1559();
+        let second = first.next();
+        let third = second.next();
+
+        history.record(&first, TransactionRemovalReason::Evicted);
+        history.record(&second, TransactionRemovalReason::Stale);
+        history.record(&third, TransactionRemovalReason::Replaced(*first.hash()));
+
+        assert_eq!(history.len(), 2);
+        assert!(history.get(first.hash()).is_none());
+        assert_eq!(history.get(second.hash()).unwrap().reason, TransactionRemovalReason::Stale);
+
+        let by_sender = history.by_sender(first.sender());
+        assert_eq!(by_sender.len(), 2);
+        assert_eq!(by_sender[0].nonce, second.nonce());
+        assert_eq!(by_sender[1].nonce, third.nonce());
+    }
+
+    #[test]
+    fn invalid_transactions_do_not_evict_removed() {
+        let mut history = RemovalHistory::new(2);
+        let removed = MockTransaction::eip1559();
+        history.record(&removed, TransactionRemovalReason::Evicted);
+
+        let mut invalid = removed.next();
+        for _ in 0..MAX_INVALID_RECORDS * 2 {
+            invalid = invalid.next();
+            history.record(&invalid, TransactionRemovalReason::Invalid);
+        }
+
+        assert_eq!(history.len(), 1);
+        assert_eq!(history.get(removed.hash()).unwrap().reason, TransactionRemovalReason::Evicted);
+        assert_eq!(history.get(invalid.hash()).unwrap().reason, TransactionRemovalReason::Invalid);
+        // the invalid bucket is bounded by the capacity of the history
+        assert_eq!(history.by_sender(removed.sender()).len(), 3);
+    }
+
+    #[test]
+    fn zero_capacity_disables_history() {
+        let mut history = RemovalHistory::new(0);
+        let tx = MockTransaction::eip1559();
+        history.record(&tx, TransactionRemovalReason::Invalid);
+        assert!(history.get(tx.hash()).is_none());
+    }
+}
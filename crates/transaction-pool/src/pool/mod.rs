@@ -69,6 +69,7 @@ use crate::{
     error::{PoolError, PoolErrorKind, PoolResult},
     identifier::{SenderId, SenderIdentifiers, TransactionId},
     pool::{
+        history::RemovalHistory,
         listener::PoolEventBroadcast,
         state::SubPool,
         txpool::{SenderInfo, TxPool},
@@ -105,13 +106,16 @@ use crate::{
 };
 pub use best::BestTransactionFilter;
 pub use blob::{blob_tx_priority, fee_delta};
-pub use events::{FullTransactionEvent, TransactionEvent};
+pub use events::{
+    FullTransactionEvent, RemovedTransaction, TransactionEvent, TransactionRemovalReason,
+};
 pub use listener::{AllTransactionsEvents, TransactionEvents};
 pub use parked::{BasefeeOrd, ParkedOrd, ParkedPool, QueuedOrd};
 pub use pending::PendingPool;
 
 mod best;
 mod blob;
+mod history;
 mod listener;
 mod parked;
 pub(crate) mod pending;
@@ -141,6 +145,8 @@ where
     config: PoolConfig,
     /// Manages listeners for transaction state change events.
     event_listener: RwLock<PoolEventBroadcast<T::Transaction>>,
+    /// Bounded record of recently removed transactions and why they were removed.
+    removal_history: RwLock<RemovalHistory>,
     /// Listeners for new _full_ pending transactions.
     pending_transaction_listener: Mutex<Vec<PendingTransactionHashListener>>,
     /// Listeners for new transactions added to the pool.
@@ -165,6 +171,7 @@ where
             identifiers: Default::default(),
             validator,
             event_listener: Default::default(),
+            removal_history: RwLock::new(RemovalHistory::new(config.max_removal_history)),
             pool: RwLock::new(TxPool::new(ordering, config.clone())),
             pending_transaction_listener: Default::default(),
             transaction_listener: Default::default(),
@@ -371,7 +378,8 @@ where
         trace!(target: "txpool", ?update, "updating pool on canonical state change");
 
         let block_info = update.block_info();
-        let CanonicalStateUpdate { new_tip, changed_accounts, mined_transactions, .. } = update;
+        let CanonicalStateUpdate { new_tip, changed_accounts, mined_transactions, reorg, .. } =
+            update;
         self.validator.on_new_head_block(new_tip);
 
         let changed_senders = self.changed_senders(changed_accounts.into_iter());
@@ -387,7 +395,7 @@ where
         self.delete_discarded_blobs(outcome.discarded.iter());
 
         // notify listeners about updates
-        self.notify_on_new_state(outcome);
+        self.notify_on_new_state(outcome, reorg);
     }
 
     /// Performs account updates on the pool.
@@ -402,6 +410,8 @@ where
         promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
         discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
 
+        self.record_removals(discarded.iter(), TransactionRemovalReason::Stale);
+
         // This deletes outdated blob txs from the blob store, based on the account's nonce. This is
         // called during txpool maintenance when the pool drifted.
         self.delete_discarded_blobs(discarded.iter());
@@ -482,6 +492,7 @@ where
             TransactionValidationOutcome::Invalid(tx, err) => {
                 let mut listener = self.event_listener.write();
                 listener.discarded(tx.hash());
+                self.removal_history.write().record(&tx, TransactionRemovalReason::Invalid);
                 Err(PoolError::new(*tx.hash(), err))
             }
             TransactionValidationOutcome::Error(tx_hash, err) => {
@@ -598,7 +609,13 @@ where
     }
 
     /// Notifies transaction listeners about changes once a block was processed.
-    fn notify_on_new_state(&self, outcome: OnNewCanonicalStateOutcome<T::Transaction>) {
+    ///
+    /// `reorg` is set if the block is the tip of a new canonical chain after a reorg.
+    fn notify_on_new_state(
+        &self,
+        outcome: OnNewCanonicalStateOutcome<T::Transaction>,
+        reorg: bool,
+    ) {
         // notify about promoted pending transactions
         {
             // emit hashes
@@ -614,7 +631,7 @@ where
             })
         }
 
-        let OnNewCanonicalStateOutcome { mined, promoted, discarded, block_hash } = outcome;
+        let OnNewCanonicalStateOutcome { mined, pruned, promoted, discarded, block_hash } = outcome;
 
         // broadcast specific transaction events
         let mut listener = self.event_listener.write();
@@ -622,6 +639,11 @@ where
         mined.iter().for_each(|tx| listener.mined(tx, block_hash));
         promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
         discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
+
+        self.record_removals(pruned.iter(), TransactionRemovalReason::Mined(block_hash));
+        let reason =
+            if reorg { TransactionRemovalReason::Reorg } else { TransactionRemovalReason::Stale };
+        self.record_removals(discarded.iter(), reason);
     }
 
     /// Fire events for the newly added transaction if there are any.
@@ -635,16 +657,51 @@ where
                 listener.pending(transaction.hash(), replaced.clone());
                 promoted.iter().for_each(|tx| listener.pending(tx.hash(), None));
                 discarded.iter().for_each(|tx| listener.discarded(tx.hash()));
+
+                self.record_removals(discarded.iter(), TransactionRemovalReason::Stale);
+                if let Some(replaced) = replaced {
+                    self.record_removals(
+                        std::iter::once(replaced),
+                        TransactionRemovalReason::Replaced(*transaction.hash()),
+                    );
+                }
             }
             AddedTransaction::Parked { transaction, replaced, .. } => {
                 listener.queued(transaction.hash());
                 if let Some(replaced) = replaced {
                     listener.replaced(replaced.clone(), *transaction.hash());
+                    self.record_removals(
+                        std::iter::once(replaced),
+                        TransactionRemovalReason::Replaced(*transaction.hash()),
+                    );
                 }
             }
         }
     }
 
+    /// Records the given transactions in the removal history.
+    fn record_removals<'a>(
+        &'a self,
+        transactions: impl IntoIterator<Item = &'a Arc<ValidPoolTransaction<T::Transaction>>>,
+        reason: TransactionRemovalReason,
+    ) {
+        let mut history = self.removal_history.write();
+        transactions.into_iter().for_each(|tx| history.record(&tx.transaction, reason));
+    }
+
+    /// Returns the most recent removal record of the given transaction, if retained.
+    pub(crate) fn removal_reason(&self, tx_hash: &TxHash) -> Option<RemovedTransaction> {
+        self.removal_history.read().get(tx_hash)
+    }
+
+    /// Returns all retained removal records of transactions sent by the given address.
+    pub(crate) fn removed_transactions_by_sender(
+        &self,
+        sender: Address,
+    ) -> Vec<RemovedTransaction> {
+        self.removal_history.read().by_sender(sender)
+    }
+
     /// Returns an iterator that yields transactions that are ready to be included in the block.
     pub(crate) fn best_transactions(&self) -> BestTransactions<T> {
         self.get_pool_data().best_transactions()
@@ -693,6 +750,8 @@ where
 
         removed.iter().for_each(|tx| listener.discarded(tx.hash()));
 
+        self.record_removals(removed.iter(), TransactionRemovalReason::Removed);
+
         removed
     }
 
@@ -781,6 +840,8 @@ where
         // delete any blobs associated with discarded blob transactions
         self.delete_discarded_blobs(discarded.iter());
 
+        self.record_removals(discarded.iter(), TransactionRemovalReason::Evicted);
+
         // then collect into tx hashes
         discarded.into_iter().map(|tx| *tx.hash()).collect()
     }
@@ -1095,6 +1156,8 @@ pub(crate) struct OnNewCanonicalStateOutcome<T: PoolTransaction> {
     pub(crate) block_hash: B256,
     /// All mined transactions.
     pub(crate) mined: Vec<TxHash>,
+    /// Mined transactions that were removed from the pool.
+    pub(crate) pruned: Vec<Arc<ValidPoolTransaction<T>>>,
     /// Transactions promoted to the pending pool.
     pub(crate) promoted: Vec<Arc<ValidPoolTransaction<T>>>,
     /// transaction that were discarded during the update
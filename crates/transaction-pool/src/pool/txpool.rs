@@ -413,8 +413,10 @@ impl<T: TransactionOrdering> TxPool<T> {
         self.all_transactions.set_block_info(block_info);
 
         // Remove all transaction that were included in the block
+        let mut pruned = Vec::new();
         for tx_hash in mined_transactions.iter() {
-            if self.prune_transaction_by_hash(tx_hash).is_some() {
+            if let Some(tx) = self.prune_transaction_by_hash(tx_hash) {
+                pruned.push(tx);
                 // Update removed transactions metric
                 self.metrics.removed_transactions.increment(1);
             }
@@ -424,7 +426,13 @@ impl<T: TransactionOrdering> TxPool<T> {
 
         self.metrics.performed_state_updates.increment(1);
 
-        OnNewCanonicalStateOutcome { block_hash, mined: mined_transactions, promoted, discarded }
+        OnNewCanonicalStateOutcome {
+            block_hash,
+            mined: mined_transactions,
+            pruned,
+            promoted,
+            discarded,
+        }
     }
 
     /// Update sub-pools size metrics.
@@ -3,7 +3,7 @@
 use crate::{
     blobstore::BlobStoreError,
     error::PoolResult,
-    pool::{state::SubPool, BestTransactionFilter, TransactionEvents},
+    pool::{state::SubPool, BestTransactionFilter, RemovedTransaction, TransactionEvents},
     validate::ValidPoolTransaction,
     AllTransactionsEvents,
 };
@@ -360,6 +360,17 @@ pub trait TransactionPool: Send + Sync + Clone {
     /// Returns a set of all senders of transactions in the pool
     fn unique_senders(&self) -> HashSet<Address>;
 
+    /// Returns the record of why the transaction with the given hash left the pool.
+    ///
+    /// The pool only keeps a bounded history of removed transactions, see
+    /// [PoolConfig::max_removal_history](crate::PoolConfig::max_removal_history), so this returns
+    /// `None` for transactions that were never seen or whose record was already evicted.
+    fn removal_reason(&self, tx_hash: &TxHash) -> Option<RemovedTransaction>;
+
+    /// Returns all retained removal records of transactions sent by the given address, oldest
+    /// first.
+    fn removed_transactions_by_sender(&self, sender: Address) -> Vec<RemovedTransaction>;
+
     /// Returns the [BlobTransactionSidecar] for the given transaction hash if it exists in the blob
     /// store.
     fn get_blob(&self, tx_hash: TxHash) -> Result<Option<BlobTransactionSidecar>, BlobStoreError>;
@@ -595,6 +606,8 @@ pub struct CanonicalStateUpdate<'a> {
     pub changed_accounts: Vec<ChangedAccount>,
     /// All mined transactions in the block range.
     pub mined_transactions: Vec<B256>,
+    /// Whether the update is the result of a reorg of the canonical chain.
+    pub reorg: bool,
 }
 
 impl<'a> CanonicalStateUpdate<'a> {
@@ -633,6 +646,7 @@ impl fmt::Display for CanonicalStateUpdate<'_> {
             .field("pending_block_blob_fee", &self.pending_block_blob_fee)
             .field("changed_accounts", &self.changed_accounts.len())
             .field("mined_transactions", &self.mined_transactions.len())
+            .field("reorg", &self.reorg)
             .finish()
     }
 }
@@ -1,9 +1,11 @@
 use assert_matches::assert_matches;
+use reth_primitives::{SealedBlock, TxHash, U256};
 use reth_transaction_pool::{
     noop::MockTransactionValidator,
-    test_utils::{MockTransactionFactory, TestPoolBuilder},
-    FullTransactionEvent, TransactionEvent, TransactionListenerKind, TransactionOrigin,
-    TransactionPool,
+    test_utils::{MockTransactionFactory, MockValidTx, TestPoolBuilder},
+    CanonicalStateUpdate, ChangedAccount, FullTransactionEvent, PoolConfig, SubPoolLimit,
+    TransactionEvent, TransactionListenerKind, TransactionOrigin, TransactionPool,
+    TransactionRemovalReason,
 };
 use std::{future::poll_fn, task::Poll};
 use tokio_stream::StreamExt;
@@ -89,3 +91,128 @@ async fn txpool_listener_new_propagate_only() {
     })
     .await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_removal_reason_replaced() {
+    let txpool = TestPoolBuilder::default();
+    let mut mock_tx_factory = MockTransactionFactory::default();
+    let transaction = mock_tx_factory.create_eip1559();
+    let replacement = transaction.transaction.inc_price_by(10).rng_hash();
+
+    let result =
+        txpool.add_transaction(TransactionOrigin::External, transaction.transaction.clone()).await;
+    assert!(result.is_ok());
+    assert!(txpool.removal_reason(transaction.hash()).is_none());
+
+    let result = txpool.add_transaction(TransactionOrigin::External, replacement.clone()).await;
+    assert!(result.is_ok());
+
+    let removed = txpool.removal_reason(transaction.hash()).unwrap();
+    assert_eq!(removed.reason, TransactionRemovalReason::Replaced(replacement.get_hash()));
+
+    let history = txpool.removed_transactions_by_sender(transaction.sender());
+    assert_eq!(history, vec![removed]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_removal_reason_evicted() {
+    let config = PoolConfig {
+        pending_limit: SubPoolLimit { max_txs: 1, max_size: usize::MAX },
+        ..Default::default()
+    };
+    let txpool = TestPoolBuilder::default().with_config(config);
+    let mut mock_tx_factory = MockTransactionFactory::default();
+    let first = mock_tx_factory.create_eip1559();
+    let second = mock_tx_factory.create_eip1559();
+
+    let result =
+        txpool.add_transaction(TransactionOrigin::External, first.transaction.clone()).await;
+    assert!(result.is_ok());
+    // either of the transactions is evicted to enforce the limit, possibly the new one
+    let _ = txpool.add_transaction(TransactionOrigin::External, second.transaction.clone()).await;
+
+    let removed: Vec<_> =
+        [&first, &second].iter().filter_map(|tx| txpool.removal_reason(tx.hash())).collect();
+    assert_eq!(removed.len(), 1);
+    assert_eq!(removed[0].reason, TransactionRemovalReason::Evicted);
+    assert!(!txpool.contains(&removed[0].hash));
+}
+
+/// Returns a canonical state update that sets the nonce of the sender of the transaction past it.
+fn executed_update<'a>(
+    tip: &'a SealedBlock,
+    transaction: &MockValidTx,
+    mined_transactions: Vec<TxHash>,
+    reorg: bool,
+) -> CanonicalStateUpdate<'a> {
+    CanonicalStateUpdate {
+        new_tip: tip,
+        pending_block_base_fee: 0,
+        pending_block_blob_fee: None,
+        changed_accounts: vec![ChangedAccount {
+            address: transaction.sender(),
+            nonce: transaction.nonce() + 1,
+            balance: U256::MAX,
+        }],
+        mined_transactions,
+        reorg,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_removal_reason_mined() {
+    let txpool = TestPoolBuilder::default();
+    let mut mock_tx_factory = MockTransactionFactory::default();
+    let transaction = mock_tx_factory.create_eip1559();
+
+    let result =
+        txpool.add_transaction(TransactionOrigin::External, transaction.transaction.clone()).await;
+    assert!(result.is_ok());
+
+    let tip = SealedBlock::default();
+    txpool.on_canonical_state_change(executed_update(
+        &tip,
+        &transaction,
+        vec![*transaction.hash()],
+        false,
+    ));
+
+    let removed = txpool.removal_reason(transaction.hash()).unwrap();
+    assert_eq!(removed.reason, TransactionRemovalReason::Mined(tip.hash()));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_removal_reason_stale() {
+    let txpool = TestPoolBuilder::default();
+    let mut mock_tx_factory = MockTransactionFactory::default();
+    let transaction = mock_tx_factory.create_eip1559();
+
+    let result =
+        txpool.add_transaction(TransactionOrigin::External, transaction.transaction.clone()).await;
+    assert!(result.is_ok());
+
+    // the nonce of the sender moved past the transaction without it being mined
+    let tip = SealedBlock::default();
+    txpool.on_canonical_state_change(executed_update(&tip, &transaction, Vec::new(), false));
+
+    let removed = txpool.removal_reason(transaction.hash()).unwrap();
+    assert_eq!(removed.reason, TransactionRemovalReason::Stale);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn txpool_removal_reason_reorg() {
+    let txpool = TestPoolBuilder::default();
+    let mut mock_tx_factory = MockTransactionFactory::default();
+    let transaction = mock_tx_factory.create_eip1559();
+
+    let result =
+        txpool.add_transaction(TransactionOrigin::External, transaction.transaction.clone()).await;
+    assert!(result.is_ok());
+
+    // the new canonical chain moved the nonce of the sender past the transaction
+    let tip = SealedBlock::default();
+    txpool.on_canonical_state_change(executed_update(&tip, &transaction, Vec::new(), true));
+
+    let removed = txpool.removal_reason(transaction.hash()).unwrap();
+    assert_eq!(removed.reason, TransactionRemovalReason::Reorg);
+}
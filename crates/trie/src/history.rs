@@ -0,0 +1,790 @@
+use crate::{
+    prefix_set::{PrefixSetMut, TriePrefixSets},
+    updates::TrieUpdates,
+    StateRoot,
+};
+use reth_db::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO},
+    models::{
+        sharded_key::NUM_OF_INDICES_IN_SHARD, storage_trie_sharded_key::StorageTrieShardedKey,
+        AccountBeforeTx, BlockNumberAddress, BlockNumberHashedAddress, ShardedKey,
+        StorageKeyPreimage,
+    },
+    table::{Decode, Decompress, Table},
+    tables,
+    transaction::{DbTx, DbTxMut},
+    BlockNumberList, DatabaseError,
+};
+use reth_etl::{Collector, EtlIter};
+use reth_interfaces::{db::DatabaseErrorInfo, trie::StateRootError};
+use reth_primitives::{
+    keccak256,
+    trie::{Nibbles, TrieChangeSetEntry},
+    Account, Address, BlockNumber, PruneCheckpoint, PruneMode, PruneSegment, StorageEntry, B256,
+    U256,
+};
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    ops::RangeInclusive,
+    path::PathBuf,
+};
+
+/// The default size in bytes of the state changes held in memory by [TrieHistory::write_blocks].
+pub const DEFAULT_ETL_FILE_SIZE: usize = 500 * 1024 * 1024;
+
+/// Access to the trie changesets, the trie nodes as they were before each block changed them.
+///
+/// The changesets are kept for every block from the first recorded one up to the current trie
+/// state, so that the trie nodes at the start of any of these blocks can be read without
+/// recomputing them, see
+/// [HistoricalTrieCursorFactory](crate::trie_cursor::HistoricalTrieCursorFactory). The blocks that
+/// changed each node are indexed in [tables::AccountsTrieHistory] and
+/// [tables::StoragesTrieHistory]. The preimages of the hashed keys changed by the blocks are kept
+/// in [tables::HashedAccountPreimages] and [tables::HashedStoragePreimages], so that the leaves at
+/// these blocks can be read through the state history indices.
+///
+/// The first block is recorded as the [PruneSegment::TrieHistory] checkpoint. Writing the trie
+/// without recording the changesets breaks the history, so once recorded they must be recorded for
+/// every block until the history is removed with [TrieHistory::clear].
+#[derive(Debug)]
+pub struct TrieHistory<'a, TX> {
+    /// A reference to the database transaction.
+    tx: &'a TX,
+    /// The maximum size in bytes of the state changes held in memory by
+    /// [TrieHistory::write_blocks] before being flushed to a temporary file.
+    etl_file_size: usize,
+    /// The directory of the temporary files of [TrieHistory::write_blocks].
+    etl_dir: Option<PathBuf>,
+}
+
+impl<'a, TX> TrieHistory<'a, TX> {
+    /// Create a new [TrieHistory] instance.
+    pub fn new(tx: &'a TX) -> Self {
+        Self { tx, etl_file_size: DEFAULT_ETL_FILE_SIZE, etl_dir: None }
+    }
+
+    /// Set the size and directory of the temporary files used by [TrieHistory::write_blocks].
+    pub fn with_etl_config(mut self, file_size: usize, dir: Option<PathBuf>) -> Self {
+        self.etl_file_size = file_size;
+        self.etl_dir = dir;
+        self
+    }
+}
+
+impl<'a, TX: DbTx> TrieHistory<'a, TX> {
+    /// Returns the first block of the trie changesets, if any were recorded.
+    pub fn first_block(&self) -> Result<Option<BlockNumber>, DatabaseError> {
+        Ok(self
+            .tx
+            .get::<tables::PruneCheckpoints>(PruneSegment::TrieHistory)?
+            .map(|checkpoint| checkpoint.block_number.map_or(0, |block_number| block_number + 1)))
+    }
+
+    /// Returns `true` if the trie nodes at the start of the given block can be read from the trie
+    /// changesets.
+    pub fn is_available(&self, block_number: BlockNumber) -> Result<bool, DatabaseError> {
+        Ok(self.first_block()?.is_some_and(|first_block| first_block <= block_number))
+    }
+}
+
+impl<'a, TX: DbTx + DbTxMut> TrieHistory<'a, TX> {
+    /// Records the trie changesets of the block and writes its trie updates.
+    ///
+    /// The trie in the database must be at the end of the previous block, and the state changesets
+    /// of the block must be written.
+    pub fn write_block_updates(
+        &self,
+        block_number: BlockNumber,
+        updates: TrieUpdates,
+    ) -> Result<(), DatabaseError> {
+        if self.first_block()?.is_none() {
+            self.set_first_block(block_number)?;
+        }
+        updates.write_changesets(self.tx, block_number)?;
+        self.write_history_indices(block_number)?;
+        self.write_preimages(block_number)?;
+        updates.flush(self.tx)
+    }
+
+    /// Computes and writes the trie of every block in the range one block at a time, recording
+    /// the trie changesets of each block.
+    ///
+    /// The hashed state and the state changesets must be written up to the end of the range, and
+    /// the trie in the database must be at the end of the block before the range. The hashed state
+    /// is temporarily reverted to the start of the range and then re-applied block by block. The
+    /// changes of the range are buffered in temporary files, see [TrieHistory::with_etl_config].
+    ///
+    /// Returns the state root of each block.
+    pub fn write_blocks(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<Vec<(BlockNumber, B256)>, StateRootError> {
+        let mut changes = HashedStateChanges::new(self.etl_file_size, self.etl_dir.clone());
+        changes.revert(self.tx, range.clone())?;
+        let mut replay = changes.replay()?;
+
+        let mut state_roots = Vec::new();
+        for block_number in range {
+            let prefix_sets = replay.apply_block(self.tx, block_number)?;
+            let (state_root, updates) =
+                StateRoot::from_tx(self.tx).with_prefix_sets(prefix_sets).root_with_updates()?;
+            self.write_block_updates(block_number, updates)?;
+            state_roots.push((block_number, state_root));
+        }
+
+        Ok(state_roots)
+    }
+
+    /// Removes the trie changesets of the blocks after the given one and their history indices.
+    ///
+    /// The trie itself is unwound separately. The preimages are kept, they stay valid.
+    pub fn unwind(&self, unwind_to: BlockNumber) -> Result<(), DatabaseError> {
+        let Some(first_block) = self.first_block()? else { return Ok(()) };
+
+        let mut account_paths = BTreeSet::new();
+        let mut account_changeset_cursor =
+            self.tx.cursor_dup_write::<tables::AccountsTrieChangeSets>()?;
+        let mut reverse_walker = account_changeset_cursor.walk_back(None)?;
+        while let Some((block_number, entry)) = reverse_walker.next().transpose()? {
+            if block_number <= unwind_to {
+                break
+            }
+            account_paths.insert(entry.nibbles);
+            reverse_walker.delete_current()?;
+        }
+
+        let mut storage_paths = BTreeSet::new();
+        let mut storage_changeset_cursor =
+            self.tx.cursor_dup_write::<tables::StoragesTrieChangeSets>()?;
+        let mut reverse_walker = storage_changeset_cursor.walk_back(None)?;
+        while let Some((key, entry)) = reverse_walker.next().transpose()? {
+            if key.block_number() <= unwind_to {
+                break
+            }
+            storage_paths.insert((key.hashed_address(), entry.nibbles));
+            reverse_walker.delete_current()?;
+        }
+
+        for nibbles in account_paths {
+            unwind_history_index::<tables::AccountsTrieHistory, _>(
+                self.tx,
+                |highest_block_number| ShardedKey::new(nibbles.clone(), highest_block_number),
+                unwind_to,
+            )?;
+        }
+        for (hashed_address, nibbles) in storage_paths {
+            unwind_history_index::<tables::StoragesTrieHistory, _>(
+                self.tx,
+                |highest_block_number| {
+                    StorageTrieShardedKey::new(
+                        hashed_address,
+                        nibbles.clone(),
+                        highest_block_number,
+                    )
+                },
+                unwind_to,
+            )?;
+        }
+
+        // The trie at the end of the unwind block is the current one, so it stays available.
+        if first_block > unwind_to + 1 {
+            self.set_first_block(unwind_to + 1)?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes all trie changesets, their history indices and the preimages.
+    pub fn clear(&self) -> Result<(), DatabaseError> {
+        if self.first_block()?.is_some() {
+            self.tx.clear::<tables::AccountsTrieChangeSets>()?;
+            self.tx.clear::<tables::StoragesTrieChangeSets>()?;
+            self.tx.clear::<tables::AccountsTrieHistory>()?;
+            self.tx.clear::<tables::StoragesTrieHistory>()?;
+            self.tx.clear::<tables::HashedAccountPreimages>()?;
+            self.tx.clear::<tables::HashedStoragePreimages>()?;
+            self.tx.delete::<tables::PruneCheckpoints>(PruneSegment::TrieHistory, None)?;
+        }
+        Ok(())
+    }
+
+    fn set_first_block(&self, block_number: BlockNumber) -> Result<(), DatabaseError> {
+        self.tx.put::<tables::PruneCheckpoints>(
+            PruneSegment::TrieHistory,
+            PruneCheckpoint {
+                block_number: block_number.checked_sub(1),
+                tx_number: None,
+                prune_mode: PruneMode::Before(block_number),
+            },
+        )
+    }
+
+    /// Appends the block to the history indices of the trie nodes in its trie changesets.
+    fn write_history_indices(&self, block_number: BlockNumber) -> Result<(), DatabaseError> {
+        let mut account_changeset_cursor =
+            self.tx.cursor_dup_read::<tables::AccountsTrieChangeSets>()?;
+        for entry in account_changeset_cursor.walk_dup(Some(block_number), None)? {
+            let (_, TrieChangeSetEntry { nibbles, .. }) = entry?;
+            append_history_index::<tables::AccountsTrieHistory, _>(
+                self.tx,
+                |highest_block_number| ShardedKey::new(nibbles.clone(), highest_block_number),
+                block_number,
+            )?;
+        }
+
+        let mut storage_changeset_cursor =
+            self.tx.cursor_dup_read::<tables::StoragesTrieChangeSets>()?;
+        for entry in storage_changeset_cursor
+            .walk_range(BlockNumberHashedAddress::range(block_number..=block_number))?
+        {
+            let (key, TrieChangeSetEntry { nibbles, .. }) = entry?;
+            append_history_index::<tables::StoragesTrieHistory, _>(
+                self.tx,
+                |highest_block_number| {
+                    StorageTrieShardedKey::new(
+                        key.hashed_address(),
+                        nibbles.clone(),
+                        highest_block_number,
+                    )
+                },
+                block_number,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Records the preimages of the hashed keys in the state changesets of the block.
+    fn write_preimages(&self, block_number: BlockNumber) -> Result<(), DatabaseError> {
+        let mut account_preimage_cursor =
+            self.tx.cursor_write::<tables::HashedAccountPreimages>()?;
+        let mut account_changeset_cursor =
+            self.tx.cursor_dup_read::<tables::AccountChangeSets>()?;
+        for entry in account_changeset_cursor.walk_dup(Some(block_number), None)? {
+            let (_, AccountBeforeTx { address, .. }) = entry?;
+            account_preimage_cursor.upsert(keccak256(address), address)?;
+        }
+
+        let mut storage_preimage_cursor =
+            self.tx.cursor_dup_write::<tables::HashedStoragePreimages>()?;
+        let mut storage_changeset_cursor = self.tx.cursor_read::<tables::StorageChangeSets>()?;
+        for entry in storage_changeset_cursor
+            .walk_range(BlockNumberAddress::range(block_number..=block_number))?
+        {
+            let (BlockNumberAddress((_, address)), StorageEntry { key, .. }) = entry?;
+            let hashed_address = keccak256(address);
+            account_preimage_cursor.upsert(hashed_address, address)?;
+
+            let hashed_key = keccak256(key);
+            if storage_preimage_cursor
+                .seek_by_key_subkey(hashed_address, hashed_key)?
+                .filter(|preimage| preimage.hashed_key == hashed_key)
+                .is_none()
+            {
+                storage_preimage_cursor
+                    .upsert(hashed_address, StorageKeyPreimage { hashed_key, key })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Appends the block to the last shard of a history index, starting a new shard if the last one
+/// is full.
+fn append_history_index<T, TX>(
+    tx: &TX,
+    sharded_key: impl Fn(BlockNumber) -> T::Key,
+    block_number: BlockNumber,
+) -> Result<(), DatabaseError>
+where
+    T: Table<Value = BlockNumberList>,
+    TX: DbTx + DbTxMut,
+{
+    let mut blocks = tx
+        .get::<T>(sharded_key(u64::MAX))?
+        .map(|list| list.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    if let Some(&highest_block_number) =
+        blocks.last().filter(|_| blocks.len() >= NUM_OF_INDICES_IN_SHARD)
+    {
+        tx.put::<T>(sharded_key(highest_block_number), BlockNumberList::new_pre_sorted(&blocks))?;
+        blocks.clear();
+    }
+    blocks.push(block_number);
+    tx.put::<T>(sharded_key(u64::MAX), BlockNumberList::new_pre_sorted(blocks))
+}
+
+/// Removes the blocks after the given one from the shards of a history index, keeping the
+/// remaining blocks of the shard they are in as the last shard.
+fn unwind_history_index<T, TX>(
+    tx: &TX,
+    sharded_key: impl Fn(BlockNumber) -> T::Key,
+    unwind_to: BlockNumber,
+) -> Result<(), DatabaseError>
+where
+    T: Table<Value = BlockNumberList>,
+    TX: DbTx + DbTxMut,
+{
+    let mut cursor = tx.cursor_write::<T>()?;
+    let mut item = cursor.seek_exact(sharded_key(u64::MAX))?;
+    while let Some((_, list)) = item {
+        cursor.delete_current()?;
+
+        let blocks = list.iter().take_while(|block| *block <= unwind_to).collect::<Vec<_>>();
+        if !blocks.is_empty() {
+            cursor.upsert(sharded_key(u64::MAX), BlockNumberList::new_pre_sorted(blocks))?;
+            break
+        }
+
+        // All blocks of the shard are unwound, continue with the previous shard of the key. The
+        // shards before the last one are keyed by their highest block.
+        item = cursor.prev()?.filter(|(key, list)| {
+            list.iter()
+                .last()
+                .is_some_and(|highest_block_number| *key == sharded_key(highest_block_number))
+        });
+    }
+    Ok(())
+}
+
+/// The hashed state changes of a block range, used to replay them one block at a time.
+///
+/// The values after each block are collected in the layout of the state changesets while the
+/// hashed state is reverted to the start of the range. They are buffered in temporary files, so
+/// the memory used doesn't grow with the range.
+#[derive(Debug)]
+struct HashedStateChanges {
+    /// The accounts after each block, keyed like [tables::AccountChangeSets].
+    accounts: Collector<BlockNumber, AccountBeforeTx>,
+    /// The storage slots after each block, keyed like [tables::StorageChangeSets].
+    storages: Collector<BlockNumberAddress, StorageEntry>,
+}
+
+impl HashedStateChanges {
+    fn new(etl_file_size: usize, etl_dir: Option<PathBuf>) -> Self {
+        Self {
+            accounts: Collector::new(etl_file_size / 2, etl_dir.clone()),
+            storages: Collector::new(etl_file_size / 2, etl_dir),
+        }
+    }
+
+    /// Writes the values before the block range to the hashed state, collecting the values after
+    /// each block of the range.
+    ///
+    /// The state changesets are walked back from the last block of the range, so the value of a
+    /// changed key in the hashed state is the one after the block of the changeset entry.
+    fn revert<TX: DbTx + DbTxMut>(
+        &mut self,
+        tx: &TX,
+        range: RangeInclusive<BlockNumber>,
+    ) -> Result<(), DatabaseError> {
+        let (first_block, last_block) = range.into_inner();
+
+        let mut hashed_account_cursor = tx.cursor_write::<tables::HashedAccounts>()?;
+        let mut account_changeset_cursor = tx.cursor_read::<tables::AccountChangeSets>()?;
+        let mut entry = match account_changeset_cursor.seek(last_block + 1)? {
+            Some(_) => account_changeset_cursor.prev()?,
+            None => account_changeset_cursor.last()?,
+        };
+        while let Some((block_number, AccountBeforeTx { address, info })) =
+            entry.filter(|(block_number, _)| *block_number >= first_block)
+        {
+            let hashed_address = keccak256(address);
+            let after = hashed_account_cursor.seek_exact(hashed_address)?.map(|e| e.1);
+            self.accounts
+                .insert(block_number, AccountBeforeTx { address, info: after })
+                .map_err(etl_error)?;
+            write_hashed_account(&mut hashed_account_cursor, hashed_address, info)?;
+            entry = account_changeset_cursor.prev()?;
+        }
+
+        let mut hashed_storage_cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+        let mut storage_changeset_cursor = tx.cursor_read::<tables::StorageChangeSets>()?;
+        let mut entry = match storage_changeset_cursor
+            .seek(BlockNumberAddress((last_block + 1, Address::ZERO)))?
+        {
+            Some(_) => storage_changeset_cursor.prev()?,
+            None => storage_changeset_cursor.last()?,
+        };
+        while let Some((key, StorageEntry { key: slot, value })) =
+            entry.filter(|(key, _)| key.block_number() >= first_block)
+        {
+            let (hashed_address, hashed_slot) = (keccak256(key.address()), keccak256(slot));
+            let after = hashed_storage_cursor
+                .seek_by_key_subkey(hashed_address, hashed_slot)?
+                .filter(|entry| entry.key == hashed_slot)
+                .map(|entry| entry.value)
+                .unwrap_or_default();
+            self.storages
+                .insert(key, StorageEntry { key: slot, value: after })
+                .map_err(etl_error)?;
+            write_hashed_storage(&mut hashed_storage_cursor, hashed_address, hashed_slot, value)?;
+            entry = storage_changeset_cursor.prev()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the collected values in block order, to be applied with
+    /// [HashedStateReplay::apply_block].
+    fn replay(&mut self) -> Result<HashedStateReplay<'_>, DatabaseError> {
+        Ok(HashedStateReplay {
+            accounts: self.accounts.iter().map_err(etl_error)?,
+            storages: self.storages.iter().map_err(etl_error)?,
+        })
+    }
+}
+
+/// The values after each block collected by [HashedStateChanges::revert], in block order.
+#[derive(Debug)]
+struct HashedStateReplay<'a> {
+    accounts: EtlIter<'a>,
+    storages: EtlIter<'a>,
+}
+
+impl<'a> HashedStateReplay<'a> {
+    /// Writes the values after the block to the hashed state and returns the prefix sets of the
+    /// changed keys.
+    ///
+    /// The blocks must be applied in order.
+    fn apply_block<TX: DbTx + DbTxMut>(
+        &mut self,
+        tx: &TX,
+        block_number: BlockNumber,
+    ) -> Result<TriePrefixSets, DatabaseError> {
+        let mut account_prefix_set = PrefixSetMut::default();
+        let mut storage_prefix_sets = HashMap::<B256, PrefixSetMut>::default();
+        let mut destroyed_accounts = HashSet::default();
+
+        let mut hashed_account_cursor = tx.cursor_write::<tables::HashedAccounts>()?;
+        while let Some((key, _)) = self.accounts.peek() {
+            if BlockNumber::decode(key)? != block_number {
+                break
+            }
+            let Some(entry) = self.accounts.next() else { break };
+            let AccountBeforeTx { address, info: account } =
+                AccountBeforeTx::decompress(entry.map_err(etl_error)?.1)?;
+            let hashed_address = keccak256(address);
+            write_hashed_account(&mut hashed_account_cursor, hashed_address, account)?;
+
+            account_prefix_set.insert(Nibbles::unpack(hashed_address));
+            if account.is_none() {
+                destroyed_accounts.insert(hashed_address);
+            }
+        }
+
+        let mut hashed_storage_cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+        while let Some((key, _)) = self.storages.peek() {
+            if BlockNumberAddress::decode(key)?.block_number() != block_number {
+                break
+            }
+            let Some(entry) = self.storages.next() else { break };
+            let (key, value) = entry.map_err(etl_error)?;
+            let hashed_address = keccak256(BlockNumberAddress::decode(key)?.address());
+            let StorageEntry { key: slot, value } = StorageEntry::decompress(value)?;
+            let hashed_slot = keccak256(slot);
+            write_hashed_storage(&mut hashed_storage_cursor, hashed_address, hashed_slot, value)?;
+
+            account_prefix_set.insert(Nibbles::unpack(hashed_address));
+            storage_prefix_sets
+                .entry(hashed_address)
+                .or_default()
+                .insert(Nibbles::unpack(hashed_slot));
+        }
+
+        Ok(TriePrefixSets {
+            account_prefix_set: account_prefix_set.freeze(),
+            storage_prefix_sets: storage_prefix_sets
+                .into_iter()
+                .map(|(k, v)| (k, v.freeze()))
+                .collect(),
+            destroyed_accounts,
+        })
+    }
+}
+
+/// Converts an error of the temporary files of [HashedStateChanges].
+fn etl_error(err: std::io::Error) -> DatabaseError {
+    DatabaseError::Read(DatabaseErrorInfo { message: err.to_string(), code: 0 })
+}
+
+fn write_hashed_account<C>(
+    cursor: &mut C,
+    hashed_address: B256,
+    account: Option<Account>,
+) -> Result<(), DatabaseError>
+where
+    C: DbCursorRO<tables::HashedAccounts> + DbCursorRW<tables::HashedAccounts>,
+{
+    match account {
+        Some(account) => cursor.upsert(hashed_address, account),
+        None => {
+            if cursor.seek_exact(hashed_address)?.is_some() {
+                cursor.delete_current()?;
+            }
+            Ok(())
+        }
+    }
+}
+
+fn write_hashed_storage<C>(
+    cursor: &mut C,
+    hashed_address: B256,
+    hashed_slot: B256,
+    value: U256,
+) -> Result<(), DatabaseError>
+where
+    C: DbDupCursorRO<tables::HashedStorages> + DbCursorRW<tables::HashedStorages>,
+{
+    if cursor
+        .seek_by_key_subkey(hashed_address, hashed_slot)?
+        .filter(|entry| entry.key == hashed_slot)
+        .is_some()
+    {
+        cursor.delete_current()?;
+    }
+    if value != U256::ZERO {
+        cursor.upsert(hashed_address, StorageEntry { key: hashed_slot, value })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        hashed_cursor::HashedPostStateCursorFactory, proof::Proof, test_utils,
+        trie_cursor::HistoricalTrieCursorFactory, verify::TrieVerifier, HashedPostState,
+    };
+    use reth_primitives::trie::StoredNibblesSubKey;
+    use reth_provider::test_utils::create_test_provider_factory;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn write_blocks_and_read_historical_proofs() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let address = |i: u64| Address::from_word(B256::from(U256::from(i)));
+        let slot = |i: u64| B256::from(U256::from(i));
+        let contract = address(1_000);
+
+        let mut accounts = (0..100)
+            .map(|i| (address(i), Account { nonce: i, ..Default::default() }))
+            .collect::<BTreeMap<_, _>>();
+        accounts.insert(contract, Account::default());
+        let mut storage =
+            (0..100).map(|i| (slot(i), U256::from(i + 1))).collect::<BTreeMap<_, _>>();
+        let state_root = |accounts: &BTreeMap<Address, Account>, storage: &BTreeMap<B256, U256>| {
+            test_utils::state_root(accounts.iter().map(|(address, account)| {
+                let storage = if *address == contract { storage.clone() } else { BTreeMap::new() };
+                (*address, (*account, storage))
+            }))
+        };
+
+        // Write the genesis state and its trie.
+        for (address, account) in &accounts {
+            tx.put::<tables::HashedAccounts>(keccak256(address), *account).unwrap();
+        }
+        for (slot, value) in &storage {
+            tx.put::<tables::HashedStorages>(
+                keccak256(contract),
+                StorageEntry { key: keccak256(slot), value: *value },
+            )
+            .unwrap();
+        }
+        let (genesis_root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        assert_eq!(genesis_root, state_root(&accounts, &storage));
+        updates.flush(tx).unwrap();
+
+        // Change, create and destroy accounts and storage slots in each block and record the
+        // state changesets.
+        let mut state_roots = vec![genesis_root];
+        for block_number in 1..=3u64 {
+            let mut changed = (block_number * 40..block_number * 40 + 20)
+                .map(|i| {
+                    let account = accounts.get(&address(i)).copied().unwrap_or_default();
+                    (address(i), Some(Account { nonce: account.nonce + 1, ..account }))
+                })
+                .collect::<Vec<_>>();
+            changed.push((address(block_number), None));
+            changed.push((contract, Some(Account { nonce: block_number, ..Default::default() })));
+            for (address, account) in changed {
+                let info = match account {
+                    Some(account) => accounts.insert(address, account),
+                    None => accounts.remove(&address),
+                };
+                tx.put::<tables::AccountChangeSets>(
+                    block_number,
+                    AccountBeforeTx { address, info },
+                )
+                .unwrap();
+            }
+
+            for i in block_number * 10..block_number * 10 + 30 {
+                let value =
+                    if i % 7 == 0 { U256::ZERO } else { U256::from(block_number * 1_000 + i) };
+                let previous = if value.is_zero() {
+                    storage.remove(&slot(i))
+                } else {
+                    storage.insert(slot(i), value)
+                };
+                tx.put::<tables::StorageChangeSets>(
+                    BlockNumberAddress((block_number, contract)),
+                    StorageEntry { key: slot(i), value: previous.unwrap_or_default() },
+                )
+                .unwrap();
+            }
+
+            state_roots.push(state_root(&accounts, &storage));
+        }
+
+        // Write the hashed state at the tip.
+        tx.clear::<tables::HashedAccounts>().unwrap();
+        tx.clear::<tables::HashedStorages>().unwrap();
+        for (address, account) in &accounts {
+            tx.put::<tables::HashedAccounts>(keccak256(address), *account).unwrap();
+        }
+        for (slot, value) in &storage {
+            tx.put::<tables::HashedStorages>(
+                keccak256(contract),
+                StorageEntry { key: keccak256(slot), value: *value },
+            )
+            .unwrap();
+        }
+
+        // Flush every collected change to a temporary file.
+        let history = TrieHistory::new(tx).with_etl_config(1, None);
+        assert_eq!(history.first_block().unwrap(), None);
+        assert_eq!(
+            history.write_blocks(1..=3).unwrap(),
+            vec![(1, state_roots[1]), (2, state_roots[2]), (3, state_roots[3])]
+        );
+        assert_eq!(history.first_block().unwrap(), Some(1));
+
+        // The hashed state is back at the tip and the trie matches it.
+        let verification = TrieVerifier::new(tx).verify().unwrap();
+        assert_eq!(verification.state_root, state_roots[3]);
+        assert!(verification.is_consistent());
+
+        // The proofs over the historical trie nodes match the recomputed ones.
+        let slots = [slot(0), slot(10), slot(14), slot(35), slot(500)];
+        for block_number in 1..=3 {
+            assert!(history.is_available(block_number).unwrap());
+            let hashed_state = HashedPostState::from_revert_range(tx, block_number..=3).unwrap();
+            let sorted = hashed_state.clone().into_sorted();
+            let historical_proof = Proof::new(tx)
+                .with_trie_cursor_factory(HistoricalTrieCursorFactory::new(tx, block_number))
+                .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted));
+            for target in [contract, address(1), address(2), address(45), address(99)] {
+                let proof = historical_proof.account_proof(target, &slots).unwrap();
+                assert_eq!(keccak256(&proof.proof[0]), state_roots[block_number as usize - 1]);
+                assert_eq!(proof, hashed_state.account_proof(tx, target, &slots).unwrap());
+            }
+        }
+
+        // The preimages of the changed keys are recorded.
+        assert_eq!(
+            tx.get::<tables::HashedAccountPreimages>(keccak256(address(45))).unwrap(),
+            Some(address(45))
+        );
+        assert_eq!(
+            tx.cursor_dup_read::<tables::HashedStoragePreimages>()
+                .unwrap()
+                .seek_by_key_subkey(keccak256(contract), keccak256(slot(14)))
+                .unwrap(),
+            Some(StorageKeyPreimage { hashed_key: keccak256(slot(14)), key: slot(14) })
+        );
+
+        history.unwind(1).unwrap();
+        assert_eq!(history.first_block().unwrap(), Some(1));
+        let mut account_changeset_cursor =
+            tx.cursor_dup_read::<tables::AccountsTrieChangeSets>().unwrap();
+        assert_eq!(account_changeset_cursor.last().unwrap().map(|(block, _)| block), Some(1));
+        let mut storage_changeset_cursor =
+            tx.cursor_dup_read::<tables::StoragesTrieChangeSets>().unwrap();
+        assert!(storage_changeset_cursor
+            .last()
+            .unwrap()
+            .is_some_and(|(key, _)| key.block_number() == 1));
+
+        // Only the first block is left in the history indices.
+        let account_index = tx
+            .cursor_read::<tables::AccountsTrieHistory>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .map(|entry| {
+                let (key, list) = entry.unwrap();
+                (key.highest_block_number, list.iter().collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        assert!(!account_index.is_empty());
+        assert!(account_index.iter().all(|entry| *entry == (u64::MAX, vec![1])));
+        let storage_index = tx
+            .cursor_read::<tables::StoragesTrieHistory>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .map(|entry| {
+                let (key, list) = entry.unwrap();
+                (key.sharded_key.highest_block_number, list.iter().collect::<Vec<_>>())
+            })
+            .collect::<Vec<_>>();
+        assert!(!storage_index.is_empty());
+        assert!(storage_index.iter().all(|entry| *entry == (u64::MAX, vec![1])));
+
+        history.clear().unwrap();
+        assert_eq!(history.first_block().unwrap(), None);
+        assert!(!history.is_available(3).unwrap());
+        assert_eq!(tx.entries::<tables::AccountsTrieHistory>().unwrap(), 0);
+        assert_eq!(tx.entries::<tables::HashedAccountPreimages>().unwrap(), 0);
+    }
+
+    #[test]
+    fn history_index_shards() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let key = |nibbles: &[u8]| {
+            let nibbles = StoredNibblesSubKey(Nibbles::from_nibbles_unchecked(nibbles));
+            move |highest_block_number| ShardedKey::new(nibbles.clone(), highest_block_number)
+        };
+        let blocks = |sharded_key| {
+            tx.get::<tables::AccountsTrieHistory>(sharded_key)
+                .unwrap()
+                .map(|list| list.iter().collect::<Vec<_>>())
+        };
+
+        // A preceding path is not touched by the unwind of the next one.
+        append_history_index::<tables::AccountsTrieHistory, _>(tx, key(&[0x0]), 5).unwrap();
+
+        let first_block = 10;
+        let last_block = first_block + NUM_OF_INDICES_IN_SHARD as u64;
+        for block_number in first_block..=last_block {
+            append_history_index::<tables::AccountsTrieHistory, _>(tx, key(&[0x1]), block_number)
+                .unwrap();
+        }
+        assert_eq!(
+            blocks(key(&[0x1])(last_block - 1)),
+            Some((first_block..last_block).collect::<Vec<_>>())
+        );
+        assert_eq!(blocks(key(&[0x1])(u64::MAX)), Some(vec![last_block]));
+
+        // Unwinding into the first shard makes it the last one.
+        unwind_history_index::<tables::AccountsTrieHistory, _>(tx, key(&[0x1]), last_block - 2)
+            .unwrap();
+        assert_eq!(blocks(key(&[0x1])(last_block - 1)), None);
+        assert_eq!(
+            blocks(key(&[0x1])(u64::MAX)),
+            Some((first_block..=last_block - 2).collect::<Vec<_>>())
+        );
+
+        unwind_history_index::<tables::AccountsTrieHistory, _>(tx, key(&[0x1]), 5).unwrap();
+        assert_eq!(tx.entries::<tables::AccountsTrieHistory>().unwrap(), 1);
+        assert_eq!(blocks(key(&[0x0])(u64::MAX)), Some(vec![5]));
+    }
+}
@@ -36,6 +36,15 @@ pub use state::*;
 /// Merkle proof generation.
 pub mod proof;
 
+/// Sparse in-memory state trie.
+pub mod sparse;
+
+/// Verification of the stored trie nodes against the hashed state.
+pub mod verify;
+
+/// Historical trie nodes kept in the trie changesets.
+pub mod history;
+
 /// The implementation of the Merkle Patricia Trie.
 mod trie;
 pub use trie::{StateRoot, StorageRoot};
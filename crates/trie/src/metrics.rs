@@ -1,6 +1,7 @@
 use crate::stats::TrieStats;
-use metrics::Histogram;
+use metrics::{Counter, Histogram};
 use reth_metrics::Metrics;
+use std::time::Duration;
 
 /// Wrapper for state root metrics.
 #[derive(Debug)]
@@ -63,3 +64,32 @@ impl TrieType {
         }
     }
 }
+
+/// Metrics for loading the nodes of a sparse trie from the database, see
+/// [DatabaseTrieNodes](crate::sparse::DatabaseTrieNodes).
+#[derive(Clone, Metrics)]
+#[metrics(scope = "trie.sparse")]
+pub struct DatabaseTrieNodesMetrics {
+    /// The number of proofs that are generated to load blinded nodes.
+    proofs: Counter,
+    /// The number of seconds it took to generate a proof of a blinded node.
+    proof_duration_seconds: Histogram,
+    /// The number of nodes loaded with a single proof.
+    proof_nodes: Histogram,
+    /// The number of branch nodes read from the trie tables.
+    branch_node_reads: Counter,
+}
+
+impl DatabaseTrieNodesMetrics {
+    /// Record a proof that loaded the given number of nodes.
+    pub fn record_proof(&self, duration: Duration, nodes: usize) {
+        self.proofs.increment(1);
+        self.proof_duration_seconds.record(duration.as_secs_f64());
+        self.proof_nodes.record(nodes as f64);
+    }
+
+    /// Record a read of a branch node from the trie tables.
+    pub fn record_branch_node_read(&self) {
+        self.branch_node_reads.increment(1);
+    }
+}
@@ -161,6 +161,11 @@ impl PrefixSet {
         false
     }
 
+    /// Returns an iterator over the sorted keys of the set.
+    pub fn iter(&self) -> impl Iterator<Item = &Nibbles> {
+        self.keys.iter()
+    }
+
     /// Returns the number of elements in the set.
     pub fn len(&self) -> usize {
         self.keys.len()
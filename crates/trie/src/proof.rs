@@ -1,43 +1,82 @@
 use crate::{
     hashed_cursor::{HashedCursorFactory, HashedStorageCursor},
     node_iter::{AccountNode, AccountNodeIter, StorageNode, StorageNodeIter},
-    prefix_set::PrefixSetMut,
-    trie_cursor::{DatabaseAccountTrieCursor, DatabaseStorageTrieCursor},
+    prefix_set::{PrefixSetMut, TriePrefixSets},
+    trie_cursor::TrieCursorFactory,
     walker::TrieWalker,
 };
 use alloy_rlp::{BufMut, Encodable};
-use reth_db::{tables, transaction::DbTx};
 use reth_interfaces::trie::{StateRootError, StorageRootError};
 use reth_primitives::{
     constants::EMPTY_ROOT_HASH,
     keccak256,
     trie::{AccountProof, HashBuilder, Nibbles, StorageProof, TrieAccount},
-    Address, B256,
+    Address, Bytes, B256,
 };
+use std::collections::BTreeMap;
 
 /// A struct for generating merkle proofs.
 ///
 /// Proof generator adds the target address and slots to the prefix set, enables the proof retainer
 /// on the hash builder and follows the same algorithm as the state root calculator.
 /// See `StateRoot::root` for more info.
+///
+/// Proofs can be generated over an overlaid state by providing a hashed cursor factory together
+/// with the prefix sets of the keys changed by the overlay, see
+/// [HashedPostState::account_proof](crate::HashedPostState::account_proof). Likewise, the trie
+/// nodes can be read from a historical view of the tries by providing a trie cursor factory, see
+/// [HistoricalTrieCursorFactory](crate::trie_cursor::HistoricalTrieCursorFactory).
 #[derive(Debug)]
-pub struct Proof<'a, TX, H> {
-    /// A reference to the database transaction.
-    tx: &'a TX,
+pub struct Proof<T, H> {
+    /// The factory for trie cursors.
+    trie_cursor_factory: T,
     /// The factory for hashed cursors.
     hashed_cursor_factory: H,
+    /// A set of prefixes that have changed in addition to the proof targets.
+    prefix_sets: TriePrefixSets,
 }
 
-impl<'a, TX> Proof<'a, TX, &'a TX> {
+impl<'a, TX> Proof<&'a TX, &'a TX> {
     /// Create a new [Proof] instance.
     pub fn new(tx: &'a TX) -> Self {
-        Self { tx, hashed_cursor_factory: tx }
+        Self {
+            trie_cursor_factory: tx,
+            hashed_cursor_factory: tx,
+            prefix_sets: TriePrefixSets::default(),
+        }
+    }
+}
+
+impl<T, H> Proof<T, H> {
+    /// Set the trie cursor factory.
+    pub fn with_trie_cursor_factory<TF>(self, trie_cursor_factory: TF) -> Proof<TF, H> {
+        Proof {
+            trie_cursor_factory,
+            hashed_cursor_factory: self.hashed_cursor_factory,
+            prefix_sets: self.prefix_sets,
+        }
+    }
+
+    /// Set the hashed cursor factory.
+    pub fn with_hashed_cursor_factory<HF>(self, hashed_cursor_factory: HF) -> Proof<T, HF> {
+        Proof {
+            trie_cursor_factory: self.trie_cursor_factory,
+            hashed_cursor_factory,
+            prefix_sets: self.prefix_sets,
+        }
+    }
+
+    /// Set the changed prefixes. Trie nodes under these prefixes are recomputed from the hashed
+    /// state instead of being taken from the database.
+    pub fn with_prefix_sets(mut self, prefix_sets: TriePrefixSets) -> Self {
+        self.prefix_sets = prefix_sets;
+        self
     }
 }
 
-impl<'a, TX, H> Proof<'a, TX, H>
+impl<T, H> Proof<T, H>
 where
-    TX: DbTx,
+    T: TrieCursorFactory,
     H: HashedCursorFactory + Clone,
 {
     /// Generate an account proof from intermediate nodes.
@@ -51,11 +90,11 @@ where
         let mut account_proof = AccountProof::new(address);
 
         let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
-        let trie_cursor =
-            DatabaseAccountTrieCursor::new(self.tx.cursor_read::<tables::AccountsTrie>()?);
+        let trie_cursor = self.trie_cursor_factory.account_trie_cursor()?;
 
         // Create the walker.
-        let mut prefix_set = PrefixSetMut::default();
+        let mut prefix_set =
+            PrefixSetMut::from(self.prefix_sets.account_prefix_set.iter().cloned());
         prefix_set.insert(target_nibbles.clone());
         let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
 
@@ -97,6 +136,90 @@ where
         Ok(account_proof)
     }
 
+    /// Generate the nodes of the account trie on the paths of the target keys.
+    ///
+    /// Returns the RLP encoded nodes by their path, except for leaf nodes which are keyed by their
+    /// full key.
+    pub fn account_trie_nodes(
+        &self,
+        targets: Vec<Nibbles>,
+    ) -> Result<BTreeMap<Nibbles, Bytes>, StateRootError> {
+        let hashed_account_cursor = self.hashed_cursor_factory.hashed_account_cursor()?;
+        let trie_cursor = self.trie_cursor_factory.account_trie_cursor()?;
+
+        let prefix_set = PrefixSetMut::from(
+            self.prefix_sets.account_prefix_set.iter().cloned().chain(targets.iter().cloned()),
+        );
+        let walker = TrieWalker::new(trie_cursor, prefix_set.freeze());
+
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(targets);
+        let mut account_rlp = Vec::with_capacity(128);
+        let mut account_node_iter = AccountNodeIter::new(walker, hashed_account_cursor);
+        while let Some(account_node) = account_node_iter.try_next()? {
+            match account_node {
+                AccountNode::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                AccountNode::Leaf(hashed_address, account) => {
+                    let storage_root = self.storage_root(hashed_address)?;
+                    account_rlp.clear();
+                    let account = TrieAccount::from((account, storage_root));
+                    account.encode(&mut account_rlp as &mut dyn BufMut);
+                    hash_builder.add_leaf(Nibbles::unpack(hashed_address), &account_rlp);
+                }
+            }
+        }
+
+        let _ = hash_builder.root();
+        Ok(hash_builder.take_proofs())
+    }
+
+    /// Generate the nodes of the storage trie of the account on the paths of the target keys.
+    ///
+    /// See [Self::account_trie_nodes] for the keys of the returned nodes.
+    pub fn storage_trie_nodes(
+        &self,
+        hashed_address: B256,
+        targets: Vec<Nibbles>,
+    ) -> Result<BTreeMap<Nibbles, Bytes>, StorageRootError> {
+        let mut hashed_storage_cursor = self.hashed_cursor_factory.hashed_storage_cursor()?;
+
+        // short circuit on empty storage
+        if hashed_storage_cursor.is_storage_empty(hashed_address)? {
+            return Ok(BTreeMap::new())
+        }
+
+        let changed_slots = self
+            .prefix_sets
+            .storage_prefix_sets
+            .get(&hashed_address)
+            .into_iter()
+            .flat_map(|prefix_set| prefix_set.iter().cloned());
+        let prefix_set = PrefixSetMut::from(targets.iter().cloned().chain(changed_slots)).freeze();
+        let trie_cursor = self.trie_cursor_factory.storage_tries_cursor(hashed_address)?;
+        let walker = TrieWalker::new(trie_cursor, prefix_set);
+
+        let mut hash_builder = HashBuilder::default().with_proof_retainer(targets);
+        let mut storage_node_iter =
+            StorageNodeIter::new(walker, hashed_storage_cursor, hashed_address);
+        while let Some(node) = storage_node_iter.try_next()? {
+            match node {
+                StorageNode::Branch(node) => {
+                    hash_builder.add_branch(node.key, node.value, node.children_are_in_trie);
+                }
+                StorageNode::Leaf(hashed_slot, value) => {
+                    hash_builder.add_leaf(
+                        Nibbles::unpack(hashed_slot),
+                        alloy_rlp::encode_fixed_size(&value).as_ref(),
+                    );
+                }
+            }
+        }
+
+        let _ = hash_builder.root();
+        Ok(hash_builder.take_proofs())
+    }
+
     /// Compute storage root.
     pub fn storage_root(&self, hashed_address: B256) -> Result<B256, StorageRootError> {
         let (storage_root, _) = self.storage_root_with_proofs(hashed_address, &[])?;
@@ -119,11 +242,15 @@ where
         }
 
         let target_nibbles = proofs.iter().map(|p| p.nibbles.clone()).collect::<Vec<_>>();
-        let prefix_set = PrefixSetMut::from(target_nibbles.clone()).freeze();
-        let trie_cursor = DatabaseStorageTrieCursor::new(
-            self.tx.cursor_dup_read::<tables::StoragesTrie>()?,
-            hashed_address,
-        );
+        let changed_slots = self
+            .prefix_sets
+            .storage_prefix_sets
+            .get(&hashed_address)
+            .into_iter()
+            .flat_map(|prefix_set| prefix_set.iter().cloned());
+        let prefix_set =
+            PrefixSetMut::from(target_nibbles.iter().cloned().chain(changed_slots)).freeze();
+        let trie_cursor = self.trie_cursor_factory.storage_tries_cursor(hashed_address)?;
         let walker = TrieWalker::new(trie_cursor, prefix_set);
 
         let mut hash_builder = HashBuilder::default().with_proof_retainer(target_nibbles);
@@ -164,9 +291,9 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::StateRoot;
+    use crate::{HashedPostState, HashedStorage, StateRoot};
     use once_cell::sync::Lazy;
-    use reth_db::database::Database;
+    use reth_db::{database::Database, tables, transaction::DbTxMut};
     use reth_interfaces::RethResult;
     use reth_primitives::{Account, Bytes, Chain, ChainSpec, StorageEntry, HOLESKY, MAINNET, U256};
     use reth_provider::{test_utils::create_test_provider_factory, HashingWriter, ProviderFactory};
@@ -441,4 +568,52 @@ mod tests {
         let account_proof = Proof::new(provider.tx_ref()).account_proof(target, &slots).unwrap();
         similar_asserts::assert_eq!(account_proof, expected);
     }
+
+    #[test]
+    fn post_state_proof_matches_database_proof() {
+        // Create test database and insert genesis accounts.
+        let factory = create_test_provider_factory();
+        insert_genesis(&factory, TEST_SPEC.clone()).unwrap();
+
+        let target = Address::from_str("0x33f0fc440b8477fcfbe9d0bf8649e7dea9baedb2").unwrap();
+        let sibling = Address::from_str("0x1ed9b1dd266b607ee278726d324b855a093394a6").unwrap();
+        let account = Account { nonce: 1, balance: U256::from(2), bytecode_hash: None };
+        let slot = B256::with_last_byte(1);
+        let value = U256::from(3);
+        let slots = [slot, B256::with_last_byte(2)];
+
+        // Generate proofs over the genesis state with the changes applied in memory.
+        let mut post_state = HashedPostState::default();
+        post_state.accounts.insert(keccak256(target), Some(account));
+        post_state
+            .storages
+            .insert(keccak256(target), HashedStorage::from_iter(false, [(keccak256(slot), value)]));
+        let overlay_proofs = {
+            let provider = factory.provider().unwrap();
+            [target, sibling].map(|address| {
+                post_state.account_proof(provider.tx_ref(), address, &slots).unwrap()
+            })
+        };
+
+        // Write the changes to the database and rebuild the trie from scratch.
+        let mut provider = factory.provider_rw().unwrap();
+        provider.insert_account_for_hashing([(target, Some(account))]).unwrap();
+        provider
+            .insert_storage_for_hashing([(target, [StorageEntry { key: slot, value }])])
+            .unwrap();
+        provider.tx_ref().clear::<tables::AccountsTrie>().unwrap();
+        provider.tx_ref().clear::<tables::StoragesTrie>().unwrap();
+        let (_, updates) = StateRoot::from_tx(provider.tx_ref()).root_with_updates().unwrap();
+        updates.flush(provider.tx_mut()).unwrap();
+        provider.commit().unwrap();
+
+        assert_eq!(overlay_proofs[0].storage_proofs[0].value, value);
+
+        let provider = factory.provider().unwrap();
+        for (address, overlay_proof) in [target, sibling].into_iter().zip(overlay_proofs) {
+            let account_proof =
+                Proof::new(provider.tx_ref()).account_proof(address, &slots).unwrap();
+            similar_asserts::assert_eq!(overlay_proof, account_proof);
+        }
+    }
 }
@@ -0,0 +1,567 @@
+#[cfg(feature = "metrics")]
+use crate::metrics::DatabaseTrieNodesMetrics;
+use crate::{
+    hashed_cursor::{HashedAccountCursor, HashedCursorFactory, HashedStorageCursor},
+    proof::Proof,
+    trie_cursor::{TrieCursor, TrieCursorFactory},
+    updates::{TrieKey, TrieOp, TrieUpdates},
+    HashedPostState,
+};
+use alloy_rlp::Decodable;
+use reth_db::{transaction::DbTx, DatabaseError};
+use reth_primitives::{
+    constants::EMPTY_ROOT_HASH,
+    keccak256,
+    trie::{
+        BranchNodeCompact, Nibbles, SparseTrie, SparseTrieError, SparseTrieUpdates, TrieAccount,
+        TrieNodeProvider,
+    },
+    Account, Bytes, B256,
+};
+use std::{
+    cell::{Cell, RefCell},
+    collections::{hash_map::Entry, BTreeMap, HashMap, HashSet},
+    fmt::Display,
+    time::{Duration, Instant},
+};
+use tracing::debug;
+
+/// Provides the RLP encoded nodes of the account trie and the storage tries that are not revealed
+/// in a [SparseStateTrie].
+pub trait StateTrieNodeProvider {
+    /// Returns the node of the account trie with the given hash at the given path.
+    fn account_node(&self, path: &Nibbles, hash: B256) -> Option<Bytes>;
+
+    /// Returns the node of the storage trie of the account with the given hash at the given path.
+    fn storage_node(&self, hashed_address: B256, path: &Nibbles, hash: B256) -> Option<Bytes>;
+
+    /// Returns the stored branch node of the account trie at the given path, see
+    /// [TrieNodeProvider::stored_branch_node].
+    fn account_branch_node(
+        &self,
+        path: &Nibbles,
+    ) -> Result<Option<BranchNodeCompact>, SparseTrieError> {
+        Err(SparseTrieError::StoredBranchNode(path.clone()))
+    }
+
+    /// Returns the stored branch node of the storage trie of the account at the given path, see
+    /// [TrieNodeProvider::stored_branch_node].
+    fn storage_branch_node(
+        &self,
+        _hashed_address: B256,
+        path: &Nibbles,
+    ) -> Result<Option<BranchNodeCompact>, SparseTrieError> {
+        Err(SparseTrieError::StoredBranchNode(path.clone()))
+    }
+}
+
+impl StateTrieNodeProvider for HashMap<B256, Bytes> {
+    fn account_node(&self, _path: &Nibbles, hash: B256) -> Option<Bytes> {
+        self.get(&hash).cloned()
+    }
+
+    fn storage_node(&self, _hashed_address: B256, _path: &Nibbles, hash: B256) -> Option<Bytes> {
+        self.get(&hash).cloned()
+    }
+}
+
+/// The nodes of the account trie of a [StateTrieNodeProvider].
+struct AccountNodes<'a>(&'a dyn StateTrieNodeProvider);
+
+impl TrieNodeProvider for AccountNodes<'_> {
+    fn trie_node(&self, path: &Nibbles, hash: B256) -> Option<Bytes> {
+        self.0.account_node(path, hash)
+    }
+
+    fn stored_branch_node(
+        &self,
+        path: &Nibbles,
+    ) -> Result<Option<BranchNodeCompact>, SparseTrieError> {
+        self.0.account_branch_node(path)
+    }
+}
+
+/// The nodes of the storage trie of an account of a [StateTrieNodeProvider].
+struct StorageNodes<'a>(&'a dyn StateTrieNodeProvider, B256);
+
+impl TrieNodeProvider for StorageNodes<'_> {
+    fn trie_node(&self, path: &Nibbles, hash: B256) -> Option<Bytes> {
+        self.0.storage_node(self.1, path, hash)
+    }
+
+    fn stored_branch_node(
+        &self,
+        path: &Nibbles,
+    ) -> Result<Option<BranchNodeCompact>, SparseTrieError> {
+        self.0.storage_branch_node(self.1, path)
+    }
+}
+
+/// Loads the nodes of the state trie at the tip of the database.
+///
+/// The node at a path is loaded by generating the proof of the first key under the path. All nodes
+/// of the proof are cached, so that the nodes below it are not loaded again. Every proof walks the
+/// trie from its root, so loading is by far the most expensive part of updating a sparse trie; the
+/// number of proofs and their duration are available from [DatabaseTrieNodes::proofs] and
+/// [DatabaseTrieNodes::proof_duration], and recorded as metrics.
+#[derive(Debug)]
+pub struct DatabaseTrieNodes<'a, TX> {
+    /// A reference to the database transaction.
+    tx: &'a TX,
+    /// The loaded nodes by hash.
+    nodes: RefCell<HashMap<B256, Bytes>>,
+    /// The number of proofs generated to load nodes.
+    proofs: Cell<u64>,
+    /// The total duration of the proofs.
+    proof_duration: Cell<Duration>,
+    /// Metrics for loading nodes.
+    #[cfg(feature = "metrics")]
+    metrics: DatabaseTrieNodesMetrics,
+}
+
+impl<'a, TX> DatabaseTrieNodes<'a, TX> {
+    /// Create a new [DatabaseTrieNodes] instance.
+    pub fn new(tx: &'a TX) -> Self {
+        Self {
+            tx,
+            nodes: RefCell::default(),
+            proofs: Cell::default(),
+            proof_duration: Cell::default(),
+            #[cfg(feature = "metrics")]
+            metrics: DatabaseTrieNodesMetrics::default(),
+        }
+    }
+
+    /// Returns the number of proofs that have been generated to load nodes.
+    pub fn proofs(&self) -> u64 {
+        self.proofs.get()
+    }
+
+    /// Returns the total duration of the proofs that have been generated to load nodes.
+    pub fn proof_duration(&self) -> Duration {
+        self.proof_duration.get()
+    }
+
+    /// Returns the cached node with the given hash, or loads the nodes and caches them.
+    fn load_node<E: Display>(
+        &self,
+        hash: B256,
+        load: impl FnOnce() -> Result<BTreeMap<Nibbles, Bytes>, E>,
+    ) -> Option<Bytes> {
+        if let Some(node) = self.nodes.borrow().get(&hash) {
+            return Some(node.clone())
+        }
+        let started_at = Instant::now();
+        let loaded = load();
+        let elapsed = started_at.elapsed();
+        self.proofs.set(self.proofs.get() + 1);
+        self.proof_duration.set(self.proof_duration.get() + elapsed);
+        match loaded {
+            Ok(loaded) => {
+                #[cfg(feature = "metrics")]
+                self.metrics.record_proof(elapsed, loaded.len());
+                let mut nodes = self.nodes.borrow_mut();
+                nodes.extend(loaded.into_values().map(|node| (keccak256(&node), node)));
+                nodes.get(&hash).cloned()
+            }
+            Err(err) => {
+                debug!(target: "trie::sparse", %hash, %err, "Failed to load trie node");
+                None
+            }
+        }
+    }
+
+    /// Reads the branch node at the path from the trie tables.
+    fn read_branch_node(
+        &self,
+        path: &Nibbles,
+        read: impl FnOnce() -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError>,
+    ) -> Result<Option<BranchNodeCompact>, SparseTrieError> {
+        #[cfg(feature = "metrics")]
+        self.metrics.record_branch_node_read();
+        match read() {
+            Ok(entry) => Ok(entry.map(|(_, node)| node)),
+            Err(err) => {
+                debug!(target: "trie::sparse", ?path, %err, "Failed to read branch node");
+                Err(SparseTrieError::StoredBranchNode(path.clone()))
+            }
+        }
+    }
+}
+
+impl<'a, TX: DbTx> StateTrieNodeProvider for DatabaseTrieNodes<'a, TX> {
+    fn account_node(&self, path: &Nibbles, hash: B256) -> Option<Bytes> {
+        self.load_node(hash, || {
+            let Some((hashed_address, _)) =
+                self.tx.hashed_account_cursor()?.seek(first_key(path))?
+            else {
+                return Ok(BTreeMap::new())
+            };
+            Proof::new(self.tx).account_trie_nodes(vec![Nibbles::unpack(hashed_address)])
+        })
+    }
+
+    fn storage_node(&self, hashed_address: B256, path: &Nibbles, hash: B256) -> Option<Bytes> {
+        self.load_node(hash, || {
+            let Some(entry) =
+                self.tx.hashed_storage_cursor()?.seek(hashed_address, first_key(path))?
+            else {
+                return Ok(BTreeMap::new())
+            };
+            Proof::new(self.tx).storage_trie_nodes(hashed_address, vec![Nibbles::unpack(entry.key)])
+        })
+    }
+
+    fn account_branch_node(
+        &self,
+        path: &Nibbles,
+    ) -> Result<Option<BranchNodeCompact>, SparseTrieError> {
+        self.read_branch_node(path, || self.tx.account_trie_cursor()?.seek_exact(path.clone()))
+    }
+
+    fn storage_branch_node(
+        &self,
+        hashed_address: B256,
+        path: &Nibbles,
+    ) -> Result<Option<BranchNodeCompact>, SparseTrieError> {
+        self.read_branch_node(path, || {
+            self.tx.storage_tries_cursor(hashed_address)?.seek_exact(path.clone())
+        })
+    }
+}
+
+/// Returns the smallest key under the path.
+fn first_key(path: &Nibbles) -> B256 {
+    let mut key = path.pack();
+    key.resize(32, 0);
+    B256::from_slice(key.as_slice())
+}
+
+/// The state trie of which only the nodes that are required to apply state changes are revealed.
+///
+/// Consists of a [SparseTrie] of the accounts and a [SparseTrie] of the storage of every account
+/// whose storage has been changed. Unrevealed nodes are loaded from the [StateTrieNodeProvider]
+/// the state changes are applied with.
+///
+/// The trie can be kept across blocks to apply the state changes of every block incrementally,
+/// [SparseStateTrie::prune] bounds the number of revealed nodes in between.
+///
+/// If enabled with [SparseStateTrie::with_updates], the changes to the trie tables of the database
+/// are retained until they are taken with [SparseStateTrie::take_updates]. The stored branch nodes
+/// are then read from the [StateTrieNodeProvider], which must serve the trie tables of the state
+/// that the unmodified nodes of the trie belong to.
+#[derive(Clone, Debug, Default)]
+pub struct SparseStateTrie {
+    /// The account trie.
+    accounts: SparseTrie,
+    /// The storage tries by hashed address.
+    storages: HashMap<B256, SparseTrie>,
+    /// The accounts whose storage tries have been updated since the last prune.
+    updated_storages: HashSet<B256>,
+    /// The retained updates of the trie tables, if enabled.
+    updates: Option<TrieUpdates>,
+}
+
+impl SparseStateTrie {
+    /// Creates a new sparse state trie with the given state root.
+    pub fn new(state_root: B256) -> Self {
+        Self {
+            accounts: SparseTrie::new(state_root),
+            storages: HashMap::new(),
+            updated_storages: HashSet::new(),
+            updates: None,
+        }
+    }
+
+    /// Set the flag indicating whether to retain the updates of the trie tables.
+    pub fn with_updates(mut self, retain_updates: bool) -> Self {
+        self.updates = retain_updates.then(TrieUpdates::default);
+        self
+    }
+
+    /// Takes the updates of the trie tables that have been retained since they were last taken.
+    pub fn take_updates(&mut self) -> TrieUpdates {
+        self.updates.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Applies the state changes and returns the new state root.
+    ///
+    /// Values are inserted before keys are removed, so that branch nodes only collapse when
+    /// necessary. Wiped storage tries and the storage tries of destroyed accounts are deleted as a
+    /// whole from the retained updates.
+    pub fn update(
+        &mut self,
+        state: &HashedPostState,
+        nodes: &dyn StateTrieNodeProvider,
+    ) -> Result<B256, SparseTrieError> {
+        let account_nodes = AccountNodes(nodes);
+        let mut storage_roots = HashMap::with_capacity(state.storages.len());
+        for (hashed_address, storage) in &state.storages {
+            let trie = match self.storages.entry(*hashed_address) {
+                Entry::Occupied(entry) if !storage.wiped => entry.into_mut(),
+                Entry::Occupied(mut entry) => {
+                    entry.insert(SparseTrie::default());
+                    entry.into_mut()
+                }
+                Entry::Vacant(entry) => {
+                    let root = if storage.wiped {
+                        EMPTY_ROOT_HASH
+                    } else {
+                        account_storage_root(&mut self.accounts, hashed_address, &account_nodes)?
+                    };
+                    entry.insert(SparseTrie::new(root))
+                }
+            };
+            self.updated_storages.insert(*hashed_address);
+            if storage.wiped {
+                // the deletion must precede the updates of the new storage trie
+                if let Some(updates) = self.updates.as_mut() {
+                    updates.extend([(TrieKey::StorageTrie(*hashed_address), TrieOp::Delete)]);
+                }
+            }
+
+            let storage_nodes = StorageNodes(nodes, *hashed_address);
+            let (removed, updated): (Vec<_>, Vec<_>) =
+                storage.storage.iter().partition(|(_, value)| value.is_zero());
+            for (hashed_slot, value) in updated {
+                let key = Nibbles::unpack(hashed_slot);
+                trie.insert(&key, alloy_rlp::encode(value), &storage_nodes)?;
+            }
+            for (hashed_slot, _) in removed {
+                trie.remove(&Nibbles::unpack(hashed_slot), &storage_nodes)?;
+            }
+            let storage_root = match self.updates.as_mut() {
+                Some(updates) => {
+                    let (root, trie_updates) = trie.root_with_updates(&storage_nodes)?;
+                    updates.extend(trie_operations(trie_updates, |path| {
+                        TrieKey::StorageNode(*hashed_address, path.into())
+                    }));
+                    root
+                }
+                None => trie.root(),
+            };
+            storage_roots.insert(*hashed_address, storage_root);
+        }
+
+        // update the storage roots of accounts that only changed storage
+        let mut accounts = Vec::with_capacity(state.accounts.len() + storage_roots.len());
+        for hashed_address in storage_roots.keys() {
+            if state.accounts.contains_key(hashed_address) {
+                continue
+            }
+            let key = Nibbles::unpack(hashed_address);
+            if let Some(encoded) = self.accounts.get(&key, &account_nodes)? {
+                let account = TrieAccount::decode(&mut encoded.as_slice())?;
+                let account = Account {
+                    nonce: account.nonce(),
+                    balance: account.balance(),
+                    bytecode_hash: Some(account.code_hash()),
+                };
+                accounts.push((*hashed_address, Some(account)));
+            }
+        }
+        accounts.extend(state.accounts.iter().map(|(address, account)| (*address, *account)));
+
+        let (destroyed, updated): (Vec<_>, Vec<_>) =
+            accounts.into_iter().partition(|(_, account)| account.is_none());
+        for (hashed_address, account) in updated {
+            let Some(account) = account else { continue };
+            let storage_root = match storage_roots.get(&hashed_address) {
+                Some(root) => *root,
+                None => account_storage_root(&mut self.accounts, &hashed_address, &account_nodes)?,
+            };
+            self.accounts.insert(
+                &Nibbles::unpack(hashed_address),
+                alloy_rlp::encode(TrieAccount::from((account, storage_root))),
+                &account_nodes,
+            )?;
+        }
+        for (hashed_address, _) in destroyed {
+            self.accounts.remove(&Nibbles::unpack(hashed_address), &account_nodes)?;
+            self.storages.remove(&hashed_address);
+            if let Some(updates) = self.updates.as_mut() {
+                updates.extend([(TrieKey::StorageTrie(hashed_address), TrieOp::Delete)]);
+            }
+        }
+
+        match self.updates.as_mut() {
+            Some(updates) => {
+                let (root, trie_updates) = self.accounts.root_with_updates(&account_nodes)?;
+                updates.extend(trie_operations(trie_updates, |path| {
+                    TrieKey::AccountNode(path.into())
+                }));
+                Ok(root)
+            }
+            None => Ok(self.accounts.root()),
+        }
+    }
+
+    /// Replaces the revealed nodes at the given depth or deeper with their hashes, see
+    /// [SparseTrie::prune].
+    ///
+    /// Storage tries that have not been updated since the last prune are dropped, their roots are
+    /// read from the account trie again once they are updated.
+    pub fn prune(&mut self, depth: usize) {
+        self.accounts.prune(depth);
+        let updated = std::mem::take(&mut self.updated_storages);
+        self.storages.retain(|hashed_address, trie| {
+            trie.prune(depth);
+            updated.contains(hashed_address)
+        });
+    }
+}
+
+/// Returns the operations on the trie tables for the updates of a [SparseTrie].
+fn trie_operations(
+    updates: SparseTrieUpdates,
+    key: impl Fn(Nibbles) -> TrieKey,
+) -> Vec<(TrieKey, TrieOp)> {
+    let mut operations =
+        updates.removed.into_iter().map(|path| (key(path), TrieOp::Delete)).collect::<Vec<_>>();
+    operations
+        .extend(updates.updated.into_iter().map(|(path, node)| (key(path), TrieOp::Update(node))));
+    operations
+}
+
+/// Returns the storage root of the account from its leaf in the account trie.
+fn account_storage_root(
+    accounts: &mut SparseTrie,
+    hashed_address: &B256,
+    nodes: &dyn TrieNodeProvider,
+) -> Result<B256, SparseTrieError> {
+    Ok(match accounts.get(&Nibbles::unpack(hashed_address), nodes)? {
+        Some(encoded) => TrieAccount::decode(&mut encoded.as_slice())?.storage_root(),
+        None => EMPTY_ROOT_HASH,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_utils::state_root_prehashed, verify::TrieVerifier, HashedStorage, StateRoot};
+    use reth_db::{tables, transaction::DbTxMut};
+    use reth_primitives::{StorageEntry, U256};
+    use reth_provider::{
+        bundle_state::HashedStateChanges, test_utils::create_test_provider_factory,
+    };
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn sparse_state_trie_root() {
+        let nodes = HashMap::<B256, Bytes>::new();
+        let mut state = (0u8..20)
+            .map(|i| {
+                let account =
+                    Account { nonce: i as u64, balance: U256::from(i), bytecode_hash: None };
+                let storage = (1u8..=i % 4)
+                    .map(|slot| (B256::with_last_byte(slot), U256::from(i) * U256::from(slot)))
+                    .collect::<BTreeMap<_, _>>();
+                (B256::repeat_byte(i), (account, storage))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let mut trie = SparseStateTrie::new(EMPTY_ROOT_HASH);
+        let post_state = HashedPostState::default()
+            .with_accounts(state.iter().map(|(address, (account, _))| (*address, Some(*account))))
+            .with_storages(state.iter().map(|(address, (_, storage))| {
+                (*address, HashedStorage::from_iter(false, storage.clone()))
+            }));
+        assert_eq!(trie.update(&post_state, &nodes).unwrap(), state_root_prehashed(state.clone()));
+
+        // destroy an account, wipe and clear storage slots, and change storage only
+        let destroyed = B256::repeat_byte(1);
+        let wiped = B256::repeat_byte(2);
+        let cleared = B256::repeat_byte(3);
+        let changed = B256::repeat_byte(5);
+        state.remove(&destroyed);
+        state.get_mut(&wiped).unwrap().1 =
+            BTreeMap::from([(B256::with_last_byte(7), U256::from(7))]);
+        state.get_mut(&cleared).unwrap().1.clear();
+        state.get_mut(&changed).unwrap().1.insert(B256::with_last_byte(1), U256::from(42));
+
+        let post_state =
+            HashedPostState::default().with_accounts([(destroyed, None)]).with_storages([
+                (destroyed, HashedStorage::new(true)),
+                (wiped, HashedStorage::from_iter(true, [(B256::with_last_byte(7), U256::from(7))])),
+                (
+                    cleared,
+                    HashedStorage::from_iter(
+                        false,
+                        (1..=3).map(|slot| (B256::with_last_byte(slot), U256::ZERO)),
+                    ),
+                ),
+                (
+                    changed,
+                    HashedStorage::from_iter(false, [(B256::with_last_byte(1), U256::from(42))]),
+                ),
+            ]);
+        assert_eq!(trie.update(&post_state, &nodes).unwrap(), state_root_prehashed(state));
+    }
+
+    #[test]
+    fn database_trie_nodes() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        for i in 0u64..1000 {
+            let hashed_address = keccak256(i.to_be_bytes());
+            let account = Account { nonce: i, balance: U256::from(i), bytecode_hash: None };
+            tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+            for slot in 0..i % 50 {
+                let entry = StorageEntry {
+                    key: keccak256(slot.to_be_bytes()),
+                    value: U256::from(slot + 1),
+                };
+                tx.put::<tables::HashedStorages>(hashed_address, entry).unwrap();
+            }
+        }
+        let (root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.flush(tx).unwrap();
+
+        let changed = keccak256(49u64.to_be_bytes());
+        let post_state = HashedPostState::default()
+            .with_accounts([
+                (keccak256(1u64.to_be_bytes()), None),
+                (
+                    keccak256(2000u64.to_be_bytes()),
+                    Some(Account { nonce: 1, balance: U256::from(1), bytecode_hash: None }),
+                ),
+            ])
+            .with_storages([
+                (keccak256(1u64.to_be_bytes()), HashedStorage::new(true)),
+                (
+                    changed,
+                    HashedStorage::from_iter(
+                        false,
+                        (0u64..20).map(|slot| {
+                            let value = if slot % 2 == 0 { U256::ZERO } else { U256::from(7) };
+                            (keccak256(slot.to_be_bytes()), value)
+                        }),
+                    ),
+                ),
+            ]);
+
+        let mut trie = SparseStateTrie::new(root).with_updates(true);
+        let nodes = DatabaseTrieNodes::new(tx);
+        assert_eq!(trie.update(&post_state, &nodes).unwrap(), post_state.state_root(tx).unwrap());
+        assert!(nodes.proofs() > 0);
+
+        // write the changes to the database, pruned nodes are loaded again from there
+        trie.take_updates().flush(tx).unwrap();
+        HashedStateChanges(post_state).write_to_db(tx).unwrap();
+        assert!(TrieVerifier::new(tx).verify().unwrap().is_consistent());
+        trie.prune(1);
+
+        let post_state = HashedPostState::default().with_storages([(
+            changed,
+            HashedStorage::from_iter(false, [(keccak256(21u64.to_be_bytes()), U256::ZERO)]),
+        )]);
+        assert_eq!(
+            trie.update(&post_state, &DatabaseTrieNodes::new(tx)).unwrap(),
+            post_state.state_root(tx).unwrap()
+        );
+        trie.take_updates().flush(tx).unwrap();
+        HashedStateChanges(post_state).write_to_db(tx).unwrap();
+        assert!(TrieVerifier::new(tx).verify().unwrap().is_consistent());
+    }
+}
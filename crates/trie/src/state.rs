@@ -1,6 +1,7 @@
 use crate::{
     hashed_cursor::HashedPostStateCursorFactory,
     prefix_set::{PrefixSetMut, TriePrefixSets},
+    proof::Proof,
     updates::TrieUpdates,
     StateRoot,
 };
@@ -11,14 +12,16 @@ use reth_db::{
     transaction::DbTx,
     DatabaseError,
 };
-use reth_interfaces::trie::StateRootError;
+use reth_interfaces::trie::{StateRootError, StorageRootError};
 use reth_primitives::{
-    keccak256, revm::compat::into_reth_acc, trie::Nibbles, Account, Address, BlockNumber, B256,
-    U256,
+    keccak256,
+    revm::compat::into_reth_acc,
+    trie::{AccountProof, Nibbles},
+    Account, Address, BlockNumber, Bytes, B256, U256,
 };
 use revm::db::BundleAccount;
 use std::{
-    collections::{hash_map, HashMap, HashSet},
+    collections::{hash_map, BTreeMap, HashMap, HashSet},
     ops::RangeInclusive,
 };
 
@@ -249,6 +252,56 @@ impl HashedPostState {
             .with_prefix_sets(prefix_sets)
             .root_with_updates()
     }
+
+    /// Generates the account proof for the given address and storage slots over the state in the
+    /// database with this [HashedPostState] applied on top.
+    /// See [Self::state_root] for more info.
+    pub fn account_proof<TX: DbTx>(
+        &self,
+        tx: &TX,
+        address: Address,
+        slots: &[B256],
+    ) -> Result<AccountProof, StateRootError> {
+        let sorted = self.clone().into_sorted();
+        let prefix_sets = self.construct_prefix_sets();
+        Proof::new(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted))
+            .with_prefix_sets(prefix_sets)
+            .account_proof(address, slots)
+    }
+
+    /// Generates the nodes of the account trie on the paths of the targets over the state in the
+    /// database with this [HashedPostState] applied on top.
+    /// See [Proof::account_trie_nodes] for more info.
+    pub fn account_trie_nodes<TX: DbTx>(
+        &self,
+        tx: &TX,
+        targets: Vec<Nibbles>,
+    ) -> Result<BTreeMap<Nibbles, Bytes>, StateRootError> {
+        let sorted = self.clone().into_sorted();
+        let prefix_sets = self.construct_prefix_sets();
+        Proof::new(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted))
+            .with_prefix_sets(prefix_sets)
+            .account_trie_nodes(targets)
+    }
+
+    /// Generates the nodes of the storage trie of the account on the paths of the targets over the
+    /// state in the database with this [HashedPostState] applied on top.
+    /// See [Proof::storage_trie_nodes] for more info.
+    pub fn storage_trie_nodes<TX: DbTx>(
+        &self,
+        tx: &TX,
+        hashed_address: B256,
+        targets: Vec<Nibbles>,
+    ) -> Result<BTreeMap<Nibbles, Bytes>, StorageRootError> {
+        let sorted = self.clone().into_sorted();
+        let prefix_sets = self.construct_prefix_sets();
+        Proof::new(tx)
+            .with_hashed_cursor_factory(HashedPostStateCursorFactory::new(tx, &sorted))
+            .with_prefix_sets(prefix_sets)
+            .storage_trie_nodes(hashed_address, targets)
+    }
 }
 
 /// Representation of in-memory hashed storage.
@@ -0,0 +1,263 @@
+use super::{TrieCursor, TrieCursorFactory};
+use crate::updates::TrieKey;
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    models::{storage_trie_sharded_key::StorageTrieShardedKey, ShardedKey},
+    tables,
+    transaction::DbTx,
+    BlockNumberList, DatabaseError,
+};
+use reth_primitives::{
+    trie::{BranchNodeCompact, Nibbles, StoredNibbles, StoredNibblesSubKey},
+    BlockNumber, B256,
+};
+
+/// The trie cursor factory for the trie nodes at the start of a historical block.
+///
+/// The nodes changed since the block are read from the trie changesets of the first block that
+/// changed them, found through the [tables::AccountsTrieHistory] and [tables::StoragesTrieHistory]
+/// indices. All other nodes are the same as in the database.
+///
+/// NOTE: The trie changesets must be available for the block, see
+/// [TrieHistory::is_available](crate::history::TrieHistory::is_available).
+#[derive(Debug, Clone)]
+pub struct HistoricalTrieCursorFactory<'a, TX> {
+    /// A reference to the database transaction.
+    tx: &'a TX,
+    /// The block at the start of which the trie nodes are read.
+    block_number: BlockNumber,
+}
+
+impl<'a, TX> HistoricalTrieCursorFactory<'a, TX> {
+    /// Create a new factory.
+    pub fn new(tx: &'a TX, block_number: BlockNumber) -> Self {
+        Self { tx, block_number }
+    }
+}
+
+impl<'a, TX: DbTx> TrieCursorFactory for HistoricalTrieCursorFactory<'a, TX> {
+    fn account_trie_cursor(&self) -> Result<Box<dyn TrieCursor + '_>, DatabaseError> {
+        Ok(Box::new(HistoricalTrieCursor::new(
+            self.tx.account_trie_cursor()?,
+            AccountTrieHistory {
+                index_cursor: self.tx.cursor_read::<tables::AccountsTrieHistory>()?,
+                changeset_cursor: self.tx.cursor_dup_read::<tables::AccountsTrieChangeSets>()?,
+                block_number: self.block_number,
+            },
+            None,
+        )))
+    }
+
+    fn storage_tries_cursor(
+        &self,
+        hashed_address: B256,
+    ) -> Result<Box<dyn TrieCursor + '_>, DatabaseError> {
+        Ok(Box::new(HistoricalTrieCursor::new(
+            self.tx.storage_tries_cursor(hashed_address)?,
+            StorageTrieHistory {
+                index_cursor: self.tx.cursor_read::<tables::StoragesTrieHistory>()?,
+                changeset_cursor: self.tx.cursor_dup_read::<tables::StoragesTrieChangeSets>()?,
+                hashed_address,
+                block_number: self.block_number,
+            },
+            Some(hashed_address),
+        )))
+    }
+}
+
+/// The history of the nodes of a trie.
+pub(crate) trait TrieNodeHistory: Send + Sync {
+    /// Returns the first path at or after the key with any recorded change.
+    fn next_changed_path(&mut self, key: Nibbles) -> Result<Option<Nibbles>, DatabaseError>;
+
+    /// Returns the node at the historical block if it was changed since, `None` if the node is
+    /// unchanged. A node that did not exist at the block is `Some(None)`.
+    fn historical_node(
+        &mut self,
+        key: &Nibbles,
+    ) -> Result<Option<Option<BranchNodeCompact>>, DatabaseError>;
+}
+
+/// Returns the first block at or after the given one in the shard of a history index.
+fn first_block_at_or_after(list: &BlockNumberList, block_number: BlockNumber) -> Option<u64> {
+    let rank = block_number.checked_sub(1).map_or(0, |block_number| list.rank(block_number));
+    list.select(rank)
+}
+
+/// The history of the account trie.
+struct AccountTrieHistory<I, C> {
+    index_cursor: I,
+    changeset_cursor: C,
+    block_number: BlockNumber,
+}
+
+impl<I, C> TrieNodeHistory for AccountTrieHistory<I, C>
+where
+    I: DbCursorRO<tables::AccountsTrieHistory> + Send + Sync,
+    C: DbDupCursorRO<tables::AccountsTrieChangeSets> + Send + Sync,
+{
+    fn next_changed_path(&mut self, key: Nibbles) -> Result<Option<Nibbles>, DatabaseError> {
+        Ok(self
+            .index_cursor
+            .seek(ShardedKey::new(StoredNibblesSubKey(key), 0))?
+            .map(|(sharded_key, _)| sharded_key.key.0))
+    }
+
+    fn historical_node(
+        &mut self,
+        key: &Nibbles,
+    ) -> Result<Option<Option<BranchNodeCompact>>, DatabaseError> {
+        let Some(changed_at) = self
+            .index_cursor
+            .seek(ShardedKey::new(StoredNibblesSubKey(key.clone()), self.block_number))?
+            .filter(|(sharded_key, _)| sharded_key.key.0 == *key)
+            .and_then(|(_, list)| first_block_at_or_after(&list, self.block_number))
+        else {
+            return Ok(None)
+        };
+
+        let entry = self
+            .changeset_cursor
+            .seek_by_key_subkey(changed_at, StoredNibblesSubKey(key.clone()))?
+            .filter(|entry| entry.nibbles.0 == *key);
+        Ok(Some(entry.and_then(|entry| entry.node)))
+    }
+}
+
+/// The history of a storage trie.
+struct StorageTrieHistory<I, C> {
+    index_cursor: I,
+    changeset_cursor: C,
+    hashed_address: B256,
+    block_number: BlockNumber,
+}
+
+impl<I, C> TrieNodeHistory for StorageTrieHistory<I, C>
+where
+    I: DbCursorRO<tables::StoragesTrieHistory> + Send + Sync,
+    C: DbDupCursorRO<tables::StoragesTrieChangeSets> + Send + Sync,
+{
+    fn next_changed_path(&mut self, key: Nibbles) -> Result<Option<Nibbles>, DatabaseError> {
+        Ok(self
+            .index_cursor
+            .seek(StorageTrieShardedKey::new(self.hashed_address, StoredNibblesSubKey(key), 0))?
+            .filter(|(sharded_key, _)| sharded_key.hashed_address == self.hashed_address)
+            .map(|(sharded_key, _)| sharded_key.sharded_key.key.0))
+    }
+
+    fn historical_node(
+        &mut self,
+        key: &Nibbles,
+    ) -> Result<Option<Option<BranchNodeCompact>>, DatabaseError> {
+        let Some(changed_at) = self
+            .index_cursor
+            .seek(StorageTrieShardedKey::new(
+                self.hashed_address,
+                StoredNibblesSubKey(key.clone()),
+                self.block_number,
+            ))?
+            .filter(|(sharded_key, _)| {
+                sharded_key.hashed_address == self.hashed_address &&
+                    sharded_key.sharded_key.key.0 == *key
+            })
+            .and_then(|(_, list)| first_block_at_or_after(&list, self.block_number))
+        else {
+            return Ok(None)
+        };
+
+        let entry = self
+            .changeset_cursor
+            .seek_by_key_subkey(
+                (changed_at, self.hashed_address).into(),
+                StoredNibblesSubKey(key.clone()),
+            )?
+            .filter(|entry| entry.nibbles.0 == *key);
+        Ok(Some(entry.and_then(|entry| entry.node)))
+    }
+}
+
+/// The trie cursor over the nodes at a historical block.
+///
+/// Merges the database cursor with the changed nodes of the trie history. A changed node that did
+/// not exist at the historical block hides the entry in the database.
+pub(crate) struct HistoricalTrieCursor<C, H> {
+    /// The database cursor.
+    cursor: C,
+    /// The history of the trie nodes.
+    history: H,
+    /// The hashed address of the storage trie, `None` for the account trie.
+    hashed_address: Option<B256>,
+    /// The key of the last returned node.
+    last_key: Option<Nibbles>,
+}
+
+impl<C, H> HistoricalTrieCursor<C, H> {
+    /// Create a new cursor over the database cursor with the changed nodes of the history.
+    pub(crate) fn new(cursor: C, history: H, hashed_address: Option<B256>) -> Self {
+        Self { cursor, history, hashed_address, last_key: None }
+    }
+
+    fn set_last_key(
+        &mut self,
+        entry: Option<(Nibbles, BranchNodeCompact)>,
+    ) -> Option<(Nibbles, BranchNodeCompact)> {
+        self.last_key = entry.as_ref().map(|(key, _)| key.clone());
+        entry
+    }
+}
+
+impl<C: TrieCursor, H: TrieNodeHistory> TrieCursor for HistoricalTrieCursor<C, H> {
+    fn seek_exact(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        let entry = match self.history.historical_node(&key)? {
+            Some(node) => node.map(|node| (key, node)),
+            None => self.cursor.seek_exact(key)?,
+        };
+        Ok(self.set_last_key(entry))
+    }
+
+    fn seek(
+        &mut self,
+        key: Nibbles,
+    ) -> Result<Option<(Nibbles, BranchNodeCompact)>, DatabaseError> {
+        let mut key = key;
+        loop {
+            // The next candidate is the first node in the database or with recorded changes.
+            let db_entry = self.cursor.seek(key.clone())?;
+            let changed_path = self.history.next_changed_path(key)?;
+            let candidate = match (&db_entry, changed_path) {
+                (Some((db_key, _)), Some(changed_path)) => {
+                    std::cmp::min(db_key.clone(), changed_path)
+                }
+                (Some((db_key, _)), None) => db_key.clone(),
+                (None, Some(changed_path)) => changed_path,
+                (None, None) => return Ok(self.set_last_key(None)),
+            };
+
+            let entry = match self.history.historical_node(&candidate)? {
+                Some(node) => node.map(|node| (candidate.clone(), node)),
+                None => match db_entry {
+                    Some(entry) if entry.0 == candidate => Some(entry),
+                    _ => self.cursor.seek_exact(candidate.clone())?,
+                },
+            };
+            if entry.is_some() {
+                return Ok(self.set_last_key(entry))
+            }
+
+            // The candidate did not exist at the historical block, continue with its immediate
+            // successor.
+            key = candidate;
+            key.push(0);
+        }
+    }
+
+    fn current(&mut self) -> Result<Option<TrieKey>, DatabaseError> {
+        Ok(self.last_key.clone().map(|key| match self.hashed_address {
+            Some(hashed_address) => TrieKey::StorageNode(hashed_address, StoredNibblesSubKey(key)),
+            None => TrieKey::AccountNode(StoredNibbles(key)),
+        }))
+    }
+}
@@ -6,6 +6,7 @@ use reth_primitives::{
 };
 
 mod database_cursors;
+mod historical;
 mod subnode;
 
 /// Noop trie cursor implementations.
@@ -13,8 +14,10 @@ pub mod noop;
 
 pub use self::{
     database_cursors::{DatabaseAccountTrieCursor, DatabaseStorageTrieCursor},
+    historical::HistoricalTrieCursorFactory,
     subnode::CursorSubNode,
 };
+pub(crate) use historical::{HistoricalTrieCursor, TrieNodeHistory};
 
 /// Factory for creating trie cursors.
 pub trait TrieCursorFactory {
@@ -1,29 +1,33 @@
 use derive_more::Deref;
 use reth_db::{
     cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    models::BlockNumberHashedAddress,
     tables,
     transaction::{DbTx, DbTxMut},
 };
 use reth_primitives::{
     trie::{
         BranchNodeCompact, HashBuilder, Nibbles, StorageTrieEntry, StoredBranchNode, StoredNibbles,
-        StoredNibblesSubKey,
+        StoredNibblesSubKey, TrieChangeSetEntry,
     },
-    B256,
+    BlockNumber, B256,
 };
-use std::collections::{hash_map::IntoIter, HashMap, HashSet};
+use std::collections::{hash_map::IntoIter, BTreeMap, HashMap, HashSet};
 
 use crate::walker::TrieWalker;
 
 /// The key of a trie node.
+///
+/// Storage tries sort before their nodes, so that a storage trie is deleted before the nodes of the
+/// new storage trie of the same account are written.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum TrieKey {
     /// A node in the account trie.
     AccountNode(StoredNibbles),
-    /// A node in the storage trie.
-    StorageNode(B256, StoredNibblesSubKey),
     /// Storage trie of an account.
     StorageTrie(B256),
+    /// A node in the storage trie.
+    StorageNode(B256, StoredNibblesSubKey),
 }
 
 /// The operation to perform on the trie.
@@ -77,7 +81,26 @@ impl TrieUpdates {
     }
 
     /// Extend the updates with trie updates.
+    ///
+    /// The updates of the nodes of storage tries that are deleted by the given updates are
+    /// discarded, as they are superseded by the deletion.
     pub fn extend(&mut self, updates: impl IntoIterator<Item = (TrieKey, TrieOp)>) {
+        let updates = Vec::from_iter(updates);
+        let deleted_storage_tries = updates
+            .iter()
+            .filter_map(|(key, _)| match key {
+                TrieKey::StorageTrie(hashed_address) => Some(*hashed_address),
+                _ => None,
+            })
+            .collect::<HashSet<_>>();
+        if !deleted_storage_tries.is_empty() {
+            self.trie_operations.retain(|key, _| match key {
+                TrieKey::StorageNode(hashed_address, _) => {
+                    !deleted_storage_tries.contains(hashed_address)
+                }
+                _ => true,
+            });
+        }
         self.trie_operations.extend(updates);
     }
 
@@ -129,6 +152,70 @@ impl TrieUpdates {
         }));
     }
 
+    /// Write the trie nodes changed by these updates, as they are in the database before the
+    /// updates are flushed, to the trie changesets of the given block.
+    ///
+    /// Must be called before [Self::flush].
+    pub fn write_changesets(
+        &self,
+        tx: &(impl DbTx + DbTxMut),
+        block_number: BlockNumber,
+    ) -> Result<(), reth_db::DatabaseError> {
+        let mut account_nodes = BTreeMap::<StoredNibblesSubKey, Option<BranchNodeCompact>>::new();
+        let mut storage_nodes =
+            BTreeMap::<B256, BTreeMap<StoredNibblesSubKey, Option<BranchNodeCompact>>>::new();
+
+        let mut account_trie_cursor = tx.cursor_read::<tables::AccountsTrie>()?;
+        let mut storage_trie_cursor = tx.cursor_dup_read::<tables::StoragesTrie>()?;
+        for key in self.trie_operations.keys() {
+            match key {
+                TrieKey::AccountNode(nibbles) => {
+                    if !nibbles.0.is_empty() {
+                        let node = account_trie_cursor.seek_exact(nibbles.clone())?.map(|e| e.1 .0);
+                        account_nodes.insert(StoredNibblesSubKey(nibbles.0.clone()), node);
+                    }
+                }
+                TrieKey::StorageNode(hashed_address, nibbles) => {
+                    if !nibbles.is_empty() {
+                        let node = storage_trie_cursor
+                            .seek_by_key_subkey(*hashed_address, nibbles.clone())?
+                            .filter(|e| &e.nibbles == nibbles)
+                            .map(|e| e.node);
+                        storage_nodes
+                            .entry(*hashed_address)
+                            .or_default()
+                            .insert(nibbles.clone(), node);
+                    }
+                }
+                TrieKey::StorageTrie(hashed_address) => {
+                    // The whole storage trie is deleted, record all of its nodes.
+                    let nodes = storage_nodes.entry(*hashed_address).or_default();
+                    for entry in storage_trie_cursor.walk_dup(Some(*hashed_address), None)? {
+                        let (_, StorageTrieEntry { nibbles, node }) = entry?;
+                        nodes.insert(nibbles, Some(node));
+                    }
+                }
+            }
+        }
+
+        let mut account_changeset_cursor =
+            tx.cursor_dup_write::<tables::AccountsTrieChangeSets>()?;
+        for (nibbles, node) in account_nodes {
+            account_changeset_cursor.upsert(block_number, TrieChangeSetEntry { nibbles, node })?;
+        }
+
+        let mut storage_changeset_cursor =
+            tx.cursor_dup_write::<tables::StoragesTrieChangeSets>()?;
+        for (hashed_address, nodes) in storage_nodes {
+            let key = BlockNumberHashedAddress((block_number, hashed_address));
+            for (nibbles, node) in nodes {
+                storage_changeset_cursor.upsert(key, TrieChangeSetEntry { nibbles, node })?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Flush updates all aggregated updates to the database.
     pub fn flush(self, tx: &(impl DbTx + DbTxMut)) -> Result<(), reth_db::DatabaseError> {
         if self.trie_operations.is_empty() {
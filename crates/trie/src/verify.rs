@@ -0,0 +1,540 @@
+use crate::{
+    prefix_set::{PrefixSet, PrefixSetMut, TriePrefixSets},
+    trie_cursor::{HistoricalTrieCursor, TrieCursor, TrieCursorFactory, TrieNodeHistory},
+    updates::{TrieKey, TrieOp, TrieUpdates},
+    StateRoot, StorageRoot,
+};
+use reth_db::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    tables,
+    transaction::DbTx,
+    DatabaseError,
+};
+use reth_interfaces::trie::StateRootError;
+use reth_primitives::{
+    trie::{BranchNodeCompact, Nibbles, StoredNibbles, StoredNibblesSubKey},
+    B256, U256,
+};
+use std::collections::{BTreeMap, HashMap};
+use tracing::{debug, trace};
+
+/// Verifies the intermediate nodes of the account and storage tries stored in the database.
+///
+/// The stored branch nodes of [tables::AccountsTrie] and [tables::StoragesTrie] are walked in
+/// reverse order, and every node is recomputed from its children: the
+/// [TrieWalker](crate::walker::TrieWalker) descends into the node, while the subtrees below its
+/// children are taken from the stored nodes and the leaves from [tables::HashedAccounts] and
+/// [tables::HashedStorages]. Since the children of a node are verified before the node itself,
+/// inconsistent children are replaced by their recomputed versions before their parent is
+/// recomputed, so that every node is checked against the correct subtrees below it.
+///
+/// The storage tries are verified before the account trie, because the account leaves include the
+/// storage roots.
+#[derive(Debug)]
+pub struct TrieVerifier<'a, TX> {
+    /// A reference to the database transaction.
+    tx: &'a TX,
+}
+
+impl<'a, TX> TrieVerifier<'a, TX> {
+    /// Create a new [TrieVerifier] instance.
+    pub fn new(tx: &'a TX) -> Self {
+        Self { tx }
+    }
+}
+
+impl<'a, TX: DbTx> TrieVerifier<'a, TX> {
+    /// Walks the stored trie nodes and compares them against the nodes recomputed from the hashed
+    /// state.
+    ///
+    /// # Returns
+    ///
+    /// The state root of the hashed state and the inconsistent trie nodes.
+    pub fn verify(&self) -> Result<TrieVerification, StateRootError> {
+        let mut corrections = TrieCorrections::default();
+        let mut inconsistencies = Vec::new();
+
+        // Visit every account with either stored storage trie nodes or storage leaves.
+        let mut storage_trie_cursor = self.tx.cursor_dup_read::<tables::StoragesTrie>()?;
+        let mut hashed_storage_cursor = self.tx.cursor_dup_read::<tables::HashedStorages>()?;
+        let mut trie_address = storage_trie_cursor.first()?.map(|(address, _)| address);
+        let mut leaves_address = hashed_storage_cursor.first()?.map(|(address, _)| address);
+        while let Some(hashed_address) = trie_address.into_iter().chain(leaves_address).min() {
+            self.verify_storage_trie(hashed_address, &mut corrections, &mut inconsistencies)?;
+
+            let Some(next_address) = next_hashed_address(hashed_address) else { break };
+            trie_address = storage_trie_cursor.seek(next_address)?.map(|(address, _)| address);
+            leaves_address = hashed_storage_cursor.seek(next_address)?.map(|(address, _)| address);
+        }
+
+        let mut account_trie_cursor = self.tx.cursor_read::<tables::AccountsTrie>()?;
+        let mut entry = account_trie_cursor.last()?;
+        while let Some((StoredNibbles(path), node)) = entry {
+            let (_, updates) = self.recompute_account_node(&path, &corrections)?;
+            self.check_recomputed(
+                None,
+                &path,
+                Some(node.0),
+                updates,
+                &mut corrections.account_nodes,
+                &mut inconsistencies,
+            )?;
+            entry = account_trie_cursor.prev()?;
+        }
+        let (state_root, updates) =
+            self.recompute_account_node(&Nibbles::default(), &corrections)?;
+        self.check_recomputed(
+            None,
+            &Nibbles::default(),
+            None,
+            updates,
+            &mut corrections.account_nodes,
+            &mut inconsistencies,
+        )?;
+
+        inconsistencies
+            .sort_unstable_by(|a, b| (a.hashed_address, &a.path).cmp(&(b.hashed_address, &b.path)));
+        debug!(
+            target: "trie::verify",
+            %state_root,
+            inconsistencies = inconsistencies.len(),
+            "verified trie"
+        );
+        Ok(TrieVerification { state_root, inconsistencies })
+    }
+
+    /// Verifies the stored nodes of a single storage trie.
+    fn verify_storage_trie(
+        &self,
+        hashed_address: B256,
+        corrections: &mut TrieCorrections,
+        inconsistencies: &mut Vec<TrieInconsistency>,
+    ) -> Result<(), StateRootError> {
+        let mut cursor = self.tx.cursor_dup_read::<tables::StoragesTrie>()?;
+
+        // The storage trie of a non-existent account is not part of the state.
+        if self.tx.get::<tables::HashedAccounts>(hashed_address)?.is_none() {
+            let mut entry = cursor.seek_exact(hashed_address)?;
+            while let Some((_, trie_entry)) = entry {
+                inconsistencies.push(TrieInconsistency {
+                    hashed_address: Some(hashed_address),
+                    path: trie_entry.nibbles.0,
+                    kind: InconsistencyKind::Extra(trie_entry.node),
+                });
+                entry = cursor.next_dup()?;
+            }
+            return Ok(())
+        }
+
+        let mut entry = match next_hashed_address(hashed_address) {
+            Some(next_address) if cursor.seek(next_address)?.is_some() => cursor.prev()?,
+            _ => cursor.last()?,
+        };
+        while let Some((address, trie_entry)) = entry {
+            if address != hashed_address {
+                break
+            }
+            let path = trie_entry.nibbles.0;
+            let updates = self.recompute_storage_node(hashed_address, &path, corrections)?;
+            self.check_recomputed(
+                Some(hashed_address),
+                &path,
+                Some(trie_entry.node),
+                updates,
+                corrections.storage_nodes.entry(hashed_address).or_default(),
+                inconsistencies,
+            )?;
+            entry = cursor.prev()?;
+        }
+        let updates =
+            self.recompute_storage_node(hashed_address, &Nibbles::default(), corrections)?;
+        self.check_recomputed(
+            Some(hashed_address),
+            &Nibbles::default(),
+            None,
+            updates,
+            corrections.storage_nodes.entry(hashed_address).or_default(),
+            inconsistencies,
+        )?;
+        trace!(target: "trie::verify", ?hashed_address, "verified storage trie");
+        Ok(())
+    }
+
+    /// Recomputes the account trie node at the path and its children.
+    ///
+    /// # Returns
+    ///
+    /// The state root and the recomputed account trie nodes.
+    fn recompute_account_node(
+        &self,
+        path: &Nibbles,
+        corrections: &TrieCorrections,
+    ) -> Result<(B256, BTreeMap<Nibbles, Option<BranchNodeCompact>>), StateRootError> {
+        let (state_root, updates) = StateRoot::from_tx(self.tx)
+            .with_trie_cursor_factory(CorrectedTrieCursorFactory { tx: self.tx, corrections })
+            .with_prefix_sets(TriePrefixSets {
+                account_prefix_set: children_prefix_set(path),
+                ..Default::default()
+            })
+            .root_with_updates()?;
+        let nodes = updates
+            .into_iter()
+            .filter_map(|(key, op)| match key {
+                TrieKey::AccountNode(nibbles) => Some((nibbles.0, updated_node(op))),
+                _ => None,
+            })
+            .collect();
+        Ok((state_root, nodes))
+    }
+
+    /// Recomputes the storage trie node at the path and its children.
+    ///
+    /// # Returns
+    ///
+    /// The recomputed storage trie nodes.
+    fn recompute_storage_node(
+        &self,
+        hashed_address: B256,
+        path: &Nibbles,
+        corrections: &TrieCorrections,
+    ) -> Result<BTreeMap<Nibbles, Option<BranchNodeCompact>>, StateRootError> {
+        let (_, _, updates) = StorageRoot::from_tx_hashed(self.tx, hashed_address)
+            .with_trie_cursor_factory(CorrectedTrieCursorFactory { tx: self.tx, corrections })
+            .with_prefix_set(children_prefix_set(path))
+            .root_with_updates()?;
+        Ok(updates
+            .into_iter()
+            .filter_map(|(key, op)| match key {
+                TrieKey::StorageNode(address, nibbles) if address == hashed_address => {
+                    Some((nibbles.0, updated_node(op)))
+                }
+                _ => None,
+            })
+            .collect())
+    }
+
+    /// Compares the stored node at the path and the nodes below it against the recomputed ones,
+    /// and records the recomputed versions of the inconsistent nodes in the corrections.
+    ///
+    /// Nodes below the path that are neither stored nor corrected yet are missing. A node stored at
+    /// the root path is always extra, since root nodes are not stored.
+    fn check_recomputed(
+        &self,
+        hashed_address: Option<B256>,
+        path: &Nibbles,
+        stored: Option<BranchNodeCompact>,
+        recomputed: BTreeMap<Nibbles, Option<BranchNodeCompact>>,
+        corrections: &mut CorrectedNodes,
+        inconsistencies: &mut Vec<TrieInconsistency>,
+    ) -> Result<(), DatabaseError> {
+        if let Some(stored) = stored {
+            let expected =
+                if path.is_empty() { None } else { recomputed.get(path).cloned().flatten() };
+            if expected.as_ref() != Some(&stored) {
+                let kind = match expected.clone() {
+                    Some(expected) => InconsistencyKind::Mismatch { stored, expected },
+                    None => InconsistencyKind::Extra(stored),
+                };
+                corrections.insert(path.clone(), expected);
+                inconsistencies.push(TrieInconsistency {
+                    hashed_address,
+                    path: path.clone(),
+                    kind,
+                });
+            }
+        }
+
+        for (node_path, node) in recomputed.range(path.clone()..) {
+            if !node_path.starts_with(path) {
+                break
+            }
+            let Some(node) = node else { continue };
+            if node_path == path ||
+                corrections.contains_key(node_path) ||
+                self.is_stored(hashed_address, node_path)?
+            {
+                continue
+            }
+            corrections.insert(node_path.clone(), Some(node.clone()));
+            inconsistencies.push(TrieInconsistency {
+                hashed_address,
+                path: node_path.clone(),
+                kind: InconsistencyKind::Missing(node.clone()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if a node is stored at the path of the trie.
+    fn is_stored(
+        &self,
+        hashed_address: Option<B256>,
+        path: &Nibbles,
+    ) -> Result<bool, DatabaseError> {
+        match hashed_address {
+            Some(hashed_address) => Ok(self
+                .tx
+                .cursor_dup_read::<tables::StoragesTrie>()?
+                .seek_by_key_subkey(hashed_address, StoredNibblesSubKey(path.clone()))?
+                .is_some_and(|entry| entry.nibbles.0 == *path)),
+            None => Ok(self.tx.get::<tables::AccountsTrie>(StoredNibbles(path.clone()))?.is_some()),
+        }
+    }
+}
+
+/// Returns the node of a trie update, `None` if the node is deleted.
+fn updated_node(op: TrieOp) -> Option<BranchNodeCompact> {
+    match op {
+        TrieOp::Update(node) => Some(node),
+        TrieOp::Delete => None,
+    }
+}
+
+/// Returns the hashed address following the given one, `None` if it is the last possible address.
+fn next_hashed_address(hashed_address: B256) -> Option<B256> {
+    U256::from_be_bytes(hashed_address.0).checked_add(U256::from(1)).map(B256::from)
+}
+
+/// Returns the prefix set that makes the walker recompute the node at the path from its children.
+///
+/// The children themselves are recomputed as well, while the subtrees below them are skipped
+/// through the stored nodes.
+fn children_prefix_set(path: &Nibbles) -> PrefixSet {
+    let mut prefix_set = PrefixSetMut::default();
+    for nibble in 0..16 {
+        let mut child = path.clone();
+        child.push(nibble);
+        prefix_set.insert(child);
+    }
+    prefix_set.freeze()
+}
+
+/// The recomputed versions of the inconsistent nodes of a trie, `None` for extra nodes.
+type CorrectedNodes = BTreeMap<Nibbles, Option<BranchNodeCompact>>;
+
+/// The corrected nodes of the account and storage tries.
+#[derive(Debug, Default)]
+struct TrieCorrections {
+    account_nodes: CorrectedNodes,
+    storage_nodes: HashMap<B256, CorrectedNodes>,
+}
+
+/// The trie cursor factory for the stored trie nodes with the corrections applied.
+///
+/// The corrections are merged with the database the same way the changed nodes of a historical
+/// block are.
+#[derive(Debug, Clone, Copy)]
+struct CorrectedTrieCursorFactory<'a, TX> {
+    tx: &'a TX,
+    corrections: &'a TrieCorrections,
+}
+
+impl<'a, TX: DbTx> TrieCursorFactory for CorrectedTrieCursorFactory<'a, TX> {
+    fn account_trie_cursor(&self) -> Result<Box<dyn TrieCursor + '_>, DatabaseError> {
+        Ok(Box::new(HistoricalTrieCursor::new(
+            self.tx.account_trie_cursor()?,
+            CorrectedTrieNodes(Some(&self.corrections.account_nodes)),
+            None,
+        )))
+    }
+
+    fn storage_tries_cursor(
+        &self,
+        hashed_address: B256,
+    ) -> Result<Box<dyn TrieCursor + '_>, DatabaseError> {
+        Ok(Box::new(HistoricalTrieCursor::new(
+            self.tx.storage_tries_cursor(hashed_address)?,
+            CorrectedTrieNodes(self.corrections.storage_nodes.get(&hashed_address)),
+            Some(hashed_address),
+        )))
+    }
+}
+
+/// The corrected nodes of a trie as its changes.
+struct CorrectedTrieNodes<'a>(Option<&'a CorrectedNodes>);
+
+impl TrieNodeHistory for CorrectedTrieNodes<'_> {
+    fn next_changed_path(&mut self, key: Nibbles) -> Result<Option<Nibbles>, DatabaseError> {
+        Ok(self.0.and_then(|nodes| nodes.range(key..).next().map(|(path, _)| path.clone())))
+    }
+
+    fn historical_node(
+        &mut self,
+        key: &Nibbles,
+    ) -> Result<Option<Option<BranchNodeCompact>>, DatabaseError> {
+        Ok(self.0.and_then(|nodes| nodes.get(key).cloned()))
+    }
+}
+
+/// The result of the trie verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieVerification {
+    /// The state root recomputed from the hashed state.
+    pub state_root: B256,
+    /// The inconsistent trie nodes, ordered by trie and path.
+    pub inconsistencies: Vec<TrieInconsistency>,
+}
+
+impl TrieVerification {
+    /// Returns `true` if the stored trie nodes match the hashed state.
+    pub fn is_consistent(&self) -> bool {
+        self.inconsistencies.is_empty()
+    }
+
+    /// Returns the roots of the subtrees affected by the inconsistent nodes, i.e. the shortest
+    /// inconsistent paths of each trie that are not below another inconsistent path.
+    ///
+    /// The hashed address is `None` for the account trie.
+    pub fn affected_subtrees(&self) -> Vec<(Option<B256>, Nibbles)> {
+        let mut subtrees = Vec::<(Option<B256>, Nibbles)>::new();
+        for inconsistency in &self.inconsistencies {
+            let is_covered = subtrees.last().map_or(false, |(hashed_address, path)| {
+                *hashed_address == inconsistency.hashed_address &&
+                    inconsistency.path.starts_with(path)
+            });
+            if !is_covered {
+                subtrees.push((inconsistency.hashed_address, inconsistency.path.clone()));
+            }
+        }
+        subtrees
+    }
+
+    /// Returns the trie updates that replace the inconsistent nodes with the recomputed ones.
+    ///
+    /// Only the inconsistent nodes are touched, the rest of the tries are left as is.
+    pub fn repair_updates(&self) -> TrieUpdates {
+        let mut updates = TrieUpdates::default();
+        updates.extend(self.inconsistencies.iter().map(|inconsistency| {
+            let key = match inconsistency.hashed_address {
+                Some(hashed_address) => {
+                    TrieKey::StorageNode(hashed_address, inconsistency.path.clone().into())
+                }
+                None => TrieKey::AccountNode(inconsistency.path.clone().into()),
+            };
+            let op = match inconsistency.kind.expected() {
+                Some(node) => TrieOp::Update(node.clone()),
+                None => TrieOp::Delete,
+            };
+            (key, op)
+        }));
+        updates
+    }
+}
+
+/// A stored trie node that does not match the hashed state.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrieInconsistency {
+    /// The hashed address of the storage trie, `None` for the account trie.
+    pub hashed_address: Option<B256>,
+    /// The path of the node.
+    pub path: Nibbles,
+    /// The kind of inconsistency.
+    pub kind: InconsistencyKind,
+}
+
+/// The kind of trie node inconsistency.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InconsistencyKind {
+    /// The node is missing from the database.
+    Missing(BranchNodeCompact),
+    /// The node is stored, but there is no such node in the trie.
+    Extra(BranchNodeCompact),
+    /// The stored node differs from the recomputed one.
+    Mismatch {
+        /// The stored node.
+        stored: BranchNodeCompact,
+        /// The recomputed node.
+        expected: BranchNodeCompact,
+    },
+}
+
+impl InconsistencyKind {
+    /// Returns the recomputed node, if the node is part of the trie.
+    pub fn expected(&self) -> Option<&BranchNodeCompact> {
+        match self {
+            Self::Missing(expected) | Self::Mismatch { expected, .. } => Some(expected),
+            Self::Extra(_) => None,
+        }
+    }
+
+    /// Returns the stored node, if any.
+    pub fn stored(&self) -> Option<&BranchNodeCompact> {
+        match self {
+            Self::Extra(stored) | Self::Mismatch { stored, .. } => Some(stored),
+            Self::Missing(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{cursor::DbCursorRW, transaction::DbTxMut};
+    use reth_primitives::{
+        keccak256,
+        trie::{StorageTrieEntry, StoredBranchNode, StoredNibbles},
+        Account, Address, StorageEntry, U256,
+    };
+    use reth_provider::test_utils::create_test_provider_factory;
+
+    #[test]
+    fn verify_and_repair() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let contract = keccak256(Address::with_last_byte(0));
+        for i in 0..1_000u64 {
+            let hashed_address = keccak256(Address::from_word(B256::from(U256::from(i))));
+            let account = Account { nonce: i, ..Default::default() };
+            tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+            let slot = keccak256(B256::from(U256::from(i)));
+            tx.put::<tables::HashedStorages>(
+                contract,
+                StorageEntry { key: slot, value: U256::from(i + 1) },
+            )
+            .unwrap();
+        }
+        tx.put::<tables::HashedAccounts>(contract, Account::default()).unwrap();
+
+        let (state_root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.flush(tx).unwrap();
+
+        let verification = TrieVerifier::new(tx).verify().unwrap();
+        assert_eq!(verification.state_root, state_root);
+        assert!(verification.is_consistent());
+
+        // Remove an account node, add a node that is not in the trie and corrupt a storage node.
+        let mut account_trie_cursor = tx.cursor_write::<tables::AccountsTrie>().unwrap();
+        let (missing_path, _) = account_trie_cursor.first().unwrap().unwrap();
+        account_trie_cursor.delete_current().unwrap();
+        let extra_path = Nibbles::from_nibbles_unchecked([0xf; 10]);
+        let extra_node = BranchNodeCompact::new(0b11, 0, 0, vec![], None);
+        tx.put::<tables::AccountsTrie>(
+            StoredNibbles(extra_path.clone()),
+            StoredBranchNode(extra_node),
+        )
+        .unwrap();
+        let mut storage_trie_cursor = tx.cursor_dup_write::<tables::StoragesTrie>().unwrap();
+        let (_, entry) = storage_trie_cursor.seek_exact(contract).unwrap().unwrap();
+        storage_trie_cursor.delete_current().unwrap();
+        let mut corrupted = entry.node.clone();
+        corrupted.root_hash = Some(B256::ZERO);
+        storage_trie_cursor
+            .upsert(contract, StorageTrieEntry { nibbles: entry.nibbles.clone(), node: corrupted })
+            .unwrap();
+
+        let verification = TrieVerifier::new(tx).verify().unwrap();
+        assert_eq!(verification.state_root, state_root);
+        assert_eq!(verification.inconsistencies.len(), 3);
+        assert_eq!(
+            verification.affected_subtrees(),
+            vec![(None, missing_path.0), (None, extra_path), (Some(contract), entry.nibbles.0)]
+        );
+
+        verification.repair_updates().flush(tx).unwrap();
+        let verification = TrieVerifier::new(tx).verify().unwrap();
+        assert!(verification.is_consistent());
+        assert_eq!(StateRoot::from_tx(tx).root().unwrap(), state_root);
+    }
+}
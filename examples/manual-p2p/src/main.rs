@@ -13,7 +13,8 @@ use once_cell::sync::Lazy;
 use reth_discv4::{DiscoveryUpdate, Discv4, Discv4ConfigBuilder, DEFAULT_DISCOVERY_ADDRESS};
 use reth_ecies::stream::ECIESStream;
 use reth_eth_wire::{
-    EthMessage, EthStream, HelloMessage, P2PStream, Status, UnauthedEthStream, UnauthedP2PStream,
+    BlockRangeUpdate, EthMessage, EthStream, EthVersion, HelloMessage, P2PStream, Status,
+    StatusMessage, UnauthedEthStream, UnauthedP2PStream,
 };
 use reth_network::config::rng_secret_key;
 use reth_primitives::{
@@ -67,7 +68,10 @@ async fn main() -> eyre::Result<()> {
 
                 println!(
                     "Successfully connected to a peer at {}:{} ({}) using eth-wire version eth/{}",
-                    peer.address, peer.tcp_port, their_hello.client_version, their_status.version
+                    peer.address,
+                    peer.tcp_port,
+                    their_hello.client_version,
+                    their_status.version()
                 );
 
                 snoop(peer, eth_stream).await;
@@ -93,7 +97,9 @@ async fn handshake_p2p(
 }
 
 // Perform a ETH Wire handshake with a peer
-async fn handshake_eth(p2p_stream: AuthedP2PStream) -> eyre::Result<(AuthedEthStream, Status)> {
+async fn handshake_eth(
+    p2p_stream: AuthedP2PStream,
+) -> eyre::Result<(AuthedEthStream, StatusMessage)> {
     let fork_filter = MAINNET.fork_filter(Head {
         timestamp: MAINNET.fork(Hardfork::Shanghai).as_timestamp().unwrap(),
         ..Default::default()
@@ -105,7 +111,16 @@ async fn handshake_eth(p2p_stream: AuthedP2PStream) -> eyre::Result<(AuthedEthSt
         .forkid(MAINNET.hardfork_fork_id(Hardfork::Shanghai).unwrap())
         .build();
 
-    let status = Status { version: p2p_stream.shared_capabilities().eth()?.version(), ..status };
+    let version = p2p_stream.shared_capabilities().eth()?.version();
+    let status = Status { version, ..status };
+    let status = if version == EthVersion::Eth69 as u8 {
+        // we don't serve any blocks
+        let block_range =
+            BlockRangeUpdate { earliest: 0, latest: 0, latest_hash: MAINNET_GENESIS_HASH };
+        StatusMessage::Eth69(status.into_eth69(block_range))
+    } else {
+        StatusMessage::Legacy(status)
+    };
     let eth_unauthed = UnauthedEthStream::new(p2p_stream);
     Ok(eth_unauthed.handshake(status, fork_filter).await?)
 }
@@ -1,3 +1,4 @@
 //! Specific test case handler implementations.
 
 pub mod blockchain_test;
+pub mod stateless_test;
@@ -0,0 +1,209 @@
+//! Test runners for `BlockchainTests` that verify the blocks statelessly from execution witnesses.
+
+use crate::{
+    cases::blockchain_test::should_skip,
+    models::{BlockchainTest, ForkSpec},
+    Case, Error, Suite,
+};
+use alloy_rlp::Decodable;
+use rayon::iter::{ParallelBridge, ParallelIterator};
+use reth_beacon_consensus_core::BeaconConsensus;
+use reth_db::{
+    database::Database,
+    test_utils::{create_test_rw_db, create_test_static_files_dir},
+    DatabaseError,
+};
+use reth_interfaces::consensus::Consensus;
+use reth_node_ethereum::EthEvmConfig;
+use reth_primitives::{
+    BlockBody, ChainSpec, SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment,
+    U256,
+};
+use reth_provider::{
+    providers::{LatestStateProviderRef, StaticFileWriter},
+    BlockWriter, DatabaseProviderRW, ProviderFactory,
+};
+use reth_revm::witness::{execute_with_witness, generate_execution_witness};
+use reth_trie::StateRoot;
+use std::{collections::BTreeMap, fs, path::Path, sync::Arc};
+
+/// A handler for the blockchain test suite that verifies every block statelessly.
+#[derive(Debug)]
+pub struct StatelessBlockchainTests {
+    suite: String,
+}
+
+impl StatelessBlockchainTests {
+    /// Create a new handler for a subset of the blockchain test suite.
+    pub fn new(suite: String) -> Self {
+        Self { suite }
+    }
+}
+
+impl Suite for StatelessBlockchainTests {
+    type Case = StatelessBlockchainTestCase;
+
+    fn suite_name(&self) -> String {
+        format!("BlockchainTests/{}", self.suite)
+    }
+}
+
+/// An Ethereum blockchain test of which every valid block is verified from its execution witness
+/// with [execute_with_witness].
+#[derive(Debug, PartialEq, Eq)]
+pub struct StatelessBlockchainTestCase {
+    tests: BTreeMap<String, BlockchainTest>,
+    skip: bool,
+}
+
+impl Case for StatelessBlockchainTestCase {
+    fn load(path: &Path) -> Result<Self, Error> {
+        Ok(StatelessBlockchainTestCase {
+            tests: {
+                let s = fs::read_to_string(path)
+                    .map_err(|error| Error::Io { path: path.into(), error })?;
+                serde_json::from_str(&s)
+                    .map_err(|error| Error::CouldNotDeserialize { path: path.into(), error })?
+            },
+            skip: should_skip(path),
+        })
+    }
+
+    /// Verifies the valid blocks of each test case in order.
+    ///
+    /// The witness of every block is generated from the database the same way it is served over
+    /// RPC, so it only contains the state that the block accesses. Each block is re-executed from
+    /// its witness alone before it is appended to the database.
+    fn run(&self) -> Result<(), Error> {
+        if self.skip {
+            return Err(Error::Skipped)
+        }
+
+        self.tests
+            .values()
+            .filter(|case| {
+                !matches!(
+                    case.network,
+                    ForkSpec::ByzantiumToConstantinopleAt5 |
+                        ForkSpec::Constantinople |
+                        ForkSpec::ConstantinopleFix |
+                        ForkSpec::MergeEOF |
+                        ForkSpec::MergeMeterInitCode |
+                        ForkSpec::MergePush0 |
+                        ForkSpec::Unknown
+                )
+            })
+            .par_bridge()
+            .try_for_each(|case| {
+                let chain_spec: Arc<ChainSpec> = Arc::new(case.network.clone().into());
+                let consensus = BeaconConsensus::new(chain_spec.clone());
+
+                let db = create_test_rw_db();
+                let (_static_files_dir, static_files_dir_path) = create_test_static_files_dir();
+                let provider =
+                    ProviderFactory::new(db.as_ref(), chain_spec.clone(), static_files_dir_path)?
+                        .provider_rw()
+                        .unwrap();
+
+                // Insert the genesis block and the pre-state with its trie.
+                let genesis = SealedBlock::new(
+                    case.genesis_block_header.clone().into(),
+                    BlockBody::default(),
+                );
+                provider
+                    .insert_historical_block(genesis.clone().try_seal_with_senders().unwrap(), None)
+                    .map_err(|err| Error::RethError(err.into()))?;
+                provider
+                    .static_file_provider()
+                    .latest_writer(StaticFileSegment::Headers)
+                    .unwrap()
+                    .commit_without_sync_all()
+                    .unwrap();
+                case.pre.write_to_db(provider.tx_ref())?;
+                let (_, updates) = StateRoot::from_tx(provider.tx_ref())
+                    .root_with_updates()
+                    .map_err(DatabaseError::from)?;
+                updates.flush(provider.tx_ref())?;
+
+                let mut parent = genesis.header;
+                let mut total_difficulty = parent.difficulty;
+
+                // Blocks without a header are invalid and expected to be rejected.
+                for block in case.blocks.iter().filter(|block| block.block_header.is_some()) {
+                    let block = SealedBlock::decode(&mut block.rlp.as_ref())?
+                        .try_seal_with_senders()
+                        .map_err(|block| {
+                            Error::Assertion(format!(
+                                "failed to recover senders of block {}",
+                                block.number
+                            ))
+                        })?;
+                    total_difficulty += block.difficulty;
+
+                    let header = block.header.clone();
+                    verify_and_append_block(
+                        &provider,
+                        chain_spec.clone(),
+                        &consensus,
+                        block,
+                        &parent,
+                        total_difficulty,
+                    )?;
+                    parent = header;
+                }
+
+                // Drop the provider without committing to the database.
+                drop(provider);
+                Ok(())
+            })
+    }
+}
+
+/// Validates the block against its parent, re-executes it from the execution witness that is
+/// generated from the database and appends it to the database.
+fn verify_and_append_block<DB: Database>(
+    provider: &DatabaseProviderRW<DB>,
+    chain_spec: Arc<ChainSpec>,
+    consensus: &dyn Consensus,
+    block: SealedBlockWithSenders,
+    parent: &SealedHeader,
+    total_difficulty: U256,
+) -> Result<(), Error> {
+    let number = block.number;
+    let invalid = |err: &dyn std::fmt::Display| {
+        Error::Assertion(format!("block {number} failed verification: {err}"))
+    };
+
+    consensus
+        .validate_header_with_total_difficulty(&block.header, total_difficulty)
+        .map_err(|err| invalid(&err))?;
+    consensus.validate_header(&block.header).map_err(|err| invalid(&err))?;
+    consensus.validate_block(&block).map_err(|err| invalid(&err))?;
+    consensus.validate_header_against_parent(&block.header, parent).map_err(|err| invalid(&err))?;
+
+    let unsealed = block.clone().unseal();
+    let witness = generate_execution_witness(
+        chain_spec.clone(),
+        EthEvmConfig::default(),
+        LatestStateProviderRef::new(provider.tx_ref(), provider.static_file_provider().clone()),
+        &**provider,
+        &unsealed,
+        total_difficulty,
+    )
+    .map_err(|err| invalid(&err))?;
+    let bundle = execute_with_witness(
+        chain_spec,
+        EthEvmConfig::default(),
+        &unsealed,
+        total_difficulty,
+        &witness,
+    )
+    .map_err(|err| invalid(&err))?;
+
+    let hashed_state = bundle.hash_state_slow();
+    let (_, trie_updates) =
+        hashed_state.state_root_with_updates(provider.tx_ref()).map_err(DatabaseError::from)?;
+    provider
+        .append_blocks_with_state(vec![block], bundle, hashed_state, trie_updates, None)
+        .map_err(|err| Error::RethError(err.into()))
+}
@@ -1,6 +1,9 @@
 #![cfg(feature = "ef-tests")]
 
-use ef_tests::{cases::blockchain_test::BlockchainTests, suite::Suite};
+use ef_tests::{
+    cases::{blockchain_test::BlockchainTests, stateless_test::StatelessBlockchainTests},
+    suite::Suite,
+};
 
 macro_rules! general_state_test {
     ($test_name:ident, $dir:ident) => {
@@ -11,6 +14,15 @@ macro_rules! general_state_test {
     };
 }
 
+macro_rules! stateless_test {
+    ($test_name:ident, $dir:ident) => {
+        #[test]
+        fn $test_name() {
+            StatelessBlockchainTests::new(format!("GeneralStateTests/{}", stringify!($dir))).run();
+        }
+    };
+}
+
 mod general_state_tests {
     use super::*;
 
@@ -76,4 +88,18 @@ mod general_state_tests {
     general_state_test!(vm_tests, VMTests);
 }
 
+/// The state tests that clear, create and destroy state, re-run from execution witnesses.
+mod stateless_tests {
+    use super::*;
+
+    stateless_test!(st_create2, stCreate2);
+    stateless_test!(st_create, stCreateTest);
+    stateless_test!(st_ext_codehash, stExtCodeHash);
+    stateless_test!(st_refund, stRefundTest);
+    stateless_test!(st_revert, stRevertTest);
+    stateless_test!(st_self_balance, stSelfBalance);
+    stateless_test!(st_sstore, stSStoreTest);
+    stateless_test!(st_system_operations, stSystemOperationsTest);
+}
+
 // TODO: Add ValidBlocks and InvalidBlocks tests